@@ -0,0 +1,15 @@
+//! Arbitrary bytes as the text of a file, which mostly exercises the recovery of the parser and
+//! the lowering of broken trees
+
+#![no_main]
+
+use gqls_ide::AnalysisHost;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // invalid utf8 is replaced as when a file is read from disk
+    let text = String::from_utf8_lossy(data);
+    let mut host = AnalysisHost::new();
+    let file = host.set_file("default", "/fuzz.graphql", text);
+    let _ = host.analysis().diagnostics(file);
+});
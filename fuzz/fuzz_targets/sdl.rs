@@ -0,0 +1,226 @@
+//! Schemas generated from the grammar of the SDL, so most of them parse and make it through
+//! lowering to the validation of the project. The names are drawn from a small pool so that they
+//! resolve, collide and form cycles, and arbitrary text is spliced in between definitions.
+
+#![no_main]
+
+use std::fmt::{self, Display};
+
+use gqls_ide::AnalysisHost;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+use libfuzzer_sys::fuzz_target;
+
+const NAMES: [&str; 8] = ["Query", "Foo", "Bar", "Node", "id", "foo", "Int", "String"];
+
+const LOCATIONS: [&str; 6] =
+    ["FIELD_DEFINITION", "OBJECT", "INTERFACE", "INPUT_FIELD_DEFINITION", "ENUM_VALUE", "QUERY"];
+
+#[derive(Debug, Arbitrary)]
+struct Ident(u8);
+
+#[derive(Debug, Arbitrary)]
+enum Type {
+    Named(Ident),
+    List(Box<Type>),
+    NonNull(Box<Type>),
+}
+
+#[derive(Debug, Arbitrary)]
+enum Value {
+    // wider than an `Int` to cover the integers that overflow it
+    Int(i128),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(Ident),
+    List(Vec<Value>),
+    Object(Vec<(Ident, Value)>),
+}
+
+#[derive(Debug, Arbitrary)]
+struct Directive {
+    name: Ident,
+    args: Vec<(Ident, Value)>,
+}
+
+#[derive(Debug, Arbitrary)]
+struct InputValue {
+    name: Ident,
+    ty: Type,
+    default: Option<Value>,
+    directives: Vec<Directive>,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Field {
+    name: Ident,
+    args: Vec<InputValue>,
+    ty: Type,
+    directives: Vec<Directive>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Operation {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Definition {
+    Schema(Vec<(Operation, Ident)>),
+    Object(Ident, Vec<Ident>, Vec<Field>),
+    Interface(Ident, Vec<Ident>, Vec<Field>),
+    Input(Ident, Vec<InputValue>),
+    Enum(Ident, Vec<Ident>),
+    Union(Ident, Vec<Ident>),
+    Scalar(Ident, Vec<Directive>),
+    Directive(Ident, Vec<InputValue>, Vec<u8>),
+    Extend(Box<Definition>),
+    Text(String),
+}
+
+impl Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", NAMES[self.0 as usize % NAMES.len()])
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Named(name) => write!(f, "{name}"),
+            Type::List(ty) => write!(f, "[{ty}]"),
+            // a nested non-null type is a syntax error, which is just as well
+            Type::NonNull(ty) => write!(f, "{ty}!"),
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x:?}"),
+            Value::String(s) => write!(f, "{s:?}"),
+            Value::Boolean(b) => write!(f, "{b}"),
+            Value::Null => write!(f, "null"),
+            Value::Enum(name) => write!(f, "{name}"),
+            Value::List(values) => write!(f, "[{}]", join(values, ", ")),
+            Value::Object(fields) => {
+                let fields = fields.iter().map(|(name, value)| format!("{name}: {value}"));
+                write!(f, "{{ {} }}", fields.collect::<Vec<_>>().join(", "))
+            }
+        }
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.name)?;
+        if !self.args.is_empty() {
+            let args = self.args.iter().map(|(name, value)| format!("{name}: {value}"));
+            write!(f, "({})", args.collect::<Vec<_>>().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for InputValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.ty)?;
+        if let Some(default) = &self.default {
+            write!(f, " = {default}")?;
+        }
+        write!(f, " {}", join(&self.directives, " "))
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.args.is_empty() {
+            write!(f, "({})", join(&self.args, ", "))?;
+        }
+        write!(f, ": {} {}", self.ty, join(&self.directives, " "))
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Query => write!(f, "query"),
+            Operation::Mutation => write!(f, "mutation"),
+            Operation::Subscription => write!(f, "subscription"),
+        }
+    }
+}
+
+impl Display for Definition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Definition::Schema(operations) => {
+                let operations = operations.iter().map(|(op, ty)| format!("{op}: {ty}"));
+                write!(f, "schema {{ {} }}", operations.collect::<Vec<_>>().join(" "))
+            }
+            Definition::Object(name, interfaces, fields) =>
+                write_fields(f, "type", name, interfaces, fields),
+            Definition::Interface(name, interfaces, fields) =>
+                write_fields(f, "interface", name, interfaces, fields),
+            Definition::Input(name, fields) =>
+                write!(f, "input {name} {{ {} }}", join(fields, " ")),
+            Definition::Enum(name, values) => write!(f, "enum {name} {{ {} }}", join(values, " ")),
+            Definition::Union(name, members) =>
+                write!(f, "union {name} = {}", join(members, " | ")),
+            Definition::Scalar(name, directives) =>
+                write!(f, "scalar {name} {}", join(directives, " ")),
+            Definition::Directive(name, args, locations) => {
+                let locations = locations
+                    .iter()
+                    .map(|&location| LOCATIONS[location as usize % LOCATIONS.len()])
+                    .collect::<Vec<_>>();
+                write!(f, "directive @{name}")?;
+                if !args.is_empty() {
+                    write!(f, "({})", join(args, ", "))?;
+                }
+                write!(f, " on {}", locations.join(" | "))
+            }
+            Definition::Extend(definition) => write!(f, "extend {definition}"),
+            Definition::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+fn write_fields(
+    f: &mut fmt::Formatter<'_>,
+    keyword: &str,
+    name: &Ident,
+    interfaces: &[Ident],
+    fields: &[Field],
+) -> fmt::Result {
+    write!(f, "{keyword} {name}")?;
+    if !interfaces.is_empty() {
+        write!(f, " implements {}", join(interfaces, " & "))?;
+    }
+    write!(f, " {{ {} }}", join(fields, " "))
+}
+
+fn join(items: &[impl Display], separator: &str) -> String {
+    items.iter().map(ToString::to_string).collect::<Vec<_>>().join(separator)
+}
+
+fuzz_target!(|files: Vec<Vec<Definition>>| {
+    let mut host = AnalysisHost::new();
+    let files = host.set_files(
+        "default",
+        files
+            .iter()
+            .enumerate()
+            .map(|(i, definitions)| (format!("/{i}.graphql"), join(definitions, "\n"))),
+    );
+    let analysis = host.analysis();
+    for file in files {
+        let _ = analysis.diagnostics(file);
+    }
+});
@@ -1,6 +1,13 @@
+mod line_index;
+mod lints;
+
+pub use self::line_index::{LineIndex, PositionEncoding};
+pub use self::lints::{Lint, LintOverrides, UnknownLint};
+
 use smallvec::SmallVec;
 use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tree_sitter::Tree;
 use vfs::FileId;
@@ -20,6 +27,83 @@ impl FileData {
 
 pub type Project = &'static str;
 
+/// Qualify the project `name` by the workspace folder `scope` it is configured in, so that the
+/// projects of the same name in different folders stay apart
+pub fn qualify_project(scope: &str, name: &str) -> String {
+    format!("{scope}#{name}")
+}
+
+/// The name of `project` as configured, without the workspace folder that may qualify it
+pub fn project_name(project: Project) -> &'static str {
+    match project.split_once('#') {
+        Some((scope, name)) if scope.contains("://") => name,
+        _ => project,
+    }
+}
+
+/// The default of [`SourceDatabase::max_file_size`]
+pub const DEFAULT_MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+/// What the configuration of a project says about a custom scalar, e.g. that a `DateTime` is an
+/// RFC 3339 timestamp
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CustomScalar {
+    /// markdown
+    pub description: Option<String>,
+    /// the url of the specification of the scalar, as given by `@specifiedBy`
+    pub specified_by: Option<String>,
+}
+
+/// The custom scalars of a project by their name
+pub type CustomScalars = HashMap<String, CustomScalar>;
+
+/// The names of the root operation types of a project without a schema definition, which are
+/// `Query`, `Mutation` and `Subscription` unless configured otherwise
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootTypes {
+    pub query: String,
+    pub mutation: String,
+    pub subscription: String,
+}
+
+impl Default for RootTypes {
+    fn default() -> Self {
+        Self {
+            query: "Query".to_owned(),
+            mutation: "Mutation".to_owned(),
+            subscription: "Subscription".to_owned(),
+        }
+    }
+}
+
+/// How generated files are recognized. A generated file is indexed like any other, but it isn't
+/// linted or formatted and its semantic errors are only hints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedFiles {
+    /// the text of a comment (e.g. `# @generated`) that marks the file it is in as generated
+    pub marker: String,
+    /// the number of lines at the start of a file that are searched for the marker
+    pub lines: usize,
+    /// the files that are generated regardless of their text, e.g. those matched by a glob
+    pub files: HashSet<FileId>,
+}
+
+impl Default for GeneratedFiles {
+    fn default() -> Self {
+        Self { marker: "@generated".to_owned(), lines: 5, files: Default::default() }
+    }
+}
+
+impl GeneratedFiles {
+    /// Whether one of the first lines of `text` is a comment containing the marker
+    pub fn is_marked(&self, text: &str) -> bool {
+        text.lines().take(self.lines).any(|line| {
+            let comment = line.trim_start().strip_prefix('#');
+            comment.map_or(false, |comment| comment.contains(self.marker.as_str()))
+        })
+    }
+}
+
 thread_local! {
     static INTERNER: std::cell::RefCell<vfs::Interner<str>> = Default::default();
 }
@@ -32,6 +116,51 @@ pub trait SourceDatabase {
     #[salsa::input]
     fn file_data(&self, file: FileId) -> FileData;
 
+    /// Groups of projects that form one logical schema, e.g. the same project in each of the
+    /// workspace folders stitched together. A project that isn't in a group is on its own.
+    #[salsa::input]
+    fn project_groups(&self) -> Arc<Vec<HashSet<Project>>>;
+
+    /// The projects that enforce graphql-import comments, see `DefDatabase::import_scope`
+    #[salsa::input]
+    fn import_projects(&self) -> Arc<HashSet<Project>>;
+
+    /// The size in bytes beyond which a file is indexed shallowly, only its items are lowered
+    /// and not their bodies
+    #[salsa::input]
+    fn max_file_size(&self) -> usize;
+
+    /// The files whose text had invalid utf8 replaced when it was read
+    #[salsa::input]
+    fn lossy_files(&self) -> Arc<HashSet<FileId>>;
+
+    /// The registry of the custom scalars of each project
+    #[salsa::input]
+    fn custom_scalars(&self) -> Arc<HashMap<Project, CustomScalars>>;
+
+    /// The configured root operation types of each project, projects without any configured use
+    /// the defaults of [`RootTypes`]
+    #[salsa::input]
+    fn root_types(&self) -> Arc<HashMap<Project, RootTypes>>;
+
+    #[salsa::input]
+    fn generated_files(&self) -> Arc<GeneratedFiles>;
+
+    /// The directories whose configuration overrides the lints of the files below them, only
+    /// these have [`SourceDatabase::directory_lints`]
+    #[salsa::input]
+    fn lint_directories(&self) -> Arc<HashSet<PathBuf>>;
+
+    #[salsa::input]
+    fn directory_lints(&self, dir: PathBuf) -> Arc<LintOverrides>;
+
+    /// The overrides of the directories containing `file`, from the outermost to the innermost
+    fn file_lint_overrides(&self, file: FileId) -> Arc<Vec<Arc<LintOverrides>>>;
+
+    fn is_shallow(&self, file: FileId) -> bool;
+
+    fn is_generated(&self, file: FileId) -> bool;
+
     #[salsa::dependencies]
     fn file_tree(&self, file: FileId) -> Tree;
 
@@ -40,29 +169,78 @@ pub trait SourceDatabase {
 
     fn file_text(&self, file: FileId) -> Arc<str>;
 
+    fn line_index(&self, file: FileId) -> Arc<LineIndex>;
+
     fn file_to_projects(&self) -> Arc<HashMap<FileId, SmallVec<[Project; 1]>>>;
 
     fn projects_of(&self, file: InProject<()>) -> SmallVec<[Project; 1]>;
 
     fn project_files(&self, project: Project) -> HashSet<FileId>;
+
+    /// The files that the names in `project` resolve to, those of every project in its group
+    fn resolution_files(&self, project: Project) -> HashSet<FileId>;
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn file_tree(db: &dyn SourceDatabase, file: FileId) -> Tree {
     db.file_data(file).tree
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn file_text(db: &dyn SourceDatabase, file: FileId) -> Arc<str> {
     db.file_data(file).text
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
+fn is_shallow(db: &dyn SourceDatabase, file: FileId) -> bool {
+    db.file_text(file).len() > db.max_file_size()
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+fn is_generated(db: &dyn SourceDatabase, file: FileId) -> bool {
+    let generated = db.generated_files();
+    generated.files.contains(file) || generated.is_marked(&db.file_text(file))
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+fn file_lint_overrides(db: &dyn SourceDatabase, file: FileId) -> Arc<Vec<Arc<LintOverrides>>> {
+    let directories = db.lint_directories();
+    let ancestors = file.ancestors().skip(1).collect::<Vec<_>>();
+    let overrides = ancestors
+        .into_iter()
+        .rev()
+        .filter(|&dir| directories.contains(dir))
+        .map(|dir| db.directory_lints(dir.to_path_buf()))
+        .collect();
+    Arc::new(overrides)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+fn line_index(db: &dyn SourceDatabase, file: FileId) -> Arc<LineIndex> {
+    Arc::new(LineIndex::new(&db.file_text(file)))
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 fn intern_project(_db: &dyn SourceDatabase, name: String) -> Project {
     INTERNER.with(|interner| interner.borrow_mut().intern(name))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn project_files(db: &dyn SourceDatabase, project: Project) -> HashSet<FileId> {
     db.projects()[project].clone()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
+fn resolution_files(db: &dyn SourceDatabase, project: Project) -> HashSet<FileId> {
+    let projects = db.projects();
+    match db.project_groups().iter().find(|group| group.contains(project)) {
+        Some(group) =>
+            group.iter().flat_map(|&project| projects.get(project)).flatten().copied().collect(),
+        None => db.project_files(project),
+    }
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 fn file_to_projects(db: &dyn SourceDatabase) -> Arc<HashMap<FileId, SmallVec<[Project; 1]>>> {
     let mut result = HashMap::<FileId, SmallVec<_>>::new();
     for (&project, files) in db.projects().iter() {
@@ -73,8 +251,10 @@ fn file_to_projects(db: &dyn SourceDatabase) -> Arc<HashMap<FileId, SmallVec<[Pr
     Arc::new(result)
 }
 
+// files outside of every project (i.e. the builtins) have no projects
+#[tracing::instrument(level = "debug", skip_all)]
 fn projects_of(db: &dyn SourceDatabase, project: InProject<()>) -> SmallVec<[Project; 1]> {
-    db.file_to_projects()[&project.file].clone()
+    db.file_to_projects().get(&project.file).cloned().unwrap_or_default()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
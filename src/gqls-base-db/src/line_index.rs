@@ -0,0 +1,141 @@
+//! Conversions between byte offsets and points of a file. The columns of tree-sitter points are
+//! byte offsets into the line whereas clients may count utf-16 code units or characters instead,
+//! so the index also records the characters of each line that aren't a single byte.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use tree_sitter::Point;
+
+/// What the column of a point counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// bytes, the columns of tree-sitter points
+    Utf8,
+    /// utf-16 code units
+    Utf16,
+    /// characters (unicode scalar values)
+    Utf32,
+}
+
+// the default of the language server protocol
+impl Default for PositionEncoding {
+    fn default() -> Self {
+        PositionEncoding::Utf16
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// the byte offset of the start of each line
+    line_starts: Vec<usize>,
+    /// the length of the text in bytes
+    len: usize,
+    /// the non-ascii characters of each line (that contains any), in order
+    wide_chars: HashMap<usize, Vec<WideChar>>,
+}
+
+/// A non-ascii character, `start` is the byte column it starts at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+    start: usize,
+    len_utf8: usize,
+    len_utf16: usize,
+}
+
+impl WideChar {
+    fn end(self) -> usize {
+        self.start + self.len_utf8
+    }
+
+    fn len(self, encoding: PositionEncoding) -> usize {
+        match encoding {
+            PositionEncoding::Utf8 => self.len_utf8,
+            PositionEncoding::Utf16 => self.len_utf16,
+            PositionEncoding::Utf32 => 1,
+        }
+    }
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars = HashMap::<usize, Vec<WideChar>>::new();
+        for (offset, c) in text.char_indices() {
+            if c == '\n' {
+                line_starts.push(offset + 1);
+            } else if !c.is_ascii() {
+                let row = line_starts.len() - 1;
+                let start = offset - line_starts[row];
+                let wide = WideChar { start, len_utf8: c.len_utf8(), len_utf16: c.len_utf16() };
+                wide_chars.entry(row).or_default().push(wide);
+            }
+        }
+        Self { line_starts, len: text.len(), wide_chars }
+    }
+
+    /// The point (with a byte column) of the byte `offset`
+    pub fn point(&self, offset: usize) -> Point {
+        let row = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        Point::new(row, offset - self.line_starts[row])
+    }
+
+    /// The byte offset of `point`, points past the last line are clamped to the end of it. The
+    /// column is clamped to the end of the line, and moved to the start of the character it is in
+    /// (the points of clients may be anywhere)
+    pub fn offset(&self, point: Point) -> usize {
+        let row = point.row.min(self.line_starts.len() - 1);
+        let line_start = self.line_starts[row];
+        let line_end = self.line_starts.get(row + 1).map_or(self.len, |next| next - 1);
+        let mut column = point.column.min(line_end - line_start);
+        if let Some(c) = self.wide_chars(row).iter().find(|c| c.start < column && column < c.end())
+        {
+            column = c.start;
+        }
+        line_start + column
+    }
+
+    /// Convert a point with a byte column to one with a column in `encoding`
+    pub fn encode(&self, point: Point, encoding: PositionEncoding) -> Point {
+        let mut column = point.column;
+        for c in self.wide_chars(point.row) {
+            if c.end() > point.column {
+                break;
+            }
+            column -= c.len_utf8 - c.len(encoding);
+        }
+        Point::new(point.row, column)
+    }
+
+    /// Convert a point with a column in `encoding` to one with a byte column
+    pub fn decode(&self, point: Point, encoding: PositionEncoding) -> Point {
+        let mut column = point.column;
+        for c in self.wide_chars(point.row) {
+            if c.start >= column {
+                break;
+            }
+            column += c.len_utf8 - c.len(encoding);
+        }
+        Point::new(point.row, column)
+    }
+
+    /// The length of the byte range in `encoding`
+    pub fn encoded_len(&self, range: Range<usize>, encoding: PositionEncoding) -> usize {
+        let mut len = range.len();
+        for row in self.point(range.start).row..=self.point(range.end).row {
+            let line_start = self.line_starts[row];
+            for c in self.wide_chars(row) {
+                if range.start <= line_start + c.start && line_start + c.end() <= range.end {
+                    len -= c.len_utf8 - c.len(encoding);
+                }
+            }
+        }
+        len
+    }
+
+    fn wide_chars(&self, row: usize) -> &[WideChar] {
+        self.wide_chars.get(&row).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests;
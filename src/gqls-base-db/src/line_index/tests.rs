@@ -0,0 +1,73 @@
+use tree_sitter::Point;
+
+use super::{LineIndex, PositionEncoding};
+
+#[test]
+fn test_line_index_points() {
+    let index = LineIndex::new("type Foo {\n  bar: Int\n}\n");
+    assert_eq!(index.point(0), Point::new(0, 0));
+    assert_eq!(index.point(10), Point::new(0, 10));
+    assert_eq!(index.point(11), Point::new(1, 0));
+    assert_eq!(index.point(15), Point::new(1, 4));
+    assert_eq!(index.point(23), Point::new(2, 0));
+    assert_eq!(index.point(24), Point::new(3, 0));
+
+    for offset in 0..=24 {
+        assert_eq!(index.offset(index.point(offset)), offset);
+    }
+}
+
+#[test]
+fn test_line_index_clamps_offsets() {
+    let text = "type é\n}";
+    let index = LineIndex::new(text);
+    assert_eq!(index.offset(Point::new(0, 100)), text.find('\n').unwrap());
+    assert_eq!(index.offset(Point::new(1, 100)), text.len());
+    assert_eq!(index.offset(Point::new(5, 0)), text.find('}').unwrap());
+    // the second byte of `é`
+    assert_eq!(index.offset(Point::new(0, 6)), text.find('é').unwrap());
+    assert_eq!(index.offset(Point::new(0, 7)), text.find('\n').unwrap());
+}
+
+#[test]
+fn test_line_index_utf16() {
+    // `é` is 2 bytes and 1 code unit, `😀` is 4 bytes and 2 code units
+    let text = "\"é😀\" type Foo\ntype Bar";
+    let index = LineIndex::new(text);
+    let foo = text.find("Foo").unwrap();
+    assert_eq!(index.point(foo), Point::new(0, 14));
+    assert_eq!(index.encode(Point::new(0, 14), PositionEncoding::Utf16), Point::new(0, 11));
+    assert_eq!(index.decode(Point::new(0, 11), PositionEncoding::Utf16), Point::new(0, 14));
+
+    // the second line has no wide characters
+    assert_eq!(index.encode(Point::new(1, 5), PositionEncoding::Utf16), Point::new(1, 5));
+    assert_eq!(index.decode(Point::new(1, 5), PositionEncoding::Utf16), Point::new(1, 5));
+
+    for (offset, _) in text.char_indices() {
+        let point = index.point(offset);
+        let encoded = index.encode(point, PositionEncoding::Utf16);
+        assert_eq!(index.decode(encoded, PositionEncoding::Utf16), point);
+    }
+
+    assert_eq!(index.encoded_len(0..text.find(' ').unwrap(), PositionEncoding::Utf16), 5);
+    let len = text.encode_utf16().count();
+    assert_eq!(index.encoded_len(0..text.len(), PositionEncoding::Utf16), len);
+}
+
+#[test]
+fn test_line_index_encodings() {
+    let text = "\"é😀\" type Foo";
+    let index = LineIndex::new(text);
+    let foo = Point::new(0, text.find("Foo").unwrap());
+    assert_eq!(index.encode(foo, PositionEncoding::Utf8), foo);
+    assert_eq!(index.encode(foo, PositionEncoding::Utf16), Point::new(0, 11));
+    assert_eq!(index.encode(foo, PositionEncoding::Utf32), Point::new(0, 10));
+
+    for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16, PositionEncoding::Utf32] {
+        for (offset, _) in text.char_indices() {
+            let point = index.point(offset);
+            assert_eq!(index.decode(index.encode(point, encoding), encoding), point);
+        }
+    }
+    assert_eq!(index.encoded_len(0..text.len(), PositionEncoding::Utf32), text.chars().count());
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Style lints are opt-in and must be enabled per project, while the lints in [`Lint::DEFAULT`]
+/// are enabled unless they are allowed.
+/// They are reported as warnings with codes in their own `L` namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Lint {
+    EnumValueCase,
+    TypeNameCase,
+    FieldNameCase,
+    UnusedDefinition,
+    /// a custom scalar that the registry of the project doesn't describe
+    UnregisteredScalar,
+    /// a project without a query type, declared by the schema definition or otherwise the type of
+    /// the (configured) name of the query type
+    MissingQueryType,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::EnumValueCase,
+        Lint::TypeNameCase,
+        Lint::FieldNameCase,
+        Lint::UnusedDefinition,
+        Lint::UnregisteredScalar,
+        Lint::MissingQueryType,
+    ];
+
+    /// The lints that are enabled by default
+    pub const DEFAULT: [Lint; 1] = [Lint::MissingQueryType];
+
+    /// The name used to enable the lint in the project configuration
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::EnumValueCase => "enum-value-case",
+            Lint::TypeNameCase => "type-name-case",
+            Lint::FieldNameCase => "field-name-case",
+            Lint::UnusedDefinition => "unused-definition",
+            Lint::UnregisteredScalar => "unregistered-scalar",
+            Lint::MissingQueryType => "missing-query-type",
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The lints that the configuration of a directory enables or disables for the files below it,
+/// regardless of their project. The configuration of a nested directory overrides that of its
+/// parents.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintOverrides {
+    pub enabled: HashSet<Lint>,
+    pub disabled: HashSet<Lint>,
+}
+
+impl LintOverrides {
+    pub fn apply(&self, lints: &mut HashSet<Lint>) {
+        lints.retain(|lint| !self.disabled.contains(lint));
+        lints.extend(&self.enabled);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLint(pub String);
+
+impl Display for UnknownLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown lint `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownLint {
+}
+
+impl FromStr for Lint {
+    type Err = UnknownLint;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == s).ok_or_else(|| UnknownLint(s.to_owned()))
+    }
+}
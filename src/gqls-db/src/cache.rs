@@ -0,0 +1,73 @@
+//! The on-disk index cache, which persists the lowered items of the files of a workspace so
+//! that a cold start only lowers the files that changed since the cache was written
+
+use crate::{GqlsDatabase, SourceDatabase};
+use gqls_ir::{CachedItems, ContentHash, DefDatabase, ItemCache};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use vfs::FileId;
+
+const CACHE_FILE: &str = "items.bin";
+
+/// Bump whenever the serialized representation of the items changes,
+/// bincode is not self-describing so an outdated cache can't be detected otherwise
+const FORMAT_VERSION: u32 = 6;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    format: u32,
+    version: String,
+}
+
+impl Header {
+    fn current() -> Self {
+        Self { format: FORMAT_VERSION, version: env!("CARGO_PKG_VERSION").to_owned() }
+    }
+}
+
+/// Read the cache in `dir`, a missing cache or one written by a different version is `None`
+pub fn read(dir: &Path) -> io::Result<Option<ItemCache>> {
+    let file = match File::open(dir.join(CACHE_FILE)) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let mut reader = BufReader::new(file);
+    let header = bincode::deserialize_from::<_, Header>(&mut reader).map_err(invalid_data)?;
+    if header != Header::current() {
+        return Ok(None);
+    }
+    bincode::deserialize_from(&mut reader).map(Some).map_err(invalid_data)
+}
+
+/// Write `cache` into `dir`, replacing the existing cache (if any)
+pub fn write(dir: &Path, cache: &ItemCache) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    // write to a temporary file first so a concurrent reader never sees a partial cache
+    let tmp = dir.join(format!("{CACHE_FILE}.{}", std::process::id()));
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    bincode::serialize_into(&mut writer, &Header::current()).map_err(invalid_data)?;
+    bincode::serialize_into(&mut writer, cache).map_err(invalid_data)?;
+    writer.flush()?;
+    drop(writer);
+    std::fs::rename(tmp, dir.join(CACHE_FILE))
+}
+
+fn invalid_data(err: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+impl GqlsDatabase {
+    /// The cache of the current items of `files`
+    pub fn collect_item_cache(&self, files: impl IntoIterator<Item = FileId>) -> ItemCache {
+        let mut cache = ItemCache::default();
+        for file in files {
+            let items = CachedItems { items: self.items(file), item_map: self.item_map(file) };
+            cache.insert(ContentHash::of(&self.file_text(file)), Arc::new(items));
+        }
+        cache
+    }
+}
@@ -1,7 +1,11 @@
 #![deny(rust_2018_idioms)]
 
-pub use gqls_base_db::{FileData, Project, SourceDatabase};
-pub use gqls_ir::DefDatabase;
+pub mod cache;
+
+pub use gqls_base_db::{
+    project_name, qualify_project, CustomScalar, CustomScalars, FileData, GeneratedFiles, LineIndex, Lint, LintOverrides, PositionEncoding, Project, RootTypes, SourceDatabase, UnknownLint, DEFAULT_MAX_FILE_SIZE
+};
+pub use gqls_ir::{DefDatabase, ItemCache};
 pub use gqls_ty::TyDatabase;
 pub use salsa::{self, Database, ParallelDatabase, Snapshot};
 
@@ -18,7 +22,9 @@ pub struct GqlsDatabase {
 
 impl Default for GqlsDatabase {
     fn default() -> Self {
-        Self { storage: Default::default() }
+        let mut db = Self { storage: Default::default() };
+        db.set_item_cache(None);
+        db
     }
 }
 
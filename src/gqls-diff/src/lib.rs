@@ -0,0 +1,402 @@
+#![deny(rust_2018_idioms)]
+
+//! Classifies the changes between two versions of a schema by how they affect existing clients,
+//! following the breaking and dangerous changes of `graphql-js`. The schemas are the merged
+//! schemas of the ide, so extensions are already applied to the types they extend.
+
+use gqls_ide::{
+    Schema, SchemaDirective, SchemaField, SchemaInputValue, SchemaType, SchemaTypeKind, TypeRef
+};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Criticality {
+    /// existing operations may no longer be valid, e.g. a field was removed
+    Breaking,
+    /// existing operations are still valid but may behave differently,
+    /// e.g. an enum value was added that clients don't handle
+    Dangerous,
+    Safe,
+}
+
+impl Display for Criticality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Criticality::Breaking => write!(f, "breaking"),
+            Criticality::Dangerous => write!(f, "dangerous"),
+            Criticality::Safe => write!(f, "safe"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaChange {
+    pub criticality: Criticality,
+    /// the schema coordinate of what changed, e.g. `Type.field(arg:)` or `@directive`
+    pub coordinate: String,
+    pub message: String,
+}
+
+/// The changes from `old` to `new`, the most critical first
+pub fn diff(old: &Schema, new: &Schema) -> Vec<SchemaChange> {
+    let mut differ = Differ::default();
+    differ.root_types(old, new);
+    differ.types(&old.types, &new.types);
+    differ.directives(&old.directives, &new.directives);
+    differ.changes.sort();
+    differ.changes
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<SchemaChange>,
+}
+
+impl Differ {
+    fn push(&mut self, criticality: Criticality, coordinate: impl Into<String>, message: String) {
+        self.changes.push(SchemaChange { criticality, coordinate: coordinate.into(), message });
+    }
+
+    fn root_types(&mut self, old: &Schema, new: &Schema) {
+        let roots = [
+            ("query", &old.query_type, &new.query_type),
+            ("mutation", &old.mutation_type, &new.mutation_type),
+            ("subscription", &old.subscription_type, &new.subscription_type),
+        ];
+        for (operation, old, new) in roots {
+            match (old, new) {
+                (Some(old), Some(new)) if old != new => self.push(
+                    Criticality::Breaking,
+                    "schema",
+                    format!("the {operation} type changed from `{old}` to `{new}`"),
+                ),
+                (Some(old), None) => self.push(
+                    Criticality::Breaking,
+                    "schema",
+                    format!("the {operation} type `{old}` was removed"),
+                ),
+                (None, Some(new)) => self.push(
+                    Criticality::Safe,
+                    "schema",
+                    format!("the {operation} type `{new}` was added"),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn types(&mut self, old: &[SchemaType], new: &[SchemaType]) {
+        for (name, versions) in pair(old, new, |ty| ty.name.as_str()) {
+            match versions {
+                (Some(_), None) =>
+                    self.push(Criticality::Breaking, name, format!("type `{name}` was removed")),
+                (None, Some(_)) =>
+                    self.push(Criticality::Safe, name, format!("type `{name}` was added")),
+                (Some(old), Some(new)) if old.kind != new.kind => self.push(
+                    Criticality::Breaking,
+                    name,
+                    format!(
+                        "`{name}` changed from {} to {}",
+                        describe_kind(old.kind),
+                        describe_kind(new.kind)
+                    ),
+                ),
+                (Some(old), Some(new)) => self.ty(old, new),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn ty(&mut self, old: &SchemaType, new: &SchemaType) {
+        let name = &old.name;
+        match old.kind {
+            SchemaTypeKind::Object | SchemaTypeKind::Interface => {
+                self.fields(name, &old.fields, &new.fields);
+                let interfaces = pair(&old.interfaces, &new.interfaces, String::as_str);
+                for (interface, versions) in interfaces {
+                    match versions {
+                        (Some(_), None) => self.push(
+                            Criticality::Breaking,
+                            name,
+                            format!("`{name}` no longer implements `{interface}`"),
+                        ),
+                        (None, Some(_)) => self.push(
+                            Criticality::Dangerous,
+                            name,
+                            format!("`{name}` now implements `{interface}`"),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            // the possible types of an interface are the objects implementing it,
+            // which are reported as changes of those objects
+            SchemaTypeKind::Union => {
+                let members = pair(&old.possible_types, &new.possible_types, String::as_str);
+                for (member, versions) in members {
+                    match versions {
+                        (Some(_), None) => self.push(
+                            Criticality::Breaking,
+                            name,
+                            format!("`{member}` was removed from union `{name}`"),
+                        ),
+                        (None, Some(_)) => self.push(
+                            Criticality::Dangerous,
+                            name,
+                            format!("`{member}` was added to union `{name}`"),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            SchemaTypeKind::Enum => {
+                let values = pair(&old.enum_values, &new.enum_values, |value| value.name.as_str());
+                for (value, versions) in values {
+                    let coordinate = format!("{name}.{value}");
+                    match versions {
+                        (Some(_), None) => self.push(
+                            Criticality::Breaking,
+                            coordinate,
+                            format!("value `{value}` was removed from enum `{name}`"),
+                        ),
+                        (None, Some(_)) => self.push(
+                            Criticality::Dangerous,
+                            coordinate,
+                            format!("value `{value}` was added to enum `{name}`"),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            SchemaTypeKind::InputObject => self.input_values(
+                "input field",
+                |field| format!("{name}.{field}"),
+                &old.input_fields,
+                &new.input_fields,
+            ),
+            SchemaTypeKind::Scalar => {}
+        }
+    }
+
+    fn fields(&mut self, ty: &str, old: &[SchemaField], new: &[SchemaField]) {
+        for (name, versions) in pair(old, new, |field| field.name.as_str()) {
+            let coordinate = format!("{ty}.{name}");
+            let (old, new) = match versions {
+                (Some(old), Some(new)) => (old, new),
+                (Some(_), None) => {
+                    let message = format!("field `{coordinate}` was removed");
+                    self.push(Criticality::Breaking, coordinate, message);
+                    continue;
+                }
+                (None, Some(_)) => {
+                    let message = format!("field `{coordinate}` was added");
+                    self.push(Criticality::Safe, coordinate, message);
+                    continue;
+                }
+                (None, None) => unreachable!(),
+            };
+
+            if !is_same_type(&old.ty, &new.ty) {
+                let criticality = if is_safe_output_change(&old.ty, &new.ty) {
+                    Criticality::Safe
+                } else {
+                    Criticality::Breaking
+                };
+                let message = format!(
+                    "field `{coordinate}` changed type from `{}` to `{}`",
+                    render(&old.ty),
+                    render(&new.ty)
+                );
+                self.push(criticality, coordinate.clone(), message);
+            }
+            if !old.is_deprecated && new.is_deprecated {
+                let message = format!("field `{coordinate}` was deprecated");
+                self.push(Criticality::Safe, coordinate.clone(), message);
+            }
+            self.input_values(
+                "argument",
+                |arg| format!("{coordinate}({arg}:)"),
+                &old.args,
+                &new.args,
+            );
+        }
+    }
+
+    fn directives(&mut self, old: &[SchemaDirective], new: &[SchemaDirective]) {
+        for (name, versions) in pair(old, new, |directive| directive.name.as_str()) {
+            let coordinate = format!("@{name}");
+            let (old, new) = match versions {
+                (Some(old), Some(new)) => (old, new),
+                (Some(_), None) => {
+                    let message = format!("directive `{coordinate}` was removed");
+                    self.push(Criticality::Breaking, coordinate, message);
+                    continue;
+                }
+                (None, Some(_)) => {
+                    let message = format!("directive `{coordinate}` was added");
+                    self.push(Criticality::Safe, coordinate, message);
+                    continue;
+                }
+                (None, None) => unreachable!(),
+            };
+
+            for (location, versions) in pair(&old.locations, &new.locations, |location| *location) {
+                match versions {
+                    (Some(_), None) => self.push(
+                        Criticality::Breaking,
+                        coordinate.clone(),
+                        format!("location `{location}` was removed from `{coordinate}`"),
+                    ),
+                    (None, Some(_)) => self.push(
+                        Criticality::Safe,
+                        coordinate.clone(),
+                        format!("location `{location}` was added to `{coordinate}`"),
+                    ),
+                    _ => {}
+                }
+            }
+            self.input_values(
+                "argument",
+                |arg| format!("{coordinate}({arg}:)"),
+                &old.args,
+                &new.args,
+            );
+        }
+    }
+
+    /// Compare arguments or input fields, `what` is which of the two they are
+    fn input_values(
+        &mut self,
+        what: &str,
+        coordinate_of: impl Fn(&str) -> String,
+        old: &[SchemaInputValue],
+        new: &[SchemaInputValue],
+    ) {
+        for (name, versions) in pair(old, new, |value| value.name.as_str()) {
+            let coordinate = coordinate_of(name);
+            match versions {
+                (Some(_), None) => {
+                    let message = format!("{what} `{coordinate}` was removed");
+                    self.push(Criticality::Breaking, coordinate, message);
+                }
+                (None, Some(new)) if is_required(new) => {
+                    let message = format!("required {what} `{coordinate}` was added");
+                    self.push(Criticality::Breaking, coordinate, message);
+                }
+                (None, Some(_)) => {
+                    let message = format!("optional {what} `{coordinate}` was added");
+                    self.push(Criticality::Dangerous, coordinate, message);
+                }
+                (Some(old), Some(new)) => {
+                    if !is_same_type(&old.ty, &new.ty) {
+                        let criticality = if is_safe_input_change(&old.ty, &new.ty) {
+                            Criticality::Safe
+                        } else {
+                            Criticality::Breaking
+                        };
+                        let message = format!(
+                            "{what} `{coordinate}` changed type from `{}` to `{}`",
+                            render(&old.ty),
+                            render(&new.ty)
+                        );
+                        self.push(criticality, coordinate.clone(), message);
+                    }
+                    if old.default_value != new.default_value {
+                        let message = format!(
+                            "default value of {what} `{coordinate}` changed from {} to {}",
+                            render_default(&old.default_value),
+                            render_default(&new.default_value)
+                        );
+                        self.push(Criticality::Dangerous, coordinate, message);
+                    }
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+}
+
+/// Pair up the elements of `old` and `new` by name, in order of name
+fn pair<'a, T>(
+    old: &'a [T],
+    new: &'a [T],
+    name: impl Fn(&'a T) -> &'a str,
+) -> BTreeMap<&'a str, (Option<&'a T>, Option<&'a T>)> {
+    let mut pairs = BTreeMap::<_, (Option<_>, Option<_>)>::new();
+    for item in old {
+        pairs.entry(name(item)).or_default().0 = Some(item);
+    }
+    for item in new {
+        pairs.entry(name(item)).or_default().1 = Some(item);
+    }
+    pairs
+}
+
+/// Whether fields of type `old` can be read as `new`, i.e. `new` is at least as strict
+fn is_safe_output_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old, new) {
+        (TypeRef::NonNull(old), TypeRef::NonNull(new)) => is_safe_output_change(old, new),
+        (TypeRef::NonNull(_), _) => false,
+        (_, TypeRef::NonNull(new)) => is_safe_output_change(old, new),
+        (TypeRef::List(old), TypeRef::List(new)) => is_safe_output_change(old, new),
+        (TypeRef::Named(_, old), TypeRef::Named(_, new)) => old == new,
+        _ => false,
+    }
+}
+
+/// Whether values of type `old` are also valid for `new`, i.e. `new` is at most as strict
+fn is_safe_input_change(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old, new) {
+        (TypeRef::NonNull(old), TypeRef::NonNull(new)) => is_safe_input_change(old, new),
+        (_, TypeRef::NonNull(_)) => false,
+        (TypeRef::NonNull(old), _) => is_safe_input_change(old, new),
+        (TypeRef::List(old), TypeRef::List(new)) => is_safe_input_change(old, new),
+        (TypeRef::Named(_, old), TypeRef::Named(_, new)) => old == new,
+        _ => false,
+    }
+}
+
+/// The kinds of named types are resolved within each schema, so they differ when the named type
+/// was removed even though the reference itself didn't change
+fn is_same_type(old: &TypeRef, new: &TypeRef) -> bool {
+    match (old, new) {
+        (TypeRef::Named(_, old), TypeRef::Named(_, new)) => old == new,
+        (TypeRef::List(old), TypeRef::List(new)) => is_same_type(old, new),
+        (TypeRef::NonNull(old), TypeRef::NonNull(new)) => is_same_type(old, new),
+        _ => false,
+    }
+}
+
+fn is_required(value: &SchemaInputValue) -> bool {
+    matches!(value.ty, TypeRef::NonNull(_)) && value.default_value.is_none()
+}
+
+fn render(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::Named(_, name) => name.clone(),
+        TypeRef::List(ty) => format!("[{}]", render(ty)),
+        TypeRef::NonNull(ty) => format!("{}!", render(ty)),
+    }
+}
+
+fn render_default(default_value: &Option<String>) -> String {
+    match default_value {
+        Some(value) => format!("`{value}`"),
+        None => "none".to_owned(),
+    }
+}
+
+fn describe_kind(kind: SchemaTypeKind) -> &'static str {
+    match kind {
+        SchemaTypeKind::Scalar => "a scalar",
+        SchemaTypeKind::Object => "an object",
+        SchemaTypeKind::Interface => "an interface",
+        SchemaTypeKind::Union => "a union",
+        SchemaTypeKind::Enum => "an enum",
+        SchemaTypeKind::InputObject => "an input object",
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,117 @@
+use expect_test::{expect, Expect};
+use gqls_ide::{Change, Changeset, Ide, Schema};
+use std::collections::{HashMap, HashSet};
+
+use crate::{diff, Criticality};
+
+fn schema(text: &str) -> Schema {
+    let mut ide = Ide::default();
+    let file = ide.vfs().intern("schema.graphql");
+    let project = ide.intern_project("default".to_owned());
+    let projects = HashMap::from([(project, HashSet::from([file]))]);
+    ide.load(Changeset::single(Change::set(file, text.to_owned())).with_projects(projects));
+    ide.snapshot().schema(file)
+}
+
+fn test(old: &str, new: &str, expect: Expect) {
+    let changes = diff(&schema(old), &schema(new));
+    let changes = changes
+        .iter()
+        .map(|change| {
+            format!("{}: {}: {}\n", change.criticality, change.coordinate, change.message)
+        })
+        .collect::<String>();
+    expect.assert_eq(&changes);
+}
+
+#[test]
+fn test_diff_identical() {
+    let text = "type Query { foo(arg: Int = 1): [String!] } enum E { A }";
+    assert!(diff(&schema(text), &schema(text)).is_empty());
+}
+
+#[test]
+fn test_diff_types() {
+    test(
+        "type Query { a: A } type A { a: Int } union U = A scalar S enum E { X Y }",
+        "type Query { a: A } type B { b: Int } union U = A | B input S { s: Int } enum E { X Z }",
+        expect![[r#"
+            breaking: A: type `A` was removed
+            breaking: E.Y: value `Y` was removed from enum `E`
+            breaking: S: `S` changed from a scalar to an input object
+            dangerous: E.Z: value `Z` was added to enum `E`
+            dangerous: U: `B` was added to union `U`
+            safe: B: type `B` was added
+        "#]],
+    );
+}
+
+#[test]
+fn test_diff_fields() {
+    test(
+        r#"
+type Query {
+    removed: Int
+    nullable: Int
+    required: Int!
+    list: [Int!]!
+    args(a: Int, b: Int!, c: Int = 1): Int
+}
+"#,
+        r#"
+type Query {
+    nullable: Int!
+    required: Int
+    list: [Int]!
+    args(a: Int!, b: Int, c: Int = 2, d: Int, e: Int!): Int
+    added: Int @deprecated
+}
+"#,
+        expect![[r#"
+            breaking: Query.args(a:): argument `Query.args(a:)` changed type from `Int` to `Int!`
+            breaking: Query.args(e:): required argument `Query.args(e:)` was added
+            breaking: Query.list: field `Query.list` changed type from `[Int!]!` to `[Int]!`
+            breaking: Query.removed: field `Query.removed` was removed
+            breaking: Query.required: field `Query.required` changed type from `Int!` to `Int`
+            dangerous: Query.args(c:): default value of argument `Query.args(c:)` changed from `1` to `2`
+            dangerous: Query.args(d:): optional argument `Query.args(d:)` was added
+            safe: Query.added: field `Query.added` was added
+            safe: Query.args(b:): argument `Query.args(b:)` changed type from `Int!` to `Int`
+            safe: Query.nullable: field `Query.nullable` changed type from `Int` to `Int!`
+        "#]],
+    );
+}
+
+#[test]
+fn test_diff_with_extensions_and_directives() {
+    test(
+        r#"
+type Query { a: Int }
+extend type Query { b: Int }
+interface Node { id: ID! }
+type Foo implements Node { id: ID! }
+directive @d(a: Int) on FIELD_DEFINITION | OBJECT
+"#,
+        r#"
+type Query { a: Int }
+interface Node { id: ID! }
+type Foo { id: ID! }
+extend type Foo implements Node
+directive @d(a: Int, b: Int!) on FIELD_DEFINITION
+directive @e on OBJECT
+"#,
+        expect![[r#"
+            breaking: @d: location `OBJECT` was removed from `@d`
+            breaking: @d(b:): required argument `@d(b:)` was added
+            breaking: Query.b: field `Query.b` was removed
+            safe: @e: directive `@e` was added
+        "#]],
+    );
+}
+
+#[test]
+fn test_diff_is_sorted_by_criticality() {
+    let changes = diff(&schema("type Query { a: Int }"), &schema("type Query { b: Int }"));
+    let criticalities = changes.iter().map(|change| change.criticality).collect::<Vec<_>>();
+    assert_eq!(criticalities, [Criticality::Breaking, Criticality::Safe]);
+}
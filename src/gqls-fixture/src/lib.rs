@@ -35,15 +35,40 @@ macro_rules! fixture {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Fixture {
     files: HashMap<FileId, FixtureFile>,
+    /// if empty, all files belong to a single `default` project
+    projects: HashMap<&'static str, HashSet<FileId>>,
 }
 
 impl Fixture {
     pub fn new(files: HashMap<FileId, FixtureFile>) -> Self {
-        Self { files }
+        Self { files, projects: Default::default() }
     }
 
     pub fn single(file: FixtureFile) -> Self {
-        Self { files: hashmap! { std::path::Path::new("") => file } }
+        Self::new(hashmap! { std::path::Path::new("") => file })
+    }
+
+    /// Add a project consisting of the given fixture files, a file may be in many projects.
+    /// Once any project is declared, the files that are not in one are excluded from all of them.
+    pub fn with_project(mut self, project: &'static str, files: &[&'static str]) -> Self {
+        let files = files
+            .iter()
+            .map(|&file| {
+                let path = std::path::Path::new(file);
+                assert!(self.files.contains_key(path), "unknown fixture file `{file}`");
+                path
+            })
+            .collect();
+        self.projects.insert(project, files);
+        self
+    }
+
+    pub fn projects(&self) -> HashMap<&'static str, HashSet<FileId>> {
+        if self.projects.is_empty() {
+            hashmap! { "default" => self.fileset() }
+        } else {
+            self.projects.clone()
+        }
     }
 
     pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
@@ -59,6 +84,10 @@ impl Fixture {
         self.files.iter().map(|(&path, file)| (path, file.annotations.iter()))
     }
 
+    pub fn diagnostics(&self) -> impl Iterator<Item = (FileId, &[ExpectedDiagnostic])> + '_ {
+        self.files.iter().map(|(&path, file)| (path, &file.diagnostics[..]))
+    }
+
     pub fn ranges(&self) -> impl Iterator<Item = (FileId, std::ops::Range<Point>)> + '_ {
         self.files
             .iter()
@@ -77,15 +106,20 @@ impl Fixture {
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// A fixture file supports three forms of annotations:
 /// - points (`^`) (if ^ points to a range, then it is shifted a further one up)
+///   - `>` (as the first character of the comment) is a point at the end of the preceding line,
+///     regardless of its column
+///   - a line consisting of only `#eof` is a point at the end of the file
 /// - inline ranges (`...`)
 ///   - if an inline range is immediately followed by a open paren  `(`, then it is treated as an annotation up to the closing paren
 /// - delimited ranges `(delimited above by `{` and below by `}` )`
 ///   This ranges from the start of the following line of `{` to the end of the the preceding line of `}`
+/// - expected diagnostics (`~~~ E0002: message`), see [`ExpectedDiagnostic`]
 pub struct FixtureFile {
     pub points: Vec<Point>,
     pub ranges: Vec<std::ops::Range<Point>>,
     pub text: String,
     pub annotations: Vec<Annotation>,
+    pub diagnostics: Vec<ExpectedDiagnostic>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -94,17 +128,47 @@ pub struct Annotation {
     pub text: String,
 }
 
+/// A diagnostic expected at the range of the `~` on the line above, the `~` are followed by its
+/// code and optionally by its message, e.g. `#~~~ E0002: unresolved type `Foo``. The rest of the
+/// line is the message, so there can only be one per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedDiagnostic {
+    pub range: std::ops::Range<Point>,
+    pub code: String,
+    /// only the code is compared if `None`
+    pub message: Option<String>,
+}
+
+impl ExpectedDiagnostic {
+    fn parse(range: std::ops::Range<Point>, text: &str) -> Self {
+        let (code, message) = match text.split_once(':') {
+            Some((code, message)) => (code.trim(), Some(message.trim().to_owned())),
+            None => (text.trim(), None),
+        };
+        assert!(!code.is_empty(), "expected a diagnostic code after `~`");
+        Self { range, code: code.to_owned(), message }
+    }
+}
+
 impl FixtureFile {
     pub fn parse(fixture: &str) -> Self {
         let mut points = vec![];
         let mut ranges = vec![];
         let mut annotations = vec![];
+        let mut diagnostics = vec![];
         let mut stack = vec![];
 
         for (row, (line, prev_line)) in
             fixture.lines().zip(std::iter::once("").chain(fixture.lines())).enumerate()
         {
             let mut range_start = None;
+            let mut diagnostic_start = None;
+            // only a comment that starts with `~` expects diagnostics, other comments may contain it
+            let annotation = line.trim_start().trim_start_matches('#').trim_start();
+            let expects_diagnostic = annotation.starts_with('~');
+            // `>` is only a point as the first character of the comment, not anywhere within it
+            let end_of_line_column =
+                annotation.starts_with('>').then(|| line.len() - annotation.len());
             for (column, char) in line.char_indices() {
                 if char == '$' {
                     points.push(Point { row, column });
@@ -114,6 +178,18 @@ impl FixtureFile {
                     continue;
                 }
 
+                if char == '~' && expects_diagnostic {
+                    if row == 0 {
+                        panic!("cannot contain `~` in the first line");
+                    }
+                    diagnostic_start.get_or_insert(Point { row: row - 1, column });
+                    continue;
+                } else if let Some(start) = diagnostic_start.take() {
+                    let range = start..Point { row: row - 1, column };
+                    diagnostics.push(ExpectedDiagnostic::parse(range, &line[column..]));
+                    break;
+                }
+
                 if char == '{' {
                     stack.push(Point { row: row + 1, column: 0 });
                 } else if char == '}' {
@@ -142,6 +218,13 @@ impl FixtureFile {
                     }
                 }
 
+                if char == '>' && Some(column) == end_of_line_column {
+                    if row == 0 {
+                        panic!("cannot contain `>` in the first line");
+                    }
+                    points.push(Point { row: row - 1, column: prev_line.len() });
+                }
+
                 if char == '^' {
                     if row == 0 {
                         panic!("cannot contain `^` in the first line");
@@ -157,11 +240,29 @@ impl FixtureFile {
             if let Some(start) = range_start {
                 ranges.push(start..Point { row: row - 1, column: line.len() });
             }
+
+            assert!(diagnostic_start.is_none(), "expected a diagnostic code after `~`");
+
+            if line.trim() == "#eof" {
+                points.push(end_of_file(fixture));
+            }
         }
 
-        Self { points, ranges, annotations, text: fixture.replace('$', " ") }
+        Self { points, ranges, annotations, diagnostics, text: fixture.replace('$', " ") }
     }
 }
 
+/// The point at the end of line `row` of `text`
+pub fn end_of_line(text: &str, row: usize) -> Point {
+    let line = text.split('\n').nth(row).unwrap_or_else(|| panic!("no line {row} in the text"));
+    Point { row, column: line.len() }
+}
+
+/// The point after the last character of `text`
+pub fn end_of_file(text: &str) -> Point {
+    let row = text.matches('\n').count();
+    end_of_line(text, row)
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,6 @@
 use gqls_syntax::Point;
 
-use crate::{Annotation, FixtureFile};
+use crate::{end_of_file, end_of_line, Annotation, ExpectedDiagnostic, FixtureFile};
 
 fn test(
     fixture: &str,
@@ -88,3 +88,103 @@ scalar E
         }],
     );
 }
+
+#[test]
+fn test_expected_diagnostics() {
+    let fixture = FixtureFile::parse(
+        r#"
+type Foo { bar: Bar baz: [Baz] }
+#               ~~~ E0002: unresolved type `Bar` (e.g. ^...)
+#                         ~~~ E0002
+        "#,
+    );
+    assert_eq!(
+        fixture.diagnostics,
+        [
+            ExpectedDiagnostic {
+                range: Point { row: 1, column: 16 }..Point { row: 1, column: 19 },
+                code: "E0002".to_owned(),
+                message: Some("unresolved type `Bar` (e.g. ^...)".to_owned()),
+            },
+            ExpectedDiagnostic {
+                range: Point { row: 1, column: 26 }..Point { row: 1, column: 29 },
+                code: "E0002".to_owned(),
+                message: None,
+            },
+        ]
+    );
+    // the messages aren't parsed for other annotations
+    assert!(fixture.points.is_empty());
+    assert!(fixture.ranges.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "expected a diagnostic code after `~`")]
+fn test_expected_diagnostic_without_code() {
+    FixtureFile::parse(
+        "scalar Foo
+#      ~~~",
+    );
+}
+
+#[test]
+fn test_tilde_in_comment() {
+    let fixture = FixtureFile::parse(
+        "# see ~/schema.graphql
+type Foo { bar: Bar }
+# neither is the ~ in this comment",
+    );
+    assert!(fixture.diagnostics.is_empty());
+}
+
+#[test]
+fn test_end_of_line_points() {
+    test(
+        r#"
+scalar Foo
+#>
+  scalar Bar @d
+#    >
+    "#,
+        vec![Point { row: 1, column: 10 }, Point { row: 3, column: 15 }],
+        vec![],
+        vec![],
+    );
+}
+
+#[test]
+fn test_arrow_in_comment() {
+    test(
+        r#"
+scalar Foo
+# Foo -> Bar is not a point
+  scalar Bar
+#    >
+    "#,
+        vec![Point { row: 3, column: 12 }],
+        vec![],
+        vec![],
+    );
+}
+
+#[test]
+fn test_end_of_file_point() {
+    test(
+        r#"
+scalar Foo
+#eof
+    "#,
+        vec![Point { row: 3, column: 4 }],
+        vec![],
+        vec![],
+    );
+}
+
+#[test]
+fn test_end_of_line_and_file() {
+    let text = "scalar Foo\n  scalar Bar\n";
+    assert_eq!(end_of_line(text, 0), Point { row: 0, column: 10 });
+    assert_eq!(end_of_line(text, 1), Point { row: 1, column: 12 });
+    assert_eq!(end_of_file(text), Point { row: 2, column: 0 });
+    assert_eq!(end_of_file("scalar Foo"), Point { row: 0, column: 10 });
+}
@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use gqls_ide::{Change, Changeset, Ide};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+const FILES: usize = 200;
+
+// each file defines a few types that refer to the types of the previous file
+fn schema_file(i: usize) -> String {
+    let mut text = String::new();
+    for j in 0..10 {
+        let prev = if i == 0 { "ID".to_owned() } else { format!("T{}_{j}", i - 1) };
+        writeln!(text, "type T{i}_{j} {{").unwrap();
+        writeln!(text, "  id: ID!").unwrap();
+        writeln!(text, "  prev(first: Int = 10): [{prev}!]!").unwrap();
+        writeln!(text, "}}").unwrap();
+    }
+    text
+}
+
+fn analysis(c: &mut Criterion) {
+    let mut ide = Ide::default();
+    let project = ide.intern_project("bench".to_owned());
+    let files = (0..FILES)
+        .map(|i| ide.intern_path(format!("bench/file{i}.graphql").into()))
+        .collect::<Vec<_>>();
+    let projects = HashMap::from([(project, files.iter().copied().collect::<HashSet<_>>())]);
+    let mut changeset = Changeset::default().with_projects(projects);
+    for (i, &file) in files.iter().enumerate() {
+        changeset = changeset.with_change(Change::set(file, schema_file(i)));
+    }
+    let _ = ide.apply(changeset);
+
+    c.bench_function("analyze_project", |b| b.iter(|| ide.snapshot().analyze_project(project)));
+    c.bench_function("analyze_project_parallel", |b| {
+        b.iter(|| ide.analyze_project_parallel(project).unwrap())
+    });
+}
+
+criterion_group!(benches, analysis);
+criterion_main!(benches);
@@ -0,0 +1,81 @@
+use gqls_db::{ParallelDatabase, Project, SourceDatabase};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use vfs::FileId;
+
+use crate::{Cancellable, Diagnostics, Ide, SemanticToken, Snapshot};
+
+/// Everything that is published for the files of a project
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProjectAnalysis {
+    pub diagnostics: Diagnostics,
+    pub semantic_tokens: HashMap<FileId, Vec<SemanticToken>>,
+}
+
+impl Snapshot {
+    /// Analyze the files of `project` one after the other, see [`Snapshot::par_analyze_project`]
+    pub fn analyze_project(&self, project: Project) -> ProjectAnalysis {
+        let mut analysis = ProjectAnalysis::default();
+        for file in self.project_files(project) {
+            analysis.diagnostics.insert(file, self.file_diagnostics(file));
+            analysis.semantic_tokens.insert(file, self.semantic_tokens(file));
+        }
+        analysis
+    }
+
+    /// Analyze the files of `project` concurrently
+    pub fn par_analyze_project(&self, project: Project) -> ProjectAnalysis {
+        let mut analysis = ProjectAnalysis::default();
+        let results = self.par_map_files(project, |snapshot, file| {
+            (snapshot.file_diagnostics(file), snapshot.semantic_tokens(file))
+        });
+        for (file, (diagnostics, tokens)) in results {
+            analysis.diagnostics.insert(file, diagnostics);
+            analysis.semantic_tokens.insert(file, tokens);
+        }
+        analysis
+    }
+
+    /// Compute `f` for each file of `project` on the rayon pool. A snapshot can't be shared
+    /// between threads, so the files are split into a chunk per thread and each chunk gets a
+    /// snapshot of its own. A cancellation unwinds from the worker into the caller as usual.
+    pub(crate) fn par_map_files<T: Send>(
+        &self,
+        project: Project,
+        f: impl Fn(&Snapshot, FileId) -> T + Sync,
+    ) -> Vec<(FileId, T)> {
+        let mut files = self.project_files(project).into_iter().collect::<Vec<_>>();
+        if files.is_empty() {
+            return vec![];
+        }
+        files.sort();
+        let threads = rayon::current_num_threads().max(1);
+        let chunk_size = (files.len() + threads - 1) / threads;
+        let jobs = files.chunks(chunk_size).map(|chunk| (self.fork(), chunk)).collect::<Vec<_>>();
+        jobs.into_par_iter()
+            .flat_map_iter(|(snapshot, files)| {
+                files.iter().map(|&file| (file, f(&snapshot, file))).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Another snapshot of the same revision that can be sent to another thread
+    fn fork(&self) -> Snapshot {
+        Snapshot {
+            snapshot: self.snapshot.snapshot(),
+            lints: Arc::clone(&self.lints),
+            field_set_directives: Arc::clone(&self.field_set_directives),
+        }
+    }
+}
+
+impl Ide {
+    /// Analyze the files of `project` concurrently, see [`Snapshot::par_analyze_project`]
+    pub fn analyze_project_parallel(&self, project: Project) -> Cancellable<ProjectAnalysis> {
+        self.snapshot().catch_cancelled(|snapshot| snapshot.par_analyze_project(project))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,32 @@
+use gqls_fixture::fixture;
+
+use crate::{DiagnosticPass, Ide};
+
+#[test]
+fn test_parallel_analysis_matches_serial_analysis() {
+    let fixture = fixture! {
+        "foo" => "type Foo { bar: Bar }"
+        "bar" => "type Bar { foo: Foo baz: Baz }"
+        "baz" => "type Baz { id: ID! } scalar Baz"
+        "qux" => "type Qux implements Node { id: ID! }"
+    }
+    .with_project("a", &["foo", "bar", "baz", "qux"]);
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let project = ide.intern_project("a".to_owned());
+
+    let parallel = ide.analyze_project_parallel(project).unwrap();
+    let serial = ide.snapshot().analyze_project(project);
+    assert_eq!(parallel.diagnostics.len(), 4);
+    assert_eq!(parallel.semantic_tokens.len(), 4);
+    assert_eq!(parallel, serial);
+    assert!(!parallel.diagnostics.values().all(|diagnostics| diagnostics.is_empty()));
+
+    let snapshot = ide.snapshot();
+    for pass in [DiagnosticPass::Local, DiagnosticPass::Project] {
+        let diagnostics = snapshot.pass_diagnostics(project, pass);
+        assert_eq!(diagnostics.len(), 4);
+        for (&file, diagnostics) in &diagnostics {
+            assert_eq!(diagnostics, &snapshot.file_pass_diagnostics(file, pass));
+        }
+    }
+}
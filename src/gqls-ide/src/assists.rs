@@ -0,0 +1,183 @@
+//! Assists are the refactorings that apply at a selection of a file. They are listed without
+//! their edits, which are only built for the assists that are resolved (e.g. once one is picked).
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_syntax::Position;
+use vfs::FileId;
+
+use crate::{Range, Snapshot, SourceChange};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum AssistKind {
+    /// rearranges the definition at the cursor without changing its meaning
+    Organize,
+    Extract,
+    Inline,
+    Rewrite,
+}
+
+impl AssistKind {
+    pub const ALL: [AssistKind; 4] =
+        [AssistKind::Organize, AssistKind::Extract, AssistKind::Inline, AssistKind::Rewrite];
+}
+
+/// Identifies an assist across requests, so a listed assist can be resolved later on
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct AssistId(pub &'static str, pub AssistKind);
+
+/// Which of the listed assists have their edits built
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AssistResolveStrategy {
+    None,
+    All,
+    /// only the assist of the given id
+    Single(String),
+}
+
+impl Default for AssistResolveStrategy {
+    fn default() -> Self {
+        AssistResolveStrategy::None
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AssistConfig {
+    /// only the assists of these kinds are considered, all of them if `None`
+    pub allowed: Option<Vec<AssistKind>>,
+    pub resolve: AssistResolveStrategy,
+}
+
+impl AssistConfig {
+    fn allows(&self, kind: AssistKind) -> bool {
+        self.allowed.as_ref().map_or(true, |allowed| allowed.contains(&kind))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assist {
+    pub id: AssistId,
+    pub label: String,
+    /// the range the assist applies to
+    pub target: Range,
+    /// `None` unless the assist is resolved
+    pub change: Option<SourceChange>,
+    /// where the user is expected to rename once the change is applied, e.g. the placeholder name
+    /// of an extracted type, `None` unless the assist is resolved
+    pub rename_at: Option<Position>,
+}
+
+struct AssistContext {
+    file: FileId,
+    range: Range,
+}
+
+impl AssistContext {
+    fn position(&self) -> Position {
+        Position::new(self.file, self.range.start)
+    }
+}
+
+struct Assists<'a> {
+    config: &'a AssistConfig,
+    assists: Vec<Assist>,
+}
+
+impl Assists<'_> {
+    /// Add an assist whose edit is only built if the assist is to be resolved, it isn't added
+    /// after all if the edit can't be built
+    fn add(
+        &mut self,
+        id: AssistId,
+        label: impl Into<String>,
+        target: Range,
+        edit: impl FnOnce() -> Option<SourceChange>,
+    ) -> Option<&mut Assist> {
+        let resolve = match &self.config.resolve {
+            AssistResolveStrategy::None => false,
+            AssistResolveStrategy::All => true,
+            AssistResolveStrategy::Single(single) => single.as_str() == id.0,
+        };
+        let label = label.into();
+        let change = if resolve { Some(edit()?) } else { None };
+        self.assists.push(Assist { id, label, target, change, rename_at: None });
+        self.assists.last_mut()
+    }
+}
+
+type Handler = fn(&Snapshot, &AssistContext, &mut Assists<'_>) -> Option<()>;
+
+const HANDLERS: [(AssistKind, Handler); 4] = [
+    (AssistKind::Organize, organize_fields),
+    (AssistKind::Extract, extract_type),
+    (AssistKind::Inline, inline_type),
+    (AssistKind::Rewrite, convert_type),
+];
+
+impl Snapshot {
+    /// The assists that apply to `range` of `file`, in a fixed order. There are none in generated
+    /// files, which aren't edited by hand.
+    pub fn assists(&self, config: &AssistConfig, file: FileId, range: Range) -> Vec<Assist> {
+        if self.is_generated(file) {
+            return vec![];
+        }
+        let ctx = AssistContext { file, range };
+        let mut acc = Assists { config, assists: vec![] };
+        for (kind, handler) in HANDLERS {
+            if config.allows(kind) {
+                handler(self, &ctx, &mut acc);
+            }
+        }
+        acc.assists
+    }
+}
+
+fn organize_fields(snapshot: &Snapshot, ctx: &AssistContext, acc: &mut Assists<'_>) -> Option<()> {
+    let target = snapshot.unorganized_fields(ctx.position())?;
+    let id = AssistId("organize_fields", AssistKind::Organize);
+    acc.add(id, "Sort fields alphabetically", target, || {
+        let patch = snapshot.organize_fields(ctx.position())?;
+        Some(SourceChange::single(ctx.file, vec![patch]))
+    });
+    Some(())
+}
+
+fn extract_type(snapshot: &Snapshot, ctx: &AssistContext, acc: &mut Assists<'_>) -> Option<()> {
+    if !snapshot.can_extract_type(ctx.file, ctx.range) {
+        return None;
+    }
+    let id = AssistId("extract_type", AssistKind::Extract);
+    let mut rename_at = None;
+    let assist = acc.add(id, "Extract fields into a new type", ctx.range, || {
+        let extracted = snapshot.extract_type(ctx.file, ctx.range)?;
+        rename_at = Some(Position::new(ctx.file, extracted.name));
+        Some(SourceChange::single(ctx.file, extracted.patches))
+    })?;
+    assist.rename_at = rename_at;
+    Some(())
+}
+
+fn inline_type(snapshot: &Snapshot, ctx: &AssistContext, acc: &mut Assists<'_>) -> Option<()> {
+    let inlining = snapshot.inlining(ctx.position())?;
+    let target = snapshot.name_at(ctx.position())?.range.into();
+    let id = AssistId("inline_type", AssistKind::Inline);
+    acc.add(id, "Inline input type", target, || inlining.change(snapshot));
+    Some(())
+}
+
+fn convert_type(snapshot: &Snapshot, ctx: &AssistContext, acc: &mut Assists<'_>) -> Option<()> {
+    let convertible = snapshot.convertible(ctx.position())?;
+    let target = snapshot.name_at(ctx.position())?.range.into();
+    // an interface can't be a member of a union, so warn about those that would break
+    let title = convertible.kind.title();
+    let label = match convertible.conflicts(snapshot)?.len() {
+        0 => title.to_owned(),
+        1 => format!("{title} (breaks 1 union membership)"),
+        n => format!("{title} (breaks {n} union memberships)"),
+    };
+    let id = AssistId("convert_type", AssistKind::Rewrite);
+    acc.add(id, label, target, || convertible.change(snapshot));
+    Some(())
+}
+
+#[cfg(test)]
+mod tests;
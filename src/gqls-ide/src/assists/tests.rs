@@ -0,0 +1,77 @@
+use gqls_fixture::{fixture, Fixture};
+
+use crate::{AssistConfig, AssistKind, AssistResolveStrategy, Ide, Point, Range};
+
+// the `$` of the fixture is placed right before the name of the type
+#[track_caller]
+fn test(fixture: &Fixture, config: AssistConfig, expected: &[(&str, bool)]) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let position = fixture.positions().next().expect("fixture must have a position");
+    let point = Point::new(position.point.row, position.point.column + 1);
+    let range = Range { start: point, end: point };
+    let assists = ide.snapshot().assists(&config, position.file, range);
+    let actual = assists
+        .iter()
+        .map(|assist| (assist.id.0, assist.change.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_assists_are_listed_without_edits() {
+    let fixture = fixture! {
+        "foo" => "type$Foo { b: Int a: Int }"
+    };
+    test(&fixture, AssistConfig::default(), &[("organize_fields", false), ("convert_type", false)]);
+}
+
+#[test]
+fn test_resolve_single_assist() {
+    let fixture = fixture! {
+        "foo" => "type$Foo { b: Int a: Int }"
+    };
+    let config = AssistConfig {
+        resolve: AssistResolveStrategy::Single("convert_type".to_owned()),
+        ..Default::default()
+    };
+    test(&fixture, config, &[("organize_fields", false), ("convert_type", true)]);
+}
+
+#[test]
+fn test_assists_of_allowed_kinds() {
+    let fixture = fixture! {
+        "foo" => "type$Foo { b: Int a: Int }"
+    };
+    let config = AssistConfig {
+        allowed: Some(vec![AssistKind::Rewrite]),
+        resolve: AssistResolveStrategy::All,
+    };
+    test(&fixture, config, &[("convert_type", true)]);
+}
+
+#[test]
+fn test_no_assists_in_generated_files() {
+    let fixture = fixture! {
+        "foo" => "# @generated\ntype$Foo { b: Int a: Int }"
+    };
+    let config = AssistConfig { resolve: AssistResolveStrategy::All, ..Default::default() };
+    test(&fixture, config, &[]);
+}
+
+#[test]
+fn test_extracted_type_is_renamed_once_resolved() {
+    let fixture = fixture! {
+        "foo" => "type Foo { $a: Int b: Int }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().expect("fixture must have a position");
+    let point = Point::new(position.point.row, position.point.column + 1);
+    let range = Range { start: point, end: point };
+    let strategies = [(AssistResolveStrategy::None, false), (AssistResolveStrategy::All, true)];
+    for (resolve, renamed) in strategies {
+        let config = AssistConfig { allowed: Some(vec![AssistKind::Extract]), resolve };
+        let assists = ide.snapshot().assists(&config, position.file, range);
+        let renamed_at = assists.iter().map(|assist| assist.rename_at.is_some());
+        assert_eq!(renamed_at.collect::<Vec<_>>(), [renamed]);
+    }
+}
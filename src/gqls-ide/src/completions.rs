@@ -1,16 +1,52 @@
+use std::cmp::Reverse;
 use std::collections::HashSet;
 use std::fmt::{self, Debug};
+use std::str::FromStr;
 
-use gqls_db::{DefDatabase, SourceDatabase};
-use gqls_ir::{DirectiveLocations, InProject, ItemKind, TypeDefinitionKind};
-use gqls_syntax::{NodeExt, NodeKind, Position};
+use gqls_db::{DefDatabase, SourceDatabase, TyDatabase};
+use gqls_ir::{
+    DirectiveLocations, InProject, ItemBodyKind, ItemKind, ItemRes, Name, Ty, TypeDefinitionKind
+};
+use gqls_syntax::{query, Node, NodeExt, NodeKind, Point, Position, Query, QueryCursor, Range};
+use gqls_ty::TyKind;
+use once_cell::sync::Lazy;
+use vfs::FileId;
 
-use crate::Snapshot;
+use crate::{render, Snapshot};
 
-#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CompletionItem {
     pub label: String,
     pub kind: CompletionItemKind,
+    /// e.g. the arguments of a directive
+    pub detail: Option<String>,
+    /// snippet to insert instead of the label
+    pub insert_text: Option<String>,
+    /// the text the item is matched against instead of the label
+    pub filter_text: Option<String>,
+    /// markdown, the description of the item
+    pub documentation: Option<String>,
+    /// deprecated items are ordered last
+    pub deprecated: bool,
+    pub locality: Locality,
+    /// the text the item is sorted by, reflecting the order of the completions
+    pub sort_text: Option<String>,
+}
+
+impl CompletionItem {
+    fn new(label: impl Into<String>, kind: CompletionItemKind) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+            detail: None,
+            insert_text: None,
+            filter_text: None,
+            documentation: None,
+            deprecated: false,
+            locality: Locality::Project,
+            sort_text: None,
+        }
+    }
 }
 
 impl Debug for CompletionItem {
@@ -19,7 +55,15 @@ impl Debug for CompletionItem {
     }
 }
 
-#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+/// Where an item is defined relative to the file being completed, closer items are ordered first
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub enum Locality {
+    File,
+    /// elsewhere in the project (or not defined at all, e.g. keywords)
+    Project,
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
 pub enum CompletionItemKind {
     Object,
     InputObject,
@@ -30,106 +74,664 @@ pub enum CompletionItemKind {
     Keyword,
     DirectiveLocation,
     Directive(DirectiveLocations),
+    EnumValue,
+    /// a field of an input object written in an object value
+    InputField,
+    /// an argument of an applied directive
+    Argument,
+    /// a template for the description (`"""` docstring) of a definition
+    Description,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompletionConfig {
+    pub type_wrappers: TypeWrappers,
+    pub docstrings: Docstrings,
+}
+
+/// How the non-null and list wrappers are offered when completing the type of a field,
+/// argument or input field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeWrappers {
+    /// only the named type, e.g. `Foo`
+    None,
+    /// an item per common wrapping of the type: `Foo`, `Foo!` and `[Foo!]!`
+    Variants,
+    /// a single snippet with a choice between the wrappings of `Variants`
+    Snippet,
+}
+
+impl TypeWrappers {
+    pub const ALL: [TypeWrappers; 3] =
+        [TypeWrappers::None, TypeWrappers::Variants, TypeWrappers::Snippet];
+
+    /// The name used in the settings
+    pub fn name(self) -> &'static str {
+        match self {
+            TypeWrappers::None => "none",
+            TypeWrappers::Variants => "variants",
+            TypeWrappers::Snippet => "snippet",
+        }
+    }
+
+    fn wrappings(name: &str) -> [String; 3] {
+        [name.to_owned(), format!("{name}!"), format!("[{name}!]!")]
+    }
+}
+
+impl Default for TypeWrappers {
+    fn default() -> Self {
+        TypeWrappers::None
+    }
+}
+
+impl FromStr for TypeWrappers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TypeWrappers::ALL
+            .into_iter()
+            .find(|wrappers| wrappers.name() == s)
+            .ok_or_else(|| format!("unknown type wrappers `{s}`"))
+    }
+}
+
+/// What the template completed after the opening `"""` of a description consists of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Docstrings {
+    /// no template is completed
+    None,
+    /// the closing `"""` with a line for the summary in between
+    Summary,
+    /// the summary followed by a bulleted list of the arguments (of a field or directive)
+    Arguments,
+}
+
+impl Docstrings {
+    pub const ALL: [Docstrings; 3] = [Docstrings::None, Docstrings::Summary, Docstrings::Arguments];
+
+    /// The name used in the settings
+    pub fn name(self) -> &'static str {
+        match self {
+            Docstrings::None => "none",
+            Docstrings::Summary => "summary",
+            Docstrings::Arguments => "arguments",
+        }
+    }
+}
+
+impl Default for Docstrings {
+    fn default() -> Self {
+        Docstrings::Arguments
+    }
+}
+
+impl FromStr for Docstrings {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Docstrings::ALL
+            .into_iter()
+            .find(|docstrings| docstrings.name() == s)
+            .ok_or_else(|| format!("unknown docstrings `{s}`"))
+    }
 }
 
 impl Snapshot {
-    pub fn completions(&self, position: Position) -> Vec<CompletionItem> {
-        CompletionCtxt::new(self, position).completions()
+    pub fn completions(
+        &self,
+        position: Position,
+        config: &CompletionConfig,
+    ) -> Vec<CompletionItem> {
+        CompletionCtxt::new(self, position, *config).completions()
     }
 }
 
 struct CompletionCtxt<'s> {
     snapshot: &'s Snapshot,
     project: InProject<()>,
+    position: Position,
     context: Context,
+    config: CompletionConfig,
     completions: Vec<CompletionItem>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Context {
     Document,
+    /// after `extend`
+    Extend,
     InputField,
     Field,
-    UnionMembers,
-    DirectiveLocations,
-    Interface,
+    /// the members of the union of the given name, if it has one
+    UnionMembers(Option<Name>),
+    /// the type of an operation of a schema definition, e.g. after `query: `
+    RootOperationType,
+    /// the locations of a directive definition, excluding the ones already listed
+    DirectiveLocations(DirectiveLocations),
+    /// the interfaces implemented by the interface of the given name, which can't implement
+    /// itself (there is no name when an object implements them)
+    Interface(Option<Name>),
     Directive(DirectiveLocations),
+    /// a default value or directive argument of the expected type, if it is known
+    Value(Option<ExpectedType>),
+    /// the key of a field of an object value of the expected input object, if it is known
+    ObjectField(Option<ExpectedType>),
+    /// right after the opening `"""` of a description
+    Description,
+    /// right after any other quote, where nothing is completed
+    String,
+    /// where a name is written, e.g. of a field or an argument definition, nothing is completed
+    /// there either
+    Name,
+    /// the arguments of the applied directive of the given name, e.g. `@deprecated($)`
+    DirectiveArguments(Name),
 }
 
-struct Queries {}
+/// The type of the value being written, e.g. `Color` (which can't be null) of the elements of a
+/// `[Color!]` list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExpectedType {
+    /// the name of the named type, which is the same for the elements of a list
+    name: Name,
+    nullable: bool,
+}
+
+/// A step from a value into the value nested in it that the cursor is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// into an element of a list
+    Element,
+    /// into the value of the field of the given name of an object
+    Field(Name),
+}
+
+impl ExpectedType {
+    /// The type of the value reached by following `path` from a value of type `ty`, the fields
+    /// of input objects are looked up in the project of `file`
+    fn new(snapshot: &Snapshot, file: FileId, ty: Ty, path: &[Step]) -> Option<Self> {
+        let mut name = ty.name();
+        let mut ty = snapshot.lower_type(ty);
+        for step in path {
+            let inner = match &ty.kind {
+                TyKind::NonNull(inner) => inner.clone(),
+                _ => ty.clone(),
+            };
+            ty = match (step, &inner.kind) {
+                (Step::Element, TyKind::List(element)) => element.clone(),
+                // not a list, so the value doesn't type check wherever it is
+                (Step::Element, _) => break,
+                (Step::Field(field), TyKind::Input(_)) => {
+                    let res = snapshot.field_of(InProject::new(file, name), field.as_str())?;
+                    name = snapshot.field(res).ty.name();
+                    snapshot.type_of_field(res)
+                }
+                (Step::Field(_), _) => return None,
+            };
+        }
+        Some(Self { name, nullable: ty.is_nullable() })
+    }
+}
+
+/// The keywords that start a type system definition, along with the kind of the extension of
+/// the definition if it can be extended
+const DEFINITION_KEYWORDS: [(&str, Option<&str>); 8] = [
+    ("type", Some(NodeKind::OBJECT_TYPE_EXTENSION)),
+    ("interface", Some(NodeKind::INTERFACE_TYPE_EXTENSION)),
+    ("union", Some(NodeKind::UNION_TYPE_EXTENSION)),
+    ("enum", Some(NodeKind::ENUM_TYPE_EXTENSION)),
+    ("input", Some(NodeKind::INPUT_OBJECT_TYPE_EXTENSION)),
+    ("scalar", Some(NodeKind::SCALAR_TYPE_EXTENSION)),
+    ("schema", Some(NodeKind::SCHEMA_EXTENSION)),
+    ("directive", None),
+];
+
+const EXTEND: &str = "extend";
+
+const BLOCK_QUOTE: &str = "\"\"\"";
+
+/// The definitions that can be described, the ones with arguments list them in the template
+const DESCRIBED_DEFINITIONS: [&str; 11] = [
+    NodeKind::SCHEMA_DEFINITION,
+    NodeKind::SCALAR_TYPE_DEFINITION,
+    NodeKind::OBJECT_TYPE_DEFINITION,
+    NodeKind::INTERFACE_TYPE_DEFINITION,
+    NodeKind::UNION_TYPE_DEFINITION,
+    NodeKind::ENUM_TYPE_DEFINITION,
+    NodeKind::INPUT_OBJECT_TYPE_DEFINITION,
+    NodeKind::DIRECTIVE_DEFINITION,
+    NodeKind::FIELD_DEFINITION,
+    NodeKind::INPUT_VALUE_DEFINITION,
+    NodeKind::ENUM_VALUE_DEFINITION,
+];
+
+/// The nodes the context of a cursor is inferred from, the innermost node the cursor is in
+/// determines the context by the name of its capture
+const CONTEXT_QUERY: &str = r#"
+(value) @value
+(default_value) @value
+(argument) @value
+(directive (arguments) @directive_arguments)
+[(fields_definition) (input_fields_definition) (arguments_definition)] @name
+(implements_interfaces) @implements
+(union_member_types) @union_members
+(root_operation_type_definition) @root_operation_type
+(directive_locations) @directive_locations
+(field_definition) @field
+(input_value_definition) @input_field
+[(enum_values_definition) (enum_value_definition)] @enum_value
+[(object_type_definition) (object_type_extension)] @object
+[(interface_type_definition) (interface_type_extension)] @interface
+[(union_type_definition) (union_type_extension)] @union
+[(enum_type_definition) (enum_type_extension)] @enum
+[(scalar_type_definition) (scalar_type_extension)] @scalar
+[(input_object_type_definition) (input_object_type_extension)] @input_object
+"#;
+
+/// The `ERROR` nodes that incomplete definitions are commonly recovered into, in order of
+/// precedence
+const RECOVERY_QUERY: &str = r#"
+; `@directive(arg: ` without the closing parenthesis
+(ERROR . "(" (name) @argument . ":" .) @argument_value
+; `@directive(` without the closing parenthesis
+(ERROR . "(") @arguments
+; `type Foo { field: ` without the closing brace
+(ERROR . "{" (name) . ":" .) @fields
+; `arg: Type = ` without the default value
+(ERROR . "=" .) @default_value
+"#;
+
+static QUERIES: Lazy<Queries> = Lazy::new(Queries::default);
+
+struct Queries {
+    context: Query,
+    recovery: Query,
+}
 
 impl Default for Queries {
     fn default() -> Self {
-        Self {}
+        Self { context: query(CONTEXT_QUERY), recovery: query(RECOVERY_QUERY) }
     }
 }
 
+impl Queries {
+    /// The innermost node of the context query that `anchor` is in, along with its capture
+    fn context_node<'tree>(
+        &self,
+        root: Node<'tree>,
+        text: &str,
+        anchor: Point,
+    ) -> Option<(&str, Node<'tree>)> {
+        // the cursor is still in a node it is at the end of, unless the node is closed
+        let contains = |node: Node<'_>| {
+            node.start_position() < anchor
+                && anchor <= node.end_position()
+                && (anchor < node.end_position() || !is_closed(node))
+        };
+        // a node that contains the anchor, even one that ends at it, intersects the lines around it
+        let mut cursor = QueryCursor::new();
+        cursor.set_point_range(
+            Point::new(anchor.row.saturating_sub(1), 0)..Point::new(anchor.row + 1, 0),
+        );
+        let capture = cursor
+            .captures(&self.context, root, text.as_bytes())
+            .map(|(captures, i)| captures.captures[i])
+            .filter(|capture| contains(capture.node))
+            .min_by_key(|capture| (Reverse(capture.node.start_byte()), capture.node.end_byte()))?;
+        Some((&self.context.capture_names()[capture.index as usize], capture.node))
+    }
+
+    /// The capture of the first recovery pattern that matches `error`, along with the argument
+    /// it captured, if any
+    fn recovery<'tree>(
+        &self,
+        error: Node<'tree>,
+        text: &str,
+    ) -> Option<(&str, Option<Node<'tree>>)> {
+        // the captures of a match are only valid until the next one, so they are copied out first
+        let mut cursor = QueryCursor::new();
+        let (capture, argument) = cursor
+            .matches(&self.recovery, error, text.as_bytes())
+            .filter_map(|captures| {
+                let capture = captures.captures.iter().find(|capture| capture.node == error)?;
+                let argument = captures.captures.iter().find(|capture| capture.node != error);
+                Some((captures.pattern_index, capture.index, argument.map(|arg| arg.node)))
+            })
+            .min_by_key(|&(pattern, ..)| pattern)
+            .map(|(_, capture, argument)| (capture, argument))?;
+        Some((&self.recovery.capture_names()[capture as usize], argument))
+    }
+}
+
+/// Whether the last token of `node` is a closing delimiter that was actually written
+fn is_closed(node: Node<'_>) -> bool {
+    let mut last = node;
+    while let Some(child) = last.child(last.child_count().saturating_sub(1)) {
+        last = child;
+    }
+    matches!(last.kind(), "}" | ")" | "]") && !last.is_missing()
+}
+
 impl<'s> CompletionCtxt<'s> {
     fn infer_context(snapshot: &'s Snapshot, position: Position) -> Context {
-        let data = snapshot.file_data(position.file);
-        // NOTE maybe we could make use of treesitter's query api to do this better
-        // HACK look backwards a few columns to try and find a notable node
-        let mut point = position.point;
-        for _ in 0..10 {
-            let node = match data.tree.root_node().named_node_at(point) {
-                Some(node) => node,
-                None => return Context::Document,
+        // the keyword isn't part of any node until the definition after it is written
+        let text = snapshot.file_text(position.file);
+        let offset = snapshot.line_index(position.file).offset(position.point);
+        if text[..offset].ends_with('"') {
+            return if Self::is_opening_description(&text, offset) {
+                Context::Description
+            } else {
+                Context::String
             };
+        }
+        let before = text[..offset]
+            .trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_')
+            .trim_end();
+        if let Some(rest) = before.strip_suffix(EXTEND) {
+            if rest.is_empty() || rest.ends_with(char::is_whitespace) {
+                return Context::Extend;
+            }
+        }
+
+        let anchor = Self::anchor(&text, offset, position.point);
+        let data = snapshot.file_data(position.file);
+        let root = data.tree.root_node();
+        if let Some(error) = Self::error_before(root, anchor) {
+            return Self::recover(snapshot, position, root, error);
+        }
+        Self::context_at(snapshot, position, root, anchor)
+    }
+
+    /// The cursor belongs to what is written before it on the same line, e.g. the type of a
+    /// field after `bar: `
+    fn anchor(text: &str, offset: usize, point: Point) -> Point {
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        Point::new(point.row, text[line_start..offset].trim_end().len())
+    }
+
+    fn context_at(snapshot: &Snapshot, position: Position, root: Node<'_>, at: Point) -> Context {
+        let text = snapshot.file_text(position.file);
+        let (capture, node) = match QUERIES.context_node(root, &text, at) {
+            Some(context) => context,
+            None => return Context::Document,
+        };
+        match capture {
+            "value" => Self::value_context(snapshot, position, node),
+            "name" => Context::Name,
+            "directive_arguments" => match node.parent() {
+                Some(directive) => Self::directive_arguments(snapshot, position, directive),
+                None => Context::Name,
+            },
+            "implements" => Self::implementing_interface(snapshot, position, node),
+            "union_members" => Self::union_members(snapshot, position, node),
+            "root_operation_type" => Context::RootOperationType,
+            "directive_locations" =>
+                Context::DirectiveLocations(Self::listed_locations(node, position.point)),
+            "field" => Context::Field,
+            "input_field" => Context::InputField,
+            "enum_value" => Context::Directive(DirectiveLocations::ENUM_VALUE),
+            "object" => Context::Directive(DirectiveLocations::OBJECT),
+            "interface" => Context::Directive(DirectiveLocations::INTERFACE),
+            "union" => Context::Directive(DirectiveLocations::UNION),
+            "enum" => Context::Directive(DirectiveLocations::ENUM),
+            "scalar" => Context::Directive(DirectiveLocations::SCALAR),
+            "input_object" => Context::Directive(DirectiveLocations::INPUT_OBJECT),
+            _ => unreachable!("unknown context capture `{capture}`"),
+        }
+    }
+
+    /// The innermost `ERROR` node that the text right before `anchor` is part of and that follows
+    /// a node the context can be inferred from
+    fn error_before(root: Node<'_>, anchor: Point) -> Option<Node<'_>> {
+        let before = Point::new(anchor.row, anchor.column.checked_sub(1)?);
+        let node = root.descendant_for_point_range(before, anchor)?;
+        std::iter::once(node).chain(node.parents()).find(|node| {
+            node.is_error() && node.prev_sibling().map_or(false, |prev| prev.is_named())
+        })
+    }
+
+    /// The context after the node before `error`, refined by what the error was recovered from
+    fn recover<'tree>(
+        snapshot: &Snapshot,
+        position: Position,
+        root: Node<'tree>,
+        error: Node<'tree>,
+    ) -> Context {
+        let text = snapshot.file_text(position.file);
+        let prev = error.prev_sibling().expect("the error follows a node");
+        let context = Self::context_at(snapshot, position, root, prev.end_position());
+        let (recovery, argument) = match QUERIES.recovery(error, &text) {
+            Some(recovery) => recovery,
+            None => return context,
+        };
+        let recovered = match recovery {
+            "argument_value" => Self::directive_before(root, error).map(|directive| {
+                let arg = argument.map(|arg| Name::new(&*text, arg));
+                Context::Value(
+                    arg.and_then(|arg| Self::argument_type(snapshot, position, directive, arg))
+                        .and_then(|ty| ExpectedType::new(snapshot, position.file, ty, &[])),
+                )
+            }),
+            "arguments" => Self::directive_before(root, error)
+                .map(|directive| Self::directive_arguments(snapshot, position, directive)),
+            "fields" => match context {
+                Context::Directive(location) if location == DirectiveLocations::INPUT_OBJECT =>
+                    Some(Context::InputField),
+                Context::Directive(location)
+                    if location == DirectiveLocations::OBJECT
+                        || location == DirectiveLocations::INTERFACE =>
+                    Some(Context::Field),
+                _ => None,
+            },
+            // the definition isn't lowered as it is part of the error, so the type is read off it
+            "default_value" => (prev.kind() == NodeKind::INPUT_VALUE_DEFINITION).then(|| {
+                let ty = prev.child_of_kind(NodeKind::TYPE);
+                let nullable = ty
+                    .and_then(|ty| ty.named_child(0))
+                    .map_or(true, |ty| ty.kind() != NodeKind::NON_NULL_TYPE);
+                let named = ty
+                    .and_then(|ty| ty.find_descendant(|node| node.kind() == NodeKind::NAMED_TYPE));
+                Context::Value(
+                    named.map(|name| ExpectedType { name: Name::new(&*text, name), nullable }),
+                )
+            }),
+            _ => unreachable!("unknown recovery capture `{recovery}`"),
+        };
+        recovered.unwrap_or(context)
+    }
+
+    /// The directive that ends right before `error`, e.g. `@foo` of `@foo(`
+    fn directive_before<'tree>(root: Node<'tree>, error: Node<'tree>) -> Option<Node<'tree>> {
+        let end = error.prev_sibling()?.end_position();
+        let before = Point::new(end.row, end.column.checked_sub(1)?);
+        let node = root.named_descendant_for_point_range(before, end)?;
+        std::iter::once(node).chain(node.parents()).find(|node| node.kind() == NodeKind::DIRECTIVE)
+    }
+
+    /// The arguments of `directive`, a directive without a name has none to complete
+    fn directive_arguments(
+        snapshot: &Snapshot,
+        position: Position,
+        directive: Node<'_>,
+    ) -> Context {
+        let text = snapshot.file_text(position.file);
+        match directive.name_node() {
+            Some(name) => Context::DirectiveArguments(Name::new(&*text, name)),
+            None => Context::Name,
+        }
+    }
+
+    /// The interface that implements the interfaces of `implements`, if it isn't an object
+    fn implementing_interface(
+        snapshot: &Snapshot,
+        position: Position,
+        implements: Node<'_>,
+    ) -> Context {
+        let text = snapshot.file_text(position.file);
+        let interface = implements
+            .parent()
+            .filter(|parent| {
+                matches!(
+                    parent.kind(),
+                    NodeKind::INTERFACE_TYPE_DEFINITION | NodeKind::INTERFACE_TYPE_EXTENSION
+                )
+            })
+            .and_then(|interface| interface.name_node());
+        Context::Interface(interface.map(|name| Name::new(&*text, name)))
+    }
+
+    /// The members of the union of `members`
+    fn union_members(snapshot: &Snapshot, position: Position, members: Node<'_>) -> Context {
+        let text = snapshot.file_text(position.file);
+        let union = members.parent().and_then(|union| union.name_node());
+        Context::UnionMembers(union.map(|name| Name::new(&*text, name)))
+    }
+
+    /// Whether the `"""` before `offset` is on a line of its own and opens a description (rather
+    /// than closing one)
+    fn is_opening_description(text: &str, offset: usize) -> bool {
+        let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let rest = text[offset..].lines().next().unwrap_or_default();
+        text[line_start..offset].trim_start() == BLOCK_QUOTE
+            && rest.trim().is_empty()
+            && text[..line_start].matches(BLOCK_QUOTE).count() % 2 == 0
+    }
+
+    /// The context of the value being written at `node`, which is either (part of) the argument
+    /// of a directive or the default value of an argument or input field
+    fn value_context(snapshot: &Snapshot, position: Position, node: Node<'_>) -> Context {
+        let text = snapshot.file_text(position.file);
+        let offset = snapshot.line_index(position.file).offset(position.point);
+        let anchor = Self::anchor(&text, offset, position.point);
+        let (path, is_key) = Self::value_path(&text, node, anchor);
+        let expected = Self::expected_type(snapshot, position, node, &path);
+        if is_key { Context::ObjectField(expected) } else { Context::Value(expected) }
+    }
+
+    /// The type of the value reached by following `path` from the value at `node`
+    fn expected_type(
+        snapshot: &Snapshot,
+        position: Position,
+        node: Node<'_>,
+        path: &[Step],
+    ) -> Option<ExpectedType> {
+        // the value may not be part of the range of its definition yet
+        let contains =
+            |range: Range| range.start_point <= position.point && position.point <= range.end_point;
+        let argument = std::iter::once(node)
+            .chain(node.parents())
+            .find(|node| node.kind() == NodeKind::ARGUMENT);
+        if let Some(argument) = argument {
+            let text = snapshot.file_text(position.file);
+            let directive = argument.parent_of_kind(NodeKind::DIRECTIVE)?;
+            let arg = Name::new(&*text, argument.name_node()?);
+            let ty = Self::argument_type(snapshot, position, directive, arg)?;
+            return ExpectedType::new(snapshot, position.file, ty, path);
+        }
+
+        let body = snapshot.item_body(snapshot.resolve_item_at(position)?)?;
+        let ty = match &body.kind {
+            ItemBodyKind::DirectiveDefinition(body) =>
+                &body.args.iter().find(|arg| contains(arg.range))?.ty,
+            _ => {
+                let field = body.fields()?.iter().find(|(_, field)| contains(field.range))?.1;
+                match field.args.iter().find(|arg| contains(arg.range)) {
+                    Some(arg) => &arg.ty,
+                    None => &field.ty,
+                }
+            }
+        };
+        ExpectedType::new(snapshot, position.file, ty.clone(), path)
+    }
+
+    /// The steps from the outermost value of the definition of `value` into the value that
+    /// `point` is in, e.g. the elements of the field `x` for `{ x: [$] }`, along with whether the
+    /// key of a field of an object is being written at `point` (e.g. `{ $ }`)
+    fn value_path(text: &str, value: Node<'_>, point: Point) -> (Vec<Step>, bool) {
+        let innermost = match value.descendant_for_point_range(point, point) {
+            Some(node) => node,
+            None => return (vec![], false),
+        };
+        // a field of an object ends with its comma, after which the next key is written
+        let contains = |node: Node<'_>| {
+            let closed = is_closed(node)
+                || (node.kind() == NodeKind::OBJECT_FIELD
+                    && node.child_of_kind(NodeKind::COMMA).is_some());
+            node.start_position() < point
+                && point <= node.end_position()
+                && (point < node.end_position() || !closed)
+        };
+        let mut path = vec![];
+        let mut is_key = None;
+        let ancestors = std::iter::once(innermost).chain(innermost.parents()).take_while(|node| {
+            !matches!(node.kind(), NodeKind::ARGUMENT | NodeKind::DEFAULT_VALUE)
+        });
+        for node in ancestors.filter(|&node| contains(node)) {
             match node.kind() {
-                NodeKind::IMPLEMENTS_INTERFACES => return Context::Interface,
-                NodeKind::OBJECT_TYPE_DEFINITION | NodeKind::OBJECT_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::OBJECT),
-                NodeKind::ENUM_TYPE_DEFINITION | NodeKind::ENUM_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::ENUM),
-                NodeKind::UNION_TYPE_DEFINITION | NodeKind::UNION_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::UNION),
-                NodeKind::INTERFACE_TYPE_DEFINITION | NodeKind::INTERFACE_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::INTERFACE),
-                NodeKind::SCALAR_TYPE_DEFINITION | NodeKind::SCALAR_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::SCALAR),
-                NodeKind::INPUT_OBJECT_TYPE_DEFINITION | NodeKind::INPUT_OBJECT_TYPE_EXTENSION =>
-                    return Context::Directive(DirectiveLocations::INPUT_OBJECT),
-                NodeKind::DIRECTIVE_LOCATIONS | NodeKind::DIRECTIVE_LOCATION =>
-                    return Context::DirectiveLocations,
-                NodeKind::ENUM_VALUES_DEFINITION
-                | NodeKind::ENUM_VALUE_DEFINITION
-                | NodeKind::ENUM_VALUE =>
-                    return Context::Directive(DirectiveLocations::ENUM_VALUE),
-                NodeKind::INPUT_VALUE_DEFINITION => return Context::InputField,
-                NodeKind::FIELD_DEFINITION => return Context::Field,
-                NodeKind::UNION_MEMBER_TYPES => return Context::UnionMembers,
-                NodeKind::NON_NULL_TYPE
-                | NodeKind::LIST_TYPE
-                | NodeKind::NAMED_TYPE
-                | NodeKind::TYPE =>
-                    if node.has_parent_of_kind(NodeKind::FIELD_DEFINITION) {
-                        return Context::Field;
-                    } else if node.has_parent_of_kind(NodeKind::INPUT_VALUE_DEFINITION) {
-                        return Context::InputField;
-                    } else if node.has_parent_of_kind(NodeKind::UNION_MEMBER_TYPES) {
-                        return Context::UnionMembers;
-                    } else if node.has_parent_of_kind(NodeKind::IMPLEMENTS_INTERFACES) {
-                        return Context::Interface;
-                    },
-                _ => {
-                    if point.column == 0 {
-                        break;
+                NodeKind::LIST_VALUE => {
+                    path.push(Step::Element);
+                    is_key.get_or_insert(false);
+                }
+                NodeKind::OBJECT_FIELD => {
+                    let colon = node.children(&mut node.walk()).find(|child| child.kind() == ":");
+                    match (node.name_node(), colon) {
+                        (Some(name), Some(colon)) if colon.end_position() <= point => {
+                            path.push(Step::Field(Name::new(text, name)));
+                            is_key.get_or_insert(false);
+                        }
+                        _ => {
+                            is_key.get_or_insert(true);
+                        }
                     }
-                    point.column -= 1;
                 }
+                NodeKind::OBJECT_VALUE => {
+                    is_key.get_or_insert(true);
+                }
+                _ => {}
             }
         }
+        path.reverse();
+        (path, is_key.unwrap_or_default())
+    }
+
+    /// The type of the argument `arg` of `directive`
+    fn argument_type(
+        snapshot: &Snapshot,
+        position: Position,
+        directive: Node<'_>,
+        arg: Name,
+    ) -> Option<Ty> {
+        let text = snapshot.file_text(position.file);
+        let name = Name::new(&*text, directive.name_node()?);
+        let res = snapshot.resolve_directive(InProject::new(position.file, name))?;
+        match &snapshot.item_body(res)?.kind {
+            ItemBodyKind::DirectiveDefinition(body) =>
+                body.args.iter().find(|a| a.name == arg).map(|arg| arg.ty.clone()),
+            _ => None,
+        }
+    }
 
-        Context::Document
+    /// The locations of `locations`, the one being written at `point` is not yet listed
+    fn listed_locations(locations: Node<'_>, point: Point) -> DirectiveLocations {
+        locations
+            .children_of_kind(&mut locations.walk(), NodeKind::DIRECTIVE_LOCATION)
+            .filter(|location| {
+                !(location.start_position()..=location.end_position()).contains(&point)
+            })
+            .filter_map(|location| location.child_by_field_name("location"))
+            .filter_map(|location| DirectiveLocations::from_name(location.kind()))
+            .fold(DirectiveLocations::default(), |acc, location| acc | location)
     }
 
-    fn new(snapshot: &'s Snapshot, position: Position) -> Self {
+    fn new(snapshot: &'s Snapshot, position: Position, config: CompletionConfig) -> Self {
         let context = Self::infer_context(snapshot, position);
         tracing::info!("inferred completion context: {:?}", context);
         Self {
             snapshot,
             project: InProject::new(position.file, ()),
+            position,
             context,
+            config,
             completions: Default::default(),
         }
     }
@@ -138,65 +740,170 @@ impl<'s> CompletionCtxt<'s> {
         match self.context {
             Context::Field => self.complete_fields(),
             Context::Document => self.complete_document(),
-            Context::UnionMembers => self.complete_union_member(),
+            Context::Extend => self.complete_extend(),
+            Context::UnionMembers(union) => self.complete_union_member(union),
+            Context::RootOperationType => self.complete_root_operation_types(),
             Context::InputField => self.complete_input_fields(),
             Context::Directive(location) => self.complete_directives(location),
-            Context::DirectiveLocations => self.complete_directive_locations(),
-            Context::Interface => self.complete_interfaces(),
+            Context::DirectiveLocations(listed) => self.complete_directive_locations(listed),
+            Context::Interface(interface) => self.complete_interfaces(interface),
+            Context::Value(expected) => self.complete_values(expected),
+            Context::ObjectField(expected) => self.complete_object_fields(expected),
+            Context::Description => self.complete_description(),
+            Context::DirectiveArguments(directive) => self.complete_directive_arguments(directive),
+            Context::String | Context::Name => {}
+        }
+
+        // the order is otherwise kept as is, i.e. alphabetical for items
+        self.completions.sort_by_key(|completion| (completion.deprecated, completion.locality));
+        for (i, completion) in self.completions.iter_mut().enumerate() {
+            completion.sort_text = Some(format!("{i:04}"));
         }
         self.completions
     }
 
     fn complete_document(&mut self) {
-        self.completions
-            .extend(["type", "scalar", "enum", "union", "interface", "directive", "input"].map(
-                |s| CompletionItem { label: s.to_owned(), kind: CompletionItemKind::Keyword },
-            ));
+        let keywords = DEFINITION_KEYWORDS.into_iter().map(|(keyword, _)| keyword);
+        self.completions.extend(
+            keywords
+                .chain([EXTEND])
+                .map(|keyword| CompletionItem::new(keyword, CompletionItemKind::Keyword)),
+        );
+    }
+
+    fn complete_extend(&mut self) {
+        let keywords = DEFINITION_KEYWORDS
+            .into_iter()
+            .filter(|(_, extension)| extension.is_some())
+            .map(|(keyword, _)| CompletionItem::new(keyword, CompletionItemKind::Keyword));
+        self.completions.extend(keywords);
     }
 
     fn items(&self) -> impl Iterator<Item = CompletionItem> {
         let project_items = self.snapshot.project_items(self.project);
-        let mut completions = HashSet::new();
-        for items in project_items.values() {
-            for (_, item) in items.iter() {
+        let mut completions = vec![];
+        for (&file, items) in project_items.iter() {
+            for (idx, item) in items.iter().filter(|(_, item)| !item.name.is_missing()) {
+                let mut documentation = items.description(idx).map(ToOwned::to_owned);
                 let kind = match item.kind {
-                    ItemKind::TypeDefinition(idx) => match items[idx].kind {
-                        TypeDefinitionKind::Object => CompletionItemKind::Object,
-                        TypeDefinitionKind::Input => CompletionItemKind::InputObject,
-                        TypeDefinitionKind::Interface => CompletionItemKind::Interface,
-                        TypeDefinitionKind::Scalar => CompletionItemKind::Scalar,
-                        TypeDefinitionKind::Enum => CompletionItemKind::Enum,
-                        TypeDefinitionKind::Union => CompletionItemKind::Union,
-                    },
+                    ItemKind::TypeDefinition(idx) => {
+                        // an extension takes on the kind (and description) of the type it extends
+                        let name = InProject::new(file, item.name);
+                        let kind = match self.snapshot.effective_typedef(name) {
+                            Some(typedef) => {
+                                let relations = self.snapshot.type_relations(file, &typedef);
+                                let docs = [typedef.description.as_deref(), Some(&relations)]
+                                    .into_iter()
+                                    .flatten()
+                                    .filter(|docs| !docs.is_empty())
+                                    .collect::<Vec<_>>();
+                                documentation = (!docs.is_empty()).then(|| docs.join("\n\n"));
+                                typedef.kind.clone()
+                            }
+                            None => items[idx].kind.clone(),
+                        };
+                        match kind {
+                            TypeDefinitionKind::Object => CompletionItemKind::Object,
+                            TypeDefinitionKind::Input => CompletionItemKind::InputObject,
+                            TypeDefinitionKind::Interface => CompletionItemKind::Interface,
+                            TypeDefinitionKind::Scalar => CompletionItemKind::Scalar,
+                            TypeDefinitionKind::Enum => CompletionItemKind::Enum,
+                            TypeDefinitionKind::Union => CompletionItemKind::Union,
+                        }
+                    }
                     ItemKind::DirectiveDefinition(idx) =>
                         CompletionItemKind::Directive(items[idx].locations),
                 };
 
-                completions.insert(CompletionItem { label: item.name.to_string(), kind });
+                let mut completion = CompletionItem::new(item.name.to_string(), kind);
+                completion.documentation = documentation;
+                completion.deprecated = items
+                    .directives(idx)
+                    .into_iter()
+                    .flatten()
+                    .any(|directive| directive.name.as_str() == "@deprecated");
+                completion.locality = self.locality(file);
+                if let ItemKind::DirectiveDefinition(_) = item.kind {
+                    if let Some(body) = self.snapshot.item_body(ItemRes::new(file, idx)) {
+                        let args = &body.as_directive().args;
+                        completion.detail =
+                            (!args.is_empty()).then(|| render::render_arg_list(args));
+                        completion.insert_text =
+                            Some(render::render_directive_snippet(&item.name, args));
+                    }
+                }
+                completions.push(completion);
+            }
+        }
+        // the parts of a type are a single completion, which is as relevant as its closest part
+        // and deprecated if any part is
+        completions.sort();
+        completions.dedup_by(|part, completion| {
+            if part.label != completion.label || part.kind != completion.kind {
+                return false;
+            }
+            completion.documentation =
+                completion.documentation.take().or(part.documentation.take());
+            completion.detail = completion.detail.take().or(part.detail.take());
+            completion.insert_text = completion.insert_text.take().or(part.insert_text.take());
+            completion.deprecated |= part.deprecated;
+            completion.locality = completion.locality.min(part.locality);
+            true
+        });
+        completions.into_iter()
+    }
+
+    fn locality(&self, file: FileId) -> Locality {
+        if file == self.project.file { Locality::File } else { Locality::Project }
+    }
+
+    /// The `item` with the wrappings of the type offered as configured, directives are unchanged
+    fn wrap_type(&self, mut item: CompletionItem) -> Vec<CompletionItem> {
+        if matches!(item.kind, CompletionItemKind::Directive(_)) {
+            return vec![item];
+        }
+        let name = item.label.clone();
+        match self.config.type_wrappers {
+            TypeWrappers::None => vec![item],
+            TypeWrappers::Variants => TypeWrappers::wrappings(&name)
+                .into_iter()
+                .map(|label| CompletionItem {
+                    label,
+                    filter_text: Some(name.clone()),
+                    ..item.clone()
+                })
+                .collect(),
+            TypeWrappers::Snippet => {
+                let choices = TypeWrappers::wrappings(&name).join(",");
+                item.insert_text = Some(format!("${{1|{choices}|}}"));
+                vec![item]
             }
         }
-        let mut v = completions.into_iter().collect::<Vec<_>>();
-        v.sort();
-        v.into_iter()
     }
 
     fn complete_input_fields(&mut self) {
-        self.completions.extend(self.items().filter(|item| match item.kind {
+        let items = self.items().filter(|item| match item.kind {
             CompletionItemKind::Directive(loc) =>
                 loc.contains(DirectiveLocations::INPUT_FIELD_DEFINITION),
             CompletionItemKind::InputObject
             | CompletionItemKind::Enum
             | CompletionItemKind::Scalar => true,
-            CompletionItemKind::Object
+            CompletionItemKind::EnumValue
+            | CompletionItemKind::Object
             | CompletionItemKind::DirectiveLocation
             | CompletionItemKind::Interface
             | CompletionItemKind::Union
-            | CompletionItemKind::Keyword => false,
-        }));
+            | CompletionItemKind::Keyword
+            | CompletionItemKind::InputField
+            | CompletionItemKind::Argument
+            | CompletionItemKind::Description => false,
+        });
+        let completions = items.flat_map(|item| self.wrap_type(item)).collect::<Vec<_>>();
+        self.completions.extend(completions);
     }
 
     fn complete_fields(&mut self) {
-        self.completions.extend(self.items().filter(|item| match item.kind {
+        let items = self.items().filter(|item| match item.kind {
             CompletionItemKind::Directive(loc) =>
                 loc.contains(DirectiveLocations::FIELD_DEFINITION),
             CompletionItemKind::Object
@@ -204,15 +911,54 @@ impl<'s> CompletionCtxt<'s> {
             | CompletionItemKind::Enum
             | CompletionItemKind::Scalar
             | CompletionItemKind::Union => true,
-            CompletionItemKind::InputObject
+            CompletionItemKind::EnumValue
+            | CompletionItemKind::InputObject
             | CompletionItemKind::Keyword
-            | CompletionItemKind::DirectiveLocation => false,
-        }));
+            | CompletionItemKind::DirectiveLocation
+            | CompletionItemKind::InputField
+            | CompletionItemKind::Argument
+            | CompletionItemKind::Description => false,
+        });
+        let completions = items.flat_map(|item| self.wrap_type(item)).collect::<Vec<_>>();
+        self.completions.extend(completions);
     }
 
-    fn complete_union_member(&mut self) {
+    /// Complete the objects that aren't members of `union` already (in any of its parts), the
+    /// member being written doesn't count
+    fn complete_union_member(&mut self, union: Option<Name>) {
+        let position = self.position;
+        let written = |file: FileId, range: Range| {
+            file == position.file
+                && range.start_point <= position.point
+                && position.point <= range.end_point
+        };
+        let mut members = HashSet::new();
+        let typedef =
+            union.and_then(|name| self.snapshot.effective_typedef(self.project.with_value(name)));
+        for &part in typedef.iter().flat_map(|typedef| &typedef.parts) {
+            let body = match self.snapshot.item_body(part) {
+                Some(body) => body,
+                None => continue,
+            };
+            if let ItemBodyKind::Union(body) = &body.kind {
+                members.extend(
+                    body.types
+                        .iter()
+                        .filter(|ty| !written(part.file, ty.range))
+                        .map(|ty| ty.name().to_string()),
+                );
+            }
+        }
+        let completions = self.items().filter(|item| {
+            matches!(item.kind, CompletionItemKind::Object) && !members.contains(&item.label)
+        });
+        self.completions.extend(completions)
+    }
+
+    /// Complete the objects, which are the only types an operation can have as its root type
+    fn complete_root_operation_types(&mut self) {
         self.completions
-            .extend(self.items().filter(|item| matches!(item.kind, CompletionItemKind::Object)))
+            .extend(self.items().filter(|item| matches!(item.kind, CompletionItemKind::Object)));
     }
 
     fn complete_directives(&mut self, location: DirectiveLocations) {
@@ -220,33 +966,239 @@ impl<'s> CompletionCtxt<'s> {
         self.completions.extend(completions);
     }
 
-    fn complete_directive_locations(&mut self) {
-        // FIXME once bitflags allows iteration
-        // DirectiveLocations::all().iter();
-        self.completions.extend(
-            [
-                "SCHEMA",
-                "SCALAR",
-                "OBJECT",
-                "FIELD_DEFINITION",
-                "ARGUMENT_DEFINITION",
-                "INTERFACE",
-                "UNION",
-                "ENUM",
-                "ENUM_VALUE",
-                "INPUT_OBJECT",
-                "INPUT_FIELD_DEFINITION",
-            ]
-            .map(|s| CompletionItem {
-                label: s.to_owned(),
-                kind: CompletionItemKind::DirectiveLocation,
-            }),
-        )
+    fn complete_directive_locations(&mut self, listed: DirectiveLocations) {
+        let locations = DirectiveLocations::NAMED
+            .into_iter()
+            .filter(|&(_, location)| !listed.contains(location))
+            .map(|(name, _)| CompletionItem::new(name, CompletionItemKind::DirectiveLocation));
+        self.completions.extend(locations)
     }
 
-    fn complete_interfaces(&mut self) {
-        self.completions
-            .extend(self.items().filter(|item| matches!(item.kind, CompletionItemKind::Interface)));
+    /// Complete the values of the expected type, i.e. the enum values of an enum, `true` and
+    /// `false` for a boolean, an empty string for a string and `null` if it is nullable
+    fn complete_values(&mut self, expected: Option<ExpectedType>) {
+        let expected = match expected {
+            Some(expected) => expected,
+            None => return,
+        };
+        let res = self.snapshot.resolve_item(self.project.with_value(expected.name));
+        let keywords = match self.snapshot.type_of_res(res.clone()).kind {
+            TyKind::Err => return,
+            TyKind::Boolean => vec!["true", "false"],
+            TyKind::String => {
+                let mut completion = CompletionItem::new("\"\"", CompletionItemKind::Keyword);
+                completion.insert_text = Some("\"$1\"".to_owned());
+                self.completions.push(completion);
+                vec![]
+            }
+            TyKind::Enum(_) => {
+                self.complete_enum_values(res.into_item());
+                vec![]
+            }
+            _ => vec![],
+        };
+        let null = expected.nullable.then(|| "null");
+        let keywords = keywords
+            .into_iter()
+            .chain(null)
+            .map(|keyword| CompletionItem::new(keyword, CompletionItemKind::Keyword));
+        self.completions.extend(keywords);
+    }
+
+    /// Complete the fields of the expected input object that aren't written in the object yet
+    fn complete_object_fields(&mut self, expected: Option<ExpectedType>) {
+        let name = match expected {
+            Some(expected) => self.project.with_value(expected.name),
+            None => return,
+        };
+        let typedef = match self.snapshot.effective_typedef(name) {
+            Some(typedef) if typedef.kind == TypeDefinitionKind::Input => typedef,
+            _ => return,
+        };
+        let written = self.written_object_fields();
+        for &res in &typedef.fields {
+            let field = self.snapshot.field(res);
+            if written.contains(&field.name) {
+                continue;
+            }
+            let kind = CompletionItemKind::InputField;
+            let mut completion = CompletionItem::new(field.name.to_string(), kind);
+            completion.detail = Some(format!("{:?}", field.ty));
+            completion.insert_text = Some(format!("{}: ", field.name));
+            completion.documentation = field.description.as_deref().map(Into::into);
+            completion.deprecated =
+                field.directives.iter().any(|directive| directive.name.as_str() == "@deprecated");
+            completion.locality = self.locality(res.item.file);
+            self.completions.push(completion);
+        }
+    }
+
+    /// Complete the arguments of `directive` that aren't written in its arguments yet
+    fn complete_directive_arguments(&mut self, directive: Name) {
+        let res = match self.snapshot.resolve_directive(self.project.with_value(directive)) {
+            Some(res) => res,
+            None => return,
+        };
+        let body = match self.snapshot.item_body(res) {
+            Some(body) => body,
+            None => return,
+        };
+        let written = self.written_arguments();
+        for arg in body.as_directive().args.iter().filter(|arg| !written.contains(&arg.name)) {
+            let kind = CompletionItemKind::Argument;
+            let mut completion = CompletionItem::new(arg.name.to_string(), kind);
+            completion.detail = Some(render::render_arg(arg));
+            completion.insert_text = Some(format!("{}: ", arg.name));
+            completion.documentation = arg.description.as_deref().map(Into::into);
+            completion.locality = self.locality(res.file);
+            self.completions.push(completion);
+        }
+    }
+
+    /// The names of the arguments of the directive the cursor is in, except for the one being
+    /// written. The arguments may be part of an `ERROR` node if they aren't closed yet.
+    fn written_arguments(&self) -> HashSet<Name> {
+        let file = self.project.file;
+        let text = self.snapshot.file_text(file);
+        let offset = self.snapshot.line_index(file).offset(self.position.point);
+        let anchor = Self::anchor(&text, offset, self.position.point);
+        let before = Point::new(anchor.row, anchor.column.saturating_sub(1));
+        let data = self.snapshot.file_data(file);
+        let arguments =
+            data.tree.root_node().descendant_for_point_range(before, anchor).and_then(|node| {
+                std::iter::once(node)
+                    .chain(node.parents())
+                    .find(|node| node.kind() == NodeKind::ARGUMENTS || node.is_error())
+            });
+        let arguments = match arguments {
+            Some(arguments) => arguments,
+            None => return HashSet::new(),
+        };
+        let children = arguments.children(&mut arguments.walk()).collect::<Vec<_>>();
+        children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, child)| match child.kind() {
+                NodeKind::ARGUMENT => child.name_node(),
+                // a recovered argument is a name followed by a colon
+                NodeKind::NAME if children.get(i + 1).map_or(false, |next| next.kind() == ":") =>
+                    Some(*child),
+                _ => None,
+            })
+            .filter(|name| !(name.start_position()..=name.end_position()).contains(&anchor))
+            .map(|name| Name::new(&*text, name))
+            .collect()
+    }
+
+    /// The keys of the object the cursor is in, except for the one being written
+    fn written_object_fields(&self) -> HashSet<Name> {
+        let file = self.project.file;
+        let text = self.snapshot.file_text(file);
+        let offset = self.snapshot.line_index(file).offset(self.position.point);
+        let anchor = Self::anchor(&text, offset, self.position.point);
+        let data = self.snapshot.file_data(file);
+        let object = data
+            .tree
+            .root_node()
+            .descendant_for_point_range(anchor, anchor)
+            .and_then(|node| {
+                std::iter::once(node)
+                    .chain(node.parents())
+                    .find(|node| node.kind() == NodeKind::OBJECT_VALUE)
+            });
+        let object = match object {
+            Some(object) => object,
+            None => return HashSet::new(),
+        };
+        object
+            .children_of_kind(&mut object.walk(), NodeKind::OBJECT_FIELD)
+            .filter_map(|field| field.name_node())
+            .filter(|name| !(name.start_position()..=name.end_position()).contains(&anchor))
+            .map(|name| Name::new(&*text, name))
+            .collect()
+    }
+
+    fn complete_enum_values(&mut self, resolutions: impl IntoIterator<Item = ItemRes>) {
+        for res in resolutions {
+            let body = match self.snapshot.item_body(res) {
+                Some(body) => body,
+                None => continue,
+            };
+            let locality = self.locality(res.file);
+            if let ItemBodyKind::Enum(body) = &body.kind {
+                self.completions.extend(body.variants.iter().map(|variant| {
+                    let kind = CompletionItemKind::EnumValue;
+                    let mut completion = CompletionItem::new(variant.name.to_string(), kind);
+                    completion.documentation = variant.description.as_deref().map(Into::into);
+                    completion.deprecated = variant.is_deprecated;
+                    completion.locality = locality;
+                    completion
+                }));
+            }
+        }
+    }
+
+    /// Complete the interfaces, except for `interface` itself
+    fn complete_interfaces(&mut self, interface: Option<Name>) {
+        let completions = self.items().filter(|item| {
+            matches!(item.kind, CompletionItemKind::Interface)
+                && interface.map_or(true, |name| item.label != name.as_str())
+        });
+        self.completions.extend(completions);
+    }
+
+    /// The template of the description of the definition on the (non-blank) line following the
+    /// opening `"""`, along with its arguments if configured
+    fn complete_description(&mut self) {
+        if self.config.docstrings == Docstrings::None {
+            return;
+        }
+        // the unterminated description swallows the definition, so it is parsed without it
+        let mut text = self.snapshot.file_text(self.project.file).to_string();
+        let offset = self.snapshot.line_index(self.project.file).offset(self.position.point);
+        let start = offset - BLOCK_QUOTE.len();
+        let indent = text[text[..start].rfind('\n').map_or(0, |i| i + 1)..start].to_owned();
+        text.replace_range(start..offset, &" ".repeat(BLOCK_QUOTE.len()));
+
+        let (row, line) = match text[offset..]
+            .lines()
+            .enumerate()
+            .skip(1)
+            .find(|(_, line)| !line.trim().is_empty())
+        {
+            Some(line) => line,
+            None => return,
+        };
+        let point = Point::new(self.position.point.row + row, line.len() - line.trim_start().len());
+        let tree = gqls_syntax::parse_fresh(&text);
+        let definition = match tree.root_node().named_node_at(point).and_then(|node| {
+            node.parents().find(|node| DESCRIBED_DEFINITIONS.contains(&node.kind()))
+        }) {
+            Some(definition) if definition.start_position() == point => definition,
+            _ => return,
+        };
+
+        let mut template = format!("\n{indent}$1");
+        if self.config.docstrings == Docstrings::Arguments {
+            if let Some(args) = definition.child_of_kind(NodeKind::ARGUMENTS_DEFINITION) {
+                let args = args
+                    .children_of_kind(&mut args.walk(), NodeKind::INPUT_VALUE_DEFINITION)
+                    .filter_map(|arg| Some(arg.name_node()?.text(&text)))
+                    .collect::<Vec<_>>();
+                if !args.is_empty() {
+                    template.push('\n');
+                }
+                for (i, arg) in args.into_iter().enumerate() {
+                    template.push_str(&format!("\n{indent}- `{arg}`: ${}", i + 2));
+                }
+            }
+        }
+        template.push_str(&format!("\n{indent}{BLOCK_QUOTE}"));
+
+        let mut completion = CompletionItem::new("description", CompletionItemKind::Description);
+        completion.locality = Locality::File;
+        completion.insert_text = Some(template);
+        self.completions.push(completion);
     }
 }
 
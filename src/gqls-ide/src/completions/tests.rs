@@ -1,12 +1,16 @@
 use expect_test::{expect, Expect};
 use gqls_fixture::{fixture, Fixture};
 
-use crate::Ide;
+use crate::{CompletionConfig, Docstrings, Ide, Locality, TypeWrappers};
 
 fn test(fixture: &Fixture, expect: Expect) {
+    test_with(fixture, &CompletionConfig::default(), expect)
+}
+
+fn test_with(fixture: &Fixture, config: &CompletionConfig, expect: Expect) {
     let ide = Ide::from_fixture_allow_errors(fixture);
     for position in fixture.positions() {
-        let completions = ide.snapshot().completions(position);
+        let completions = ide.snapshot().completions(position, config);
         expect.assert_debug_eq(&completions);
     }
 }
@@ -31,6 +35,27 @@ fn test_interface_completions() {
     );
 }
 
+#[test]
+fn test_interface_completions_exclude_the_implementing_interface() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Bar { foo: Foo }
+            interface Foo implements Bar & $
+        "
+        "bar" => "
+            extend interface Foo implements $ { bar: Bar }
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Bar :: Interface,
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn test_toplevel_keyword_completions() {
     let fixture = fixture! {
@@ -41,17 +66,57 @@ fn test_toplevel_keyword_completions() {
         expect![[r#"
             [
                 type :: Keyword,
-                scalar :: Keyword,
-                enum :: Keyword,
-                union :: Keyword,
                 interface :: Keyword,
+                union :: Keyword,
+                enum :: Keyword,
+                input :: Keyword,
+                scalar :: Keyword,
+                schema :: Keyword,
                 directive :: Keyword,
+                extend :: Keyword,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_extend_keyword_completions() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { a: Int }
+            extend $
+        "
+        "bar" => "extend sch$"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                type :: Keyword,
+                interface :: Keyword,
+                union :: Keyword,
+                enum :: Keyword,
                 input :: Keyword,
+                scalar :: Keyword,
+                schema :: Keyword,
             ]
         "#]],
     );
 }
 
+// the keywords and extensions must be those of the grammar
+#[test]
+fn test_definition_keywords_are_in_grammar() {
+    let language = gqls_syntax::language();
+    for (keyword, extension) in super::DEFINITION_KEYWORDS {
+        assert_ne!(language.id_for_node_kind(keyword, false), 0, "{keyword}");
+        if let Some(extension) = extension {
+            assert_ne!(language.id_for_node_kind(extension, true), 0, "{extension}");
+        }
+    }
+    assert_ne!(language.id_for_node_kind(super::EXTEND, false), 0);
+}
+
 #[test]
 fn test_object_field_completions() {
     let fixture = fixture! {
@@ -128,6 +193,14 @@ fn test_directive_locations_completions() {
         &fixture,
         expect![[r#"
             [
+                QUERY :: DirectiveLocation,
+                MUTATION :: DirectiveLocation,
+                SUBSCRIPTION :: DirectiveLocation,
+                FIELD :: DirectiveLocation,
+                FRAGMENT_DEFINITION :: DirectiveLocation,
+                FRAGMENT_SPREAD :: DirectiveLocation,
+                INLINE_FRAGMENT :: DirectiveLocation,
+                VARIABLE_DEFINITION :: DirectiveLocation,
                 SCHEMA :: DirectiveLocation,
                 SCALAR :: DirectiveLocation,
                 OBJECT :: DirectiveLocation,
@@ -144,6 +217,39 @@ fn test_directive_locations_completions() {
     );
 }
 
+#[test]
+fn test_directive_locations_completions_exclude_listed() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @qux on OBJECT | INTERFACE | $
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                QUERY :: DirectiveLocation,
+                MUTATION :: DirectiveLocation,
+                SUBSCRIPTION :: DirectiveLocation,
+                FIELD :: DirectiveLocation,
+                FRAGMENT_DEFINITION :: DirectiveLocation,
+                FRAGMENT_SPREAD :: DirectiveLocation,
+                INLINE_FRAGMENT :: DirectiveLocation,
+                VARIABLE_DEFINITION :: DirectiveLocation,
+                SCHEMA :: DirectiveLocation,
+                SCALAR :: DirectiveLocation,
+                FIELD_DEFINITION :: DirectiveLocation,
+                ARGUMENT_DEFINITION :: DirectiveLocation,
+                UNION :: DirectiveLocation,
+                ENUM :: DirectiveLocation,
+                ENUM_VALUE :: DirectiveLocation,
+                INPUT_OBJECT :: DirectiveLocation,
+                INPUT_FIELD_DEFINITION :: DirectiveLocation,
+            ]
+        "#]],
+    );
+}
+
 #[test]
 fn test_union_member_field_completions() {
     let fixture = fixture! {
@@ -170,6 +276,456 @@ fn test_union_member_field_completions() {
     );
 }
 
+#[test]
+fn test_union_member_completions_exclude_members() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { foo: Int }
+            type Bar { bar: Int }
+            type Baz { baz: Int }
+            type Qux { qux: Int }
+
+            union Union = Foo | $ | Bar
+        "
+        "bar" => "
+            extend union Union =
+              | Baz
+              | $
+        "
+        "baz" => "extend union Union = Qu$"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Qux :: Object,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_root_operation_type_completions() {
+    let fixture = fixture! {
+        "types" => "
+            type Query { foo: Int }
+            type Mutation { foo: Int }
+            interface IgnoreInterface { bar: Int }
+            input IgnoreInput { bar: Int }
+            scalar IgnoreScalar
+            directive @qux on FIELD_DEFINITION
+        "
+        "foo" => "
+            schema {
+              query: $
+            }
+        "
+        "bar" => "extend schema { mutation: Mu$ }"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Mutation :: Object,
+                Query :: Object,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_type_wrapper_variants() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @d on FIELD_DEFINITION
+            type Foo {
+               bar: $
+            }
+        "
+    };
+    let config =
+        CompletionConfig { type_wrappers: TypeWrappers::Variants, ..Default::default() };
+    test_with(
+        &fixture,
+        &config,
+        expect![[r#"
+            [
+                @d :: Directive(FIELD_DEFINITION),
+                Foo :: Object,
+                Foo! :: Object,
+                [Foo!]! :: Object,
+            ]
+        "#]],
+    );
+
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let filter_texts = ide
+        .snapshot()
+        .completions(position, &config)
+        .into_iter()
+        .map(|item| item.filter_text)
+        .collect::<Vec<_>>();
+    let foo = Some("Foo".to_owned());
+    assert_eq!(filter_texts, [None, foo.clone(), foo.clone(), foo]);
+}
+
+#[test]
+fn test_type_wrapper_snippet() {
+    let fixture = fixture! {
+        "foo" => "
+            input Foo {
+               bar: $
+            }
+        "
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let config =
+        CompletionConfig { type_wrappers: TypeWrappers::Snippet, ..Default::default() };
+    let completions = ide
+        .snapshot()
+        .completions(position, &config)
+        .into_iter()
+        .map(|item| (item.label, item.insert_text))
+        .collect::<Vec<_>>();
+    assert_eq!(completions, [("Foo".to_owned(), Some("${1|Foo,Foo!,[Foo!]!|}".to_owned()))]);
+}
+
+#[test]
+fn test_parse_type_wrappers() {
+    assert_eq!("variants".parse(), Ok(TypeWrappers::Variants));
+    assert_eq!("snippet".parse(), Ok(TypeWrappers::Snippet));
+    assert!("all".parse::<TypeWrappers>().is_err());
+}
+
+#[test]
+fn test_input_extension_field_completions() {
+    let fixture = fixture! {
+        "foo" => "
+            input Foo { a: Int }
+            extend input Foo {
+               bar: $
+            }
+
+            type IgnoreObjects { bar: Int }
+            enum Enum { A, B }
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Enum :: Enum,
+                Foo :: InputObject,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_extension_completes_as_extended_type() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { a: Int }
+            extend interface Foo { b: Int }
+
+            type Bar {
+               foo: $
+            }
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Bar :: Object,
+                Foo :: Object,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_completion_documentation() {
+    let fixture = fixture! {
+        "foo" => r#"
+            "A `Foo`"
+            type Foo { a: Int }
+            extend type Foo { b: Int }
+
+            "A directive"
+            directive @d on FIELD_DEFINITION
+
+            type Bar implements I {
+               foo: $
+            }
+
+            interface I { id: ID! }
+        "#
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let documentation = ide
+        .snapshot()
+        .completions(position, &Default::default())
+        .into_iter()
+        .map(|completion| (completion.label, completion.documentation))
+        .collect::<Vec<_>>();
+    assert_eq!(documentation, [
+        ("@d".to_owned(), Some("A directive".to_owned())),
+        ("Bar".to_owned(), Some("**implements** `I`".to_owned())),
+        ("Foo".to_owned(), Some("A `Foo`".to_owned())),
+        ("I".to_owned(), Some("**implemented by** `Bar`".to_owned())),
+    ]);
+}
+
+#[test]
+fn test_enum_value_completions() {
+    let fixture = fixture! {
+        "schema" => "
+            enum Color { RED GREEN }
+            directive @paint(color: Color) on OBJECT
+        "
+        "input field default" => "input Foo { color: Color = R$ }"
+        "argument default" => "type Foo { foo(color: Color = R$): Int }"
+        "directive argument default" => "directive @qux(color: Color = R$) on OBJECT"
+        "directive argument" => "type Foo @paint(color: R$) { foo: Int }"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                RED :: EnumValue,
+                GREEN :: EnumValue,
+                null :: Keyword,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_value_completions_of_expected_type() {
+    let schema = "
+        enum Color { RED GREEN }
+        directive @paint(colors: [Color!], glossy: Boolean!) on OBJECT
+    ";
+    let cases = [
+        ("type Foo @paint(colors: [$]) { foo: Int }", vec!["RED", "GREEN"]),
+        ("type Foo @paint(glossy: t$) { foo: Int }", vec!["true", "false"]),
+        ("type Foo { foo(flag: Boolean = t$): Int }", vec!["true", "false", "null"]),
+        ("type Foo { foo(color: Color! = R$): Int }", vec!["RED", "GREEN"]),
+        ("type Foo { foo(size: Int = 1$): Int }", vec!["null"]),
+    ];
+    for (text, expected) in cases {
+        let fixture = fixture! {
+            "schema" => schema
+            "foo" => text
+        };
+        let ide = Ide::from_fixture_allow_errors(&fixture);
+        for position in fixture.positions() {
+            let completions = ide.snapshot().completions(position, &Default::default());
+            let labels = completions.iter().map(|completion| &completion.label).collect::<Vec<_>>();
+            assert_eq!(labels, expected, "at the `$` of {text:?}");
+        }
+    }
+}
+
+#[test]
+fn test_object_field_completions_of_expected_type() {
+    let schema = r#"
+        enum Order { ASC DESC }
+        input Filter {
+            name: String
+            order: Order
+            nested: [Filter!]
+        }
+        directive @filter(filter: Filter) on OBJECT
+    "#;
+    let cases = [
+        ("type Foo { foo(filter: Filter = { $ }): Int }", vec!["name", "order", "nested"]),
+        (r#"type Foo { foo(filter: Filter = { name: "x", $ }): Int }"#, vec!["order", "nested"]),
+        (r#"type Foo { foo(filter: Filter = { name: "x", o$ }): Int }"#, vec!["order", "nested"]),
+        ("type Foo { foo(f: Filter = { nested: [{ $ }] }): Int }", vec!["name", "order", "nested"]),
+        ("type Foo { foo(filter: Filter = { order: D$ }): Int }", vec!["ASC", "DESC", "null"]),
+        ("type Foo @filter(filter: { order: ASC, $ }) { foo: Int }", vec!["name", "nested"]),
+    ];
+    for (text, expected) in cases {
+        let fixture = fixture! {
+            "schema" => schema
+            "foo" => text
+        };
+        let ide = Ide::from_fixture_allow_errors(&fixture);
+        for position in fixture.positions() {
+            let completions = ide.snapshot().completions(position, &Default::default());
+            let labels = completions.iter().map(|completion| &completion.label).collect::<Vec<_>>();
+            assert_eq!(labels, expected, "at the `$` of {text:?}");
+        }
+    }
+}
+
+#[test]
+fn test_completions_are_ranked() {
+    let fixture = fixture! {
+        "other" => "type Alpha { a: Int }\nenum Color { RED BLUE @deprecated GREEN }"
+        "foo" => "
+            type Zeta { z: Int }
+            type Old @deprecated { o: Int }
+            type Bar {
+               bar: $
+            }
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                Bar :: Object,
+                Zeta :: Object,
+                Alpha :: Object,
+                Color :: Enum,
+                Old :: Object,
+            ]
+        "#]],
+    );
+
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let sort_texts = ide
+        .snapshot()
+        .completions(position, &Default::default())
+        .into_iter()
+        .map(|completion| completion.sort_text.unwrap())
+        .collect::<Vec<_>>();
+    assert!(sort_texts.windows(2).all(|pair| pair[0] < pair[1]), "{sort_texts:?}");
+}
+
+#[test]
+fn test_parts_of_a_type_are_merged() {
+    let fixture = fixture! {
+        "other" => r#""An old type" type Old @deprecated { o: Int }"#
+        "foo" => "
+            extend type Old { p: Int }
+            type Bar {
+               bar: $
+            }
+        "
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let completions = ide.snapshot().completions(position, &Default::default());
+    let old = completions.into_iter().find(|completion| completion.label == "Old").unwrap();
+    assert!(old.deprecated);
+    assert_eq!(old.locality, Locality::File);
+    assert_eq!(old.documentation.as_deref(), Some("An old type"));
+}
+
+#[test]
+fn test_deprecated_enum_value_completions_are_last() {
+    let fixture = fixture! {
+        "schema" => "enum Color { RED BLUE @deprecated GREEN }"
+        "input" => "input Foo { color: Color = B$ }"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                RED :: EnumValue,
+                GREEN :: EnumValue,
+                null :: Keyword,
+                BLUE :: EnumValue,
+            ]
+        "#]],
+    );
+}
+
+fn test_docstring(fixture: &Fixture, docstrings: Docstrings, expect: Expect) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let config = CompletionConfig { docstrings, ..Default::default() };
+    for position in fixture.positions() {
+        let completions = ide.snapshot().completions(position, &config);
+        let templates = completions.into_iter().filter_map(|completion| completion.insert_text);
+        expect.assert_debug_eq(&templates.collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn test_docstring_completions() {
+    let fixture = fixture! {
+        "foo" => r#"
+type Query {
+  """$
+  users(first: Int, after: String): [User]
+}
+"#
+    };
+    test_docstring(
+        &fixture,
+        Docstrings::Arguments,
+        expect![[r#"
+            [
+                "\n  $1\n\n  - `first`: $2\n  - `after`: $3\n  \"\"\"",
+            ]
+        "#]],
+    );
+    test_docstring(
+        &fixture,
+        Docstrings::Summary,
+        expect![[r#"
+            [
+                "\n  $1\n  \"\"\"",
+            ]
+        "#]],
+    );
+    test_docstring(
+        &fixture,
+        Docstrings::None,
+        expect![[r#"
+            []
+        "#]],
+    );
+
+    let fixture = fixture! {
+        "foo" => r#"
+"""$
+
+type User {
+  name: String
+}
+"#
+    };
+    test_docstring(
+        &fixture,
+        Docstrings::Arguments,
+        expect![[r#"
+            [
+                "\n$1\n\"\"\"",
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_docstring_completions_after_other_quotes() {
+    let fixture = fixture! {
+        "closing" => r#"
+"""
+The user
+"""$
+type User {
+  name: String @deprecated(reason: "$
+}
+"#
+    };
+    test_docstring(
+        &fixture,
+        Docstrings::Arguments,
+        expect![[r#"
+            []
+        "#]],
+    );
+}
+
 #[cfg(test)]
 mod infer_context;
 
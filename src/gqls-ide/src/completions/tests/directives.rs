@@ -120,3 +120,105 @@ fn test_complete_directives() {
         "#]]
     );
 }
+
+#[test]
+fn test_directive_completion_detail_and_snippet() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @d(a: Int = 1, b: String!, c: Int) on OBJECT
+            directive @e on OBJECT
+            type Foo $
+        "
+    };
+    let ide = crate::Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let completions = ide
+        .snapshot()
+        .completions(position, &Default::default())
+        .into_iter()
+        .map(|item| (item.label, item.detail, item.insert_text))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            (
+                "@d",
+                Some(
+                    "(a: Int = 1, b: String!, c: Int)",
+                ),
+                Some(
+                    "@d(b: \"$1\")",
+                ),
+            ),
+            (
+                "@e",
+                None,
+                Some(
+                    "@e",
+                ),
+            ),
+        ]
+    "#]]
+    .assert_debug_eq(&completions);
+}
+
+#[test]
+fn test_complete_directive_arguments() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @paint(size: Int, color: String!) on OBJECT
+            type Foo @paint(size: 1, $) { foo: Int }
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                color :: Argument,
+            ]
+        "#]],
+    );
+
+    let fixture = fixture! {
+        "foo" => "
+            directive @paint(size: Int, color: String!) on OBJECT
+            type Foo @paint($
+        "
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                size :: Argument,
+                color :: Argument,
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_complete_builtin_directive_arguments() {
+    let fixture = fixture! {
+        "foo" => "type Foo { foo: Int @deprecated($) }"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                reason :: Argument,
+            ]
+        "#]],
+    );
+
+    let fixture = fixture! {
+        "foo" => "type Foo { foo: Int @deprecated(reason: $) }"
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            [
+                "" :: Keyword,
+                null :: Keyword,
+            ]
+        "#]],
+    );
+}
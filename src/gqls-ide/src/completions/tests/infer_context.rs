@@ -1,9 +1,9 @@
 use gqls_fixture::{fixture, Fixture};
-use gqls_ir::DirectiveLocations;
+use gqls_ir::{DirectiveLocations, Name};
 
 use crate::Ide;
 
-use super::super::{CompletionCtxt, Context};
+use super::super::{CompletionCtxt, Context, ExpectedType};
 
 #[track_caller]
 fn test(fixture: &Fixture, expected: Context) {
@@ -20,20 +20,64 @@ fn test_infer_interface_context() {
     let fixture = fixture! {
         "after implements" => "type Foo implements $"
         "after implements and &" => "type Foo implements Bar & $"
-        // FIXME
-        // "after implements with brace" => "type Foo implements $ {}"
-        // "after implements and & with brace" => "type Foo implements Foo & $ {}"
+        "after implements with brace" => "type Foo implements $ {}"
+        "after implements and & with brace" => "type Foo implements Foo & $ {}"
+    };
+    test(&fixture, Context::Interface(None));
+}
+
+#[test]
+fn test_infer_interface_context_of_interface() {
+    let fixture = fixture! {
+        "definition" => "interface Foo implements Bar & $"
+        "extension" => "extend interface Foo implements $ {}"
     };
-    test(&fixture, Context::Interface);
+    test(&fixture, Context::Interface(Some(Name::unranged("Foo"))));
+}
+
+#[test]
+fn test_infer_extend_context() {
+    let fixture = fixture! {
+        "after extend" => "extend $"
+        "partial keyword" => "extend ty$"
+        "on the next line" => "
+            extend
+            $
+        "
+    };
+    test(&fixture, Context::Extend);
+
+    let fixture = fixture! {
+        "field named extend" => "type Foo { extend: $ }"
+        "prefix" => "type Foo { a: Int } unextend $"
+    };
+    for position in fixture.positions() {
+        let ide = Ide::from_fixture_allow_errors(&fixture);
+        let context = CompletionCtxt::infer_context(&ide.snapshot(), position);
+        assert_ne!(context, Context::Extend);
+    }
 }
 
 #[test]
 fn test_infer_directive_locations_context() {
     let fixture = fixture! {
-        "after bar" => "directive @foo on FIELD | $"
         "after on" => "directive @foo on $"
+        "after an unknown location" => "directive @foo on FOO | $"
+    };
+    test(&fixture, Context::DirectiveLocations(DirectiveLocations::empty()));
+
+    let fixture = fixture! {
+        "after an executable location" => "directive @foo on FIELD | $"
+    };
+    test(&fixture, Context::DirectiveLocations(DirectiveLocations::FIELD));
+
+    let fixture = fixture! {
+        "after bar" => "directive @foo on OBJECT | $"
+        "before bar" => "directive @foo on $ | OBJECT"
+        "at the end of a location" => "directive @foo on OBJECT | ENUM$"
+        "replacing" => "directive @foo on OBJECT | UNI$ON"
     };
-    test(&fixture, Context::DirectiveLocations);
+    test(&fixture, Context::DirectiveLocations(DirectiveLocations::OBJECT));
 }
 
 #[test]
@@ -68,12 +112,10 @@ fn test_infer_field_context() {
             }
         "
 
-
-        // FIXME
-        // "qux" => "
-        //     type Foo {
-        //         bar: $$$
-        // "
+        "qux" => "
+            type Foo {
+                bar: $$$
+        "
     };
     test(&fixture, Context::Field);
 }
@@ -100,12 +142,12 @@ fn test_infer_input_field_context() {
             }
         "
 
-        // FIXMEs
-        // "baz" => "
-        //     input Foo {
-        //         bar: Foo$$$
-        // "
+        "baz" => "
+            input Foo {
+                bar: Foo$$$
+        "
 
+        // FIXME
         // "qux" => "
         //     type Foo {
         //         bar: $$$
@@ -125,7 +167,13 @@ fn test_infer_top_level_context() {
             }
             $
         "
-        //FIXME adding a $ after closing } fails
+        "after closing brace" => "type Foo { bar: Int! }$"
+        "eof" => "
+            type Foo {
+                bar: Int!
+            }
+            #eof
+        "
     };
     test(&fixture, Context::Document);
 }
@@ -135,15 +183,50 @@ fn test_infer_union_member_types_context() {
     let fixture = fixture! {
         "foo" => "union Union = $ Foo $ | $ Bar $ | $ Baz $$$"
     };
-    test(&fixture, Context::UnionMembers);
+    test(&fixture, Context::UnionMembers(Some(Name::unranged("Union"))));
 }
 
 #[test]
 fn test_infer_union_member_types_context_first_member() {
     let fixture = fixture! {
         "foo" => "union Union = $"
+        "bar" => "extend union Union = | $"
+    };
+    test(&fixture, Context::UnionMembers(Some(Name::unranged("Union"))));
+}
+
+#[test]
+fn test_infer_union_member_types_context_after_pipe() {
+    let fixture = fixture! {
+        "foo" => "union Union = Foo |$"
+        "bar" => "union Union = Foo | Bar | B$"
+        "baz" => "
+            union Union =
+              | Foo
+              | $
+        "
+        "qux" => "
+            union Union = Foo
+              | $
+
+            type Foo { foo: Int }
+        "
+    };
+    test(&fixture, Context::UnionMembers(Some(Name::unranged("Union"))));
+}
+
+#[test]
+fn test_infer_root_operation_type_context() {
+    let fixture = fixture! {
+        "foo" => "schema { query: $ }"
+        "bar" => "
+            schema {
+              query: Qu$
+            }
+        "
+        "baz" => "extend schema { mutation: $ }"
     };
-    test(&fixture, Context::UnionMembers);
+    test(&fixture, Context::RootOperationType);
 }
 
 #[test]
@@ -222,3 +305,83 @@ fn test_infer_scalar_enum_value_context() {
     };
     test(&fixture, Context::Directive(DirectiveLocations::ENUM_VALUE));
 }
+
+#[test]
+fn test_infer_value_context() {
+    let fixture = fixture! {
+        "schema" => "
+            enum Color { RED GREEN }
+            directive @paint(color: Color) on OBJECT
+        "
+        "input field default" => "input Foo { color: [Color] = [R$] }"
+        "argument default" => "type Foo { foo(color: Color = R$): Int }"
+        "directive argument" => "type Foo @paint(color: R$) { foo: Int }"
+    };
+    let expected = ExpectedType { name: Name::unranged("Color"), nullable: true };
+    test(&fixture, Context::Value(Some(expected)));
+}
+
+#[test]
+fn test_infer_non_null_value_context() {
+    let fixture = fixture! {
+        "schema" => "
+            enum Color { RED GREEN }
+            directive @paint(colors: [[Color!]]) on OBJECT
+        "
+        "input field default" => "input Foo { color: [Color!] = [R$] }"
+        "argument default" => "type Foo { foo(color: Color! = R$): Int }"
+        "nested list" => "type Foo @paint(colors: [[R$]]) { foo: Int }"
+        "recovered default" => "input Foo { color: Color! = $ }"
+    };
+    let expected = ExpectedType { name: Name::unranged("Color"), nullable: false };
+    test(&fixture, Context::Value(Some(expected)));
+}
+
+#[test]
+fn test_infer_context_table() {
+    let schema = "
+        enum Color { RED GREEN }
+        directive @paint(size: Int, color: Color) on OBJECT
+    ";
+    let color = ExpectedType { name: Name::unranged("Color"), nullable: true };
+    let color = Context::Value(Some(color));
+    let paint = Context::DirectiveArguments(Name::unranged("@paint"));
+    let cases = [
+        // after `implements`
+        ("type Foo implements Bar &$", Context::Interface(None)),
+        ("type Foo implements Bar & Baz & $", Context::Interface(None)),
+        ("type Foo implements $ {}", Context::Interface(None)),
+        // inside the arguments of a directive
+        ("type Foo @paint($) { foo: Int }", paint),
+        ("type Foo @paint(size: 1, $) { foo: Int }", paint),
+        ("type Foo @paint(color: $) { foo: Int }", color),
+        // inside `ERROR` nodes
+        ("type Foo @paint($", paint),
+        ("type Foo @paint(color: $", color),
+        ("type Foo @paint(size: 1, color: $", color),
+        ("input Foo { color: Color = $ }", color),
+        ("type Foo { foo(color: Color = $): Int }", color),
+        ("union Union = Foo | Bar |$", Context::UnionMembers(Some(Name::unranged("Union")))),
+        (
+            "directive @foo on OBJECT | UNI$ON",
+            Context::DirectiveLocations(DirectiveLocations::OBJECT),
+        ),
+        // at the end of the file
+        ("type Foo {\n  bar: $", Context::Field),
+        ("input Foo {\n  bar: $", Context::InputField),
+        ("type Foo { bar: Int }$", Context::Document),
+        // where the name of a field is written
+        ("type Foo {\n  bar: Int\n  $\n}", Context::Name),
+    ];
+    for (text, expected) in cases {
+        let fixture = fixture! {
+            "schema" => schema
+            "foo" => text
+        };
+        let ide = Ide::from_fixture_allow_errors(&fixture);
+        for position in fixture.positions() {
+            let context = CompletionCtxt::infer_context(&ide.snapshot(), position);
+            assert_eq!(context, expected, "at the `$` of {text:?}");
+        }
+    }
+}
@@ -1,13 +1,29 @@
 use super::super::Queries;
 use gqls_syntax::{parse_fresh, Query, QueryCursor, QueryExt};
 
-fn _test(query: &Query, source: &str, should_match: bool) {
+#[track_caller]
+fn test(query: &Query, source: &str, should_match: bool) {
     let tree = parse_fresh(source);
     let mut cursor = QueryCursor::new();
     assert_eq!(query.is_match(&mut cursor, tree.root_node(), source.as_bytes()), should_match);
 }
 
 #[test]
-fn test_queries() {
-    let _queries = Queries::default();
+fn test_context_query() {
+    let queries = Queries::default();
+    test(&queries.context, "type Foo", true);
+    test(&queries.context, "type Foo implements A & ", true);
+    test(&queries.context, "schema { query: Query }", true);
+    test(&queries.context, "schema @foo", false);
+}
+
+#[test]
+fn test_recovery_query() {
+    let queries = Queries::default();
+    test(&queries.recovery, "type Foo @d(arg: ", true);
+    test(&queries.recovery, "type Foo @d(", true);
+    test(&queries.recovery, "type Foo { bar: ", true);
+    test(&queries.recovery, "input Foo { bar: Int = }", true);
+    test(&queries.recovery, "type Foo { bar: Int }", false);
+    test(&queries.recovery, "type Foo implements A & ", false);
 }
@@ -0,0 +1,153 @@
+//! Converting an object type to an interface and vice versa. All parts of the type are rewritten
+//! and an interface that becomes an object type is removed from the `implements` clauses of the
+//! project. An interface can't be a member of a union, such memberships are left as is and
+//! reported as conflicts instead.
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{
+    builtins_file, InProject, ItemKind, ItemRes, ItemResolutions, Name, Res, TypeDefinitionKind
+};
+use gqls_syntax::{NodeExt, NodeKind, Position};
+
+use crate::{Location, Patch, Range, Snapshot, SourceChange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeConversionKind {
+    ObjectToInterface,
+    InterfaceToObject,
+}
+
+impl TypeConversionKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            TypeConversionKind::ObjectToInterface => "Convert object type to interface",
+            TypeConversionKind::InterfaceToObject => "Convert interface to object type",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeConversion {
+    pub kind: TypeConversionKind,
+    pub change: SourceChange,
+    /// the union member types that refer to the type, which would then be an interface
+    pub conflicts: Vec<Location>,
+}
+
+/// An object type or interface that can be converted, see [`Snapshot::convert_type`]
+pub(crate) struct Convertible {
+    pub kind: TypeConversionKind,
+    name: InProject<Name>,
+    resolutions: ItemResolutions,
+}
+
+impl Snapshot {
+    /// Convert the object type or interface whose definition (or extension) is named at `position`
+    pub fn convert_type(&self, position: Position) -> Option<TypeConversion> {
+        let convertible = self.convertible(position)?;
+        let conflicts = convertible.conflicts(self)?;
+        let change = convertible.change(self)?;
+        Some(TypeConversion { kind: convertible.kind, change, conflicts })
+    }
+
+    /// The object type or interface named at `position` if it can be converted, without building
+    /// the change that converts it
+    pub(crate) fn convertible(&self, position: Position) -> Option<Convertible> {
+        let name = self.name_at(position)?;
+        let resolutions =
+            self.resolve_item(InProject::new(position.file, name)).try_into_item().ok()?;
+        let is_definition = resolutions
+            .iter()
+            .any(|&res| res.file == position.file && self.item(res).name.range == name.range);
+        if !is_definition || resolutions.iter().any(|res| res.file == builtins_file()) {
+            return None;
+        }
+
+        let kinds = resolutions
+            .iter()
+            .map(|&res| match self.item(res).kind {
+                ItemKind::TypeDefinition(idx) => Some(self.typedef(res.file, idx).kind),
+                ItemKind::DirectiveDefinition(_) => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let kind = if kinds.iter().all(|kind| *kind == TypeDefinitionKind::Object) {
+            TypeConversionKind::ObjectToInterface
+        } else if kinds.iter().all(|kind| *kind == TypeDefinitionKind::Interface) {
+            TypeConversionKind::InterfaceToObject
+        } else {
+            return None;
+        };
+        let name = InProject::new(position.file, name);
+        Some(Convertible { kind, name, resolutions })
+    }
+
+    /// The patch that removes `interface` from the `implements` clause of `implementation`, along
+    /// with the clause itself if nothing else is implemented
+    fn remove_implementation(&self, implementation: ItemRes, interface: Name) -> Option<Patch> {
+        let data = self.file_data(implementation.file);
+        let clause = data
+            .tree
+            .root_node()
+            .named_node_at(self.item(implementation).name.range.start_point)?
+            .parent()?
+            .child_of_kind(NodeKind::IMPLEMENTS_INTERFACES)?;
+        let interfaces =
+            clause.children_of_kind(&mut clause.walk(), NodeKind::NAMED_TYPE).collect::<Vec<_>>();
+        let i = interfaces.iter().position(|node| node.text(&data.text) == interface.as_str())?;
+        let (start, end) = match (i, &interfaces[..]) {
+            (_, [_]) => (clause.prev_sibling()?.end_position(), clause.end_position()),
+            (0, [interface, next, ..]) => (interface.start_position(), next.start_position()),
+            _ => (interfaces[i - 1].end_position(), interfaces[i].end_position()),
+        };
+        Some(Patch::new(Range { start, end }, String::new()))
+    }
+}
+
+impl Convertible {
+    /// The union member types that refer to the type, which would then be an interface
+    pub(crate) fn conflicts(&self, snapshot: &Snapshot) -> Option<Vec<Location>> {
+        let mut conflicts = vec![];
+        if self.kind == TypeConversionKind::ObjectToInterface {
+            for (file, range) in snapshot.references(Res::Item(self.resolutions.clone())) {
+                let data = snapshot.file_data(file);
+                let node = data.tree.root_node().named_node_at(range.start_point)?;
+                if node.has_parent_of_kind(NodeKind::UNION_MEMBER_TYPES) {
+                    conflicts.push(Location::new(file, range));
+                }
+            }
+            conflicts.sort();
+        }
+        Some(conflicts)
+    }
+
+    /// The change that rewrites the keyword of every part of the type, and removes an interface
+    /// that becomes an object type from the types that implement it
+    pub(crate) fn change(&self, snapshot: &Snapshot) -> Option<SourceChange> {
+        let (from, to) = match self.kind {
+            TypeConversionKind::ObjectToInterface => ("type", "interface"),
+            TypeConversionKind::InterfaceToObject => ("interface", "type"),
+        };
+        let mut change = SourceChange::default();
+        for &res in &self.resolutions {
+            let data = snapshot.file_data(res.file);
+            let name = snapshot.item(res).name;
+            let definition = data.tree.root_node().named_node_at(name.range.start_point)?.parent()?;
+            let keyword = definition
+                .children(&mut definition.walk())
+                .find(|child| !child.is_named() && child.kind() == from)?;
+            let patch = Patch::new(keyword.range().into(), to.to_owned());
+            change.insert(res.file, patch);
+        }
+
+        if self.kind == TypeConversionKind::InterfaceToObject {
+            for implementation in snapshot.implementations(self.name) {
+                let patch = snapshot.remove_implementation(implementation, self.name.value)?;
+                change.insert(implementation.file, patch);
+            }
+        }
+        Some(change)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use gqls_fixture::{fixture, Fixture};
+use gqls_syntax::Position;
+use ropey::Rope;
+
+use crate::{Ide, Location, Point, TypeConversionKind};
+
+// the `$` of the fixtures is placed right before the name of the type to be converted
+fn positions(fixture: &Fixture) -> impl Iterator<Item = Position> + '_ {
+    fixture.positions().map(|position| {
+        Position::new(position.file, Point::new(position.point.row, position.point.column + 1))
+    })
+}
+
+#[track_caller]
+fn test(fixture: &Fixture, kind: TypeConversionKind, expected: HashMap<&str, &str>) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let snapshot = ide.snapshot();
+    for position in positions(fixture) {
+        let conversion = snapshot.convert_type(position).expect("expected a conversion");
+        assert_eq!(conversion.kind, kind);
+        let mut actual = HashMap::new();
+        for mut file_patches in conversion.change {
+            let mut rope = Rope::from_str(&snapshot.file_text(file_patches.file));
+            // apply the later patches first so the ranges of the earlier ones are still valid
+            file_patches.patches.sort_by_key(|patch| patch.range.start);
+            for patch in file_patches.patches.iter().rev() {
+                let _ = patch.apply(&mut rope);
+            }
+            actual.insert(file_patches.file.to_str().unwrap(), rope.to_string());
+        }
+        let expected = expected.iter().map(|(&file, &text)| (file, text.to_owned())).collect();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn test_convert_object_to_interface() {
+    let fixture = fixture! {
+        "foo" => "type$Foo { a: Int }\nextend type Foo @d\ntype Bar { foo: Foo }\n"
+    };
+    test(
+        &fixture,
+        TypeConversionKind::ObjectToInterface,
+        HashMap::from([(
+            "foo",
+            "interface Foo { a: Int }\nextend interface Foo @d\ntype Bar { foo: Foo }\n",
+        )]),
+    );
+}
+
+#[test]
+fn test_convert_interface_to_object() {
+    let fixture = fixture! {
+        "i" => "interface$I { a: Int }\n"
+        "a" => "type A implements I { a: Int }\n"
+        "b" => "type B implements & I & J { a: Int }\ntype C implements J & I & K { a: Int }\n"
+        "j" => "interface J implements I { a: Int }\n"
+    };
+    test(
+        &fixture,
+        TypeConversionKind::InterfaceToObject,
+        HashMap::from([
+            ("i", "type I { a: Int }\n"),
+            ("a", "type A { a: Int }\n"),
+            ("b", "type B implements & J { a: Int }\ntype C implements J & K { a: Int }\n"),
+            ("j", "interface J { a: Int }\n"),
+        ]),
+    );
+}
+
+#[test]
+fn test_convert_object_in_union_to_interface() {
+    let fixture = fixture! {
+        "foo" => "type$Foo { a: Int }\nunion U = Bar | Foo\n               #...\n"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let position = positions(&fixture).next().unwrap();
+    let conversion = snapshot.convert_type(position).unwrap();
+    let expected = fixture.ranges().map(|(file, range)| Location::new(file, range));
+    assert_eq!(conversion.conflicts, expected.collect::<Vec<_>>());
+}
+
+#[test]
+fn test_convert_type_not_applicable() {
+    let fixture = fixture! {
+        "foo" => "type Foo { a: Int }\ntype Bar { foo:$Foo }\ninput$I { a: Int }\nscalar$S"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    for position in positions(&fixture) {
+        assert!(ide.snapshot().convert_type(position).is_none());
+    }
+}
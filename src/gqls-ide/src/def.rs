@@ -1,16 +1,106 @@
-use gqls_db::DefDatabase;
-use gqls_syntax::Position;
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{InProject, Item, ItemKind, ItemRes, Name};
+use gqls_syntax::{NodeExt, NodeKind, Position, RangeExt};
+use vfs::FileId;
 
 use crate::{Location, Snapshot};
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DefinitionConfig {
+    /// only go to the base definition of a type rather than to its extensions as well, the
+    /// extensions are still found by find references
+    pub exclude_extensions: bool,
+}
+
 impl Snapshot {
-    pub fn goto_definition(&self, position: Position) -> Vec<Location> {
-        self.resolve_item_name_at(position)
+    /// The base definition of the name at `position` followed by its extensions (by file and
+    /// then by position). Extensions without a base definition are returned even if excluded by
+    /// `config`, as there is nothing else to go to.
+    pub fn goto_definition(&self, position: Position, config: &DefinitionConfig) -> Vec<Location> {
+        if let Some(field) = self.resolve_field_set_at(position) {
+            return vec![Location::new(field.item.file, self.field(field).name.range)];
+        }
+
+        let mut parts = self.parts_at(position);
+        if config.exclude_extensions && parts.iter().any(|(is_ext, ..)| !is_ext) {
+            parts.retain(|(is_ext, ..)| !is_ext);
+        }
+        parts.into_iter().map(|(_, file, item)| Location::new(file, item.name.range)).collect()
+    }
+
+    /// The `extend` blocks of the type named at `position` (by file and then by position),
+    /// whether the name is that of its definition, an extension or a reference to it
+    pub fn extensions(&self, position: Position) -> Vec<Location> {
+        self.parts_at(position)
+            .into_iter()
+            .filter(|(is_ext, ..)| *is_ext)
+            .map(|(_, file, item)| Location::new(file, item.range))
+            .collect()
+    }
+
+    // the definition and extensions of the name at `position`, the base definition first
+    fn parts_at(&self, position: Position) -> Vec<(bool, FileId, Item)> {
+        let res = self.resolve_item_name_at(position).or_else(|| {
+            let name = self.description_name_at(position)?;
+            Some(self.resolve_item(InProject::new(position.file, name)))
+        });
+        let mut parts = res
             .into_iter()
             .flat_map(|res| res.try_into_item().ok())
             .flatten()
-            .map(|res| Location::new(res.file, self.item(res).name.range))
-            .collect()
+            .map(|res| (self.is_extension(res), res.file, self.item(res)))
+            .collect::<Vec<_>>();
+        parts.sort_by_key(|(is_ext, file, item)| (*is_ext, *file, item.range.start_point));
+        parts
+    }
+
+    /// The word at `position` if it is within a description, which refers to the type or
+    /// directive (with its leading `@`) of that name if there is one, e.g. "a `Foo` or `@bar`"
+    fn description_name_at(&self, position: Position) -> Option<Name> {
+        let root = self.file_tree(position.file).root_node();
+        let node = root.named_node_at(position.point)?;
+        if node.kind() != NodeKind::DESCRIPTION && !node.has_parent_of_kind(NodeKind::DESCRIPTION) {
+            return None;
+        }
+        let text = self.file_text(position.file);
+        let line = text.lines().nth(position.point.row)?;
+        let column = position.point.column;
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let start = line.get(..column)?.rfind(|c| !is_name_char(c)).map_or(0, |i| i + 1);
+        let end = line.get(column..)?.find(|c| !is_name_char(c)).map_or(line.len(), |i| column + i);
+        let start = if line[..start].ends_with('@') { start - 1 } else { start };
+        (start < end).then(|| Name::unranged(&line[start..end]))
+    }
+
+    pub(crate) fn is_extension(&self, res: ItemRes) -> bool {
+        let items = self.items(res.file);
+        match items[res.value].kind {
+            ItemKind::TypeDefinition(typedef) => items[typedef].is_ext,
+            ItemKind::DirectiveDefinition(_) => false,
+        }
+    }
+
+    /// The definition or extension of the name under the cursor that follows the one the cursor
+    /// is within, cycling back to the first. The pieces are ordered by file and then by position
+    /// so that repeated invocations visit every piece exactly once.
+    pub fn goto_next_definition(&self, position: Position) -> Option<Location> {
+        let mut parts = self
+            .resolve_item_name_at(position)?
+            .try_into_item()
+            .ok()?
+            .into_iter()
+            .map(|res| (res.file, self.item(res)))
+            .collect::<Vec<_>>();
+        parts.sort_by_key(|(file, item)| (*file, item.range.start_point));
+        let current = parts
+            .iter()
+            .position(|(file, item)| *file == position.file && item.range.contains(position.point));
+        let (file, item) = match current {
+            Some(idx) => &parts[(idx + 1) % parts.len()],
+            // from a reference, start with the first piece
+            None => parts.first()?,
+        };
+        Some(Location::new(*file, item.name.range))
     }
 }
 
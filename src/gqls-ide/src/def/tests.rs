@@ -1,19 +1,25 @@
 use std::collections::HashSet;
 
-use crate::{point, position, range, Ide, Location};
+use crate::{point, position, range, DefinitionConfig, Ide, Location, Position};
 use gqls_db::DefDatabase;
 use gqls_fixture::{fixture, Fixture};
 use gqls_ir::Name;
 use testing::file_id;
 
 fn test(fixture: Fixture) {
-    let ide = Ide::from_fixture(&fixture);
+    test_with(Ide::from_fixture(&fixture), fixture, DefinitionConfig::default())
+}
+
+fn test_with(ide: Ide, fixture: Fixture, config: DefinitionConfig) {
     let snapshot = ide.snapshot();
     let expected_locations =
         fixture.ranges().map(|(file, range)| Location::new(file, range)).collect::<HashSet<_>>();
 
     for position in fixture.positions() {
-        let locations = snapshot.goto_definition(position).into_iter().collect::<HashSet<_>>();
+        let locations = snapshot
+            .goto_definition(position, &config)
+            .into_iter()
+            .collect::<HashSet<_>>();
         assert_eq!(expected_locations, locations);
     }
 }
@@ -52,6 +58,25 @@ type Qux {
     test(fixture);
 }
 
+#[test]
+fn test_goto_definition_of_root_operation_type() {
+    let fixture = fixture!(
+        "foo" => "
+type Root {
+    #....
+    foo: Int
+}
+"
+        "bar" => "
+schema {
+    query: Root
+          #^^^^
+}
+"
+    );
+    test(fixture);
+}
+
 #[test]
 fn test_goto_definition() {
     let mut ide = Ide::default();
@@ -90,9 +115,191 @@ type Bar {
     }
     assert!(snapshot.name_at(position!(foo:2:12)).is_none());
 
-    assert!(snapshot.goto_definition(position!(foo:0:0)).is_empty());
+    let config = DefinitionConfig::default();
+    assert!(snapshot.goto_definition(position!(foo:0:0), &config).is_empty());
     assert_eq!(
         vec![Location { file: foo, range: range!(1:5..1:8) }],
-        snapshot.goto_definition(position!(foo:1:6)),
+        snapshot.goto_definition(position!(foo:1:6), &config),
+    );
+}
+
+#[test]
+fn test_goto_definition_argument_type() {
+    let fixture = fixture!(
+        "foo" => r#"
+input BarFilter {
+     #.........
+    name: String
+}
+
+type Foo {
+    bar(filter: BarFilter = { name: "x" }): Int
+                #^
+}
+"#
+    );
+    test(fixture);
+}
+
+#[test]
+fn test_goto_definition_base_definition_first() {
+    let fixture = fixture!(
+        "a" => "
+extend type Foo {
+           #...
+    bar: Int
+}
+"
+        "b" => "
+type Foo {
+    #...
+    foo: $Foo
+}
+"
+    );
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let location = |file| {
+        let (file, range) = fixture.ranges().find(|&(f, _)| f == file).unwrap();
+        Location::new(file, range)
+    };
+    let (extension, base) = (location(file_id!("a")), location(file_id!("b")));
+    let position = fixture.positions().next().unwrap();
+    assert_eq!(snapshot.goto_definition(position, &DefinitionConfig::default()), [base, extension]);
+    let config = DefinitionConfig { exclude_extensions: true };
+    assert_eq!(snapshot.goto_definition(position, &config), [base]);
+}
+
+#[test]
+fn test_goto_definition_excluding_extensions_of_undefined_type() {
+    let fixture = fixture!(
+        "foo" => "
+extend type Foo {
+           #...
+    bar: Int
+}
+
+type Bar {
+    foo: $Foo
+}
+"
+    );
+    test_with(
+        Ide::from_fixture_allow_errors(&fixture),
+        fixture,
+        DefinitionConfig { exclude_extensions: true },
+    );
+}
+
+#[test]
+fn test_extensions() {
+    let fixture = fixture!(
+        "foo" => "
+type Foo {
+    bar: Int
+}
+
+type Bar {
+    foo: $Foo
+}
+"
+        "bar" => "
+extend type Foo {
+    baz: Int
+}
+extend type Foo { qux: Int }
+"
+    );
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let bar = file_id!("bar");
+    let expected = [Location::new(bar, range!(1:0..3:1)), Location::new(bar, range!(4:0..4:28))];
+    let position = fixture.positions().next().unwrap();
+    assert_eq!(snapshot.extensions(position), expected);
+    assert_eq!(snapshot.extensions(position!(bar:4:13)), expected);
+    // only names of types have extensions, not fields
+    assert!(snapshot.extensions(position!(bar:2:4)).is_empty());
+}
+
+#[test]
+fn test_goto_next_definition_cycles() {
+    let fixture = fixture!(
+        "foo" => "
+type Foo {
+    #...
+    bar: Bar
+}
+
+type Bar {
+    foo: $Foo
+}
+"
+        "bar" => "
+extend type Foo {
+           #...
+    baz: Int
+}
+
+extend type Foo {
+           #...
+    qux: Int
+}
+"
     );
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let position = fixture.positions().next().unwrap();
+
+    // from a reference the first piece is visited, after which each piece is visited in order
+    let mut visited = vec![];
+    let mut location = snapshot.goto_next_definition(position).unwrap();
+    while !visited.contains(&location) {
+        visited.push(location);
+        let position = Position::new(location.file, location.range.start);
+        location = snapshot.goto_next_definition(position).unwrap();
+    }
+    assert_eq!(location, visited[0]);
+
+    let mut expected =
+        fixture.ranges().map(|(file, range)| Location::new(file, range)).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(visited, expected);
+}
+
+#[test]
+fn test_goto_next_definition_without_name() {
+    let fixture = fixture!(
+        "foo" => "
+$
+type Foo {
+    bar: Int
+}
+"
+    );
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    for position in fixture.positions() {
+        assert_eq!(snapshot.goto_next_definition(position), None);
+    }
+}
+
+#[test]
+fn test_goto_definition_in_description() {
+    let fixture = fixture!(
+        "foo" => r#"
+type Foo {
+    #...
+    bar: Int
+}
+
+type Bar {
+    """
+    the `Foo` of the bar
+         #^^^
+    """
+    foo: Foo
+}
+"#
+    );
+    test(fixture);
 }
@@ -1,9 +1,9 @@
 use gqls_db::{DefDatabase, Project, SourceDatabase, TyDatabase};
 use gqls_ir::{
-    Arg, Directive, Implementations, InProject, ItemKind, ItemRes, Ty, TypeDefinitionKind, Value
+    Arg, DefaultValue, Directive, Implementations, InProject, ItemBodyKind, ItemKind, ItemRes, Name, Ty, TypeDefinitionKind, Value
 };
-use gqls_syntax::{query, Query, QueryCursor};
-use gqls_ty::TyKind;
+use gqls_syntax::{query, Node, NodeExt, NodeKind, Query, QueryCursor};
+use gqls_ty::{PathSegment, TyKind, TypeMismatch, ValueMismatch};
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display};
@@ -11,7 +11,25 @@ use std::ops::Deref;
 use std::str::FromStr;
 use vfs::FileId;
 
-use crate::{Location, Range, Snapshot};
+use crate::edit::deletion_range;
+use crate::lints::{
+    is_camel_case, is_pascal_case, is_screaming_snake_case, to_camel_case, to_pascal_case, to_screaming_snake_case
+};
+use crate::schema::BUILTIN_SCALARS;
+use crate::{Lint, Location, Patch, Point, Range, Snapshot};
+
+/// Diagnostics are split into passes so the syntax errors of a file can be published
+/// immediately, while the semantic checks (of a file and across the whole project) trail behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticPass {
+    /// syntax errors and invalid utf-8, which only need the tree of the file
+    Syntax,
+    /// resolution, type and lint checks of the items in a file
+    Local,
+    /// duplicates, empty definitions, orphaned extensions, interface conformance and the type
+    /// system rules of the spec, which depend on other files
+    Project,
+}
 
 impl Snapshot {
     pub fn diagnostics(&self, project: Project) -> Diagnostics {
@@ -21,9 +39,23 @@ impl Snapshot {
             .collect()
     }
 
+    /// The `pass` diagnostics of each file of `project`, which are computed concurrently
+    pub fn pass_diagnostics(&self, project: Project, pass: DiagnosticPass) -> Diagnostics {
+        self.par_map_files(project, |snapshot, file| snapshot.file_pass_diagnostics(file, pass))
+            .into_iter()
+            .collect()
+    }
+
     // TODO this can probably be a database query so we get benefit of caching
     pub fn file_diagnostics(&self, file: FileId) -> FileDiagnostics {
-        DiagnosticsCtxt::new(self, file).diagnostics()
+        let mut diagnostics = self.file_pass_diagnostics(file, DiagnosticPass::Syntax);
+        diagnostics.extend(self.file_pass_diagnostics(file, DiagnosticPass::Local));
+        diagnostics.extend(self.file_pass_diagnostics(file, DiagnosticPass::Project));
+        diagnostics
+    }
+
+    pub fn file_pass_diagnostics(&self, file: FileId, pass: DiagnosticPass) -> FileDiagnostics {
+        DiagnosticsCtxt::new(self, file).diagnostics(pass)
     }
 }
 
@@ -73,6 +105,112 @@ macro_rules! error_msg {
     (E0010) => {
         "value `{value}` is incompatible with type `{ty}` ({err})"
     };
+    (E0011) => {
+        "invalid field set: {err}"
+    };
+    (E0012) => {
+        "type `{typename}` has no field `{field}`"
+    };
+    (E0013) => {
+        "duplicate field definition `{name}`"
+    };
+    (E0014) => {
+        "type `{typename}` is missing field `{field}` of interface `{interface}`"
+    };
+    (E0015) => {
+        "unclosed `{{`, expected `}}`"
+    };
+    (E0016) => {
+        "expected at least one {expected} between the braces"
+    };
+    (E0017) => {
+        "unexpected trailing `{separator}`"
+    };
+    (E0018) => {
+        "expected {expected}"
+    };
+    (E0019) => {
+        "missing {token} after {after}"
+    };
+    (E0020) => {
+        "invalid UTF-8, replaced with `\u{FFFD}`"
+    };
+    (E0021) => {
+        "expected an object type, found {ty_desc} `{ty}`"
+    };
+    (E0022) => {
+        "duplicate union member `{name}`"
+    };
+    (E0023) => {
+        "duplicate enum value `{name}`"
+    };
+    (E0024) => {
+        "{kind} `{name}` must not start with `__`, which is reserved for introspection"
+    };
+    (E0025) => {
+        "duplicate argument `{name}`"
+    };
+    (E0026) => {
+        "extension of undefined type `{name}`"
+    };
+    (E0027) => {
+        "duplicate root operation type `{kind}`"
+    };
+    (E0028) => {
+        "duplicate schema definition"
+    };
+    (E0029) => {
+        "interface `{name}` cannot implement itself"
+    };
+    (E0030) => {
+        "union `{name}` cannot be a member of itself"
+    };
+    (E0031) => {
+        "`{typename}` must also implement `{interface}`, which `{implemented}` implements"
+    };
+    (E0032) => {
+        "field `{field}` of `{typename}` must have the type `{expected}` of interface `{interface}` or a subtype of it, found `{ty}`"
+    };
+    (E0033) => {
+        "field `{field}` is missing argument `{arg}` of interface `{interface}`"
+    };
+    (E0034) => {
+        "argument `{arg}` of field `{field}` must have the type `{expected}` of interface `{interface}`, found `{ty}`"
+    };
+    (E0035) => {
+        "argument `{arg}` of field `{field}` must be optional as interface `{interface}` doesn't define it"
+    };
+    (E0036) => {
+        "required {kind} `{name}` cannot be deprecated"
+    };
+    (E0037) => {
+        "input `{name}` references itself through non-null fields only: {path}"
+    };
+    (I0001) => {
+        "file is larger than {max} bytes, only its definitions are indexed"
+    };
+    (L0001) => {
+        "enum value `{name}` should be in SCREAMING_SNAKE_CASE: `{suggestion}`"
+    };
+    (L0002) => {
+        "type `{name}` should be in PascalCase: `{suggestion}`"
+    };
+    (L0003) => {
+        "field `{name}` should be in camelCase: `{suggestion}`"
+    };
+    // not a [`Lint`], it is reported by the server for projects with a published schema
+    (L0004) => {
+        "breaking change to the published schema: {change}"
+    };
+    (L0005) => {
+        "{kind} `{name}` is never used"
+    };
+    (L0006) => {
+        "scalar `{name}` is not in the custom scalar registry"
+    };
+    (L0007) => {
+        "the schema has no query type, {reason}"
+    };
     ($ident:ident) => {
         compile_error!("unknown error code")
     };
@@ -80,7 +218,11 @@ macro_rules! error_msg {
 
 impl ErrorCode {
     pub fn severity(self) -> Severity {
-        Severity::Error
+        match self {
+            ErrorCode::Error(_) => Severity::Error,
+            ErrorCode::Lint(_) => Severity::Warning,
+            ErrorCode::Info(_) => Severity::Info,
+        }
     }
 }
 
@@ -101,28 +243,125 @@ impl<'a> DiagnosticsCtxt<'a> {
         Self { snapshot, file, diagnostics: Default::default() }
     }
 
-    fn diagnostics(mut self) -> HashSet<Diagnostic> {
-        self.ir_diagnostics();
-        self.syntax();
-        self.empty_fields();
-        self.duplicate_definitions();
-        self.check_items();
+    fn diagnostics(mut self, pass: DiagnosticPass) -> HashSet<Diagnostic> {
+        match pass {
+            DiagnosticPass::Syntax => {
+                self.invalid_utf8();
+                self.shallow();
+                self.syntax();
+            }
+            DiagnosticPass::Local => {
+                self.ir_diagnostics();
+                self.check_items();
+                self.reserved_names();
+                self.duplicate_arguments();
+                self.lints();
+            }
+            DiagnosticPass::Project => {
+                self.empty_fields();
+                self.duplicate_definitions();
+                self.duplicate_fields();
+                self.conformance();
+                self.union_members();
+                self.duplicate_enum_values();
+                self.orphaned_extensions();
+                self.root_operation_types();
+                self.field_sets();
+                self.missing_query_type();
+                self.unused_definitions();
+                self.spec_validation();
+            }
+        }
+        if pass != DiagnosticPass::Syntax && self.is_generated(self.file) {
+            self.demote();
+        }
         self.diagnostics
     }
 
+    // a generated file isn't edited by hand, so its errors are only hints without any fixes
+    fn demote(&mut self) {
+        self.diagnostics = std::mem::take(&mut self.diagnostics)
+            .into_iter()
+            .map(|diagnostic| Diagnostic { severity: Severity::Hint, fixes: vec![], ..diagnostic })
+            .collect();
+    }
+
     fn diagnose(&mut self, diagnostic: Diagnostic) {
         self.diagnostics.insert(diagnostic);
     }
 
+    // the server decodes files lossily, so any invalid bytes show up as replacement characters,
+    // which are only reported in the files that had any as a file may well contain some of its own
+    fn invalid_utf8(&mut self) {
+        if !self.lossy_files().contains(&self.file) {
+            return;
+        }
+        let text = self.file_text(self.file);
+        let len = char::REPLACEMENT_CHARACTER.len_utf8();
+        for (row, line) in text.split('\n').enumerate() {
+            let mut replaced = line.match_indices(char::REPLACEMENT_CHARACTER).peekable();
+            while let Some((start, _)) = replaced.next() {
+                let mut end = start + len;
+                while replaced.next_if(|&(next, _)| next == end).is_some() {
+                    end += len;
+                }
+                let range = Range { start: Point::new(row, start), end: Point::new(row, end) };
+                self.diagnose(diagnostic!(E0020 @ range));
+            }
+        }
+    }
+
+    fn shallow(&mut self) {
+        if self.is_shallow(self.file) {
+            let start = Point::new(0, 0);
+            let range = Range { start, end: start };
+            self.diagnose(diagnostic!(I0001 @ range, max = self.max_file_size()));
+        }
+    }
+
     fn ir_diagnostics(&mut self) {
         let items = self.items(self.file);
         for (idx, _) in items.iter() {
             if let Some(body) = self.item_body(ItemRes::new(self.file, idx)) {
-                self.diagnostics.extend(body.diagnostics.iter().map(Into::into))
+                for diagnostic in &body.diagnostics {
+                    let diagnostic = self.ir_diagnostic(diagnostic);
+                    self.diagnose(diagnostic);
+                }
             }
         }
     }
 
+    fn ir_diagnostic(&self, diagnostic: &gqls_ir::Diagnostic) -> Diagnostic {
+        let mut converted = Diagnostic::from(diagnostic);
+        match &diagnostic.kind {
+            gqls_ir::DiagnosticKind::UnresolvedType(typename) =>
+                if let Some(similar) = self.similar_typename(typename) {
+                    converted.message.push_str(&format!(", did you mean `{similar}`?"));
+                    converted.fixes.push(Fix {
+                        title: format!("Replace with `{similar}`"),
+                        patch: Patch::new(converted.range, similar.to_owned()),
+                    });
+                },
+        }
+        converted
+    }
+
+    /// The type in the project (or builtin scalar) that `typename` is most likely a typo of
+    fn similar_typename(&self, typename: &str) -> Option<&'static str> {
+        let project_items = self.project_items(InProject::unit(self.file));
+        let typenames = project_items
+            .iter()
+            .flat_map(|(_, items)| {
+                items
+                    .iter()
+                    .filter(|(_, item)| matches!(item.kind, ItemKind::TypeDefinition(_)))
+                    .map(|(_, item)| item.name.as_str())
+                    .collect::<Vec<_>>()
+            })
+            .chain(BUILTIN_SCALARS);
+        similar_name(typename, typenames)
+    }
+
     fn empty_fields(&mut self) {
         for (file, items) in self.project_items(InProject::unit(self.file)).iter() {
             for (idx, item) in items.iter() {
@@ -132,8 +371,15 @@ impl<'a> DiagnosticsCtxt<'a> {
                     .as_ref()
                     .and_then(|body| body.fields())
                 {
-                    if fields.is_empty() {
-                        let kind = items[item.kind.into_type_definition()].kind.desc();
+                    // an extension may only add directives, and empty braces are reported as a
+                    // syntax error instead
+                    let typedef = &items[item.kind.into_type_definition()];
+                    if fields.is_empty()
+                        && !typedef.is_ext
+                        && !self.is_shallow(file)
+                        && !self.has_braces(file, item.range)
+                    {
+                        let kind = typedef.kind.desc();
                         self.diagnose(
                             diagnostic!(E0006 @ item.range, typedef_kind = kind, name = item.name),
                         );
@@ -149,7 +395,8 @@ impl<'a> DiagnosticsCtxt<'a> {
         let mut typedefs = HashMap::new();
 
         for (file, items) in project_items.iter() {
-            for (_, item) in items.iter() {
+            // incomplete definitions all share the same placeholder name
+            for (_, item) in items.iter().filter(|(_, item)| !item.name.is_missing()) {
                 let location = Location::new(file, item.name.range);
                 match item.kind {
                     ItemKind::TypeDefinition(typedef) => {
@@ -176,18 +423,46 @@ impl<'a> DiagnosticsCtxt<'a> {
         }
     }
 
+    // fields are reported where they are redefined, which may be an extension in another file
+    fn duplicate_fields(&mut self) {
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            if !matches!(item.kind, ItemKind::TypeDefinition(_)) {
+                continue;
+            }
+            let res = ItemRes::new(self.file, idx);
+            let name = InProject::new(self.file, item.name);
+            let typedef = match self.effective_typedef(name) {
+                Some(typedef) => typedef,
+                None => continue,
+            };
+            for &(duplicate, previous) in &typedef.duplicate_fields {
+                if duplicate.item != res {
+                    continue;
+                }
+                let name = self.field(duplicate).name;
+                let location = Location::new(previous.item.file, self.field(previous).name.range);
+                self.diagnose(diagnostic!(E0013 @ name.range, name = name; [
+                    location => format!("previous definition of field `{name}` here")
+                ]));
+            }
+        }
+    }
+
     fn check_items(&mut self) {
         let items = self.items(self.file);
         for (idx, item) in items.iter() {
             let typedef = match item.kind {
                 ItemKind::TypeDefinition(idx) => &items[idx],
-                ItemKind::DirectiveDefinition(_) => continue,
+                ItemKind::DirectiveDefinition(_) => {
+                    if let Some(body) = self.snapshot.item_body(ItemRes::new(self.file, idx)) {
+                        self.check_args(&body.as_directive().args);
+                    }
+                    continue;
+                }
             };
 
             self.check_directives(&typedef.directives);
-            if let Some(impls) = &typedef.implementations {
-                self.check_implementations(impls);
-            };
 
             if let Some(fields) = self
                 .snapshot
@@ -196,7 +471,7 @@ impl<'a> DiagnosticsCtxt<'a> {
                 .and_then(|body| body.fields())
             {
                 for (_, field) in fields.iter() {
-                    // TODO check default value
+                    self.check_default_value(field.default_value.as_ref(), field.ty.clone());
                     self.check_directives(&field.directives);
                     self.check_args(&field.args);
                     match typedef.kind {
@@ -212,24 +487,217 @@ impl<'a> DiagnosticsCtxt<'a> {
         }
     }
 
+    fn conformance(&mut self) {
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            if let ItemKind::TypeDefinition(typedef) = item.kind {
+                if let Some(impls) = &items[typedef].implementations {
+                    let is_interface = items[typedef].kind == TypeDefinitionKind::Interface;
+                    self.check_implementations(item.name, is_interface, impls);
+                }
+                self.check_interface_fields(ItemRes::new(self.file, idx));
+            }
+        }
+    }
+
+    // every field of the implemented interfaces must be defined by the type or its extensions
+    fn check_interface_fields(&mut self, res: ItemRes) {
+        let name = InProject::new(self.file, self.item(res).name);
+        let typedef = match self.effective_typedef(name) {
+            // only reported on the first part of the type
+            Some(typedef) if typedef.parts[0] == res => typedef,
+            _ => return,
+        };
+        // the fields of a shallowly indexed file are unknown
+        if typedef.parts.iter().any(|part| self.is_shallow(part.file)) {
+            return;
+        }
+        let fields =
+            typedef.fields.iter().map(|&field| self.field(field).name).collect::<HashSet<_>>();
+        for interface in &typedef.implementations {
+            let interface_typedef =
+                match self.effective_typedef(InProject::new(self.file, *interface)) {
+                    Some(typedef) if typedef.kind == TypeDefinitionKind::Interface => typedef,
+                    // reported by `check_implementations`
+                    _ => continue,
+                };
+            for &field_res in &interface_typedef.fields {
+                let field = self.field(field_res);
+                if fields.contains(&field.name) {
+                    continue;
+                }
+                let location = Location::new(field_res.item.file, field.name.range);
+                let diagnostic = diagnostic!(E0014 @ typedef.name.range, typename = typedef.name, field = field.name, interface = interface; [
+                    location => "defined here"
+                ]);
+                self.diagnose(diagnostic);
+            }
+        }
+    }
+
+    // members are reported where they are listed again, which may be an extension in another file
+    fn union_members(&mut self) {
+        let relisted = self.relisted_names(TypeDefinitionKind::Union, |body| match body {
+            ItemBodyKind::Union(body) => body.types.iter().map(Ty::name).collect(),
+            _ => vec![],
+        });
+        for (name, previous) in relisted {
+            self.diagnose(diagnostic!(E0022 @ name.range, name = name; [
+                previous => format!("previous listing of `{name}` here")
+            ]));
+        }
+
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            let body = match self.snapshot.item_body(ItemRes::new(self.file, idx)) {
+                Some(body) => body,
+                None => continue,
+            };
+            let members = match &body.kind {
+                ItemBodyKind::Union(body) => &body.types,
+                _ => continue,
+            };
+            for member in members {
+                if member.name() == item.name {
+                    self.diagnose(diagnostic!(E0030 @ member.range, name = item.name));
+                } else {
+                    self.check_union_member(member.clone());
+                }
+            }
+        }
+    }
+
+    // the names that are listed again in the bodies of the types of `kind` in this file, along
+    // with where they were first listed in the definition or any extension of the type
+    fn relisted_names(
+        &self,
+        kind: TypeDefinitionKind,
+        names: impl Fn(&ItemBodyKind) -> Vec<Name>,
+    ) -> Vec<(Name, Location)> {
+        let items = self.items(self.file);
+        let mut relisted = vec![];
+        for (idx, item) in items.iter() {
+            match item.kind {
+                ItemKind::TypeDefinition(typedef) if items[typedef].kind == kind => {}
+                _ => continue,
+            }
+            let res = ItemRes::new(self.file, idx);
+            let typedef = match self.effective_typedef(InProject::new(self.file, item.name)) {
+                Some(typedef) => typedef,
+                None => continue,
+            };
+            let mut listed = HashMap::new();
+            for &part in &typedef.parts {
+                let body = match self.snapshot.item_body(part) {
+                    Some(body) => body,
+                    None => continue,
+                };
+                for name in names(&body.kind) {
+                    match listed.get(&name) {
+                        Some(&previous) =>
+                            if part == res {
+                                relisted.push((name, previous));
+                            },
+                        None => {
+                            listed.insert(name, Location::new(part.file, name.range));
+                        }
+                    }
+                }
+            }
+        }
+        relisted
+    }
+
+    fn check_union_member(&mut self, member: Ty) {
+        let (range, name) = (member.range, member.name());
+        let definition = member.item_resolutions().first().copied();
+        let ty = self.lower_type(member);
+        match (&ty.kind, definition) {
+            // unresolved types are reported when the file is lowered
+            (TyKind::Object(_) | TyKind::Err, _) => {}
+            (_, Some(res)) => {
+                let location = Location::new(res.file, self.item(res).name.range);
+                self.diagnose(diagnostic!(E0021 @ range, ty_desc = ty.desc(), ty = name; [
+                    location => "not an object type"
+                ]));
+            }
+            (_, None) => self.diagnose(diagnostic!(E0021 @ range, ty_desc = ty.desc(), ty = name)),
+        }
+    }
+
+    // the field sets of the federation (and configured) directives may select fields from other
+    // files
+    fn field_sets(&mut self) {
+        for field_set in self.snapshot.field_sets(self.file) {
+            match field_set {
+                Ok(fields) =>
+                    for field in fields {
+                        if let Err(typename) = field.res {
+                            let diag = diagnostic!(E0012 @ field.range, typename = typename, field = field.name);
+                            self.diagnose(diag);
+                        }
+                    },
+                Err((range, err)) => self.diagnose(diagnostic!(E0011 @ range, err = err.message)),
+            }
+        }
+    }
+
     fn check_args(&mut self, args: &[Arg]) {
         for arg in args {
             self.check_directives(&arg.directives);
             self.check_input_ty(arg.ty.clone());
-            if let Some(default_value) = arg.default_value.clone() {
-                self.ensure_subtype(arg.range.into(), default_value, arg.ty.clone());
-            }
+            self.check_default_value(arg.default_value.as_ref(), arg.ty.clone());
+        }
+    }
+
+    fn check_default_value(&mut self, default_value: Option<&DefaultValue>, ty: Ty) {
+        if let Some(default_value) = default_value {
+            self.ensure_subtype(default_value.range.into(), default_value.value.clone(), ty);
         }
     }
 
+    // the values nested in an object or list are reported where they are written
     fn ensure_subtype(&mut self, range: Range, value: Value, ty: Ty) {
         let ty = self.lower_type(ty);
-        if let Err(err) = self.ensure_has_type(value.clone(), ty.clone()) {
-            let diag = diagnostic!(E0010 @ range, value = value, ty = ty, err = err);
-            self.diagnose(diag);
+        for mismatch in self.value_mismatches(value, ty) {
+            let range = self.mismatch_range(range, &mismatch);
+            let ValueMismatch { value, ty, mismatch: err, .. } = mismatch;
+            self.diagnose(diagnostic!(E0010 @ range, value = value, ty = ty, err = err));
         }
     }
 
+    /// The range of the value of `mismatch` within the value at `range`, or of the key of the
+    /// field that isn't a member of the object's type
+    fn mismatch_range(&self, range: Range, mismatch: &ValueMismatch) -> Range {
+        if let (TypeMismatch::ExtraneousField(name, _), Value::Object(obj)) =
+            (&mismatch.mismatch, &mismatch.value)
+        {
+            let key = obj.keys().find(|key| key.symbol() == *name);
+            return key.map_or(range, |key| key.range.into());
+        }
+        let data = self.file_data(self.file);
+        let root = data.tree.root_node();
+        let mut value = root.named_descendant_for_point_range(range.start, range.end);
+        for segment in &mismatch.path {
+            value = value.and_then(|value| match *segment {
+                PathSegment::Field(key) => root
+                    .named_descendant_for_range(key.range)?
+                    .parent()
+                    .filter(|field| field.kind() == NodeKind::OBJECT_FIELD)?
+                    .child_of_kind(NodeKind::VALUE),
+                PathSegment::Element(i) => {
+                    // the value node and its list have the same range
+                    let list = match value.kind() {
+                        NodeKind::VALUE => value.sole_named_child()?,
+                        _ => value,
+                    };
+                    list.children_of_kind(&mut list.walk(), NodeKind::VALUE).nth(i)
+                }
+            });
+        }
+        value.map_or(range, |value| value.range().into())
+    }
+
     fn check_input_ty(&mut self, ty: Ty) {
         let range = ty.range;
         let ty = self.lower_type(ty);
@@ -246,9 +714,19 @@ impl<'a> DiagnosticsCtxt<'a> {
         }
     }
 
-    fn check_implementations(&mut self, impls: &Implementations) {
+    fn check_implementations(
+        &mut self,
+        implementor: Name,
+        is_interface: bool,
+        impls: &Implementations,
+    ) {
         for name in impls {
-            let res = self.resolve_item(InProject::new(self.file, name.clone()));
+            // an object implementing itself is reported as not implementing an interface
+            if is_interface && *name == implementor {
+                self.diagnose(diagnostic!(E0029 @ name.range, name = name));
+                continue;
+            }
+            let res = self.resolve_item(InProject::new(self.file, *name));
             let ty = self.type_of_res(res.clone());
             match ty.kind {
                 TyKind::Boolean | TyKind::Float | TyKind::ID | TyKind::Int | TyKind::String =>
@@ -275,13 +753,172 @@ impl<'a> DiagnosticsCtxt<'a> {
 
     fn check_directives<'d>(&mut self, directives: impl IntoIterator<Item = &'d Directive>) {
         for directive in directives {
-            if self
-                .snapshot
-                .resolve_item(InProject::new(self.file, directive.name.clone()))
-                .is_err()
+            let name = InProject::new(self.file, directive.name);
+            if self.snapshot.resolve_item(name).is_err() {
+                self.diagnose(diagnostic!(E0002 @ directive.name.range, name = directive.name));
+                continue;
+            }
+            if let Some(res) = self.snapshot.resolve_directive(name) {
+                self.check_directive_arguments(directive, res);
+            }
+        }
+    }
+
+    // only the types of the given arguments are checked, not whether the required ones are present
+    fn check_directive_arguments(&mut self, directive: &Directive, res: ItemRes) {
+        let body = match self.snapshot.item_body(res) {
+            Some(body) => body,
+            None => return,
+        };
+        for argument in &directive.arguments {
+            let args = &body.as_directive().args;
+            if let Some(arg) = args.iter().find(|arg| arg.name == argument.name) {
+                let range = argument.value_range.into();
+                self.ensure_subtype(range, argument.value.clone(), arg.ty.clone());
+            }
+        }
+    }
+
+    /// The lints enabled by any of the projects of the file, with the overrides of the directories
+    /// containing the file applied from the outermost to the innermost. Generated files aren't
+    /// linted at all.
+    fn enabled_lints(&self) -> HashSet<Lint> {
+        if self.is_generated(self.file) {
+            return HashSet::new();
+        }
+        let mut lints = self
+            .projects_of(InProject::unit(self.file))
+            .iter()
+            .filter_map(|project| self.snapshot.lints.get(project))
+            .flatten()
+            .copied()
+            .collect();
+        for overrides in self.file_lint_overrides(self.file).iter() {
+            overrides.apply(&mut lints);
+        }
+        lints
+    }
+
+    fn lints(&mut self) {
+        let enabled = self.enabled_lints();
+        if enabled.is_empty() {
+            return;
+        }
+
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            let typedef = match item.kind {
+                ItemKind::TypeDefinition(idx) => &items[idx],
+                ItemKind::DirectiveDefinition(_) => continue,
+            };
+
+            // the name of an extension is not its own to choose
+            if enabled.contains(&Lint::TypeNameCase)
+                && !typedef.is_ext
+                && !item.name.is_missing()
+                && !is_pascal_case(&item.name)
             {
-                self.diagnose(diagnostic!(E0002 @ directive.name.range, name = directive.name))
+                let suggestion = to_pascal_case(&item.name);
+                self.diagnose(
+                    diagnostic!(L0002 @ item.name.range, name = item.name, suggestion = suggestion),
+                );
             }
+
+            if enabled.contains(&Lint::UnregisteredScalar)
+                && typedef.kind == TypeDefinitionKind::Scalar
+                && !typedef.is_ext
+                && !item.name.is_missing()
+            {
+                self.check_registered_scalar(item.name);
+            }
+
+            let body = match self.snapshot.item_body(ItemRes::new(self.file, idx)) {
+                Some(body) => body,
+                None => continue,
+            };
+
+            if enabled.contains(&Lint::FieldNameCase) {
+                for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                    if !is_camel_case(&field.name) {
+                        let suggestion = to_camel_case(&field.name);
+                        self.diagnose(
+                            diagnostic!(L0003 @ field.name.range, name = field.name, suggestion = suggestion),
+                        );
+                    }
+                }
+            }
+
+            if enabled.contains(&Lint::EnumValueCase) {
+                if let ItemBodyKind::Enum(enum_body) = &body.kind {
+                    for variant in &enum_body.variants {
+                        if !is_screaming_snake_case(&variant.name) {
+                            let suggestion = to_screaming_snake_case(&variant.name);
+                            self.diagnose(
+                                diagnostic!(L0001 @ variant.name.range, name = variant.name, suggestion = suggestion),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // projects without a registry have nothing to check their scalars against
+    fn check_registered_scalar(&mut self, name: Name) {
+        let registry = self.custom_scalars();
+        let registered = self
+            .projects_of(InProject::unit(self.file))
+            .iter()
+            .filter_map(|project| registry.get(project))
+            .flat_map(|scalars| scalars.keys().map(String::as_str))
+            .collect::<Vec<_>>();
+        if registered.is_empty() || registered.contains(&name.as_str()) {
+            return;
+        }
+        let mut diagnostic = diagnostic!(L0006 @ name.range, name = name);
+        if let Some(similar) = similar_name(&name, registered) {
+            diagnostic.message.push_str(&format!(", did you mean `{similar}`?"));
+        }
+        self.diagnose(diagnostic);
+    }
+
+    // a definition is used if its name is referenced anywhere in the project, root operation
+    // types, implementations of interfaces and federation entities are reachable regardless
+    fn unused_definitions(&mut self) {
+        if !self.enabled_lints().contains(&Lint::UnusedDefinition) {
+            return;
+        }
+
+        let used = self
+            .projects_of(InProject::unit(self.file))
+            .iter()
+            .map(|&project| self.used_names(project))
+            .collect::<Vec<_>>();
+        let text = self.file_text(self.file);
+        let items = self.items(self.file);
+        for (_, item) in items.iter() {
+            let kind = match item.kind {
+                ItemKind::TypeDefinition(idx) => {
+                    let typedef = &items[idx];
+                    let is_entity =
+                        typedef.directives.iter().any(|directive| directive.name.as_str() == "@key");
+                    if typedef.is_ext || typedef.implementations.is_some() || is_entity {
+                        continue;
+                    }
+                    typedef.kind.desc()
+                }
+                ItemKind::DirectiveDefinition(_) => "directive",
+            };
+            if item.name.is_missing() || used.iter().any(|used| used.contains(item.name.as_str())) {
+                continue;
+            }
+            let mut diagnostic =
+                diagnostic!(L0005 @ item.name.range, kind = kind, name = item.name);
+            diagnostic.fixes.push(Fix {
+                title: format!("Remove unused {kind} `{}`", item.name),
+                patch: Patch::new(deletion_range(&text, item.range.into()), String::new()),
+            });
+            self.diagnose(diagnostic);
         }
     }
 
@@ -292,16 +929,203 @@ impl<'a> DiagnosticsCtxt<'a> {
         let data = self.file_data(self.file);
         let tree = data.tree;
         cursor.set_match_limit(30);
-        let diags = cursor
+        let errors = cursor
             .captures(&QUERY, tree.root_node(), data.text.as_bytes())
             .flat_map(|(captures, _)| captures.captures)
-            .map(|capture| capture.node)
-            .chain(gqls_syntax::traverse_preorder(&tree).filter(|node| node.is_missing()))
-            .map(|node| diagnostic!(E0001 @ node.range()));
-        self.diagnostics.extend(diags);
+            .map(|capture| self.syntax_error(capture.node));
+        let missing = gqls_syntax::traverse_preorder(&tree)
+            .filter(|node| node.is_missing())
+            .map(|node| self.missing_node(node));
+        // empty braces are syntactically valid to keep the rest of the definition intact
+        let empty_braces = gqls_syntax::traverse_preorder(&tree)
+            .filter(|node| {
+                let is_braces = matches!(
+                    node.kind(),
+                    NodeKind::FIELDS_DEFINITION | NodeKind::INPUT_FIELDS_DEFINITION
+                );
+                is_braces
+                    && !node.has_error()
+                    && node.relevant_children(&mut node.walk()).next().is_none()
+            })
+            .map(|node| {
+                let expected = match node.kind() {
+                    NodeKind::INPUT_FIELDS_DEFINITION => "input field",
+                    _ => "field",
+                };
+                diagnostic!(E0016 @ node.range(), expected = expected)
+            });
+        // errors are often nested or contain missing nodes, only the first of any overlapping
+        // diagnostics is reported
+        let mut diags = errors.chain(missing).collect::<Vec<_>>();
+        diags.sort_by_key(|diag| (diag.range.start, diag.range.end));
+        let mut reported = Vec::<Diagnostic>::with_capacity(diags.len());
+        for diag in diags {
+            let overlaps = reported.last().map_or(false, |prev| {
+                diag.range.start < prev.range.end || diag.range.start == prev.range.start
+            });
+            if !overlaps {
+                reported.push(diag);
+            }
+        }
+        let empty_braces = empty_braces.collect::<Vec<_>>();
+        self.diagnostics.extend(reported.into_iter().chain(empty_braces));
+    }
+
+    // try to recognize the common mistakes within an error node, falling back to a generic error
+    fn syntax_error(&self, node: Node<'_>) -> Diagnostic {
+        let tokens = gqls_syntax::traverse_node_preorder(node)
+            .filter(|token| token.child_count() == 0 && !token.is_extra())
+            .collect::<Vec<_>>();
+        match tokens.iter().map(|token| token.kind()).collect::<Vec<_>>()[..] {
+            // enum values are the only braces that are required to be non-empty by the grammar
+            ["{", "}"] => {
+                let is_enum = |node: &Node<'_>| {
+                    matches!(
+                        node.kind(),
+                        NodeKind::ENUM_TYPE_DEFINITION | NodeKind::ENUM_TYPE_EXTENSION
+                    )
+                };
+                // the braces are either within the enum definition or follow it
+                let in_enum = node.parents().any(|parent| is_enum(&parent));
+                let after_enum =
+                    node.prev_named_sibling().and_then(|prev| prev.find_descendant(is_enum));
+                let expected =
+                    if in_enum || after_enum.is_some() { "enum value" } else { "field" };
+                return diagnostic!(E0016 @ node.range(), expected = expected);
+            }
+            [separator @ ("&" | "|")] =>
+                return diagnostic!(E0017 @ node.range(), separator = separator),
+            _ => {}
+        }
+
+        let mut open = vec![];
+        for token in &tokens {
+            match token.kind() {
+                "{" => open.push(*token),
+                "}" => {
+                    open.pop();
+                }
+                _ => {}
+            }
+        }
+        match open.first() {
+            Some(brace) => {
+                let end = node.end_position();
+                diagnostic!(E0015 @ brace.range(); [
+                    Location::new(self.file, end..end) => "expected `}` here"
+                ])
+            }
+            None => match expected_within(node) {
+                Some(expected) => diagnostic!(E0018 @ node.range(), expected = expected),
+                None => diagnostic!(E0001 @ node.range()),
+            },
+        }
+    }
+
+    fn missing_node(&self, node: Node<'_>) -> Diagnostic {
+        if node.kind() == "}" {
+            let parent = node.parent();
+            let open = parent.and_then(|parent| {
+                parent.children(&mut parent.walk()).find(|child| child.kind() == "{")
+            });
+            if let Some(open) = open {
+                return diagnostic!(E0015 @ open.range(); [
+                    Location::new(self.file, node.range()) => "expected `}` here"
+                ]);
+            }
+        }
+        // a missing token is most often a forgotten separator, e.g. the `:` between a field and its type
+        if !node.is_named() {
+            if let Some(prev) = node.prev_sibling().filter(|prev| !prev.is_missing()) {
+                let token = format!("`{}`", node.kind());
+                return diagnostic!(E0019 @ prev.range(), token = token, after = describe(prev));
+            }
+        }
+        let expected = if node.is_named() {
+            node.kind().replace('_', " ")
+        } else {
+            format!("`{}`", node.kind())
+        };
+        diagnostic!(E0018 @ node.range(), expected = expected)
+    }
+
+    /// Whether the definition at `range` in `file` has braces for its fields (which may be empty)
+    fn has_braces(&self, file: FileId, range: gqls_syntax::Range) -> bool {
+        let data = self.file_data(file);
+        let node = match data.tree.root_node().named_descendant_for_range(range) {
+            Some(node) => node,
+            None => return false,
+        };
+        [NodeKind::FIELDS_DEFINITION, NodeKind::INPUT_FIELDS_DEFINITION]
+            .into_iter()
+            .any(|kind| node.child_of_kind(kind).is_some())
     }
 }
 
+/// What the erroneous `node` should have been, based on the node it occurs within
+fn expected_within(node: Node<'_>) -> Option<&'static str> {
+    let expected = match node.parent()?.kind() {
+        NodeKind::FIELDS_DEFINITION => "field definition",
+        NodeKind::INPUT_FIELDS_DEFINITION => "input field definition",
+        NodeKind::ENUM_VALUES_DEFINITION => "enum value definition",
+        NodeKind::ARGUMENTS_DEFINITION => "argument definition",
+        NodeKind::ARGUMENTS => "argument",
+        NodeKind::DIRECTIVE_LOCATIONS => "directive location",
+        _ => return None,
+    };
+    Some(expected)
+}
+
+/// A description of `node` for use in a message, e.g. `field name`
+fn describe(node: Node<'_>) -> String {
+    let parent = node.parent();
+    match (node.kind(), parent.map(|parent| parent.kind())) {
+        (NodeKind::NAME, Some(NodeKind::FIELD_DEFINITION)) => "field name".to_owned(),
+        (NodeKind::NAME, Some(NodeKind::INPUT_VALUE_DEFINITION)) =>
+            match parent.and_then(|parent| parent.parent()).map(|node| node.kind()) {
+                Some(NodeKind::ARGUMENTS_DEFINITION) => "argument name".to_owned(),
+                _ => "input field name".to_owned(),
+            },
+        (NodeKind::NAME, Some(NodeKind::ARGUMENT)) => "argument name".to_owned(),
+        (kind, _) if node.is_named() => kind.replace('_', " "),
+        (kind, _) => format!("`{kind}`"),
+    }
+}
+
+/// The candidate closest to `name`, provided it is close enough for `name` to be a typo of it
+fn similar_name<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = name.len().max(3) / 3;
+    candidates
+        .into_iter()
+        .filter(|&candidate| candidate != name)
+        .filter_map(|candidate| {
+            // differing only in case is always a typo
+            if candidate.eq_ignore_ascii_case(name) {
+                return Some((0, candidate));
+            }
+            let distance = edit_distance(name, candidate);
+            (distance <= max_distance).then(|| (distance, candidate))
+        })
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// The levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 pub type Diagnostics = HashMap<FileId, FileDiagnostics>;
 pub type FileDiagnostics = HashSet<Diagnostic>;
 
@@ -312,11 +1136,23 @@ pub struct Diagnostic {
     pub message: String,
     pub severity: Severity,
     pub labels: Vec<DiagnosticLabel>,
+    /// quick fixes of the diagnostic, which apply to the file it is reported in
+    pub fixes: Vec<Fix>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Fix {
+    pub title: String,
+    pub patch: Patch,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Severity {
     Error,
+    Warning,
+    Info,
+    /// the semantic errors of generated files
+    Hint,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -336,7 +1172,7 @@ impl Diagnostic {
         message: String,
         labels: Vec<DiagnosticLabel>,
     ) -> Self {
-        Self { range, code, message, severity: code.severity(), labels }
+        Self { range, code, message, severity: code.severity(), labels, fixes: vec![] }
     }
 }
 
@@ -350,17 +1186,30 @@ impl<'a> From<&'_ gqls_ir::Diagnostic> for Diagnostic {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct ErrorCode(u16);
+pub enum ErrorCode {
+    /// `E` codes
+    Error(u16),
+    /// `L` codes, only reported if the lint is enabled
+    Lint(u16),
+    /// `I` codes
+    Info(u16),
+}
 
 impl ErrorCode {
     pub fn code(self) -> u16 {
-        self.0
+        match self {
+            ErrorCode::Error(code) | ErrorCode::Lint(code) | ErrorCode::Info(code) => code,
+        }
     }
 }
 
 impl Display for ErrorCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:04}", self.0)
+        match self {
+            ErrorCode::Error(code) => write!(f, "{:04}", code),
+            ErrorCode::Lint(code) => write!(f, "L{:04}", code),
+            ErrorCode::Info(code) => write!(f, "I{:04}", code),
+        }
     }
 }
 
@@ -368,10 +1217,18 @@ impl FromStr for ErrorCode {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        assert!(s.starts_with('E'));
-        Ok(Self(u16::from_str_radix(&s[1..], 10).expect("failed to parse error code")))
+        let code = u16::from_str_radix(&s[1..], 10).expect("failed to parse error code");
+        match &s[..1] {
+            "E" => Ok(Self::Error(code)),
+            "L" => Ok(Self::Lint(code)),
+            "I" => Ok(Self::Info(code)),
+            _ => panic!("unknown error code prefix `{s}`"),
+        }
     }
 }
 
+mod spec;
+mod validation;
+
 #[cfg(test)]
 mod tests;
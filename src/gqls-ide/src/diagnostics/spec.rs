@@ -0,0 +1,279 @@
+//! The rules of the type system (section 3 of the spec) that a schema resolves fine without, e.g.
+//! that a field implementing an interface field has a compatible type. Each rule has its own code
+//! and they are all checked by [`DiagnosticsCtxt::spec_validation`].
+
+use gqls_db::DefDatabase;
+use gqls_ir::{
+    Arg, Directive, EffectiveTypeDefinition, Field, InProject, ItemBodyKind, ItemKind, ItemRes, Name, Ty, TyKind, TypeDefinitionKind
+};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::DiagnosticsCtxt;
+use crate::Location;
+
+impl DiagnosticsCtxt<'_> {
+    pub(super) fn spec_validation(&mut self) {
+        self.transitive_interfaces();
+        self.interface_field_implementations();
+        self.deprecated_required_inputs();
+        self.input_cycles();
+    }
+
+    /// The types with a part in this file, the fields of shallowly indexed files are unknown so
+    /// the types with a part in one are skipped
+    fn typedefs_of_file(&self) -> Vec<(ItemRes, Arc<EffectiveTypeDefinition>)> {
+        let items = self.items(self.file);
+        let mut seen = HashSet::new();
+        items
+            .iter()
+            .filter(|(_, item)| matches!(item.kind, ItemKind::TypeDefinition(_)))
+            .filter(|(_, item)| seen.insert(item.name))
+            .filter_map(|(idx, item)| {
+                let typedef = self.effective_typedef(InProject::new(self.file, item.name))?;
+                let is_shallow = typedef.parts.iter().any(|part| self.is_shallow(part.file));
+                (!is_shallow).then(|| (ItemRes::new(self.file, idx), typedef))
+            })
+            .collect()
+    }
+
+    /// The interface of the given name, the others are reported by `check_implementations`
+    fn interface(&self, name: Name) -> Option<Arc<EffectiveTypeDefinition>> {
+        self.effective_typedef(InProject::new(self.file, name))
+            .filter(|typedef| typedef.kind == TypeDefinitionKind::Interface)
+    }
+
+    // the interfaces of the implemented interfaces must be declared too, reported on the first
+    // part of the type
+    fn transitive_interfaces(&mut self) {
+        for (res, typedef) in self.typedefs_of_file() {
+            if typedef.parts[0] != res {
+                continue;
+            }
+            let mut reported = HashSet::new();
+            for &interface in &typedef.implementations {
+                let implemented = match self.interface(interface) {
+                    Some(implemented) => implemented,
+                    None => continue,
+                };
+                for &transitive in &implemented.implementations {
+                    if transitive == typedef.name
+                        || typedef.implementations.contains(&transitive)
+                        || !reported.insert(transitive)
+                    {
+                        continue;
+                    }
+                    let location = Location::new(implemented.parts[0].file, implemented.name.range);
+                    self.diagnose(diagnostic!(E0031 @ typedef.name.range, typename = typedef.name, interface = transitive, implemented = interface; [
+                        location => format!("`{interface}` implements `{transitive}`")
+                    ]));
+                }
+            }
+        }
+    }
+
+    // the fields of this file that implement a field of an interface must have a compatible type
+    // and accept the same arguments
+    fn interface_field_implementations(&mut self) {
+        for (_, typedef) in self.typedefs_of_file() {
+            let interfaces = typedef
+                .implementations
+                .iter()
+                .filter_map(|&interface| self.interface(interface))
+                .collect::<Vec<_>>();
+            let file = self.file;
+            for &res in typedef.fields.iter().filter(|res| res.item.file == file) {
+                let field = self.field(res);
+                for interface in &interfaces {
+                    let expected = interface.fields.iter().find_map(|&expected| {
+                        let expected_field = self.field(expected);
+                        (expected_field.name == field.name).then(|| (expected, expected_field))
+                    });
+                    let (expected_res, expected) = match expected {
+                        Some(expected) => expected,
+                        None => continue,
+                    };
+                    if !self.is_valid_implementation_type(&field.ty, &expected.ty) {
+                        let location = Location::new(expected_res.item.file, expected.name.range);
+                        let ty = format!("{:?}", field.ty);
+                        let expected = format!("{:?}", expected.ty);
+                        self.diagnose(diagnostic!(E0032 @ field.ty.range, field = field.name, typename = typedef.name, expected = expected, interface = interface.name, ty = ty; [
+                            location => "defined here"
+                        ]));
+                    }
+                    self.check_implementation_args(&field, interface.name, &expected.args);
+                }
+            }
+        }
+    }
+
+    /// The arguments of `field` must be those of the interface field, additional arguments must
+    /// be optional
+    fn check_implementation_args(&mut self, field: &Field, interface: Name, expected: &[Arg]) {
+        let name = field.name;
+        for expected_arg in expected {
+            match field.args.iter().find(|arg| arg.name == expected_arg.name) {
+                Some(arg) =>
+                    if !is_same_type(&arg.ty, &expected_arg.ty) {
+                        let ty = format!("{:?}", arg.ty);
+                        let expected = format!("{:?}", expected_arg.ty);
+                        self.diagnose(diagnostic!(E0034 @ arg.ty.range, arg = arg.name, field = name, expected = expected, interface = interface, ty = ty));
+                    },
+                None => self.diagnose(
+                    diagnostic!(E0033 @ name.range, field = name, arg = expected_arg.name, interface = interface),
+                ),
+            }
+        }
+        for arg in &field.args {
+            if is_required(&arg.ty, arg.default_value.is_some())
+                && !expected.iter().any(|expected| expected.name == arg.name)
+            {
+                self.diagnose(diagnostic!(E0035 @ arg.name.range, arg = arg.name, field = name, interface = interface));
+            }
+        }
+    }
+
+    /// Whether a field of type `ty` can implement an interface field of type `expected`, i.e.
+    /// `ty` is the same type or a subtype of it
+    fn is_valid_implementation_type(&self, ty: &Ty, expected: &Ty) -> bool {
+        match (&ty.kind, &expected.kind) {
+            (TyKind::NonNull(ty), TyKind::NonNull(expected)) =>
+                self.is_valid_implementation_type(ty, expected),
+            (TyKind::NonNull(ty), _) => self.is_valid_implementation_type(ty, expected),
+            (_, TyKind::NonNull(_)) => false,
+            (TyKind::List(ty), TyKind::List(expected)) =>
+                self.is_valid_implementation_type(ty, expected),
+            (TyKind::List(_), _) | (_, TyKind::List(_)) => false,
+            (TyKind::Named(name, _), TyKind::Named(expected, _)) =>
+                name == expected || self.is_subtype(*name, *expected),
+            // unresolved types are reported already
+            (TyKind::Err(_), _) | (_, TyKind::Err(_)) => true,
+        }
+    }
+
+    /// Whether `name` is a member of the union `of` or implements the interface `of`
+    fn is_subtype(&self, name: Name, of: Name) -> bool {
+        let of = match self.effective_typedef(InProject::new(self.file, of)) {
+            Some(of) => of,
+            None => return false,
+        };
+        match of.kind {
+            TypeDefinitionKind::Union => of.members.iter().any(|member| member.name() == name),
+            TypeDefinitionKind::Interface => self
+                .effective_typedef(InProject::new(self.file, name))
+                .map_or(false, |typedef| typedef.implementations.contains(&of.name)),
+            _ => false,
+        }
+    }
+
+    // a required argument or input field must always be provided, so it can't be deprecated
+    fn deprecated_required_inputs(&mut self) {
+        let items = self.items(self.file);
+        for (idx, _) in items.iter() {
+            let body = match self.snapshot.item_body(ItemRes::new(self.file, idx)) {
+                Some(body) => body,
+                None => continue,
+            };
+            match &body.kind {
+                ItemBodyKind::DirectiveDefinition(body) =>
+                    for arg in &body.args {
+                        self.check_deprecated_required(arg);
+                    },
+                ItemBodyKind::InputObject(_) =>
+                    for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                        if is_required(&field.ty, field.default_value.is_some()) {
+                            self.check_deprecated("input field", field.name, &field.directives);
+                        }
+                    },
+                ItemBodyKind::Object(_) | ItemBodyKind::Interface(_) =>
+                    for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                        for arg in &field.args {
+                            self.check_deprecated_required(arg);
+                        }
+                    },
+                ItemBodyKind::Enum(_)
+                | ItemBodyKind::Union(_)
+                | ItemBodyKind::Scalar(_)
+                | ItemBodyKind::Todo => {}
+            }
+        }
+    }
+
+    fn check_deprecated_required(&mut self, arg: &Arg) {
+        if is_required(&arg.ty, arg.default_value.is_some()) {
+            self.check_deprecated("argument", arg.name, &arg.directives);
+        }
+    }
+
+    fn check_deprecated(&mut self, kind: &str, name: Name, directives: &[Directive]) {
+        let deprecated =
+            directives.iter().find(|directive| directive.name.as_str() == "@deprecated");
+        if let Some(deprecated) = deprecated {
+            self.diagnose(diagnostic!(E0036 @ deprecated.range, kind = kind, name = name));
+        }
+    }
+
+    // an input object can't reference itself through non-null fields only, as no value of it
+    // could ever be written, reported on the first part of the input object
+    fn input_cycles(&mut self) {
+        for (res, typedef) in self.typedefs_of_file() {
+            if typedef.parts[0] != res || typedef.kind != TypeDefinitionKind::Input {
+                continue;
+            }
+            let mut path = vec![];
+            if self.find_input_cycle(typedef.name, typedef.name, &mut HashSet::new(), &mut path) {
+                let path = path.iter().map(|step| format!("`{step}`")).collect::<Vec<_>>();
+                let path = path.join(" -> ");
+                self.diagnose(diagnostic!(E0037 @ typedef.name.range, name = typedef.name, path = path));
+            }
+        }
+    }
+
+    /// Whether `target` is reachable from the input object `current` through non-null fields,
+    /// `path` is left with the fields (e.g. `Foo.bar`) that lead to it
+    fn find_input_cycle(
+        &self,
+        target: Name,
+        current: Name,
+        visited: &mut HashSet<Name>,
+        path: &mut Vec<String>,
+    ) -> bool {
+        let typedef = match self.effective_typedef(InProject::new(self.file, current)) {
+            Some(typedef) if typedef.kind == TypeDefinitionKind::Input => typedef,
+            _ => return false,
+        };
+        for &res in &typedef.fields {
+            let field = self.field(res);
+            let name = match &field.ty.kind {
+                TyKind::NonNull(ty) => match &ty.kind {
+                    TyKind::Named(name, _) => *name,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            path.push(format!("{current}.{}", field.name));
+            if name == target
+                || (visited.insert(name) && self.find_input_cycle(target, name, visited, path))
+            {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+/// Whether an input value of type `ty` must be provided
+fn is_required(ty: &Ty, has_default: bool) -> bool {
+    matches!(ty.kind, TyKind::NonNull(_)) && !has_default
+}
+
+/// Whether `a` and `b` are the same type, regardless of where they are written
+fn is_same_type(a: &Ty, b: &Ty) -> bool {
+    match (&a.kind, &b.kind) {
+        (TyKind::NonNull(a), TyKind::NonNull(b)) | (TyKind::List(a), TyKind::List(b)) =>
+            is_same_type(a, b),
+        (TyKind::Named(a, _) | TyKind::Err(a), TyKind::Named(b, _) | TyKind::Err(b)) => a == b,
+        _ => false,
+    }
+}
@@ -4,7 +4,7 @@ use std::hash::Hash;
 use std::str::FromStr;
 
 use expect_test::{expect, Expect};
-use gqls_fixture::{Annotation, Fixture};
+use gqls_fixture::{Annotation, ExpectedDiagnostic, Fixture};
 use gqls_syntax::Point;
 
 use crate::diagnostics::ErrorCode;
@@ -30,11 +30,78 @@ fn test_rendered(gql: &str, expect: Expect) {
     expect.assert_eq(&rendered);
 }
 
+/// Compare the diagnostics of each file against the expected diagnostics (`#~~~ E0002: message`)
+/// of the fixture, every diagnostic must be expected
+#[track_caller]
+fn test_diagnostics(fixture: &Fixture) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let snapshot = ide.snapshot();
+    let mut files = fixture.diagnostics().collect::<Vec<_>>();
+    files.sort_by_key(|&(file, _)| file);
+    let mut mismatches = vec![];
+    for (file, expected) in files {
+        let mut expected = expected.iter().collect::<Vec<_>>();
+        let mut diagnostics = snapshot.file_diagnostics(file).into_iter().collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diag| diag.range.start);
+        for diagnostic in diagnostics {
+            let is_expected = |expected: &&ExpectedDiagnostic| {
+                Range::from(expected.range.clone()) == diagnostic.range
+                    && expected.code.parse::<ErrorCode>().unwrap() == diagnostic.code
+                    && expected
+                        .message
+                        .as_ref()
+                        .map_or(true, |message| *message == diagnostic.message)
+            };
+            match expected.iter().position(is_expected) {
+                Some(idx) => {
+                    expected.remove(idx);
+                }
+                None => mismatches.push(format!(
+                    "unexpected `{}` {:?} {}: {}",
+                    file.display(),
+                    diagnostic.range,
+                    prefixed(diagnostic.code),
+                    diagnostic.message
+                )),
+            }
+        }
+        for expected in expected {
+            let range = Range::from(expected.range.clone());
+            let message = expected.message.as_deref().map_or(String::new(), |m| format!(": {m}"));
+            mismatches.push(format!(
+                "missing `{}` {range:?} {}{message}",
+                file.display(),
+                expected.code
+            ));
+        }
+    }
+    assert!(mismatches.is_empty(), "mismatched diagnostics:\n{}", mismatches.join("\n"));
+}
+
+/// The code as it is written in the fixtures, e.g. `E0002` rather than `0002`
+fn prefixed(code: ErrorCode) -> String {
+    match code {
+        ErrorCode::Error(code) => format!("E{code:04}"),
+        ErrorCode::Lint(code) => format!("L{code:04}"),
+        ErrorCode::Info(code) => format!("I{code:04}"),
+    }
+}
+
 fn test_common<R>(fixture: &Fixture, f: impl Fn(&Diagnostic) -> R, g: impl Fn(&Annotation) -> R)
 where
     R: Hash + Eq + Debug,
 {
-    let ide = Ide::from_fixture_allow_errors(fixture);
+    test_common_with(Ide::from_fixture_allow_errors(fixture), fixture, f, g)
+}
+
+fn test_common_with<R>(
+    ide: Ide,
+    fixture: &Fixture,
+    f: impl Fn(&Diagnostic) -> R,
+    g: impl Fn(&Annotation) -> R,
+) where
+    R: Hash + Eq + Debug,
+{
     let snapshot = ide.snapshot();
     for (file, annotations) in fixture.annotations() {
         let diagnostics = snapshot.file_diagnostics(file);
@@ -72,6 +139,9 @@ fn render_diagnostics(gql: &str, diagnostics: impl IntoIterator<Item = Diagnosti
         }));
         diagnostic::Diagnostic::new(match diagnostic.severity {
             crate::Severity::Error => diagnostic::Severity::Error,
+            crate::Severity::Warning => diagnostic::Severity::Warning,
+            crate::Severity::Info => diagnostic::Severity::Note,
+            crate::Severity::Hint => diagnostic::Severity::Help,
         })
         .with_message(&diagnostic.message)
         .with_code(diagnostic.code.to_string())
@@ -125,20 +195,29 @@ fn render_diagnostics(gql: &str, diagnostics: impl IntoIterator<Item = Diagnosti
 
 #[test]
 fn test_display_error_code() {
-    assert_eq!(ErrorCode(2).to_string(), "0002");
-    assert_eq!(ErrorCode(42).to_string(), "0042");
+    assert_eq!(ErrorCode::Error(2).to_string(), "0002");
+    assert_eq!(ErrorCode::Error(42).to_string(), "0042");
+    assert_eq!(ErrorCode::Lint(3).to_string(), "L0003");
 }
 
 #[test]
 fn test_parse_error_code() {
-    assert_eq!(ErrorCode::from_str("E0002"), Ok(ErrorCode(2)));
-    assert_eq!(ErrorCode::from_str("E42"), Ok(ErrorCode(42)));
+    assert_eq!(ErrorCode::from_str("E0002"), Ok(ErrorCode::Error(2)));
+    assert_eq!(ErrorCode::from_str("E42"), Ok(ErrorCode::Error(42)));
+    assert_eq!(ErrorCode::from_str("L0001"), Ok(ErrorCode::Lint(1)));
 }
 
 mod duplicate;
 mod empty_fields;
+mod federation;
+mod files;
 mod impl_non_interface;
+mod interface_fields;
 mod io;
+mod lints;
+mod spec;
 mod syntax;
 mod typecheck;
+mod union_members;
 mod unresolved;
+mod validation;
@@ -1,5 +1,6 @@
 use super::*;
 use expect_test::expect;
+use gqls_fixture::fixture;
 
 #[test]
 fn test_duplicate_directive_definition() {
@@ -51,3 +52,73 @@ fn test_type_extension_no_duplicate_diagnostic() {
     ";
     test_rendered(gql, expect![[]])
 }
+
+#[test]
+fn test_duplicate_field_in_extension() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { id: ID! }
+        "
+        "bar" => "
+            extend type Foo { id: ID }
+                             #..(duplicate field definition `id`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_field_in_definition() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                id: ID!
+                id: ID
+               #..(duplicate field definition `id`)
+            }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_arguments() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                foo(a: Int, b: Int, a: ID): Int
+                                   #.(duplicate argument `a`)
+                bar(a: Int): Int
+            }
+
+            directive @d(x: Int, x: Int) on FIELD_DEFINITION
+                                #.(duplicate argument `x`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_argument_rendered() {
+    let gql = "
+        type Foo {
+            foo(
+                a: Int
+                a: ID
+            ): Int
+        }
+    ";
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0025]: duplicate argument `a`
+              ┌─ test.graphql:5:17
+              │
+            4 │                 a: Int
+              │                 - previous definition of argument `a` here
+            5 │                 a: ID
+              │                 ^
+
+        "#]],
+    )
+}
@@ -19,24 +19,61 @@ fn test_no_field() {
     test_rendered(
         gql,
         expect![[r#"
-            error[0006]: object `Foo` must define at least one field
-              ┌─ test.graphql:9:9
+            error[0016]: expected at least one field between the braces
+              ┌─ test.graphql:9:18
               │
             9 │         type Foo {}
-              │         ^^^^^^^^^^^
+              │                  ^^
 
-            error[0006]: interface `Bar` must define at least one field
-               ┌─ test.graphql:10:9
+            error[0016]: expected at least one field between the braces
+               ┌─ test.graphql:10:23
                │
             10 │         interface Bar {}
-               │         ^^^^^^^^^^^^^^^^
+               │                       ^^
 
-            error[0006]: input `FooInput` must define at least one field
-               ┌─ test.graphql:11:9
+            error[0016]: expected at least one input field between the braces
+               ┌─ test.graphql:11:24
                │
             11 │         input FooInput {}
-               │         ^^^^^^^^^^^^^^^^^
+               │                        ^^
 
         "#]],
     );
 }
+
+#[test]
+fn test_no_field_without_braces() {
+    let gql = "
+        type Foo
+        interface Bar
+    ";
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0006]: object `Foo` must define at least one field
+              ┌─ test.graphql:2:9
+              │
+            2 │         type Foo
+              │         ^^^^^^^^
+
+            error[0006]: interface `Bar` must define at least one field
+              ┌─ test.graphql:3:9
+              │
+            3 │         interface Bar
+              │         ^^^^^^^^^^^^^
+
+        "#]],
+    );
+}
+
+#[test]
+fn test_extension_without_fields() {
+    let gql = "
+        type Foo { foo: Int }
+        interface Bar { bar: Int }
+        extend type Foo @d
+        extend interface Bar @d
+        directive @d on OBJECT | INTERFACE
+    ";
+    test_rendered(gql, expect![[""]]);
+}
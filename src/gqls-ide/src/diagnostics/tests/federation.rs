@@ -0,0 +1,108 @@
+use gqls_fixture::fixture;
+use maplit::hashmap;
+
+use super::{test_common_with, test_error_message};
+use crate::{Changeset, FieldSetDirective, Ide};
+
+#[test]
+fn test_invalid_field_sets() {
+    let fixture = fixture! {
+        "foo" => r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") {
+                query: Query
+            }
+
+            type Query {
+                me: User @provides(fields: "name { first nickname }")
+                                                        #........(type `Name` has no field `nickname`)
+            }
+
+            type User @key(fields: "id organization { id name }") {
+                                                        #....(type `Organization` has no field `name`)
+                id: ID!
+                name: Name
+                organization: Organization
+                friends: [User!]! @requires(fields: "age")
+                                                    #...(type `User` has no field `age`)
+            }
+
+            type Name {
+                first: String
+            }
+
+            type Organization @key(fields: "id {") {
+                                               #.(invalid field set: expected at least one field)
+                id: ID!
+            }
+        "#
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_field_sets_are_not_checked_without_federation() {
+    let fixture = fixture! {
+        "foo" => r#"
+            type User @key(fields: "id") {
+                     #....(unresolved directive `@key`)
+                id: ID!
+            }
+        "#
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_field_sets_respect_link_imports() {
+    let fixture = fixture! {
+        "foo" => r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@requires"]) {
+                query: Query
+            }
+        "#
+        "bar" => r#"
+            type User @key(fields: "unchecked") {
+                id: ID!
+                friends: [User!]! @requires(fields: "age")
+                                                    #...(type `User` has no field `age`)
+            }
+        "#
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_configured_field_sets() {
+    let fixture = fixture! {
+        "foo" => r#"
+            directive @cacheKey(fields: String!) on OBJECT | FIELD_DEFINITION
+            directive @preload(select: String!) on FIELD_DEFINITION
+
+            type User @cacheKey(fields: "id nickname") {
+                                           #........(type `User` has no field `nickname`)
+                id: ID!
+                friends: [User!]! @cacheKey(fields: "age")
+                                                    #...(type `User` has no field `age`)
+                organization: Organization @preload(select: "name")
+                                                            #....(type `Organization` has no field `name`)
+            }
+
+            type Organization {
+                id: ID!
+            }
+        "#
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let directive = |name: &str, argument: &str, returned| FieldSetDirective {
+        name: name.to_owned(),
+        argument: argument.to_owned(),
+        returned,
+    };
+    let _ = ide.apply(Changeset::default().with_field_set_directives(hashmap! {
+        "default" => vec![
+            directive("@cacheKey", "fields", false),
+            directive("@preload", "select", true),
+        ]
+    }));
+    test_common_with(ide, &fixture, |diag| diag.message.clone(), |ann| ann.text.clone());
+}
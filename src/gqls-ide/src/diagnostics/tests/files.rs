@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+
+use gqls_db::DefDatabase;
+use maplit::{hashmap, hashset};
+
+use crate::{Change, Changeset, ErrorCode, GeneratedFiles, Ide, Lint, Point, Range, Severity};
+
+#[test]
+fn test_invalid_utf8() {
+    let text = "type Foo {\n  bar: Int # \u{FFFD}\u{FFFD} and \u{FFFD}\n}";
+    let (mut ide, file) = Ide::from_file(text);
+    let ranges = |ide: &Ide| {
+        ide.snapshot()
+            .file_diagnostics(file)
+            .into_iter()
+            .filter(|diag| diag.code == ErrorCode::Error(20))
+            .map(|diag| diag.range)
+            .collect::<HashSet<_>>()
+    };
+    // the replacement characters are the file's own
+    assert_eq!(ranges(&ide), hashset! {});
+
+    let _ = ide.apply(Change::set(file, text.to_owned()).with_lossy(true));
+    assert_eq!(
+        ranges(&ide),
+        hashset! {
+            Range { start: Point::new(1, 13), end: Point::new(1, 19) },
+            Range { start: Point::new(1, 24), end: Point::new(1, 27) },
+        }
+    );
+
+    let _ = ide.apply(Change::set(file, text.to_owned()));
+    assert_eq!(ranges(&ide), hashset! {});
+}
+
+#[test]
+fn test_large_file_is_indexed_shallowly() {
+    let (mut ide, file) = Ide::from_file("type Foo {\n  bar: Bar\n}\n");
+    let codes = |ide: &Ide| {
+        ide.snapshot().file_diagnostics(file).into_iter().map(|diag| diag.code).collect::<Vec<_>>()
+    };
+    assert_eq!(codes(&ide), vec![ErrorCode::Error(3)]);
+
+    // the fields aren't lowered so neither the unresolved type nor the empty type are reported
+    let _ = ide.apply(Changeset::default().with_max_file_size(8));
+    let diagnostics = ide.snapshot().file_diagnostics(file).into_iter().collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, ErrorCode::Info(1));
+    assert_eq!(diagnostics[0].severity, Severity::Info);
+    assert_eq!(
+        diagnostics[0].message,
+        "file is larger than 8 bytes, only its definitions are indexed"
+    );
+    assert!(ide.snapshot().items(file).iter().any(|(_, item)| item.name.as_str() == "Foo"));
+
+    let _ = ide.apply(Changeset::default().with_max_file_size(1024));
+    assert_eq!(codes(&ide), vec![ErrorCode::Error(3)]);
+}
+
+#[test]
+fn test_generated_file_is_not_linted() {
+    let (mut ide, file) = Ide::from_file("# @generated\ntype foo {\n  bar: Bar\n}\n");
+    let _ = ide.apply(Changeset::default().with_lints(hashmap! {
+        "default" => hashset! { Lint::TypeNameCase }
+    }));
+    let diagnostics = ide.snapshot().file_diagnostics(file).into_iter().collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, ErrorCode::Error(3));
+    assert_eq!(diagnostics[0].severity, Severity::Hint);
+}
+
+#[test]
+fn test_generated_file_by_path() {
+    let (mut ide, file) = Ide::from_file("type Foo {\n  bar: Bar\n}\n");
+    let severities = |ide: &Ide| {
+        let diagnostics = ide.snapshot().file_diagnostics(file);
+        diagnostics.into_iter().map(|diag| diag.severity).collect::<Vec<_>>()
+    };
+    assert_eq!(severities(&ide), vec![Severity::Error]);
+
+    let generated = GeneratedFiles { files: hashset! { file }, ..Default::default() };
+    let _ = ide.apply(Changeset::default().with_generated_files(generated));
+    assert_eq!(severities(&ide), vec![Severity::Hint]);
+}
@@ -42,3 +42,23 @@ fn test_object_implements_builtin_scalar() {
         "#]],
     );
 }
+
+#[test]
+fn test_interface_implements_itself() {
+    let gql = "
+        interface Node implements Node {
+            id: ID!
+        }
+    ";
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0029]: interface `Node` cannot implement itself
+              ┌─ test.graphql:2:35
+              │
+            2 │         interface Node implements Node {
+              │                                   ^^^^
+
+        "#]],
+    );
+}
@@ -0,0 +1,35 @@
+use gqls_fixture::fixture;
+
+use super::test_error_message;
+
+#[test]
+fn test_missing_interface_fields() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Node { id: ID! }
+            type Foo implements Node { id: ID! }
+            type Bar implements Node { name: String }
+                #...(type `Bar` is missing field `id` of interface `Node`)
+        "
+        "bar" => "
+            extend interface Node { name: String }
+            extend type Foo { name: String }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_interface_implemented_by_extension() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Node { id: ID! }
+            type Foo { name: String }
+                #...(type `Foo` is missing field `id` of interface `Node`)
+        "
+        "bar" => "
+            extend type Foo implements Node
+        "
+    };
+    test_error_message(&fixture);
+}
@@ -0,0 +1,304 @@
+use gqls_fixture::{fixture, Fixture};
+use maplit::{hashmap, hashset};
+use ropey::Rope;
+use std::path::PathBuf;
+
+use super::test_common_with;
+use crate::{range, Changeset, CustomScalar, Ide, Lint, LintOverrides, RootTypes};
+
+fn test(fixture: &Fixture, lints: &[Lint]) {
+    let mut ide = Ide::from_fixture_allow_errors(fixture);
+    let _ = ide.apply(Changeset::default().with_lints(hashmap! {
+        "default" => lints.iter().copied().collect()
+    }));
+    test_common_with(ide, fixture, |diag| diag.code, |annotation| annotation.text.parse().unwrap());
+}
+
+#[test]
+fn test_lints_are_opt_in() {
+    let fixture = fixture! {
+        "foo" => "
+            type foo {
+                BadField: Int
+            }
+
+            enum E { lower }
+        "
+    };
+    test(&fixture, &[]);
+}
+
+#[test]
+fn test_enum_value_case_lint() {
+    let fixture = fixture! {
+        "foo" => "
+            enum E {
+                GOOD_VALUE
+                badValue
+               #........(L0001)
+                Bad_Value
+               #.........(L0001)
+            }
+        "
+    };
+    test(&fixture, &[Lint::EnumValueCase]);
+}
+
+#[test]
+fn test_type_name_case_lint() {
+    let fixture = fixture! {
+        "foo" => "
+            type fooBar {
+                #......(L0002)
+                x: Int
+            }
+
+            extend type fooBar {
+                y: Int
+            }
+
+            input Foo_Input {
+                 #.........(L0002)
+                x: Int
+            }
+
+            scalar URL
+        "
+    };
+    test(&fixture, &[Lint::TypeNameCase]);
+}
+
+#[test]
+fn test_field_name_case_lint() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                goodField: Int
+                BadField: Int
+               #........(L0003)
+                bad_field: Int
+               #.........(L0003)
+                __typename: String
+               #..........(E0024)
+            }
+
+            input I {
+                Bad: Int
+               #...(L0003)
+            }
+        "
+    };
+    test(&fixture, &[Lint::FieldNameCase]);
+}
+
+#[test]
+fn test_lint_message() {
+    let fixture = fixture! {
+        "foo" => "
+            enum E {
+                badValue
+               #........(enum value `badValue` should be in SCREAMING_SNAKE_CASE: `BAD_VALUE`)
+            }
+        "
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let _ = ide.apply(Changeset::default().with_lints(hashmap! {
+        "default" => Lint::ALL.into_iter().collect()
+    }));
+    test_common_with(
+        ide,
+        &fixture,
+        |diag| diag.message.clone(),
+        |annotation| annotation.text.clone(),
+    );
+}
+
+#[test]
+fn test_unused_definition_lint() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @used on OBJECT
+            directive @unused on OBJECT
+                     #.......(L0005)
+
+            type Query @used { foo: Foo }
+            type Foo implements Node { id: ID! }
+            interface Node { id: ID! }
+            type Unused { a: Int }
+                #......(L0005)
+            extend type Unused { b: Int }
+            enum Unreferenced { A }
+                #............(L0005)
+        "
+    };
+    test(&fixture, &[Lint::UnusedDefinition]);
+}
+
+#[test]
+fn test_directory_lint_overrides() {
+    let fixture = fixture! {
+        "schema/foo" => "
+            type Query { foo: Int }
+            type Unused { a: Int }
+                #......(L0005)
+        "
+        "schema/generated/bar" => "
+            type Generated { a: Int }
+        "
+        "schema/generated/vendor/baz" => "
+            type Vendored { a: Int }
+                #........(L0005)
+        "
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let lints = hashset! { Lint::UnusedDefinition };
+    let disabled = LintOverrides { disabled: lints.clone(), ..Default::default() };
+    let enabled = LintOverrides { enabled: lints.clone(), ..Default::default() };
+    let _ = ide.apply(
+        Changeset::default()
+            .with_lints(hashmap! { "default" => lints })
+            .with_directory_lints(hashmap! {
+                PathBuf::from("schema/generated") => disabled,
+                PathBuf::from("schema/generated/vendor") => enabled,
+            }),
+    );
+    test_common_with(ide, &fixture, |diag| diag.code, |annotation| {
+        annotation.text.parse().unwrap()
+    });
+}
+
+#[test]
+fn test_unused_definition_fix() {
+    let gql = "type Query { a: Int }\n\n\
+        type Unused {\n  a: Int\n}\n\n\
+        type Other implements Node { a: Int }\n\n\
+        scalar Last\n";
+    let (mut ide, file) = Ide::from_file(gql);
+    let _ = ide.apply(Changeset::default().with_lints(hashmap! {
+        "default" => hashset! { Lint::UnusedDefinition }
+    }));
+    let snapshot = ide.snapshot();
+    let mut fixes = snapshot
+        .file_diagnostics(file)
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.fixes)
+        .map(|fix| {
+            let mut rope = Rope::from_str(gql);
+            let _ = fix.patch.apply(&mut rope);
+            (fix.title, rope.to_string())
+        })
+        .collect::<Vec<_>>();
+    fixes.sort();
+    assert_eq!(
+        fixes,
+        [
+            (
+                "Remove unused object `Unused`".to_owned(),
+                "type Query { a: Int }\n\ntype Other implements Node { a: Int }\n\nscalar Last\n"
+                    .to_owned(),
+            ),
+            (
+                "Remove unused scalar `Last`".to_owned(),
+                "type Query { a: Int }\n\ntype Unused {\n  a: Int\n}\n\n\
+                 type Other implements Node { a: Int }\n"
+                    .to_owned(),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unregistered_scalar_lint() {
+    let fixture = fixture! {
+        "foo" => r#"
+            scalar DateTime
+            scalar Datetime
+                  #........(scalar `Datetime` is not in the custom scalar registry, did you mean `DateTime`?)
+            scalar Url
+                  #...(scalar `Url` is not in the custom scalar registry)
+            extend scalar Url @specifiedBy(url: "https://url.spec.whatwg.org")
+        "#
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let _ = ide.apply(
+        Changeset::default()
+            .with_lints(hashmap! { "default" => hashset! { Lint::UnregisteredScalar } })
+            .with_custom_scalars(hashmap! {
+                "default" => hashmap! { "DateTime".to_owned() => CustomScalar::default() }
+            }),
+    );
+    test_common_with(
+        ide,
+        &fixture,
+        |diag| diag.message.clone(),
+        |annotation| annotation.text.clone(),
+    );
+}
+
+#[test]
+fn test_missing_query_type_lint() {
+    let root_types = || RootTypes { query: "RootQuery".to_owned(), ..Default::default() };
+    let diagnostics = |gql: &str, root_types: Option<RootTypes>| {
+        let (mut ide, file) = Ide::from_file(gql);
+        let mut changeset = Changeset::default().with_lints(hashmap! {
+            "default" => hashset! { Lint::MissingQueryType }
+        });
+        if let Some(root_types) = root_types {
+            changeset = changeset.with_root_types(hashmap! { "default" => root_types });
+        }
+        let _ = ide.apply(changeset);
+        ide.snapshot().file_diagnostics(file).into_iter().collect::<Vec<_>>()
+    };
+    let messages = |gql: &str, root_types: Option<RootTypes>| {
+        diagnostics(gql, root_types).into_iter().map(|diag| diag.message).collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        messages("type Foo { a: Int }", None),
+        ["the schema has no query type, neither a schema definition nor a `Query` type is defined"]
+    );
+    assert!(messages("type Query { a: Int }", None).is_empty());
+    assert!(messages("extend type Query { a: Int }", None).is_empty());
+    assert_eq!(
+        messages("type Query { a: Int }", Some(root_types())),
+        [
+            "the schema has no query type, neither a schema definition nor a `RootQuery` type is \
+             defined"
+        ]
+    );
+    assert!(messages("type RootQuery { a: Int }", Some(root_types())).is_empty());
+    assert!(messages("schema { query: Foo }\ntype Foo { a: Int }", None).is_empty());
+    assert_eq!(messages("schema { mutation: Foo }\ntype Foo { a: Int }", None), [
+        "the schema has no query type, the schema definition doesn't declare one"
+    ]);
+
+    // reported at the schema definition, or otherwise at the first definition
+    let schema = diagnostics("schema { mutation: Foo }\ntype Foo { a: Int }", None);
+    assert_eq!(schema[0].range, range!(0:0..0:24));
+    assert_eq!(diagnostics("\ntype Foo { a: Int }", None)[0].range, range!(1:5..1:8));
+}
+
+#[test]
+fn test_unused_definition_lint_with_configured_root_types() {
+    let fixture = fixture! {
+        "foo" => "
+            type RootQuery { a: Int }
+            type Query { a: Int }
+                #.....(L0005)
+        "
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let _ = ide.apply(
+        Changeset::default()
+            .with_lints(hashmap! { "default" => hashset! { Lint::UnusedDefinition } })
+            .with_root_types(hashmap! {
+                "default" => RootTypes { query: "RootQuery".to_owned(), ..Default::default() }
+            }),
+    );
+    test_common_with(
+        ide,
+        &fixture,
+        |diag| diag.code,
+        |annotation| annotation.text.parse().unwrap(),
+    );
+}
@@ -0,0 +1,110 @@
+use gqls_fixture::fixture;
+
+use super::test_diagnostics;
+
+#[test]
+fn test_transitive_interfaces() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Node { id: ID! }
+            interface Entity implements Node { id: ID! }
+            type Foo implements Entity { id: ID! }
+                #~~~ E0031: `Foo` must also implement `Node`, which `Entity` implements
+            type Bar implements Entity & Node { id: ID! }
+        "
+        "bar" => "
+            extend type Foo implements Node
+        "
+        "baz" => "
+            type Baz implements Entity { id: ID! }
+                #~~~ E0031: `Baz` must also implement `Node`, which `Entity` implements
+        "
+    };
+    test_diagnostics(&fixture);
+}
+
+#[test]
+fn test_interface_field_types() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Node { id: ID! parent: Node friends: [Node] }
+            union Thing = Foo
+            interface HasThing { thing: Thing }
+            type Foo implements Node & HasThing {
+                id: ID
+                   #~~ E0032: field `id` of `Foo` must have the type `ID!` of interface `Node` or a subtype of it, found `ID`
+                parent: Foo!
+                friends: [Foo!]!
+                thing: Foo
+            }
+            type Bar implements Node {
+                id: ID!
+                parent: String
+                       #~~~~~~ E0032: field `parent` of `Bar` must have the type `Node` of interface `Node` or a subtype of it, found `String`
+                friends: Node
+                        #~~~~ E0032: field `friends` of `Bar` must have the type `[Node]` of interface `Node` or a subtype of it, found `Node`
+            }
+        "
+    };
+    test_diagnostics(&fixture);
+}
+
+#[test]
+fn test_interface_field_arguments() {
+    let fixture = fixture! {
+        "foo" => "
+            interface Node { field(a: Int, b: String): Int }
+            type Foo implements Node {
+                field(
+               #~~~~~ E0033: field `field` is missing argument `b` of interface `Node`
+                    a: Int!
+                      #~~~~ E0034: argument `a` of field `field` must have the type `Int` of interface `Node`, found `Int!`
+                    c: Int!
+                   #~ E0035: argument `c` of field `field` must be optional as interface `Node` doesn't define it
+                    d: Int
+                    e: Int! = 1
+                ): Int
+            }
+            type Bar implements Node {
+                field(b: String, a: Int, c: Boolean): Int
+            }
+        "
+    };
+    test_diagnostics(&fixture);
+}
+
+#[test]
+fn test_deprecated_required_inputs() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @d(a: Int! @deprecated) on FIELD
+                                #~~~~~~~~~~~ E0036: required argument `a` cannot be deprecated
+            type Foo {
+                foo(a: Int @deprecated, b: Int! = 1 @deprecated): Int @deprecated
+            }
+            input Filter {
+                id: ID! @deprecated
+                       #~~~~~~~~~~~ E0036: required input field `id` cannot be deprecated
+                name: String @deprecated
+            }
+        "
+    };
+    test_diagnostics(&fixture);
+}
+
+#[test]
+fn test_input_cycles() {
+    let fixture = fixture! {
+        "foo" => "
+            input A { b: B! }
+                 #~ E0037: input `A` references itself through non-null fields only: `A.b` -> `B.a`
+            input C { c: C }
+            input D { d: [D!]! }
+        "
+        "bar" => "
+            input B { a: A! }
+                 #~ E0037: input `B` references itself through non-null fields only: `B.a` -> `A.b`
+        "
+    };
+    test_diagnostics(&fixture);
+}
@@ -1,6 +1,6 @@
 use gqls_fixture::fixture;
 
-use super::{test_error_code, test_error_message};
+use super::{test_diagnostics, test_error_code, test_error_message};
 
 #[test]
 fn test_syntax_diagnostics() {
@@ -34,3 +34,115 @@ fn test_syntax_diagnostics_by_message() {
     };
     test_error_message(&fixture);
 }
+
+#[test]
+fn test_missing_closing_brace() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                    #^(E0015)
+                foo: Int
+        "
+    };
+    test_error_code(&fixture);
+}
+
+#[test]
+fn test_empty_braces() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {}
+                    #^^(E0016)
+            input Bar {}
+                     #^^(E0016)
+            extend type Foo {}
+                           #^^(E0016)
+        "
+    };
+    test_error_code(&fixture);
+}
+
+#[test]
+fn test_empty_braces_message() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {}
+                    #^^(expected at least one field between the braces)
+            input Bar {}
+                     #^^(expected at least one input field between the braces)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_trailing_separator() {
+    let fixture = fixture! {
+        "foo" => "
+            type A { a: Int }
+            type B { b: Int }
+            union U = A | B |
+                           #^(E0017)
+        "
+    };
+    test_error_code(&fixture);
+}
+
+#[test]
+fn test_missing_colon_after_field_name() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                foo Int
+               #...(missing `:` after field name)
+            }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_expected_field_definition() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                foo: Int
+                123
+               #...(expected field definition)
+            }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_overlapping_syntax_errors_are_reported_once() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                foo Int
+               #...(E0019)
+                bar: Int
+            }
+            enum E {
+                A
+                1
+               #^(E0018)
+            }
+        "
+    };
+    test_error_code(&fixture);
+}
+
+#[test]
+fn test_escape_sequences() {
+    let fixture = fixture! {
+        "foo" => r#"
+            """
+            a \""" b
+            """
+            scalar Foo @specifiedBy(url: "a \"b\" \\ \u00e9")
+        "#
+    };
+    test_diagnostics(&fixture);
+}
@@ -21,10 +21,10 @@ fn test_incompatible_field_argument_scalar() {
         gql,
         expect![[r#"
             error[0010]: value `false` is incompatible with type `ID!` (cannot use boolean value as ID type)
-              ┌─ test.graphql:3:19
+              ┌─ test.graphql:3:29
               │
             3 │             badid(id: ID! = false): [Foo!]
-              │                   ^^^^^^^^^^^^^^^
+              │                             ^^^^^
 
             error[0003]: unresolved type `BadType`
               ┌─ test.graphql:6:55
@@ -33,16 +33,16 @@ fn test_incompatible_field_argument_scalar() {
               │                                                       ^^^^^^^
 
             error[0010]: value `0` is incompatible with type `Boolean!` (cannot use integer value as boolean type)
-              ┌─ test.graphql:9:21
+              ┌─ test.graphql:9:36
               │
             9 │             badbool(id: Boolean! = 0): [Foo!]
-              │                     ^^^^^^^^^^^^^^^^
+              │                                    ^
 
             error[0010]: value `234` is incompatible with type `String!` (cannot use integer value as string type)
-               ┌─ test.graphql:12:20
+               ┌─ test.graphql:12:34
                │
             12 │             badstr(id: String! = 234): [Foo!]
-               │                    ^^^^^^^^^^^^^^^^^
+               │                                  ^^^
 
         "#]],
     )
@@ -64,10 +64,10 @@ fn test_incompatible_field_argument_enum() {
         gql,
         expect![[r#"
             error[0010]: value `D` is incompatible with type `E!` (`D` is not a valid variant of enum `E`)
-              ┌─ test.graphql:8:15
+              ┌─ test.graphql:8:23
               │
             8 │             d(e: E! = D): [Foo!]
-              │               ^^^^^^^^^
+              │                       ^
 
         "#]],
     )
@@ -93,22 +93,75 @@ fn test_incompatible_field_argument_object() {
         gql,
         expect![[r#"
             error[0010]: value `{ bool: false }` is incompatible with type `Input!` (non-nullable field `id` must be provided)
-               ┌─ test.graphql:10:32
+               ┌─ test.graphql:10:48
                │
             10 │             badOmitNonNullable(input: Input! = { bool: false }): Boolean!
-               │                                ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+               │                                                ^^^^^^^^^^^^^^^
 
             error[0010]: value `{ id: "1", random: 1 }` is incompatible with type `Input!` (field `random` is not a member of type `Input`)
-               ┌─ test.graphql:11:27
+               ┌─ test.graphql:11:54
                │
             11 │             badExtraField(input: Input! = { id: "1", random: 1 }): Boolean!
-               │                           ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+               │                                                      ^^^^^^
 
-            error[0010]: value `{ id: { random: 3 } }` is incompatible with type `Input!` (cannot use object value as ID type)
-               ┌─ test.graphql:12:32
+            error[0010]: value `{ random: 3 }` is incompatible with type `ID!` (cannot use object value as ID type)
+               ┌─ test.graphql:12:54
                │
             12 │             incorrectFieldType(input: Input! = { id: { random: 3 } }): Boolean!
-               │                                ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+               │                                                      ^^^^^^^^^^^^^
+
+        "#]],
+    )
+}
+
+#[test]
+fn test_incompatible_nested_object_fields() {
+    let gql = r#"
+        input Filter {
+            name: String!
+            limit: Int = 10
+            nested: Nested
+        }
+
+        input Nested { flag: Boolean! }
+
+        type Foo {
+            a(filter: Filter = { name: "x", limit: "ten", bogus: 1, nested: { flag: 1 } }): Int
+            b(filters: [Filter!] = [{ nested: {} }]): Int
+        }
+    "#;
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0010]: value `"ten"` is incompatible with type `Int` (cannot use string value as int type)
+               ┌─ test.graphql:11:52
+               │
+            11 │             a(filter: Filter = { name: "x", limit: "ten", bogus: 1, nested: { flag: 1 } }): Int
+               │                                                    ^^^^^
+
+            error[0010]: value `{ name: "x", limit: "ten", bogus: 1, nested: { flag: 1 } }` is incompatible with type `Filter` (field `bogus` is not a member of type `Filter`)
+               ┌─ test.graphql:11:59
+               │
+            11 │             a(filter: Filter = { name: "x", limit: "ten", bogus: 1, nested: { flag: 1 } }): Int
+               │                                                           ^^^^^
+
+            error[0010]: value `1` is incompatible with type `Boolean!` (cannot use integer value as boolean type)
+               ┌─ test.graphql:11:85
+               │
+            11 │             a(filter: Filter = { name: "x", limit: "ten", bogus: 1, nested: { flag: 1 } }): Int
+               │                                                                                     ^
+
+            error[0010]: value `{ nested: {  } }` is incompatible with type `Filter!` (non-nullable field `name` must be provided)
+               ┌─ test.graphql:12:37
+               │
+            12 │             b(filters: [Filter!] = [{ nested: {} }]): Int
+               │                                     ^^^^^^^^^^^^^^
+
+            error[0010]: value `{  }` is incompatible with type `Nested` (non-nullable field `flag` must be provided)
+               ┌─ test.graphql:12:47
+               │
+            12 │             b(filters: [Filter!] = [{ nested: {} }]): Int
+               │                                               ^^
 
         "#]],
     )
@@ -128,17 +181,157 @@ fn test_incompatible_field_argument_list() {
     test_rendered(
         gql,
         expect![[r#"
-            error[0010]: value `[null]` is incompatible with type `[Int!]` (expected non-nullable value)
-              ┌─ test.graphql:6:17
+            error[0010]: value `null` is incompatible with type `Int!` (expected non-nullable value)
+              ┌─ test.graphql:6:30
               │
             6 │             bad(e: [Int!] = [null]): [Foo!]
-              │                 ^^^^^^^^^^^^^^^^^^
+              │                              ^^^^
 
-            error[0010]: value `[1, false, 3]` is incompatible with type `[Int!]` (cannot use boolean value as int type)
-              ┌─ test.graphql:7:25
+            error[0010]: value `false` is incompatible with type `Int!` (cannot use boolean value as int type)
+              ┌─ test.graphql:7:41
               │
             7 │             invalidType(e: [Int!] = [1, false, 3]): [Foo!]
-              │                         ^^^^^^^^^^^^^^^^^^^^^^^^^
+              │                                         ^^^^^
+
+        "#]],
+    )
+}
+
+#[test]
+fn test_incompatible_input_field_default() {
+    let gql = r#"
+        enum E { A B }
+
+        input Input {
+            count: Int = "hi"
+            ratio: Float = 1
+            flags: [Boolean!] = true
+            es: [E!]! = [A, B, C]
+            nested: Nested = { required: null }
+        }
+
+        input Nested {
+            required: Int!
+            withDefault: Int! = 0
+        }
+    "#;
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0010]: value `"hi"` is incompatible with type `Int` (cannot use string value as int type)
+              ┌─ test.graphql:5:26
+              │
+            5 │             count: Int = "hi"
+              │                          ^^^^
+
+            error[0010]: value `C` is incompatible with type `E!` (`C` is not a valid variant of enum `E`)
+              ┌─ test.graphql:8:32
+              │
+            8 │             es: [E!]! = [A, B, C]
+              │                                ^
+
+            error[0010]: value `null` is incompatible with type `Int!` (expected non-nullable value)
+              ┌─ test.graphql:9:42
+              │
+            9 │             nested: Nested = { required: null }
+              │                                          ^^^^
+
+        "#]],
+    )
+}
+
+#[test]
+fn test_default_value_coercion() {
+    let gql = r#"
+        scalar DateTime
+
+        type Foo {
+            a(at: DateTime = "2022-01-01"): Int
+            b(at: [DateTime!] = 0): Int
+            c(ids: [ID!] = 1): Int
+            d(ids: [ID!] = [1, "2"]): Int
+        }
+    "#;
+    test_rendered(gql, expect![[]])
+}
+
+#[test]
+fn test_incompatible_directive_argument_default() {
+    let gql = r#"
+        directive @d(a: Int = "1", b: [String!] = "ok") on FIELD_DEFINITION
+    "#;
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0010]: value `"1"` is incompatible with type `Int` (cannot use string value as int type)
+              ┌─ test.graphql:2:31
+              │
+            2 │         directive @d(a: Int = "1", b: [String!] = "ok") on FIELD_DEFINITION
+              │                               ^^^
+
+        "#]],
+    )
+}
+
+#[test]
+fn test_incompatible_directive_argument() {
+    let gql = r#"
+        scalar Url @specifiedBy(url: "https://example.com")
+
+        type Foo {
+            a: Int @deprecated(reason: 1)
+            b: Int @deprecated(reason: "use c")
+            c: Int @deprecated
+        }
+    "#;
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0010]: value `1` is incompatible with type `String` (cannot use integer value as string type)
+              ┌─ test.graphql:5:40
+              │
+            5 │             a: Int @deprecated(reason: 1)
+              │                                        ^
+
+        "#]],
+    )
+}
+
+#[test]
+fn test_int_out_of_range() {
+    let gql = r#"
+        scalar BigInt
+
+        input Foo {
+            a: Int = 2147483647
+            b: Int = 2147483648
+            c: Float = 2147483648
+            d: [Int!] = [-2147483648, -2147483649]
+            e: Float = 99999999999999999999
+            f: BigInt = -99999999999999999999
+            g: [Int] = [1, 99999999999999999999]
+        }
+    "#;
+    test_rendered(
+        gql,
+        expect![[r#"
+            error[0010]: value `2147483648` is incompatible with type `Int` (out of range of a 32-bit signed integer)
+              ┌─ test.graphql:6:22
+              │
+            6 │             b: Int = 2147483648
+              │                      ^^^^^^^^^^
+
+            error[0010]: value `-2147483649` is incompatible with type `Int!` (out of range of a 32-bit signed integer)
+              ┌─ test.graphql:8:39
+              │
+            8 │             d: [Int!] = [-2147483648, -2147483649]
+              │                                       ^^^^^^^^^^^
+
+            error[0010]: value `99999999999999999999` is incompatible with type `Int` (out of range of a 32-bit signed integer)
+               ┌─ test.graphql:11:28
+               │
+            11 │             g: [Int] = [1, 99999999999999999999]
+               │                            ^^^^^^^^^^^^^^^^^^^^
 
         "#]],
     )
@@ -0,0 +1,61 @@
+use gqls_fixture::fixture;
+
+use super::test_error_message;
+
+#[test]
+fn test_union_member_not_an_object() {
+    let fixture = fixture! {
+        "foo" => "
+            interface I { i: Int }
+            scalar S
+            union V = Foo
+            type Foo { foo: Int }
+
+            union U =
+              | Foo
+              | I
+               #.(expected an object type, found interface `I`)
+              | S
+               #.(expected an object type, found scalar `S`)
+              | V
+               #.(expected an object type, found union `V`)
+              | Int
+               #...(expected an object type, found int `Int`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_union_members() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { foo: Int }
+            type Bar { bar: Int }
+            union U = Foo | Bar | Foo
+                                 #...(duplicate union member `Foo`)
+        "
+        "bar" => "
+            extend union U = Bar
+                            #...(duplicate union member `Bar`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_union_member_of_itself() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { foo: Int }
+            union U = Foo | U
+                           #.(union `U` cannot be a member of itself)
+        "
+        "bar" => "
+            union V = Foo
+            extend union V = V
+                            #.(union `V` cannot be a member of itself)
+        "
+    };
+    test_error_message(&fixture);
+}
@@ -1,7 +1,10 @@
 use expect_test::expect;
 use gqls_fixture::fixture;
 
-use super::{test_error_code, test_error_message, test_rendered};
+use maplit::hashset;
+
+use super::{test_common_with, test_diagnostics, test_error_code, test_error_message, test_rendered};
+use crate::{Changeset, ErrorCode, Ide};
 
 #[test]
 fn test_unresolved_directives() {
@@ -30,6 +33,23 @@ fn test_unresolved_directives() {
     test_error_code(&fixture);
 }
 
+#[test]
+fn test_builtin_directives_resolve() {
+    let fixture = fixture! {
+        "foo" => r#"
+            scalar Url @specifiedBy(url: "https://url.spec.whatwg.org")
+
+            type Foo {
+                bar: Int @deprecated
+                baz: Int @deprecated(reason: "use bar") @skip(if: true) @include(if: false)
+                qux: Int @cached
+                        #.......(E0002)
+            }
+        "#
+    };
+    test_error_code(&fixture);
+}
+
 #[test]
 fn test_unresolved_type_in_field() {
     let fixture = fixture! {
@@ -70,3 +90,89 @@ fn test_unresolved_interface_in_implements_clause() {
     "#]],
     );
 }
+
+#[test]
+fn test_unresolved_type_suggests_similar_name() {
+    let fixture = fixture! {
+        "foo" => "
+            type Users { id: ID! }
+            type Query {
+                users: [Usrs!]!
+                       #....(unresolved type `Usrs`, did you mean `Users`?)
+                user(id: Id!): Users
+                        #..(unresolved type `Id`, did you mean `ID`?)
+                lower: users
+                      #.....(unresolved type `users`, did you mean `Users`?)
+                other: Unrelated
+                      #.........(unresolved type `Unrelated`)
+            }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_unresolved_type_fix() {
+    let (ide, file) = Ide::from_file("type Users { id: ID! } type Query { users: [Usrs!]! }");
+    let snapshot = ide.snapshot();
+    let fixes = snapshot
+        .file_diagnostics(file)
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.fixes)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            Fix {
+                title: "Replace with `Users`",
+                patch: Patch {
+                    range: 0:44..0:48,
+                    with: "Users",
+                },
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&fixes);
+}
+
+#[test]
+fn test_unimported_types_are_unresolved() {
+    let fixture = fixture! {
+        "foo" => r#"
+# import Bar from "types/bar"
+# import * from "types/qux"
+type Foo {
+    bar: Bar
+    baz: Baz
+        #...(E0003)
+    qux: Qux
+}
+"#
+        "types/bar" => "type Bar { id: ID! } type Baz { id: ID! }"
+        "types/qux" => "type Qux { id: ID! }"
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let _ = ide.apply(Changeset::default().with_import_projects(hashset! { "default" }));
+    test_common_with(
+        ide,
+        &fixture,
+        |diag| diag.code,
+        |annotation| annotation.text.parse::<ErrorCode>().unwrap(),
+    );
+}
+
+#[test]
+fn test_unresolved_expected_diagnostics() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                bar: Bar
+                    #~~~ E0003: unresolved type `Bar`
+                baz: [Baz!]!
+                     #~~~ E0003
+                qux: Int @qux
+                        #~~~~ E0002
+            }
+        "
+    };
+    test_diagnostics(&fixture);
+}
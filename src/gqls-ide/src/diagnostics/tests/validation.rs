@@ -0,0 +1,236 @@
+use expect_test::expect;
+use gqls_fixture::fixture;
+use std::path::Path;
+
+use super::test_error_message;
+use crate::Ide;
+
+#[test]
+fn test_duplicate_enum_values() {
+    let fixture = fixture! {
+        "foo" => "
+            enum E { A B A }
+                        #.(duplicate enum value `A`)
+        "
+        "bar" => "
+            extend enum E { B C }
+                           #.(duplicate enum value `B`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_distinct_enums_may_share_values() {
+    let fixture = fixture! {
+        "foo" => "
+            enum E { A B }
+            enum F { A B }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_reserved_names() {
+    let fixture = fixture! {
+        "foo" => "
+            type __Foo {
+                #.....(type `__Foo` must not start with `__`, which is reserved for introspection)
+                __foo(
+               #.....(field `__foo` must not start with `__`, which is reserved for introspection)
+                    __arg: Int
+                   #.....(argument `__arg` must not start with `__`, which is reserved for introspection)
+                ): Int
+                _bar: Int
+            }
+
+            enum E { __A B }
+                    #...(enum value `__A` must not start with `__`, which is reserved for introspection)
+
+            directive @__d on FIELD_DEFINITION
+                     #....(directive `@__d` must not start with `__`, which is reserved for introspection)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_orphaned_extensions() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { a: Int }
+        "
+        "bar" => "
+            extend type Foo { b: Int }
+            extend type Bar { b: Int }
+                       #...(extension of undefined type `Bar`)
+            extend enum E { A }
+                       #.(extension of undefined type `E`)
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_orphaned_extension_fix() {
+    let (ide, file) = Ide::from_file("extend type Foo { id: ID! }");
+    let snapshot = ide.snapshot();
+    let fixes = snapshot
+        .file_diagnostics(file)
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.fixes)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            Fix {
+                title: "Convert into a definition of `Foo`",
+                patch: Patch {
+                    range: 0:0..0:7,
+                    with: "",
+                },
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&fixes);
+}
+
+#[test]
+fn test_orphaned_directive_extension_fix() {
+    let (ide, file) = Ide::from_file("directive @d on OBJECT\nextend type Foo @d");
+    let snapshot = ide.snapshot();
+    let fixes = snapshot
+        .file_diagnostics(file)
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.fixes)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            Fix {
+                title: "Convert into a definition of `Foo`",
+                patch: Patch {
+                    range: 1:0..1:18,
+                    with: "type Foo @d {\n  _: Boolean\n}",
+                },
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&fixes);
+}
+
+#[test]
+fn test_federated_extensions_may_be_orphaned() {
+    // the definitions are owned by other subgraphs
+    let fixture = fixture! {
+        "foo" => r#"
+            extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") {
+                query: Query
+            }
+
+            extend type Query {
+                me: User
+            }
+
+            extend type User @key(fields: "id") {
+                id: ID!
+            }
+        "#
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_root_operation_types() {
+    let fixture = fixture! {
+        "foo" => "
+            schema {
+                query: Query
+                mutation: Query
+                query: Root
+                      #....(duplicate root operation type `query`)
+            }
+
+            type Query { foo: Int }
+            type Root { foo: Int }
+        "
+        "bar" => "
+            extend schema {
+                mutation: Root
+                         #....(duplicate root operation type `mutation`)
+                subscription: Root
+            }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_root_operation_types_must_be_objects() {
+    let fixture = fixture! {
+        "foo" => "
+            schema {
+                query: Query
+                mutation: I
+                         #.(expected an object type, found interface `I`)
+                subscription: S
+                             #.(expected an object type, found scalar `S`)
+            }
+
+            type Query { foo: Int }
+            interface I { foo: Int }
+            scalar S
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_duplicate_schema_definitions() {
+    let fixture = fixture! {
+        "foo" => "
+            schema { query: Query }
+           #.......................(duplicate schema definition)
+            extend schema { subscription: Query }
+
+            type Query { foo: Int }
+        "
+        "bar" => "
+            schema { mutation: Mutation }
+
+            type Mutation { foo: Int }
+        "
+    };
+    test_error_message(&fixture);
+}
+
+#[test]
+fn test_root_operation_type_conflicts_across_extensions() {
+    let fixture = fixture! {
+        "foo" => "
+schema { query: Query }
+
+type Query { foo: Int }
+"
+        "bar" => "
+extend schema { query: Root }
+
+type Root { foo: Int }
+"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let labels = ide
+        .snapshot()
+        .file_diagnostics(Path::new("bar"))
+        .into_iter()
+        .flat_map(|diagnostic| diagnostic.labels)
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            DiagnosticLabel {
+                location: foo:1:16..1:21,
+                message: "previous definition of `query` here",
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&labels);
+}
@@ -0,0 +1,291 @@
+//! Checks of the rules of the type system in the spec that aren't covered by resolution and
+//! typechecking
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{
+    Arg, InProject, ItemBodyKind, ItemKind, ItemRes, Name, OperationKind, TypeDefinitionKind
+};
+use std::collections::HashMap;
+
+use super::{DiagnosticsCtxt, Fix};
+use crate::schema::BUILTIN_SCALARS;
+use crate::{Lint, Location, Patch, Point, Range, FEDERATION_PRELUDE_PATH};
+
+impl DiagnosticsCtxt<'_> {
+    // values are reported where they are defined again, which may be an extension in another file
+    pub(super) fn duplicate_enum_values(&mut self) {
+        let relisted = self.relisted_names(TypeDefinitionKind::Enum, |body| match body {
+            ItemBodyKind::Enum(body) => body.variants.iter().map(|variant| variant.name).collect(),
+            _ => vec![],
+        });
+        for (name, previous) in relisted {
+            self.diagnose(diagnostic!(E0023 @ name.range, name = name; [
+                previous => format!("previous definition of `{name}` here")
+            ]));
+        }
+    }
+
+    // the query type is declared by the schema definition (and its extensions), without one it is
+    // the object of the (configured) name. Its absence is reported once per project, at the schema
+    // definition or otherwise at the first definition of the first file that has any.
+    pub(super) fn missing_query_type(&mut self) {
+        if !self.enabled_lints().contains(&Lint::MissingQueryType) {
+            return;
+        }
+        let project_items = self.project_items(InProject::unit(self.file));
+        let mut files = project_items
+            .keys()
+            .copied()
+            .filter(|&file| file != FEDERATION_PRELUDE_PATH.as_path())
+            .collect::<Vec<_>>();
+        files.sort();
+
+        let mut schemas = files
+            .iter()
+            .flat_map(|&file| project_items[&file].schemas.iter().map(move |schema| (file, schema)))
+            .collect::<Vec<_>>();
+        schemas.sort_by_key(|(_, schema)| schema.is_ext);
+        if let Some(&(file, schema)) = schemas.first() {
+            let mut operations = schemas.iter().flat_map(|(_, schema)| &schema.operations);
+            if file == self.file && operations.all(|op| op.kind != OperationKind::Query) {
+                let reason = "the schema definition doesn't declare one";
+                self.diagnose(diagnostic!(L0007 @ schema.range, reason = reason));
+            }
+            return;
+        }
+
+        let root_types = self.root_types_of(InProject::unit(self.file));
+        let is_defined = project_items.values().any(|items| {
+            items.iter().any(|(_, item)| match item.kind {
+                ItemKind::TypeDefinition(idx) =>
+                    item.name.as_str() == root_types.query
+                        && items[idx].kind == TypeDefinitionKind::Object,
+                ItemKind::DirectiveDefinition(_) => false,
+            })
+        });
+        if is_defined {
+            return;
+        }
+        let first = files.iter().find_map(|&file| {
+            let items = project_items[&file].iter().map(|(_, item)| item.name.range);
+            items.min_by_key(|range| range.start_byte).map(|range| (file, range))
+        });
+        if let Some((file, range)) = first {
+            if file == self.file {
+                let reason = format!(
+                    "neither a schema definition nor a `{}` type is defined",
+                    root_types.query
+                );
+                self.diagnose(diagnostic!(L0007 @ range, reason = reason));
+            }
+        }
+    }
+
+    // the type an extension extends must be defined, except in federated projects where the
+    // definition may be owned by another subgraph
+    pub(super) fn orphaned_extensions(&mut self) {
+        if self.is_federated(self.file) {
+            return;
+        }
+        let defined = self
+            .projects_of(InProject::unit(self.file))
+            .iter()
+            .map(|&project| self.defined_typenames(project))
+            .collect::<Vec<_>>();
+        let is_defined = |name: &str| {
+            BUILTIN_SCALARS.contains(&name) || defined.iter().any(|defined| defined.contains(name))
+        };
+
+        let text = self.file_text(self.file);
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            match item.kind {
+                ItemKind::TypeDefinition(typedef) if items[typedef].is_ext => {}
+                _ => continue,
+            }
+            if item.name.is_missing() || is_defined(item.name.as_str()) {
+                continue;
+            }
+            let mut diagnostic = diagnostic!(E0026 @ item.name.range, name = item.name);
+            if let Some(range) = extend_keyword_range(&text, item.range.start_point) {
+                // an extension that only adds directives has no fields, which a definition of
+                // this kind must have, so a placeholder field is added
+                let has_no_fields = self
+                    .snapshot
+                    .item_body(ItemRes::new(self.file, idx))
+                    .as_ref()
+                    .and_then(|body| body.fields())
+                    .map_or(false, |fields| fields.is_empty())
+                    && !self.has_braces(self.file, item.range);
+                let patch = if has_no_fields {
+                    let definition = text[item.range.start_byte..item.range.end_byte]
+                        .trim_start_matches("extend")
+                        .trim_start();
+                    let range = Range { start: range.start, end: item.range.end_point };
+                    Patch::new(range, format!("{definition} {{\n  _: Boolean\n}}"))
+                } else {
+                    Patch::new(range, String::new())
+                };
+                diagnostic.fixes.push(Fix {
+                    title: format!("Convert into a definition of `{}`", item.name),
+                    patch,
+                });
+            }
+            self.diagnose(diagnostic);
+        }
+    }
+
+    // a project has at most one schema definition, which together with its extensions defines
+    // each operation type at most once, and the root types must be objects
+    pub(super) fn root_operation_types(&mut self) {
+        let project_items = self.project_items(InProject::unit(self.file));
+        let mut files = project_items.keys().copied().collect::<Vec<_>>();
+        files.sort();
+        // the definition comes first, the extensions follow in file order
+        let mut schemas = files
+            .iter()
+            .flat_map(|&file| project_items[&file].schemas.iter().map(move |schema| (file, schema)))
+            .collect::<Vec<_>>();
+        schemas.sort_by_key(|(_, schema)| schema.is_ext);
+
+        let mut definition = None;
+        let mut defined = HashMap::new();
+        for (file, schema) in schemas {
+            if !schema.is_ext {
+                match definition {
+                    Some(previous) =>
+                        if file == self.file {
+                            self.diagnose(diagnostic!(E0028 @ schema.range; [
+                                previous => "previous schema definition here"
+                            ]));
+                        },
+                    None => definition = Some(Location::new(file, schema.range)),
+                }
+            }
+            for operation in &schema.operations {
+                let (kind, ty) = (operation.kind, operation.ty);
+                match defined.get(&kind) {
+                    Some(&previous) =>
+                        if file == self.file {
+                            self.diagnose(diagnostic!(E0027 @ ty.range, kind = kind; [
+                                previous => format!("previous definition of `{kind}` here")
+                            ]));
+                        },
+                    None => {
+                        defined.insert(kind, Location::new(file, ty.range));
+                    }
+                }
+                if file == self.file {
+                    self.check_root_operation_type(ty);
+                }
+            }
+        }
+    }
+
+    fn check_root_operation_type(&mut self, ty: Name) {
+        // only the types that resolve are checked
+        let typedef = match self.effective_typedef(InProject::new(self.file, ty)) {
+            Some(typedef) => typedef,
+            None => return,
+        };
+        if typedef.kind == TypeDefinitionKind::Object {
+            return;
+        }
+        let res = typedef.parts[0];
+        let location = Location::new(res.file, self.item(res).name.range);
+        self.diagnose(diagnostic!(E0021 @ ty.range, ty_desc = typedef.kind.desc(), ty = ty; [
+            location => "not an object type"
+        ]));
+    }
+
+    pub(super) fn duplicate_arguments(&mut self) {
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            let body = match self.snapshot.item_body(ItemRes::new(self.file, idx)) {
+                Some(body) => body,
+                None => continue,
+            };
+            match item.kind {
+                ItemKind::DirectiveDefinition(_) =>
+                    self.check_duplicate_arguments(&body.as_directive().args),
+                ItemKind::TypeDefinition(_) =>
+                    for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                        self.check_duplicate_arguments(&field.args);
+                    },
+            }
+        }
+    }
+
+    fn check_duplicate_arguments(&mut self, args: &[Arg]) {
+        let mut defined = HashMap::new();
+        for arg in args {
+            let name = arg.name;
+            match defined.get(&name) {
+                Some(&previous) => {
+                    let location = Location::new(self.file, previous);
+                    self.diagnose(diagnostic!(E0025 @ name.range, name = name; [
+                        location => format!("previous definition of argument `{name}` here")
+                    ]));
+                }
+                None => {
+                    defined.insert(name, name.range);
+                }
+            }
+        }
+    }
+
+    // names starting with `__` are reserved for the introspection system
+    pub(super) fn reserved_names(&mut self) {
+        let items = self.items(self.file);
+        for (idx, item) in items.iter() {
+            let body = self.snapshot.item_body(ItemRes::new(self.file, idx));
+            match item.kind {
+                ItemKind::DirectiveDefinition(_) => {
+                    self.check_reserved_name("directive", item.name);
+                    if let Some(body) = &body {
+                        self.check_reserved_arg_names(&body.as_directive().args);
+                    }
+                }
+                ItemKind::TypeDefinition(typedef) => {
+                    // the name of an extension is reported on the definition it extends
+                    if !items[typedef].is_ext {
+                        self.check_reserved_name("type", item.name);
+                    }
+                    let body = match &body {
+                        Some(body) => body,
+                        None => continue,
+                    };
+                    for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                        self.check_reserved_name("field", field.name);
+                        self.check_reserved_arg_names(&field.args);
+                    }
+                    if let ItemBodyKind::Enum(body) = &body.kind {
+                        for variant in &body.variants {
+                            self.check_reserved_name("enum value", variant.name);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_reserved_arg_names(&mut self, args: &[Arg]) {
+        for arg in args {
+            self.check_reserved_name("argument", arg.name);
+        }
+    }
+
+    fn check_reserved_name(&mut self, kind: &str, name: Name) {
+        if name.trim_start_matches('@').starts_with("__") {
+            self.diagnose(diagnostic!(E0024 @ name.range, kind = kind, name = name));
+        }
+    }
+}
+
+/// The range of the `extend` keyword at `start` along with the whitespace that follows it
+fn extend_keyword_range(text: &str, start: Point) -> Option<Range> {
+    let line = text.lines().nth(start.row)?;
+    let rest = line.get(start.column..)?.strip_prefix("extend")?;
+    let len = line.len() - start.column - rest.trim_start().len();
+    Some(Range { start, end: Point::new(start.row, start.column + len) })
+}
@@ -1,19 +1,33 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
+use std::path::PathBuf;
 
-use gqls_db::Project;
+use gqls_db::{CustomScalars, GeneratedFiles, Project, RootTypes};
 pub use tree_sitter::Point;
 
 use ropey::Rope;
+use serde::Serialize;
 use vfs::FileId;
 
+use crate::{FieldSetDirective, Lint, LintOverrides};
+
 /// Similar to [`tree_sitter::Range`] but only containing points (but no byte offsets)
-#[derive(Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord, Default)]
+#[derive(Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord, Default, Serialize)]
 pub struct Range {
+    #[serde(with = "PointDef")]
     pub start: Point,
+    #[serde(with = "PointDef")]
     pub end: Point,
 }
 
+// the columns are byte offsets
+#[derive(Serialize)]
+#[serde(remote = "Point")]
+struct PointDef {
+    row: usize,
+    column: usize,
+}
+
 impl Debug for Range {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}..{}:{}", self.start.row, self.start.column, self.end.row, self.end.column)
@@ -63,7 +77,50 @@ impl FilePatches {
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, PartialOrd, Ord, Default)]
+/// The patches of an edit that may span several files, grouped by file
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct SourceChange {
+    pub files: Vec<FilePatches>,
+}
+
+impl SourceChange {
+    pub fn new(files: Vec<FilePatches>) -> Self {
+        Self { files }
+    }
+
+    pub fn single(file: FileId, patches: Vec<Patch>) -> Self {
+        Self::new(vec![FilePatches::new(file, patches)])
+    }
+
+    /// Add `patch` to the patches of `file`
+    pub fn insert(&mut self, file: FileId, patch: Patch) {
+        match self.files.iter_mut().find(|patches| patches.file == file) {
+            Some(patches) => patches.patches.push(patch),
+            None => self.files.push(FilePatches::new(file, vec![patch])),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.iter().all(|patches| patches.patches.is_empty())
+    }
+}
+
+impl From<FilePatches> for SourceChange {
+    fn from(patches: FilePatches) -> Self {
+        Self::new(vec![patches])
+    }
+}
+
+impl IntoIterator for SourceChange {
+    type Item = FilePatches;
+    type IntoIter = std::vec::IntoIter<FilePatches>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, PartialOrd, Ord, Hash, Default)]
 pub struct Patch {
     pub range: Range,
     pub with: String,
@@ -79,9 +136,11 @@ impl Patch {
         let Patch { range, with } = self;
         let start_byte = rope.line_to_byte(range.start.row) + range.start.column;
         let old_end_byte = rope.line_to_byte(range.end.row) + range.end.column;
-        rope.remove(start_byte..old_end_byte);
-        rope.insert(start_byte, with);
-        let new_end_byte = rope.char_to_byte(start_byte + with.len());
+        // the rope is indexed by chars whereas the columns of the range are bytes
+        let start_char = rope.byte_to_char(start_byte);
+        rope.remove(start_char..rope.byte_to_char(old_end_byte));
+        rope.insert(start_char, with);
+        let new_end_byte = start_byte + with.len();
         let new_end_line = rope.byte_to_line(new_end_byte);
         let new_end_position =
             Point { row: new_end_line, column: new_end_byte - rope.line_to_byte(new_end_line) };
@@ -96,16 +155,90 @@ impl Patch {
     }
 }
 
+/// Move the lines of `text` after the first from column `from` to `indent`, so that the
+/// indentation relative to the first line is preserved. Blank lines are left empty.
+pub(crate) fn reindent(text: &str, from: usize, indent: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let whitespace = line.len() - line.trim_start().len();
+            match i {
+                0 => line.to_owned(),
+                _ if line.trim().is_empty() => String::new(),
+                _ => format!("{indent}{}", &line[whitespace.min(from)..]),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The lines of `range` along with the blank lines after them (or before them if nothing
+/// follows), so that deleting it leaves no gap. Only `range` itself if it shares a line.
+pub(crate) fn deletion_range(text: &str, range: Range) -> Range {
+    let lines = text.lines().collect::<Vec<_>>();
+    // a range may end on the line after the last (i.e. after the final newline)
+    let line = |row: usize| lines.get(row).copied().unwrap_or_default();
+    let is_blank = |row: usize| matches!(lines.get(row), Some(line) if line.trim().is_empty());
+    let before = line(range.start.row).get(..range.start.column);
+    let after = line(range.end.row).get(range.end.column..);
+    match (before, after) {
+        (Some(before), Some(after)) if before.trim().is_empty() && after.trim().is_empty() => {}
+        _ => return range,
+    }
+
+    let mut start = range.start.row;
+    let mut end = range.end.row + 1;
+    while is_blank(end) {
+        end += 1;
+    }
+    if end < lines.len() {
+        return Range { start: Point::new(start, 0), end: Point::new(end, 0) };
+    }
+
+    while start > 0 && is_blank(start - 1) {
+        start -= 1;
+    }
+    let start = match start.checked_sub(1) {
+        Some(row) => Point::new(row, line(row).len()),
+        None => Point::new(0, 0),
+    };
+    match lines.len().checked_sub(1) {
+        Some(last) => Range { start, end: Point::new(last, lines[last].len()) },
+        None => range,
+    }
+}
+
 #[must_use]
 #[derive(Default, Debug, Eq, PartialEq, Clone)]
 pub struct Changeset {
     pub(crate) projects: Option<HashMap<Project, HashSet<FileId>>>,
+    pub(crate) lints: Option<HashMap<Project, HashSet<Lint>>>,
+    pub(crate) directory_lints: Option<HashMap<PathBuf, LintOverrides>>,
+    pub(crate) field_set_directives: Option<HashMap<Project, Vec<FieldSetDirective>>>,
+    pub(crate) project_groups: Option<Vec<HashSet<Project>>>,
+    pub(crate) import_projects: Option<HashSet<Project>>,
+    pub(crate) max_file_size: Option<usize>,
+    pub(crate) custom_scalars: Option<HashMap<Project, CustomScalars>>,
+    pub(crate) root_types: Option<HashMap<Project, RootTypes>>,
+    pub(crate) generated_files: Option<GeneratedFiles>,
     pub(crate) changes: Vec<Change>,
 }
 
 impl Changeset {
     pub fn new(changes: Vec<Change>) -> Self {
-        Self { changes, projects: None }
+        Self {
+            changes,
+            projects: None,
+            lints: None,
+            directory_lints: None,
+            field_set_directives: None,
+            project_groups: None,
+            import_projects: None,
+            max_file_size: None,
+            custom_scalars: None,
+            root_types: None,
+            generated_files: None,
+        }
     }
 
     pub fn single(change: Change) -> Self {
@@ -117,6 +250,70 @@ impl Changeset {
         self
     }
 
+    /// Set the lints that are enabled for each project (replaces any previously enabled lints)
+    pub fn with_lints(mut self, lints: HashMap<Project, HashSet<Lint>>) -> Self {
+        self.lints = Some(lints);
+        self
+    }
+
+    /// Set the lint overrides of each directory, which apply to the files below it on top of the
+    /// lints of their projects (replaces any previously set overrides)
+    pub fn with_directory_lints(mut self, lints: HashMap<PathBuf, LintOverrides>) -> Self {
+        self.directory_lints = Some(lints);
+        self
+    }
+
+    /// Set the directives whose string arguments are field sets for each project, in addition to
+    /// the federation directives (replaces any previously set directives)
+    pub fn with_field_set_directives(
+        mut self,
+        directives: HashMap<Project, Vec<FieldSetDirective>>,
+    ) -> Self {
+        self.field_set_directives = Some(directives);
+        self
+    }
+
+    /// Set the groups of projects whose names resolve across each other's files, see
+    /// [`gqls_db::SourceDatabase::project_groups`]
+    pub fn with_project_groups(mut self, groups: Vec<HashSet<Project>>) -> Self {
+        self.project_groups = Some(groups);
+        self
+    }
+
+    /// Set the projects in which a file that has graphql-import comments (e.g.
+    /// `# import Foo from "foo.graphql"`) only sees the names it imports
+    pub fn with_import_projects(mut self, projects: HashSet<Project>) -> Self {
+        self.import_projects = Some(projects);
+        self
+    }
+
+    /// Set the size in bytes beyond which a file is indexed shallowly, i.e. only its definitions
+    /// are indexed and not their fields, arguments, values or members
+    pub fn with_max_file_size(mut self, size: usize) -> Self {
+        self.max_file_size = Some(size);
+        self
+    }
+
+    /// Set the registry of the custom scalars of each project, which describes the scalars in
+    /// hovers and is checked by the `unregistered-scalar` lint (replaces any previous registry)
+    pub fn with_custom_scalars(mut self, scalars: HashMap<Project, CustomScalars>) -> Self {
+        self.custom_scalars = Some(scalars);
+        self
+    }
+
+    /// Set the names of the root operation types of each project that doesn't define them with a
+    /// schema definition (replaces any previously set names)
+    pub fn with_root_types(mut self, root_types: HashMap<Project, RootTypes>) -> Self {
+        self.root_types = Some(root_types);
+        self
+    }
+
+    /// Set how generated files are recognized, see [`GeneratedFiles`]
+    pub fn with_generated_files(mut self, generated: GeneratedFiles) -> Self {
+        self.generated_files = Some(generated);
+        self
+    }
+
     pub fn with_change(mut self, change: Change) -> Self {
         self.changes.push(change);
         self
@@ -133,11 +330,13 @@ impl From<Change> for Changeset {
 pub struct Change {
     pub file: FileId,
     pub kind: ChangeKind,
+    /// whether the text that is set had invalid utf8 replaced, which is reported
+    pub lossy: bool,
 }
 
 impl Change {
     pub fn new(file: FileId, kind: ChangeKind) -> Self {
-        Self { file, kind }
+        Self { file, kind, lossy: false }
     }
 
     pub fn set(file: FileId, text: String) -> Self {
@@ -147,6 +346,11 @@ impl Change {
     pub fn patch(file: FileId, patch: Patch) -> Self {
         Self::new(file, ChangeKind::Patch(patch))
     }
+
+    pub fn with_lossy(mut self, lossy: bool) -> Self {
+        self.lossy = lossy;
+        self
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -1,4 +1,4 @@
-use crate::edit::RangeExt;
+use crate::edit::{deletion_range, RangeExt};
 use crate::{point, range};
 use ropey::Rope;
 
@@ -79,6 +79,24 @@ fn test_apply_patch() {
     assert_eq!(rope.to_string(), "the quick fox\njumps\nnothing");
 }
 
+#[test]
+fn test_apply_patch_with_multibyte_chars() {
+    let mut rope = Rope::from_str("\"é\" type Foo");
+    // the columns are bytes, `é` is 2 bytes
+    assert_eq!(
+        patch!(0:10..0:13 => "Bär").apply(&mut rope),
+        tree_sitter::InputEdit {
+            start_byte: 10,
+            old_end_byte: 13,
+            new_end_byte: 14,
+            start_position: point!(0:10),
+            old_end_position: point!(0:13),
+            new_end_position: point!(0:14),
+        }
+    );
+    assert_eq!(rope.to_string(), "\"é\" type Bär");
+}
+
 #[test]
 fn test_range_contains_point() {
     assert!(!range!(0:0..0:0).contains(point!(0:0)));
@@ -94,3 +112,14 @@ fn test_range_intersection() {
     assert!(range!(0:0..0:2).intersects(range!(0:0..0:1)));
     assert!(!range!(0:0..0:1).intersects(range!(0:1..0:2)));
 }
+
+#[test]
+fn test_deletion_range_at_end_of_file() {
+    let text = "type Foo { id: ID! }\n\ntype Bar { id: ID! }";
+    assert_eq!(deletion_range(text, range!(2:0..2:20)), range!(0:20..2:20));
+    // the range ends after the final newline, or beyond the text entirely
+    let text = "type Foo { id: ID! }\n\ntype Bar { id: ID! }\n";
+    assert_eq!(deletion_range(text, range!(2:0..3:0)), range!(0:20..2:20));
+    assert_eq!(deletion_range(text, range!(2:0..4:2)), range!(2:0..4:2));
+    assert_eq!(deletion_range("", range!(0:0..0:0)), range!(0:0..0:0));
+}
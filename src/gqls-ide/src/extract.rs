@@ -0,0 +1,87 @@
+//! Extracting a selection of the fields of an object or input type into a new type of its own,
+//! the fields are replaced by a single field of the new type.
+
+use gqls_db::{FileData, SourceDatabase};
+use gqls_syntax::{NodeExt, NodeKind};
+use tree_sitter::Node;
+use vfs::FileId;
+
+use crate::edit::{reindent, RangeExt};
+use crate::organize::{self, Member};
+use crate::{Patch, Point, Range, Snapshot};
+
+/// The placeholder name of the extracted type, which is expected to be renamed right away
+pub const EXTRACTED_TYPE: &str = "NewType";
+
+const EXTRACTED_FIELD: &str = "newType";
+
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedType {
+    pub patches: Vec<Patch>,
+    /// the start of the name of the new type once the patches are applied
+    pub name: Point,
+}
+
+impl Snapshot {
+    /// Extract the fields that intersect `range` into a new type defined after the type they
+    /// belong to, `None` if the range doesn't select any fields of an object or input type
+    pub fn extract_type(&self, file: FileId, range: Range) -> Option<ExtractedType> {
+        let data = self.file_data(file);
+        let (definition, first, last) = selected_fields(&data, range)?;
+        let keyword = match definition.kind() {
+            NodeKind::INPUT_OBJECT_TYPE_DEFINITION | NodeKind::INPUT_OBJECT_TYPE_EXTENSION =>
+                "input",
+            _ => "type",
+        };
+
+        let text = &data.text;
+        let extracted = Range { start: first.range().start, end: last.range().end };
+
+        let fields = &text[first.first.start_byte()..last.last.end_byte()];
+        let fields = format!("{INDENT}{}", reindent(fields, extracted.start.column, INDENT));
+
+        let end = definition.end_position();
+        let definition = format!("\n\n{keyword} {EXTRACTED_TYPE} {{\n{fields}\n}}");
+        let patches = vec![
+            Patch::new(extracted, format!("{EXTRACTED_FIELD}: {EXTRACTED_TYPE}")),
+            Patch::new(Range { start: end, end }, definition),
+        ];
+        let removed_rows = extracted.end.row - extracted.start.row;
+        let name = Point::new(end.row - removed_rows + 2, keyword.len() + 1);
+        Some(ExtractedType { patches, name })
+    }
+
+    /// Whether `range` selects any fields to extract, without building the patches that do so
+    pub(crate) fn can_extract_type(&self, file: FileId, range: Range) -> bool {
+        selected_fields(&self.file_data(file), range).is_some()
+    }
+}
+
+/// The definition of the fields that intersect `range` along with the first and last of them
+fn selected_fields(
+    data: &FileData,
+    range: Range,
+) -> Option<(Node<'_>, Member<'_, '_>, Member<'_, '_>)> {
+    let definition = data.tree.root_node().named_node_at(range.start)?.parents().find(|node| {
+        matches!(
+            node.kind(),
+            NodeKind::OBJECT_TYPE_DEFINITION
+                | NodeKind::OBJECT_TYPE_EXTENSION
+                | NodeKind::INPUT_OBJECT_TYPE_DEFINITION
+                | NodeKind::INPUT_OBJECT_TYPE_EXTENSION
+        )
+    })?;
+    if definition.has_error() {
+        return None;
+    }
+    let members = organize::members(&data.text, organize::members_of(definition)?)?;
+    let mut selected = members.into_iter().filter(|member| member.range().intersects(range));
+    let first = selected.next()?;
+    let last = selected.last().unwrap_or(first);
+    Some((definition, first, last))
+}
+
+#[cfg(test)]
+mod tests;
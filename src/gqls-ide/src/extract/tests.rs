@@ -0,0 +1,77 @@
+use gqls_fixture::{fixture, Fixture};
+use ropey::Rope;
+
+use crate::{Ide, Range, EXTRACTED_TYPE};
+
+#[track_caller]
+fn test(fixture: &Fixture, expected: &str) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let snapshot = ide.snapshot();
+    let positions = fixture.positions().collect::<Vec<_>>();
+    let (start, end) = (positions[0], positions[positions.len() - 1]);
+    let extracted = snapshot
+        .extract_type(start.file, Range { start: start.point, end: end.point })
+        .expect("expected the selection to be extracted");
+
+    let mut rope = Rope::from_str(&snapshot.file_text(start.file));
+    // apply the later patch first so the earlier one's range is still valid
+    for patch in extracted.patches.iter().rev() {
+        let _ = patch.apply(&mut rope);
+    }
+    assert_eq!(rope.to_string(), expected);
+
+    let name = rope.line_to_char(extracted.name.row) + extracted.name.column;
+    assert_eq!(rope.slice(name..name + EXTRACTED_TYPE.len()), EXTRACTED_TYPE);
+}
+
+#[test]
+fn test_extract_type() {
+    let fixture = fixture! {
+        "foo" => r#"type Foo {
+  id: ID
+ $street: String
+  "the city"
+  city(short: Boolean): String @deprecated # trailing
+  zip:$String
+  name: String
+}
+"#
+    };
+    test(
+        &fixture,
+        r#"type Foo {
+  id: ID
+  newType: NewType
+  name: String
+}
+
+type NewType {
+  street: String
+  "the city"
+  city(short: Boolean): String @deprecated # trailing
+  zip: String
+}
+"#,
+    );
+}
+
+#[test]
+fn test_extract_single_field_of_input() {
+    let fixture = fixture! {
+        "input" => "input I { a: Int b:$Int = 1$c: Int }"
+    };
+    test(&fixture, "input I { a: Int newType: NewType c: Int }\n\ninput NewType {\n  b: Int = 1\n}");
+}
+
+#[test]
+fn test_extract_type_outside_fields() {
+    let fixture = fixture! {
+        "enum" => "enum E { A$B }"
+        "scalar" => "scalar$S type Foo { a: Int }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    for position in fixture.positions() {
+        let range = Range { start: position.point, end: position.point };
+        assert!(ide.snapshot().extract_type(position.file, range).is_none());
+    }
+}
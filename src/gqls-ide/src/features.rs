@@ -0,0 +1,111 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use vfs::FileId;
+
+use crate::Snapshot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// A standalone graphql document (`.graphql`, `.graphqls`, `.gql`)
+    Graphql,
+    /// Any other file that was included in a project (e.g. graphql embedded in a host language)
+    Embedded,
+}
+
+impl DocumentKind {
+    pub fn of(file: &Path) -> Self {
+        match file.extension().and_then(|ext| ext.to_str()) {
+            Some("graphql" | "graphqls" | "gql") => DocumentKind::Graphql,
+            _ => DocumentKind::Embedded,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DocumentKind::Graphql => "graphql",
+            DocumentKind::Embedded => "embedded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Feature {
+    Completion,
+    Definition,
+    Diagnostics,
+    DocumentSymbols,
+    Extraction,
+    Formatting,
+    Hover,
+    Implementation,
+    References,
+    Rename,
+    SemanticTokens,
+    TypeDefinition,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 12] = [
+        Feature::Completion,
+        Feature::Definition,
+        Feature::Diagnostics,
+        Feature::DocumentSymbols,
+        Feature::Extraction,
+        Feature::Formatting,
+        Feature::Hover,
+        Feature::Implementation,
+        Feature::References,
+        Feature::Rename,
+        Feature::SemanticTokens,
+        Feature::TypeDefinition,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Completion => "completion",
+            Feature::Definition => "definition",
+            Feature::Diagnostics => "diagnostics",
+            Feature::DocumentSymbols => "documentSymbols",
+            Feature::Extraction => "extraction",
+            Feature::Formatting => "formatting",
+            Feature::Hover => "hover",
+            Feature::Implementation => "implementation",
+            Feature::References => "references",
+            Feature::Rename => "rename",
+            Feature::SemanticTokens => "semanticTokens",
+            Feature::TypeDefinition => "typeDefinition",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentFeatures {
+    pub kind: DocumentKind,
+    pub enabled: BTreeSet<Feature>,
+}
+
+impl DocumentFeatures {
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled.contains(&feature)
+    }
+}
+
+impl Snapshot {
+    /// The features that are available for `file`, so clients can hide what won't work
+    pub fn document_features(&self, file: FileId) -> DocumentFeatures {
+        let kind = DocumentKind::of(file);
+        let enabled = match kind {
+            DocumentKind::Graphql => Feature::ALL.into_iter().collect(),
+            // the types of an embedded document can be extracted, but formatting would rewrite
+            // the host language around it
+            DocumentKind::Embedded => Feature::ALL
+                .into_iter()
+                .filter(|&feature| feature != Feature::Formatting)
+                .collect(),
+        };
+        DocumentFeatures { kind, enabled }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use crate::{DocumentKind, Feature, Ide};
+
+#[test]
+fn test_document_kind() {
+    assert_eq!(DocumentKind::of(Path::new("foo.graphql")), DocumentKind::Graphql);
+    assert_eq!(DocumentKind::of(Path::new("foo.gql")), DocumentKind::Graphql);
+    assert_eq!(DocumentKind::of(Path::new("foo.graphqls")), DocumentKind::Graphql);
+    assert_eq!(DocumentKind::of(Path::new("foo.ts")), DocumentKind::Embedded);
+    assert_eq!(DocumentKind::of(Path::new("foo")), DocumentKind::Embedded);
+}
+
+#[test]
+fn test_graphql_document_features() {
+    let ide = Ide::default();
+    let features = ide.snapshot().document_features(Path::new("foo.graphql"));
+    assert_eq!(features.kind, DocumentKind::Graphql);
+    assert!(features.is_enabled(Feature::Completion));
+    assert!(features.is_enabled(Feature::Diagnostics));
+    assert_eq!(features.enabled.len(), Feature::ALL.len());
+}
+
+#[test]
+fn test_embedded_document_features() {
+    let ide = Ide::default();
+    let features = ide.snapshot().document_features(Path::new("foo.ts"));
+    assert_eq!(features.kind, DocumentKind::Embedded);
+    assert!(features.is_enabled(Feature::Extraction));
+    assert!(features.is_enabled(Feature::Completion));
+    assert!(!features.is_enabled(Feature::Formatting));
+}
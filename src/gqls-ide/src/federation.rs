@@ -0,0 +1,372 @@
+use std::path::PathBuf;
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{Directive, FieldRes, InProject, ItemKind, ItemRes, Name, Value};
+use gqls_syntax::{Point, Position, RangeExt};
+use once_cell::sync::Lazy;
+use vfs::FileId;
+
+use crate::{Range, Snapshot, VFS};
+
+/// The federation directives and types, see [`Snapshot::links_federation`]
+pub const FEDERATION_PRELUDE: &str = include_str!("federation/prelude.graphql");
+
+/// Where the prelude lives as far as the vfs is concerned.
+/// The file is not read, but clients may write it out so that goto definition has somewhere to go.
+pub static FEDERATION_PRELUDE_PATH: Lazy<PathBuf> =
+    Lazy::new(|| std::env::temp_dir().join("gqls").join("federation.graphql"));
+
+const FEDERATION_SPEC_URL: &str = "https://specs.apollo.dev/federation/";
+
+/// A directive whose string argument is a field set, as configured for a project in addition to
+/// the federation directives (e.g. a `@cacheKey(fields: "id")` of a custom convention)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldSetDirective {
+    /// the name of the directive, including the `@`
+    pub name: String,
+    pub argument: String,
+    /// whether the field set of the directive applied to a field selects fields of the type the
+    /// field returns (like `@provides`), rather than of the type it belongs to (like `@requires`)
+    pub returned: bool,
+}
+
+/// A field set argument of a directive and the type its selections are relative to
+#[derive(Debug)]
+struct FieldSetArg {
+    ty: Name,
+    /// range of the string value, including the quotes
+    range: gqls_syntax::Range,
+}
+
+/// A selected field of a field set, e.g. `id` and `organization { id }` of `id organization { id }`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Selection<'a> {
+    pub name: &'a str,
+    /// byte offset of the name within the field set
+    pub offset: usize,
+    pub selections: Vec<Selection<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FieldSetError {
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+/// A selection of a field set along with what it resolved to, the error case is the type that
+/// does not have the selected field
+#[derive(Debug)]
+pub(crate) struct FieldSetField {
+    pub name: String,
+    pub range: Range,
+    pub res: Result<FieldRes, Name>,
+}
+
+/// The `@link` of the federation spec along with the names it imports
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct FederationLink {
+    /// `None` if the link has no `import` argument
+    imports: Option<Vec<Import>>,
+}
+
+/// e.g. `"@key"` or `{ name: "@key", as: "@primaryKey" }`
+#[derive(Debug, PartialEq, Eq)]
+struct Import {
+    name: String,
+    alias: String,
+}
+
+impl FederationLink {
+    /// The link applied by `directive` if it is a `@link` of the federation spec, i.e.
+    /// `@link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key"])`
+    pub(crate) fn from_directive(directive: &Directive) -> Option<Self> {
+        if directive.name.as_str() != "@link" {
+            return None;
+        }
+        match &directive.argument("url")?.value {
+            Value::String(url) if url.contains(FEDERATION_SPEC_URL) => {}
+            _ => return None,
+        }
+        let imports = directive.argument("import").map(|import| match &import.value {
+            Value::List(imports) => imports.iter().filter_map(Import::from_value).collect(),
+            _ => vec![],
+        });
+        Some(Self { imports })
+    }
+
+    /// Whether the applied directive `name` refers to the federation directive `directive`.
+    /// The namespaced `@federation__key` always refers to `@key`, and the unqualified `@key` does
+    /// if it is imported. Without an `import` argument the unqualified names are accepted as the
+    /// prelude declares them.
+    fn refers_to(&self, name: &str, directive: &str) -> bool {
+        if name.strip_prefix("@federation__") == directive.strip_prefix('@') {
+            return true;
+        }
+        match &self.imports {
+            Some(imports) =>
+                imports.iter().any(|import| import.name == directive && import.alias == name),
+            None => name == directive,
+        }
+    }
+}
+
+impl Import {
+    fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(name) => Some(Self { name: name.to_string(), alias: name.to_string() }),
+            Value::Object(fields) => {
+                let field = |key: &str| {
+                    match fields.iter().find(|(name, _)| name.as_str() == key)? {
+                        (_, Value::String(value)) => Some(value.to_string()),
+                        _ => None,
+                    }
+                };
+                let name = field("name")?;
+                let alias = field("as").unwrap_or_else(|| name.clone());
+                Some(Self { name, alias })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Snapshot {
+    /// Whether a schema definition or extension in `file` links the federation spec, i.e.
+    /// `extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") { .. }`
+    pub(crate) fn links_federation(&self, file: FileId) -> bool {
+        let items = self.items(file);
+        let mut directives = items.schemas.iter().flat_map(|schema| &schema.directives);
+        directives.any(|directive| FederationLink::from_directive(directive).is_some())
+    }
+
+    /// Whether the project(s) of `file` include the federation prelude
+    pub(crate) fn is_federated(&self, file: FileId) -> bool {
+        let prelude = VFS.read().get(&*FEDERATION_PRELUDE_PATH);
+        prelude
+            .map_or(false, |prelude| self.related_files(InProject::unit(file)).contains(&prelude))
+    }
+
+    /// The federation link of the merged schema definition of the project(s) of `file`
+    pub(crate) fn federation_link(&self, file: FileId) -> Option<FederationLink> {
+        if !self.is_federated(file) {
+            return None;
+        }
+        let definition = self.schema_definition(file);
+        definition.directives.iter().find_map(FederationLink::from_directive)
+    }
+
+    /// Resolve each selection of every field set in `file`
+    pub(crate) fn field_sets(
+        &self,
+        file: FileId,
+    ) -> Vec<Result<Vec<FieldSetField>, (Range, FieldSetError)>> {
+        self.field_set_args(file)
+            .into_iter()
+            .map(|arg| self.resolve_field_set(file, &arg))
+            .collect()
+    }
+
+    /// The field named within a field set string at `position`
+    pub(crate) fn resolve_field_set_at(&self, position: Position) -> Option<FieldRes> {
+        let arg = self
+            .field_set_args(position.file)
+            .into_iter()
+            .find(|arg| arg.range.contains(position.point))?;
+        self.resolve_field_set(position.file, &arg)
+            .ok()?
+            .into_iter()
+            .find(|field| field.range.start <= position.point && position.point < field.range.end)?
+            .res
+            .ok()
+    }
+
+    /// The configured field set directives of the project(s) of `file`
+    fn field_set_directives(&self, file: FileId) -> Vec<&FieldSetDirective> {
+        self.projects_of(InProject::unit(file))
+            .iter()
+            .filter_map(|project| self.field_set_directives.get(project))
+            .flatten()
+            .collect()
+    }
+
+    fn field_set_args(&self, file: FileId) -> Vec<FieldSetArg> {
+        let link = self.federation_link(file);
+        let configured = self.field_set_directives(file);
+        if link.is_none() && configured.is_empty() {
+            return vec![];
+        }
+        let refers_to = |name: &str, directive: &str| {
+            link.as_ref().map_or(false, |link| link.refers_to(name, directive))
+        };
+
+        let mut args = vec![];
+        let mut push = |ty: Name, directive: &Directive, argument: &str| {
+            let fields = directive.argument(argument);
+            if let Some(fields) = fields.filter(|arg| matches!(arg.value, Value::String(_))) {
+                args.push(FieldSetArg { ty, range: fields.value_range });
+            }
+        };
+
+        let items = self.items(file);
+        for (idx, item) in items.iter() {
+            let typedef = match item.kind {
+                ItemKind::TypeDefinition(idx) => &items[idx],
+                ItemKind::DirectiveDefinition(_) => continue,
+            };
+            for directive in &typedef.directives {
+                if refers_to(&directive.name, "@key") {
+                    push(item.name, directive, "fields");
+                }
+                for configured in configured.iter().filter(|d| d.name == directive.name.as_str()) {
+                    push(item.name, directive, &configured.argument);
+                }
+            }
+
+            let body = self.item_body(ItemRes::new(file, idx));
+            let fields = body.as_ref().and_then(|body| body.fields());
+            for (_, field) in fields.into_iter().flat_map(|fields| fields.iter()) {
+                for directive in &field.directives {
+                    // `@requires` selects external fields of the enclosing type
+                    if refers_to(&directive.name, "@requires") {
+                        push(item.name, directive, "fields");
+                    }
+                    // `@provides` selects fields of the returned type
+                    if refers_to(&directive.name, "@provides") {
+                        push(field.ty.name(), directive, "fields");
+                    }
+                    let matching = configured.iter().filter(|d| d.name == directive.name.as_str());
+                    for configured in matching {
+                        let ty = if configured.returned { field.ty.name() } else { item.name };
+                        push(ty, directive, &configured.argument);
+                    }
+                }
+            }
+        }
+        args
+    }
+
+    fn resolve_field_set(
+        &self,
+        file: FileId,
+        arg: &FieldSetArg,
+    ) -> Result<Vec<FieldSetField>, (Range, FieldSetError)> {
+        let text = self.file_text(file);
+        let raw = &text[arg.range.start_byte..arg.range.end_byte];
+        let quote = if raw.starts_with("\"\"\"") { 3 } else { 1 };
+        let content = &raw[quote..raw.len() - quote];
+        let range_of = |offset: usize, len: usize| {
+            let start = arg.range.start_byte + quote + offset;
+            Range {
+                start: point_at(&text, arg.range, start),
+                end: point_at(&text, arg.range, start + len),
+            }
+        };
+
+        let selections = parse_field_set(content).map_err(|err| {
+            // the offending character, or the closing quote if the field set ended early
+            let len = content[err.offset..].chars().next().map_or(1, char::len_utf8);
+            (range_of(err.offset, len), err)
+        })?;
+        let mut fields = vec![];
+        self.resolve_selections(file, arg.ty, &selections, &range_of, &mut fields);
+        Ok(fields)
+    }
+
+    fn resolve_selections(
+        &self,
+        file: FileId,
+        ty: Name,
+        selections: &[Selection<'_>],
+        range_of: &impl Fn(usize, usize) -> Range,
+        fields: &mut Vec<FieldSetField>,
+    ) {
+        for selection in selections {
+            let range = range_of(selection.offset, selection.name.len());
+            let res = self.field_of(InProject::new(file, ty), selection.name);
+            if let Some(field) = res {
+                let ty = self.field(field).ty.name();
+                self.resolve_selections(file, ty, &selection.selections, range_of, fields);
+            }
+            let res = res.ok_or(ty);
+            fields.push(FieldSetField { name: selection.name.to_owned(), range, res });
+        }
+    }
+}
+
+// the point of the byte offset `byte`, which must be within `range`
+fn point_at(text: &str, range: gqls_syntax::Range, byte: usize) -> Point {
+    let prefix = &text[range.start_byte..byte];
+    match prefix.rfind('\n') {
+        Some(newline) => Point::new(
+            range.start_point.row + prefix.matches('\n').count(),
+            prefix.len() - newline - 1,
+        ),
+        None => Point::new(range.start_point.row, range.start_point.column + prefix.len()),
+    }
+}
+
+/// Parse the contents of a `FieldSet` string, which is a selection set without the outer braces.
+/// Aliases, arguments and fragments are not supported.
+pub(crate) fn parse_field_set(text: &str) -> Result<Vec<Selection<'_>>, FieldSetError> {
+    FieldSetParser { text, pos: 0 }.selections(false)
+}
+
+struct FieldSetParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> FieldSetParser<'a> {
+    fn selections(&mut self, nested: bool) -> Result<Vec<Selection<'a>>, FieldSetError> {
+        let mut selections = vec![];
+        loop {
+            self.skip_ignored();
+            match self.peek() {
+                None | Some('}') if selections.is_empty() =>
+                    return Err(self.error("expected at least one field")),
+                None if nested => return Err(self.error("unclosed selection set")),
+                None => return Ok(selections),
+                Some('}') if nested => {
+                    self.pos += 1;
+                    return Ok(selections);
+                }
+                Some(c) if c == '_' || c.is_ascii_alphabetic() =>
+                    selections.push(self.selection()?),
+                Some(_) => return Err(self.error("expected a field name")),
+            }
+        }
+    }
+
+    fn selection(&mut self) -> Result<Selection<'a>, FieldSetError> {
+        let offset = self.pos;
+        let len = self.text[offset..]
+            .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+            .unwrap_or(self.text.len() - offset);
+        self.pos += len;
+        self.skip_ignored();
+        let selections = if self.peek() == Some('{') {
+            self.pos += 1;
+            self.selections(true)?
+        } else {
+            vec![]
+        };
+        Ok(Selection { name: &self.text[offset..offset + len], offset, selections })
+    }
+
+    fn skip_ignored(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos +=
+            rest.len() - rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',').len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn error(&self, message: &'static str) -> FieldSetError {
+        FieldSetError { offset: self.pos, message }
+    }
+}
+
+#[cfg(test)]
+mod tests;
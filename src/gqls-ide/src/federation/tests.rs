@@ -0,0 +1,196 @@
+use expect_test::expect;
+use gqls_fixture::{fixture, Fixture};
+use maplit::hashmap;
+use std::collections::HashSet;
+use testing::file_id;
+
+use super::parse_field_set;
+use crate::{Change, Changeset, DefinitionConfig, FieldSetDirective, Ide, Location};
+
+fn test_goto(fixture: Fixture) {
+    test_goto_with(Ide::from_fixture(&fixture), fixture)
+}
+
+fn test_goto_with(ide: Ide, fixture: Fixture) {
+    let snapshot = ide.snapshot();
+    let expected =
+        fixture.ranges().map(|(file, range)| Location::new(file, range)).collect::<HashSet<_>>();
+    for position in fixture.positions() {
+        let locations = snapshot
+            .goto_definition(position, &DefinitionConfig::default())
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(expected, locations);
+    }
+}
+
+#[test]
+fn test_goto_definition_in_field_set() {
+    test_goto(fixture! {
+        "foo" => r#"
+extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") {
+    query: Query
+}
+
+type Query {
+    me: User
+}
+
+type User @key(fields: "id organization { id }") {
+                          #^
+    id: ID!
+    organization: Organization
+   #............
+}
+
+type Organization {
+    id: ID!
+}
+"#
+    });
+}
+
+#[test]
+fn test_goto_definition_in_nested_field_set() {
+    test_goto(fixture! {
+        "foo" => r#"
+extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") {
+    query: Query
+}
+
+type Query {
+    me: User
+}
+
+type User @key(fields: "id organization { id }") {
+                                         #^
+    id: ID!
+    organization: Organization
+}
+
+type Organization {
+    id: ID!
+   #..
+}
+"#
+    });
+}
+
+#[test]
+fn test_goto_definition_in_configured_field_set() {
+    let fixture = fixture! {
+        "foo" => r#"
+directive @cacheKey(fields: String!) on OBJECT
+
+type User @cacheKey(fields: "id organization") {
+                               #^
+    id: ID!
+    organization: Organization
+   #............
+}
+
+type Organization {
+    id: ID!
+}
+"#
+    };
+    let mut ide = Ide::from_fixture(&fixture);
+    let directive = FieldSetDirective {
+        name: "@cacheKey".to_owned(),
+        argument: "fields".to_owned(),
+        returned: false,
+    };
+    let _ = ide.apply(
+        Changeset::default().with_field_set_directives(hashmap! { "default" => vec![directive] }),
+    );
+    test_goto_with(ide, fixture);
+}
+
+#[test]
+fn test_link_federation_after_changes() {
+    let link = r#"extend schema @link(url: "https://specs.apollo.dev/federation/v2.0")"#;
+    let fixture = fixture! {
+        "foo" => link
+        "bar" => "type Query { foo: Int }"
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let (foo, bar) = (file_id!("foo"), file_id!("bar"));
+    assert!(ide.snapshot().is_federated(bar));
+
+    // only the changed file is scanned again, the link of the other is remembered
+    let _ = ide.apply(Change::set(bar, "type Query { bar: Int }".to_owned()));
+    assert!(ide.snapshot().is_federated(bar));
+
+    let _ = ide.apply(Change::set(foo, String::new()));
+    assert!(!ide.snapshot().is_federated(bar));
+
+    let _ = ide.apply(Change::set(foo, link.to_owned()));
+    assert!(ide.snapshot().is_federated(bar));
+}
+
+#[test]
+fn test_parse_field_set() {
+    expect![[r#"
+        Ok(
+            [
+                Selection {
+                    name: "id",
+                    offset: 0,
+                    selections: [],
+                },
+                Selection {
+                    name: "organization",
+                    offset: 3,
+                    selections: [
+                        Selection {
+                            name: "id",
+                            offset: 18,
+                            selections: [],
+                        },
+                    ],
+                },
+            ],
+        )
+    "#]]
+    .assert_debug_eq(&parse_field_set("id organization { id }"));
+}
+
+#[test]
+fn test_parse_invalid_field_set() {
+    expect![[r#"
+        Err(
+            FieldSetError {
+                offset: 2,
+                message: "expected at least one field",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&parse_field_set("  "));
+    expect![[r#"
+        Err(
+            FieldSetError {
+                offset: 5,
+                message: "unclosed selection set",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&parse_field_set("a { b"));
+    expect![[r#"
+        Err(
+            FieldSetError {
+                offset: 2,
+                message: "expected a field name",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&parse_field_set("a ...on B"));
+    expect![[r#"
+        Err(
+            FieldSetError {
+                offset: 4,
+                message: "expected at least one field",
+            },
+        )
+    "#]]
+    .assert_debug_eq(&parse_field_set("a { }"));
+}
@@ -0,0 +1,296 @@
+//! The formatter, a canonical layout of a graphql document. Every definition is separated by a
+//! blank line and the members of a block are each on their own (indented) line, anything else
+//! is kept on a single line. Comments are preserved, as are single blank lines between members.
+
+use gqls_db::SourceDatabase;
+use gqls_syntax::{NodeExt, NodeKind};
+use tree_sitter::Node;
+use vfs::FileId;
+
+use crate::{Patch, Range, Snapshot};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// the number of spaces per level of indentation
+    pub indent_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self { indent_width: 2 }
+    }
+}
+
+/// The formatted `text`, or `None` if it doesn't parse
+pub fn format(text: &str, config: &FormatConfig) -> Option<String> {
+    let tree = gqls_syntax::parse_fresh(text);
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+    let mut formatter =
+        Formatter { text, config: *config, out: String::new(), indent: 0, space: Space::None };
+    formatter.node(root);
+    if !formatter.out.is_empty() {
+        formatter.out.push('\n');
+    }
+    Some(formatter.out)
+}
+
+impl Snapshot {
+    /// The patches that format `file`, none if it is already formatted, doesn't parse or is
+    /// generated
+    pub fn format(&self, file: FileId, config: &FormatConfig) -> Vec<Patch> {
+        if self.is_generated(file) {
+            return vec![];
+        }
+        let text = self.file_text(file);
+        match format(&text, config) {
+            Some(formatted) if formatted != *text => {
+                let end = self.line_index(file).point(text.len());
+                let range = Range { start: Default::default(), end };
+                vec![Patch::new(range, formatted)]
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// The whitespace between two tokens, the larger of two requested spaces wins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Space {
+    None,
+    Space,
+    Newline,
+    BlankLine,
+}
+
+/// How the members of a delimited node (e.g. `{ ... }` or `( ... )`) are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// `(a: Int, b: Int)`
+    Inline,
+    /// each member on its own line, indented
+    Block,
+}
+
+impl Layout {
+    fn of(node: Node<'_>) -> Option<Self> {
+        match node.kind() {
+            NodeKind::SCHEMA_DEFINITION
+            | NodeKind::SCHEMA_EXTENSION
+            | NodeKind::FIELDS_DEFINITION
+            | NodeKind::INPUT_FIELDS_DEFINITION
+            | NodeKind::ENUM_VALUES_DEFINITION => Some(Layout::Block),
+            // these are broken into blocks when they can't be written on one line
+            NodeKind::ARGUMENTS_DEFINITION
+            | NodeKind::ARGUMENTS
+            | NodeKind::LIST_VALUE
+            | NodeKind::OBJECT_VALUE => {
+                let mut descendants = gqls_syntax::traverse_node_preorder(node);
+                let multiline = descendants
+                    .any(|node| matches!(node.kind(), NodeKind::COMMENT | NodeKind::DESCRIPTION));
+                Some(if multiline { Layout::Block } else { Layout::Inline })
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Formatter<'a> {
+    text: &'a str,
+    config: FormatConfig,
+    out: String,
+    indent: usize,
+    /// the whitespace to write before the next token
+    space: Space,
+}
+
+impl<'a> Formatter<'a> {
+    fn node(&mut self, node: Node<'_>) {
+        if node.child_count() == 0
+            || matches!(node.kind(), NodeKind::STRING_VALUE | NodeKind::DIRECTIVE_NAME)
+        {
+            self.token(node);
+            return;
+        }
+
+        let layout = Layout::of(node);
+        let mut cursor = node.walk();
+        // commas are insignificant, the inline layout writes its own
+        let children = node
+            .children(&mut cursor)
+            .filter(|child| child.kind() != NodeKind::COMMA)
+            .collect::<Vec<_>>();
+        let mut prev = None::<Node<'_>>;
+        // whether the child is within the delimiters of the layout
+        let mut delimited = false;
+        for child in children {
+            // a leading `&` or `|` is optional
+            if matches!(child.kind(), "&" | "|") && !matches!(prev, Some(prev) if prev.is_named()) {
+                continue;
+            }
+            let is_comment = child.kind() == NodeKind::COMMENT;
+            match (layout, prev) {
+                (Some(layout), Some(prev)) if is_open(prev) => {
+                    delimited = true;
+                    if !is_close(child) {
+                        self.open(layout, prev);
+                    }
+                }
+                (Some(layout), Some(_)) if delimited && is_close(child) => {
+                    delimited = false;
+                    self.close(layout, child);
+                }
+                (Some(Layout::Block), Some(prev)) if delimited && !is_comment =>
+                    self.space(self.gap(prev, child, Space::Newline)),
+                // there are no comments within an inline layout
+                (Some(Layout::Inline), Some(_)) if delimited => {
+                    self.out.push(',');
+                    self.space(Space::Space);
+                }
+                (_, Some(prev)) if !is_comment => self.space(self.separator(node, prev, child)),
+                _ => {}
+            }
+            if is_comment {
+                self.comment(node, prev, child);
+            } else {
+                self.node(child);
+            }
+            prev = Some(child);
+        }
+    }
+
+    fn open(&mut self, layout: Layout, open: Node<'_>) {
+        match layout {
+            Layout::Block => {
+                self.indent += 1;
+                self.space(Space::Newline);
+            }
+            Layout::Inline if open.kind() == "{" => self.space(Space::Space),
+            Layout::Inline => {}
+        }
+    }
+
+    fn close(&mut self, layout: Layout, close: Node<'_>) {
+        match layout {
+            Layout::Block => {
+                self.indent -= 1;
+                self.space(Space::Newline);
+            }
+            Layout::Inline if close.kind() == "}" => self.space(Space::Space),
+            Layout::Inline => {}
+        }
+    }
+
+    fn separator(&self, parent: Node<'_>, prev: Node<'_>, next: Node<'_>) -> Space {
+        if parent.kind() == NodeKind::DOCUMENT {
+            return match prev.kind() {
+                NodeKind::COMMENT => self.gap(prev, next, Space::Newline),
+                _ => Space::BlankLine,
+            };
+        }
+        if prev.kind() == NodeKind::COMMENT {
+            return self.gap(prev, next, Space::Newline);
+        }
+        if prev.kind() == NodeKind::DESCRIPTION {
+            return Space::Newline;
+        }
+        match (prev.kind(), next.kind()) {
+            (_, ":" | "!" | "]" | ")" | NodeKind::ARGUMENTS | NodeKind::ARGUMENTS_DEFINITION) =>
+                Space::None,
+            ("(" | "[" | "$", _) => Space::None,
+            _ => Space::Space,
+        }
+    }
+
+    /// A blank line if there was one between `prev` and `next`, otherwise `space`
+    fn gap(&self, prev: Node<'_>, next: Node<'_>, space: Space) -> Space {
+        if next.start_position().row > prev.end_position().row + 1 {
+            Space::BlankLine
+        } else {
+            space
+        }
+    }
+
+    fn comment(&mut self, parent: Node<'_>, prev: Option<Node<'_>>, comment: Node<'_>) {
+        match prev {
+            // a trailing comment stays on the line of the token it follows
+            Some(prev) if prev.end_position().row == comment.start_position().row =>
+                self.space = Space::Space,
+            Some(prev) if parent.kind() == NodeKind::DOCUMENT && prev.kind() != NodeKind::COMMENT =>
+                self.space(Space::BlankLine),
+            Some(prev) if !is_open(prev) => self.space(self.gap(prev, comment, Space::Newline)),
+            _ => self.space(Space::Newline),
+        }
+        self.token(comment);
+        self.space(Space::Newline);
+    }
+
+    fn space(&mut self, space: Space) {
+        self.space = self.space.max(space);
+    }
+
+    fn token(&mut self, node: Node<'_>) {
+        if !self.out.is_empty() {
+            match self.space {
+                Space::None => {}
+                Space::Space => self.out.push(' '),
+                Space::Newline => self.newline(),
+                Space::BlankLine => {
+                    self.out.push('\n');
+                    self.newline();
+                }
+            }
+        }
+        self.space = Space::None;
+
+        let text = node.text(self.text);
+        if node.kind() == NodeKind::STRING_VALUE && text.starts_with("\"\"\"") {
+            self.block_string(text);
+        } else {
+            self.out.push_str(text.trim_end());
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        let width = self.indent * self.config.indent_width;
+        self.out.extend(std::iter::repeat(' ').take(width));
+    }
+
+    /// Reindent the lines of a block string to the current indentation. The value of a block
+    /// string is stripped of the common indentation of its lines, so this only drops the
+    /// whitespace of blank lines.
+    fn block_string(&mut self, text: &str) {
+        let mut lines = text.lines();
+        self.out.push_str(lines.next().unwrap_or_default());
+        let lines = lines.collect::<Vec<_>>();
+        let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+        let common = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| indentation(line))
+            .min()
+            .unwrap_or_default();
+        for line in lines {
+            if line.trim().is_empty() {
+                self.out.push('\n');
+            } else {
+                self.newline();
+                self.out.push_str(&line[common..]);
+            }
+        }
+    }
+}
+
+fn is_open(node: Node<'_>) -> bool {
+    matches!(node.kind(), "{" | "(" | "[")
+}
+
+fn is_close(node: Node<'_>) -> bool {
+    matches!(node.kind(), "}" | ")" | "]")
+}
+
+#[cfg(test)]
+mod tests;
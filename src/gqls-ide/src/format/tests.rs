@@ -0,0 +1,206 @@
+use expect_test::{expect, Expect};
+use gqls_fixture::fixture;
+use std::path::Path;
+
+use super::{format, FormatConfig};
+use crate::Ide;
+
+#[track_caller]
+fn test(text: &str, expect: Expect) {
+    test_with(text, &FormatConfig::default(), expect)
+}
+
+#[track_caller]
+fn test_with(text: &str, config: &FormatConfig, expect: Expect) {
+    let formatted = format(text, config).expect("expected the text to parse");
+    expect.assert_eq(&formatted);
+    assert_eq!(format(&formatted, config).as_deref(), Some(formatted.as_str()), "not idempotent");
+}
+
+#[test]
+fn test_format_type_definitions() {
+    test(
+        r#"
+type Foo implements & Bar&Baz @qux(a:1,b:[1 2],c:{x:"y"}) {
+        bar( a :Int = 1 , b: [Int !]!): Bar!   @deprecated
+    baz : Int
+}
+interface Bar{bar(a: Int, b: [Int!]!): Bar!}
+union U =|Foo|  Bar
+enum E {A B
+C }
+input I { a: Int = 1 @qux b: Float }
+scalar S
+extend type Foo { qux: S }
+directive @qux(a: Int, b: [Int], c: I) repeatable on | OBJECT|FIELD_DEFINITION
+schema { query: Foo mutation: Foo }
+"#,
+        expect![[r#"
+            type Foo implements Bar & Baz @qux(a: 1, b: [1, 2], c: { x: "y" }) {
+              bar(a: Int = 1, b: [Int!]!): Bar! @deprecated
+              baz: Int
+            }
+
+            interface Bar {
+              bar(a: Int, b: [Int!]!): Bar!
+            }
+
+            union U = Foo | Bar
+
+            enum E {
+              A
+              B
+              C
+            }
+
+            input I {
+              a: Int = 1 @qux
+              b: Float
+            }
+
+            scalar S
+
+            extend type Foo {
+              qux: S
+            }
+
+            directive @qux(a: Int, b: [Int], c: I) repeatable on OBJECT | FIELD_DEFINITION
+
+            schema {
+              query: Foo
+              mutation: Foo
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn test_format_descriptions() {
+    test(
+        r#"
+"a type"
+type Foo {
+          """
+          a field
+            indented
+          """
+  bar(
+"an argument" a: Int b: Int): Int
+  "an empty type"
+  baz: Empty
+}
+type Empty {}
+"#,
+        expect![[r#"
+            "a type"
+            type Foo {
+              """
+              a field
+                indented
+              """
+              bar(
+                "an argument"
+                a: Int
+                b: Int
+              ): Int
+              "an empty type"
+              baz: Empty
+            }
+
+            type Empty {}
+        "#]],
+    );
+}
+
+#[test]
+fn test_format_comments() {
+    test(
+        r#"
+# leading comment
+type Foo { # trailing brace comment
+  bar: Int # trailing comment
+
+  # leading field comment
+  baz: Int
+  # dangling comment
+}
+# between definitions
+scalar S
+"#,
+        expect![[r#"
+            # leading comment
+            type Foo { # trailing brace comment
+              bar: Int # trailing comment
+
+              # leading field comment
+              baz: Int
+              # dangling comment
+            }
+
+            # between definitions
+            scalar S
+        "#]],
+    );
+}
+
+#[test]
+fn test_format_indent_width() {
+    test_with(
+        r#"
+type Foo {
+  """
+  a field
+  """
+  bar(a: Int # an argument
+  b: Int): Int
+}
+"#,
+        &FormatConfig { indent_width: 4 },
+        expect![[r#"
+            type Foo {
+                """
+                a field
+                """
+                bar(
+                    a: Int # an argument
+                    b: Int
+                ): Int
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn test_format_empty() {
+    test("", expect![[""]]);
+    test("  \n\n", expect![[""]]);
+}
+
+#[test]
+fn test_format_syntax_error() {
+    assert_eq!(format("type Foo {", &FormatConfig::default()), None);
+}
+
+#[test]
+fn test_format_patches() {
+    let fixture = fixture! {
+        "foo" => "type Foo {bar: Int}"
+        "bar" => "type Bar {\n  bar: Int\n}\n"
+    };
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let config = FormatConfig::default();
+    let patches = snapshot.format(Path::new("foo"), &config);
+    assert_eq!(patches.len(), 1);
+    assert_eq!(patches[0].with, "type Foo {\n  bar: Int\n}\n");
+    assert!(snapshot.format(Path::new("bar"), &config).is_empty());
+}
+
+#[test]
+fn test_generated_files_are_not_formatted() {
+    let fixture = fixture! {
+        "foo" => "# @generated by a tool\ntype Foo {bar: Int}"
+    };
+    let ide = Ide::from_fixture(&fixture);
+    assert!(ide.snapshot().format(Path::new("foo"), &FormatConfig::default()).is_empty());
+}
@@ -0,0 +1,136 @@
+//! The dependency hierarchy of a type, i.e. the types it refers to through the types of its
+//! fields, arguments and union members (outgoing) and the items that refer to it (incoming)
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{DependencyGraph, InProject, ItemKind, ItemRes, Name, Res};
+use gqls_syntax::Position;
+use std::collections::HashSet;
+use std::sync::Arc;
+use vfs::FileId;
+
+use crate::{Range, Snapshot, SymbolKind};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HierarchyItem {
+    pub name: Name,
+    pub kind: SymbolKind,
+    pub file: FileId,
+    pub range: Range,
+    /// the range of the name
+    pub selection_range: Range,
+    /// `extension` for the extensions of a type
+    pub detail: Option<String>,
+}
+
+/// An item of the hierarchy along with the references between it and the item it is related to
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HierarchyCall {
+    pub item: HierarchyItem,
+    /// the ranges of the references, which are in the file of the item that refers to the other
+    pub ranges: Vec<Range>,
+}
+
+impl Snapshot {
+    /// The definition and extensions of the type or directive named at `position`
+    pub fn prepare_dependency_hierarchy(&self, position: Position) -> Vec<HierarchyItem> {
+        let name = match self.name_at(position) {
+            Some(name) => name,
+            None => return vec![],
+        };
+        self.parts(InProject::new(position.file, name))
+            .into_iter()
+            .map(|res| self.hierarchy_item(res))
+            .collect()
+    }
+
+    /// The items that refer to the type of the item at `position`
+    pub fn incoming_dependencies(&self, position: Position) -> Vec<HierarchyCall> {
+        let res = match self.resolve_item_at(position) {
+            Some(res) => res,
+            None => return vec![],
+        };
+        let name = self.item(res).name;
+        let graphs = self.dependency_graphs(position.file);
+        let mut seen = HashSet::new();
+        let mut calls = Vec::<(ItemRes, Vec<Range>)>::new();
+        for dependency in graphs.iter().flat_map(|graph| graph.incoming(name)) {
+            if !seen.insert((dependency.from, dependency.to.range.start_byte)) {
+                continue;
+            }
+            match calls.iter_mut().find(|(from, _)| *from == dependency.from) {
+                Some((_, ranges)) => ranges.push(dependency.to.range.into()),
+                None => calls.push((dependency.from, vec![dependency.to.range.into()])),
+            }
+        }
+        calls
+            .into_iter()
+            .map(|(from, ranges)| HierarchyCall { item: self.hierarchy_item(from), ranges })
+            .collect()
+    }
+
+    /// The types the item at `position` refers to, the ranges are in the file of `position`
+    pub fn outgoing_dependencies(&self, position: Position) -> Vec<HierarchyCall> {
+        let res = match self.resolve_item_at(position) {
+            Some(res) => res,
+            None => return vec![],
+        };
+        let graphs = self.dependency_graphs(position.file);
+        let mut seen = HashSet::new();
+        let mut calls = Vec::<(Name, Vec<Range>)>::new();
+        for dependency in graphs.iter().flat_map(|graph| graph.outgoing(res)) {
+            if !seen.insert(dependency.to.range.start_byte) {
+                continue;
+            }
+            match calls.iter_mut().find(|(to, _)| *to == dependency.to) {
+                Some((_, ranges)) => ranges.push(dependency.to.range.into()),
+                None => calls.push((dependency.to, vec![dependency.to.range.into()])),
+            }
+        }
+        calls
+            .into_iter()
+            .filter_map(|(to, ranges)| {
+                let to = *self.parts(InProject::new(position.file, to)).first()?;
+                Some(HierarchyCall { item: self.hierarchy_item(to), ranges })
+            })
+            .collect()
+    }
+
+    // the graphs of the projects of `file`, which overlap when the file is in several of them
+    fn dependency_graphs(&self, file: FileId) -> Vec<Arc<DependencyGraph>> {
+        self.projects_of(InProject::unit(file))
+            .iter()
+            .map(|&project| self.dependency_graph(project))
+            .collect()
+    }
+
+    // the base definition of the type named `name` followed by its extensions
+    fn parts(&self, name: InProject<Name>) -> Vec<ItemRes> {
+        if let Some(typedef) = self.effective_typedef(name) {
+            return typedef.parts.clone();
+        }
+        match self.resolve_item(name) {
+            Res::Item(resolutions) => resolutions.into_iter().collect(),
+            Res::Builtin(_) | Res::Err => vec![],
+        }
+    }
+
+    fn hierarchy_item(&self, res: ItemRes) -> HierarchyItem {
+        let items = self.items(res.file);
+        let item = &items[res.value];
+        let is_ext = match item.kind {
+            ItemKind::TypeDefinition(typedef) => items[typedef].is_ext,
+            ItemKind::DirectiveDefinition(_) => false,
+        };
+        HierarchyItem {
+            name: item.name,
+            kind: item.kind.into(),
+            file: res.file,
+            range: item.range.into(),
+            selection_range: item.name.range.into(),
+            detail: is_ext.then(|| "extension".to_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
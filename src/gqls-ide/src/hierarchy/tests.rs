@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use expect_test::{expect, Expect};
+use gqls_fixture::{fixture, Fixture};
+use gqls_syntax::{Point, Position};
+
+use crate::{HierarchyCall, HierarchyItem, Ide};
+
+fn item(item: &HierarchyItem) -> String {
+    let detail = item.detail.as_ref().map(|detail| format!(" ({detail})")).unwrap_or_default();
+    format!("{}:{:?} {}{}", item.file.display(), item.selection_range, item.name, detail)
+}
+
+fn calls(calls: Vec<HierarchyCall>) -> Vec<String> {
+    calls.iter().map(|call| format!("{} {:?}", item(&call.item), call.ranges)).collect()
+}
+
+fn fixture() -> Fixture {
+    fixture! {
+        "foo" => "
+type Query {
+    user(filter: Filter): User
+    users: [User!]!
+}
+
+type User {
+    friends: [User!]!
+}
+
+input Filter {
+    id: ID
+}
+"
+        "bar" => "
+extend type User {
+    query: Query
+}
+"
+    }
+}
+
+fn test(f: impl FnOnce(&crate::Snapshot) -> Vec<String>, expect: Expect) {
+    let ide = Ide::from_fixture(&fixture());
+    expect.assert_debug_eq(&f(&ide.snapshot()));
+}
+
+fn position(file: &'static str, row: usize, column: usize) -> Position {
+    Position::new(Path::new(file), Point::new(row, column))
+}
+
+#[test]
+fn test_prepare_dependency_hierarchy() {
+    test(
+        |snapshot| {
+            snapshot
+                .prepare_dependency_hierarchy(position("foo", 2, 27))
+                .iter()
+                .map(item)
+                .collect()
+        },
+        expect![[r#"
+            [
+                "foo:6:5..6:9 User",
+                "bar:1:12..1:16 User (extension)",
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_incoming_dependencies() {
+    test(
+        |snapshot| calls(snapshot.incoming_dependencies(position("foo", 6, 5))),
+        expect![[r#"
+            [
+                "foo:1:5..1:10 Query [2:26..2:30, 3:12..3:16]",
+                "foo:6:5..6:9 User [7:14..7:18]",
+            ]
+        "#]],
+    );
+}
+
+#[test]
+fn test_outgoing_dependencies() {
+    test(
+        |snapshot| calls(snapshot.outgoing_dependencies(position("foo", 1, 5))),
+        expect![[r#"
+            [
+                "foo:10:6..10:12 Filter [2:17..2:23]",
+                "foo:6:5..6:9 User [2:26..2:30, 3:12..3:16]",
+            ]
+        "#]],
+    );
+    // the dependencies of an extension are its own
+    test(
+        |snapshot| calls(snapshot.outgoing_dependencies(position("bar", 1, 12))),
+        expect![[r#"
+            [
+                "foo:1:5..1:10 Query [2:11..2:16]",
+            ]
+        "#]],
+    );
+}
@@ -1,6 +1,7 @@
 // FIXME entire file is a messy hack that barely works
 // There is definitely a cleaner way to implement this
 use std::fmt::{self, Debug};
+use std::sync::Arc;
 
 use gqls_db::{DefDatabase, SourceDatabase};
 use gqls_ir::{ItemKind, TypeDefinitionKind};
@@ -50,6 +51,7 @@ impl Snapshot {
 struct Highlighter<'a, 'tree> {
     snapshot: &'a Snapshot,
     file: FileId,
+    text: Arc<str>,
     nodes: Traverse<'tree>,
     tokens: Vec<SemanticToken>,
     scopes: Vec<Scope>,
@@ -74,9 +76,11 @@ enum Scope {
 impl Scope {
     fn from_node_kind(kind: &'static str) -> Option<Self> {
         match kind {
-            NodeKind::ARGUMENTS_DEFINITION => Some(Scope::Argument),
+            NodeKind::ARGUMENTS_DEFINITION | NodeKind::ARGUMENTS => Some(Scope::Argument),
             NodeKind::DIRECTIVE_DEFINITION => Some(Scope::Directive),
-            NodeKind::FIELD_DEFINITION | NodeKind::INPUT_FIELDS_DEFINITION => Some(Scope::Field),
+            NodeKind::FIELD_DEFINITION
+            | NodeKind::INPUT_FIELDS_DEFINITION
+            | NodeKind::OBJECT_FIELD => Some(Scope::Field),
             NodeKind::OBJECT_TYPE_DEFINITION | NodeKind::OBJECT_TYPE_EXTENSION =>
                 Some(Scope::Object),
             NodeKind::INTERFACE_TYPE_DEFINITION | NodeKind::INTERFACE_TYPE_EXTENSION =>
@@ -105,7 +109,15 @@ macro_rules! next {
 
 impl<'a, 'tree> Highlighter<'a, 'tree> {
     fn new(snapshot: &'a Snapshot, file: FileId, nodes: Traverse<'tree>) -> Self {
-        Self { snapshot, file, nodes, tokens: Default::default(), scopes: vec![Scope::Document] }
+        let text = snapshot.file_text(file);
+        Self {
+            snapshot,
+            file,
+            text,
+            nodes,
+            tokens: Default::default(),
+            scopes: vec![Scope::Document],
+        }
     }
 
     fn highlight(mut self) -> Vec<SemanticToken> {
@@ -149,17 +161,22 @@ impl<'a, 'tree> Highlighter<'a, 'tree> {
 
             let at = node.range().start_point;
             let kind = match node.kind() {
-                //TODO missing anonymous symbols
-                "type" | "enum" | "scalar" | "interface" | "union" | "directive" | "on"
+                "type" | "enum" | "scalar" | "interface" | "union" | "input" | "directive"
+                | "extend" | "implements" | "repeatable" | "schema" | "on"
                     if !node.is_named() =>
                     SemanticTokenKind::Keyword,
+                NodeKind::OPERATION_TYPE | NodeKind::BOOLEAN_VALUE | NodeKind::NULL_VALUE =>
+                    SemanticTokenKind::Keyword,
                 // TODO builtin types (ID, String, Int should be defaultLibrary types)
                 NodeKind::TYPE if matches!(self.scope(), Scope::Type) => self.highlight_type(at),
                 NodeKind::NAMED_TYPE if matches!(self.scope(), Scope::UnionMember) =>
                     self.highlight_type(at),
-                NodeKind::DIRECTIVE => SemanticTokenKind::Directive,
+                NodeKind::COMMENT => SemanticTokenKind::Comment,
+                NodeKind::DESCRIPTION | NodeKind::STRING_VALUE => SemanticTokenKind::String,
+                NodeKind::INT_VALUE | NodeKind::FLOAT_VALUE => SemanticTokenKind::Number,
                 NodeKind::ENUM_VALUE => SemanticTokenKind::EnumValue,
-                NodeKind::NAME | NodeKind::DIRECTIVE_NAME => match self.scope() {
+                NodeKind::DIRECTIVE_NAME => SemanticTokenKind::Directive,
+                NodeKind::NAME => match self.scope() {
                     Scope::Argument => SemanticTokenKind::Argument,
                     Scope::Directive => SemanticTokenKind::Directive,
                     Scope::Enum => SemanticTokenKind::Enum,
@@ -175,7 +192,22 @@ impl<'a, 'tree> Highlighter<'a, 'tree> {
                 _ => continue,
             };
 
-            let range = node.range();
+            skip_until = Some(node);
+            self.push(node.range(), kind);
+        }
+    }
+
+    // clients don't necessarily support tokens that span multiple lines (e.g. block strings),
+    // so those are split into a token per line
+    fn push(&mut self, range: Range, kind: SemanticTokenKind) {
+        let mut start_byte = range.start_byte;
+        let mut start_point = range.start_point;
+        for line in self.text[range.start_byte..range.end_byte].split('\n') {
+            let len = line.trim_end_matches('\r').len();
+            let end_point = Point::new(start_point.row, start_point.column + len);
+            let range = Range { start_byte, end_byte: start_byte + len, start_point, end_point };
+            start_byte += line.len() + 1;
+            start_point = Point::new(start_point.row + 1, 0);
             if range.is_empty() {
                 continue;
             }
@@ -188,7 +220,6 @@ impl<'a, 'tree> Highlighter<'a, 'tree> {
                 );
                 assert!(!prev.range.intersects(range));
             }
-            skip_until = Some(node);
             self.tokens.push(token);
         }
     }
@@ -163,6 +163,7 @@ fn test_highlight_directive() {
                     17:16..17:20 :: Directive,
                     17:23..17:26 :: Object,
                     17:29..17:48 :: Type,
+                    19:8..19:13 :: Keyword,
                     19:14..19:15 :: InputObject,
                     19:16..19:20 :: Directive,
                     20:12..20:13 :: Field,
@@ -203,6 +204,7 @@ fn test_highlight_fields() {
                     5:9..5:15 :: Object,
                     6:8..6:13 :: Field,
                     6:15..6:16 :: Interface,
+                    9:4..9:9 :: Keyword,
                     9:10..9:15 :: InputObject,
                     10:8..10:9 :: Field,
                     10:11..10:15 :: Type,
@@ -284,3 +286,58 @@ fn test_highlight_arguments() {
         },
     );
 }
+
+#[test]
+fn test_highlight_literals() {
+    let fixture = fixture!("foo" => r#"
+    """
+    A block
+    description
+    """
+    type Foo @d(s: "s", i: 1, f: 1.5, b: true, n: null, e: RED, l: [1], o: { x: 1 }) {
+        # comment
+        "field"
+        foo(a: Int = 2): Int
+    }
+    "#);
+    test(
+        fixture,
+        hashmap! {
+            "foo" => expect![[r#"
+                [
+                    1:4..1:7 :: String,
+                    2:0..2:11 :: String,
+                    3:0..3:15 :: String,
+                    4:0..4:7 :: String,
+                    5:4..5:8 :: Keyword,
+                    5:9..5:12 :: Object,
+                    5:13..5:15 :: Directive,
+                    5:16..5:17 :: Argument,
+                    5:19..5:22 :: String,
+                    5:24..5:25 :: Argument,
+                    5:27..5:28 :: Number,
+                    5:30..5:31 :: Argument,
+                    5:33..5:36 :: Number,
+                    5:38..5:39 :: Argument,
+                    5:41..5:45 :: Keyword,
+                    5:47..5:48 :: Argument,
+                    5:50..5:54 :: Keyword,
+                    5:56..5:57 :: Argument,
+                    5:59..5:62 :: EnumValue,
+                    5:64..5:65 :: Argument,
+                    5:68..5:69 :: Number,
+                    5:72..5:73 :: Argument,
+                    5:77..5:78 :: Field,
+                    5:80..5:81 :: Number,
+                    6:8..6:17 :: Comment,
+                    7:8..7:15 :: String,
+                    8:8..8:11 :: Field,
+                    8:12..8:13 :: Argument,
+                    8:15..8:18 :: Type,
+                    8:21..8:22 :: Number,
+                    8:25..8:28 :: Type,
+                ]
+            "#]],
+        },
+    );
+}
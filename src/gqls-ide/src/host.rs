@@ -0,0 +1,198 @@
+//! A facade over [`Ide`] and [`Snapshot`] for embedding the analyzer without speaking LSP,
+//! e.g. in a build tool. [`AnalysisHost`] owns the files and projects and applies changes to
+//! them, while an [`Analysis`] answers queries about the state of the host when it was taken.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{
+    Cancellable, Change, Changeset, CompletionConfig, CompletionItem, DefinitionConfig, FileDiagnostics, FileId, FormatConfig, Hover, Ide, LineIndex, Lint, Location, Patch, Position, Project, RenameError, Schema, SemanticToken, Snapshot, SourceChange, SymbolTree, WorkspaceSymbol, VFS
+};
+
+/// The mutable half of the analyzer. Files are identified by their path and belong to any
+/// number of named projects, names only resolve to definitions in the same project.
+#[derive(Default)]
+pub struct AnalysisHost {
+    ide: Ide,
+    projects: HashMap<Project, HashSet<FileId>>,
+    lints: HashMap<Project, HashSet<Lint>>,
+}
+
+impl AnalysisHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text of the file at `path` and add it to `project`, which is created if it
+    /// doesn't exist yet
+    pub fn set_file(
+        &mut self,
+        project: &str,
+        path: impl AsRef<Path>,
+        text: impl Into<String>,
+    ) -> FileId {
+        self.set_files(project, [(path, text)])[0]
+    }
+
+    /// Set the texts of the files at the paths and add them to `project` as a single change,
+    /// which is much cheaper than setting them one by one when loading many files
+    pub fn set_files<P: AsRef<Path>, T: Into<String>>(
+        &mut self,
+        project: &str,
+        files: impl IntoIterator<Item = (P, T)>,
+    ) -> Vec<FileId> {
+        let project = self.ide.intern_project(project.to_owned());
+        let project_files = self.projects.entry(project).or_default();
+        let mut is_added = false;
+        let mut ids = vec![];
+        let mut changes = vec![];
+        for (path, text) in files {
+            let file = self.ide.intern_path(path.as_ref().to_path_buf());
+            is_added |= project_files.insert(file);
+            ids.push(file);
+            changes.push(Change::set(file, text.into()));
+        }
+        // the projects are only sent again if a file joined one, editing is a change of text
+        let mut changeset = Changeset::new(changes);
+        if is_added {
+            changeset = changeset.with_projects(self.projects.clone());
+        }
+        self.ide.load(changeset);
+        ids
+    }
+
+    /// Remove the file at `path` from every project, returning whether it was in any of them
+    pub fn remove_file(&mut self, path: impl AsRef<Path>) -> bool {
+        let file = match self.ide.vfs().get(path) {
+            Some(file) => file,
+            None => return false,
+        };
+        let mut removed = false;
+        for files in self.projects.values_mut() {
+            removed |= files.remove(&file);
+        }
+        if removed {
+            self.ide.load(Changeset::default().with_projects(self.projects.clone()));
+        }
+        removed
+    }
+
+    /// Enable `lints` in `project`, replacing the lints that were enabled before
+    pub fn set_lints(&mut self, project: &str, lints: impl IntoIterator<Item = Lint>) {
+        let project = self.ide.intern_project(project.to_owned());
+        self.lints.insert(project, lints.into_iter().collect());
+        self.ide.load(Changeset::default().with_lints(self.lints.clone()));
+    }
+
+    /// The project named `project`
+    pub fn project(&self, project: &str) -> Project {
+        self.ide.intern_project(project.to_owned())
+    }
+
+    /// Take an [`Analysis`] of the current state. Any further change to the host cancels the
+    /// queries of the analyses taken before it, which then return [`crate::Cancelled`].
+    pub fn analysis(&self) -> Analysis {
+        Analysis { snapshot: self.ide.snapshot() }
+    }
+
+    /// The underlying [`Ide`], for the changes the facade doesn't cover
+    pub fn raw(&mut self) -> &mut Ide {
+        &mut self.ide
+    }
+}
+
+/// An immutable snapshot of an [`AnalysisHost`]. Every query is cancellable, so an analysis
+/// can be moved to another thread while the host keeps applying changes.
+pub struct Analysis {
+    snapshot: Snapshot,
+}
+
+impl Analysis {
+    /// The id of the file at `path`, if the host has seen it
+    pub fn file_id(&self, path: impl AsRef<Path>) -> Option<FileId> {
+        VFS.read().get(path)
+    }
+
+    pub fn file_text(&self, file: FileId) -> Cancellable<Arc<str>> {
+        self.with(|snapshot| snapshot.file_text(file))
+    }
+
+    /// The line index of `file`, to convert the byte columns of points to other encodings
+    pub fn line_index(&self, file: FileId) -> Cancellable<Arc<LineIndex>> {
+        self.with(|snapshot| snapshot.line_index(file))
+    }
+
+    /// The diagnostics of both passes of `file`
+    pub fn diagnostics(&self, file: FileId) -> Cancellable<FileDiagnostics> {
+        self.with(|snapshot| snapshot.file_diagnostics(file))
+    }
+
+    pub fn hover(&self, position: Position) -> Cancellable<Option<Hover>> {
+        self.with(|snapshot| snapshot.hover(position))
+    }
+
+    pub fn completions(
+        &self,
+        position: Position,
+        config: &CompletionConfig,
+    ) -> Cancellable<Vec<CompletionItem>> {
+        self.with(|snapshot| snapshot.completions(position, config))
+    }
+
+    pub fn goto_definition(
+        &self,
+        position: Position,
+        config: &DefinitionConfig,
+    ) -> Cancellable<Vec<Location>> {
+        self.with(|snapshot| snapshot.goto_definition(position, config))
+    }
+
+    pub fn find_references(&self, position: Position) -> Cancellable<Vec<Location>> {
+        self.with(|snapshot| snapshot.find_references(position))
+    }
+
+    pub fn rename(
+        &self,
+        position: Position,
+        to: &str,
+    ) -> Cancellable<Result<SourceChange, RenameError>> {
+        self.with(|snapshot| snapshot.rename(position, to))
+    }
+
+    pub fn document_symbols(&self, file: FileId) -> Cancellable<SymbolTree> {
+        self.with(|snapshot| snapshot.document_symbols(file))
+    }
+
+    /// The definitions in every project whose name contains `query`, ignoring case
+    pub fn workspace_symbols(&self, query: &str) -> Cancellable<Vec<WorkspaceSymbol>> {
+        self.with(|snapshot| snapshot.workspace_symbols(query))
+    }
+
+    pub fn semantic_tokens(&self, file: FileId) -> Cancellable<Vec<SemanticToken>> {
+        self.with(|snapshot| snapshot.semantic_tokens(file))
+    }
+
+    /// The patches that format `file`, empty if it is already formatted or doesn't parse
+    pub fn format(&self, file: FileId, config: &FormatConfig) -> Cancellable<Vec<Patch>> {
+        self.with(|snapshot| snapshot.format(file, config))
+    }
+
+    /// The schema of the project of `file`, with the extensions merged into their definitions
+    pub fn schema(&self, file: FileId) -> Cancellable<Schema> {
+        self.with(|snapshot| snapshot.schema(file))
+    }
+
+    /// The underlying [`Snapshot`], for the queries the facade doesn't cover. These aren't
+    /// guarded against cancellation, see [`Snapshot::catch_cancelled`].
+    pub fn raw(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    fn with<T>(&self, f: impl FnOnce(&Snapshot) -> T) -> Cancellable<T> {
+        self.snapshot.catch_cancelled(f)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use crate::{AnalysisHost, DefinitionConfig, Lint, Location, Point, Position, Range};
+
+#[test]
+fn test_analysis_host_resolves_within_project() {
+    let mut host = AnalysisHost::new();
+    let foo = host.set_file("a", "host/a/foo.graphql", "type Foo { bar: Bar }");
+    let bar = host.set_file("a", "host/a/bar.graphql", "type Bar { id: ID }");
+    host.set_file("b", "host/b/baz.graphql", "type Baz { bar: Bar }");
+
+    let analysis = host.analysis();
+    assert_eq!(analysis.file_id("host/a/foo.graphql"), Some(foo));
+    assert!(analysis.diagnostics(foo).unwrap().is_empty());
+    let position = Position::new(foo, Point::new(0, 16));
+    let range = Range { start: Point::new(0, 5), end: Point::new(0, 8) };
+    assert_eq!(
+        analysis.goto_definition(position, &DefinitionConfig::default()).unwrap(),
+        vec![Location::new(bar, range)]
+    );
+
+    let baz = analysis.file_id("host/b/baz.graphql").unwrap();
+    let diagnostics = analysis.diagnostics(baz).unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics.iter().all(|diag| diag.message.starts_with("unresolved type `Bar`")));
+}
+
+#[test]
+fn test_analysis_host_remove_file() {
+    let mut host = AnalysisHost::new();
+    let foo = host.set_file("default", "host/remove/foo.graphql", "type Foo { bar: Bar }");
+    host.set_file("default", "host/remove/bar.graphql", "type Bar { id: ID }");
+    assert!(host.analysis().diagnostics(foo).unwrap().is_empty());
+
+    assert!(host.remove_file("host/remove/bar.graphql"));
+    assert!(!host.remove_file("host/remove/bar.graphql"));
+    assert_eq!(host.analysis().diagnostics(foo).unwrap().len(), 1);
+}
+
+#[test]
+fn test_analysis_host_set_files() {
+    let mut host = AnalysisHost::new();
+    let files = host.set_files(
+        "default",
+        [
+            ("host/batch/foo.graphql", "type Foo { bar: Bar }"),
+            ("host/batch/bar.graphql", "type Bar { id: ID }"),
+        ],
+    );
+    assert_eq!(files.len(), 2);
+    assert!(host.analysis().diagnostics(files[0]).unwrap().is_empty());
+
+    // the file is already in the project, so only its text changes
+    host.set_file("default", "host/batch/bar.graphql", "type Baz { id: ID }");
+    assert_eq!(host.analysis().diagnostics(files[0]).unwrap().len(), 1);
+}
+
+#[test]
+fn test_analysis_host_lints() {
+    let mut host = AnalysisHost::new();
+    let foo = host.set_file("default", "host/lints/foo.graphql", "type foo { id: ID }");
+    assert!(host.analysis().diagnostics(foo).unwrap().is_empty());
+
+    host.set_lints("default", [Lint::TypeNameCase]);
+    let diagnostics = host.analysis().diagnostics(foo).unwrap();
+    assert_eq!(
+        diagnostics.into_iter().map(|diag| diag.message).collect::<Vec<_>>(),
+        vec!["type `foo` should be in PascalCase: `Foo`"]
+    );
+}
@@ -0,0 +1,308 @@
+use gqls_db::{project_name, DefDatabase, Project, SourceDatabase};
+use gqls_ir::{
+    Arg, EffectiveTypeDefinition, Field, InProject, ItemBodyKind, ItemKind, ItemRes, Name, TypeDefinitionKind
+};
+use gqls_syntax::{Position, RangeExt};
+use std::fmt::{self, Display, Write};
+use vfs::FileId;
+
+use crate::{render, Snapshot};
+
+/// The number of implementors listed in the summary of an interface before the rest are elided
+const MAX_IMPLEMENTORS: usize = 5;
+
+/// The number of fields (or enum values) listed in the definition of a type before eliding the rest
+const MAX_HOVER_FIELDS: usize = 20;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Hover {
+    /// markdown
+    pub contents: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FileSummary {
+    /// projects the file belongs to, with the number of files in each
+    pub projects: Vec<(Project, usize)>,
+    /// names of the types defined (or extended) in the file
+    pub types: Vec<String>,
+    pub diagnostics: usize,
+}
+
+impl Display for FileSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (project, files) in &self.projects {
+            let s = if *files == 1 { "" } else { "s" };
+            writeln!(f, "**project** `{}` ({files} file{s})", project_name(project))?;
+            writeln!(f)?;
+        }
+        match &self.types[..] {
+            [] => writeln!(f, "**types** none")?,
+            types => writeln!(
+                f,
+                "**types** {}",
+                types.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+            )?,
+        }
+        writeln!(f)?;
+        write!(f, "**diagnostics** {}", self.diagnostics)
+    }
+}
+
+impl Snapshot {
+    pub fn hover(&self, position: Position) -> Option<Hover> {
+        // hovering before the first item in the file shows a summary of the file
+        let items = self.items(position.file);
+        if items.iter().all(|(_, item)| position.point < item.range.start_point)
+            && items.schemas.iter().all(|schema| position.point < schema.range.start_point)
+        {
+            let contents = self.file_summary(position.file).to_string();
+            return Some(Hover { contents });
+        }
+
+        let directive = self
+            .name_at(position)
+            .and_then(|name| self.resolve_directive(InProject::new(position.file, name)));
+        if let Some(res) = directive {
+            return self.directive_hover(res);
+        }
+
+        if let Some(res) = self.resolve_field_at(position) {
+            let field = self.field(res);
+            if field.name.range.contains(position.point) {
+                return Some(self.field_hover(&field));
+            }
+        }
+
+        if let Some(arg) = self.arg_at(position) {
+            return Some(self.arg_hover(&arg));
+        }
+
+        let typedef = self
+            .name_at(position)
+            .and_then(|name| self.effective_typedef(InProject::new(position.file, name)));
+        if let Some(typedef) = typedef {
+            return Some(self.typedef_hover(position.file, &typedef));
+        }
+
+        // elsewhere within a schema definition or extension shows the merged schema definition
+        items
+            .schemas
+            .iter()
+            .any(|schema| schema.range.contains(position.point))
+            .then(|| self.schema_hover(position.file))
+    }
+
+    fn schema_hover(&self, file: FileId) -> Hover {
+        let definition = self.schema_definition(file);
+        let mut schema = String::from("schema");
+        for directive in &definition.directives {
+            write!(schema, " {}", render::render_directive(directive)).unwrap();
+        }
+        schema.push_str(" {\n");
+        for operation in &definition.operations {
+            writeln!(schema, "  {operation:?}").unwrap();
+        }
+        schema.push('}');
+        Hover { contents: format!("```graphql\n{schema}\n```") }
+    }
+
+    // the type as it is seen by the rest of the schema, with all extensions merged in
+    fn typedef_hover(&self, file: FileId, typedef: &EffectiveTypeDefinition) -> Hover {
+        let fields = typedef.fields.iter().map(|&field| self.field(field)).collect::<Vec<_>>();
+        let sdl = render_typedef(typedef, &fields, MAX_HOVER_FIELDS);
+        let mut contents = format!("```graphql\n{sdl}\n```");
+        push_docs(&mut contents, typedef.description.as_deref().unwrap_or_default());
+        // the configuration describes the custom scalars that the schema itself doesn't
+        let custom = match typedef.kind {
+            TypeDefinitionKind::Scalar =>
+                self.custom_scalar(InProject::new(file, typedef.name)).unwrap_or_default(),
+            _ => Default::default(),
+        };
+        push_docs(&mut contents, custom.description.as_deref().unwrap_or_default());
+        if let Some(url) = self.specified_by(typedef).or(custom.specified_by) {
+            push_docs(&mut contents, &format!("**specified by** <{url}>"));
+        }
+        push_docs(&mut contents, &self.type_relations(file, typedef));
+        push_docs(&mut contents, &self.extension_docs(typedef));
+        Hover { contents }
+    }
+
+    /// e.g. ``**extended in** `a.graphql`, `b.graphql` ``, or the empty string if there are none
+    fn extension_docs(&self, typedef: &EffectiveTypeDefinition) -> String {
+        let mut files = typedef
+            .parts
+            .iter()
+            .filter(|&&part| self.is_extension(part))
+            .map(|part| format!("`{}`", part.file.display()))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        match &files[..] {
+            [] => String::new(),
+            files => format!("**extended in** {}", files.join(", ")),
+        }
+    }
+
+    /// The url of the `@specifiedBy` directive of any part of the scalar `typedef`
+    fn specified_by(&self, typedef: &EffectiveTypeDefinition) -> Option<String> {
+        typedef.parts.iter().find_map(|&part| match &self.item_body(part)?.kind {
+            ItemBodyKind::Scalar(body) => body.specified_by.as_deref().map(ToOwned::to_owned),
+            _ => None,
+        })
+    }
+
+    /// Markdown summary of the interfaces `typedef` implements and, if it is an interface,
+    /// the types that implement it
+    pub(crate) fn type_relations(&self, file: FileId, typedef: &EffectiveTypeDefinition) -> String {
+        let mut docs = String::new();
+        if !typedef.implementations.is_empty() {
+            write!(docs, "**implements** {}", render_names(&typedef.implementations)).unwrap();
+        }
+        if typedef.kind != TypeDefinitionKind::Interface {
+            return docs;
+        }
+
+        // a type may implement the interface in more than one of its parts
+        let mut implementors = self
+            .implementations(InProject::new(file, typedef.name))
+            .into_iter()
+            .map(|res| self.items(res.file)[res.value].name)
+            .collect::<Vec<_>>();
+        implementors.sort();
+        implementors.dedup();
+        if implementors.is_empty() {
+            return docs;
+        }
+        if !docs.is_empty() {
+            docs.push_str("\n\n");
+        }
+        let elided = implementors.len().saturating_sub(MAX_IMPLEMENTORS);
+        implementors.truncate(MAX_IMPLEMENTORS);
+        write!(docs, "**implemented by** {}", render_names(&implementors)).unwrap();
+        if elided > 0 {
+            write!(docs, " +{elided} more").unwrap();
+        }
+        docs
+    }
+
+    fn field_hover(&self, field: &Field) -> Hover {
+        let mut contents = format!("```graphql\n{field:?}\n```");
+        push_docs(&mut contents, field.description.as_deref().unwrap_or_default());
+        push_docs(&mut contents, &render::render_arg_docs(&field.args));
+        Hover { contents }
+    }
+
+    /// The argument of a field or directive definition whose name is at `position`
+    fn arg_at(&self, position: Position) -> Option<Arg> {
+        let body = self.item_body(self.resolve_item_at(position)?)?;
+        let args = match &body.kind {
+            ItemBodyKind::DirectiveDefinition(body) => body.args.clone(),
+            _ => self.field(self.resolve_field_at(position)?).args,
+        };
+        args.into_iter().find(|arg| arg.name.range.contains(position.point))
+    }
+
+    fn arg_hover(&self, arg: &Arg) -> Hover {
+        let mut contents = format!("```graphql\n{arg:?}\n```");
+        push_docs(&mut contents, arg.description.as_deref().unwrap_or_default());
+        Hover { contents }
+    }
+
+    fn directive_hover(&self, res: ItemRes) -> Option<Hover> {
+        let items = self.items(res.file);
+        let item = &items[res.value];
+        let locations = match item.kind {
+            ItemKind::DirectiveDefinition(idx) => items[idx].locations,
+            ItemKind::TypeDefinition(_) => return None,
+        };
+        let args = &self.item_body(res)?.as_directive().args;
+        let signature = render::render_directive_signature(&item.name, args, locations);
+        let mut contents = format!("```graphql\n{signature}\n```");
+        push_docs(&mut contents, items.description(res.value).unwrap_or_default());
+        push_docs(&mut contents, &render::render_arg_docs(args));
+        Some(Hover { contents })
+    }
+
+    pub fn file_summary(&self, file: FileId) -> FileSummary {
+        let mut projects = self
+            .projects_of(InProject::unit(file))
+            .into_iter()
+            .map(|project| (project, self.project_files(project).len()))
+            .collect::<Vec<_>>();
+        projects.sort();
+
+        let items = self.items(file);
+        let mut types = vec![];
+        for (_, item) in items.iter() {
+            let name = item.name.to_string();
+            if matches!(item.kind, ItemKind::TypeDefinition(_)) && !types.contains(&name) {
+                types.push(name);
+            }
+        }
+
+        FileSummary { projects, types, diagnostics: self.file_diagnostics(file).len() }
+    }
+}
+
+/// The sdl of `typedef` with its extensions merged in, where `fields` are those of `typedef`.
+/// Only the first `max_lines` fields (or enum values) are listed, the rest are elided.
+fn render_typedef(typedef: &EffectiveTypeDefinition, fields: &[Field], max_lines: usize) -> String {
+    let keyword = match typedef.kind {
+        TypeDefinitionKind::Object => "type",
+        TypeDefinitionKind::Interface => "interface",
+        TypeDefinitionKind::Input => "input",
+        TypeDefinitionKind::Scalar => "scalar",
+        TypeDefinitionKind::Enum => "enum",
+        TypeDefinitionKind::Union => "union",
+    };
+    let mut sdl = format!("{keyword} {}", typedef.name);
+    if !typedef.implementations.is_empty() {
+        let interfaces = typedef.implementations.iter().map(|name| name.to_string());
+        write!(sdl, " implements {}", interfaces.collect::<Vec<_>>().join(" & ")).unwrap();
+    }
+    for directive in &typedef.directives {
+        write!(sdl, " {}", render::render_directive(directive)).unwrap();
+    }
+    let lines = match typedef.kind {
+        TypeDefinitionKind::Scalar => return sdl,
+        TypeDefinitionKind::Union => {
+            if !typedef.members.is_empty() {
+                let members = typedef.members.iter().map(|ty| format!("{ty:?}"));
+                write!(sdl, " = {}", members.collect::<Vec<_>>().join(" | ")).unwrap();
+            }
+            return sdl;
+        }
+        TypeDefinitionKind::Enum =>
+            typedef.variants.iter().map(|variant| format!("{variant:?}")).collect::<Vec<_>>(),
+        TypeDefinitionKind::Object
+        | TypeDefinitionKind::Interface
+        | TypeDefinitionKind::Input => fields.iter().map(|field| format!("{field:?}")).collect(),
+    };
+    sdl.push_str(" {\n");
+    for line in lines.iter().take(max_lines) {
+        writeln!(sdl, "  {line}").unwrap();
+    }
+    if lines.len() > max_lines {
+        writeln!(sdl, "  # ... {} more", lines.len() - max_lines).unwrap();
+    }
+    sdl.push('}');
+    sdl
+}
+
+/// e.g. `` `Foo`, `Bar` ``
+fn render_names(names: &[Name]) -> String {
+    names.iter().map(|name| format!("`{name}`")).collect::<Vec<_>>().join(", ")
+}
+
+/// Append a markdown paragraph to the hover contents if there is anything to add
+fn push_docs(contents: &mut String, docs: &str) {
+    let docs = docs.trim_end();
+    if !docs.is_empty() {
+        contents.push_str("\n\n");
+        contents.push_str(docs);
+    }
+}
+
+#[cfg(test)]
+mod tests;
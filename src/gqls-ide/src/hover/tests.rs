@@ -0,0 +1,497 @@
+use expect_test::{expect, Expect};
+use gqls_fixture::{fixture, Fixture};
+use maplit::hashmap;
+
+use crate::{Changeset, CustomScalar, Ide};
+
+fn test(fixture: Fixture, expect: Expect) {
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    for position in fixture.positions() {
+        let hover = snapshot.hover(position).map(|hover| hover.contents);
+        expect.assert_debug_eq(&hover);
+    }
+}
+
+#[test]
+fn test_hover_file_summary() {
+    let fixture = fixture! {
+        "foo" => "
+$
+type Foo {
+    bar: Bar
+}
+
+extend type Foo {
+    baz: Int
+}
+
+directive @qux on FIELD_DEFINITION
+
+scalar S
+"
+        "bar" => "
+type Bar {
+    foo: Unresolved
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "**project** `default` (2 files)\n\n**types** `Foo`, `S`\n\n**diagnostics** 0",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_file_summary_empty_file() {
+    let fixture = fixture! {
+        "foo" => "$"
+        "bar" => "
+type Bar {
+    foo: Unresolved
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "**project** `default` (2 files)\n\n**types** none\n\n**diagnostics** 0",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_file_summary_counts_diagnostics() {
+    let fixture = fixture! {
+        "foo" => "
+$
+type Foo {
+    bar: Unresolved
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "**project** `default` (1 file)\n\n**types** `Foo`\n\n**diagnostics** 1",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_file_summary_of_qualified_project() {
+    let fixture = fixture! {
+        "foo" => "
+$
+type Foo {
+    id: ID!
+}
+"
+    }
+    .with_project("file:///workspace#default", &["foo"]);
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "**project** `default` (1 file)\n\n**types** `Foo`\n\n**diagnostics** 0",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_hover_after_first_item() {
+    let fixture = fixture! {
+        "foo" => "
+type Foo {
+    $
+    bar: Int
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            None
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_directive() {
+    let fixture = fixture! {
+        "foo" => r#"
+directive @$d(
+    "the first arg"
+    a: Int = 1
+    b: String!
+) on FIELD_DEFINITION | OBJECT
+
+type Foo {
+    foo: Int @$d(b: "x")
+}
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\ndirective @d(a: Int = 1, b: String!) on FIELD_DEFINITION | OBJECT\n```\n\n- `a: Int = 1`: the first arg",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_directive_without_args() {
+    let fixture = fixture! {
+        "foo" => "
+directive @d on OBJECT
+
+type Foo @$d {
+    foo: Int
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\ndirective @d on OBJECT\n```",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_schema_definition() {
+    let fixture = fixture! {
+        "foo" => r#"
+type Foo {
+    foo: Int
+}
+
+schema {
+    query: Query
+    $
+}
+"#
+        "bar" => r#"
+extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key"]) {
+    $mutation: Mutation
+}
+
+directive @link(url: String!, import: [String]) on SCHEMA
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\nschema @link(url: \"https://specs.apollo.dev/federation/v2.0\", import: [\"@key\"]) {\n  query: Query\n  mutation: Mutation\n}\n```",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_type_with_extensions() {
+    let fixture = fixture! {
+        "foo" => "
+interface Node { id: ID! }
+
+type Foo implements Node @d {
+    id: ID!
+}
+
+type Bar {
+    foo: $Foo
+}
+
+directive @d on OBJECT
+"
+        "bar" => "
+extend type $Foo @e {
+    bars(first: Int = 10): [Bar!]!
+}
+
+directive @e on OBJECT
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\ntype Foo implements Node @d @e {\n  id: ID!\n  bars(first: Int = 10): [Bar!]!\n}\n```\n\n**implements** `Node`\n\n**extended in** `bar`",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_type_elides_fields() {
+    let fields = (0..22).map(|i| format!("f{i}: Int")).collect::<Vec<_>>().join("\n");
+    let fixture = fixture! {
+        "foo" => format!("type $Foo {{\n{fields}\n}}")
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let contents = ide.snapshot().hover(position).unwrap().contents;
+    assert!(contents.contains("  f19: Int\n  # ... 2 more\n}"), "{contents}");
+    assert!(!contents.contains("f20"));
+}
+
+#[test]
+fn test_hover_interface_implementors() {
+    let fixture = fixture! {
+        "foo" => "
+interface $Node { id: ID! }
+
+interface $Entity implements Node { id: ID! }
+
+type A implements Node { id: ID! }
+type B implements Node { id: ID! }
+type C implements Node { id: ID! }
+"
+        "bar" => "
+type D implements Node { id: ID! }
+type E { id: ID! }
+extend type E implements Node
+type F implements Node & Entity { id: ID! }
+extend type F implements Node
+type G implements Node { id: ID! }
+"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let mut hovers = fixture
+        .positions()
+        .map(|position| snapshot.hover(position).unwrap().contents)
+        .collect::<Vec<_>>();
+    hovers.sort();
+    expect![[r#"
+        [
+            "```graphql\ninterface Entity implements Node {\n  id: ID!\n}\n```\n\n**implements** `Node`\n\n**implemented by** `F`",
+            "```graphql\ninterface Node {\n  id: ID!\n}\n```\n\n**implemented by** `A`, `B`, `C`, `D`, `E` +3 more",
+        ]
+    "#]]
+    .assert_debug_eq(&hovers);
+}
+
+#[test]
+fn test_hover_union_and_enum_with_extensions() {
+    let fixture = fixture! {
+        "foo" => "
+type A { a: Int }
+union $U = A
+enum E { X }
+"
+        "bar" => "
+type B { b: Int }
+extend union U = B
+extend enum $E { Y }
+"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let mut hovers = fixture
+        .positions()
+        .map(|position| snapshot.hover(position).unwrap().contents)
+        .collect::<Vec<_>>();
+    hovers.sort();
+    assert_eq!(
+        hovers,
+        [
+            "```graphql\nenum E {\n  X\n  Y\n}\n```\n\n**extended in** `bar`",
+            "```graphql\nunion U = A | B\n```\n\n**extended in** `bar`",
+        ]
+    );
+}
+
+#[test]
+fn test_hover_scalar_specified_by() {
+    let fixture = fixture! {
+        "foo" => r#"
+scalar $Url @specifiedBy(url: "https://url.spec.whatwg.org")
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\nscalar Url @specifiedBy(url: \"https://url.spec.whatwg.org\")\n```\n\n**specified by** <https://url.spec.whatwg.org>",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_registered_custom_scalar() {
+    let fixture = fixture! {
+        "foo" => r#"
+scalar $DateTime
+scalar $Url @specifiedBy(url: "https://url.spec.whatwg.org")
+"#
+    };
+    let mut ide = Ide::from_fixture_allow_errors(&fixture);
+    let _ = ide.apply(Changeset::default().with_custom_scalars(hashmap! {
+        "default" => hashmap! {
+            "DateTime".to_owned() => CustomScalar {
+                description: Some("An RFC 3339 date-time".to_owned()),
+                specified_by: Some("https://datatracker.ietf.org/doc/html/rfc3339".to_owned()),
+            },
+            "Url".to_owned() => CustomScalar {
+                description: None,
+                specified_by: Some("https://example.com/url".to_owned()),
+            },
+        }
+    }));
+    let snapshot = ide.snapshot();
+    let hovers = fixture
+        .positions()
+        .map(|position| snapshot.hover(position).unwrap().contents)
+        .collect::<Vec<_>>();
+    // the `@specifiedBy` of the schema takes precedence over the registry
+    expect![[r#"
+        [
+            "```graphql\nscalar DateTime\n```\n\nAn RFC 3339 date-time\n\n**specified by** <https://datatracker.ietf.org/doc/html/rfc3339>",
+            "```graphql\nscalar Url @specifiedBy(url: \"https://url.spec.whatwg.org\")\n```\n\n**specified by** <https://url.spec.whatwg.org>",
+        ]
+    "#]]
+    .assert_debug_eq(&hovers);
+}
+
+#[test]
+fn test_hover_type_description() {
+    let fixture = fixture! {
+        "foo" => r#"
+"""
+A **foo**
+
+- with
+- markdown
+"""
+type Foo {
+    bar: Bar
+}
+
+type Bar {
+    foo: $Foo
+}
+"#
+        "bar" => r#"
+extend type $Foo {
+    baz: Int
+}
+
+"a directive"
+directive @d on OBJECT
+
+type Baz @$d {
+    baz: Int
+}
+"#
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let mut hovers = fixture
+        .positions()
+        .map(|position| snapshot.hover(position).unwrap().contents)
+        .collect::<Vec<_>>();
+    hovers.sort();
+    hovers.dedup();
+    expect![[r#"
+        [
+            "```graphql\ndirective @d on OBJECT\n```\n\na directive",
+            "```graphql\ntype Foo {\n  bar: Bar\n  baz: Int\n}\n```\n\nA **foo**\n\n- with\n- markdown\n\n**extended in** `bar`",
+        ]
+    "#]]
+    .assert_debug_eq(&hovers);
+}
+
+#[test]
+fn test_hover_field() {
+    let fixture = fixture! {
+        "foo" => r#"
+type Foo {
+    """
+    The `bar` of a foo
+    """
+    $bar(
+        "the number of bars"
+        first: Int = 10
+    ): [Bar!]!
+}
+
+type Bar {
+    bar: Int
+}
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\nbar(first: Int = 10): [Bar!]!\n```\n\nThe `bar` of a foo\n\n- `first: Int = 10`: the number of bars",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_field_argument() {
+    let fixture = fixture! {
+        "foo" => r#"
+type Foo {
+    bar(
+        "the filter of the bars"
+        filter: BarFilter = { name: "x", limit: 10 }
+        #^
+    ): [Bar!]!
+}
+
+input BarFilter {
+    name: String
+    limit: Int
+}
+
+type Bar {
+    bar: Int
+}
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\nfilter: BarFilter = { name: \"x\", limit: 10 }\n```\n\nthe filter of the bars",
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_hover_directive_definition_argument() {
+    let fixture = fixture! {
+        "foo" => "
+directive @d(a: [Int!] = [1]) on OBJECT
+#            ^
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                "```graphql\na: [Int!] = [1]\n```",
+            )
+        "#]],
+    );
+}
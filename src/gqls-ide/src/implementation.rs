@@ -12,8 +12,7 @@ impl Snapshot {
         };
         self.implementations(InProject::new(position.file, name))
             .into_iter()
-            .map(|res| self.item(res))
-            .map(|item| Location::new(position.file, item.name.range))
+            .map(|res| Location::new(res.file, self.item(res).name.range))
             .collect()
     }
 }
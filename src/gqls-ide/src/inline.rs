@@ -0,0 +1,176 @@
+//! Inlining an input object that is referenced exactly once, its fields take the place of the
+//! argument (or input field) that references it and its definition is removed.
+
+use std::collections::HashSet;
+
+use gqls_db::{DefDatabase, FileData, SourceDatabase};
+use gqls_ir::{ItemBodyKind, ItemKind, ItemRes, TypeDefinitionKind};
+use gqls_syntax::{NodeExt, NodeKind, Position};
+use tree_sitter::Node;
+
+use crate::edit::{deletion_range, reindent, RangeExt};
+use crate::organize::{self, Member};
+use crate::{Location, Patch, Range, Snapshot, SourceChange};
+
+/// An input object that can be inlined into its only reference
+pub(crate) struct Inlining {
+    res: ItemRes,
+    reference: Location,
+}
+
+impl Snapshot {
+    /// The patches that inline the input object named at `position` (its definition or its only
+    /// reference), `None` if it isn't referenced exactly once by an argument or input field that
+    /// has nothing but a name and the (possibly non-null) input object as its type
+    pub fn inline_type(&self, position: Position) -> Option<SourceChange> {
+        self.inlining(position)?.change(self)
+    }
+
+    /// The input object named at `position` if it can be inlined, without building the change
+    /// that inlines it
+    pub(crate) fn inlining(&self, position: Position) -> Option<Inlining> {
+        let res = match self.resolve_item_name_at(position)?.try_into_item().ok()?[..] {
+            [res] => res,
+            // the extensions would have to be inlined as well
+            _ => return None,
+        };
+        let item = self.item(res);
+        match item.kind {
+            ItemKind::TypeDefinition(idx)
+                if self.typedef(res.file, idx).kind == TypeDefinitionKind::Input => {}
+            _ => return None,
+        }
+        let definition = Range::from(item.range);
+        let reference = match self.input_references(res)[..] {
+            [reference] => reference,
+            _ => return None,
+        };
+        if reference.file == res.file && definition.intersects(reference.range) {
+            return None;
+        }
+
+        let inlining = Inlining { res, reference };
+        inlining.parts(self, &self.file_data(res.file), &self.file_data(reference.file))?;
+        Some(inlining)
+    }
+
+    /// The types that name the input object `res`, i.e. those of input fields and the arguments
+    /// of fields and directives
+    fn input_references(&self, res: ItemRes) -> Vec<Location> {
+        let name = self.item(res).name;
+        let mut references = vec![];
+        for (&file, items) in self.project_items(res.project()).iter() {
+            for (idx, _) in items.iter() {
+                let body = match self.item_body(ItemRes::new(file, idx)) {
+                    Some(body) => body,
+                    None => continue,
+                };
+                let fields = body.fields_slice().unwrap_or(&[]);
+                let args = match &body.kind {
+                    ItemBodyKind::DirectiveDefinition(directive) => directive.args.as_slice(),
+                    _ => &[],
+                };
+                let tys = fields.iter().map(|field| &field.ty).chain(
+                    fields.iter().flat_map(|field| &field.args).chain(args).map(|arg| &arg.ty),
+                );
+                references.extend(
+                    tys.filter(|ty| ty.name() == name)
+                        .map(|ty| Location::new(file, ty.name().range)),
+                );
+            }
+        }
+        references
+    }
+}
+
+impl Inlining {
+    /// The change that replaces the reference with the fields and removes the definition
+    pub(crate) fn change(&self, snapshot: &Snapshot) -> Option<SourceChange> {
+        let data = snapshot.file_data(self.res.file);
+        let reference_data = snapshot.file_data(self.reference.file);
+        let (members, arg) = self.parts(snapshot, &data, &reference_data)?;
+        let (first, last) = (members.first()?, members.last()?);
+        let text = &reference_data.text;
+
+        // an argument on a line of its own is replaced by the fields on lines of their own (along
+        // with their comments), otherwise the fields are listed inline
+        let line_start = text[..arg.start_byte()].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[arg.end_byte()..].find('\n').map_or(text.len(), |i| arg.end_byte() + i);
+        let before = &text[line_start..arg.start_byte()];
+        let after = &text[arg.end_byte()..line_end];
+        let fields = if before.trim().is_empty() {
+            // a trailing comment would swallow whatever follows the argument
+            let end = if after.trim().is_empty() { last.last } else { last.node };
+            let fields = &data.text[first.first.start_byte()..end.end_byte()];
+            reindent(fields, first.range().start.column, before)
+        } else {
+            let fields = members.iter().map(|member| member.node.text(&data.text));
+            fields.collect::<Vec<_>>().join(", ")
+        };
+
+        let definition = Range::from(snapshot.item(self.res).range);
+        let mut change = SourceChange::default();
+        change.insert(self.reference.file, Patch::new(arg.range().into(), fields));
+        let deletion = deletion_range(&data.text, definition);
+        change.insert(self.res.file, Patch::new(deletion, String::new()));
+        Some(change)
+    }
+
+    /// The fields of the input object and the argument (or input field) they take the place of,
+    /// `None` if they can't take its place
+    fn parts<'a>(
+        &self,
+        snapshot: &Snapshot,
+        data: &'a FileData,
+        reference_data: &'a FileData,
+    ) -> Option<(Vec<Member<'a, 'a>>, Node<'a>)> {
+        let block = data
+            .tree
+            .root_node()
+            .named_node_at(snapshot.item(self.res).name.range.start_point)?
+            .parent_of_kind(NodeKind::INPUT_OBJECT_TYPE_DEFINITION)
+            .and_then(organize::members_of)?;
+        if block.has_error() {
+            return None;
+        }
+        let members = organize::members(&data.text, block)?;
+        if members.is_empty() {
+            return None;
+        }
+
+        let text = &reference_data.text;
+        let arg = reference_data
+            .tree
+            .root_node()
+            .named_node_at(self.reference.range.start)?
+            .parents()
+            .find(|node| {
+                !matches!(
+                    node.kind(),
+                    NodeKind::NAMED_TYPE | NodeKind::NON_NULL_TYPE | NodeKind::TYPE
+                )
+            })
+            .filter(|node| node.kind() == NodeKind::INPUT_VALUE_DEFINITION && !node.has_error())?;
+        let is_plain = [NodeKind::DESCRIPTION, NodeKind::DEFAULT_VALUE, NodeKind::DIRECTIVES]
+            .into_iter()
+            .all(|kind| arg.child_of_kind(kind).is_none());
+        if !is_plain {
+            return None;
+        }
+
+        // the fields must not clash with the other arguments
+        let parent = arg.parent()?;
+        let siblings = parent
+            .children_of_kind(&mut parent.walk(), NodeKind::INPUT_VALUE_DEFINITION)
+            .filter(|&sibling| sibling != arg)
+            .filter_map(|sibling| Some(sibling.name_node()?.text(text)))
+            .collect::<HashSet<_>>();
+        if members.iter().any(|member| siblings.contains(member.name)) {
+            return None;
+        }
+        Some((members, arg))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use gqls_fixture::{fixture, Fixture};
+use gqls_syntax::Position;
+use ropey::Rope;
+
+use crate::{Ide, Point};
+
+// the `$` of the fixtures is placed right before the name of the type to be inlined
+fn positions(fixture: &Fixture) -> impl Iterator<Item = Position> + '_ {
+    fixture.positions().map(|position| {
+        Position::new(position.file, Point::new(position.point.row, position.point.column + 1))
+    })
+}
+
+#[track_caller]
+fn test(fixture: &Fixture, expected: HashMap<&str, &str>) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let snapshot = ide.snapshot();
+    for position in positions(fixture) {
+        let patches = snapshot.inline_type(position).expect("expected the type to be inlined");
+        let mut actual = HashMap::new();
+        for mut file_patches in patches {
+            let mut rope = Rope::from_str(&snapshot.file_text(file_patches.file));
+            // apply the later patches first so the ranges of the earlier ones are still valid
+            file_patches.patches.sort_by_key(|patch| patch.range.start);
+            for patch in file_patches.patches.iter().rev() {
+                let _ = patch.apply(&mut rope);
+            }
+            actual.insert(file_patches.file.to_str().unwrap(), rope.to_string());
+        }
+        let expected = expected.iter().map(|(&file, &text)| (file, text.to_owned())).collect();
+        assert_eq!(actual, expected);
+    }
+}
+
+#[test]
+fn test_inline_input_into_arguments() {
+    let fixture = fixture! {
+        "foo" => r#"type Query {
+  foo(id: ID, input:$FooInput!): Int
+}
+
+input$FooInput {
+  a: Int
+  b: String = "b"
+}
+"#
+    };
+    test(
+        &fixture,
+        HashMap::from([(
+            "foo",
+            "type Query {\n  foo(id: ID, a: Int, b: String = \"b\"): Int\n}\n",
+        )]),
+    );
+}
+
+#[test]
+fn test_inline_input_on_its_own_line() {
+    let fixture = fixture! {
+        "query" => r#"type Query {
+  foo(
+    id: ID
+    input:$FooInput
+  ): Int
+}
+"#
+        "input" => r#"scalar S
+
+input FooInput {
+  # about a
+  a: Int
+  b: [Int!] @deprecated # trailing
+}
+"#
+    };
+    test(
+        &fixture,
+        HashMap::from([
+            (
+                "query",
+                r#"type Query {
+  foo(
+    id: ID
+    # about a
+    a: Int
+    b: [Int!] @deprecated # trailing
+  ): Int
+}
+"#,
+            ),
+            ("input", "scalar S\n"),
+        ]),
+    );
+}
+
+#[test]
+fn test_inline_input_into_input_fields() {
+    let fixture = fixture! {
+        "foo" => "input Bar { foo:$Foo! c: Int }\n\ninput Foo { a: Int b: Int }\n"
+    };
+    test(&fixture, HashMap::from([("foo", "input Bar { a: Int, b: Int c: Int }\n")]));
+}
+
+#[test]
+fn test_inline_input_not_applicable() {
+    let fixture = fixture! {
+        "twice" => "type Query { a(x:$Twice): Int b(y: Twice): Int }\ninput Twice { t: Int }"
+        "list" => "type Query { c(x:$[Listed]): Int }\ninput Listed { l: Int }"
+        "default" => "type Query { d(x:$Defaulted = {}): Int }\ninput Defaulted { d: Int }"
+        "clash" => "type Query { e(e: Int, x:$Clash): Int }\ninput Clash { e: Int }"
+        "object" => "type Query { f(x:$Object): Int }\ntype Object { o: Int }"
+        "unused" => "input$Unused { u: Int }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    for position in positions(&fixture) {
+        assert!(ide.snapshot().inline_type(position).is_none(), "{position:?}");
+    }
+}
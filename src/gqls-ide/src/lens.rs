@@ -0,0 +1,95 @@
+use gqls_db::DefDatabase;
+use gqls_ir::{ItemKind, TypeDefinitionKind};
+use gqls_syntax::Position;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use vfs::FileId;
+
+use crate::{Location, Range, Snapshot};
+
+/// An unresolved code lens, the locations are computed lazily by [`Snapshot::resolve_code_lens`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CodeLens {
+    pub position: Position,
+    pub range: Range,
+    pub kind: CodeLensKind,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CodeLensKind {
+    Implementations,
+    References,
+}
+
+impl CodeLensKind {
+    pub fn title(self, count: usize) -> String {
+        let noun = match self {
+            CodeLensKind::Implementations => "implementation",
+            CodeLensKind::References => "reference",
+        };
+        let s = if count == 1 { "" } else { "s" };
+        format!("{count} {noun}{s}")
+    }
+}
+
+impl Display for CodeLensKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeLensKind::Implementations => write!(f, "implementations"),
+            CodeLensKind::References => write!(f, "references"),
+        }
+    }
+}
+
+impl FromStr for CodeLensKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "implementations" => Ok(CodeLensKind::Implementations),
+            "references" => Ok(CodeLensKind::References),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Snapshot {
+    pub fn code_lenses(&self, file: FileId) -> Vec<CodeLens> {
+        let items = self.items(file);
+        let mut lenses = vec![];
+        for (_, item) in items.iter() {
+            let typedef = match item.kind {
+                ItemKind::TypeDefinition(idx) => &items[idx],
+                ItemKind::DirectiveDefinition(_) => continue,
+            };
+
+            // the lenses are shown on the definition only
+            if typedef.is_ext {
+                continue;
+            }
+
+            let position = Position::new(file, item.name.range.start_point);
+            let range = item.name.range.into();
+            if typedef.kind == TypeDefinitionKind::Interface {
+                lenses.push(CodeLens { position, range, kind: CodeLensKind::Implementations });
+            }
+            lenses.push(CodeLens { position, range, kind: CodeLensKind::References });
+        }
+        lenses
+    }
+
+    pub fn resolve_code_lens(&self, lens: CodeLens) -> Vec<Location> {
+        match lens.kind {
+            CodeLensKind::Implementations => self.goto_implementation(lens.position),
+            // the definition itself is not interesting to count
+            CodeLensKind::References => self
+                .find_references(lens.position)
+                .into_iter()
+                .filter(|&location| location != Location::new(lens.position.file, lens.range))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use expect_test::{expect, Expect};
+use gqls_fixture::{fixture, Fixture};
+
+use crate::Ide;
+
+fn test(fixture: Fixture, expect: Expect) {
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let lenses = snapshot
+        .code_lenses(Path::new("foo"))
+        .into_iter()
+        .map(|lens| {
+            let count = snapshot.resolve_code_lens(lens).len();
+            format!("{:?} {}", lens.range, lens.kind.title(count))
+        })
+        .collect::<Vec<_>>();
+    expect.assert_debug_eq(&lenses);
+}
+
+#[test]
+fn test_code_lenses() {
+    let fixture = fixture! {
+        "foo" => "
+interface Iface {
+    i: Int
+}
+
+type Foo implements Iface {
+    i: Int
+    bar: Bar
+}
+
+extend type Foo {
+    foos: [Foo!]!
+}
+
+directive @d on FIELD_DEFINITION
+"
+        "bar" => "
+type Bar implements Iface {
+    i: Int
+    foo: Foo
+}
+"
+    };
+    test(
+        fixture,
+        expect![[r#"
+            [
+                "1:10..1:15 2 implementations",
+                "1:10..1:15 0 references",
+                "5:5..5:8 3 references",
+            ]
+        "#]],
+    );
+}
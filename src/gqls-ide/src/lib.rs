@@ -1,37 +1,90 @@
 #![deny(rust_2018_idioms)]
 
+mod analysis;
+mod assists;
 mod completions;
+mod convert;
 mod def;
 mod diagnostics;
 mod edit;
+mod extract;
+mod features;
+mod federation;
+mod format;
+mod hierarchy;
 mod highlight;
+mod host;
+mod hover;
 mod implementation;
+mod inline;
+mod lens;
+mod links;
+mod lints;
 mod macros;
+mod organize;
 mod references;
 mod rename;
+mod render;
 mod resolve;
+mod schema;
+mod signature_help;
 mod symbols;
+mod type_info;
 mod typedef;
 
-pub use self::completions::{CompletionItem, CompletionItemKind};
-use self::diagnostics::Diagnostics;
-pub use self::diagnostics::{Diagnostic, DiagnosticLabel, ErrorCode, FileDiagnostics, Severity};
-pub use self::edit::{Change, ChangeKind, Changeset, FilePatches, Patch, Point, Range};
+pub use self::analysis::ProjectAnalysis;
+pub use self::assists::{Assist, AssistConfig, AssistId, AssistKind, AssistResolveStrategy};
+pub use self::completions::{
+    CompletionConfig, CompletionItem, CompletionItemKind, Docstrings, Locality, TypeWrappers
+};
+pub use self::convert::{TypeConversion, TypeConversionKind};
+pub use self::def::DefinitionConfig;
+pub use self::diagnostics::{
+    Diagnostic, DiagnosticLabel, DiagnosticPass, Diagnostics, ErrorCode, FileDiagnostics, Fix, Severity
+};
+pub use self::edit::{Change, ChangeKind, Changeset, FilePatches, Patch, Point, Range, SourceChange};
+pub use self::extract::{ExtractedType, EXTRACTED_TYPE};
+pub use self::features::{DocumentFeatures, DocumentKind, Feature};
+pub use self::federation::{FieldSetDirective, FEDERATION_PRELUDE, FEDERATION_PRELUDE_PATH};
+pub use self::format::{format, FormatConfig};
+pub use gqls_db::DEFAULT_MAX_FILE_SIZE;
+pub use gqls_ir::{BUILTINS, BUILTINS_PATH};
+pub use self::hierarchy::{HierarchyCall, HierarchyItem};
 pub use self::highlight::{SemanticToken, SemanticTokenKind};
+pub use self::host::{Analysis, AnalysisHost};
+pub use self::hover::{FileSummary, Hover};
+pub use self::lens::{CodeLens, CodeLensKind};
+pub use self::links::{DocumentLink, DocumentLinkTarget};
+pub use self::lints::{Lint, LintOverrides, UnknownLint};
 pub use self::rename::RenameError;
+pub use self::schema::{
+    AppliedDirective, Schema, SchemaDependency, SchemaDirective, SchemaEnumValue, SchemaField, SchemaInputValue, SchemaType, SchemaTypeKind, TypeRef
+};
+pub use self::signature_help::{SignatureHelp, SignatureParameter};
 pub use self::symbols::{DocumentSymbol, SymbolKind, SymbolTree, WorkspaceSymbol};
+pub use self::type_info::TypeInfo;
 use gqls_ir::InProject;
+use gqls_syntax::{NodeExt, Tree};
 pub use gqls_syntax::{Position, RangeExt};
 use parking_lot::RwLock;
+use serde::Serialize;
 pub use tree_sitter;
 pub use vfs::{FileId, Vfs};
 
 use std::collections::{HashMap, HashSet};
+use std::io;
 use std::ops::Deref;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use gqls_db::{Database, FileData, GqlsDatabase, ParallelDatabase, Project, SourceDatabase};
+pub use gqls_db::salsa::Cancelled;
+pub use gqls_db::{
+    project_name, qualify_project, CustomScalar, CustomScalars, GeneratedFiles, LineIndex, PositionEncoding, Project, RootTypes
+};
+use gqls_db::{
+    Database, DefDatabase, FileData, GqlsDatabase, ItemCache, ParallelDatabase, SourceDatabase
+};
 use once_cell::sync::Lazy;
 use ropey::Rope;
 use std::fmt::{self, Debug};
@@ -39,19 +92,52 @@ use std::fmt::{self, Debug};
 // bit of a hack, there is probably a nicer way (we need access to the interner for `path` related conversion)
 pub static VFS: Lazy<RwLock<Vfs>> = Lazy::new(Default::default);
 
-#[derive(Default)]
 pub struct Ide {
     db: GqlsDatabase,
     file_ropes: HashMap<FileId, Rope>,
+    lints: Arc<HashMap<Project, HashSet<Lint>>>,
+    field_set_directives: Arc<HashMap<Project, Vec<FieldSetDirective>>>,
+    /// the files that link the federation spec, see [`Ide::link_federation`]
+    federation_links: HashSet<FileId>,
+}
+
+impl Default for Ide {
+    fn default() -> Self {
+        let mut ide = Self {
+            db: Default::default(),
+            file_ropes: Default::default(),
+            lints: Default::default(),
+            field_set_directives: Default::default(),
+            federation_links: Default::default(),
+        };
+        ide.db.set_project_groups(Default::default());
+        ide.db.set_import_projects(Default::default());
+        ide.db.set_max_file_size(gqls_db::DEFAULT_MAX_FILE_SIZE);
+        ide.db.set_lossy_files(Default::default());
+        ide.db.set_custom_scalars(Default::default());
+        ide.db.set_root_types(Default::default());
+        ide.db.set_generated_files(Default::default());
+        ide.db.set_lint_directories(Default::default());
+        // names resolve to the builtins when the project doesn't define them itself
+        ide.patch_tree(&Change::set(gqls_ir::builtins_file(), gqls_ir::BUILTINS.to_owned()));
+        ide
+    }
 }
 
 #[derive(Default, Debug, PartialEq, Eq, Clone)]
 pub struct ChangesetSummary {
+    /// the [`DiagnosticPass::Syntax`] diagnostics of the files of the affected projects
     pub diagnostics: Diagnostics,
+    /// the projects containing a changed file, their [`DiagnosticPass::Local`] and
+    /// [`DiagnosticPass::Project`] diagnostics are left to the caller as they are comparatively
+    /// expensive
+    pub projects: HashSet<Project>,
 }
 
 pub struct Snapshot {
     snapshot: gqls_db::Snapshot<GqlsDatabase>,
+    lints: Arc<HashMap<Project, HashSet<Lint>>>,
+    field_set_directives: Arc<HashMap<Project, Vec<FieldSetDirective>>>,
 }
 
 impl Deref for Snapshot {
@@ -62,9 +148,59 @@ impl Deref for Snapshot {
     }
 }
 
+/// The result of a request that is abandoned if a change is applied while it is running
+pub type Cancellable<T> = Result<T, Cancelled>;
+
 impl Snapshot {
-    pub fn syntax_tree(&self, file: FileId) -> String {
-        self.file_tree(file).root_node().to_sexp()
+    /// Run a request against the snapshot. Applying a change cancels all outstanding snapshots,
+    /// which unwinds from the next query they make, so long running requests stop early
+    /// rather than holding up the change.
+    pub fn catch_cancelled<T>(&self, f: impl FnOnce(&Self) -> T) -> Cancellable<T> {
+        Cancelled::catch(AssertUnwindSafe(|| f(self)))
+    }
+
+    /// The line index of the file, see [`LineIndex`]
+    pub fn line_index(&self, file: FileId) -> Arc<LineIndex> {
+        self.snapshot.line_index(file)
+    }
+
+    pub fn file_text(&self, file: FileId) -> Arc<str> {
+        self.snapshot.file_text(file)
+    }
+
+    /// The s-expression of the parse tree of the file, or of the smallest named node that
+    /// covers `range`
+    pub fn syntax_tree(&self, file: FileId, range: Option<Range>) -> String {
+        let root = self.file_tree(file).root_node();
+        range
+            .and_then(|range| root.named_descendant_for_point_range(range.start, range.end))
+            .unwrap_or(root)
+            .to_sexp()
+    }
+
+    /// The text of the comments of the file, without the leading `#`
+    pub fn comments(&self, file: FileId) -> Vec<String> {
+        let text = self.file_text(file);
+        gqls_syntax::traverse_preorder(&self.file_tree(file))
+            .filter(|node| node.kind() == gqls_syntax::NodeKind::COMMENT)
+            .map(|node| node.text(&text).trim_start_matches('#').to_owned())
+            .collect()
+    }
+
+    /// The files of all of `projects`, sorted and without duplicates
+    pub fn files_of(&self, projects: &HashSet<Project>) -> Vec<FileId> {
+        let mut files = projects
+            .iter()
+            .flat_map(|&project| self.project_files(project))
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// The files of every project, sorted and without duplicates
+    pub fn all_files(&self) -> Vec<FileId> {
+        self.files_of(&self.projects().keys().copied().collect())
     }
 }
 
@@ -79,12 +215,29 @@ impl VfsProxy {
     pub fn get(&self, path: impl AsRef<Path>) -> Option<FileId> {
         VFS.read().get(path)
     }
+
+    pub fn set_overlay(&self, file: FileId, text: String) {
+        VFS.write().set_overlay(file, text)
+    }
+
+    pub fn remove_overlay(&self, file: FileId) -> Option<String> {
+        VFS.write().remove_overlay(file)
+    }
+
+    /// The contents of the open buffer of `file`, see [`Vfs::overlay`]
+    pub fn overlay(&self, file: FileId) -> Option<String> {
+        VFS.read().overlay(file).map(ToOwned::to_owned)
+    }
 }
 
 impl Ide {
     pub fn snapshot(&self) -> Snapshot {
         self.db.unwind_if_cancelled();
-        Snapshot { snapshot: self.db.snapshot() }
+        Snapshot {
+            snapshot: self.db.snapshot(),
+            lints: Arc::clone(&self.lints),
+            field_set_directives: Arc::clone(&self.field_set_directives),
+        }
     }
 
     pub fn intern_path(&mut self, path: PathBuf) -> FileId {
@@ -104,33 +257,160 @@ impl Ide {
         self.apply_(changeset.into())
     }
 
+    /// Apply the changeset without computing any diagnostics, returning the affected projects.
+    /// This is for callers that compute the diagnostics themselves, e.g. to report progress.
+    pub fn load(&mut self, changeset: impl Into<Changeset>) -> HashSet<Project> {
+        self.load_(changeset.into())
+    }
+
+    /// Reuse the items of the index cache in `dir` for the files that haven't changed since it
+    /// was written (see [`Ide::save_index_cache`]), returning the number of cached files
+    pub fn load_index_cache(&mut self, dir: &Path) -> io::Result<usize> {
+        let cache = gqls_db::cache::read(dir)?;
+        let len = cache.as_ref().map_or(0, ItemCache::len);
+        self.db.set_item_cache(cache.map(Arc::new));
+        Ok(len)
+    }
+
+    /// Write the items of the files of every project to the index cache in `dir`
+    pub fn save_index_cache(&self, dir: &Path) -> io::Result<()> {
+        let files = self.db.projects().values().flatten().copied().collect::<HashSet<_>>();
+        gqls_db::cache::write(dir, &self.db.collect_item_cache(files))
+    }
+
     #[must_use]
     fn apply_(&mut self, changeset: Changeset) -> ChangesetSummary {
+        let affected_projects = self.load_(changeset);
+        let snapshot = self.snapshot();
+        let diagnostics = affected_projects
+            .iter()
+            .flat_map(|&project| snapshot.pass_diagnostics(project, DiagnosticPass::Syntax))
+            .collect();
+        ChangesetSummary { diagnostics, projects: affected_projects }
+    }
+
+    fn load_(&mut self, changeset: Changeset) -> HashSet<Project> {
         self.db.request_cancellation();
+        // the links of every file are scanned again if the files of the projects changed
+        let rescan = changeset.projects.is_some();
         if let Some(projects) = changeset.projects {
             self.db.set_projects(Arc::new(projects));
         }
 
+        if let Some(lints) = changeset.lints {
+            self.lints = Arc::new(lints);
+        }
+
+        if let Some(lints) = changeset.directory_lints {
+            let directories = lints.keys().cloned().collect();
+            for (dir, overrides) in lints {
+                self.db.set_directory_lints(dir, Arc::new(overrides));
+            }
+            self.db.set_lint_directories(Arc::new(directories));
+        }
+
+        if let Some(directives) = changeset.field_set_directives {
+            self.field_set_directives = Arc::new(directives);
+        }
+
+        if let Some(groups) = changeset.project_groups {
+            self.db.set_project_groups(Arc::new(groups));
+        }
+
+        if let Some(projects) = changeset.import_projects {
+            self.db.set_import_projects(Arc::new(projects));
+        }
+
+        if let Some(size) = changeset.max_file_size {
+            let previous = self.db.max_file_size();
+            self.db.set_max_file_size(size);
+            // the files that crossed the limit are parsed again (shallowly or not)
+            let crossed = self
+                .file_ropes
+                .iter()
+                .filter(|(_, rope)| (rope.len_bytes() > previous) != (rope.len_bytes() > size))
+                .map(|(&file, rope)| (file, rope.to_string()))
+                .collect::<Vec<_>>();
+            for (file, text) in crossed {
+                let tree = self.parse(&text, None);
+                self.db.set_file_data(file, FileData::new(text, tree));
+            }
+        }
+
+        if let Some(scalars) = changeset.custom_scalars {
+            self.db.set_custom_scalars(Arc::new(scalars));
+        }
+
+        if let Some(root_types) = changeset.root_types {
+            self.db.set_root_types(Arc::new(root_types));
+        }
+
+        if let Some(generated) = changeset.generated_files {
+            self.db.set_generated_files(Arc::new(generated));
+        }
+
         changeset.changes.iter().for_each(|change| self.apply_change(change));
+        let changed = changeset.changes.iter().map(|change| change.file).collect();
+        self.link_federation((!rescan).then(|| changed));
         let snapshot = self.snapshot();
 
-        let affected_projects = changeset
+        changeset
             .changes
             .iter()
             .map(|change| change.file)
             .flat_map(|file| snapshot.projects_of(InProject::unit(file)))
-            .collect::<HashSet<_>>();
+            .collect()
+    }
+
+    // projects that link the federation spec implicitly include the federation prelude. Only the
+    // `changed` files are scanned for a link, or the files of every project if `None`.
+    fn link_federation(&mut self, changed: Option<Vec<FileId>>) {
+        let prelude = self.intern_path(FEDERATION_PRELUDE_PATH.clone());
+        let snapshot = self.snapshot();
+        let (files, rescan) = match changed {
+            Some(files) => (files, false),
+            None => (snapshot.projects().values().flatten().copied().collect(), true),
+        };
+        let mut relinked = rescan;
+        for file in files.into_iter().filter(|&file| file != prelude) {
+            relinked |= match snapshot.links_federation(file) {
+                true => self.federation_links.insert(file),
+                false => self.federation_links.remove(&file),
+            };
+        }
+        if !relinked {
+            return;
+        }
+
+        let mut projects = HashMap::clone(&snapshot.projects());
+        let mut changed = false;
+        for files in projects.values_mut() {
+            let linked = files.iter().any(|file| self.federation_links.contains(file));
+            changed |= if linked { files.insert(prelude) } else { files.remove(&prelude) };
+        }
+        drop(snapshot);
 
-        affected_projects.iter().map(|project| snapshot.diagnostics(project)).fold(
-            Default::default(),
-            |mut summary, diagnostics| {
-                summary.diagnostics.extend(diagnostics);
-                summary
-            },
-        )
+        if changed {
+            if !self.file_ropes.contains_key(&prelude) {
+                self.patch_tree(&Change::set(prelude, FEDERATION_PRELUDE.to_owned()));
+            }
+            self.db.set_projects(Arc::new(projects));
+        }
     }
 
     fn apply_change(&mut self, change: &Change) {
+        // a patched file stays as lossy as it was
+        if let ChangeKind::Set(_) = change.kind {
+            let lossy_files = self.db.lossy_files();
+            if lossy_files.contains(&change.file) != change.lossy {
+                let mut lossy_files = HashSet::clone(&lossy_files);
+                match change.lossy {
+                    true => lossy_files.insert(change.file),
+                    false => lossy_files.remove(&change.file),
+                };
+                self.db.set_lossy_files(Arc::new(lossy_files));
+            }
+        }
         self.patch_tree(change);
     }
 
@@ -141,23 +421,33 @@ impl Ide {
                 let mut rope = self.file_ropes.get(&file).cloned().expect("patch on initial edit");
                 let edit = patch.apply(&mut rope);
                 let text = rope.to_string();
+                // the tree of a shallow file isn't the tree of its text, so it can't be edited
+                let was_shallow = self.db.is_shallow(file);
                 let mut old =
                     self.file_ropes.insert(file, rope).map(|_| self.db.file_tree(file)).unwrap();
                 old.edit(&edit);
-                let tree = gqls_syntax::parse(&text, Some(&old));
+                let tree = self.parse(&text, (!was_shallow).then(|| &old));
                 FileData::new(text, tree)
             }
             ChangeKind::Set(text) => {
                 let rope = Rope::from_str(text);
                 self.file_ropes.insert(file, rope);
-                FileData::new(text, gqls_syntax::parse_fresh(text))
+                FileData::new(text, self.parse(text, None))
             }
         };
         self.db.set_file_data(file, data);
     }
+
+    /// Parse `text`, only its definitions if it is larger than the maximum file size
+    fn parse(&self, text: &str, old: Option<&Tree>) -> Tree {
+        match text.len() > self.db.max_file_size() {
+            true => gqls_syntax::parse_shallow(text),
+            false => gqls_syntax::parse(text, old),
+        }
+    }
 }
 
-#[derive(Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord)]
+#[derive(Eq, PartialEq, Copy, Clone, Hash, PartialOrd, Ord, Serialize)]
 pub struct Location {
     pub file: FileId,
     pub range: Range,
@@ -0,0 +1,69 @@
+//! Document links, i.e. the urls of `@specifiedBy` directives of scalars and the files imported
+//! by graphql-import comments.
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{ItemKind, TypeDefinitionKind};
+use vfs::FileId;
+
+use crate::{Point, Range, Snapshot};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DocumentLink {
+    /// the range of the url or path (excluding the quotes if it is written on a single line)
+    pub range: Range,
+    pub target: DocumentLinkTarget,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DocumentLinkTarget {
+    Url(String),
+    File(FileId),
+}
+
+impl Snapshot {
+    pub fn document_links(&self, file: FileId) -> Vec<DocumentLink> {
+        let items = self.items(file);
+        let text = self.file_text(file);
+        let mut links = vec![];
+        for (_, item) in items.iter() {
+            let typedef = match item.kind {
+                ItemKind::TypeDefinition(idx) => &items[idx],
+                ItemKind::DirectiveDefinition(_) => continue,
+            };
+            if typedef.kind != TypeDefinitionKind::Scalar {
+                continue;
+            }
+            for directive in &typedef.directives {
+                let (target, argument) = match directive.specified_by() {
+                    Some(url) => url,
+                    None => continue,
+                };
+                let range = argument.value_range;
+                let quotes = if text[range.start_byte..].starts_with("\"\"\"") { 3 } else { 1 };
+                let range = if range.start_point.row == range.end_point.row {
+                    let Point { row, column } = range.start_point;
+                    Range {
+                        start: Point::new(row, column + quotes),
+                        end: Point::new(row, range.end_point.column - quotes),
+                    }
+                } else {
+                    range.into()
+                };
+                let target = DocumentLinkTarget::Url(target.to_owned());
+                links.push(DocumentLink { range, target });
+            }
+        }
+
+        // imports of files that aren't part of the project are left unlinked
+        for import in self.imports(file).iter() {
+            if let Some(target) = self.import_target(file, import.path.clone()) {
+                let target = DocumentLinkTarget::File(target);
+                links.push(DocumentLink { range: import.range.into(), target });
+            }
+        }
+        links
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use gqls_fixture::fixture;
+use maplit::{hashmap, hashset};
+
+use crate::{Change, Changeset, DocumentLink, DocumentLinkTarget, Ide, Point, Range};
+
+#[test]
+fn test_document_links() {
+    let fixture = fixture! {
+        "foo" => r#"
+scalar DateTime @specifiedBy(url: "https://scalars.graphql.org/andimarek/date-time")
+                                  #...............................................
+scalar Json
+extend scalar Json @specifiedBy(url: """https://www.json.org""")
+                                       #....................
+scalar Empty @specifiedBy(url: "")
+type Foo @specifiedBy(url: "https://example.com") { foo: Int }
+"#
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let links = snapshot.document_links(Path::new("foo"));
+    let expected = fixture.ranges().map(|(_, range)| Range::from(range)).collect::<Vec<_>>();
+    assert_eq!(links.iter().map(|link| link.range).collect::<Vec<_>>(), expected);
+    assert_eq!(
+        links[0].target,
+        DocumentLinkTarget::Url("https://scalars.graphql.org/andimarek/date-time".to_owned())
+    );
+    assert_eq!(links[1].target, DocumentLinkTarget::Url("https://www.json.org".to_owned()));
+}
+
+#[test]
+fn test_document_links_to_imports() {
+    let mut ide = Ide::default();
+    let foo = ide.vfs().intern("schema/foo.graphql");
+    let bar = ide.vfs().intern("schema/types/bar.graphql");
+    let text = r#"# import Bar from "./types/bar.graphql"
+# import * from "../missing.graphql"
+type Foo { bar: Bar }
+"#;
+    let _ = ide.apply(
+        Changeset::default()
+            .with_projects(hashmap! { "default" => hashset! { foo, bar } })
+            .with_change(Change::set(foo, text.to_owned()))
+            .with_change(Change::set(bar, "type Bar { id: ID! }".to_owned())),
+    );
+    assert_eq!(
+        ide.snapshot().document_links(foo),
+        [DocumentLink {
+            range: Range { start: Point::new(0, 19), end: Point::new(0, 38) },
+            target: DocumentLinkTarget::File(bar),
+        }]
+    );
+}
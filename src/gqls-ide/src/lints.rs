@@ -0,0 +1,87 @@
+pub use gqls_db::{Lint, LintOverrides, UnknownLint};
+
+use crate::ErrorCode;
+
+impl From<Lint> for ErrorCode {
+    fn from(lint: Lint) -> Self {
+        match lint {
+            Lint::EnumValueCase => ErrorCode::Lint(1),
+            Lint::TypeNameCase => ErrorCode::Lint(2),
+            Lint::FieldNameCase => ErrorCode::Lint(3),
+            // `L0004` is taken by breaking changes to the published schema
+            Lint::UnusedDefinition => ErrorCode::Lint(5),
+            Lint::UnregisteredScalar => ErrorCode::Lint(6),
+            Lint::MissingQueryType => ErrorCode::Lint(7),
+        }
+    }
+}
+
+// leading underscores are ignored by all the case checks (e.g. `__typename`)
+fn trim(s: &str) -> &str {
+    s.trim_start_matches('_')
+}
+
+pub(crate) fn is_screaming_snake_case(s: &str) -> bool {
+    s.chars().all(|c| !c.is_lowercase())
+}
+
+pub(crate) fn is_pascal_case(s: &str) -> bool {
+    let s = trim(s);
+    s.is_empty() || (s.starts_with(|c: char| !c.is_lowercase()) && !s.contains('_'))
+}
+
+pub(crate) fn is_camel_case(s: &str) -> bool {
+    let s = trim(s);
+    s.is_empty() || (s.starts_with(|c: char| !c.is_uppercase()) && !s.contains('_'))
+}
+
+/// Split an identifier into words on underscores and lowercase to uppercase transitions
+fn words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    for part in s.split('_').filter(|part| !part.is_empty()) {
+        let mut word = String::new();
+        let mut prev_is_lower = false;
+        for c in part.chars() {
+            if c.is_uppercase() && prev_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_is_lower = c.is_lowercase() || c.is_ascii_digit();
+            word.push(c);
+        }
+        words.push(word);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+fn leading_underscores(s: &str) -> &str {
+    &s[..s.len() - trim(s).len()]
+}
+
+pub(crate) fn to_screaming_snake_case(s: &str) -> String {
+    words(s).iter().map(|word| word.to_uppercase()).collect::<Vec<_>>().join("_")
+}
+
+pub(crate) fn to_pascal_case(s: &str) -> String {
+    let pascal = words(s).iter().map(|word| capitalize(word)).collect::<String>();
+    format!("{}{pascal}", leading_underscores(s))
+}
+
+pub(crate) fn to_camel_case(s: &str) -> String {
+    let camel = words(s)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+        .collect::<String>();
+    format!("{}{camel}", leading_underscores(s))
+}
+
+#[cfg(test)]
+mod tests;
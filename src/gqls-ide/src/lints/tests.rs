@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_parse_lint() {
+    for lint in Lint::ALL {
+        assert_eq!(lint.name().parse::<Lint>(), Ok(lint));
+    }
+    assert_eq!("random".parse::<Lint>(), Err(UnknownLint("random".to_owned())));
+}
+
+#[test]
+fn test_case_checks() {
+    assert!(is_screaming_snake_case("FOO_BAR2"));
+    assert!(!is_screaming_snake_case("Foo"));
+
+    assert!(is_pascal_case("FooBar"));
+    assert!(is_pascal_case("URL"));
+    assert!(!is_pascal_case("fooBar"));
+    assert!(!is_pascal_case("Foo_Bar"));
+
+    assert!(is_camel_case("fooBar"));
+    assert!(is_camel_case("__typename"));
+    assert!(!is_camel_case("FooBar"));
+    assert!(!is_camel_case("foo_bar"));
+}
+
+#[test]
+fn test_case_conversions() {
+    assert_eq!(to_screaming_snake_case("fooBar"), "FOO_BAR");
+    assert_eq!(to_screaming_snake_case("Foo_bar"), "FOO_BAR");
+    assert_eq!(to_pascal_case("foo_bar"), "FooBar");
+    assert_eq!(to_pascal_case("fooBar"), "FooBar");
+    assert_eq!(to_camel_case("FooBar"), "fooBar");
+    assert_eq!(to_camel_case("foo_bar_baz"), "fooBarBaz");
+    assert_eq!(to_camel_case("__Foo_bar"), "__fooBar");
+}
@@ -1,8 +1,15 @@
+// The forms taking the source text resolve `eol` (the end of the line), negative columns (relative
+// to the end of the line) and `eof` (the end of the text), these rely on `gqls_fixture` so are only
+// usable from tests
+
 #[macro_export]
 macro_rules! position {
     ($file:ident:$row:literal:$col:expr) => {
         $crate::Position { file: $file, point: point!($row: $col) }
     };
+    ($text:expr, $file:ident:$($point:tt)+) => {
+        $crate::Position { file: $file, point: $crate::point!($text, $($point)+) }
+    };
 }
 
 #[macro_export]
@@ -10,6 +17,19 @@ macro_rules! point {
     ($row:literal:$col:expr) => {
         $crate::tree_sitter::Point { row: $row, column: $col }
     };
+    ($text:expr, eof) => {
+        gqls_fixture::end_of_file($text)
+    };
+    ($text:expr, $row:literal:eol) => {
+        gqls_fixture::end_of_line($text, $row)
+    };
+    ($text:expr, $row:literal:-$offset:literal) => {{
+        let end = gqls_fixture::end_of_line($text, $row);
+        $crate::tree_sitter::Point { row: $row, column: end.column - $offset }
+    }};
+    ($text:expr, $row:literal:$col:literal) => {
+        $crate::point!($row: $col)
+    };
 }
 
 #[macro_export]
@@ -17,4 +37,10 @@ macro_rules! range {
     ($a:literal:$b:literal..$x:literal:$y:literal) => {
         $crate::Range { start: $crate::point!($a: $b), end: $crate::point!($x: $y) }
     };
+    ($text:expr, $a:literal:$b:literal..eof) => {
+        $crate::Range { start: $crate::point!($a: $b), end: $crate::point!($text, eof) }
+    };
+    ($text:expr, $a:literal:$b:literal..$x:literal:$($y:tt)+) => {
+        $crate::Range { start: $crate::point!($a: $b), end: $crate::point!($text, $x:$($y)+) }
+    };
 }
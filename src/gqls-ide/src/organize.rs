@@ -0,0 +1,136 @@
+//! Organizing the members of a definition, i.e. sorting the fields, input fields or enum values
+//! alphabetically. A member moves along with its description and directives (which are part of
+//! its node) and the comments attached to it, the whitespace in between is left as is.
+
+use gqls_db::{FileData, SourceDatabase};
+use gqls_syntax::{NodeExt, NodeKind, Position};
+use tree_sitter::Node;
+
+use crate::{Patch, Point, Range, Snapshot};
+
+impl Snapshot {
+    /// The patch that sorts the members of the definition at `position`, `None` if there is
+    /// nothing to sort or they are already in order
+    pub fn organize_fields(&self, position: Position) -> Option<Patch> {
+        let data = self.file_data(position.file);
+        organize(&data.text, block_at(&data, position.point)?)
+    }
+
+    /// The range of the members of the definition at `position` if they are out of order, without
+    /// building the patch that sorts them
+    pub(crate) fn unorganized_fields(&self, position: Position) -> Option<Range> {
+        let data = self.file_data(position.file);
+        let members = members(&data.text, block_at(&data, position.point)?)?;
+        sorted(&members)?;
+        span(&members)
+    }
+}
+
+/// The block of members of the definition at `point`, `None` if it has errors
+fn block_at(data: &FileData, point: Point) -> Option<Node<'_>> {
+    let node = data.tree.root_node().named_node_at(point)?;
+    node.parents().find_map(members_of).filter(|block| !block.has_error())
+}
+
+/// The block of members of `node` if it is a definition (or is such a block itself)
+pub(crate) fn members_of(node: Node<'_>) -> Option<Node<'_>> {
+    const BLOCKS: [&str; 3] = [
+        NodeKind::FIELDS_DEFINITION,
+        NodeKind::INPUT_FIELDS_DEFINITION,
+        NodeKind::ENUM_VALUES_DEFINITION,
+    ];
+    if BLOCKS.contains(&node.kind()) {
+        return Some(node);
+    }
+    BLOCKS.into_iter().find_map(|kind| node.child_of_kind(kind))
+}
+
+/// A member along with the comments attached to it
+#[derive(Clone, Copy)]
+pub(crate) struct Member<'a, 'tree> {
+    pub name: &'a str,
+    pub node: Node<'tree>,
+    /// the member or its first leading comment
+    pub first: Node<'tree>,
+    /// the member or its trailing comment
+    pub last: Node<'tree>,
+}
+
+impl Member<'_, '_> {
+    pub fn range(&self) -> Range {
+        Range { start: self.first.start_position(), end: self.last.end_position() }
+    }
+}
+
+/// The members of the `block` of a definition, `None` if any of them is incomplete
+pub(crate) fn members<'a, 'tree>(
+    text: &'a str,
+    block: Node<'tree>,
+) -> Option<Vec<Member<'a, 'tree>>> {
+    let children = block.children(&mut block.walk()).collect::<Vec<_>>();
+    let mut members = vec![];
+    for (i, &child) in children.iter().enumerate() {
+        let name = match child.kind() {
+            NodeKind::ENUM_VALUE_DEFINITION => child.child_of_kind(NodeKind::ENUM_VALUE)?,
+            NodeKind::FIELD_DEFINITION | NodeKind::INPUT_VALUE_DEFINITION => child.name_node()?,
+            _ => continue,
+        };
+
+        // the comments on the lines directly above belong to the member, unless they trail
+        // whatever precedes them
+        let mut first = child;
+        for j in (0..i).rev() {
+            let prev = children[j];
+            let trails = j > 0 && children[j - 1].end_position().row == prev.start_position().row;
+            if prev.kind() != NodeKind::COMMENT
+                || trails
+                || prev.end_position().row + 1 != first.start_position().row
+            {
+                break;
+            }
+            first = prev;
+        }
+
+        // as does a comment following it on the same line
+        let last = match children.get(i + 1) {
+            Some(&next)
+                if next.kind() == NodeKind::COMMENT
+                    && next.start_position().row == child.end_position().row =>
+                next,
+            _ => child,
+        };
+        members.push(Member { name: name.text(text), node: child, first, last });
+    }
+    Some(members)
+}
+
+fn organize(text: &str, block: Node<'_>) -> Option<Patch> {
+    let members = members(text, block)?;
+    let sorted = sorted(&members)?;
+
+    // each member takes the place of the one at its sorted position
+    let mut organized = String::new();
+    for (i, member) in sorted.iter().enumerate() {
+        organized.push_str(&text[member.first.start_byte()..member.last.end_byte()]);
+        if let Some(next) = members.get(i + 1) {
+            organized.push_str(&text[members[i].last.end_byte()..next.first.start_byte()]);
+        }
+    }
+    Some(Patch::new(span(&members)?, organized))
+}
+
+/// The members sorted by name, `None` if they are in order already
+fn sorted<'m, 'a, 'tree>(members: &'m [Member<'a, 'tree>]) -> Option<Vec<&'m Member<'a, 'tree>>> {
+    let mut sorted = members.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|member| (member.name.to_lowercase(), member.name));
+    let in_order = sorted.iter().zip(members).all(|(a, b)| a.first == b.first);
+    (!in_order).then(|| sorted)
+}
+
+/// The range from the first to the last of `members`
+fn span(members: &[Member<'_, '_>]) -> Option<Range> {
+    Some(Range { start: members.first()?.range().start, end: members.last()?.range().end })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,69 @@
+use gqls_fixture::{fixture, Fixture};
+use ropey::Rope;
+
+use crate::Ide;
+
+#[track_caller]
+fn test(fixture: &Fixture, expected: &str) {
+    let ide = Ide::from_fixture_allow_errors(fixture);
+    let snapshot = ide.snapshot();
+    for position in fixture.positions() {
+        let mut rope = Rope::from_str(&snapshot.file_text(position.file));
+        if let Some(patch) = snapshot.organize_fields(position) {
+            let _ = patch.apply(&mut rope);
+        }
+        assert_eq!(rope.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_organize_fields() {
+    let fixture = fixture! {
+        "foo" => r#"type Foo {
+  zeta:$Int
+  "the alpha"
+  alpha(b: Int, a: Int): String @deprecated
+  # about beta
+  Beta: Foo # trailing
+  gamma: Int
+}
+"#
+    };
+    test(
+        &fixture,
+        r#"type Foo {
+  "the alpha"
+  alpha(b: Int, a: Int): String @deprecated
+  # about beta
+  Beta: Foo # trailing
+  gamma: Int
+  zeta: Int
+}
+"#,
+    );
+}
+
+#[test]
+fn test_organize_input_fields_and_enum_values() {
+    let fixture = fixture! {
+        "input" => "input I { c: Int b: Int = 1 a:$Int }"
+    };
+    test(&fixture, "input I { a: Int b: Int = 1 c: Int }");
+
+    let fixture = fixture! {
+        "enum" => "enum E {\n  C\n  A$@deprecated\n  B\n}"
+    };
+    test(&fixture, "enum E {\n  A @deprecated\n  B\n  C\n}");
+}
+
+#[test]
+fn test_organize_sorted_fields_is_noop() {
+    let fixture = fixture! {
+        "sorted" => "type Foo { a:$Int b: Int }"
+        "outside" => "scalar$S type Foo { b: Int a: Int }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    for position in fixture.positions() {
+        assert!(ide.snapshot().organize_fields(position).is_none());
+    }
+}
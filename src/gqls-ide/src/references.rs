@@ -1,11 +1,24 @@
-use gqls_db::DefDatabase;
-use gqls_ir::Res;
-use gqls_syntax::Position;
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{Arg, Directive, FieldRes, InProject, ItemBodyKind, ItemKind, ItemRes, Name, Res};
+use gqls_syntax::{Node, NodeExt, NodeKind, Position, Range, RangeExt};
+use vfs::FileId;
 
 use crate::{Location, Snapshot};
 
+/// A field or enum value, which unlike items are referred to within values and field sets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Member {
+    Field(FieldRes),
+    /// the name of the enum and the name of the value
+    EnumValue(Name, Name),
+}
+
 impl Snapshot {
     pub fn find_references(&self, position: Position) -> Vec<Location> {
+        if let Some(member) = self.member_at(position) {
+            return self.member_references(position.file, member);
+        }
+
         let res = match self.resolve_item_name_at(position).and_then(|res| res.try_into_item().ok())
         {
             Some(res) => res,
@@ -16,6 +29,199 @@ impl Snapshot {
             .map(|(file, range)| Location::new(file, range))
             .collect()
     }
+
+    // the field or enum value named at `position`, either where it is defined or where it is used
+    fn member_at(&self, position: Position) -> Option<Member> {
+        if let Some(res) = self.resolve_field_at(position) {
+            if self.field(res).name.range.contains(position.point) {
+                return Some(Member::Field(res));
+            }
+        }
+
+        if let Some(res) = self.resolve_field_set_at(position) {
+            return Some(Member::Field(res));
+        }
+
+        let variant = self.resolve_item_at(position).and_then(|res| {
+            let body = self.item_body(res)?;
+            let variants = match &body.kind {
+                ItemBodyKind::Enum(body) => &body.variants,
+                _ => return None,
+            };
+            let variant = variants.iter().find(|v| v.name.range.contains(position.point))?;
+            Some(Member::EnumValue(self.item(res).name, variant.name))
+        });
+        if variant.is_some() {
+            return variant;
+        }
+
+        self.member_uses(position.file)
+            .into_iter()
+            .find(|(range, _)| range.contains(position.point))
+            .map(|(_, member)| member)
+    }
+
+    fn member_references(&self, file: FileId, member: Member) -> Vec<Location> {
+        let mut references = vec![];
+        match member {
+            Member::Field(res) =>
+                references.push(Location::new(res.item.file, self.field(res).name.range)),
+            Member::EnumValue(ty, value) => {
+                let typedef = self.effective_typedef(InProject::new(file, ty));
+                for &part in typedef.iter().flat_map(|typedef| &typedef.parts) {
+                    if let Some(ItemBodyKind::Enum(body)) =
+                        self.item_body(part).as_deref().map(|body| &body.kind)
+                    {
+                        references.extend(
+                            body.variants
+                                .iter()
+                                .filter(|variant| variant.name == value)
+                                .map(|variant| Location::new(part.file, variant.name.range)),
+                        );
+                    }
+                }
+            }
+        }
+
+        for &file in self.related_files(InProject::unit(file)).iter() {
+            if let Member::Field(res) = member {
+                references.extend(
+                    self.field_sets(file)
+                        .into_iter()
+                        .flatten()
+                        .flatten()
+                        .filter(|field| field.res == Ok(res))
+                        .map(|field| Location::new(file, field.range)),
+                );
+            }
+            references.extend(
+                self.member_uses(file)
+                    .into_iter()
+                    .filter(|&(_, used)| used == member)
+                    .map(|(range, _)| Location::new(file, range)),
+            );
+        }
+        references
+    }
+
+    /// The fields and enum values named within the default values and directive arguments of
+    /// `file`, e.g. `x` and `A` of `input: In = { x: A }`
+    fn member_uses(&self, file: FileId) -> Vec<(Range, Member)> {
+        // the types of the values along with their ranges
+        let mut values = vec![];
+        let items = self.items(file);
+        for (idx, item) in items.iter() {
+            if let ItemKind::TypeDefinition(typedef) = item.kind {
+                self.directive_values(file, &items[typedef].directives, &mut values);
+            }
+            let body = match self.item_body(ItemRes::new(file, idx)) {
+                Some(body) => body,
+                None => continue,
+            };
+            if let ItemBodyKind::DirectiveDefinition(directive) = &body.kind {
+                self.arg_values(file, &directive.args, &mut values);
+            }
+            for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                if let Some(default_value) = &field.default_value {
+                    values.push((field.ty.name(), default_value.range));
+                }
+                self.directive_values(file, &field.directives, &mut values);
+                self.arg_values(file, &field.args, &mut values);
+            }
+        }
+
+        // the lowered values don't keep the ranges of their parts, so those come from the syntax
+        let data = self.file_data(file);
+        let root = data.tree.root_node();
+        let mut uses = vec![];
+        for (ty, range) in values {
+            let mut node = match root.named_descendant_for_range(range) {
+                Some(node) => node,
+                None => continue,
+            };
+            while node.kind() != NodeKind::VALUE {
+                node = match node.parent() {
+                    Some(parent) => parent,
+                    None => break,
+                };
+            }
+            self.value_uses(file, &data.text, ty, node, &mut uses);
+        }
+        uses
+    }
+
+    fn arg_values(&self, file: FileId, args: &[Arg], values: &mut Vec<(Name, Range)>) {
+        for arg in args {
+            if let Some(default_value) = &arg.default_value {
+                values.push((arg.ty.name(), default_value.range));
+            }
+            self.directive_values(file, &arg.directives, values);
+        }
+    }
+
+    fn directive_values(
+        &self,
+        file: FileId,
+        directives: &[Directive],
+        values: &mut Vec<(Name, Range)>,
+    ) {
+        for directive in directives {
+            let definition = self
+                .resolve_directive(InProject::new(file, directive.name))
+                .and_then(|res| self.item_body(res));
+            let definition = match &definition {
+                Some(body) => body.as_directive(),
+                None => continue,
+            };
+            for argument in &directive.arguments {
+                if let Some(arg) = definition.args.iter().find(|arg| arg.name == argument.name) {
+                    values.push((arg.ty.name(), argument.value_range));
+                }
+            }
+        }
+    }
+
+    // `value` is expected to be of the type named `ty`
+    fn value_uses(
+        &self,
+        file: FileId,
+        text: &str,
+        ty: Name,
+        value: Node<'_>,
+        uses: &mut Vec<(Range, Member)>,
+    ) {
+        if value.kind() != NodeKind::VALUE {
+            return;
+        }
+        let value = match value.sole_named_child() {
+            Some(value) => value,
+            None => return,
+        };
+        match value.kind() {
+            NodeKind::ENUM_VALUE =>
+                uses.push((value.range(), Member::EnumValue(ty, Name::new(text, value)))),
+            NodeKind::LIST_VALUE =>
+                for value in value.children_of_kind(&mut value.walk(), NodeKind::VALUE) {
+                    self.value_uses(file, text, ty, value, uses);
+                },
+            NodeKind::OBJECT_VALUE =>
+                for field in value.children_of_kind(&mut value.walk(), NodeKind::OBJECT_FIELD) {
+                    let name = match field.child_of_kind(NodeKind::NAME) {
+                        Some(name) => name,
+                        None => continue,
+                    };
+                    let res = match self.field_of(InProject::new(file, ty), name.text(text)) {
+                        Some(res) => res,
+                        None => continue,
+                    };
+                    uses.push((name.range(), Member::Field(res)));
+                    if let Some(value) = field.child_of_kind(NodeKind::VALUE) {
+                        self.value_uses(file, text, self.field(res).ty.name(), value, uses);
+                    }
+                },
+            _ => {}
+        }
+    }
 }
 
 #[cfg(test)]
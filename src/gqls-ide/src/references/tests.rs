@@ -6,7 +6,11 @@ use crate::{Ide, Location};
 
 #[track_caller]
 fn test(fixture: Fixture) {
-    let ide = Ide::from_fixture(&fixture);
+    test_with(Ide::from_fixture(&fixture), fixture);
+}
+
+#[track_caller]
+fn test_with(ide: Ide, fixture: Fixture) {
     let expected = fixture
         .ranges()
         .map(|(file, range)| Location::new(file, range))
@@ -77,7 +81,11 @@ fn test_find_references_to_object_like_type() {
                 }
             "#
         };
-        test(fixture);
+        // an extension without a definition is an error, but its references are still found
+        match kind {
+            "extend type" => test_with(Ide::from_fixture_allow_errors(&fixture), fixture),
+            _ => test(fixture),
+        }
     }
 }
 
@@ -139,10 +147,10 @@ fn test_find_references_to_scalar() {
 fn test_find_references_to_type_within_union() {
     let fixture = fixture! {
         "foo" => "
-            scalar S
-                  #.
-                  #^
-            scalar T
+            type S { s: Int }
+                #.
+                #^
+            type T { t: Int }
 
 
             union U = S | T
@@ -158,7 +166,7 @@ fn test_find_references_to_type_within_union() {
                 #^^^
                 t: T
             }
-            scalar T
+            type T { t: Int }
 
 
             union U = Foo | T
@@ -235,3 +243,104 @@ fn test_find_directive_references() {
     };
     test(fixture);
 }
+
+#[test]
+fn test_find_references_to_field_in_default_values() {
+    let fixture = fixture! {
+        "foo" => "
+            input In {
+                x: Int
+               #.
+               #^
+                nested: In
+            }
+
+            type Query {
+                foo(in: In = { x: 1, nested: { x: 2 } }): Int
+                              #.              #.
+                              #^              #^
+            }
+        "
+    };
+    test(fixture);
+}
+
+#[test]
+fn test_find_references_to_field_in_directive_arguments() {
+    let fixture = fixture! {
+        "foo" => "
+            directive @d(in: In) on OBJECT | FIELD_DEFINITION
+
+            input In {
+                x: Int
+               #.
+               #^
+            }
+
+            type Query @d(in: { x: 1 }) {
+                               #.
+                foo: Int @d(in: { x: 2 })
+                                 #.
+                                 #^
+            }
+        "
+    };
+    test(fixture);
+}
+
+#[test]
+fn test_find_references_to_field_in_field_set() {
+    let fixture = fixture! {
+        "foo" => r#"
+extend schema @link(url: "https://specs.apollo.dev/federation/v2.0") {
+    query: Query
+}
+
+type Query {
+    me: User
+}
+
+type User @key(fields: "id organization { id }") {
+                       #..
+                       #^
+    id: ID!
+   #..
+   #^
+    organization: Organization
+}
+
+type Organization {
+    id: ID!
+}
+"#
+    };
+    test(fixture);
+}
+
+#[test]
+fn test_find_references_to_enum_value() {
+    let fixture = fixture! {
+        "foo" => "
+            enum E {
+                A
+               #.
+               #^
+                B
+            }
+
+            extend enum E {
+                C
+            }
+
+            directive @d(e: [E!]) on FIELD_DEFINITION
+
+            type Query {
+                foo(e: E = A): E @d(e: [B, A])
+                          #.              #.
+                          #^              #^
+                bar(e: E = B): Int
+            }
+        "
+    };
+    test(fixture);
+}
@@ -1,56 +1,76 @@
-use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Display};
 
 use gqls_db::DefDatabase;
+use gqls_ir::{builtins_file, InProject, Name, Res};
 use gqls_syntax::Position;
-use vfs::FileId;
 
-use crate::{FilePatches, Patch, Range, Snapshot};
+use crate::{Location, Patch, Range, Snapshot, SourceChange};
 
 impl Snapshot {
     // TODO can return a range indicating the rename scope (default behaviour works well enough for now)
     pub fn prepare_rename(&self, position: Position) -> Result<Range, RenameError> {
-        let err = Err("no references found at position".to_owned());
-        match self.resolve_item_name_at(position) {
-            Some(res) if res.is_err() => err?,
-            None => err?,
-            _ => {
-                let name = self
-                    .name_at(position)
-                    .expect("there were references here so it must be a name");
-                Ok(name.range.into())
-            }
+        let name = self.name_at(position).ok_or(RenameError::NoReferences)?;
+        match self.resolve_item(InProject::new(position.file, name)) {
+            Res::Err => Err(RenameError::NoReferences),
+            Res::Builtin(_) => Err(RenameError::Builtin(name.to_string())),
+            Res::Item(resolutions) if resolutions.iter().any(|res| res.file == builtins_file()) =>
+                Err(RenameError::Builtin(name.to_string())),
+            Res::Item(_) => Ok(name.range.into()),
         }
     }
 
-    pub fn rename(&self, position: Position, to: &str) -> Result<Vec<FilePatches>, RenameError> {
+    pub fn rename(&self, position: Position, to: &str) -> Result<SourceChange, RenameError> {
         self.prepare_rename(position)?;
-        let mut patches = HashMap::<FileId, FilePatches>::new();
+        self.check_rename_conflicts(position, to)?;
+        let mut change = SourceChange::default();
         self.find_references(position).into_iter().for_each(|location| {
-            patches
-                .entry(location.file)
-                .or_insert_with(|| FilePatches::empty(location.file))
-                .patches
-                .push(Patch::new(location.range, to.to_owned()));
+            change.insert(location.file, Patch::new(location.range, to.to_owned()));
         });
-        assert!(!patches.is_empty());
-        Ok(patches.into_values().collect())
+        assert!(!change.is_empty());
+        Ok(change)
     }
-}
-
-#[derive(Debug)]
-pub struct RenameError(String);
 
-impl From<String> for RenameError {
-    fn from(s: String) -> Self {
-        Self(s)
+    // Renaming to a name that is already taken would silently merge the two items (or shadow a builtin),
+    // so refuse up front rather than leaving the user with duplicate definition errors
+    fn check_rename_conflicts(&self, position: Position, to: &str) -> Result<(), RenameError> {
+        let name = self.name_at(position).expect("checked by `prepare_rename`");
+        if name.as_str() == to {
+            return Ok(());
+        }
+        match self.resolve_item(InProject::new(position.file, Name::unranged(to))) {
+            Res::Err => Ok(()),
+            Res::Builtin(_) => Err(RenameError::Builtin(to.to_owned())),
+            Res::Item(resolutions) if resolutions.iter().any(|res| res.file == builtins_file()) =>
+                Err(RenameError::Builtin(to.to_owned())),
+            Res::Item(resolutions) => {
+                let mut locations = resolutions
+                    .into_iter()
+                    .map(|res| Location::new(res.file, self.item(res).name.range))
+                    .collect::<Vec<_>>();
+                locations.sort();
+                Err(RenameError::Conflict { name: to.to_owned(), locations })
+            }
+        }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    NoReferences,
+    /// Builtin scalars and directives can neither be renamed nor be renamed to
+    Builtin(String),
+    /// The new name is already defined at `locations`
+    Conflict { name: String, locations: Vec<Location> },
+}
+
 impl Display for RenameError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            RenameError::NoReferences => write!(f, "no references found at position"),
+            RenameError::Builtin(name) => write!(f, "`{name}` is a builtin"),
+            RenameError::Conflict { name, .. } => write!(f, "`{name}` is already defined"),
+        }
     }
 }
 
@@ -2,7 +2,7 @@ use gqls_db::DefDatabase;
 use gqls_fixture::{fixture, Fixture};
 use ropey::Rope;
 
-use crate::Ide;
+use crate::{Ide, Location, RenameError};
 
 fn test(fixture: &Fixture, to: &str) {
     let ide = Ide::from_fixture(fixture);
@@ -64,5 +64,49 @@ fn test_rename_field_references() {
     test(&before, "Foobar");
 }
 
+#[test]
+fn test_rename_conflict() {
+    let fixture = fixture! {
+        "foo" => "
+            scalar Bar
+                   #^
+        "
+        "baz" => "
+            type Baz { bar: Bar }
+                #...
+        "
+    };
+    let ide = Ide::from_fixture(&fixture);
+    let position = fixture.positions().next().unwrap();
+    let locations =
+        fixture.ranges().map(|(file, range)| Location::new(file, range)).collect::<Vec<_>>();
+    assert_eq!(
+        ide.snapshot().rename(position, "Baz"),
+        Err(RenameError::Conflict { name: "Baz".to_owned(), locations })
+    );
+}
+
+#[test]
+fn test_rename_to_builtin() {
+    let fixture = fixture! {
+        "foo" => "
+            scalar Bar
+                   #^
+            directive @qux on OBJECT
+                      #^
+        "
+    };
+    let ide = Ide::from_fixture(&fixture);
+    let snapshot = ide.snapshot();
+    let mut positions = fixture.positions();
+    let bar = positions.next().unwrap();
+    assert_eq!(snapshot.rename(bar, "String"), Err(RenameError::Builtin("String".to_owned())));
+    let qux = positions.next().unwrap();
+    assert_eq!(
+        snapshot.rename(qux, "@deprecated"),
+        Err(RenameError::Builtin("@deprecated".to_owned()))
+    );
+}
+
 #[cfg(test)]
 mod prepare_rename;
@@ -0,0 +1,77 @@
+use gqls_ir::{Arg, Directive, DirectiveLocations, Name, TyKind};
+use std::fmt::Write;
+
+// Shared rendering so hover, signature help and completions all present arguments the same way
+
+/// e.g. `a: Int = 1`
+pub(crate) fn render_arg(arg: &Arg) -> String {
+    match &arg.default_value {
+        Some(default) => format!("{}: {:?} = {:?}", arg.name, arg.ty, default),
+        None => format!("{}: {:?}", arg.name, arg.ty),
+    }
+}
+
+/// e.g. `(a: Int = 1, b: String!)`, or the empty string if there are no arguments
+pub(crate) fn render_arg_list(args: &[Arg]) -> String {
+    if args.is_empty() {
+        return String::new();
+    }
+    format!("({})", args.iter().map(render_arg).collect::<Vec<_>>().join(", "))
+}
+
+/// A markdown list of the arguments that have descriptions
+pub(crate) fn render_arg_docs(args: &[Arg]) -> String {
+    let mut docs = String::new();
+    for arg in args {
+        if let Some(description) = &arg.description {
+            writeln!(docs, "- `{}`: {description}", render_arg(arg)).unwrap();
+        }
+    }
+    docs
+}
+
+/// e.g. `directive @d(a: Int = 1) on FIELD_DEFINITION`
+pub(crate) fn render_directive_signature(
+    name: &Name,
+    args: &[Arg],
+    locations: DirectiveLocations,
+) -> String {
+    format!("directive {name}{} on {locations:?}", render_arg_list(args))
+}
+
+/// Snippet for a directive with placeholders for the arguments that must be provided, the
+/// placeholders of strings are quoted
+pub(crate) fn render_directive_snippet(name: &Name, args: &[Arg]) -> String {
+    let is_string = |arg: &Arg| match &arg.ty.kind {
+        TyKind::NonNull(ty) =>
+            matches!(ty.kind, TyKind::Named(..)) && ty.name().as_str() == "String",
+        _ => false,
+    };
+    let required = args
+        .iter()
+        .filter(|arg| arg.default_value.is_none() && matches!(arg.ty.kind, TyKind::NonNull(_)))
+        .enumerate()
+        .map(|(i, arg)| {
+            if is_string(arg) {
+                format!("{}: \"${}\"", arg.name, i + 1)
+            } else {
+                format!("{}: ${}", arg.name, i + 1)
+            }
+        })
+        .collect::<Vec<_>>();
+    match &required[..] {
+        [] => name.to_string(),
+        required => format!("{name}({})", required.join(", ")),
+    }
+}
+
+/// An applied directive, e.g. `@key(fields: "id", resolvable: false)`
+pub(crate) fn render_directive(directive: &Directive) -> String {
+    match &directive.arguments[..] {
+        [] => directive.name.to_string(),
+        args => {
+            let args = args.iter().map(|arg| format!("{arg:?}")).collect::<Vec<_>>();
+            format!("{}({})", directive.name, args.join(", "))
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use gqls_db::DefDatabase;
-use gqls_ir::{FieldRes, InProject, ItemRes, Res};
+use gqls_ir::{FieldRes, InProject, ItemKind, ItemRes, Name, Res};
 use gqls_syntax::{Position, RangeExt};
 
 use crate::Snapshot;
@@ -10,6 +10,13 @@ impl Snapshot {
         self.name_at(position).map(|name| self.resolve_item(InProject::new(position.file, name)))
     }
 
+    /// Resolve `name` to the definition of a directive, ignoring any other items of the same name
+    pub(crate) fn resolve_directive(&self, name: InProject<Name>) -> Option<ItemRes> {
+        self.resolve_item(name).try_into_item().ok()?.into_iter().find(|res| {
+            matches!(self.items(res.file)[res.value].kind, ItemKind::DirectiveDefinition(_))
+        })
+    }
+
     pub(crate) fn resolve_item_at(&self, position: Position) -> Option<ItemRes> {
         self.item_at(position).map(|idx| ItemRes::new(position.file, idx))
     }
@@ -20,6 +27,19 @@ impl Snapshot {
             field.range.contains(position.point).then(|| FieldRes { item, idx })
         })
     }
+
+    /// The field named `name` of the type `ty`, from any of its definitions or extensions
+    pub(crate) fn field_of(&self, ty: InProject<Name>, name: &str) -> Option<FieldRes> {
+        let resolutions = match self.resolve_item(ty) {
+            Res::Item(resolutions) => resolutions,
+            Res::Builtin(_) | Res::Err => return None,
+        };
+        resolutions.into_iter().find_map(|res| {
+            let body = self.item_body(res)?;
+            let (idx, _) = body.fields()?.iter().find(|(_, field)| field.name.as_str() == name)?;
+            Some(FieldRes::new(res, idx))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,542 @@
+use std::collections::{BTreeMap, HashSet};
+
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{
+    Arg, DependencyKind, Directive, DirectiveLocations, Directives, Field, InProject, ItemBodyKind, ItemKind, ItemRes, Name, OperationKind, Res, RootOperation, TyKind, TypeDefinitionKind
+};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use vfs::FileId;
+
+use crate::{Location, Snapshot};
+
+/// The schema of a project with all type extensions merged into their definitions
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Schema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    /// sorted by name, including the builtin scalars
+    pub types: Vec<SchemaType>,
+    /// sorted by name, without the leading `@`
+    pub directives: Vec<SchemaDirective>,
+}
+
+/// The schema definition of a project merged with all of its schema extensions
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct SchemaDefinition {
+    pub directives: Directives,
+    /// at most one per operation kind, ordered by kind
+    pub operations: Vec<RootOperation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SchemaTypeKind {
+    Scalar,
+    Object,
+    Interface,
+    Union,
+    Enum,
+    InputObject,
+}
+
+impl SchemaTypeKind {
+    /// The `__TypeKind` name of the kind
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaTypeKind::Scalar => "SCALAR",
+            SchemaTypeKind::Object => "OBJECT",
+            SchemaTypeKind::Interface => "INTERFACE",
+            SchemaTypeKind::Union => "UNION",
+            SchemaTypeKind::Enum => "ENUM",
+            SchemaTypeKind::InputObject => "INPUT_OBJECT",
+        }
+    }
+}
+
+impl From<&TypeDefinitionKind> for SchemaTypeKind {
+    fn from(kind: &TypeDefinitionKind) -> Self {
+        match kind {
+            TypeDefinitionKind::Object => SchemaTypeKind::Object,
+            TypeDefinitionKind::Interface => SchemaTypeKind::Interface,
+            TypeDefinitionKind::Input => SchemaTypeKind::InputObject,
+            TypeDefinitionKind::Scalar => SchemaTypeKind::Scalar,
+            TypeDefinitionKind::Enum => SchemaTypeKind::Enum,
+            TypeDefinitionKind::Union => SchemaTypeKind::Union,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaType {
+    pub kind: SchemaTypeKind,
+    pub name: String,
+    /// the description of the definition
+    pub description: Option<String>,
+    /// the directives of the definition and the extensions
+    pub directives: Vec<AppliedDirective>,
+    /// only for objects and interfaces, a field that is defined more than once is only included
+    /// once
+    pub fields: Vec<SchemaField>,
+    /// only for input objects
+    pub input_fields: Vec<SchemaInputValue>,
+    /// only for objects and interfaces
+    pub interfaces: Vec<String>,
+    /// only for interfaces and unions
+    pub possible_types: Vec<String>,
+    /// only for enums
+    pub enum_values: Vec<SchemaEnumValue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaField {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<SchemaInputValue>,
+    #[serde(rename = "type")]
+    pub ty: TypeRef,
+    pub is_deprecated: bool,
+    pub directives: Vec<AppliedDirective>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaInputValue {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: TypeRef,
+    /// the default value as a graphql literal
+    pub default_value: Option<String>,
+    pub directives: Vec<AppliedDirective>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaEnumValue {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_deprecated: bool,
+}
+
+/// A directive applied to a type, field or input value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedDirective {
+    /// without the leading `@`
+    pub name: String,
+    /// the name and value (as a graphql literal) of each argument
+    pub arguments: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDirective {
+    pub name: String,
+    pub locations: Vec<&'static str>,
+    pub args: Vec<SchemaInputValue>,
+}
+
+/// A reference to a type from the body of a type or directive, see
+/// [`Snapshot::schema_dependencies`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaDependency {
+    /// the type, or the directive with its `@`, the reference is in
+    pub from: String,
+    pub to: String,
+    /// the field or argument the type is the type of, e.g. `user` or `user(id)`, which is `None`
+    /// for the members of a union
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeRef {
+    /// the kind is `None` if the name does not resolve
+    Named(Option<SchemaTypeKind>, String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+/// In the shape of the type references of introspection, e.g.
+/// `{ "kind": "LIST", "name": null, "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null } }`
+impl Serialize for TypeRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (kind, name, of_type) = match self {
+            TypeRef::Named(kind, name) => (kind.map(SchemaTypeKind::name), Some(name), None),
+            TypeRef::List(ty) => (Some("LIST"), None, Some(ty)),
+            TypeRef::NonNull(ty) => (Some("NON_NULL"), None, Some(ty)),
+        };
+        let mut ty = serializer.serialize_struct("TypeRef", 3)?;
+        ty.serialize_field("kind", &kind)?;
+        ty.serialize_field("name", &name)?;
+        ty.serialize_field("ofType", &of_type)?;
+        ty.end()
+    }
+}
+
+pub(crate) const BUILTIN_SCALARS: [&str; 5] = ["Boolean", "Float", "ID", "Int", "String"];
+
+// FIXME once bitflags allows iteration
+const DIRECTIVE_LOCATIONS: [(DirectiveLocations, &str); 11] = [
+    (DirectiveLocations::SCHEMA, "SCHEMA"),
+    (DirectiveLocations::SCALAR, "SCALAR"),
+    (DirectiveLocations::OBJECT, "OBJECT"),
+    (DirectiveLocations::FIELD_DEFINITION, "FIELD_DEFINITION"),
+    (DirectiveLocations::ARGUMENT_DEFINITION, "ARGUMENT_DEFINITION"),
+    (DirectiveLocations::INTERFACE, "INTERFACE"),
+    (DirectiveLocations::UNION, "UNION"),
+    (DirectiveLocations::ENUM, "ENUM"),
+    (DirectiveLocations::ENUM_VALUE, "ENUM_VALUE"),
+    (DirectiveLocations::INPUT_OBJECT, "INPUT_OBJECT"),
+    (DirectiveLocations::INPUT_FIELD_DEFINITION, "INPUT_FIELD_DEFINITION"),
+];
+
+impl Snapshot {
+    /// Build the merged schema of the project(s) that `file` belongs to
+    pub fn schema(&self, file: FileId) -> Schema {
+        let mut typedefs = BTreeMap::<String, Vec<ItemRes>>::new();
+        let mut directives = BTreeMap::<String, ItemRes>::new();
+        for (&file, items) in self.project_items(InProject::new(file, ())).iter() {
+            for (idx, item) in items.iter() {
+                let res = ItemRes::new(file, idx);
+                match item.kind {
+                    ItemKind::TypeDefinition(_) =>
+                        typedefs.entry(item.name.to_string()).or_default().push(res),
+                    ItemKind::DirectiveDefinition(_) => {
+                        let name = item.name.trim_start_matches('@').to_owned();
+                        directives.entry(name).or_insert(res);
+                    }
+                }
+            }
+        }
+
+        let mut types = BUILTIN_SCALARS
+            .into_iter()
+            .filter(|name| !typedefs.contains_key(*name))
+            .map(|name| SchemaType::new(SchemaTypeKind::Scalar, name.to_owned()))
+            .collect::<Vec<_>>();
+        types.extend(
+            typedefs.iter().filter_map(|(name, resolutions)| self.schema_type(name, resolutions)),
+        );
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // without explicit root operations, the root types are found by their (configured) names
+        let definition = self.schema_definition(file);
+        let root_types = self.root_types_of(InProject::unit(file));
+        let root_type = |kind: OperationKind, name: &str| {
+            if !definition.operations.is_empty() {
+                let operation = definition.operations.iter().find(|op| op.kind == kind);
+                return operation.map(|op| op.ty.to_string());
+            }
+            types
+                .iter()
+                .any(|ty| ty.name == name && ty.kind == SchemaTypeKind::Object)
+                .then(|| name.to_owned())
+        };
+
+        Schema {
+            query_type: root_type(OperationKind::Query, &root_types.query),
+            mutation_type: root_type(OperationKind::Mutation, &root_types.mutation),
+            subscription_type: root_type(OperationKind::Subscription, &root_types.subscription),
+            directives: directives
+                .into_iter()
+                .map(|(name, res)| self.schema_directive(name, res))
+                .collect(),
+            types,
+        }
+    }
+
+    /// The location of the definition at the schema `coordinate` (e.g. `Type.field(arg:)` or
+    /// `@directive(arg:)`) in the project(s) of `file`. If the definition doesn't exist, the
+    /// location of the closest enclosing one is returned instead, e.g. the type of a missing field.
+    pub fn coordinate_location(&self, file: FileId, coordinate: &str) -> Option<Location> {
+        let (path, arg) = match coordinate.strip_suffix(":)").and_then(|s| s.split_once('(')) {
+            Some((path, arg)) => (path, Some(arg)),
+            None => (coordinate, None),
+        };
+        let (name, member) = match path.split_once('.') {
+            Some((name, member)) => (name, Some(member)),
+            None => (path, None),
+        };
+
+        // definitions are preferred over extensions, the members may be in either
+        let mut resolutions = vec![];
+        for (&file, items) in self.project_items(InProject::new(file, ())).iter() {
+            for (idx, item) in items.iter() {
+                if item.name.as_str() != name {
+                    continue;
+                }
+                let is_ext = match item.kind {
+                    ItemKind::TypeDefinition(typedef) => items[typedef].is_ext,
+                    ItemKind::DirectiveDefinition(_) => false,
+                };
+                resolutions.push((is_ext, ItemRes::new(file, idx)));
+            }
+        }
+        resolutions.sort_by_key(|&(is_ext, res)| (is_ext, res.file));
+        let &(_, item) = resolutions.first()?;
+        let item_location = Location::new(item.file, self.items(item.file)[item.value].name.range);
+
+        let bodies = resolutions
+            .iter()
+            .filter_map(|&(_, res)| Some((res.file, self.item_body(res)?)))
+            .collect::<Vec<_>>();
+        let field = |name: &str| {
+            bodies.iter().find_map(|(file, body)| {
+                let fields = body.fields_slice().unwrap_or_default();
+                let field = fields.iter().find(|field| field.name.as_str() == name)?;
+                Some((*file, field))
+            })
+        };
+        let location = match (member, arg) {
+            (None, None) => None,
+            (None, Some(arg)) => bodies.iter().find_map(|(file, body)| match &body.kind {
+                ItemBodyKind::DirectiveDefinition(directive) => directive
+                    .args
+                    .iter()
+                    .find(|candidate| candidate.name.as_str() == arg)
+                    .map(|arg| Location::new(*file, arg.name.range)),
+                _ => None,
+            }),
+            (Some(member), None) => field(member)
+                .map(|(file, field)| Location::new(file, field.name.range))
+                .or_else(|| {
+                    bodies.iter().find_map(|(file, body)| match &body.kind {
+                        ItemBodyKind::Enum(body) => body
+                            .variants
+                            .iter()
+                            .find(|variant| variant.name.as_str() == member)
+                            .map(|variant| Location::new(*file, variant.name.range)),
+                        _ => None,
+                    })
+                }),
+            (Some(member), Some(arg)) => field(member).map(|(file, field)| {
+                let arg = field.args.iter().find(|candidate| candidate.name.as_str() == arg);
+                Location::new(file, arg.map_or(field.name.range, |arg| arg.name.range))
+            }),
+        };
+        Some(location.unwrap_or(item_location))
+    }
+
+    /// The references between the types and directives of the project(s) of `file` in the order
+    /// they occur, excluding those to builtin scalars and unresolved types
+    pub fn schema_dependencies(&self, file: FileId) -> Vec<SchemaDependency> {
+        let mut seen = HashSet::new();
+        let mut dependencies = vec![];
+        // the projects of the file may overlap
+        for &project in self.projects_of(InProject::unit(file)).iter() {
+            for &dependency in self.dependency_graph(project).iter() {
+                if !seen.insert(dependency) {
+                    continue;
+                }
+                let label = match dependency.kind {
+                    DependencyKind::Field(name) | DependencyKind::DirectiveArg(name) =>
+                        Some(name.to_string()),
+                    DependencyKind::FieldArg(field, arg) => Some(format!("{field}({arg})")),
+                    DependencyKind::Member => None,
+                };
+                dependencies.push(SchemaDependency {
+                    from: self.item(dependency.from).name.to_string(),
+                    to: dependency.to.to_string(),
+                    label,
+                });
+            }
+        }
+        dependencies
+    }
+
+    /// Merge the schema definition and extensions of the project(s) of `file`.
+    /// The definition is applied first and the extensions follow in file order.
+    pub(crate) fn schema_definition(&self, file: FileId) -> SchemaDefinition {
+        let project_items = self.project_items(InProject::unit(file));
+        let mut files = project_items.keys().copied().collect::<Vec<_>>();
+        files.sort();
+        let mut schemas =
+            files.iter().flat_map(|file| project_items[file].schemas.iter()).collect::<Vec<_>>();
+        schemas.sort_by_key(|schema| schema.is_ext);
+
+        let mut definition = SchemaDefinition::default();
+        for schema in schemas {
+            definition.directives.extend(schema.directives.iter().cloned());
+            for operation in &schema.operations {
+                if definition.operations.iter().all(|op| op.kind != operation.kind) {
+                    definition.operations.push(operation.clone());
+                }
+            }
+        }
+        definition.operations.sort_by_key(|operation| operation.kind);
+        definition
+    }
+
+    pub(crate) fn schema_type(&self, name: &str, resolutions: &[ItemRes]) -> Option<SchemaType> {
+        let typedefs = resolutions
+            .iter()
+            .map(|res| {
+                let items = self.items(res.file);
+                (*res, self.typedef(res.file, items[res.value].kind.into_type_definition()))
+            })
+            .collect::<Vec<_>>();
+        // prefer the definition, but an extension of an undefined type is still exported
+        let kind = typedefs
+            .iter()
+            .find(|(_, typedef)| !typedef.is_ext)
+            .or_else(|| typedefs.first())
+            .map(|(_, typedef)| typedef.kind.clone())?;
+
+        let mut ty = SchemaType::new(SchemaTypeKind::from(&kind), name.to_owned());
+        let mut seen = HashSet::new();
+        for (res, typedef) in typedefs.iter().filter(|(_, typedef)| typedef.kind == kind) {
+            if !typedef.is_ext && ty.description.is_none() {
+                ty.description = typedef.description.as_deref().map(ToOwned::to_owned);
+            }
+            ty.directives.extend(applied_directives(&typedef.directives));
+            ty.interfaces.extend(typedef.implementations.iter().flatten().map(ToString::to_string));
+            let body = match self.item_body(*res) {
+                Some(body) => body,
+                None => continue,
+            };
+            let fields = body.fields_slice().unwrap_or_default();
+            let fields = fields.iter().filter(|field| seen.insert(field.name.to_string()));
+            match kind {
+                TypeDefinitionKind::Object | TypeDefinitionKind::Interface =>
+                    ty.fields.extend(fields.map(|field| self.schema_field(field))),
+                TypeDefinitionKind::Input =>
+                    ty.input_fields.extend(fields.map(|field| self.schema_input_field(field))),
+                TypeDefinitionKind::Enum =>
+                    ty.enum_values.extend(body.as_enum().variants.iter().map(|variant| {
+                        SchemaEnumValue {
+                            name: variant.name.to_string(),
+                            description: variant.description.as_deref().map(ToOwned::to_owned),
+                            is_deprecated: variant.is_deprecated,
+                        }
+                    })),
+                TypeDefinitionKind::Union | TypeDefinitionKind::Scalar => {}
+            }
+        }
+        ty.interfaces.sort();
+        ty.interfaces.dedup();
+
+        if matches!(kind, TypeDefinitionKind::Interface | TypeDefinitionKind::Union) {
+            let name = InProject::new(resolutions[0].file, Name::unranged(name));
+            ty.possible_types = self
+                .possible_types(name)
+                .into_iter()
+                .map(|res| self.items(res.file)[res.value].name.to_string())
+                .collect();
+            ty.possible_types.sort();
+        }
+        Some(ty)
+    }
+
+    fn schema_directive(&self, name: String, res: ItemRes) -> SchemaDirective {
+        let items = self.items(res.file);
+        let locations = match items[res.value].kind {
+            ItemKind::DirectiveDefinition(idx) => items[idx].locations,
+            ItemKind::TypeDefinition(_) => unreachable!("expected directive definition"),
+        };
+        let args = self
+            .item_body(res)
+            .map(|body| body.as_directive().args.iter().map(|arg| self.schema_arg(arg)).collect())
+            .unwrap_or_default();
+        SchemaDirective {
+            name,
+            locations: DIRECTIVE_LOCATIONS
+                .into_iter()
+                .filter(|(location, _)| locations.contains(*location))
+                .map(|(_, name)| name)
+                .collect(),
+            args,
+        }
+    }
+
+    fn schema_field(&self, field: &Field) -> SchemaField {
+        SchemaField {
+            name: field.name.to_string(),
+            description: field.description.as_deref().map(ToOwned::to_owned),
+            args: field.args.iter().map(|arg| self.schema_arg(arg)).collect(),
+            ty: self.type_ref(&field.ty),
+            is_deprecated: field
+                .directives
+                .iter()
+                .any(|directive| directive.name.as_str() == "@deprecated"),
+            directives: applied_directives(&field.directives),
+        }
+    }
+
+    fn schema_input_field(&self, field: &Field) -> SchemaInputValue {
+        SchemaInputValue {
+            name: field.name.to_string(),
+            description: field.description.as_deref().map(ToOwned::to_owned),
+            ty: self.type_ref(&field.ty),
+            default_value: field.default_value.as_ref().map(|default| format!("{default:?}")),
+            directives: applied_directives(&field.directives),
+        }
+    }
+
+    fn schema_arg(&self, arg: &Arg) -> SchemaInputValue {
+        SchemaInputValue {
+            name: arg.name.to_string(),
+            description: arg.description.as_deref().map(ToOwned::to_owned),
+            ty: self.type_ref(&arg.ty),
+            default_value: arg.default_value.as_ref().map(|default| format!("{default:?}")),
+            directives: applied_directives(&arg.directives),
+        }
+    }
+
+    pub(crate) fn type_ref(&self, ty: &gqls_ir::Ty) -> TypeRef {
+        match &ty.kind {
+            TyKind::Named(name, res) => {
+                let kind = match res {
+                    Res::Builtin(_) => Some(SchemaTypeKind::Scalar),
+                    Res::Item(resolutions) => resolutions.iter().find_map(|res| {
+                        let items = self.items(res.file);
+                        match items[res.value].kind {
+                            ItemKind::TypeDefinition(idx) =>
+                                Some(SchemaTypeKind::from(&items[idx].kind)),
+                            ItemKind::DirectiveDefinition(_) => None,
+                        }
+                    }),
+                    Res::Err => None,
+                };
+                TypeRef::Named(kind, name.to_string())
+            }
+            TyKind::Err(name) => TypeRef::Named(None, name.to_string()),
+            TyKind::NonNull(ty) => TypeRef::NonNull(Box::new(self.type_ref(ty))),
+            TyKind::List(ty) => TypeRef::List(Box::new(self.type_ref(ty))),
+        }
+    }
+}
+
+impl SchemaType {
+    pub(crate) fn new(kind: SchemaTypeKind, name: String) -> Self {
+        Self {
+            kind,
+            name,
+            description: None,
+            directives: vec![],
+            fields: vec![],
+            input_fields: vec![],
+            interfaces: vec![],
+            possible_types: vec![],
+            enum_values: vec![],
+        }
+    }
+}
+
+fn applied_directives(directives: &[Directive]) -> Vec<AppliedDirective> {
+    directives
+        .iter()
+        .map(|directive| AppliedDirective {
+            name: directive.name.trim_start_matches('@').to_owned(),
+            arguments: directive
+                .arguments
+                .iter()
+                .map(|arg| (arg.name.to_string(), format!("{:?}", arg.value)))
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,146 @@
+use gqls_fixture::fixture;
+use std::path::Path;
+
+use crate::{range, Ide, Location, SchemaInputValue, SchemaTypeKind, TypeRef};
+
+#[test]
+fn test_schema_merges_extensions() {
+    let fixture = fixture! {
+        "foo" => r#"
+            type Query {
+                foo(
+                    "the id"
+                    id: ID! = "1"
+                ): Foo
+            }
+
+            interface Node { id: ID! }
+
+            type Foo implements Node {
+                id: ID!
+                old: Int @deprecated
+            }
+        "#
+        "bar" => "
+            extend type Foo {
+                bars: [Bar!]!
+            }
+
+            enum Bar { A B }
+
+            union U = Foo
+
+            directive @d(a: Int = 1) on FIELD_DEFINITION | OBJECT
+        "
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let schema = ide.snapshot().schema(Path::new("foo"));
+
+    assert_eq!(schema.query_type.as_deref(), Some("Query"));
+    assert_eq!(schema.mutation_type, None);
+    assert_eq!(
+        schema.types.iter().map(|ty| ty.name.as_str()).collect::<Vec<_>>(),
+        ["Bar", "Boolean", "Float", "Foo", "ID", "Int", "Node", "Query", "String", "U"]
+    );
+
+    let ty = |name: &str| schema.types.iter().find(|ty| ty.name == name).unwrap();
+
+    let foo = ty("Foo");
+    assert_eq!(foo.kind, SchemaTypeKind::Object);
+    assert_eq!(foo.interfaces, ["Node"]);
+    assert_eq!(foo.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>(), [
+        "id", "old", "bars"
+    ]);
+    assert!(foo.fields[1].is_deprecated);
+    assert_eq!(
+        foo.fields[2].ty,
+        TypeRef::NonNull(Box::new(TypeRef::List(Box::new(TypeRef::NonNull(Box::new(
+            TypeRef::Named(Some(SchemaTypeKind::Enum), "Bar".to_owned())
+        ))))))
+    );
+
+    assert_eq!(ty("Query").fields[0].args, [SchemaInputValue {
+        name: "id".to_owned(),
+        description: Some("the id".to_owned()),
+        ty: TypeRef::NonNull(Box::new(TypeRef::Named(
+            Some(SchemaTypeKind::Scalar),
+            "ID".to_owned()
+        ))),
+        default_value: Some(r#""1""#.to_owned()),
+        directives: vec![],
+    }]);
+
+    assert_eq!(ty("Node").possible_types, ["Foo"]);
+    assert_eq!(ty("U").possible_types, ["Foo"]);
+    let values = &ty("Bar").enum_values;
+    assert_eq!(values.iter().map(|value| value.name.as_str()).collect::<Vec<_>>(), ["A", "B"]);
+
+    assert_eq!(schema.directives.len(), 1);
+    assert_eq!(schema.directives[0].name, "d");
+    assert_eq!(schema.directives[0].locations, ["OBJECT", "FIELD_DEFINITION"]);
+    assert_eq!(schema.directives[0].args[0].default_value.as_deref(), Some("1"));
+}
+
+#[test]
+fn test_schema_root_types_from_schema_definition() {
+    let fixture = fixture! {
+        "foo" => "
+            schema {
+                query: Root
+            }
+
+            type Root { foo: Int }
+
+            type Query { foo: Int }
+
+            type Mutation { foo: Int }
+        "
+        "bar" => "
+            extend schema {
+                subscription: Events
+            }
+
+            type Events { foo: Int }
+        "
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let schema = ide.snapshot().schema(Path::new("foo"));
+
+    assert_eq!(schema.query_type.as_deref(), Some("Root"));
+    assert_eq!(schema.mutation_type, None);
+    assert_eq!(schema.subscription_type.as_deref(), Some("Events"));
+}
+
+#[test]
+fn test_coordinate_location() {
+    let fixture = fixture! {
+        "foo" => "
+type Foo {
+    bar(baz: Int): Int
+}
+enum E { A }
+directive @d(a: Int) on OBJECT
+"
+        "bar" => "
+extend type Foo {
+    ext: Int
+}
+"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let foo = Path::new("foo");
+    let location = |coordinate| snapshot.coordinate_location(foo, coordinate);
+
+    assert_eq!(location("Foo"), Some(Location::new(foo, range!(1:5..1:8))));
+    assert_eq!(location("Foo.bar"), Some(Location::new(foo, range!(2:4..2:7))));
+    assert_eq!(location("Foo.bar(baz:)"), Some(Location::new(foo, range!(2:8..2:11))));
+    assert_eq!(location("Foo.ext"), Some(Location::new(Path::new("bar"), range!(2:4..2:7))));
+    assert_eq!(location("E.A"), Some(Location::new(foo, range!(4:9..4:10))));
+    assert_eq!(location("@d(a:)"), Some(Location::new(foo, range!(5:13..5:14))));
+    // the closest enclosing definition of what doesn't exist
+    assert_eq!(location("Foo.missing"), location("Foo"));
+    assert_eq!(location("Foo.bar(missing:)"), location("Foo.bar"));
+    assert_eq!(location("@d(missing:)"), Some(Location::new(foo, range!(5:10..5:12))));
+    assert_eq!(location("Missing"), None);
+}
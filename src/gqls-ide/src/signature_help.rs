@@ -0,0 +1,62 @@
+use gqls_db::{DefDatabase, SourceDatabase};
+use gqls_ir::{InProject, Name};
+use gqls_syntax::{NodeExt, NodeKind, Position};
+
+use crate::{render, Snapshot};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SignatureHelp {
+    /// e.g. `@d(a: Int = 1, b: String!)`
+    pub label: String,
+    pub parameters: Vec<SignatureParameter>,
+    pub active_parameter: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SignatureParameter {
+    /// e.g. `a: Int = 1`, always a substring of the signature label
+    pub label: String,
+    pub documentation: Option<String>,
+}
+
+impl Snapshot {
+    /// Signature of the directive whose arguments `position` is within
+    pub fn signature_help(&self, position: Position) -> Option<SignatureHelp> {
+        let data = self.file_data(position.file);
+        let node = data.tree.root_node().named_node_at(position.point)?;
+        let directive = match node.kind() {
+            NodeKind::DIRECTIVE => node,
+            _ => node.parent_of_kind(NodeKind::DIRECTIVE)?,
+        };
+        let arguments = directive.child_of_kind(NodeKind::ARGUMENTS)?;
+        if position.point < arguments.start_position() {
+            return None;
+        }
+
+        let name = Name::new(&data.text, directive.child_of_kind(NodeKind::DIRECTIVE_NAME)?);
+        let res = self.resolve_directive(InProject::new(position.file, name))?;
+        let args = &self.item_body(res)?.as_directive().args;
+
+        let active_name = match node.kind() {
+            NodeKind::ARGUMENT => Some(node),
+            _ => node.parent_of_kind(NodeKind::ARGUMENT),
+        }
+        .and_then(|argument| argument.name_node())
+        .map(|name| name.text(&data.text));
+        let active_parameter =
+            active_name.and_then(|active| args.iter().position(|arg| arg.name.as_str() == active));
+
+        let parameters = args
+            .iter()
+            .map(|arg| SignatureParameter {
+                label: render::render_arg(arg),
+                documentation: arg.description.as_deref().map(ToOwned::to_owned),
+            })
+            .collect();
+        let label = format!("{name}{}", render::render_arg_list(args));
+        Some(SignatureHelp { label, parameters, active_parameter })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,90 @@
+use expect_test::{expect, Expect};
+use gqls_fixture::{fixture, Fixture};
+
+use crate::Ide;
+
+fn test(fixture: Fixture, expect: Expect) {
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    for position in fixture.positions() {
+        expect.assert_debug_eq(&snapshot.signature_help(position));
+    }
+}
+
+const DIRECTIVE: &str = r#"
+directive @d(
+    "the first arg"
+    a: Int = 1
+    b: String!
+) on FIELD_DEFINITION
+"#;
+
+#[test]
+fn test_signature_help_active_parameter() {
+    let fixture = fixture! {
+        "foo" => format!(r#"{DIRECTIVE}
+type Foo {{
+    foo: Int @d(a: 2, $b: "x")
+}}
+"#)
+    };
+    test(
+        fixture,
+        expect![[r#"
+            Some(
+                SignatureHelp {
+                    label: "@d(a: Int = 1, b: String!)",
+                    parameters: [
+                        SignatureParameter {
+                            label: "a: Int = 1",
+                            documentation: Some(
+                                "the first arg",
+                            ),
+                        },
+                        SignatureParameter {
+                            label: "b: String!",
+                            documentation: None,
+                        },
+                    ],
+                    active_parameter: Some(
+                        1,
+                    ),
+                },
+            )
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_signature_help_on_directive_name() {
+    let fixture = fixture! {
+        "foo" => format!(r#"{DIRECTIVE}
+type Foo {{
+    foo: Int @$d(b: "x")
+}}
+"#)
+    };
+    test(
+        fixture,
+        expect![[r#"
+            None
+        "#]],
+    );
+}
+
+#[test]
+fn test_no_signature_help_for_unresolved_directive() {
+    let fixture = fixture! {
+        "foo" => r#"
+type Foo {
+    foo: Int @unresolved(a: $1)
+}
+"#
+    };
+    test(
+        fixture,
+        expect![[r#"
+            None
+        "#]],
+    );
+}
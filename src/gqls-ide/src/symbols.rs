@@ -103,9 +103,10 @@ impl Snapshot {
             let symbols = items
                 .iter()
                 .map(|(_, item)| item)
+                .filter(|item| !item.name.is_missing())
                 .filter(|item| item.name.to_uppercase().contains(&query))
                 .map(|item| WorkspaceSymbol {
-                    name: item.name.clone(),
+                    name: item.name,
                     kind: item.kind.into(),
                     location: Location::new(file, item.range),
                 });
@@ -118,7 +119,8 @@ impl Snapshot {
     pub fn document_symbols(&self, file: FileId) -> SymbolTree {
         let mut tree = SymbolTree::default();
         let items = self.items(file);
-        for (idx, item) in items.iter() {
+        // the placeholders of incomplete definitions would only flicker in the outline
+        for (idx, item) in items.iter().filter(|(_, item)| !item.is_recovered) {
             let children = self
                 .item_body(ItemRes::new(file, idx))
                 .as_ref()
@@ -128,7 +130,7 @@ impl Snapshot {
                         .iter()
                         .map(|(_, field)| {
                             DocumentSymbol::leaf(
-                                field.name.clone(),
+                                field.name,
                                 SymbolKind::Field,
                                 field.range.into(),
                             )
@@ -139,7 +141,7 @@ impl Snapshot {
                 .unwrap_or_default();
 
             let symbol = DocumentSymbol::new(
-                item.name.clone(),
+                item.name,
                 item.kind.into(),
                 item.range.into(),
                 children,
@@ -31,7 +31,7 @@ fn test_document_symbols() {
                 i: Int
             }
 
-            extend type Extension {
+            extend type Foo {
                 foo: Foo
             }
             "
@@ -51,9 +51,33 @@ fn test_document_symbols() {
                   foo :: Field @ 11:16..11:24 (Foo),
                 Input :: Struct @ 14:12..16:13
                   i :: Field @ 15:16..15:22 (Int),
-                Extension :: Struct @ 18:12..20:13
+                Foo :: Struct @ 18:12..20:13
                   foo :: Field @ 19:16..19:24 (Foo),
             ]
         "#]],
     );
 }
+
+#[test]
+fn test_document_symbols_skip_incomplete_definitions() {
+    let fixture = fixture! {
+        "foo" => "
+            type Foo {
+                bar: Int
+            }
+
+            type {
+                baz: Int
+            }
+            "
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let symbols = ide.snapshot().document_symbols("foo".as_ref());
+    expect![[r#"
+        [
+            Foo :: Struct @ 1:12..3:13
+              bar :: Field @ 2:16..2:24 (Int),
+        ]
+    "#]]
+    .assert_debug_eq(&symbols);
+}
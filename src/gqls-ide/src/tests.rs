@@ -1,9 +1,13 @@
 use expect_test::expect;
+use gqls_db::{DefDatabase, SourceDatabase};
 use gqls_fixture::{fixture, fixture_file, Fixture};
+use gqls_ir::InProject;
 use maplit::hashmap;
+use std::path::Path;
+use std::sync::mpsc;
 use vfs::FileId;
 
-use crate::{Changeset, ChangesetSummary, Ide, VFS};
+use crate::{Changeset, ChangesetSummary, DiagnosticPass, Ide, Point, Range, VFS};
 
 macro_rules! idx {
     ($idx:expr) => {
@@ -52,21 +56,21 @@ impl Ide {
     }
 
     pub fn setup_fixture(&mut self, fixture: &Fixture) {
-        let summary = self.setup_fixture_allow_errors(fixture);
+        self.setup_fixture_allow_errors(fixture);
+        let snapshot = self.snapshot();
         for file in fixture.fileset() {
+            let diagnostics = snapshot.file_diagnostics(file);
             assert!(
-                summary.diagnostics[file].is_empty(),
+                diagnostics.is_empty(),
                 "expected no diagnostics, file `{}`: `{:?}`",
                 file.display(),
-                summary.diagnostics[file],
+                diagnostics,
             );
         }
     }
 
     pub fn setup_fixture_allow_errors(&mut self, fixture: &Fixture) -> ChangesetSummary {
-        let mut changeset = Changeset::default().with_projects(hashmap! {
-            "default" => fixture.fileset()
-        });
+        let mut changeset = Changeset::default().with_projects(fixture.projects());
         for (file, fixture_file) in fixture.files() {
             changeset = changeset.with_change(change!(file => fixture_file.text));
         }
@@ -83,11 +87,167 @@ fn test_ide() {
     let foo = ide.vfs().intern("foo.graphql");
     assert_eq!(ide.file_ropes[&foo].to_string(), "scalar Foo");
     expect![[r#"(document (item (type_definition (scalar_type_definition (name)))))"#]]
-        .assert_eq(&ide.snapshot().syntax_tree(foo));
+        .assert_eq(&ide.snapshot().syntax_tree(foo, None));
 
     let summary = apply!(ide: foo:0:7..0:10 => "Baz");
     assert_eq!(summary.diagnostics, hashmap! { foo => Default::default() });
     assert_eq!(ide.file_ropes[&foo].to_string(), "scalar Baz");
     expect![[r#"(document (item (type_definition (scalar_type_definition (name)))))"#]]
-        .assert_eq(&ide.snapshot().syntax_tree(foo));
+        .assert_eq(&ide.snapshot().syntax_tree(foo, None));
+}
+
+#[test]
+fn test_syntax_tree_of_range() {
+    let mut ide = Ide::default();
+    ide.setup_fixture(&fixture! {
+        "foo.graphql" => "scalar Foo type Bar { baz: Foo }"
+    });
+    let foo = ide.vfs().intern("foo.graphql");
+    let range = |start, end| Some(Range { start: Point::new(0, start), end: Point::new(0, end) });
+    expect![[r#"(field_definition (name) (type (named_type)))"#]]
+        .assert_eq(&ide.snapshot().syntax_tree(foo, range(22, 30)));
+    expect![[r#"(name)"#]].assert_eq(&ide.snapshot().syntax_tree(foo, range(7, 8)));
+    // a range that spans definitions is covered by the document
+    expect![[r#"(document (item (type_definition (scalar_type_definition (name)))) (item (type_definition (object_type_definition (name) (fields_definition (field_definition (name) (type (named_type))))))))"#]]
+        .assert_eq(&ide.snapshot().syntax_tree(foo, range(0, 20)));
+}
+
+#[test]
+fn test_changeset_summary_only_has_syntax_diagnostics() {
+    let mut ide = Ide::default();
+    let fixture = fixture! {
+        "foo" => "
+            type Foo { foo: Unresolved }
+            scalar S
+            scalar S
+        "
+        "bar" => "bad gql"
+    };
+    let foo = ide.vfs().intern("foo");
+    let bar = ide.vfs().intern("bar");
+    let summary = ide.setup_fixture_allow_errors(&fixture);
+    let codes = |diagnostics: &crate::FileDiagnostics| {
+        let mut codes = diagnostics.iter().map(|diag| diag.code.to_string()).collect::<Vec<_>>();
+        codes.sort();
+        codes
+    };
+
+    // the unresolved type is found by the local pass and the duplicate definition by the
+    // project pass, neither of which are part of the summary
+    assert!(summary.diagnostics[foo].is_empty());
+    assert_eq!(codes(&summary.diagnostics[bar]), ["0001"]);
+    let project = summary.projects.iter().copied().next().unwrap();
+    let diagnostics = ide.snapshot().pass_diagnostics(project, DiagnosticPass::Local);
+    assert_eq!(codes(&diagnostics[foo]), ["0003"]);
+    let diagnostics = ide.snapshot().pass_diagnostics(project, DiagnosticPass::Project);
+    assert_eq!(codes(&diagnostics[foo]), ["0005"]);
+    assert_eq!(codes(&ide.snapshot().file_diagnostics(foo)), ["0003", "0005"]);
+}
+
+#[test]
+fn test_load_then_files_of_projects() {
+    let fixture = fixture! {
+        "foo" => "type Foo { bar: Bar }"
+        "bar" => "type Bar { id: ID! }"
+        "baz" => "type Baz { id: ID! }"
+    }
+    .with_project("a", &["foo", "bar"])
+    .with_project("b", &["bar"]);
+    let mut ide = Ide::default();
+    let mut changeset = Changeset::default().with_projects(fixture.projects());
+    for (file, fixture_file) in fixture.files() {
+        changeset = changeset.with_change(change!(file => fixture_file.text));
+    }
+    let projects = ide.load(changeset);
+    assert_eq!(projects.len(), 2);
+    let files = ide.snapshot().files_of(&projects);
+    assert_eq!(files, [Path::new("bar"), Path::new("foo")]);
+}
+
+#[test]
+fn test_index_cache_roundtrip() {
+    let fixture = fixture! {
+        "foo" => "type Foo { bar: Bar }"
+        "bar" => "type Bar { id: ID! }"
+    };
+    let dir = std::env::temp_dir().join(format!("gqls-index-cache-{}", std::process::id()));
+    let ide = Ide::from_fixture(&fixture);
+    ide.save_index_cache(&dir).unwrap();
+
+    let mut cached = Ide::default();
+    assert_eq!(cached.load_index_cache(&dir.join("missing")).unwrap(), 0);
+    assert_eq!(cached.load_index_cache(&dir).unwrap(), 2);
+    cached.setup_fixture(&fixture);
+    let foo = cached.vfs().intern("foo");
+    assert_eq!(cached.snapshot().items(foo), ide.snapshot().items(foo));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_fixture_with_projects() {
+    let fixture = fixture! {
+        "foo" => "type Foo { bar: Bar }"
+        "bar" => "type Bar { id: ID! }"
+        "baz" => "type Baz { bar: Bar foo: Foo }"
+        "excluded" => "type Excluded { foo: Foo }"
+    }
+    .with_project("a", &["foo", "bar"])
+    .with_project("b", &["bar", "baz"]);
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let projects_of = |file: &str| {
+        let mut projects = snapshot.projects_of(InProject::unit(Path::new(file))).to_vec();
+        projects.sort();
+        projects
+    };
+
+    assert_eq!(projects_of("bar"), ["a", "b"]);
+    assert_eq!(projects_of("baz"), ["b"]);
+    assert!(projects_of("excluded").is_empty());
+
+    // `Foo` is only visible within project `a`
+    assert!(snapshot.file_diagnostics(Path::new("foo")).is_empty());
+    let diagnostics = snapshot.file_diagnostics(Path::new("baz"));
+    assert_eq!(
+        diagnostics.iter().map(|diag| diag.message.as_str()).collect::<Vec<_>>(),
+        ["unresolved type `Foo`"]
+    );
+}
+
+#[test]
+fn test_text_relative_macros() {
+    let text = "type Foo {\n  foo: Int\n}\n";
+    let foo = Path::new("foo");
+    assert_eq!(crate::point!(text, 1:eol), crate::point!(1: 10));
+    assert_eq!(crate::point!(text, 1:-3), crate::point!(1: 7));
+    assert_eq!(crate::point!(text, eof), crate::point!(3: 0));
+    assert_eq!(crate::position!(text, foo:0:eol), crate::position!(foo:0:10));
+    assert_eq!(crate::range!(text, 1:2..1:-5), crate::range!(1:2..1:5));
+    assert_eq!(crate::range!(text, 2:0..eof), crate::range!(2:0..3:0));
+}
+
+#[test]
+fn test_change_cancels_in_flight_request() {
+    let mut ide = Ide::from_fixture(&fixture! {
+        "foo" => "type Foo { foo: Int }"
+    });
+    let foo = ide.vfs().intern("foo");
+    let snapshot = ide.snapshot();
+    let (started, has_started) = mpsc::channel();
+    let request = std::thread::spawn(move || {
+        snapshot.catch_cancelled(|snapshot| {
+            started.send(()).unwrap();
+            // stands in for a long running request, it only stops by observing the cancellation
+            loop {
+                snapshot.file_diagnostics(foo);
+            }
+        })
+    });
+    has_started.recv().unwrap();
+    // blocks until the request unwinds and releases its snapshot
+    let _ = ide.apply(change!(foo => "type Bar { bar: Int }"));
+    assert!(request.join().unwrap().is_err());
+
+    let symbols = ide.snapshot().catch_cancelled(|snapshot| snapshot.document_symbols(foo).len());
+    assert!(matches!(symbols, Ok(1)));
 }
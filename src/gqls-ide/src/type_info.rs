@@ -0,0 +1,56 @@
+//! A structured description of a single type of a project, for external consumers such as code
+//! generators that would otherwise have to parse (and merge) the schema themselves
+
+use std::collections::BTreeMap;
+
+use gqls_db::DefDatabase;
+use gqls_ir::{InProject, Name};
+use serde::Serialize;
+use vfs::FileId;
+
+use crate::schema::BUILTIN_SCALARS;
+use crate::{Location, SchemaType, SchemaTypeKind, Snapshot};
+
+/// A type with all of its extensions merged into its definition, see [`Snapshot::schema`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeInfo {
+    #[serde(flatten)]
+    pub ty: SchemaType,
+    /// the definition (if there is one) followed by the extensions, empty for builtin scalars
+    pub locations: Vec<Location>,
+    /// the location of each field of an object or interface or input field of an input object
+    pub field_locations: BTreeMap<String, Location>,
+}
+
+impl Snapshot {
+    /// The type `name` of the project(s) of `file`, `None` if there is no such type
+    pub fn type_info(&self, file: FileId, name: &str) -> Option<TypeInfo> {
+        let typedef = match self.effective_typedef(InProject::new(file, Name::unranged(name))) {
+            Some(typedef) => typedef,
+            None =>
+                return BUILTIN_SCALARS.contains(&name).then(|| TypeInfo {
+                    ty: SchemaType::new(SchemaTypeKind::Scalar, name.to_owned()),
+                    locations: vec![],
+                    field_locations: BTreeMap::new(),
+                }),
+        };
+        let field_locations = typedef
+            .fields
+            .iter()
+            .map(|&res| {
+                let field = self.field(res);
+                (field.name.to_string(), Location::new(res.item.file, field.name.range))
+            })
+            .collect();
+        let locations = typedef
+            .parts
+            .iter()
+            .map(|&res| Location::new(res.file, self.item(res).name.range))
+            .collect();
+        Some(TypeInfo { ty: self.schema_type(name, &typedef.parts)?, locations, field_locations })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,115 @@
+use gqls_fixture::fixture;
+
+use serde_json::json;
+
+use crate::{range, AppliedDirective, Ide, Location, SchemaTypeKind, TypeRef};
+
+#[test]
+fn test_type_info_merges_extensions() {
+    let fixture = fixture! {
+        "foo" => r#"
+"a foo"
+type Foo @key(fields: "id") {
+    id: ID!
+    bar(first: Int = 10): Bar @deprecated
+}
+
+type Bar { id: ID! }
+"#
+        "bar" => "
+extend type Foo implements Node {
+    id: ID!
+    baz: Int
+}
+
+interface Node { id: ID! }
+"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let foo = ide.snapshot().type_info(file_id!("foo"), "Foo").unwrap();
+
+    assert_eq!(foo.ty.kind, SchemaTypeKind::Object);
+    assert_eq!(foo.ty.description.as_deref(), Some("a foo"));
+    assert_eq!(foo.ty.directives, [AppliedDirective {
+        name: "key".to_owned(),
+        arguments: vec![("fields".to_owned(), r#""id""#.to_owned())]
+    }]);
+    assert_eq!(foo.ty.interfaces, ["Node"]);
+    assert_eq!(foo.ty.fields.iter().map(|field| field.name.as_str()).collect::<Vec<_>>(), [
+        "id", "bar", "baz"
+    ]);
+    assert_eq!(foo.field_locations["baz"], Location::new(file_id!("bar"), range!(3:4..3:7)));
+
+    let bar = &foo.ty.fields[1];
+    assert_eq!(bar.ty, TypeRef::Named(Some(SchemaTypeKind::Object), "Bar".to_owned()));
+    assert_eq!(bar.args.len(), 1);
+    assert_eq!(bar.args[0].default_value.as_deref(), Some("10"));
+    assert_eq!(bar.directives[0].name, "deprecated");
+
+    assert_eq!(foo.locations, [
+        Location::new(file_id!("foo"), range!(2:5..2:8)),
+        Location::new(file_id!("bar"), range!(1:12..1:15)),
+    ]);
+}
+
+#[test]
+fn test_type_info_of_builtin_scalar() {
+    let fixture = fixture! {
+        "foo" => "type Foo { id: ID! }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let snapshot = ide.snapshot();
+    let id = snapshot.type_info(file_id!("foo"), "ID").unwrap();
+    assert_eq!(id.ty.kind, SchemaTypeKind::Scalar);
+    assert!(id.locations.is_empty());
+    assert_eq!(snapshot.type_info(file_id!("foo"), "Missing"), None);
+}
+
+#[test]
+fn test_type_info_serialization() {
+    let fixture = fixture! {
+        "foo" => "type Foo { bar: [ID!] }"
+    };
+    let ide = Ide::from_fixture_allow_errors(&fixture);
+    let foo = ide.snapshot().type_info(file_id!("foo"), "Foo").unwrap();
+    let location = |range: [usize; 4]| {
+        json!({
+            "file": file_id!("foo"),
+            "range": {
+                "start": { "row": range[0], "column": range[1] },
+                "end": { "row": range[2], "column": range[3] },
+            },
+        })
+    };
+    assert_eq!(
+        serde_json::to_value(&foo).unwrap(),
+        json!({
+            "kind": "OBJECT",
+            "name": "Foo",
+            "description": null,
+            "directives": [],
+            "fields": [{
+                "name": "bar",
+                "description": null,
+                "args": [],
+                "type": {
+                    "kind": "LIST",
+                    "name": null,
+                    "ofType": {
+                        "kind": "NON_NULL",
+                        "name": null,
+                        "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null },
+                    },
+                },
+                "isDeprecated": false,
+                "directives": [],
+            }],
+            "inputFields": [],
+            "interfaces": [],
+            "possibleTypes": [],
+            "enumValues": [],
+            "locations": [location([0, 5, 0, 8])],
+            "fieldLocations": { "bar": location([0, 11, 0, 14]) },
+        })
+    );
+}
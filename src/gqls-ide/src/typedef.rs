@@ -1,7 +1,7 @@
 use gqls_db::DefDatabase;
 use gqls_syntax::Position;
 
-use crate::{Location, Snapshot};
+use crate::{DefinitionConfig, Location, Snapshot};
 
 impl Snapshot {
     pub fn goto_type_definition(&self, position: Position) -> Vec<Location> {
@@ -15,7 +15,7 @@ impl Snapshot {
                     .map(|&res| Location::new(res.file, self.item(res).name.range))
                     .collect()
             }
-            None => self.goto_definition(position),
+            None => self.goto_definition(position, &DefinitionConfig::default()),
         }
     }
 }
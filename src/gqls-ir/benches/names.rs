@@ -0,0 +1,94 @@
+//! Names are interned, so hashing and comparing them is a matter of hashing and comparing a
+//! pointer rather than the contents of the name, and each distinct name is stored only once.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use gqls_base_db::{InProject, SourceDatabaseStorage};
+use gqls_fixture::FixtureFile;
+use gqls_ir::{DefDatabase, DefDatabaseStorage, Name, Symbol};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::Hash;
+use std::path::Path;
+use std::sync::Arc;
+use testing::TestDatabaseExt;
+
+const TYPES: usize = 500;
+const FIELDS_PER_TYPE: usize = 50;
+
+#[salsa::database(SourceDatabaseStorage, DefDatabaseStorage)]
+struct BenchDB {
+    storage: salsa::Storage<BenchDB>,
+}
+
+impl Default for BenchDB {
+    fn default() -> Self {
+        let mut db = Self { storage: Default::default() };
+        db.set_item_cache(None);
+        db
+    }
+}
+
+impl salsa::Database for BenchDB {
+}
+
+// field names repeat across types (as they tend to in real schemas)
+fn field_name(i: usize) -> String {
+    format!("field{}", i % (FIELDS_PER_TYPE * 2))
+}
+
+// tens of thousands of fields, each referring to another type of the schema
+fn schema() -> String {
+    let mut schema = String::new();
+    for i in 0..TYPES {
+        writeln!(schema, "type T{i} {{").unwrap();
+        for j in 0..FIELDS_PER_TYPE {
+            writeln!(schema, "  {}: T{}", field_name(i + j), (i + j) % TYPES).unwrap();
+        }
+        writeln!(schema, "}}").unwrap();
+    }
+    schema
+}
+
+// the typical use of names: a map from name to whatever is known about it
+fn index<K: Hash + Eq + Clone>(names: &[K]) -> HashMap<K, usize> {
+    let mut map = HashMap::new();
+    for name in names {
+        *map.entry(name.clone()).or_default() += 1;
+    }
+    for name in names {
+        assert!(map.contains_key(name));
+    }
+    map
+}
+
+fn names(c: &mut Criterion) {
+    let strings = (0..TYPES * FIELDS_PER_TYPE).map(field_name).collect::<Vec<_>>();
+    let arcs = strings.iter().map(|s| Arc::<str>::from(s.as_str())).collect::<Vec<_>>();
+    let symbols = strings.iter().map(|s| Symbol::intern(s)).collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("names");
+    group.bench_function("index by string", |b| b.iter(|| index(&arcs)));
+    group.bench_function("index by symbol", |b| b.iter(|| index(&symbols)));
+    group.finish();
+
+    let schema = FixtureFile::parse(&schema());
+    let file = Path::new("");
+    c.bench_function("resolve field types", |b| {
+        b.iter_batched(
+            || BenchDB::from_fixture_file(&schema),
+            |db| {
+                for i in 0..TYPES {
+                    let name = InProject::new(file, Name::unranged(&format!("T{i}")));
+                    let typedef = db.effective_typedef(name).unwrap();
+                    for &field in &typedef.fields {
+                        assert!(!db.field(field).ty.item_resolutions().is_empty());
+                    }
+                }
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, names);
+criterion_main!(benches);
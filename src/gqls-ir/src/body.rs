@@ -1,12 +1,15 @@
-use gqls_syntax::Range;
+use gqls_syntax::{Point, Range};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
 use std::sync::Arc;
 
 use la_arena::Arena;
 
-use crate::{ArenaExt, Diagnostic, Directives, Name, Ty};
+use crate::{cache, ArenaExt, Diagnostic, Directives, Name, Ty};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ItemBody {
@@ -21,6 +24,8 @@ pub enum ItemBodyKind {
     InputObject(InputTypeDefinitionBody),
     Object(ObjectTypeDefinitionBody),
     Union(UnionDefinitionBody),
+    Scalar(ScalarDefinitionBody),
+    DirectiveDefinition(DirectiveDefinitionBody),
     Todo,
 }
 
@@ -30,7 +35,11 @@ impl ItemBody {
             ItemBodyKind::Object(typedef) => &typedef.fields,
             ItemBodyKind::InputObject(typedef) => &typedef.fields,
             ItemBodyKind::Interface(iface) => &iface.fields,
-            ItemBodyKind::Enum(_) | ItemBodyKind::Union(_) | ItemBodyKind::Todo => return None,
+            ItemBodyKind::Enum(_)
+            | ItemBodyKind::Union(_)
+            | ItemBodyKind::Scalar(_)
+            | ItemBodyKind::DirectiveDefinition(_)
+            | ItemBodyKind::Todo => return None,
         };
         Some(&fields.fields)
     }
@@ -46,6 +55,19 @@ impl ItemBody {
     pub fn as_enum(&self) -> &EnumDefinitionBody {
         if let ItemBodyKind::Enum(v) = &self.kind { v } else { panic!("expected enum typedef") }
     }
+
+    pub fn as_directive(&self) -> &DirectiveDefinitionBody {
+        if let ItemBodyKind::DirectiveDefinition(v) = &self.kind {
+            v
+        } else {
+            panic!("expected directive definition")
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveDefinitionBody {
+    pub args: Args,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -58,6 +80,8 @@ pub type Variants = Vec<Variant>;
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Variant {
     pub name: Name,
+    pub description: Option<Arc<str>>,
+    pub is_deprecated: bool,
 }
 
 impl Debug for Variant {
@@ -76,6 +100,12 @@ pub struct UnionDefinitionBody {
     pub types: Vec<Ty>,
 }
 
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScalarDefinitionBody {
+    /// the url given by the `@specifiedBy` directive of the scalar
+    pub specified_by: Option<Arc<str>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ObjectTypeDefinitionBody {
     pub fields: Fields,
@@ -123,7 +153,8 @@ pub struct Field {
     pub ty: Ty,
     pub directives: Directives,
     pub args: Args, // only valid for object fields (empty for input fields)
-    pub default_value: Option<Value>, // only valid for input fields (None for object fields)
+    pub default_value: Option<DefaultValue>, // only valid for input fields (None for object fields)
+    pub description: Option<Arc<str>>,
 }
 
 impl Debug for Field {
@@ -149,8 +180,9 @@ pub struct Arg {
     pub range: Range,
     pub name: Name,
     pub ty: Ty,
-    pub default_value: Option<Value>,
+    pub default_value: Option<DefaultValue>,
     pub directives: Directives,
+    pub description: Option<Arc<str>>,
 }
 
 impl Debug for Arg {
@@ -169,22 +201,48 @@ impl Debug for Arg {
     }
 }
 
-#[derive(Clone, Hash, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct DefaultValue {
+    pub range: Range,
+    pub value: Value,
+}
+
+impl Debug for DefaultValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.value, f)
+    }
+}
+
+#[derive(Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Value {
-    // storing as u64 to avoid f64 Eq pain
+    /// the text of an integer literal that doesn't fit in an `i64`, which is only out of range
+    /// for an `Int` as floats and custom scalars can be larger
+    BigInt(Arc<str>),
     Boolean(bool),
     Enum(Arc<str>),
+    // storing as u64 to avoid f64 Eq pain
     Float(u64),
-    Int(i32),
+    /// wider than an `Int`, so values of custom scalars can be larger, the type checker checks the
+    /// range of `Int` values
+    Int(i64),
     List(Arc<[Value]>),
     Null,
     Object(Arc<BTreeMap<Name, Value>>),
-    String(Arc<str>),
+    String(StringValue),
 }
 
 impl Value {
+    /// Parse an int literal of the spec, which (unlike `str::parse`) doesn't allow a leading `+`
+    pub fn parse_int(text: &str) -> Option<i64> {
+        if text.starts_with('+') {
+            return None;
+        }
+        text.parse().ok()
+    }
+
     pub fn desc(&self) -> &'static str {
         match self {
+            Value::BigInt(_) => "integer",
             Value::Boolean(_) => "boolean",
             Value::Enum(_) => "enum",
             Value::Float(_) => "float",
@@ -199,6 +257,12 @@ impl Value {
 
 impl From<i32> for Value {
     fn from(v: i32) -> Self {
+        Self::Int(v.into())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(v: i64) -> Self {
         Self::Int(v)
     }
 }
@@ -211,7 +275,55 @@ impl From<f64> for Value {
 
 impl<'a> From<&'a str> for Value {
     fn from(s: &'a str) -> Self {
-        Value::String(s.into())
+        Value::String(StringValue::unranged(s))
+    }
+}
+
+/// The value of a string literal along with the range of the raw literal (including its quotes),
+/// like the range of a [`Name`] the range is ignored by comparisons
+#[derive(Clone, Eq, Serialize, Deserialize)]
+pub struct StringValue {
+    /// the value with its escape sequences decoded (or its indentation removed for a block string)
+    pub value: Arc<str>,
+    #[serde(with = "cache::range")]
+    pub range: Range,
+}
+
+impl StringValue {
+    pub fn unranged(value: &str) -> Self {
+        let range = Range {
+            start_byte: 0,
+            end_byte: 0,
+            start_point: Point::new(0, 0),
+            end_point: Point::new(0, 0),
+        };
+        Self { value: Arc::from(value), range }
+    }
+}
+
+impl Deref for StringValue {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl PartialEq for StringValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Hash for StringValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl Display for StringValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.value, f)
     }
 }
 
@@ -226,13 +338,36 @@ impl Debug for Value {
         match self {
             Self::Int(i) => write!(f, "{i}"),
             Self::Float(u) => write!(f, "{}", f64::from_bits(*u)),
-            Self::String(s) => write!(f, "{s:?}"),
+            Self::String(s) => write_string(f, s),
             Self::Boolean(b) => write!(f, "{b}"),
             Self::Enum(s) => write!(f, "{s}"),
             Self::List(vs) => write!(f, "[{:?}]", vs.iter().format(", ")),
-            Self::Object(vs) =>
-                write!(f, "{{ {} }}", vs.iter().map(|(k, v)| format!("{k}: {v:?}")).format(", ")),
+            // the fields are written in the order of the source
+            Self::Object(vs) => {
+                let fields = vs.iter().sorted_by_key(|(k, _)| k.range.start_byte);
+                write!(f, "{{ {} }}", fields.map(|(k, v)| format!("{k}: {v:?}")).format(", "))
+            }
             Self::Null => write!(f, "null"),
+            Self::BigInt(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Write `s` as a graphql string literal, escaping the characters that can't appear in it verbatim
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            c if c.is_control() => write!(f, "\\u{:04X}", c as u32)?,
+            c => write!(f, "{c}")?,
         }
     }
+    f.write_str("\"")
 }
@@ -0,0 +1,171 @@
+//! Items lowered by a previous run, keyed by the hash of the text they were lowered from.
+//! Lowering only depends on the text of a file, so an unchanged file can reuse its items
+//! (see [`DefDatabase::items`]). Persisting the cache is left to the database.
+
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// The hash of the text of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ContentHash([u8; 32]);
+
+impl ContentHash {
+    pub fn of(text: &str) -> Self {
+        Self(*blake3::hash(text.as_bytes()).as_bytes())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedItems {
+    pub items: Arc<Items>,
+    #[serde(with = "item_map")]
+    pub item_map: Arc<ItemMap>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemCache {
+    files: HashMap<ContentHash, Arc<CachedItems>>,
+}
+
+impl ItemCache {
+    pub fn get(&self, hash: ContentHash) -> Option<Arc<CachedItems>> {
+        self.files.get(&hash).cloned()
+    }
+
+    pub fn insert(&mut self, hash: ContentHash, items: Arc<CachedItems>) {
+        self.files.insert(hash, items);
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+// the syntax and arena types don't implement serde, so the fields that hold them use these
+
+pub(crate) mod range {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    type Repr = (usize, usize, (usize, usize), (usize, usize));
+
+    pub(crate) fn serialize<S: Serializer>(
+        range: &Range,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let Range { start_byte, end_byte, start_point, end_point } = *range;
+        let repr: Repr = (
+            start_byte,
+            end_byte,
+            (start_point.row, start_point.column),
+            (end_point.row, end_point.column),
+        );
+        repr.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Range, D::Error> {
+        let (start_byte, end_byte, start, end) = Repr::deserialize(deserializer)?;
+        Ok(Range {
+            start_byte,
+            end_byte,
+            start_point: Point::new(start.0, start.1),
+            end_point: Point::new(end.0, end.1),
+        })
+    }
+}
+
+pub(crate) mod arena {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<T, S>(arena: &Arena<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(arena.iter().map(|(_, value)| value))
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Arena<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+pub(crate) mod idx {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<T, S: Serializer>(
+        idx: &Idx<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        u32::from(idx.into_raw()).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Idx<T>, D::Error> {
+        Ok(Idx::from_raw(RawIdx::from(u32::deserialize(deserializer)?)))
+    }
+}
+
+pub(crate) mod locations {
+    use super::*;
+    use serde::de::Error;
+    use serde::{Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        locations: &DirectiveLocations,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        locations.bits().serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DirectiveLocations, D::Error> {
+        let bits = u32::deserialize(deserializer)?;
+        DirectiveLocations::from_bits(bits)
+            .ok_or_else(|| D::Error::custom(format!("invalid directive locations `{bits:#x}`")))
+    }
+}
+
+mod item_map {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        map: &Arc<ItemMap>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter().map(|(name, indices)| {
+            (name, indices.iter().map(|&idx| u32::from(idx.into_raw())).collect::<Vec<_>>())
+        }))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<ItemMap>, D::Error> {
+        let entries = Vec::<(Symbol, Vec<u32>)>::deserialize(deserializer)?;
+        let map: ItemMap = entries
+            .into_iter()
+            .map(|(name, indices)| {
+                (name, indices.into_iter().map(|idx| Idx::from_raw(RawIdx::from(idx))).collect())
+            })
+            .collect();
+        Ok(Arc::new(map))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use crate::tests::TestDB;
+use crate::{CachedItems, ContentHash, DefDatabase, ItemCache};
+use gqls_base_db::{FileData, SourceDatabase};
+use gqls_fixture::fixture;
+use testing::TestDatabaseExt;
+use vfs::Vfs;
+
+const FOO: &str = r#"
+    schema @link(url: "https://example.com", import: ["@key"]) { query: Query }
+    type Query implements Node @key(fields: "id", resolvable: true) {
+        id: ID!
+    }
+    extend type Query @tag(name: 1.5, values: [1, 2], by: { x: null, y: ENUM })
+    directive @tag(name: Float, values: [Int], by: Input) repeatable on OBJECT | FIELD_DEFINITION
+"#;
+
+fn cached_items(db: &TestDB, file: vfs::FileId) -> (ContentHash, Arc<CachedItems>) {
+    let items = CachedItems { items: db.items(file), item_map: db.item_map(file) };
+    (ContentHash::of(&db.file_text(file)), Arc::new(items))
+}
+
+#[test]
+fn test_cached_items_roundtrip() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo");
+    let db = TestDB::from_fixture(&fixture! { foo => FOO });
+    let (_, cached) = cached_items(&db, foo);
+
+    let bytes = bincode::serialize(&cached).unwrap();
+    let roundtripped = bincode::deserialize::<CachedItems>(&bytes).unwrap();
+    assert_eq!(roundtripped, *cached);
+    // names compare equal regardless of their range, but the debug output includes it
+    assert_eq!(format!("{roundtripped:?}"), format!("{cached:?}"));
+}
+
+#[test]
+fn test_items_are_reused_from_cache() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo");
+    let fixture = fixture! { foo => FOO };
+    let (hash, cached) = cached_items(&TestDB::from_fixture(&fixture), foo);
+
+    let mut cache = ItemCache::default();
+    cache.insert(hash, Arc::clone(&cached));
+    let mut db = TestDB::default();
+    db.set_item_cache(Some(Arc::new(cache)));
+    db.setup_fixture(&fixture);
+    assert!(Arc::ptr_eq(&db.items(foo), &cached.items));
+    assert!(Arc::ptr_eq(&db.item_map(foo), &cached.item_map));
+
+    let text = "type Foo { foo: Foo }";
+    db.set_file_data(foo, FileData::new(text, gqls_syntax::parse_fresh(text)));
+    assert!(!Arc::ptr_eq(&db.items(foo), &cached.items));
+    assert_eq!(db.items(foo).len(), 1);
+}
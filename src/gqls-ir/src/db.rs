@@ -1,8 +1,9 @@
+use std::collections::hash_map::Entry;
 use std::mem::discriminant;
 use std::sync::Arc;
 use std::vec;
 
-use gqls_base_db::{InProject, SourceDatabase};
+use gqls_base_db::{CustomScalar, InProject, Project, RootTypes, SourceDatabase};
 use gqls_syntax::{NodeExt, NodeKind, Position, RangeExt};
 use smallvec::smallvec;
 use vfs::FileId;
@@ -12,8 +13,20 @@ use crate::*;
 
 #[salsa::query_group(DefDatabaseStorage)]
 pub trait DefDatabase: SourceDatabase {
+    /// The items lowered by a previous run, see [`ItemCache`]
+    #[salsa::input]
+    fn item_cache(&self) -> Option<Arc<ItemCache>>;
+
+    fn cached_items(&self, file: FileId) -> Option<Arc<CachedItems>>;
+    fn custom_scalar(&self, name: InProject<Name>) -> Option<CustomScalar>;
+    fn defined_typenames(&self, project: Project) -> Arc<HashSet<String>>;
+    fn dependency_graph(&self, project: Project) -> Arc<DependencyGraph>;
+    fn effective_typedef(&self, name: InProject<Name>) -> Option<Arc<EffectiveTypeDefinition>>;
     fn field(&self, res: FieldRes) -> Field;
     fn implementations(&self, interface: InProject<Name>) -> Vec<ItemRes>;
+    fn import_scope(&self, file: FileId) -> Option<Arc<ImportScope>>;
+    fn import_target(&self, file: FileId, path: String) -> Option<FileId>;
+    fn imports(&self, file: FileId) -> Arc<[Import]>;
     fn item(&self, res: ItemRes) -> Item;
     fn item_at(&self, position: Position) -> Option<Idx<Item>>;
     fn item_body(&self, res: ItemRes) -> Option<Arc<ItemBody>>;
@@ -22,24 +35,120 @@ pub trait DefDatabase: SourceDatabase {
     fn items(&self, file: FileId) -> Arc<Items>;
     fn project_items(&self, project: InProject<()>) -> Arc<ProjectItems>;
     fn name_at(&self, position: Position) -> Option<Name>;
+    fn possible_types(&self, ty: InProject<Name>) -> Vec<ItemRes>;
     fn related_files(&self, file: InProject<()>) -> HashSet<FileId>;
     fn references(&self, res: Res) -> References;
     fn resolve(&self, position: Position) -> Option<Res>;
     fn resolve_item(&self, name: InProject<Name>) -> Res;
+    fn root_types_of(&self, project: InProject<()>) -> RootTypes;
     fn type_at(&self, position: Position) -> Option<Ty>;
     fn typedef(&self, file: FileId, idx: Idx<TypeDefinition>) -> TypeDefinition;
+    fn used_names(&self, project: Project) -> Arc<HashSet<String>>;
 }
 
 // all files that are in a common project with `file`
+#[tracing::instrument(level = "debug", skip_all)]
 fn related_files(db: &dyn DefDatabase, file: InProject<()>) -> HashSet<FileId> {
-    db.projects_of(file).iter().flat_map(|project| db.project_files(project)).collect()
+    db.projects_of(file).iter().flat_map(|project| db.resolution_files(project)).collect()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn project_items(db: &dyn DefDatabase, file: InProject<()>) -> Arc<ProjectItems> {
     let data = db.related_files(file).iter().map(|&file| (file, db.items(file))).collect();
     Arc::new(data)
 }
 
+// the references between the items of `project` through the types in their bodies, excluding
+// those to builtin scalars and unresolved types
+#[tracing::instrument(level = "debug", skip_all)]
+fn dependency_graph(db: &dyn DefDatabase, project: Project) -> Arc<DependencyGraph> {
+    let mut dependencies = vec![];
+    for file in db.resolution_files(project) {
+        let items = db.items(file);
+        for (idx, _) in items.iter() {
+            let from = ItemRes::new(file, idx);
+            let body = match db.item_body(from) {
+                Some(body) => body,
+                None => continue,
+            };
+            let mut tys = vec![];
+            match &body.kind {
+                ItemBodyKind::Union(union) =>
+                    tys.extend(union.types.iter().map(|ty| (ty, DependencyKind::Member))),
+                ItemBodyKind::DirectiveDefinition(directive) => tys.extend(
+                    directive
+                        .args
+                        .iter()
+                        .map(|arg| (&arg.ty, DependencyKind::DirectiveArg(arg.name))),
+                ),
+                _ => {}
+            }
+            for (_, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                tys.push((&field.ty, DependencyKind::Field(field.name)));
+                tys.extend(
+                    field
+                        .args
+                        .iter()
+                        .map(|arg| (&arg.ty, DependencyKind::FieldArg(field.name, arg.name))),
+                );
+            }
+            for (ty, kind) in tys {
+                let to = ty.name();
+                if let Res::Item(_) = db.resolve_item(InProject::new(file, to)) {
+                    dependencies.push(Dependency { from, to, kind });
+                }
+            }
+        }
+    }
+    // the project items are unordered
+    dependencies.sort_by_key(|dependency| {
+        (dependency.from.file, dependency.from.value, dependency.to.range.start_point)
+    });
+    Arc::new(DependencyGraph { dependencies })
+}
+
+// the names of the types defined in `project`, as opposed to only extended
+#[tracing::instrument(level = "debug", skip_all)]
+fn defined_typenames(db: &dyn DefDatabase, project: Project) -> Arc<HashSet<String>> {
+    let mut defined = HashSet::new();
+    for file in db.resolution_files(project) {
+        let items = db.items(file);
+        defined.extend(
+            items
+                .iter()
+                .filter(|(_, item)| match item.kind {
+                    ItemKind::TypeDefinition(typedef) => !items[typedef].is_ext,
+                    ItemKind::DirectiveDefinition(_) => false,
+                })
+                .map(|(_, item)| item.name.to_string()),
+        );
+    }
+    Arc::new(defined)
+}
+
+// the names of the types and directives referenced anywhere in `project`, along with its root
+// operation types which are reachable regardless
+#[tracing::instrument(level = "debug", skip_all)]
+fn used_names(db: &dyn DefDatabase, project: Project) -> Arc<HashSet<String>> {
+    let RootTypes { query, mutation, subscription } =
+        db.root_types().get(project).cloned().unwrap_or_default();
+    let mut used = HashSet::from([query, mutation, subscription]);
+    for file in db.resolution_files(project) {
+        let data = db.file_data(file);
+        used.extend(gqls_syntax::traverse_preorder(&data.tree).filter_map(|node| {
+            let is_reference = match node.kind() {
+                NodeKind::NAMED_TYPE => true,
+                NodeKind::DIRECTIVE_NAME =>
+                    node.parent().map(|parent| parent.kind()) == Some(NodeKind::DIRECTIVE),
+                _ => false,
+            };
+            is_reference.then(|| node.text(&data.text).to_owned())
+        }));
+    }
+    Arc::new(used)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 fn implementations(db: &dyn DefDatabase, interface: InProject<Name>) -> Vec<ItemRes> {
     let mut implementations = vec![];
     for (&file, items) in db.project_items(interface.project()).iter() {
@@ -52,34 +161,156 @@ fn implementations(db: &dyn DefDatabase, interface: InProject<Name>) -> Vec<Item
     implementations
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
+fn imports(db: &dyn DefDatabase, file: FileId) -> Arc<[Import]> {
+    let data = db.file_data(file);
+    gqls_syntax::traverse_preorder(&data.tree)
+        .filter(|node| node.kind() == NodeKind::COMMENT)
+        .filter_map(|node| Import::parse(node, node.text(&data.text)))
+        .collect()
+}
+
+// the file of the project at the path, which need not be normalized
+#[tracing::instrument(level = "debug", skip_all)]
+fn import_target(db: &dyn DefDatabase, file: FileId, path: String) -> Option<FileId> {
+    let path = imports::import_path(file, &path);
+    db.related_files(InProject::unit(file))
+        .into_iter()
+        .find(|related| imports::normalize(related) == path)
+}
+
+// only the files that import anything in a project that enforces imports are restricted
+#[tracing::instrument(level = "debug", skip_all)]
+fn import_scope(db: &dyn DefDatabase, file: FileId) -> Option<Arc<ImportScope>> {
+    let enforced = db.import_projects();
+    if !db.projects_of(InProject::unit(file)).iter().any(|project| enforced.contains(project)) {
+        return None;
+    }
+    let imports = db.imports(file);
+    if imports.is_empty() {
+        return None;
+    }
+    let mut scope = ImportScope::new(file);
+    for import in imports.iter() {
+        if let Some(target) = db.import_target(file, import.path.clone()) {
+            scope.import(target, import.names.as_deref());
+        }
+    }
+    Some(Arc::new(scope))
+}
+
+// the object types that a value of the composite type `ty` may be at runtime
+#[tracing::instrument(level = "debug", skip_all)]
+fn possible_types(db: &dyn DefDatabase, ty: InProject<Name>) -> Vec<ItemRes> {
+    let is_object = |res: &ItemRes| {
+        let items = db.items(res.file);
+        match items[res.value].kind {
+            ItemKind::TypeDefinition(idx) => items[idx].kind == TypeDefinitionKind::Object,
+            ItemKind::DirectiveDefinition(_) => false,
+        }
+    };
+
+    let resolutions = match db.resolve_item(ty) {
+        Res::Item(resolutions) => resolutions,
+        Res::Builtin(_) | Res::Err => return vec![],
+    };
+
+    let mut possible_types = vec![];
+    for res in resolutions {
+        let items = db.items(res.file);
+        let typedef = match items[res.value].kind {
+            ItemKind::TypeDefinition(idx) => &items[idx],
+            ItemKind::DirectiveDefinition(_) => continue,
+        };
+        match typedef.kind {
+            TypeDefinitionKind::Object => possible_types.push(res),
+            TypeDefinitionKind::Interface =>
+                possible_types.extend(db.implementations(ty).into_iter().filter(is_object)),
+            TypeDefinitionKind::Union =>
+                if let Some(body) = db.item_body(res) {
+                    for member in &body.as_union().types {
+                        possible_types
+                            .extend(member.item_resolutions().iter().copied().filter(is_object));
+                    }
+                },
+            TypeDefinitionKind::Enum | TypeDefinitionKind::Input | TypeDefinitionKind::Scalar => {}
+        }
+    }
+
+    // a type may be split across extensions, only keep one resolution per type
+    let mut names = HashSet::new();
+    possible_types.retain(|res| names.insert(db.items(res.file)[res.value].name));
+    possible_types
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+fn cached_items(db: &dyn DefDatabase, file: FileId) -> Option<Arc<CachedItems>> {
+    let cache = db.item_cache()?;
+    cache.get(ContentHash::of(&db.file_text(file)))
+}
+
+// the registry entry of the first project of the file that has one
+#[tracing::instrument(level = "debug", skip_all)]
+fn custom_scalar(db: &dyn DefDatabase, name: InProject<Name>) -> Option<CustomScalar> {
+    let registry = db.custom_scalars();
+    db.projects_of(name.project())
+        .iter()
+        .find_map(|project| registry.get(project)?.get(name.as_str()))
+        .cloned()
+}
+
+// the configuration of the first project of the file that has one
+#[tracing::instrument(level = "debug", skip_all)]
+fn root_types_of(db: &dyn DefDatabase, project: InProject<()>) -> RootTypes {
+    let root_types = db.root_types();
+    db.projects_of(project)
+        .iter()
+        .find_map(|project| root_types.get(project))
+        .cloned()
+        .unwrap_or_default()
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 fn items(db: &dyn DefDatabase, file: FileId) -> Arc<Items> {
+    if let Some(cached) = db.cached_items(file) {
+        return Arc::clone(&cached.items);
+    }
     let data = db.file_data(file);
     lower::ItemCtxt::new(data.text).lower(data.tree)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn item_at(db: &dyn DefDatabase, position: Position) -> Option<Idx<Item>> {
     db.items(position.file)
         .iter()
         .find_map(|(idx, item)| item.range.contains(position.point).then(|| idx))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn item(db: &dyn DefDatabase, res: ItemRes) -> Item {
     db.items(res.file).items[res.value].clone()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn field(db: &dyn DefDatabase, res: FieldRes) -> Field {
     db.item_body(res.item).unwrap().fields().unwrap()[res.idx].clone()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn item_map(db: &dyn DefDatabase, file: FileId) -> Arc<ItemMap> {
+    if let Some(cached) = db.cached_items(file) {
+        return Arc::clone(&cached.item_map);
+    }
     let items = db.items(file);
     let mut map = ItemMap::with_capacity(items.len());
-    for (idx, item) in items.iter() {
-        map.entry(item.name.clone()).or_default().push(idx);
+    // the placeholder names of incomplete definitions can't be referred to
+    for (idx, item) in items.iter().filter(|(_, item)| !item.name.is_missing()) {
+        map.entry(item.name.symbol()).or_default().push(idx);
     }
     Arc::new(map)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn item_body(db: &dyn DefDatabase, res: ItemRes) -> Option<Arc<ItemBody>> {
     let items = db.items(res.file);
     let tree = db.file_tree(res.file);
@@ -88,11 +319,12 @@ fn item_body(db: &dyn DefDatabase, res: ItemRes) -> Option<Arc<ItemBody>> {
     let bcx = BodyCtxt::new(db, res.file);
     let body = match item.kind {
         ItemKind::TypeDefinition(_) => bcx.lower_typedef(item_node),
-        ItemKind::DirectiveDefinition(_) => return None,
+        ItemKind::DirectiveDefinition(_) => bcx.lower_directive_definition(item_node),
     };
     Some(Arc::new(body))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn name_at(db: &dyn DefDatabase, position: Position) -> Option<Name> {
     let data = db.file_data(position.file);
     let root = data.tree.root_node();
@@ -103,6 +335,7 @@ fn name_at(db: &dyn DefDatabase, position: Position) -> Option<Name> {
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn type_at(db: &dyn DefDatabase, position: Position) -> Option<Ty> {
     let data = db.file_data(position.file);
     let root = data.tree.root_node();
@@ -115,6 +348,7 @@ fn type_at(db: &dyn DefDatabase, position: Position) -> Option<Ty> {
     BodyCtxt::new(db, position.file).lower_type(type_node)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn resolve(db: &dyn DefDatabase, position: Position) -> Option<Res> {
     let data = db.file_data(position.file);
     let root = data.tree.root_node();
@@ -129,9 +363,15 @@ fn resolve(db: &dyn DefDatabase, position: Position) -> Option<Res> {
         | NodeKind::OBJECT_TYPE_DEFINITION
         | NodeKind::OBJECT_TYPE_EXTENSION
         | NodeKind::ENUM_TYPE_DEFINITION
+        | NodeKind::ENUM_TYPE_EXTENSION
         | NodeKind::SCALAR_TYPE_DEFINITION
+        | NodeKind::SCALAR_TYPE_EXTENSION
+        | NodeKind::UNION_TYPE_DEFINITION
+        | NodeKind::UNION_TYPE_EXTENSION
         | NodeKind::INPUT_OBJECT_TYPE_DEFINITION
-        | NodeKind::INTERFACE_TYPE_DEFINITION => {
+        | NodeKind::INPUT_OBJECT_TYPE_EXTENSION
+        | NodeKind::INTERFACE_TYPE_DEFINITION
+        | NodeKind::INTERFACE_TYPE_EXTENSION => {
             let idx = db
                 .items(position.file)
                 .items
@@ -145,6 +385,7 @@ fn resolve(db: &dyn DefDatabase, position: Position) -> Option<Res> {
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn resolve_item(db: &dyn DefDatabase, name: InProject<Name>) -> Res {
     match name.as_str() {
         "ID" => return Res::Builtin(BuiltinScalar::ID),
@@ -155,11 +396,15 @@ fn resolve_item(db: &dyn DefDatabase, name: InProject<Name>) -> Res {
         _ => (),
     }
 
+    let scope = db.import_scope(name.file);
     let mut resolutions = smallvec![];
     for project in db.projects_of(name.project()) {
-        for file in db.project_files(project).iter() {
+        for file in db.resolution_files(project).iter() {
+            if scope.as_ref().map_or(false, |scope| !scope.is_visible(file, name.as_str())) {
+                continue;
+            }
             let map = db.item_map(file);
-            if let Some(items) = map.get(&name.value) {
+            if let Some(items) = map.get(&name.symbol()) {
                 for &idx in items {
                     resolutions.push(ItemRes::new(file, idx));
                 }
@@ -167,9 +412,18 @@ fn resolve_item(db: &dyn DefDatabase, name: InProject<Name>) -> Res {
         }
     }
 
+    // the builtin directives may be redefined by a project, but otherwise resolve to the builtins
+    if resolutions.is_empty() && BUILTIN_DIRECTIVES.contains(&name.as_str()) {
+        let file = builtins_file();
+        if let Some(items) = db.item_map(file).get(&name.symbol()) {
+            resolutions.extend(items.iter().map(|&idx| ItemRes::new(file, idx)));
+        }
+    }
+
     if resolutions.is_empty() { Res::Err } else { Res::Item(resolutions) }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn item_references(db: &dyn DefDatabase, res: ItemRes) -> References {
     let mut references = vec![];
     let res_item = db.item(res);
@@ -192,7 +446,7 @@ fn item_references(db: &dyn DefDatabase, res: ItemRes) -> References {
             match res_item.kind {
                 ItemKind::TypeDefinition(_) => references.extend(
                     fields
-                        .filter(|field| field.ty.name() == name.clone())
+                        .filter(|field| field.ty.name() == name)
                         .map(|field| (file, field.ty.name().range)),
                 ),
                 ItemKind::DirectiveDefinition(_) => references.extend(
@@ -208,6 +462,7 @@ fn item_references(db: &dyn DefDatabase, res: ItemRes) -> References {
     references
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn references(db: &dyn DefDatabase, res: Res) -> References {
     match res {
         Res::Item(resolutions) => match resolutions[..] {
@@ -218,6 +473,81 @@ fn references(db: &dyn DefDatabase, res: Res) -> References {
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn typedef(db: &dyn DefDatabase, file: FileId, idx: Idx<TypeDefinition>) -> TypeDefinition {
     db.items(file).typedefs[idx].clone()
 }
+
+#[tracing::instrument(level = "debug", skip_all)]
+fn effective_typedef(
+    db: &dyn DefDatabase,
+    name: InProject<Name>,
+) -> Option<Arc<EffectiveTypeDefinition>> {
+    let mut parts = db
+        .resolve_item(name)
+        .try_into_item()
+        .ok()?
+        .into_iter()
+        .filter_map(|res| match db.item(res).kind {
+            ItemKind::TypeDefinition(idx) => Some((res, db.typedef(res.file, idx))),
+            ItemKind::DirectiveDefinition(_) => None,
+        })
+        .collect::<Vec<_>>();
+    // the base definition comes first, followed by the extensions in a stable order
+    parts.sort_by_key(|(res, typedef)| (typedef.is_ext, res.file, res.value));
+
+    // any further base definitions are duplicates (which are reported separately), not parts
+    let kind = parts.first()?.1.kind.clone();
+    let mut has_base = false;
+    parts.retain(|(_, typedef)| {
+        let is_duplicate = !typedef.is_ext && std::mem::replace(&mut has_base, true);
+        typedef.kind == kind && !is_duplicate
+    });
+
+    let mut typedef = EffectiveTypeDefinition {
+        name: db.item(parts[0].0).name,
+        kind,
+        description: parts[0].1.description.clone(),
+        parts: vec![],
+        directives: vec![],
+        implementations: vec![],
+        fields: vec![],
+        duplicate_fields: vec![],
+        members: vec![],
+        variants: vec![],
+    };
+    let mut fields = HashMap::new();
+    for (res, part) in parts {
+        typedef.parts.push(res);
+        typedef.directives.extend(part.directives);
+        let mut implementations = part.implementations.into_iter().flatten().collect::<Vec<_>>();
+        implementations.sort();
+        for interface in implementations {
+            if !typedef.implementations.contains(&interface) {
+                typedef.implementations.push(interface);
+            }
+        }
+
+        let body = match db.item_body(res) {
+            Some(body) => body,
+            None => continue,
+        };
+        match &body.kind {
+            ItemBodyKind::Union(union) => typedef.members.extend(union.types.iter().cloned()),
+            ItemBodyKind::Enum(body) => typedef.variants.extend(body.variants.iter().cloned()),
+            _ =>
+                for (idx, field) in body.fields().into_iter().flat_map(|fields| fields.iter()) {
+                    let field_res = FieldRes::new(res, idx);
+                    match fields.entry(field.name) {
+                        Entry::Occupied(entry) =>
+                            typedef.duplicate_fields.push((field_res, *entry.get())),
+                        Entry::Vacant(entry) => {
+                            entry.insert(field_res);
+                            typedef.fields.push(field_res);
+                        }
+                    }
+                },
+        }
+    }
+    Some(Arc::new(typedef))
+}
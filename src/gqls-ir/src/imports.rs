@@ -0,0 +1,114 @@
+//! graphql-import comments, e.g. `# import Foo, Bar from "./other.graphql"`. The imported files
+//! are linked to and, in the projects that enforce imports, a file that imports anything only
+//! sees the names it imports (along with its own).
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use gqls_syntax::{Node, Point, Range};
+use vfs::FileId;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Import {
+    /// the imported names, `None` if everything (`*`) is imported
+    pub names: Option<Vec<String>>,
+    /// the path of the imported file, relative to the importing file
+    pub path: String,
+    /// the range of the path (excluding the quotes)
+    pub range: Range,
+}
+
+impl Import {
+    /// Parse an import `comment`, the node is only used for its position
+    pub(crate) fn parse(comment: Node<'_>, text: &str) -> Option<Self> {
+        let rest = text.strip_prefix('#')?.trim_start().strip_prefix("import")?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let (names, from) = rest.split_once(" from ")?;
+        let names = match names.trim() {
+            "*" => None,
+            // a field import (`Foo.bar`) makes the type visible
+            names => Some(
+                names
+                    .split(',')
+                    .map(|name| name.split('.').next().unwrap_or_default().trim().to_owned())
+                    .filter(|name| !name.is_empty())
+                    .collect(),
+            ),
+        };
+
+        let from = from.trim_start();
+        let quote = from.chars().next().filter(|&c| c == '"' || c == '\'')?;
+        let path = &from[1..][..from[1..].find(quote)?];
+        let start = text.len() - from.len() + 1;
+        let Point { row, column } = comment.start_position();
+        let range = Range {
+            start_byte: comment.start_byte() + start,
+            end_byte: comment.start_byte() + start + path.len(),
+            start_point: Point::new(row, column + start),
+            end_point: Point::new(row, column + start + path.len()),
+        };
+        Some(Self { names, path: path.to_owned(), range })
+    }
+}
+
+/// What a file that imports anything sees of the files of its projects, see
+/// [`DefDatabase::import_scope`](crate::DefDatabase::import_scope)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportScope {
+    file: FileId,
+    /// the imported files with the names imported from them, `None` if everything is
+    imported: HashMap<FileId, Option<HashSet<String>>>,
+}
+
+impl ImportScope {
+    pub(crate) fn new(file: FileId) -> Self {
+        Self { file, imported: Default::default() }
+    }
+
+    pub(crate) fn import(&mut self, file: FileId, names: Option<&[String]>) {
+        let imported = self.imported.entry(file).or_insert_with(|| Some(HashSet::new()));
+        match (imported.as_mut(), names) {
+            (Some(imported), Some(names)) => imported.extend(names.iter().cloned()),
+            _ => *imported = None,
+        }
+    }
+
+    /// Whether the definitions (and extensions) of `name` in `file` are visible, directives may
+    /// be imported with or without their `@`
+    pub fn is_visible(&self, file: FileId, name: &str) -> bool {
+        if file == self.file {
+            return true;
+        }
+        match self.imported.get(&file) {
+            Some(Some(names)) => names.iter().any(|imported| {
+                imported.trim_start_matches('@') == name.trim_start_matches('@')
+            }),
+            Some(None) => true,
+            None => false,
+        }
+    }
+}
+
+/// The path of the file imported by `file`, with the `.` and `..` components resolved lexically
+pub(crate) fn import_path(file: FileId, path: &str) -> PathBuf {
+    normalize(&file.parent().unwrap_or_else(|| Path::new("")).join(path))
+}
+
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests;
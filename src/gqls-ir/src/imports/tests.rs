@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use gqls_syntax::NodeKind;
+
+use super::{import_path, Import};
+
+fn parse(comment: &str) -> Option<Import> {
+    let tree = gqls_syntax::parse_fresh(comment);
+    let node = gqls_syntax::traverse_preorder(&tree).find(|node| node.kind() == NodeKind::COMMENT)?;
+    Import::parse(node, comment)
+}
+
+#[test]
+fn test_parse_import() {
+    let import = parse(r#"# import Foo, Bar.baz from "./foo.graphql""#).unwrap();
+    assert_eq!(import.names, Some(vec!["Foo".to_owned(), "Bar".to_owned()]));
+    assert_eq!(import.path, "./foo.graphql");
+    assert_eq!((import.range.start_point.column, import.range.end_point.column), (28, 41));
+
+    let import = parse("#import * from '../foo.graphql'").unwrap();
+    assert_eq!(import.names, None);
+    assert_eq!(import.path, "../foo.graphql");
+
+    assert!(parse("# important from \"foo.graphql\"").is_none());
+    assert!(parse("# import Foo").is_none());
+    assert!(parse("# import Foo from foo.graphql").is_none());
+}
+
+#[test]
+fn test_import_path() {
+    let file = Path::new("schema/types/foo.graphql");
+    assert_eq!(import_path(file, "./bar.graphql"), Path::new("schema/types/bar.graphql"));
+    assert_eq!(import_path(file, "../bar.graphql"), Path::new("schema/bar.graphql"));
+    assert_eq!(import_path(Path::new("foo.graphql"), "bar.graphql"), Path::new("bar.graphql"));
+}
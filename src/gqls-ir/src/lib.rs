@@ -1,34 +1,66 @@
 #![deny(rust_2018_idioms)]
 
 mod body;
+mod cache;
 mod db;
 mod diagnostic;
+mod imports;
 mod lower;
+mod symbol;
 mod ty;
 
 pub use self::body::*;
+pub use self::cache::{CachedItems, ContentHash, ItemCache};
 pub use self::db::{DefDatabase, DefDatabaseStorage};
 pub use self::diagnostic::{Diagnostic, DiagnosticKind};
+pub use self::imports::{Import, ImportScope};
+pub use self::symbol::Symbol;
 pub use self::ty::*;
 pub use gqls_base_db::{InFile, InProject, SourceDatabase, SourceDatabaseStorage};
 pub use la_arena::{Arena, Idx, IdxRange, RawIdx};
 
 use gqls_syntax::{Node, NodeExt, Point, Range, RangeExt};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
-use smol_str::SmolStr;
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Debug, Display};
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::sync::Arc;
 use vfs::FileId;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The builtin directive definitions, see [`builtins_file`]
+pub const BUILTINS: &str = include_str!("builtins.graphql");
+
+/// Where the builtins live as far as the database is concerned.
+/// The file is not read, but clients may write it out so that goto definition has somewhere to go.
+pub static BUILTINS_PATH: Lazy<PathBuf> =
+    Lazy::new(|| std::env::temp_dir().join("gqls").join("builtins.graphql"));
+
+const BUILTIN_DIRECTIVES: [&str; 4] = ["@skip", "@include", "@deprecated", "@specifiedBy"];
+
+const SPECIFIED_BY: &str = "@specifiedBy";
+
+/// The file containing [`BUILTINS`], which is not part of any project.
+/// Directives that don't resolve within a project fall back to its definitions, so the database
+/// must have its file data set before resolving names.
+pub fn builtins_file() -> FileId {
+    BUILTINS_PATH.as_path()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Items {
+    #[serde(with = "cache::arena")]
     pub items: Arena<Item>,
+    #[serde(with = "cache::arena")]
     typedefs: Arena<TypeDefinition>,
+    #[serde(with = "cache::arena")]
     directives: Arena<DirectiveDefinition>,
+    /// schema definitions and extensions are kept separately as they are not named
+    pub schemas: Vec<SchemaDefinition>,
 }
 
 macro_rules! impl_index {
@@ -55,6 +87,14 @@ impl Items {
         }
     }
 
+    pub fn description(&self, idx: Idx<Item>) -> Option<&str> {
+        match self.items[idx].kind {
+            ItemKind::TypeDefinition(typedef) => self.typedefs[typedef].description.as_deref(),
+            ItemKind::DirectiveDefinition(directive) =>
+                self.directives[directive].description.as_deref(),
+        }
+    }
+
     pub fn implements(&self, idx: Idx<Item>, interface: &Name) -> bool {
         match self.items[idx].kind {
             ItemKind::TypeDefinition(typedef) => &self.typedefs[typedef].implementations,
@@ -74,45 +114,174 @@ impl Deref for Items {
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Item {
     pub name: Name,
+    #[serde(with = "cache::range")]
     pub range: Range,
     pub kind: ItemKind,
+    /// whether the definition is incomplete and parts of it (e.g. the name) are placeholders
+    pub is_recovered: bool,
 }
 
 impl Debug for Item {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("Item")
-            .field("name", &self.name)
+        let mut s = f.debug_struct("Item");
+        s.field("name", &self.name)
             .field("range", &self.range.debug())
-            .field("kind", &self.kind)
+            .field("kind", &self.kind);
+        if self.is_recovered {
+            s.field("is_recovered", &self.is_recovered);
+        }
+        s.finish()
+    }
+}
+
+/// A schema definition or extension, e.g. `extend schema @link(url: "..") { query: Query }`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaDefinition {
+    #[serde(with = "cache::range")]
+    pub range: Range,
+    pub directives: Directives,
+    pub operations: Vec<RootOperation>,
+    pub is_ext: bool,
+}
+
+impl Debug for SchemaDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaDefinition")
+            .field("range", &self.range.debug())
+            .field("directives", &self.directives)
+            .field("operations", &self.operations)
+            .field("is_ext", &self.is_ext)
             .finish()
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootOperation {
+    pub kind: OperationKind,
+    pub ty: Name,
+}
+
+impl Debug for RootOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.ty)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+impl Display for OperationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OperationKind::Query => write!(f, "query"),
+            OperationKind::Mutation => write!(f, "mutation"),
+            OperationKind::Subscription => write!(f, "subscription"),
+        }
+    }
+}
+
 pub type Directives = Vec<Directive>;
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Directive {
+    #[serde(with = "cache::range")]
     pub range: Range,
     pub name: Name,
+    pub arguments: Vec<Argument>,
+}
+
+impl Directive {
+    pub fn argument(&self, name: &str) -> Option<&Argument> {
+        self.arguments.iter().find(|arg| arg.name.as_str() == name)
+    }
+
+    /// The (trimmed) url of a `@specifiedBy` directive along with the argument it is given by
+    pub fn specified_by(&self) -> Option<(&str, &Argument)> {
+        if self.name.as_str() != SPECIFIED_BY {
+            return None;
+        }
+        let argument = self.argument("url")?;
+        match &argument.value {
+            Value::String(url) if !url.trim().is_empty() => Some((url.trim(), argument)),
+            _ => None,
+        }
+    }
+}
+
+/// An argument applied to a directive, e.g. `fields: "id"` of `@key(fields: "id")`
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Argument {
+    pub name: Name,
+    pub value: Value,
+    /// the range of the value node (including the quotes of strings)
+    #[serde(with = "cache::range")]
+    pub value_range: Range,
+}
+
+impl Debug for Argument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}", self.name, self.value)
+    }
 }
 
 bitflags::bitflags! {
     #[derive(Default)]
-    pub struct DirectiveLocations: u16 {
+    pub struct DirectiveLocations: u32 {
         const ARGUMENT_DEFINITION = 1 << 0;
         const ENUM = 1 << 1;
         const ENUM_VALUE = 1 << 2;
-        const FIELD_DEFINITION = 1 << 3;
-        const INPUT_FIELD_DEFINITION = 1 << 4;
-        const INPUT_OBJECT = 1 << 5;
-        const INTERFACE = 1 << 6;
-        const OBJECT = 1 << 7;
-        const SCALAR = 1 << 8;
-        const SCHEMA = 1 << 9;
-        const UNION = 1 << 10;
+        const FIELD = 1 << 3;
+        const FIELD_DEFINITION = 1 << 4;
+        const FRAGMENT_DEFINITION = 1 << 5;
+        const FRAGMENT_SPREAD = 1 << 6;
+        const INLINE_FRAGMENT = 1 << 7;
+        const INPUT_FIELD_DEFINITION = 1 << 8;
+        const INPUT_OBJECT = 1 << 9;
+        const INTERFACE = 1 << 10;
+        const MUTATION = 1 << 11;
+        const OBJECT = 1 << 12;
+        const QUERY = 1 << 13;
+        const SCALAR = 1 << 14;
+        const SCHEMA = 1 << 15;
+        const SUBSCRIPTION = 1 << 16;
+        const UNION = 1 << 17;
+        const VARIABLE_DEFINITION = 1 << 18;
+    }
+}
+
+impl DirectiveLocations {
+    /// The executable and type system locations by name, in the order of the specification
+    pub const NAMED: [(&'static str, Self); 19] = [
+        ("QUERY", Self::QUERY),
+        ("MUTATION", Self::MUTATION),
+        ("SUBSCRIPTION", Self::SUBSCRIPTION),
+        ("FIELD", Self::FIELD),
+        ("FRAGMENT_DEFINITION", Self::FRAGMENT_DEFINITION),
+        ("FRAGMENT_SPREAD", Self::FRAGMENT_SPREAD),
+        ("INLINE_FRAGMENT", Self::INLINE_FRAGMENT),
+        ("VARIABLE_DEFINITION", Self::VARIABLE_DEFINITION),
+        ("SCHEMA", Self::SCHEMA),
+        ("SCALAR", Self::SCALAR),
+        ("OBJECT", Self::OBJECT),
+        ("FIELD_DEFINITION", Self::FIELD_DEFINITION),
+        ("ARGUMENT_DEFINITION", Self::ARGUMENT_DEFINITION),
+        ("INTERFACE", Self::INTERFACE),
+        ("UNION", Self::UNION),
+        ("ENUM", Self::ENUM),
+        ("ENUM_VALUE", Self::ENUM_VALUE),
+        ("INPUT_OBJECT", Self::INPUT_OBJECT),
+        ("INPUT_FIELD_DEFINITION", Self::INPUT_FIELD_DEFINITION),
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMED.into_iter().find(|&(n, _)| n == name).map(|(_, location)| location)
     }
 }
 
@@ -122,10 +291,10 @@ impl Debug for Directive {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ItemKind {
-    TypeDefinition(Idx<TypeDefinition>),
-    DirectiveDefinition(Idx<DirectiveDefinition>),
+    TypeDefinition(#[serde(with = "cache::idx")] Idx<TypeDefinition>),
+    DirectiveDefinition(#[serde(with = "cache::idx")] Idx<DirectiveDefinition>),
 }
 
 impl ItemKind {
@@ -136,15 +305,36 @@ impl ItemKind {
 
 pub type Implementations = HashSet<Name>;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TypeDefinition {
     pub directives: Directives,
     pub implementations: Option<Implementations>,
     pub kind: TypeDefinitionKind,
     pub is_ext: bool,
+    pub description: Option<Arc<str>>,
 }
 
+/// A type definition merged with all of its extensions, see [`DefDatabase::effective_typedef`]
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveTypeDefinition {
+    /// the name of the first part
+    pub name: Name,
+    pub kind: TypeDefinitionKind,
+    /// the description of the base definition
+    pub description: Option<Arc<str>>,
+    /// the base definition (if there is one) followed by the extensions
+    pub parts: Vec<ItemRes>,
+    pub directives: Directives,
+    pub implementations: Vec<Name>,
+    /// the fields of all parts, if a field is defined more than once the first definition wins
+    pub fields: Vec<FieldRes>,
+    /// pairs of a field and the earlier definition of a field of the same name
+    pub duplicate_fields: Vec<(FieldRes, FieldRes)>,
+    pub members: Vec<Ty>,
+    pub variants: Variants,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TypeDefinitionKind {
     Object,
     Interface,
@@ -167,46 +357,53 @@ impl TypeDefinitionKind {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DirectiveDefinition {
+    #[serde(with = "cache::locations")]
     pub locations: DirectiveLocations,
+    pub description: Option<Arc<str>>,
 }
 
-#[derive(Clone, Ord, PartialOrd, Eq)]
+#[derive(Clone, Copy, Ord, PartialOrd, Eq, Serialize, Deserialize)]
 pub struct Name {
-    name: SmolStr,
+    symbol: Symbol,
+    #[serde(with = "cache::range")]
     pub range: Range,
 }
 
-impl Borrow<SmolStr> for Name {
-    fn borrow(&self) -> &SmolStr {
-        &self.name
+impl Borrow<Symbol> for Name {
+    fn borrow(&self) -> &Symbol {
+        &self.symbol
     }
 }
 
 impl Name {
-    pub fn name(&self) -> SmolStr {
-        self.name.clone()
+    pub fn symbol(self) -> Symbol {
+        self.symbol
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.symbol.as_str()
     }
 }
 
 impl Deref for Name {
-    type Target = SmolStr;
+    type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        &self.name
+        self.symbol.as_str()
     }
 }
 
 impl PartialEq for Name {
     fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
+        self.symbol == other.symbol
     }
 }
 
 impl Hash for Name {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+        self.symbol.hash(state);
     }
 }
 
@@ -218,18 +415,30 @@ impl Debug for Name {
 
 impl Display for Name {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Display::fmt(&self.name, f)
+        fmt::Display::fmt(&self.symbol, f)
     }
 }
 
 impl Name {
     pub fn new(text: &(impl HasText + ?Sized), node: Node<'_>) -> Self {
-        Self { name: SmolStr::new(node.text(text.text())), range: node.range() }
+        Self { symbol: Symbol::intern(node.text(text.text())), range: node.range() }
+    }
+
+    /// The text of the placeholder of a missing name, which can't clash with a valid name
+    pub const MISSING: &'static str = "{missing}";
+
+    /// The placeholder of a name that is missing from an incomplete definition at `range`
+    pub fn missing(range: Range) -> Self {
+        Self { symbol: Symbol::intern(Self::MISSING), range }
+    }
+
+    pub fn is_missing(&self) -> bool {
+        self.as_str() == Self::MISSING
     }
 
     pub fn unranged(s: &str) -> Self {
         Self {
-            name: SmolStr::new(s),
+            symbol: Symbol::intern(s),
             range: Range {
                 start_byte: 0,
                 end_byte: 0,
@@ -260,11 +469,57 @@ impl HasText for str {
 }
 
 pub type ProjectItems = HashMap<FileId, Arc<Items>>;
-pub type ItemMap = HashMap<Name, SmallVec<[Idx<Item>; 1]>>;
+pub type ItemMap = HashMap<Symbol, SmallVec<[Idx<Item>; 1]>>;
 pub type ItemResolutions = SmallVec<[ItemRes; 1]>;
 // TODO what is the right type for these (should it be something `Range` based or something more like `Res` and index based)
 pub type References = Vec<(FileId, Range)>;
 
+/// A reference to a type from the body of an item, i.e. from the type of one of its fields or
+/// arguments or from one of its union members
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Dependency {
+    /// the definition or extension the reference is in
+    pub from: ItemRes,
+    /// the name of the referenced type, ranged at the reference
+    pub to: Name,
+    pub kind: DependencyKind,
+}
+
+/// What in the body of an item a [`Dependency`] is the type of
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DependencyKind {
+    /// a field or input field
+    Field(Name),
+    /// an argument of the field
+    FieldArg(Name, Name),
+    /// an argument of a directive definition
+    DirectiveArg(Name),
+    /// a union has the type as a member
+    Member,
+}
+
+/// The dependencies between the items of a project, see [`DefDatabase::dependency_graph`]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    dependencies: Vec<Dependency>,
+}
+
+impl DependencyGraph {
+    pub fn iter(&self) -> impl Iterator<Item = &Dependency> {
+        self.dependencies.iter()
+    }
+
+    /// The dependencies of the definition or extension `res`
+    pub fn outgoing(&self, res: ItemRes) -> impl Iterator<Item = &Dependency> {
+        self.dependencies.iter().filter(move |dependency| dependency.from == res)
+    }
+
+    /// The dependencies on the type named `name`
+    pub fn incoming(&self, name: Name) -> impl Iterator<Item = &Dependency> {
+        self.dependencies.iter().filter(move |dependency| dependency.to == name)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum Res {
     Builtin(BuiltinScalar),
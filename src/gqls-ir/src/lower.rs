@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use gqls_syntax::{Node, NodeExt, NodeKind, Tree};
+use gqls_syntax::{Node, NodeExt, NodeKind, Range, Tree};
 
 use crate::*;
 
@@ -8,31 +8,50 @@ pub(crate) struct BodyCtxt<'db> {
     db: &'db dyn DefDatabase,
     text: Arc<str>,
     file: FileId,
+    /// only the items of a file larger than [`SourceDatabase::max_file_size`] are lowered, their
+    /// bodies are left empty
+    shallow: bool,
     diagnostics: Vec<Diagnostic>,
 }
 
 impl<'db> BodyCtxt<'db> {
     pub(crate) fn new(db: &'db dyn DefDatabase, file: FileId) -> Self {
         let text = db.file_text(file);
-        Self { db, text, file, diagnostics: Default::default() }
+        let shallow = db.is_shallow(file);
+        Self { db, text, file, shallow, diagnostics: Default::default() }
+    }
+
+    /// The child of `node` of the given kind that holds (part of) its body
+    fn body_of<'tree>(&self, node: Node<'tree>, kind: &'static str) -> Option<Node<'tree>> {
+        node.child_of_kind(kind).filter(|_| !self.shallow)
     }
 
     pub fn lower_typedef(mut self, node: Node<'_>) -> ItemBody {
         let kind = match node.kind() {
             NodeKind::OBJECT_TYPE_DEFINITION | NodeKind::OBJECT_TYPE_EXTENSION =>
                 ItemBodyKind::Object(self.lower_object_typedef(node)),
-            NodeKind::INTERFACE_TYPE_DEFINITION =>
+            NodeKind::INTERFACE_TYPE_DEFINITION | NodeKind::INTERFACE_TYPE_EXTENSION =>
                 ItemBodyKind::Interface(self.lower_interface_typedef(node)),
-            NodeKind::INPUT_OBJECT_TYPE_DEFINITION =>
+            NodeKind::INPUT_OBJECT_TYPE_DEFINITION | NodeKind::INPUT_OBJECT_TYPE_EXTENSION =>
                 ItemBodyKind::InputObject(self.lower_input_object_typedef(node)),
-            NodeKind::UNION_TYPE_DEFINITION => ItemBodyKind::Union(self.lower_union_typedef(node)),
-            NodeKind::ENUM_TYPE_DEFINITION => ItemBodyKind::Enum(self.lower_enum_typedef(node)),
-            // TODO extensions etc
+            NodeKind::UNION_TYPE_DEFINITION | NodeKind::UNION_TYPE_EXTENSION =>
+                ItemBodyKind::Union(self.lower_union_typedef(node)),
+            NodeKind::ENUM_TYPE_DEFINITION | NodeKind::ENUM_TYPE_EXTENSION =>
+                ItemBodyKind::Enum(self.lower_enum_typedef(node)),
+            NodeKind::SCALAR_TYPE_DEFINITION | NodeKind::SCALAR_TYPE_EXTENSION =>
+                ItemBodyKind::Scalar(self.lower_scalar_typedef(node)),
             _ => ItemBodyKind::Todo,
         };
         ItemBody { diagnostics: self.diagnostics, kind }
     }
 
+    pub fn lower_directive_definition(mut self, node: Node<'_>) -> ItemBody {
+        assert_eq!(node.kind(), NodeKind::DIRECTIVE_DEFINITION);
+        let args = self.lower_args_of(node);
+        let kind = ItemBodyKind::DirectiveDefinition(DirectiveDefinitionBody { args });
+        ItemBody { diagnostics: self.diagnostics, kind }
+    }
+
     fn lower_object_typedef(&mut self, node: Node<'_>) -> ObjectTypeDefinitionBody {
         assert!(
             [NodeKind::OBJECT_TYPE_DEFINITION, NodeKind::OBJECT_TYPE_EXTENSION]
@@ -42,28 +61,52 @@ impl<'db> BodyCtxt<'db> {
     }
 
     fn lower_input_object_typedef(&mut self, node: Node<'_>) -> InputTypeDefinitionBody {
-        assert_eq!(node.kind(), NodeKind::INPUT_OBJECT_TYPE_DEFINITION);
-        let fields = node
-            .child_of_kind(NodeKind::INPUT_FIELDS_DEFINITION)
+        assert!(
+            [NodeKind::INPUT_OBJECT_TYPE_DEFINITION, NodeKind::INPUT_OBJECT_TYPE_EXTENSION]
+                .contains(&node.kind())
+        );
+        let fields = self
+            .body_of(node, NodeKind::INPUT_FIELDS_DEFINITION)
             .map(|fields| self.lower_input_fields(fields))
             .unwrap_or_default();
         InputTypeDefinitionBody { fields }
     }
 
     fn lower_interface_typedef(&mut self, node: Node<'_>) -> InterfaceDefinitionBody {
-        assert_eq!(node.kind(), NodeKind::INTERFACE_TYPE_DEFINITION);
+        assert!(
+            [NodeKind::INTERFACE_TYPE_DEFINITION, NodeKind::INTERFACE_TYPE_EXTENSION]
+                .contains(&node.kind())
+        );
         InterfaceDefinitionBody { fields: self.lower_fields_of(node) }
     }
 
     fn lower_enum_typedef(&mut self, node: Node<'_>) -> EnumDefinitionBody {
-        assert_eq!(node.kind(), NodeKind::ENUM_TYPE_DEFINITION);
-        let variants = node
-            .child_of_kind(NodeKind::ENUM_VALUES_DEFINITION)
+        assert!(
+            [NodeKind::ENUM_TYPE_DEFINITION, NodeKind::ENUM_TYPE_EXTENSION].contains(&node.kind())
+        );
+        let variants = self
+            .body_of(node, NodeKind::ENUM_VALUES_DEFINITION)
             .map(|variants| self.lower_enum_variants(variants))
             .unwrap_or_default();
         EnumDefinitionBody { variants }
     }
 
+    fn lower_scalar_typedef(&mut self, node: Node<'_>) -> ScalarDefinitionBody {
+        assert!(
+            [NodeKind::SCALAR_TYPE_DEFINITION, NodeKind::SCALAR_TYPE_EXTENSION]
+                .contains(&node.kind())
+        );
+        if self.shallow {
+            return ScalarDefinitionBody::default();
+        }
+        let specified_by = self
+            .lower_directives_of(node)
+            .iter()
+            .find_map(|directive| directive.specified_by())
+            .map(|(url, _)| Arc::from(url));
+        ScalarDefinitionBody { specified_by }
+    }
+
     fn lower_enum_variants(&mut self, node: Node<'_>) -> Variants {
         assert_eq!(node.kind(), NodeKind::ENUM_VALUES_DEFINITION);
         node.children_of_kind(&mut node.walk(), NodeKind::ENUM_VALUE_DEFINITION)
@@ -73,13 +116,21 @@ impl<'db> BodyCtxt<'db> {
 
     fn lower_enum_variant(&mut self, node: Node<'_>) -> Option<Variant> {
         assert_eq!(node.kind(), NodeKind::ENUM_VALUE_DEFINITION);
-        Some(Variant { name: self.name_of(node.child_of_kind(NodeKind::ENUM_VALUE)?)? })
+        let name = self.name_of(node.child_of_kind(NodeKind::ENUM_VALUE)?)?;
+        let description = self.lower_description_of(node);
+        let is_deprecated = self
+            .lower_directives_of(node)
+            .iter()
+            .any(|directive| directive.name.as_str() == "@deprecated");
+        Some(Variant { name, description, is_deprecated })
     }
 
     fn lower_union_typedef(&mut self, node: Node<'_>) -> UnionDefinitionBody {
-        assert_eq!(node.kind(), NodeKind::UNION_TYPE_DEFINITION);
-        let types = node
-            .child_of_kind(NodeKind::UNION_MEMBER_TYPES)
+        assert!(
+            [NodeKind::UNION_TYPE_DEFINITION, NodeKind::UNION_TYPE_EXTENSION].contains(&node.kind())
+        );
+        let types = self
+            .body_of(node, NodeKind::UNION_MEMBER_TYPES)
             .map(|node| self.lower_union_member_types(node))
             .unwrap_or_default();
         UnionDefinitionBody { types }
@@ -93,7 +144,7 @@ impl<'db> BodyCtxt<'db> {
     }
 
     fn lower_fields_of(&mut self, node: Node<'_>) -> Fields {
-        node.child_of_kind(NodeKind::FIELDS_DEFINITION)
+        self.body_of(node, NodeKind::FIELDS_DEFINITION)
             .map(|fields| self.lower_fields(fields))
             .unwrap_or_default()
     }
@@ -113,6 +164,7 @@ impl<'db> BodyCtxt<'db> {
         let ty = self.lower_type(node.child_of_kind(NodeKind::TYPE)?)?;
         let default_value = self.lower_default_value_of(node);
         let directives = self.lower_directives_of(node);
+        let description = self.lower_description_of(node);
         Some(Field {
             range: node.range(),
             name,
@@ -120,6 +172,7 @@ impl<'db> BodyCtxt<'db> {
             directives,
             default_value,
             args: Default::default(),
+            description,
         })
     }
 
@@ -129,50 +182,13 @@ impl<'db> BodyCtxt<'db> {
         let ty = self.lower_type(node.child_of_kind(NodeKind::TYPE)?)?;
         let default_value = self.lower_default_value_of(node);
         let directives = self.lower_directives_of(node);
-        Some(Arg { range: node.range(), name, ty, default_value, directives })
-    }
-
-    fn lower_default_value_of(&mut self, node: Node<'_>) -> Option<Value> {
-        node.child_of_kind(NodeKind::DEFAULT_VALUE)
-            .and_then(NodeExt::sole_named_child)
-            .and_then(|value| self.lower_value(value))
-    }
-
-    fn lower_value(&mut self, node: Node<'_>) -> Option<Value> {
-        assert_eq!(node.kind(), NodeKind::VALUE);
-        let value = node.sole_named_child()?;
-        let t = self.text_of(value);
-        let value = match value.kind() {
-            NodeKind::STRING_VALUE => Value::String(Arc::from(t.trim_matches('"'))),
-            NodeKind::INT_VALUE => Value::Int(t.parse().unwrap()),
-            NodeKind::FLOAT_VALUE => Value::Float(t.parse().unwrap()),
-            NodeKind::BOOLEAN_VALUE => match t {
-                "true" => Value::Boolean(true),
-                "false" => Value::Boolean(false),
-                _ => unreachable!(),
-            },
-            NodeKind::NULL_VALUE => Value::Null,
-            NodeKind::ENUM_VALUE => Value::Enum(Arc::from(t)),
-            NodeKind::LIST_VALUE => Value::List(
-                value
-                    .children_of_kind(&mut value.walk(), NodeKind::VALUE)
-                    .filter_map(|value| self.lower_value(value))
-                    .collect(),
-            ),
-            NodeKind::OBJECT_VALUE => Value::Object(Arc::new(
-                value
-                    .children_of_kind(&mut value.walk(), NodeKind::OBJECT_FIELD)
-                    .filter_map(|field| self.lower_object_field(field))
-                    .collect(),
-            )),
-            _ => unreachable!(),
-        };
-        Some(value)
+        let description = self.lower_description_of(node);
+        Some(Arg { range: node.range(), name, ty, default_value, directives, description })
     }
 
-    fn lower_object_field(&mut self, node: Node<'_>) -> Option<(Name, Value)> {
-        assert_eq!(node.kind(), NodeKind::OBJECT_FIELD);
-        Some((self.name_of(node)?, self.lower_value(node.child_of_kind(NodeKind::VALUE)?)?))
+    fn lower_default_value_of(&mut self, node: Node<'_>) -> Option<DefaultValue> {
+        let value = node.child_of_kind(NodeKind::DEFAULT_VALUE)?.sole_named_child()?;
+        Some(DefaultValue { range: value.range(), value: self.lower_value(value)? })
     }
 
     fn lower_fields(&mut self, node: Node<'_>) -> Fields {
@@ -189,11 +205,20 @@ impl<'db> BodyCtxt<'db> {
         let name = self.name_of(node)?;
         let directives = self.lower_directives_of(node);
         let args = self.lower_args_of(node);
-        Some(Field { range: node.range(), name, ty, directives, args, default_value: None })
+        let description = self.lower_description_of(node);
+        Some(Field {
+            range: node.range(),
+            name,
+            ty,
+            directives,
+            args,
+            default_value: None,
+            description,
+        })
     }
 
     fn lower_args_of(&mut self, node: Node<'_>) -> Args {
-        node.child_of_kind(NodeKind::ARGUMENTS_DEFINITION)
+        self.body_of(node, NodeKind::ARGUMENTS_DEFINITION)
             .map(|args| self.lower_args(args))
             .unwrap_or_default()
     }
@@ -220,10 +245,11 @@ impl<'db> BodyCtxt<'db> {
                 match inner.kind() {
                     NodeKind::NAMED_TYPE => TyKind::NonNull(self.lower_named_type(inner)),
                     NodeKind::LIST_TYPE => TyKind::NonNull(self.lower_list_type(inner)?),
-                    _ => unreachable!(),
+                    // the sole named child can be an error node in a broken tree
+                    _ => return None,
                 }
             }
-            _ => unreachable!(),
+            _ => return None,
         };
         Some(Arc::new(Type { range: ty.range(), kind }))
     }
@@ -238,11 +264,11 @@ impl<'db> BodyCtxt<'db> {
         assert_eq!(node.kind(), NodeKind::NAMED_TYPE);
         let name = Name::new(self, node);
         let range = name.range;
-        let res = self.db.resolve_item(InProject::new(self.file, name.clone()));
+        let res = self.db.resolve_item(InProject::new(self.file, name));
         let kind = match res {
             Res::Err => {
                 self.diagnostics
-                    .push(Diagnostic::new(range, DiagnosticKind::UnresolvedType(name.clone())));
+                    .push(Diagnostic::new(range, DiagnosticKind::UnresolvedType(name)));
                 TyKind::Err(name)
             }
             _ => TyKind::Named(name, res),
@@ -268,13 +294,48 @@ impl ItemCtxt {
             .relevant_children(&mut node.walk())
             .filter_map(|node| self.lower_item(node))
             .collect();
+        let schemas = node
+            .relevant_children(&mut node.walk())
+            .filter_map(|node| node.sole_named_child())
+            .filter(|def| {
+                matches!(def.kind(), NodeKind::SCHEMA_DEFINITION | NodeKind::SCHEMA_EXTENSION)
+            })
+            .map(|def| self.lower_schema(def))
+            .collect();
+
+        Arc::new(Items { items, typedefs: self.typedefs, directives: self.directives, schemas })
+    }
 
-        Arc::new(Items { items, typedefs: self.typedefs, directives: self.directives })
+    fn lower_schema(&mut self, node: Node<'_>) -> SchemaDefinition {
+        let cursor = &mut node.walk();
+        let operations = node
+            .children_of_kind(cursor, NodeKind::ROOT_OPERATION_TYPE_DEFINITION)
+            .filter_map(|operation| {
+                let kind = match self.text_of(operation.child_of_kind(NodeKind::OPERATION_TYPE)?) {
+                    "query" => OperationKind::Query,
+                    "mutation" => OperationKind::Mutation,
+                    "subscription" => OperationKind::Subscription,
+                    // a missing operation type, which is reported as a syntax error
+                    _ => return None,
+                };
+                let ty = Name::new(self, operation.child_of_kind(NodeKind::NAMED_TYPE)?);
+                Some(RootOperation { kind, ty })
+            })
+            .collect();
+        SchemaDefinition {
+            range: node.range(),
+            directives: self.lower_directives_of(node),
+            operations,
+            is_ext: node.kind() == NodeKind::SCHEMA_EXTENSION,
+        }
     }
 
+    // an incomplete definition is lowered with placeholders for its missing parts rather than
+    // dropped, so that it is still there to complete, hover etc. while it is being typed
     fn lower_item(&mut self, node: Node<'_>) -> Option<Item> {
         assert_eq!(node.kind(), NodeKind::ITEM);
         let def = node.sole_named_child()?;
+        let mut is_recovered = false;
         let (name, kind) = match def.kind() {
             NodeKind::TYPE_DEFINITION => {
                 let typedef = def.sole_named_child()?;
@@ -285,14 +346,13 @@ impl ItemCtxt {
                     NodeKind::ENUM_TYPE_DEFINITION => TypeDefinitionKind::Enum,
                     NodeKind::UNION_TYPE_DEFINITION => TypeDefinitionKind::Union,
                     NodeKind::INPUT_OBJECT_TYPE_DEFINITION => TypeDefinitionKind::Input,
-                    _ => {
-                        unreachable!("invalid node kind for type definition: {:?}", typedef.kind())
-                    }
+                    // the sole named child can be an error node in a broken tree
+                    _ => return None,
                 };
-                let name_node = typedef.name_node()?;
-                let name = Name::new(self, name_node);
+                let name = self.name_or_missing(typedef, &mut is_recovered);
                 let directives = self.lower_directives_of(typedef);
                 let implementations = self.try_lower_implementations_of(typedef);
+                let description = self.lower_description_of(typedef);
                 (
                     name,
                     ItemKind::TypeDefinition(self.typedefs.alloc(TypeDefinition {
@@ -300,6 +360,7 @@ impl ItemCtxt {
                         kind,
                         directives,
                         implementations,
+                        description,
                     })),
                 )
             }
@@ -307,11 +368,14 @@ impl ItemCtxt {
                 let type_ext = def.sole_named_child()?;
                 let kind = match type_ext.kind() {
                     NodeKind::OBJECT_TYPE_EXTENSION => TypeDefinitionKind::Object,
-                    // TODO
+                    NodeKind::INTERFACE_TYPE_EXTENSION => TypeDefinitionKind::Interface,
+                    NodeKind::SCALAR_TYPE_EXTENSION => TypeDefinitionKind::Scalar,
+                    NodeKind::ENUM_TYPE_EXTENSION => TypeDefinitionKind::Enum,
+                    NodeKind::UNION_TYPE_EXTENSION => TypeDefinitionKind::Union,
+                    NodeKind::INPUT_OBJECT_TYPE_EXTENSION => TypeDefinitionKind::Input,
                     _ => return None,
                 };
-                let name_node = type_ext.name_node()?;
-                let name = Name::new(self, name_node);
+                let name = self.name_or_missing(type_ext, &mut is_recovered);
                 let directives = self.lower_directives_of(type_ext);
                 let implementations = self.try_lower_implementations_of(type_ext);
                 (
@@ -321,44 +385,66 @@ impl ItemCtxt {
                         kind,
                         directives,
                         implementations,
+                        // extensions can't have descriptions
+                        description: None,
                     })),
                 )
             }
             NodeKind::DIRECTIVE_DEFINITION => {
-                let name = Name::new(self, def.name_node()?);
-                let locations_node = def.child_of_kind(NodeKind::DIRECTIVE_LOCATIONS)?;
-                let locations = locations_node
-                    .children_of_kind(&mut locations_node.walk(), NodeKind::DIRECTIVE_LOCATION)
-                    .filter_map(|location| {
-                        Some(match location.child_by_field_name("location")?.kind() {
-                            "ARGUMENT_DEFINITION" => DirectiveLocations::ARGUMENT_DEFINITION,
-                            "ENUM" => DirectiveLocations::ENUM,
-                            "ENUM_VALUE" => DirectiveLocations::ENUM_VALUE,
-                            "FIELD_DEFINITION" => DirectiveLocations::FIELD_DEFINITION,
-                            "INPUT_FIELD_DEFINITION" => DirectiveLocations::INPUT_FIELD_DEFINITION,
-                            "INPUT_OBJECT" => DirectiveLocations::INPUT_OBJECT,
-                            "INTERFACE" => DirectiveLocations::INTERFACE,
-                            "OBJECT" => DirectiveLocations::OBJECT,
-                            "SCALAR" => DirectiveLocations::SCALAR,
-                            "SCHEMA" => DirectiveLocations::SCHEMA,
-                            "UNION" => DirectiveLocations::UNION,
-                            location => {
-                                unreachable!("found invalid directive location: `{location}`",)
+                let name = self.name_or_missing(def, &mut is_recovered);
+                let locations = match def.child_of_kind(NodeKind::DIRECTIVE_LOCATIONS) {
+                    Some(locations) => locations
+                        .children_of_kind(&mut locations.walk(), NodeKind::DIRECTIVE_LOCATION)
+                        .filter_map(|location| {
+                            let location = location.child_by_field_name("location")?;
+                            if location.is_missing() {
+                                is_recovered = true;
+                                return None;
                             }
+                            // an error node in a broken tree
+                            let location = DirectiveLocations::from_name(location.kind());
+                            is_recovered |= location.is_none();
+                            location
                         })
-                    })
-                    .fold(DirectiveLocations::default(), |acc, location| acc | location);
+                        .fold(DirectiveLocations::default(), |acc, location| acc | location),
+                    None => {
+                        is_recovered = true;
+                        DirectiveLocations::default()
+                    }
+                };
+                let description = self.lower_description_of(def);
                 (
                     name,
                     ItemKind::DirectiveDefinition(
-                        self.directives.alloc(DirectiveDefinition { locations }),
+                        self.directives.alloc(DirectiveDefinition { locations, description }),
                     ),
                 )
             }
+            // schema definitions are lowered separately, see `lower_schema`
+            NodeKind::SCHEMA_DEFINITION | NodeKind::SCHEMA_EXTENSION => return None,
             // TODO
             _ => return None,
         };
-        Some(Item { range: def.range(), name, kind })
+        Some(Item { range: def.range(), name, kind, is_recovered })
+    }
+
+    /// The name of the definition `node`, or a placeholder at where it is expected if it is missing
+    fn name_or_missing(&mut self, node: Node<'_>, is_recovered: &mut bool) -> Name {
+        match node.name_node() {
+            Some(name) if !name.has_error() && !self.text_of(name).is_empty() =>
+                Name::new(self, name),
+            Some(name) => {
+                *is_recovered = true;
+                Name::missing(name.range())
+            }
+            None => {
+                *is_recovered = true;
+                let (start_byte, start_point) = (node.start_byte(), node.start_position());
+                let range =
+                    Range { start_byte, end_byte: start_byte, start_point, end_point: start_point };
+                Name::missing(range)
+            }
+        }
     }
 
     fn try_lower_implementations_of(&mut self, node: Node<'_>) -> Option<Implementations> {
@@ -378,6 +464,11 @@ pub(crate) trait LowerCtxt: HasText {
         node.name_node().map(|node| Name::new(self, node))
     }
 
+    fn lower_description_of(&mut self, node: Node<'_>) -> Option<Arc<str>> {
+        let text = self.text_of(node.child_of_kind(NodeKind::DESCRIPTION)?);
+        Some(Arc::from(string_value(text).trim()))
+    }
+
     fn lower_directives_of(&mut self, node: Node<'_>) -> Directives {
         node.child_of_kind(NodeKind::DIRECTIVES)
             .map(|node| self.lower_directives(node))
@@ -393,15 +484,170 @@ pub(crate) trait LowerCtxt: HasText {
 
     fn lower_directive(&mut self, node: Node<'_>) -> Option<Directive> {
         assert_eq!(node.kind(), NodeKind::DIRECTIVE);
-        // TODO arguments
         let name = Name::new(self, node.name_node()?);
-        Some(Directive { range: node.range(), name })
+        let arguments = match node.child_of_kind(NodeKind::ARGUMENTS) {
+            Some(arguments) => arguments
+                .children_of_kind(&mut arguments.walk(), NodeKind::ARGUMENT)
+                .filter_map(|argument| self.lower_argument(argument))
+                .collect(),
+            None => vec![],
+        };
+        Some(Directive { range: node.range(), name, arguments })
+    }
+
+    fn lower_argument(&mut self, node: Node<'_>) -> Option<Argument> {
+        assert_eq!(node.kind(), NodeKind::ARGUMENT);
+        let name = self.name_of(node)?;
+        let value = node.child_of_kind(NodeKind::VALUE)?;
+        Some(Argument { name, value_range: value.range(), value: self.lower_value(value)? })
+    }
+
+    fn lower_value(&mut self, node: Node<'_>) -> Option<Value> {
+        assert_eq!(node.kind(), NodeKind::VALUE);
+        let value = node.sole_named_child()?;
+        let t = self.text_of(value);
+        let value = match value.kind() {
+            NodeKind::STRING_VALUE => {
+                let range = value.range();
+                Value::String(StringValue { value: Arc::from(string_value(t)), range })
+            }
+            // the type checker decides whether an integer that is too large is out of range
+            NodeKind::INT_VALUE => match Value::parse_int(t) {
+                Some(i) => Value::Int(i),
+                None => Value::BigInt(Arc::from(t)),
+            },
+            NodeKind::FLOAT_VALUE => Value::from(t.parse::<f64>().ok()?),
+            NodeKind::BOOLEAN_VALUE => match t {
+                "true" => Value::Boolean(true),
+                "false" => Value::Boolean(false),
+                _ => return None,
+            },
+            NodeKind::NULL_VALUE => Value::Null,
+            NodeKind::ENUM_VALUE => Value::Enum(Arc::from(t)),
+            NodeKind::LIST_VALUE => Value::List(
+                value
+                    .children_of_kind(&mut value.walk(), NodeKind::VALUE)
+                    .filter_map(|value| self.lower_value(value))
+                    .collect(),
+            ),
+            NodeKind::OBJECT_VALUE => Value::Object(Arc::new(
+                value
+                    .children_of_kind(&mut value.walk(), NodeKind::OBJECT_FIELD)
+                    .filter_map(|field| self.lower_object_field(field))
+                    .collect(),
+            )),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn lower_object_field(&mut self, node: Node<'_>) -> Option<(Name, Value)> {
+        assert_eq!(node.kind(), NodeKind::OBJECT_FIELD);
+        Some((self.name_of(node)?, self.lower_value(node.child_of_kind(NodeKind::VALUE)?)?))
     }
 }
 
 impl<C: HasText> LowerCtxt for C {
 }
 
+/// The value of a string literal (including its quotes) as defined by the spec, i.e. the escape
+/// sequences of a regular string are decoded and the indentation of a block string is removed.
+/// The raw literal remains at the range of the [`StringValue`].
+fn string_value(literal: &str) -> String {
+    const BLOCK_QUOTE: &str = "\"\"\"";
+    let block = literal.strip_prefix(BLOCK_QUOTE).and_then(|s| s.strip_suffix(BLOCK_QUOTE));
+    match block {
+        Some(block) => block_string_value(&block.replace("\\\"\"\"", BLOCK_QUOTE)),
+        None => {
+            let inner = literal.strip_prefix('"').unwrap_or(literal);
+            unescape(inner.strip_suffix('"').unwrap_or(inner))
+        }
+    }
+}
+
+/// Remove the common indentation of the lines of a block string (excluding the first line as it
+/// follows the opening quotes) and its leading and trailing blank lines, so indentation is
+/// significant in the markdown of descriptions
+fn block_string_value(block: &str) -> String {
+    let is_whitespace = |c: char| c == ' ' || c == '\t';
+    let block = block.replace("\r\n", "\n");
+    let lines = block.split(|c: char| c == '\n' || c == '\r').collect::<Vec<_>>();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| line.find(|c: char| !is_whitespace(c)))
+        .min()
+        .unwrap_or(0);
+    let lines = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line,
+            // a line shorter than the indentation is blank
+            _ => line.get(indent..).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+    let is_blank = |line: &&str| line.chars().all(is_whitespace);
+    let start = lines.iter().position(|line| !is_blank(line)).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !is_blank(line)).map_or(start, |end| end + 1);
+    lines[start..end].join("\n")
+}
+
+/// Decode the escape sequences of a regular string, an invalid escape sequence is kept as is
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(idx) = rest.find('\\') {
+        value.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        let (c, len) = unescape_one(rest).unwrap_or(('\\', 1));
+        value.push(c);
+        rest = &rest[len..];
+    }
+    value.push_str(rest);
+    value
+}
+
+/// The character of the escape sequence at the start of `s` and the length of the sequence
+fn unescape_one(s: &str) -> Option<(char, usize)> {
+    let c = match s.as_bytes().get(1).copied()? {
+        b'"' => '"',
+        b'\\' => '\\',
+        b'/' => '/',
+        b'b' => '\u{8}',
+        b'f' => '\u{c}',
+        b'n' => '\n',
+        b'r' => '\r',
+        b't' => '\t',
+        b'u' => return unescape_unicode(s),
+        _ => return None,
+    };
+    Some((c, 2))
+}
+
+// either a variable width `\u{1F600}` or a fixed width `\uXXXX`, where a pair of surrogates
+// (e.g. `\uD83D\uDE00`) encodes a single character
+fn unescape_unicode(s: &str) -> Option<(char, usize)> {
+    let is_hex = |hex: &&str| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    if let Some(braced) = s.strip_prefix("\\u{") {
+        let end = braced.find('}')?;
+        let code = braced.get(..end).filter(is_hex)?;
+        let c = char::from_u32(u32::from_str_radix(code, 16).ok()?)?;
+        return Some((c, end + 4));
+    }
+    let unit = |s: &str| {
+        let hex = s.strip_prefix("\\u")?.get(..4).filter(is_hex)?;
+        u16::from_str_radix(hex, 16).ok()
+    };
+    let lead = unit(s)?;
+    if let Some(c) = char::from_u32(lead.into()) {
+        return Some((c, 6));
+    }
+    let trail = unit(s.get(6..)?)?;
+    let c = char::decode_utf16([lead, trail]).next()?.ok()?;
+    Some((c, 12))
+}
+
 impl HasText for ItemCtxt {
     fn text(&self) -> &str {
         &self.text
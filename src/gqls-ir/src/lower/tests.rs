@@ -2,8 +2,9 @@ use expect_test::{expect, Expect};
 use gqls_fixture::{fixture_file, FixtureFile};
 use testing::{file_id, TestDatabaseExt};
 
+use super::string_value;
 use crate::tests::{idx, TestDB};
-use crate::{DefDatabase, ItemRes};
+use crate::{DefDatabase, ItemRes, Name, TypeDefinitionKind, Value};
 
 fn test(fixture: &FixtureFile, expect: Expect) {
     let db = TestDB::from_fixture_file(&fixture);
@@ -220,3 +221,338 @@ fn test_lower_value() {
         "#]],
     );
 }
+
+#[test]
+fn test_lower_scalar_body() {
+    let fixture = fixture_file! {
+        r#"
+        scalar DateTime @specifiedBy(url: "https://datatracker.ietf.org/doc/html/rfc3339")
+        "#
+    };
+    test(
+        &fixture,
+        expect![[r#"
+            Some(
+                ItemBody {
+                    diagnostics: [],
+                    kind: Scalar(
+                        ScalarDefinitionBody {
+                            specified_by: Some(
+                                "https://datatracker.ietf.org/doc/html/rfc3339",
+                            ),
+                        },
+                    ),
+                },
+            )
+        "#]],
+    )
+}
+
+#[test]
+fn test_lower_directive_definition_body() {
+    let fixture = fixture_file! {
+        r#"
+        directive @d(
+            "the first arg"
+            a: Int = 1
+            """
+            the second arg
+            """
+            b: String! @qux
+        ) on FIELD_DEFINITION
+        directive @qux on ARGUMENT_DEFINITION
+        "#
+    };
+
+    test(
+        &fixture,
+        expect![[r#"
+            Some(
+                ItemBody {
+                    diagnostics: [],
+                    kind: DirectiveDefinition(
+                        DirectiveDefinitionBody {
+                            args: [
+                                a: Int = 1,
+                                b: String! @qux,
+                            ],
+                        },
+                    ),
+                },
+            )
+        "#]],
+    );
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let body = db.item_body(ItemRes::new(file_id!(), idx!(0))).unwrap();
+    let descriptions =
+        body.as_directive().args.iter().map(|arg| arg.description.as_deref()).collect::<Vec<_>>();
+    assert_eq!(descriptions, [Some("the first arg"), Some("the second arg")]);
+}
+
+#[test]
+fn test_lower_directive_arguments() {
+    let fixture = fixture_file! {
+        r#"
+        type Foo @key(fields: "id", resolvable: false) @qux {
+            id: ID!
+        }
+        "#
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let directives = items.directives(idx!(0)).unwrap();
+    expect![[r#"
+        [
+            [
+                fields: "id",
+                resolvable: false,
+            ],
+            [],
+        ]
+    "#]]
+    .assert_debug_eq(&directives.iter().map(|directive| &directive.arguments).collect::<Vec<_>>());
+    assert_eq!(directives[0].argument("fields").map(|arg| &arg.value), Some(&Value::from("id")));
+}
+
+#[test]
+fn test_lower_block_string_value() {
+    let fixture = fixture_file! {
+        r#"
+        type Foo @doc(text: """
+
+            first
+              indented
+
+        """) {
+            id: ID!
+        }
+        "#
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let directives = items.directives(idx!(0)).unwrap();
+    let text = directives[0].argument("text").map(|arg| &arg.value);
+    assert_eq!(text, Some(&Value::from("first\n  indented")));
+}
+
+#[test]
+fn test_lower_escaped_string_value() {
+    let fixture = fixture_file! {
+        r#"
+        type Foo @doc(text: "a \"b\" \\ c", block: """a \""" b""") {
+            id: ID!
+        }
+        "#
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let directives = items.directives(idx!(0)).unwrap();
+    let text = directives[0].argument("text").unwrap();
+    assert_eq!(text.value, Value::from("a \"b\" \\ c"));
+    // the raw literal is kept at the range of the value
+    match &text.value {
+        Value::String(s) => assert_eq!(s.range, text.value_range),
+        value => panic!("expected a string, found {value}"),
+    }
+    let block = directives[0].argument("block").map(|arg| &arg.value);
+    assert_eq!(block, Some(&Value::from("a \"\"\" b")));
+}
+
+#[test]
+fn test_string_value() {
+    assert_eq!(string_value(r#""a \"b\" \\ \/ \b\f\n\r\t""#), "a \"b\" \\ / \u{8}\u{c}\n\r\t");
+    assert_eq!(string_value(r#""\u00e9 \u{1F600} \uD83D\uDE00""#), "\u{e9} \u{1F600} \u{1F600}");
+    // invalid escape sequences are kept as is
+    assert_eq!(string_value(r#""\q \u12 \uD83D \u{}""#), r"\q \u12 \uD83D \u{}");
+
+    assert_eq!(string_value("\"\"\"\n  a\n    b\n  \\\"\"\"\n\"\"\""), "a\n  b\n\"\"\"");
+    // the first line follows the quotes so it doesn't count towards the common indentation
+    assert_eq!(string_value("\"\"\" first\n    second\"\"\""), " first\nsecond");
+}
+
+#[test]
+fn test_lower_schema_definitions() {
+    let fixture = fixture_file! {
+        r#"
+        schema {
+            query: Query
+            mutation: Mutation
+        }
+
+        extend schema @link(url: "https://specs.apollo.dev/federation/v2.0", import: ["@key"]) {
+            subscription: Subscription
+        }
+        "#
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    assert!(items.is_empty());
+    expect![[r#"
+        [
+            SchemaDefinition {
+                range: 1:8..4:9,
+                directives: [],
+                operations: [
+                    query: Query,
+                    mutation: Mutation,
+                ],
+                is_ext: false,
+            },
+            SchemaDefinition {
+                range: 6:8..8:9,
+                directives: [
+                    @link,
+                ],
+                operations: [
+                    subscription: Subscription,
+                ],
+                is_ext: true,
+            },
+        ]
+    "#]]
+    .assert_debug_eq(&items.schemas);
+    expect![[r#"
+        [
+            url: "https://specs.apollo.dev/federation/v2.0",
+            import: ["@key"],
+        ]
+    "#]]
+    .assert_debug_eq(&items.schemas[1].directives[0].arguments);
+}
+
+#[test]
+fn test_lower_type_extensions() {
+    let fixture = fixture_file! {
+        "
+        extend interface I { i: Int }
+        extend input In { i: Int }
+        extend enum E { A }
+        extend union U = A | B
+        extend scalar S @qux
+        "
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let kinds = items
+        .iter()
+        .map(|(_, item)| {
+            let typedef = &items[item.kind.into_type_definition()];
+            (item.name.to_string(), typedef.kind.clone(), typedef.is_ext)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [
+        ("I".to_owned(), TypeDefinitionKind::Interface, true),
+        ("In".to_owned(), TypeDefinitionKind::Input, true),
+        ("E".to_owned(), TypeDefinitionKind::Enum, true),
+        ("U".to_owned(), TypeDefinitionKind::Union, true),
+        ("S".to_owned(), TypeDefinitionKind::Scalar, true),
+    ]);
+
+    let body = |idx| db.item_body(ItemRes::new(file_id!(), idx)).unwrap();
+    let field_names = |idx| {
+        let body = body(idx);
+        body.fields_slice().unwrap().iter().map(|field| field.name.to_string()).collect::<Vec<_>>()
+    };
+    assert_eq!(field_names(idx!(0)), ["i"]);
+    assert_eq!(field_names(idx!(1)), ["i"]);
+    assert_eq!(body(idx!(2)).as_enum().variants.len(), 1);
+    assert_eq!(body(idx!(3)).as_union().types.len(), 2);
+}
+
+#[test]
+fn test_lower_descriptions() {
+    let fixture = fixture_file! {
+        r#"
+        """
+        A type with a description
+
+            an indented code block
+        """
+        type Foo {
+            "a field"
+            foo: Int
+            bar: Int
+        }
+
+        enum E {
+            """
+            a variant
+            """
+            A
+            B
+        }
+
+        "a directive"
+        directive @d on FIELD_DEFINITION
+
+        extend type Foo {
+            "an extension field"
+            baz: Int
+        }
+        "#
+    };
+
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let descriptions = items.iter().map(|(idx, _)| items.description(idx)).collect::<Vec<_>>();
+    assert_eq!(descriptions, [
+        Some("A type with a description\n\n    an indented code block"),
+        None,
+        Some("a directive"),
+        None,
+    ]);
+
+    let body = |idx| db.item_body(ItemRes::new(file_id!(), idx)).unwrap();
+    let field_descriptions = |idx| {
+        let body = body(idx);
+        body.fields_slice()
+            .unwrap()
+            .iter()
+            .map(|field| field.description.as_deref().map(ToOwned::to_owned))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(field_descriptions(idx!(0)), [Some("a field".to_owned()), None]);
+    assert_eq!(field_descriptions(idx!(3)), [Some("an extension field".to_owned())]);
+    let variants = body(idx!(1)).as_enum().variants.clone();
+    let variant_descriptions =
+        variants.iter().map(|variant| variant.description.as_deref()).collect::<Vec<_>>();
+    assert_eq!(variant_descriptions, [Some("a variant"), None]);
+}
+
+#[test]
+fn test_lower_incomplete_items() {
+    let fixture = fixture_file! {
+        "type Foo { a: Int }
+type {
+  b: Int
+}
+directive @x on
+extend type "
+    };
+    let db = TestDB::from_fixture_file(&fixture);
+    let items = db.items(file_id!());
+    let items = items
+        .iter()
+        .map(|(_, item)| (item.name.to_string(), item.is_recovered))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items,
+        [
+            ("Foo".to_owned(), false),
+            (Name::MISSING.to_owned(), true),
+            ("@x".to_owned(), true),
+            (Name::MISSING.to_owned(), true),
+        ]
+    );
+
+    // the placeholder still has its body but can't be referred to
+    let body = db.item_body(ItemRes::new(file_id!(), idx!(1))).unwrap();
+    assert_eq!(body.fields().unwrap().iter().next().unwrap().1.name.as_str(), "b");
+    assert!(!db.item_map(file_id!()).contains_key(&Name::unranged(Name::MISSING).symbol()));
+}
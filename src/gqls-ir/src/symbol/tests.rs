@@ -0,0 +1,21 @@
+use super::Symbol;
+use std::collections::HashSet;
+
+#[test]
+fn test_symbols_are_interned() {
+    let foo = Symbol::intern("foo");
+    assert_eq!(foo, Symbol::intern(&String::from("foo")));
+    assert_ne!(foo, Symbol::intern("bar"));
+    assert!(std::ptr::eq(foo.as_str(), Symbol::intern("foo").as_str()));
+    assert_eq!(&*foo, "foo");
+
+    let symbols = ["foo", "bar", "foo"].into_iter().map(Symbol::intern).collect::<HashSet<_>>();
+    assert_eq!(symbols.len(), 2);
+}
+
+#[test]
+fn test_symbols_are_ordered_by_contents() {
+    let mut symbols = ["c", "a", "b"].map(Symbol::intern);
+    symbols.sort();
+    assert_eq!(symbols.map(|symbol| symbol.as_str()), ["a", "b", "c"]);
+}
@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-use crate::{DefDatabase, DefDatabaseStorage, ItemRes, Name};
+use crate::{
+    DefDatabase, DefDatabaseStorage, DependencyKind, ItemRes, Name, Symbol, TypeDefinitionKind
+};
 use expect_test::expect;
 use gqls_base_db::{InProject, SourceDatabaseStorage};
 use gqls_fixture::fixture;
@@ -10,11 +12,18 @@ use testing::TestDatabaseExt;
 use vfs::Vfs;
 
 #[salsa::database(SourceDatabaseStorage, DefDatabaseStorage)]
-#[derive(Default)]
 pub(crate) struct TestDB {
     storage: salsa::Storage<TestDB>,
 }
 
+impl Default for TestDB {
+    fn default() -> Self {
+        let mut db = Self { storage: Default::default() };
+        db.set_item_cache(None);
+        db
+    }
+}
+
 impl salsa::Database for TestDB {
 }
 
@@ -87,13 +96,13 @@ fn test_definitions() {
     assert_eq!(
         *item_map,
         hashmap! {
-            Name::unranged("Foo") => smallvec![idx!(0), idx!(1)],
-            Name::unranged("Bar") => smallvec![idx!(2), idx!(3)],
-            Name::unranged("@qux") => smallvec![idx!(4)],
-            Name::unranged("S") => smallvec![idx!(5)],
-            Name::unranged("U") => smallvec![idx!(6)],
-            Name::unranged("I") => smallvec![idx!(7)],
-            Name::unranged("Iface") => smallvec![idx!(8)],
+            Symbol::intern("Foo") => smallvec![idx!(0), idx!(1)],
+            Symbol::intern("Bar") => smallvec![idx!(2), idx!(3)],
+            Symbol::intern("@qux") => smallvec![idx!(4)],
+            Symbol::intern("S") => smallvec![idx!(5)],
+            Symbol::intern("U") => smallvec![idx!(6)],
+            Symbol::intern("I") => smallvec![idx!(7)],
+            Symbol::intern("Iface") => smallvec![idx!(8)],
         }
     );
 
@@ -197,18 +206,21 @@ fn test_definitions() {
                         implementations: None,
                         kind: Object,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [],
                         implementations: None,
                         kind: Object,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [],
                         implementations: None,
                         kind: Object,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [],
@@ -219,6 +231,7 @@ fn test_definitions() {
                         ),
                         kind: Object,
                         is_ext: true,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [
@@ -227,6 +240,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Scalar,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [
@@ -235,6 +249,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Union,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [
@@ -243,6 +258,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Input,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [
@@ -251,6 +267,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Interface,
                         is_ext: false,
+                        description: None,
                     },
                 ],
             },
@@ -259,9 +276,11 @@ fn test_definitions() {
                 data: [
                     DirectiveDefinition {
                         locations: FIELD_DEFINITION | INPUT_OBJECT | OBJECT,
+                        description: None,
                     },
                 ],
             },
+            schemas: [],
         }
     "#]]
     .assert_debug_eq(&items);
@@ -303,6 +322,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Object,
                         is_ext: false,
+                        description: None,
                     },
                     TypeDefinition {
                         directives: [
@@ -311,6 +331,7 @@ fn test_definitions() {
                         implementations: None,
                         kind: Object,
                         is_ext: false,
+                        description: None,
                     },
                 ],
             },
@@ -319,10 +340,166 @@ fn test_definitions() {
                 data: [
                     DirectiveDefinition {
                         locations: FIELD_DEFINITION,
+                        description: None,
                     },
                 ],
             },
+            schemas: [],
         }
     "#]]
     .assert_debug_eq(&items);
 }
+
+#[test]
+fn test_possible_types() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo");
+    let fixture = fixture! {
+        foo => "
+            interface I { i: Int }
+            interface J implements I { i: Int }
+            type A implements I { i: Int }
+            type B { b: Int }
+            extend type B implements I
+            type C { c: Int }
+            union U = A | C
+            extend union U = B | J
+            scalar S
+        "
+    };
+
+    let db = TestDB::from_fixture(&fixture);
+    let possible_types = |name: &str| {
+        db.possible_types(InProject::new(foo, Name::unranged(name)))
+            .into_iter()
+            .map(|res| db.items(foo)[res.value].name.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(possible_types("A"), ["A"]);
+    assert_eq!(possible_types("I"), ["A", "B"]);
+    assert_eq!(possible_types("J"), Vec::<String>::new());
+    assert_eq!(possible_types("U"), ["A", "C", "B"]);
+    assert!(possible_types("S").is_empty());
+    assert!(possible_types("Int").is_empty());
+}
+
+#[test]
+fn test_effective_typedef() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo");
+    let bar = vfs.intern("bar");
+    let fixture = fixture! {
+        foo => "
+            extend type Foo implements J @b {
+                bar: Int
+                foo: String
+            }
+            type Foo implements I @a { foo: Int }
+            extend union U = B
+            union U = A
+            extend enum E { Y }
+            directive @Foo on OBJECT
+        "
+        bar => "
+            extend type Foo implements I & K { baz: Int }
+            enum E { X }
+        "
+    };
+
+    let db = TestDB::from_fixture(&fixture);
+    let typedef = |name: &str| db.effective_typedef(InProject::new(foo, Name::unranged(name)));
+    let names = |names: &[Name]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+
+    let foo_typedef = typedef("Foo").unwrap();
+    assert_eq!(foo_typedef.kind, TypeDefinitionKind::Object);
+    // the base definition comes first even though an extension precedes it in the file
+    let files = foo_typedef.parts.iter().map(|res| res.file).collect::<Vec<_>>();
+    assert_eq!(files, [foo, bar, foo]);
+    let base = foo_typedef.parts[0];
+    assert!(!db.typedef(foo, db.item(base).kind.into_type_definition()).is_ext);
+    assert_eq!(names(&foo_typedef.implementations), ["I", "K", "J"]);
+    let directives = foo_typedef.directives.iter().map(|d| d.name).collect::<Vec<_>>();
+    assert_eq!(names(&directives), ["@a", "@b"]);
+    let fields = foo_typedef.fields.iter().map(|&res| db.field(res).name).collect::<Vec<_>>();
+    assert_eq!(names(&fields), ["foo", "baz", "bar"]);
+    assert_eq!(foo_typedef.duplicate_fields.len(), 1);
+    let (duplicate, previous) = foo_typedef.duplicate_fields[0];
+    assert_eq!(db.field(duplicate).ty.name().as_str(), "String");
+    assert_eq!(db.field(previous).ty.name().as_str(), "Int");
+
+    let members = typedef("U").unwrap().members.iter().map(|ty| ty.name()).collect::<Vec<_>>();
+    assert_eq!(names(&members), ["A", "B"]);
+    let enum_typedef = typedef("E").unwrap();
+    let variants = enum_typedef.variants.iter().map(|v| v.name).collect::<Vec<_>>();
+    assert_eq!(names(&variants), ["X", "Y"]);
+
+    assert!(typedef("@Foo").is_none());
+    assert!(typedef("Int").is_none());
+    assert!(typedef("Unresolved").is_none());
+}
+
+#[test]
+fn test_dependency_graph() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo");
+    let bar = vfs.intern("bar");
+    let fixture = fixture! {
+        foo => "
+            type Query { user(id: ID!, filter: Filter): User, users: [User!]! }
+            type User { friends: [User!]! role: Role missing: Missing }
+            input Filter { role: Role }
+            union U = User
+            directive @d(filter: Filter) on OBJECT
+        "
+        bar => "
+            enum Role { ADMIN }
+            extend type User { query: Query }
+        "
+    };
+
+    let db = TestDB::from_fixture(&fixture);
+    let graph = db.dependency_graph("default");
+    let name_of = |res: ItemRes| db.item(res).name.to_string();
+    let outgoing = |file, name: &str| {
+        let (idx, _) = db.items(file).iter().find(|(_, item)| item.name.as_str() == name).unwrap();
+        graph
+            .outgoing(ItemRes::new(file, idx))
+            .map(|dependency| dependency.to.to_string())
+            .collect::<Vec<_>>()
+    };
+    let incoming = |name: &str| {
+        graph
+            .incoming(Name::unranged(name))
+            .map(|dependency| name_of(dependency.from))
+            .collect::<Vec<_>>()
+    };
+
+    // builtin scalars and unresolved types are not dependencies
+    assert_eq!(outgoing(foo, "Query"), ["Filter", "User", "User"]);
+    assert_eq!(outgoing(foo, "User"), ["User", "Role"]);
+    assert_eq!(outgoing(bar, "User"), ["Query"]);
+    assert_eq!(outgoing(foo, "@d"), ["Filter"]);
+    assert_eq!(incoming("User"), ["Query", "Query", "User", "U"]);
+    assert_eq!(incoming("Role"), ["User", "Filter"]);
+    assert!(incoming("Missing").is_empty());
+
+    let kinds = |file, name: &str| {
+        let (idx, _) = db.items(file).iter().find(|(_, item)| item.name.as_str() == name).unwrap();
+        graph
+            .outgoing(ItemRes::new(file, idx))
+            .map(|dependency| dependency.kind)
+            .collect::<Vec<_>>()
+    };
+    let name = Name::unranged;
+    assert_eq!(
+        kinds(foo, "Query"),
+        [
+            DependencyKind::FieldArg(name("user"), name("filter")),
+            DependencyKind::Field(name("user")),
+            DependencyKind::Field(name("users")),
+        ]
+    );
+    assert_eq!(kinds(foo, "U"), [DependencyKind::Member]);
+    assert_eq!(kinds(foo, "@d"), [DependencyKind::DirectiveArg(name("filter"))]);
+}
@@ -24,7 +24,7 @@ impl Type {
 
     pub fn name(&self) -> Name {
         match &self.kind {
-            TyKind::Named(name, _) | TyKind::Err(name) => name.clone(),
+            TyKind::Named(name, _) | TyKind::Err(name) => *name,
             TyKind::NonNull(ty) | TyKind::List(ty) => ty.name(),
         }
     }
@@ -62,7 +62,7 @@ impl Debug for Type {
 impl Debug for TyKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
-            TyKind::Named(name, _) | TyKind::Err(name) => write!(f, "{}", name.name),
+            TyKind::Named(name, _) | TyKind::Err(name) => write!(f, "{name}"),
             TyKind::NonNull(ty) => write!(f, "{ty:?}!"),
             TyKind::List(ty) => write!(f, "[{ty:?}]"),
         }
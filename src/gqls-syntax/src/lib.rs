@@ -19,6 +19,11 @@ pub fn traverse_preorder(tree: &Tree) -> impl Iterator<Item = Node<'_>> {
     tree_sitter_traversal::traverse_tree(tree, tree_sitter_traversal::Order::Pre)
 }
 
+/// Preorder traversal of `node` and its descendants
+pub fn traverse_node_preorder(node: Node<'_>) -> impl Iterator<Item = Node<'_>> {
+    tree_sitter_traversal::traverse(node.walk(), tree_sitter_traversal::Order::Pre)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Position {
     pub file: FileId,
@@ -148,7 +153,9 @@ impl<'tree> NodeExt<'tree> for Node<'tree> {
     }
 
     fn text(self, source: &str) -> &str {
-        self.utf8_text(source.as_bytes()).expect("text was not valid utf8")
+        // the source is always valid utf8, but a tree that is out of sync with it may not line up
+        // with its char boundaries
+        source.get(self.start_byte()..self.end_byte()).unwrap_or_default()
     }
 
     fn find_descendant(self, f: impl FnMut(&Node<'tree>) -> bool) -> Option<Node<'tree>> {
@@ -188,6 +195,9 @@ extern "C" {
     fn tree_sitter_graphql() -> Language;
 }
 
+/// The graphql language compiled from the vendored `tree-sitter-graphql` grammar.
+/// This crate is the only parsing entry point in the workspace, downstream crates should go through
+/// here rather than the grammar crate.
 pub fn language() -> Language {
     unsafe { tree_sitter_graphql() }
 }
@@ -201,6 +211,28 @@ pub fn parse(text: &str, old_tree: Option<&Tree>) -> Tree {
     parser.parse(text, old_tree).unwrap()
 }
 
+/// Parse only the definitions of `text`, their bodies (fields, values, members and arguments) are
+/// parsed as whitespace so that the tree of a large file stays small. The ranges of the nodes
+/// are those of `text`, but the tree can't be edited into the tree of another text.
+pub fn parse_shallow(text: &str) -> Tree {
+    const BODIES: [&str; 5] = [
+        NodeKind::FIELDS_DEFINITION,
+        NodeKind::INPUT_FIELDS_DEFINITION,
+        NodeKind::ENUM_VALUES_DEFINITION,
+        NodeKind::UNION_MEMBER_TYPES,
+        NodeKind::ARGUMENTS_DEFINITION,
+    ];
+    let tree = parse_fresh(text);
+    let mut blanked = text.as_bytes().to_vec();
+    for node in traverse_preorder(&tree).filter(|node| BODIES.contains(&node.kind())) {
+        // the lines are kept so that the points of the nodes after the body are unchanged
+        blanked[node.byte_range()].iter_mut().filter(|b| **b != b'\n').for_each(|b| *b = b' ');
+    }
+    // the characters of a body are blanked whole
+    let blanked = String::from_utf8(blanked).expect("blanked text was not valid utf8");
+    parse_fresh(&blanked)
+}
+
 pub fn query(query: &str) -> Query {
     Query::new(language(), query).unwrap()
 }
@@ -1,7 +1,7 @@
 use expect_test::{expect, Expect};
 use tree_sitter::Point;
 
-use crate::{make_parser, parse_fresh, NodeExt};
+use crate::{make_parser, parse_fresh, parse_shallow, NodeExt};
 
 fn test(s: &str, expect: Expect) {
     let sexp = make_parser().parse(s, None).unwrap().root_node().to_sexp();
@@ -36,6 +36,25 @@ fn test_parse_empty_type() {
     );
 }
 
+#[test]
+fn test_parse_shallow() {
+    let s = "
+type Foo implements Bar {
+    a(x: Int): Int
+}
+union U = A | B
+enum E { A }
+directive @d(a: Int) on FIELD_DEFINITION";
+    let tree = parse_shallow(s);
+    expect![[
+        r#"(document (item (type_definition (object_type_definition (name) (implements_interfaces (named_type))))) (item (type_definition (union_type_definition (name)))) (item (type_definition (enum_type_definition (name)))) (item (directive_definition (directive_name) (directive_locations (directive_location)))))"#
+    ]]
+    .assert_eq(&tree.root_node().to_sexp());
+    let directive = tree.root_node().named_child(3).unwrap();
+    assert_eq!(directive.start_position(), Point::new(6, 0));
+    assert_eq!(directive.end_byte(), s.len());
+}
+
 #[test]
 fn test_parse_directive_definition() {
     test(
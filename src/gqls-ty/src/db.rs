@@ -1,5 +1,6 @@
 use gqls_ir::{self as ir, DefDatabase, ItemKind, ItemRes, TypeDefinitionKind};
-use ir::{FieldRes, Res, Value};
+use ir::{FieldRes, Name, Res, Value};
+use std::collections::BTreeMap;
 
 use crate::*;
 
@@ -7,6 +8,9 @@ use crate::*;
 pub trait TyDatabase: DefDatabase {
     fn is_subtype(&self, ty: Ty, of: Ty) -> bool;
     fn ensure_has_type(&self, value: Value, ty: Ty) -> Result<(), TypeMismatch>;
+    /// All the mismatches of `value` and the values nested in it against `ty`
+    #[salsa::transparent]
+    fn value_mismatches(&self, value: Value, ty: Ty) -> Vec<ValueMismatch>;
     fn has_type(&self, value: Value, ty: Ty) -> bool;
     fn type_of_res(&self, res: Res) -> Ty;
     fn type_of_item(&self, res: ItemRes) -> Ty;
@@ -16,68 +20,122 @@ pub trait TyDatabase: DefDatabase {
     fn implements_interface(&self, obj: ObjectType, interface: InterfaceType) -> Option<ImplError>;
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn is_subtype(_db: &dyn TyDatabase, ty: Ty, of: Ty) -> bool {
     // TODO
     ty == of
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn ensure_has_type(db: &dyn TyDatabase, value: Value, ty: Ty) -> Result<(), TypeMismatch> {
-    match (value, &ty.kind) {
-        (_, TyKind::Err)
-        | (Value::Boolean(_), TyKind::Boolean)
-        | (Value::Float(_), TyKind::Float)
-        | (Value::Int(_), TyKind::Int)
-        | (Value::String(_), TyKind::String)
-        | (Value::String(_), TyKind::ID) => Ok(()),
-        (Value::Enum(variant), TyKind::Enum(e)) if e.variants.contains(&variant) => Ok(()),
-        (Value::Enum(variant), TyKind::Enum(e)) =>
-            Err(TypeMismatch::InvalidVariant(variant, e.clone())),
-        (Value::Null, kind) => match kind {
-            TyKind::NonNull(_) => Err(TypeMismatch::InvalidNull),
-            _ => Ok(()),
-        },
-        (value, TyKind::NonNull(ty)) => db.ensure_has_type(value, ty.clone()),
-        (Value::List(values), TyKind::List(ty)) => {
-            match values
-                .iter()
-                .map(|value| db.ensure_has_type(value.clone(), ty.clone()))
-                .find_map(|x| x.err())
-            {
-                Some(err) => Err(err),
-                None => Ok(()),
+    match db.value_mismatches(value, ty).into_iter().next() {
+        Some(mismatch) => Err(mismatch.mismatch),
+        None => Ok(()),
+    }
+}
+
+// not memoized, as the keys of objects are equal regardless of their ranges
+#[tracing::instrument(level = "debug", skip_all)]
+fn value_mismatches(db: &dyn TyDatabase, value: Value, ty: Ty) -> Vec<ValueMismatch> {
+    let mut checker = ValueChecker { db, path: vec![], mismatches: vec![] };
+    checker.check(&value, &ty, &value, &ty);
+    checker.mismatches
+}
+
+struct ValueChecker<'db> {
+    db: &'db dyn TyDatabase,
+    /// the steps to the value being checked
+    path: Vec<PathSegment>,
+    mismatches: Vec<ValueMismatch>,
+}
+
+impl ValueChecker<'_> {
+    /// Check `value` against `ty`, where `value` is `at` of type `at_ty` (or a value it is coerced
+    /// from), which is the value at the end of `path`
+    fn check(&mut self, at: &Value, at_ty: &Ty, value: &Value, ty: &Ty) {
+        let mismatch = match (value, &ty.kind) {
+            (Value::Int(i), TyKind::Int) if i32::try_from(*i).is_err() =>
+                TypeMismatch::IntOutOfRange,
+            (Value::BigInt(_), TyKind::Int) => TypeMismatch::IntOutOfRange,
+            (_, TyKind::Err)
+            | (Value::BigInt(_), TyKind::Float)
+            | (Value::BigInt(_), TyKind::ID)
+            | (Value::Boolean(_), TyKind::Boolean)
+            | (Value::Float(_), TyKind::Float)
+            | (Value::Int(_), TyKind::Int)
+            | (Value::Int(_), TyKind::Float)
+            | (Value::Int(_), TyKind::ID)
+            | (Value::String(_), TyKind::String)
+            | (Value::String(_), TyKind::ID) => return,
+            (Value::Enum(variant), TyKind::Enum(e)) if e.variants.contains(variant) => return,
+            (Value::Enum(variant), TyKind::Enum(e)) =>
+                TypeMismatch::InvalidVariant(variant.clone(), e.clone()),
+            (Value::Null, kind) => match kind {
+                TyKind::NonNull(_) => TypeMismatch::InvalidNull,
+                _ => return,
+            },
+            // custom scalars are opaque so we can't say anything about the values they accept
+            (_, TyKind::Scalar(_)) => return,
+            (value, TyKind::NonNull(ty)) => return self.check(at, at_ty, value, ty),
+            (Value::List(values), TyKind::List(ty)) => {
+                for (i, value) in values.iter().enumerate() {
+                    self.path.push(PathSegment::Element(i));
+                    self.check(value, ty, value, ty);
+                    self.path.pop();
+                }
+                return;
+            }
+            // a single value is coerced into a list of one
+            (value, TyKind::List(ty)) => return self.check(at, at_ty, value, ty),
+            (Value::Object(obj), TyKind::Input(input)) =>
+                return self.check_object(at, at_ty, obj, input, ty),
+            (value, _) => TypeMismatch::Obvious(value.clone(), ty.clone()),
+        };
+        self.mismatch(at, at_ty, mismatch);
+    }
+
+    fn check_object(
+        &mut self,
+        at: &Value,
+        at_ty: &Ty,
+        obj: &BTreeMap<Name, Value>,
+        input: &InputObjectType,
+        ty: &Ty,
+    ) {
+        let fields = &input.fields.fields;
+        for name in obj.keys() {
+            if !fields.iter().any(|field| field.name == name.symbol()) {
+                self.mismatch(at, at_ty, TypeMismatch::ExtraneousField(name.symbol(), ty.clone()));
             }
         }
-        (Value::Object(obj), TyKind::Input(input)) => {
-            let fields = &input.fields.fields;
-            for name in obj.keys() {
-                if !fields.iter().any(|field| field.name == name.name()) {
-                    return Err(TypeMismatch::ExtraneousField(name.name(), ty.clone()));
+        for field in fields {
+            let field_ty = self.db.type_of_field(field.res);
+            match obj.get_key_value(&field.name) {
+                Some((&key, value)) => {
+                    self.path.push(PathSegment::Field(key));
+                    self.check(value, &field_ty, value, &field_ty);
+                    self.path.pop();
                 }
-            }
-            match fields
-                .iter()
-                .map(|field| match obj.get(&field.name) {
-                    Some(value) =>
-                        db.ensure_has_type(value.clone(), db.type_of_field(field.res.clone())),
-                    None => match db.type_of_field(field.res).is_nullable() {
-                        true => Ok(()),
-                        false => Err(TypeMismatch::InvalidNullField(field.name.clone())),
-                    },
-                })
-                .find_map(|x| x.err())
-            {
-                Some(err) => Err(err),
-                None => Ok(()),
+                None if field_ty.is_nullable() => {}
+                // non-nullable fields may be omitted if they have a default value
+                None if self.db.field(field.res).default_value.is_some() => {}
+                None => self.mismatch(at, at_ty, TypeMismatch::InvalidNullField(field.name)),
             }
         }
-        (value, _) => Err(TypeMismatch::Obvious(value, ty)),
+    }
+
+    fn mismatch(&mut self, value: &Value, ty: &Ty, mismatch: TypeMismatch) {
+        let (path, value, ty) = (self.path.clone(), value.clone(), ty.clone());
+        self.mismatches.push(ValueMismatch { path, value, ty, mismatch });
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn has_type(db: &dyn TyDatabase, value: Value, ty: Ty) -> bool {
     db.ensure_has_type(value, ty).is_ok()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn implements_interface(
     _db: &dyn TyDatabase,
     _obj: ObjectType,
@@ -86,6 +144,7 @@ fn implements_interface(
     todo!()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn lower_type(db: &dyn TyDatabase, ty: ir::Ty) -> Ty {
     match ty.kind.clone() {
         ir::TyKind::Named(_, res) => return db.type_of_res(res),
@@ -96,6 +155,7 @@ fn lower_type(db: &dyn TyDatabase, ty: ir::Ty) -> Ty {
     .intern()
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn type_of_res(db: &dyn TyDatabase, res: Res) -> Ty {
     match res {
         Res::Item(res) => match res[..] {
@@ -108,11 +168,13 @@ fn type_of_res(db: &dyn TyDatabase, res: Res) -> Ty {
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn type_of_field(db: &dyn TyDatabase, res: FieldRes) -> Ty {
     let field = db.field(res);
     db.lower_type(field.ty)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn field_types_of(db: &dyn TyDatabase, res: ItemRes) -> FieldTypes {
     let body = db.item_body(res).expect("queried `field_types` on item with no fields");
     FieldTypes {
@@ -120,11 +182,15 @@ fn field_types_of(db: &dyn TyDatabase, res: ItemRes) -> FieldTypes {
             .fields()
             .unwrap()
             .iter()
-            .map(|(idx, field)| FieldType { name: field.name.name(), res: FieldRes::new(res, idx) })
+            .map(|(idx, field)| FieldType {
+                name: field.name.symbol(),
+                res: FieldRes::new(res, idx),
+            })
             .collect(),
     }
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 fn type_of_item(db: &dyn TyDatabase, res: ItemRes) -> Ty {
     // FIXME aggregate over all the extensions
     // if there's any ambiguities/duplicates/whatever just return TyKind::Err
@@ -133,12 +199,10 @@ fn type_of_item(db: &dyn TyDatabase, res: ItemRes) -> Ty {
         ItemKind::TypeDefinition(idx) => {
             let typedef = &db.items(res.file)[idx];
             let body = db.item_body(res).expect("typedef should have a body");
-            let name = item.name.name();
+            let name = item.name.symbol();
             let kind = match &typedef.kind {
-                TypeDefinitionKind::Object => TyKind::Object(ObjectType {
-                    name: item.name.name(),
-                    fields: db.field_types_of(res),
-                }),
+                TypeDefinitionKind::Object =>
+                    TyKind::Object(ObjectType { name, fields: db.field_types_of(res) }),
                 TypeDefinitionKind::Interface =>
                     TyKind::Interface(InterfaceType { name, fields: db.field_types_of(res) }),
                 TypeDefinitionKind::Input =>
@@ -150,7 +214,7 @@ fn type_of_item(db: &dyn TyDatabase, res: ItemRes) -> Ty {
                         .as_enum()
                         .variants
                         .iter()
-                        .map(|v| v.name.name().as_str().into())
+                        .map(|v| v.name.as_str().into())
                         .collect(),
                 }),
                 TypeDefinitionKind::Union => TyKind::Union(UnionType {
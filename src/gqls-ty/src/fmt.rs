@@ -170,6 +170,7 @@ impl Display for TypeMismatch {
                 write!(f, "field `{name}` is not a member of type `{ty}`")
             }
             TypeMismatch::InvalidNull => write!(f, "expected non-nullable value"),
+            TypeMismatch::IntOutOfRange => write!(f, "out of range of a 32-bit signed integer"),
             TypeMismatch::Obvious(value, ty) =>
                 write!(f, "cannot use {} value as {} type", value.desc(), ty.desc()),
         }
@@ -3,8 +3,7 @@ pub use db::{TyDatabase, TyDatabaseStorage};
 mod db;
 mod fmt;
 
-use gqls_ir::{BuiltinScalar, FieldRes, Value};
-use smol_str::SmolStr;
+use gqls_ir::{BuiltinScalar, FieldRes, Name, Symbol, Value};
 use std::borrow::Cow;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -120,7 +119,7 @@ impl From<BuiltinScalar> for TyKind {
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct UnionType {
-    name: SmolStr,
+    name: Symbol,
     types: Vec<Ty>,
 }
 
@@ -129,12 +128,12 @@ impl UnionType {
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct ScalarType {
-    name: SmolStr,
+    name: Symbol,
 }
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct EnumType {
-    name: SmolStr,
+    name: Symbol,
     variants: Arc<[Arc<str>]>,
 }
 
@@ -145,25 +144,25 @@ pub struct FieldTypes {
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct ObjectType {
-    name: SmolStr,
+    name: Symbol,
     fields: FieldTypes,
 }
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct InputObjectType {
-    name: SmolStr,
+    name: Symbol,
     fields: FieldTypes,
 }
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct InterfaceType {
-    name: SmolStr,
+    name: Symbol,
     fields: FieldTypes,
 }
 
 #[derive(PartialEq, Eq, Clone, Hash)]
 pub struct FieldType {
-    name: SmolStr,
+    name: Symbol,
     res: FieldRes,
 }
 
@@ -201,11 +200,33 @@ impl TyKind {
 pub enum TypeMismatch {
     InvalidVariant(Arc<str>, EnumType),
     InvalidNull,
-    InvalidNullField(SmolStr),
-    ExtraneousField(SmolStr, Ty),
+    InvalidNullField(Symbol),
+    /// an `Int` is a 32-bit signed integer
+    IntOutOfRange,
+    ExtraneousField(Symbol, Ty),
     Obvious(Value, Ty),
 }
 
+/// A step from a value to a value nested in it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment {
+    /// the value of the field of an object with the given key
+    Field(Name),
+    /// the element of a list at the given index
+    Element(usize),
+}
+
+/// A mismatch of a value nested in the value that was checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueMismatch {
+    /// the steps from the value that was checked to the value, outermost first
+    pub path: Vec<PathSegment>,
+    /// the value that doesn't have its type, which is an object for a missing or extraneous field
+    pub value: Value,
+    pub ty: Ty,
+    pub mismatch: TypeMismatch,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ImplError;
 
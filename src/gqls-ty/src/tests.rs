@@ -9,11 +9,18 @@ use gqls_ir::{DefDatabase, DefDatabaseStorage, InProject, Name, SourceDatabaseSt
 use testing::{file_id, TestDatabaseExt};
 
 #[salsa::database(SourceDatabaseStorage, DefDatabaseStorage, TyDatabaseStorage)]
-#[derive(Default)]
 pub(crate) struct TestDB {
     storage: salsa::Storage<TestDB>,
 }
 
+impl Default for TestDB {
+    fn default() -> Self {
+        let mut db = Self { storage: Default::default() };
+        db.set_item_cache(None);
+        db
+    }
+}
+
 impl salsa::Database for TestDB {
 }
 
@@ -1,3 +1,5 @@
+use gqls_ir::Value;
+
 use crate::{ty, val, TyDatabase};
 
 use super::TestDB;
@@ -32,3 +34,38 @@ fn test_has_type() {
     assert!(db.has_type(val!([0, null]), ty!(![Int])));
     assert!(!db.has_type(val!([0, null]), ty!([!Int])));
 }
+
+#[test]
+fn test_has_type_coercion() {
+    let db = TestDB::default();
+
+    assert!(db.has_type(val!(1), ty!(Float)));
+    assert!(db.has_type(val!(1), ty!(!ID)));
+    assert!(!db.has_type(val!(1.5), ty!(Int)));
+
+    assert!(db.has_type(val!(1), ty!([Int])));
+    assert!(db.has_type(val!(1), ty!(![!Int])));
+    assert!(db.has_type(val!(A), ty!([!A | B])));
+    assert!(!db.has_type(val!(C), ty!([!A | B])));
+    assert!(!db.has_type(val!(false), ty!([Int])));
+    assert!(!db.has_type(val!("A"), ty!(A | B)));
+    assert!(db.has_type(val!([[1], [2, 3]]), ty!([[Int]])));
+}
+
+#[test]
+fn test_has_type_int_range() {
+    let db = TestDB::default();
+
+    assert!(db.has_type(val!(2147483647), ty!(Int)));
+    assert!(db.has_type(val!(-2147483648), ty!(Int)));
+    assert!(!db.has_type(val!(2147483648i64), ty!(Int)));
+    assert!(!db.has_type(val!([0, 2147483648i64]), ty!([Int])));
+    assert!(db.has_type(val!(2147483648i64), ty!(Float)));
+    assert!(db.has_type(val!(2147483648i64), ty!(ID)));
+
+    let big = || Value::BigInt("99999999999999999999".into());
+    assert!(!db.has_type(big(), ty!(Int)));
+    assert!(db.has_type(big(), ty!(Float)));
+    assert!(db.has_type(big(), ty!(ID)));
+    assert!(!db.has_type(big(), ty!(String)));
+}
@@ -0,0 +1,191 @@
+//! A JavaScript API to the analyzer for browser based editors, built with
+//! `wasm-pack build src/gqls-wasm`.
+//!
+//! The tree-sitter parser is written in C, so building for `wasm32-unknown-unknown` needs a clang
+//! with the wasm32 target and a libc sysroot for its headers, e.g. that of wasi-libc:
+//! `CC_wasm32_unknown_unknown=clang CFLAGS_wasm32_unknown_unknown=--sysroot=/opt/wasi-sysroot`.
+//!
+//! Positions and ranges are in utf-16 code units like the strings of JavaScript and the
+//! language server protocol, with zero-based lines and characters.
+
+#![deny(rust_2018_idioms)]
+
+use gqls_ide::{
+    AnalysisHost, Cancellable, CompletionConfig, CompletionItem, CompletionItemKind, Diagnostic, LineIndex, Point, Position, PositionEncoding, Range, Severity
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// Every file is in a single project, so names resolve across all of them
+const PROJECT: &str = "default";
+
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Analyzer {
+    host: AnalysisHost,
+}
+
+#[wasm_bindgen]
+impl Analyzer {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the text of the file at `path`, adding it if it is new
+    #[wasm_bindgen(js_name = setFile)]
+    pub fn set_file(&mut self, path: &str, text: String) {
+        self.host.set_file(PROJECT, path, text);
+    }
+
+    /// Remove the file at `path`, returning whether there was one
+    #[wasm_bindgen(js_name = removeFile)]
+    pub fn remove_file(&mut self, path: &str) -> bool {
+        self.host.remove_file(path)
+    }
+
+    /// The diagnostics of the file at `path` as an array of
+    /// `{ range, severity, code, message }`
+    pub fn diagnostics(&self, path: &str) -> Result<JsValue, JsValue> {
+        let analysis = self.host.analysis();
+        let file = match analysis.file_id(path) {
+            Some(file) => file,
+            None => return Err(unknown_file(path)),
+        };
+        let index = cancellable(analysis.line_index(file))?;
+        let mut diagnostics = cancellable(analysis.diagnostics(file))?
+            .iter()
+            .map(|diagnostic| JsDiagnostic::new(&index, diagnostic))
+            .collect::<Vec<_>>();
+        diagnostics.sort_by_key(|diagnostic| (diagnostic.range.start, diagnostic.range.end));
+        to_value(&diagnostics)
+    }
+
+    /// The completions at `line` and `character` of the file at `path` as an array of
+    /// `{ label, kind, detail, insertText, filterText, sortText, documentation, deprecated }`
+    pub fn completions(
+        &self,
+        path: &str,
+        line: usize,
+        character: usize,
+    ) -> Result<JsValue, JsValue> {
+        let analysis = self.host.analysis();
+        let file = match analysis.file_id(path) {
+            Some(file) => file,
+            None => return Err(unknown_file(path)),
+        };
+        let index = cancellable(analysis.line_index(file))?;
+        let point = index.decode(Point::new(line, character), PositionEncoding::Utf16);
+        let config = CompletionConfig::default();
+        let completions = cancellable(analysis.completions(Position::new(file, point), &config))?
+            .into_iter()
+            .map(JsCompletionItem::from)
+            .collect::<Vec<_>>();
+        to_value(&completions)
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct JsPosition {
+    line: usize,
+    character: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsRange {
+    start: JsPosition,
+    end: JsPosition,
+}
+
+impl JsRange {
+    fn new(index: &LineIndex, range: Range) -> Self {
+        let encode = |point| {
+            let point = index.encode(point, PositionEncoding::Utf16);
+            JsPosition { line: point.row, character: point.column }
+        };
+        Self { start: encode(range.start), end: encode(range.end) }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsDiagnostic {
+    range: JsRange,
+    severity: &'static str,
+    code: String,
+    message: String,
+}
+
+impl JsDiagnostic {
+    fn new(index: &LineIndex, diagnostic: &Diagnostic) -> Self {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        };
+        Self {
+            range: JsRange::new(index, diagnostic.range),
+            severity,
+            code: diagnostic.code.to_string(),
+            message: diagnostic.message.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsCompletionItem {
+    label: String,
+    kind: &'static str,
+    detail: Option<String>,
+    insert_text: Option<String>,
+    filter_text: Option<String>,
+    sort_text: Option<String>,
+    documentation: Option<String>,
+    deprecated: bool,
+}
+
+impl From<CompletionItem> for JsCompletionItem {
+    fn from(item: CompletionItem) -> Self {
+        let kind = match item.kind {
+            CompletionItemKind::Object => "object",
+            CompletionItemKind::InputObject => "inputObject",
+            CompletionItemKind::Interface => "interface",
+            CompletionItemKind::Enum => "enum",
+            CompletionItemKind::Scalar => "scalar",
+            CompletionItemKind::Union => "union",
+            CompletionItemKind::Keyword => "keyword",
+            CompletionItemKind::DirectiveLocation => "directiveLocation",
+            CompletionItemKind::Directive(_) => "directive",
+            CompletionItemKind::EnumValue => "enumValue",
+            CompletionItemKind::InputField => "inputField",
+            CompletionItemKind::Argument => "argument",
+            CompletionItemKind::Description => "description",
+        };
+        Self {
+            label: item.label,
+            kind,
+            detail: item.detail,
+            insert_text: item.insert_text,
+            filter_text: item.filter_text,
+            sort_text: item.sort_text,
+            documentation: item.documentation,
+            deprecated: item.deprecated,
+        }
+    }
+}
+
+fn cancellable<T>(result: Cancellable<T>) -> Result<T, JsValue> {
+    result.map_err(|cancelled| JsValue::from_str(&cancelled.to_string()))
+}
+
+fn unknown_file(path: &str) -> JsValue {
+    JsValue::from_str(&format!("unknown file `{path}`"))
+}
+
+fn to_value(value: &impl Serialize) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value).map_err(JsValue::from)
+}
+
+#[cfg(test)]
+mod tests;
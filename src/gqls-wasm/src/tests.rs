@@ -0,0 +1,54 @@
+use gqls_ide::{CompletionConfig, Point, Position};
+
+use crate::{Analyzer, JsCompletionItem, JsDiagnostic, JsPosition};
+
+#[test]
+fn test_diagnostic_ranges_are_utf16() {
+    let mut analyzer = Analyzer::new();
+    analyzer.set_file("wasm/utf16.graphql", r#"type Foo { "😀" foo: Unresolved }"#.to_owned());
+    let analysis = analyzer.host.analysis();
+    let file = analysis.file_id("wasm/utf16.graphql").unwrap();
+    let index = analysis.line_index(file).unwrap();
+    let diagnostics = analysis.diagnostics(file).unwrap();
+    let diagnostics =
+        diagnostics.iter().map(|diag| JsDiagnostic::new(&index, diag)).collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    // the emoji is four bytes but only two code units
+    assert_eq!(diagnostic.range.start, JsPosition { line: 0, character: 19 });
+    assert_eq!(diagnostic.range.end, JsPosition { line: 0, character: 29 });
+    assert_eq!(diagnostic.severity, "error");
+    assert!(diagnostic.message.starts_with("unresolved type `Unresolved`"));
+}
+
+#[test]
+fn test_remove_file() {
+    let mut analyzer = Analyzer::new();
+    analyzer.set_file("wasm/remove/foo.graphql", "type Foo { bar: Bar }".to_owned());
+    analyzer.set_file("wasm/remove/bar.graphql", "type Bar { id: ID }".to_owned());
+    let foo = analyzer.host.analysis().file_id("wasm/remove/foo.graphql").unwrap();
+    assert!(analyzer.host.analysis().diagnostics(foo).unwrap().is_empty());
+
+    assert!(analyzer.remove_file("wasm/remove/bar.graphql"));
+    assert!(!analyzer.remove_file("wasm/remove/bar.graphql"));
+    assert_eq!(analyzer.host.analysis().diagnostics(foo).unwrap().len(), 1);
+}
+
+#[test]
+fn test_completion_kinds() {
+    let mut analyzer = Analyzer::new();
+    analyzer.set_file("wasm/completions.graphql", "type Foo { foo:  }\nscalar S".to_owned());
+    let analysis = analyzer.host.analysis();
+    let file = analysis.file_id("wasm/completions.graphql").unwrap();
+    let position = Position::new(file, Point::new(0, 16));
+    let completions = analysis
+        .completions(position, &CompletionConfig::default())
+        .unwrap()
+        .into_iter()
+        .map(JsCompletionItem::from)
+        .collect::<Vec<_>>();
+    let kind_of =
+        |label: &str| completions.iter().find(|item| item.label == label).map(|item| item.kind);
+    assert_eq!(kind_of("Foo"), Some("object"));
+    assert_eq!(kind_of("S"), Some("scalar"));
+}
@@ -0,0 +1,103 @@
+//! A baseline records the existing lint violations of a workspace so lints can be adopted
+//! incrementally, the recorded violations are suppressed and only new ones are reported
+
+use gqls_ide::{Diagnostic, Diagnostics, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// the violations of each file, keyed by the path relative to the workspace root.
+    /// Locations are not recorded so that unrelated edits don't invalidate the baseline.
+    files: BTreeMap<String, Vec<Violation>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Violation {
+    code: String,
+    message: String,
+}
+
+impl Violation {
+    fn new(diagnostic: &Diagnostic) -> Self {
+        Self { code: diagnostic.code.to_string(), message: diagnostic.message.clone() }
+    }
+}
+
+impl Baseline {
+    /// Record the lint violations of the files under `root`, errors are never part of a baseline
+    pub fn record(root: &Path, diagnostics: &Diagnostics) -> Self {
+        let mut files = BTreeMap::new();
+        for (&file, diagnostics) in diagnostics {
+            let key = match relative_path(root, file) {
+                Some(key) => key,
+                None => continue,
+            };
+            let mut violations = diagnostics
+                .iter()
+                .filter(|diagnostic| matches!(diagnostic.code, ErrorCode::Lint(_)))
+                .map(Violation::new)
+                .collect::<Vec<_>>();
+            if violations.is_empty() {
+                continue;
+            }
+            violations.sort();
+            files.insert(key, violations);
+        }
+        Self { files }
+    }
+
+    /// Remove the diagnostics of the files under `root` that are recorded in the baseline.
+    /// Each recorded violation suppresses at most one diagnostic (the earliest in the file),
+    /// so any additional occurrences of the same violation are still reported.
+    pub fn suppress(&self, root: &Path, diagnostics: &mut Diagnostics) {
+        for (&file, diagnostics) in diagnostics.iter_mut() {
+            let recorded = match relative_path(root, file).and_then(|key| self.files.get(&key)) {
+                Some(recorded) => recorded,
+                None => continue,
+            };
+            let mut remaining = recorded.iter().collect::<Vec<_>>();
+            let mut candidates = diagnostics.iter().cloned().collect::<Vec<_>>();
+            candidates.sort_by_key(|diagnostic| (diagnostic.range.start, diagnostic.range.end));
+            for diagnostic in candidates {
+                let violation = Violation::new(&diagnostic);
+                if let Some(idx) = remaining.iter().position(|&recorded| *recorded == violation) {
+                    remaining.swap_remove(idx);
+                    diagnostics.remove(&diagnostic);
+                }
+            }
+        }
+    }
+
+    /// The total number of recorded violations
+    pub fn len(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+}
+
+/// `path` relative to `root` with `/` separators so baselines can be shared across platforms
+pub(crate) fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let components = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    Some(components.join("/"))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use gqls_ide::{Diagnostic, Diagnostics, ErrorCode, Point};
+use maplit::{hashmap, hashset};
+use std::path::Path;
+
+use super::Baseline;
+
+fn diagnostic(row: usize, code: ErrorCode, message: &str) -> Diagnostic {
+    let range = Point::new(row, 0)..Point::new(row, 1);
+    Diagnostic::new(range.into(), code, message.to_owned())
+}
+
+fn rows(diagnostics: &Diagnostics, file: &Path) -> Vec<usize> {
+    let mut rows = diagnostics[file].iter().map(|diag| diag.range.start.row).collect::<Vec<_>>();
+    rows.sort();
+    rows
+}
+
+#[test]
+fn test_baseline_suppresses_recorded_lints() {
+    let root = Path::new("/root");
+    let foo = Path::new("/root/foo.graphql");
+    let bar = Path::new("/root/nested/bar.graphql");
+    let diagnostics = hashmap! {
+        foo => hashset![
+            diagnostic(0, ErrorCode::Lint(3), "field `a_b` should be in camelCase: `aB`"),
+            diagnostic(1, ErrorCode::Error(4), "unresolved type `Bar`"),
+        ],
+        bar => hashset![
+            diagnostic(0, ErrorCode::Lint(2), "type `bar` should be in PascalCase: `Bar`"),
+        ],
+    };
+    let baseline = Baseline::record(root, &diagnostics);
+    // errors are never recorded
+    assert_eq!(baseline.len(), 2);
+
+    let mut next = hashmap! {
+        foo => hashset![
+            diagnostic(3, ErrorCode::Lint(3), "field `a_b` should be in camelCase: `aB`"),
+            diagnostic(4, ErrorCode::Lint(3), "field `a_b` should be in camelCase: `aB`"),
+            diagnostic(5, ErrorCode::Lint(3), "field `c_d` should be in camelCase: `cD`"),
+            diagnostic(6, ErrorCode::Error(4), "unresolved type `Bar`"),
+        ],
+        bar => hashset![
+            diagnostic(2, ErrorCode::Lint(2), "type `bar` should be in PascalCase: `Bar`"),
+        ],
+    };
+    baseline.suppress(root, &mut next);
+    // each recorded violation only suppresses a single occurrence, regardless of where it is
+    assert_eq!(rows(&next, foo), vec![4, 5, 6]);
+    assert!(next[bar].is_empty());
+}
+
+#[test]
+fn test_baseline_ignores_files_outside_root() {
+    let root = Path::new("/root");
+    let outside = Path::new("/elsewhere/foo.graphql");
+    let violations =
+        || hashset![diagnostic(0, ErrorCode::Lint(3), "field `a_b` should be in camelCase: `aB`")];
+    let baseline = Baseline::record(root, &hashmap! { outside => violations() });
+    assert!(baseline.is_empty());
+
+    let mut diagnostics = hashmap! { outside => violations() };
+    baseline.suppress(root, &mut diagnostics);
+    assert_eq!(diagnostics[outside].len(), 1);
+}
+
+#[test]
+fn test_baseline_roundtrip() -> anyhow::Result<()> {
+    let root = Path::new("/root");
+    let diagnostics = hashmap! {
+        Path::new("/root/foo.graphql") => hashset![
+            diagnostic(0, ErrorCode::Lint(3), "field `a_b` should be in camelCase: `aB`"),
+        ],
+    };
+    let baseline = Baseline::record(root, &diagnostics);
+    let json = serde_json::to_string(&baseline)?;
+    assert_eq!(
+        json,
+        r#"{"files":{"foo.graphql":[{"code":"L0003","message":"field `a_b` should be in camelCase: `aB`"}]}}"#
+    );
+    assert_eq!(serde_json::from_str::<Baseline>(&json)?, baseline);
+    Ok(())
+}
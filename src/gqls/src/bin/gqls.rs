@@ -1,8 +1,8 @@
 use anyhow::Result;
-use gqls::Gqls;
-use tower_lsp::Server;
+use gqls::{DiffArgs, FormatArgs, GraphArgs, IndexArgs, LintArgs, TimingsLayer, Transport};
 use tracing::metadata::LevelFilter;
 use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt::format::FmtSpan;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::EnvFilter;
 
@@ -10,7 +10,11 @@ use tracing_subscriber::EnvFilter;
 async fn main() -> Result<()> {
     Targets::new();
 
-    let mut layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    // the duration of each request is logged when its span closes, as is that of each query at
+    // the debug level
+    let mut layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_span_events(FmtSpan::CLOSE);
     if atty::isnt(atty::Stream::Stderr) {
         layer = layer.with_ansi(false);
     }
@@ -23,10 +27,32 @@ async fn main() -> Result<()> {
 
     let targets =
         Targets::new().with_target("salsa", LevelFilter::WARN).with_default(LevelFilter::TRACE);
-    tracing_subscriber::registry().with(filtered_layer).with(targets).init();
+    tracing_subscriber::registry()
+        .with(filtered_layer)
+        .with(targets)
+        .with(TimingsLayer::default())
+        .init();
 
-    let (stdin, stdout) = (tokio::io::stdin(), tokio::io::stdout());
-    let (service, socket) = Gqls::service();
-    Server::new(stdin, stdout, socket).serve(service).await;
-    Ok(())
+    // `gqls lint [--baseline <path>] [--format <text|json|sarif>]` checks the workspace in the
+    // current directory, `gqls format [--check] [paths...]` formats its graphql files
+    // (or the given ones), `gqls diff <old> <new>` reports the changes between two schemas and
+    // `gqls index [--output <path>]` exports an LSIF dump of the workspace for code search tools
+    // and `gqls graph [--format <dot|json>] [--output <path>]` exports the dependencies between
+    // its types, otherwise runs the language server over stdio, or `--tcp <port|address>` or
+    // `--socket <path>`
+    let mut args = std::env::args().skip(1).peekable();
+    if let Some(command) = args.next_if(|arg| !arg.starts_with('-')) {
+        let root = std::env::current_dir()?;
+        let success = match command.as_str() {
+            "lint" => gqls::lint(&root, &LintArgs::parse(args)?, &mut std::io::stdout())?,
+            "format" => gqls::format(&root, &FormatArgs::parse(args)?, &mut std::io::stdout())?,
+            "diff" => gqls::diff(&root, &DiffArgs::parse(args)?, &mut std::io::stdout())?,
+            "index" => gqls::index(&root, &IndexArgs::parse(args)?, &mut std::io::stdout())?,
+            "graph" => gqls::graph(&root, &GraphArgs::parse(args)?, &mut std::io::stdout())?,
+            _ => anyhow::bail!("unknown command `{command}`"),
+        };
+        std::process::exit(if success { 0 } else { 1 });
+    }
+
+    gqls::serve(&Transport::parse(args)?).await
 }
@@ -1,9 +1,11 @@
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use globset::Glob;
 use serde::{Deserialize, Deserializer};
 
+use crate::settings::LintLevel;
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum Config {
@@ -14,18 +16,96 @@ pub enum Config {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
 pub struct Projects {
     projects: BTreeMap<String, ProjectConfig>,
+    /// the lint baseline of the whole workspace, relative to the workspace root
+    #[serde(default)]
+    baseline: Option<PathBuf>,
+    /// the size in bytes beyond which a file only has its definitions indexed
+    #[serde(default)]
+    max_file_size: Option<usize>,
+    /// how the generated files of the workspace are recognized
+    #[serde(default)]
+    generated: Option<GeneratedConfig>,
 }
 
 impl Projects {
     #[cfg(test)]
     fn new(projects: BTreeMap<String, ProjectConfig>) -> Self {
-        Self { projects }
+        Self { projects, baseline: None, max_file_size: None, generated: None }
     }
 }
 
 pub const DEFAULT_PROJECT: &str = "default";
 
 impl Config {
+    fn project(&self, project: &str) -> Option<&ProjectConfig> {
+        match self {
+            Config::Project(config) => (project == DEFAULT_PROJECT).then(|| config),
+            Config::Projects(projects) => projects.projects.get(project),
+        }
+    }
+
+    /// Return the names of the lints enabled for `project`
+    pub fn lints(&self, project: &str) -> &[String] {
+        self.project(project).map_or(&[], |config| config.lints.as_slice())
+    }
+
+    /// Return the directives configured to take a field set for `project`
+    pub fn field_sets(&self, project: &str) -> &[FieldSetConfig] {
+        self.project(project).map_or(&[], |config| config.field_sets.as_slice())
+    }
+
+    /// Return the custom scalars described for `project`, by name
+    pub fn scalars(&self, project: &str) -> Option<&BTreeMap<String, ScalarConfig>> {
+        self.project(project).map(|config| &config.scalars)
+    }
+
+    /// Return the names of the root operation types configured for `project`
+    pub fn root_types(&self, project: &str) -> Option<&RootTypesConfig> {
+        self.project(project).map(|config| &config.root_types)
+    }
+
+    /// Return whether the graphql-import comments of `project` restrict what a file sees
+    pub fn enforces_imports(&self, project: &str) -> bool {
+        self.project(project).map_or(false, |config| config.enforce_imports)
+    }
+
+    /// Return the path of the lint baseline (relative to the workspace root) if one is configured
+    pub fn baseline(&self) -> Option<&Path> {
+        match self {
+            Config::Project(config) => config.baseline.as_deref(),
+            Config::Projects(projects) => projects.baseline.as_deref(),
+        }
+    }
+
+    /// Return the size in bytes beyond which a file is indexed shallowly if one is configured
+    pub fn max_file_size(&self) -> Option<usize> {
+        match self {
+            Config::Project(config) => config.max_file_size,
+            Config::Projects(projects) => projects.max_file_size,
+        }
+    }
+
+    /// Return how generated files are recognized if it is configured
+    pub fn generated(&self) -> Option<&GeneratedConfig> {
+        match self {
+            Config::Project(config) => config.generated.as_ref(),
+            Config::Projects(projects) => projects.generated.as_ref(),
+        }
+    }
+
+    /// Return the published schemas that projects are checked against for breaking changes
+    pub fn published(&self) -> Vec<(&str, &PublishedConfig)> {
+        match self {
+            Config::Project(config) =>
+                config.published.iter().map(|published| (DEFAULT_PROJECT, published)).collect(),
+            Config::Projects(projects) => projects
+                .projects
+                .iter()
+                .filter_map(|(name, project)| Some((name.as_str(), project.published.as_ref()?)))
+                .collect(),
+        }
+    }
+
     /// Return list of projects that a `path` belongs to
     pub fn project_matches(&self, path: &Path) -> Vec<&str> {
         match self {
@@ -39,6 +119,21 @@ impl Config {
         }
     }
 
+    /// Return the schema globs of every project, with duplicates removed
+    pub fn schema_globs(&self) -> Vec<&str> {
+        let projects = match self {
+            Config::Project(config) => vec![config],
+            Config::Projects(projects) => projects.projects.values().collect(),
+        };
+        let mut globs = projects
+            .into_iter()
+            .flat_map(|config| config.schema.iter().map(Glob::glob))
+            .collect::<Vec<_>>();
+        globs.sort_unstable();
+        globs.dedup();
+        globs
+    }
+
     pub(crate) fn read(path: &Path) -> anyhow::Result<Self> {
         assert!(path.exists());
         let s = std::fs::read_to_string(path)?;
@@ -53,10 +148,121 @@ impl Config {
     }
 }
 
+/// The name of the configuration file of a directory, see [`DirectoryConfig`]
+pub const DIRECTORY_CONFIG: &str = ".gqlsrc.toml";
+
+/// The configuration of a directory of the workspace (`.gqlsrc.toml`), which applies to the files
+/// below it on top of the workspace configuration, e.g. to disable lints for generated schemas.
+/// The configuration of a nested directory overrides that of its parents.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct DirectoryConfig {
+    /// the level of lints by name (e.g. `unused-definition = "allow"`)
+    #[serde(default)]
+    pub lints: BTreeMap<String, LintLevel>,
+    /// globs of the files (relative to the directory) that are never loaded
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl DirectoryConfig {
+    pub(crate) fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(toml::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
 pub struct ProjectConfig {
     #[serde(deserialize_with = "deserialize_schema")]
     schema: OneOrMany<Glob>,
+    /// opt-in lints (e.g. `field-name-case`)
+    #[serde(default)]
+    lints: Vec<String>,
+    /// only meaningful when this is the only project, see [`Projects::baseline`]
+    #[serde(default)]
+    baseline: Option<PathBuf>,
+    /// only meaningful when this is the only project, see [`Projects::max_file_size`]
+    #[serde(default)]
+    max_file_size: Option<usize>,
+    /// only meaningful when this is the only project, see [`Projects::generated`]
+    #[serde(default)]
+    generated: Option<GeneratedConfig>,
+    #[serde(default)]
+    published: Option<PublishedConfig>,
+    /// directives (besides the federation ones) whose argument selects fields of the type
+    #[serde(default)]
+    field_sets: Vec<FieldSetConfig>,
+    /// a file with graphql-import comments (e.g. `# import Foo from "foo.graphql"`) only sees
+    /// the names it imports
+    #[serde(default)]
+    enforce_imports: bool,
+    /// the registry of the custom scalars of the project, e.g. `DateTime` as an RFC 3339 string
+    #[serde(default)]
+    scalars: BTreeMap<String, ScalarConfig>,
+    /// the names of the root operation types when the schema has no schema definition
+    #[serde(default)]
+    root_types: RootTypesConfig,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct FieldSetConfig {
+    /// the name of the directive, with or without the leading `@`
+    pub directive: String,
+    #[serde(default = "default_field_set_argument")]
+    pub argument: String,
+    /// whether the fields are selected on the type returned by the annotated field rather than
+    /// the type the field belongs to
+    #[serde(default)]
+    pub returned: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct ScalarConfig {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// the url of the specification of the scalar, as if given by `@specifiedBy`
+    #[serde(default)]
+    pub specified_by: Option<String>,
+}
+
+/// The names that override the conventional `Query`, `Mutation` and `Subscription`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct RootTypesConfig {
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub mutation: Option<String>,
+    #[serde(default)]
+    pub subscription: Option<String>,
+}
+
+fn default_field_set_argument() -> String {
+    "fields".to_owned()
+}
+
+/// Generated files are still indexed, but they aren't linted or formatted and their semantic
+/// errors are only hints
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct GeneratedConfig {
+    /// the text of a comment that marks a file as generated, `@generated` by default
+    #[serde(default)]
+    pub marker: Option<String>,
+    /// the number of lines at the start of a file that are searched for the marker
+    #[serde(default)]
+    pub lines: Option<usize>,
+    /// globs of the files (relative to the workspace root) that are generated regardless of
+    /// whether they have the marker
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize)]
+pub struct PublishedConfig {
+    /// a schema file or directory relative to the workspace root, or the url of a graphql endpoint
+    /// which is introspected
+    pub schema: String,
+    /// the schema coordinates (e.g. `Query.users`) whose breaking changes are intended
+    #[serde(default)]
+    pub allow: Vec<String>,
 }
 
 // minor hack as the default `Glob` deserialize impl doesn't work with owned strings
@@ -4,7 +4,9 @@ use maplit::btreemap;
 
 use crate::config::{OneOrMany, Projects};
 
-use super::{Config, ProjectConfig};
+use super::{
+    Config, FieldSetConfig, GeneratedConfig, ProjectConfig, PublishedConfig, RootTypesConfig, ScalarConfig
+};
 
 #[test]
 fn test_parse_config_ignores_unknown_fields() {
@@ -23,8 +25,18 @@ fn test_parse_config() -> Result<()> {
     }
     .try_into::<ProjectConfig>()?;
 
-    let expected_project_config =
-        ProjectConfig { schema: OneOrMany::One(Glob::new("foo.graphql")?) };
+    let expected_project_config = ProjectConfig {
+        schema: OneOrMany::One(Glob::new("foo.graphql")?),
+        lints: vec![],
+        baseline: None,
+        max_file_size: None,
+        generated: None,
+        published: None,
+        field_sets: vec![],
+        enforce_imports: false,
+        scalars: Default::default(),
+        root_types: Default::default(),
+    };
     assert_eq!(project_config, expected_project_config);
 
     let config = toml::toml! {
@@ -48,12 +60,268 @@ fn test_parse_config() -> Result<()> {
         Config::Projects(Projects::new(btreemap! {
            "project1".to_owned() => ProjectConfig {
                schema: OneOrMany::Many(vec![Glob::new("foo.graphql")?,
-               Glob::new("bar.graphql")?])
+               Glob::new("bar.graphql")?]),
+               lints: vec![],
+               baseline: None,
+               max_file_size: None,
+               generated: None,
+               published: None,
+               field_sets: vec![],
+               enforce_imports: false,
+               scalars: Default::default(),
+               root_types: Default::default(),
             },
            "project2".to_owned() => ProjectConfig {
-               schema: OneOrMany::One(Glob::new("**/*.graphql")?)
+               schema: OneOrMany::One(Glob::new("**/*.graphql")?),
+               lints: vec![],
+               baseline: None,
+               max_file_size: None,
+               generated: None,
+               published: None,
+               field_sets: vec![],
+               enforce_imports: false,
+               scalars: Default::default(),
+               root_types: Default::default(),
            }
         }))
     );
     Ok(())
 }
+
+#[test]
+fn test_parse_config_lints() -> Result<()> {
+    let config = toml::toml! {
+        [projects.project1]
+        schema = "foo.graphql"
+        lints = ["field-name-case", "type-name-case"]
+
+        [projects.project2]
+        schema = "bar.graphql"
+    }
+    .try_into::<Config>()?;
+
+    assert_eq!(config.lints("project1"), ["field-name-case", "type-name-case"]);
+    assert!(config.lints("project2").is_empty());
+    assert!(config.lints("unknown").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_field_sets() -> Result<()> {
+    let config = toml::toml! {
+        schema = "foo.graphql"
+        field_sets = [
+            { directive = "@cacheKey" },
+            { directive = "preload", argument = "select", returned = true },
+        ]
+    }
+    .try_into::<Config>()?;
+
+    assert_eq!(
+        config.field_sets("default"),
+        [
+            FieldSetConfig {
+                directive: "@cacheKey".to_owned(),
+                argument: "fields".to_owned(),
+                returned: false,
+            },
+            FieldSetConfig {
+                directive: "preload".to_owned(),
+                argument: "select".to_owned(),
+                returned: true,
+            },
+        ]
+    );
+    assert!(config.field_sets("unknown").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_scalars() -> Result<()> {
+    let config = toml::toml! {
+        schema = "foo.graphql"
+
+        [scalars.DateTime]
+        description = "An RFC 3339 date-time"
+        specified_by = "https://datatracker.ietf.org/doc/html/rfc3339"
+
+        [scalars.Json]
+    }
+    .try_into::<Config>()?;
+
+    assert_eq!(
+        config.scalars("default"),
+        Some(&btreemap! {
+            "DateTime".to_owned() => ScalarConfig {
+                description: Some("An RFC 3339 date-time".to_owned()),
+                specified_by: Some("https://datatracker.ietf.org/doc/html/rfc3339".to_owned()),
+            },
+            "Json".to_owned() => ScalarConfig::default(),
+        })
+    );
+    assert_eq!(config.scalars("unknown"), None);
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_root_types() -> Result<()> {
+    let config = toml::toml! {
+        schema = "foo.graphql"
+
+        [root_types]
+        query = "RootQuery"
+        mutation = "RootMutation"
+    }
+    .try_into::<Config>()?;
+
+    assert_eq!(
+        config.root_types("default"),
+        Some(&RootTypesConfig {
+            query: Some("RootQuery".to_owned()),
+            mutation: Some("RootMutation".to_owned()),
+            subscription: None,
+        })
+    );
+    assert_eq!(config.root_types("unknown"), None);
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_enforce_imports() -> Result<()> {
+    let config = toml::toml! {
+        [projects.project1]
+        schema = "foo.graphql"
+        enforce_imports = true
+
+        [projects.project2]
+        schema = "bar.graphql"
+    }
+    .try_into::<Config>()?;
+
+    assert!(config.enforces_imports("project1"));
+    assert!(!config.enforces_imports("project2"));
+    assert!(!config.enforces_imports("unknown"));
+    Ok(())
+}
+
+#[test]
+fn test_config_schema_globs() -> Result<()> {
+    let config = toml::toml! {
+        [projects.project1]
+        schema = ["./foo.graphql", "shared/*.graphql"]
+
+        [projects.project2]
+        schema = "shared/*.graphql"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.schema_globs(), vec!["foo.graphql", "shared/*.graphql"]);
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_baseline() -> Result<()> {
+    let config = toml::toml! {
+        schema = "foo.graphql"
+        baseline = "baseline.json"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.baseline(), Some("baseline.json".as_ref()));
+
+    let config = toml::toml! {
+        baseline = "lints/baseline.json"
+
+        [projects.project1]
+        schema = "foo.graphql"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.baseline(), Some("lints/baseline.json".as_ref()));
+
+    let config = toml::toml! {
+        schema = "foo.graphql"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.baseline(), None);
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_max_file_size() -> Result<()> {
+    let config = toml::toml! {
+        schema = "foo.graphql"
+        max_file_size = 1024
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.max_file_size(), Some(1024));
+
+    let config = toml::toml! {
+        max_file_size = 2048
+
+        [projects.project1]
+        schema = "foo.graphql"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.max_file_size(), Some(2048));
+
+    let config = toml::toml! {
+        schema = "foo.graphql"
+    }
+    .try_into::<Config>()?;
+    assert_eq!(config.max_file_size(), None);
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_generated() -> Result<()> {
+    let config = toml::toml! {
+        schema = "**/*.graphql"
+
+        [generated]
+        marker = "DO NOT EDIT"
+        files = ["generated/**"]
+    }
+    .try_into::<Config>()?;
+    assert_eq!(
+        config.generated(),
+        Some(&GeneratedConfig {
+            marker: Some("DO NOT EDIT".to_owned()),
+            lines: None,
+            files: vec!["generated/**".to_owned()],
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_config_published() -> Result<()> {
+    let config = toml::toml! {
+        [projects.project1]
+        schema = "foo.graphql"
+        published = { schema = "https://example.com/graphql", allow = ["Query.users"] }
+
+        [projects.project2]
+        schema = "bar.graphql"
+        published = { schema = "published.graphql" }
+    }
+    .try_into::<Config>()?;
+
+    assert_eq!(
+        config.published(),
+        [
+            (
+                "project1",
+                &PublishedConfig {
+                    schema: "https://example.com/graphql".to_owned(),
+                    allow: vec!["Query.users".to_owned()],
+                }
+            ),
+            ("project2", &PublishedConfig { schema: "published.graphql".to_owned(), allow: vec![] }),
+        ]
+    );
+
+    let config = toml::toml! {
+        schema = "foo.graphql"
+    }
+    .try_into::<Config>()?;
+    assert!(config.published().is_empty());
+    Ok(())
+}
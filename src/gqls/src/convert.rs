@@ -1,8 +1,11 @@
+use gqls_ide::{FileId, LineIndex, PositionEncoding, Snapshot, SourceChange};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tower_lsp::jsonrpc;
 use tower_lsp::lsp_types::{self, NumberOrString};
 
-use crate::lsp::VfsExt;
+use crate::lsp::{AssistData, CodeLensData, DocumentFeaturesResponse, VfsExt, ORGANIZE_FIELDS};
 use crate::tokens;
 
 pub trait UrlExt {
@@ -32,12 +35,110 @@ impl PathExt for Path {
 }
 
 pub(crate) fn locations_to_goto_definition_response(
+    indices: &impl LineIndices,
     locations: &[gqls_ide::Location],
 ) -> Option<lsp_types::GotoDefinitionResponse> {
     match locations {
         [] => None,
-        [location] => Some(lsp_types::GotoDefinitionResponse::Scalar(location.convert())),
-        locations => Some(lsp_types::GotoDefinitionResponse::Array(locations.convert())),
+        [location] =>
+            Some(lsp_types::GotoDefinitionResponse::Scalar(location.convert_with(indices))),
+        locations =>
+            Some(lsp_types::GotoDefinitionResponse::Array(locations.convert_with(indices))),
+    }
+}
+
+/// The diagnostic of `file`, its labels may be in other files
+pub fn convert_diagnostic(
+    indices: &impl LineIndices,
+    file: FileId,
+    diagnostic: &gqls_ide::Diagnostic,
+) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: diagnostic.range.convert_in(&indices.file_index(file)),
+        severity: Some(diagnostic.severity.convert()),
+        message: diagnostic.message.clone(),
+        code: Some(match diagnostic.code {
+            gqls_ide::ErrorCode::Error(code) => NumberOrString::Number(code as i32),
+            gqls_ide::ErrorCode::Lint(_) | gqls_ide::ErrorCode::Info(_) =>
+                NumberOrString::String(diagnostic.code.to_string()),
+        }),
+        source: Some("gqls".to_owned()),
+        related_information: Some(diagnostic.labels.convert_with(indices)),
+        ..Default::default()
+    }
+}
+
+/// The position encoding negotiated with the client
+pub fn position_encoding_kind(encoding: PositionEncoding) -> lsp_types::PositionEncodingKind {
+    match encoding {
+        PositionEncoding::Utf8 => lsp_types::PositionEncodingKind::UTF8,
+        PositionEncoding::Utf16 => lsp_types::PositionEncodingKind::UTF16,
+        PositionEncoding::Utf32 => lsp_types::PositionEncodingKind::UTF32,
+    }
+}
+
+/// Pick the encoding the server works with most cheaply out of those the client supports,
+/// utf-16 (which every client supports) if it doesn't say
+pub fn negotiate_position_encoding(
+    capabilities: &lsp_types::ClientCapabilities,
+) -> PositionEncoding {
+    let supported = capabilities
+        .general
+        .as_ref()
+        .and_then(|general| general.position_encodings.as_deref())
+        .unwrap_or_default();
+    [PositionEncoding::Utf8, PositionEncoding::Utf32]
+        .into_iter()
+        .find(|&encoding| supported.contains(&position_encoding_kind(encoding)))
+        .unwrap_or(PositionEncoding::Utf16)
+}
+
+/// The line index of each file, which maps positions between the server, whose columns are
+/// bytes, and the client, whose columns are in the negotiated encoding
+pub trait LineIndices {
+    fn line_index(&self, file: FileId) -> Arc<LineIndex>;
+
+    fn encoding(&self) -> PositionEncoding;
+
+    fn file_index(&self, file: FileId) -> FileIndex {
+        FileIndex { index: self.line_index(file), encoding: self.encoding() }
+    }
+}
+
+/// The line indices of the files of a snapshot
+pub(crate) struct SnapshotIndices<'a> {
+    pub snapshot: &'a Snapshot,
+    pub encoding: PositionEncoding,
+}
+
+impl LineIndices for SnapshotIndices<'_> {
+    fn line_index(&self, file: FileId) -> Arc<LineIndex> {
+        self.snapshot.line_index(file)
+    }
+
+    fn encoding(&self) -> PositionEncoding {
+        self.encoding
+    }
+}
+
+/// The line index of a single file along with the encoding of the client
+#[derive(Debug, Clone)]
+pub struct FileIndex {
+    pub index: Arc<LineIndex>,
+    pub encoding: PositionEncoding,
+}
+
+impl FileIndex {
+    pub fn encode(&self, point: gqls_ide::Point) -> gqls_ide::Point {
+        self.index.encode(point, self.encoding)
+    }
+
+    pub fn decode(&self, point: gqls_ide::Point) -> gqls_ide::Point {
+        self.index.decode(point, self.encoding)
+    }
+
+    pub fn encoded_len(&self, range: std::ops::Range<usize>) -> usize {
+        self.index.encoded_len(range, self.encoding)
     }
 }
 
@@ -69,19 +170,37 @@ where
     }
 }
 
-impl Convert for gqls_ide::Diagnostic {
-    type Converted = lsp_types::Diagnostic;
+// Conversions of values with positions in a single file, using the index of that file
+pub trait ConvertIn {
+    type Converted;
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted;
+}
 
-    fn convert(&self) -> Self::Converted {
-        lsp_types::Diagnostic {
-            range: self.range.convert(),
-            severity: Some(self.severity.convert()),
-            message: self.message.clone(),
-            code: Some(NumberOrString::Number(self.code.code() as i32)),
-            source: Some("gqls".to_owned()),
-            related_information: Some(self.labels.convert()),
-            ..Default::default()
-        }
+impl<T> ConvertIn for [T]
+where
+    T: ConvertIn,
+{
+    type Converted = Vec<T::Converted>;
+
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        self.iter().map(|x| x.convert_in(index)).collect()
+    }
+}
+
+// Conversions of values that know which file(s) their positions are in
+pub trait ConvertWith {
+    type Converted;
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted;
+}
+
+impl<T> ConvertWith for [T]
+where
+    T: ConvertWith,
+{
+    type Converted = Vec<T::Converted>;
+
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        self.iter().map(|x| x.convert_with(indices)).collect()
     }
 }
 
@@ -91,91 +210,193 @@ impl Convert for gqls_ide::Severity {
     fn convert(&self) -> Self::Converted {
         match self {
             gqls_ide::Severity::Error => lsp_types::DiagnosticSeverity::ERROR,
+            gqls_ide::Severity::Warning => lsp_types::DiagnosticSeverity::WARNING,
+            gqls_ide::Severity::Info => lsp_types::DiagnosticSeverity::INFORMATION,
+            gqls_ide::Severity::Hint => lsp_types::DiagnosticSeverity::HINT,
         }
     }
 }
 
-impl Convert for gqls_ide::DiagnosticLabel {
+impl ConvertWith for gqls_ide::DiagnosticLabel {
     type Converted = lsp_types::DiagnosticRelatedInformation;
 
-    fn convert(&self) -> Self::Converted {
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
         lsp_types::DiagnosticRelatedInformation {
-            location: self.location.convert(),
+            location: self.location.convert_with(indices),
             message: self.message.to_string(),
         }
     }
 }
 
-impl Convert for lsp_types::Range {
+impl ConvertIn for lsp_types::Range {
     type Converted = gqls_ide::Range;
 
-    fn convert(&self) -> Self::Converted {
-        gqls_ide::Range { start: self.start.convert(), end: self.end.convert() }
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        gqls_ide::Range { start: self.start.convert_in(index), end: self.end.convert_in(index) }
     }
 }
 
-impl Convert for lsp_types::TextDocumentPositionParams {
+impl ConvertWith for lsp_types::TextDocumentPositionParams {
     type Converted = Result<gqls_ide::Position, jsonrpc::Error>;
 
-    fn convert(&self) -> Self::Converted {
-        Ok(gqls_ide::Position {
-            file: gqls_ide::VFS.read().path(&self.text_document.uri)?,
-            point: self.position.convert(),
-        })
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let file = gqls_ide::VFS.read().path(&self.text_document.uri)?;
+        let point = self.position.convert_in(&indices.file_index(file));
+        Ok(gqls_ide::Position { file, point })
     }
 }
 
-impl Convert for gqls_ide::FilePatches {
+impl ConvertWith for gqls_ide::FilePatches {
     type Converted = lsp_types::TextDocumentEdit;
 
-    fn convert(&self) -> Self::Converted {
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let index = indices.file_index(self.file);
         lsp_types::TextDocumentEdit {
             text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
                 uri: self.file.to_url(),
                 version: None,
             },
-            edits: self.patches.convert().into_iter().map(lsp_types::OneOf::Left).collect(),
+            edits: self
+                .patches
+                .convert_in(&index)
+                .into_iter()
+                .map(lsp_types::OneOf::Left)
+                .collect(),
         }
     }
 }
 
-impl Convert for gqls_ide::Patch {
-    type Converted = lsp_types::TextEdit;
+/// The edit that applies `change`. The edits of the documents that are open in the client carry
+/// the version (of `versions`) they were computed against, so the client rejects them if the
+/// document has changed since, the edits of other files are unversioned.
+pub(crate) fn workspace_edit(
+    indices: &impl LineIndices,
+    versions: &HashMap<FileId, i32>,
+    change: &SourceChange,
+) -> lsp_types::WorkspaceEdit {
+    let edits = change
+        .files
+        .iter()
+        .map(|patches| {
+            let mut edit = patches.convert_with(indices);
+            edit.text_document.version = versions.get(&patches.file).copied();
+            edit
+        })
+        .collect();
+    lsp_types::WorkspaceEdit {
+        document_changes: Some(lsp_types::DocumentChanges::Edits(edits)),
+        ..Default::default()
+    }
+}
+
+/// The client command that renames the name at a position, which isn't part of the protocol
+pub(crate) const RENAME_AT_COMMAND: &str = "gqls.renameAt";
+
+/// The code action of `assist`, an unresolved one carries `data` so it can be resolved later.
+/// The placeholder it introduces is only renamed if the client can run [`RENAME_AT_COMMAND`].
+pub(crate) fn convert_assist(
+    indices: &impl LineIndices,
+    versions: &HashMap<FileId, i32>,
+    assist: &gqls_ide::Assist,
+    data: AssistData,
+    rename_at: bool,
+) -> lsp_types::CodeAction {
+    // the position is in the text after the change, which there is no line index of yet, but the
+    // names to rename are placeholders that only consist of ascii. The command only comes along
+    // with the edit it follows, an unresolved action gets it once it is resolved.
+    let position = assist.change.as_ref().and(assist.rename_at).filter(|_| rename_at);
+    let command = position.map(|position| lsp_types::Command {
+        title: "Rename the placeholder".to_owned(),
+        command: RENAME_AT_COMMAND.to_owned(),
+        arguments: Some(vec![
+            serde_json::json!(position.file.to_url()),
+            serde_json::json!(lsp_types::Position::new(
+                position.point.row as u32,
+                position.point.column as u32
+            )),
+        ]),
+    });
+    lsp_types::CodeAction {
+        title: assist.label.clone(),
+        kind: Some(assist.id.1.convert()),
+        edit: assist.change.as_ref().map(|change| workspace_edit(indices, versions, change)),
+        command,
+        data: assist.change.is_none().then(|| serde_json::json!(data)),
+        ..Default::default()
+    }
+}
+
+impl Convert for gqls_ide::AssistKind {
+    type Converted = lsp_types::CodeActionKind;
 
     fn convert(&self) -> Self::Converted {
-        lsp_types::TextEdit { range: self.range.convert(), new_text: self.with.clone() }
+        match self {
+            gqls_ide::AssistKind::Organize => lsp_types::CodeActionKind::from(ORGANIZE_FIELDS),
+            gqls_ide::AssistKind::Extract => lsp_types::CodeActionKind::REFACTOR_EXTRACT,
+            gqls_ide::AssistKind::Inline => lsp_types::CodeActionKind::REFACTOR_INLINE,
+            gqls_ide::AssistKind::Rewrite => lsp_types::CodeActionKind::REFACTOR_REWRITE,
+        }
+    }
+}
+
+impl ConvertIn for gqls_ide::Patch {
+    type Converted = lsp_types::TextEdit;
+
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        lsp_types::TextEdit { range: self.range.convert_in(index), new_text: self.with.clone() }
     }
 }
 
-impl Convert for gqls_ide::Location {
+impl ConvertWith for gqls_ide::Location {
     type Converted = lsp_types::Location;
 
-    fn convert(&self) -> Self::Converted {
-        lsp_types::Location { range: self.range.convert(), uri: self.file.to_url() }
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let range = self.range.convert_in(&indices.file_index(self.file));
+        lsp_types::Location { range, uri: self.file.to_url() }
     }
 }
 
-impl Convert for gqls_ide::Range {
+/// The result of `gqls/typeInfo`, with the locations converted to those of the protocol
+impl ConvertWith for gqls_ide::TypeInfo {
+    type Converted = serde_json::Value;
+
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let mut info = serde_json::to_value(self).expect("type info is serializable");
+        info["locations"] = serde_json::json!(self.locations.convert_with(indices));
+        info["fieldLocations"] = self
+            .field_locations
+            .iter()
+            .map(|(name, location)| {
+                (name.clone(), serde_json::json!(location.convert_with(indices)))
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+        info
+    }
+}
+
+impl ConvertIn for gqls_ide::Range {
     type Converted = lsp_types::Range;
 
-    fn convert(&self) -> lsp_types::Range {
-        lsp_types::Range { start: self.start.convert(), end: self.end.convert() }
+    fn convert_in(&self, index: &FileIndex) -> lsp_types::Range {
+        lsp_types::Range { start: self.start.convert_in(index), end: self.end.convert_in(index) }
     }
 }
 
-impl Convert for lsp_types::Position {
+impl ConvertIn for lsp_types::Position {
     type Converted = gqls_ide::Point;
 
-    fn convert(&self) -> Self::Converted {
-        gqls_ide::Point::new(self.line as usize, self.character as usize)
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        index.decode(gqls_ide::Point::new(self.line as usize, self.character as usize))
     }
 }
 
-impl Convert for gqls_ide::Point {
+impl ConvertIn for gqls_ide::Point {
     type Converted = lsp_types::Position;
 
-    fn convert(&self) -> Self::Converted {
-        lsp_types::Position::new(self.row as u32, self.column as u32)
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        let point = index.encode(*self);
+        lsp_types::Position::new(point.row as u32, point.column as u32)
     }
 }
 
@@ -191,40 +412,150 @@ impl Convert for gqls_ide::SymbolKind {
     }
 }
 
-impl Convert for gqls_ide::DocumentSymbol {
+impl ConvertIn for gqls_ide::DocumentSymbol {
     type Converted = lsp_types::DocumentSymbol;
 
-    fn convert(&self) -> Self::Converted {
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
         #[allow(deprecated)]
         lsp_types::DocumentSymbol {
             name: self.name.to_string(),
             detail: self.detail.clone(),
             kind: self.kind.convert(),
-            range: self.range.convert(),
-            selection_range: self.range.convert(),
-            children: Some(self.children.convert()),
+            range: self.range.convert_in(index),
+            selection_range: self.range.convert_in(index),
+            children: Some(self.children.convert_in(index)),
             tags: None,
             deprecated: None,
         }
     }
 }
 
-impl Convert for gqls_ide::WorkspaceSymbol {
+impl ConvertIn for gqls_ide::DocumentLink {
+    type Converted = lsp_types::DocumentLink;
+
+    fn convert_in(&self, index: &FileIndex) -> Self::Converted {
+        lsp_types::DocumentLink {
+            range: self.range.convert_in(index),
+            target: match &self.target {
+                // urls that can't be parsed aren't linked
+                gqls_ide::DocumentLinkTarget::Url(url) => lsp_types::Url::parse(url).ok(),
+                gqls_ide::DocumentLinkTarget::File(file) => Some(file.to_url()),
+            },
+            tooltip: None,
+            data: None,
+        }
+    }
+}
+
+impl ConvertWith for gqls_ide::WorkspaceSymbol {
     type Converted = lsp_types::SymbolInformation;
 
-    fn convert(&self) -> Self::Converted {
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
         #[allow(deprecated)]
         lsp_types::SymbolInformation {
             name: self.name.to_string(),
             kind: self.kind.convert(),
             tags: None,
             deprecated: None,
-            location: self.location.convert(),
+            location: self.location.convert_with(indices),
             container_name: None,
         }
     }
 }
 
+impl ConvertWith for gqls_ide::HierarchyItem {
+    type Converted = lsp_types::CallHierarchyItem;
+
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let index = indices.file_index(self.file);
+        lsp_types::CallHierarchyItem {
+            name: self.name.to_string(),
+            kind: self.kind.convert(),
+            tags: None,
+            detail: self.detail.clone(),
+            uri: self.file.to_url(),
+            range: self.range.convert_in(&index),
+            selection_range: self.selection_range.convert_in(&index),
+            data: None,
+        }
+    }
+}
+
+impl Convert for gqls_ide::Hover {
+    type Converted = lsp_types::Hover;
+
+    fn convert(&self) -> Self::Converted {
+        lsp_types::Hover {
+            contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+                kind: lsp_types::MarkupKind::Markdown,
+                value: self.contents.clone(),
+            }),
+            range: None,
+        }
+    }
+}
+
+impl Convert for gqls_ide::SignatureHelp {
+    type Converted = lsp_types::SignatureHelp;
+
+    fn convert(&self) -> Self::Converted {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|param| lsp_types::ParameterInformation {
+                label: lsp_types::ParameterLabel::Simple(param.label.clone()),
+                documentation: param.documentation.clone().map(lsp_types::Documentation::String),
+            })
+            .collect();
+        lsp_types::SignatureHelp {
+            signatures: vec![lsp_types::SignatureInformation {
+                label: self.label.clone(),
+                documentation: None,
+                parameters: Some(parameters),
+                active_parameter: self.active_parameter.map(|idx| idx as u32),
+            }],
+            active_signature: Some(0),
+            active_parameter: self.active_parameter.map(|idx| idx as u32),
+        }
+    }
+}
+
+impl ConvertWith for gqls_ide::CodeLens {
+    type Converted = lsp_types::CodeLens;
+
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
+        let index = indices.file_index(self.position.file);
+        let data = CodeLensData {
+            text_document_position: lsp_types::TextDocumentPositionParams {
+                text_document: lsp_types::TextDocumentIdentifier {
+                    uri: self.position.file.to_url(),
+                },
+                position: self.position.point.convert_in(&index),
+            },
+            kind: self.kind.to_string(),
+        };
+        lsp_types::CodeLens {
+            range: self.range.convert_in(&index),
+            command: None,
+            data: Some(serde_json::to_value(data).expect("code lens data is serializable")),
+        }
+    }
+}
+
+impl Convert for gqls_ide::DocumentFeatures {
+    type Converted = DocumentFeaturesResponse;
+
+    fn convert(&self) -> Self::Converted {
+        DocumentFeaturesResponse {
+            kind: self.kind.name().to_owned(),
+            features: gqls_ide::Feature::ALL
+                .into_iter()
+                .map(|feature| (feature.name().to_owned(), self.is_enabled(feature)))
+                .collect(),
+        }
+    }
+}
+
 impl Convert for gqls_ide::CompletionItem {
     type Converted = lsp_types::CompletionItem;
 
@@ -232,7 +563,27 @@ impl Convert for gqls_ide::CompletionItem {
         lsp_types::CompletionItem {
             label: self.label.clone(),
             kind: Some(self.kind.convert()),
-            // TODO
+            detail: self.detail.clone(),
+            insert_text: self.insert_text.clone(),
+            filter_text: self.filter_text.clone(),
+            sort_text: self.sort_text.clone(),
+            tags: self.deprecated.then(|| vec![lsp_types::CompletionItemTag::DEPRECATED]),
+            insert_text_format: self
+                .insert_text
+                .is_some()
+                .then(|| lsp_types::InsertTextFormat::SNIPPET),
+            // multiline templates are already indented
+            insert_text_mode: self
+                .insert_text
+                .as_ref()
+                .filter(|text| text.contains('\n'))
+                .map(|_| lsp_types::InsertTextMode::AS_IS),
+            documentation: self.documentation.as_ref().map(|documentation| {
+                lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: documentation.clone(),
+                })
+            }),
             ..Default::default()
         }
     }
@@ -253,6 +604,10 @@ impl Convert for gqls_ide::CompletionItemKind {
             gqls_ide::CompletionItemKind::Union => lsp_types::CompletionItemKind::CLASS,
             gqls_ide::CompletionItemKind::DirectiveLocation =>
                 lsp_types::CompletionItemKind::MODULE,
+            gqls_ide::CompletionItemKind::EnumValue => lsp_types::CompletionItemKind::ENUM_MEMBER,
+            gqls_ide::CompletionItemKind::InputField => lsp_types::CompletionItemKind::FIELD,
+            gqls_ide::CompletionItemKind::Argument => lsp_types::CompletionItemKind::PROPERTY,
+            gqls_ide::CompletionItemKind::Description => lsp_types::CompletionItemKind::SNIPPET,
         }
     }
 }
@@ -281,15 +636,22 @@ impl Convert for gqls_ide::SemanticTokenKind {
     }
 }
 
-impl Convert for gqls_ide::RenameError {
+impl ConvertWith for gqls_ide::RenameError {
     type Converted = jsonrpc::Error;
 
-    fn convert(&self) -> Self::Converted {
+    fn convert_with(&self, indices: &impl LineIndices) -> Self::Converted {
         jsonrpc::Error {
             // FIXME change to `RequestFailed` when available: https://github.com/microsoft/language-server-protocol/issues/1341
             code: jsonrpc::ErrorCode::InvalidParams,
             message: self.to_string(),
-            data: None,
+            data: match self {
+                gqls_ide::RenameError::Conflict { locations, .. } =>
+                    Some(serde_json::json!({ "locations": locations.convert_with(indices) })),
+                _ => None,
+            },
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
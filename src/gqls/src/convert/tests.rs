@@ -0,0 +1,127 @@
+use gqls_ide::tree_sitter::{self, Point};
+use gqls_ide::{
+    point, range, FileId, LineIndex, PositionEncoding, SemanticToken, SemanticTokenKind
+};
+use std::sync::Arc;
+use tower_lsp::lsp_types;
+
+use super::*;
+use crate::tokens;
+
+// `é` is a single utf-16 code unit (but 2 bytes), `😀` is 2 code units (4 bytes)
+const TEXT: &str = "\"é😀\" type Foo { bar: Int }";
+
+struct SingleFile(Arc<LineIndex>, PositionEncoding);
+
+impl LineIndices for SingleFile {
+    fn line_index(&self, _: FileId) -> Arc<LineIndex> {
+        Arc::clone(&self.0)
+    }
+
+    fn encoding(&self) -> PositionEncoding {
+        self.1
+    }
+}
+
+fn file_index(encoding: PositionEncoding) -> FileIndex {
+    FileIndex { index: Arc::new(LineIndex::new(TEXT)), encoding }
+}
+
+fn byte_range(s: &str) -> tree_sitter::Range {
+    let start_byte = TEXT.find(s).unwrap();
+    let end_byte = start_byte + s.len();
+    let (start_point, end_point) = (Point::new(0, start_byte), Point::new(0, end_byte));
+    tree_sitter::Range { start_byte, end_byte, start_point, end_point }
+}
+
+#[test]
+fn test_convert_positions() {
+    // `Foo` starts at byte 14, which is the 11th utf-16 code unit and the 10th character
+    for (encoding, column) in
+        [(PositionEncoding::Utf8, 14), (PositionEncoding::Utf16, 11), (PositionEncoding::Utf32, 10)]
+    {
+        let index = file_index(encoding);
+        let start = lsp_types::Position::new(0, column);
+        let end = lsp_types::Position::new(0, column + 3);
+        assert_eq!(range!(0:14..0:17).convert_in(&index), lsp_types::Range::new(start, end));
+        assert_eq!(start.convert_in(&index), point!(0:14));
+    }
+}
+
+#[test]
+fn test_convert_diagnostic_to_utf16() {
+    let indices = SingleFile(Arc::new(LineIndex::new(TEXT)), PositionEncoding::Utf16);
+    let file = gqls_ide::VFS.write().intern("test.graphql");
+    let diagnostic = gqls_ide::diagnostic!(E0001 @ range!(0:14..0:17));
+    let converted = convert_diagnostic(&indices, file, &diagnostic);
+    assert_eq!(converted.range.start, lsp_types::Position::new(0, 11));
+    assert_eq!(converted.range.end, lsp_types::Position::new(0, 14));
+}
+
+#[test]
+fn test_convert_semantic_tokens() {
+    let tokens = [
+        SemanticToken { range: byte_range("\"é😀\""), kind: SemanticTokenKind::String },
+        SemanticToken { range: byte_range("Foo"), kind: SemanticTokenKind::Object },
+    ];
+    let converted = tokens::convert(&file_index(PositionEncoding::Utf16), &tokens);
+    assert_eq!((converted[0].delta_start, converted[0].length), (0, 5));
+    assert_eq!((converted[1].delta_start, converted[1].length), (11, 3));
+
+    let converted = tokens::convert(&file_index(PositionEncoding::Utf8), &tokens);
+    assert_eq!((converted[0].delta_start, converted[0].length), (0, 8));
+    assert_eq!((converted[1].delta_start, converted[1].length), (14, 3));
+}
+
+#[test]
+fn test_negotiate_position_encoding() {
+    let capabilities =
+        |encodings: Option<Vec<lsp_types::PositionEncodingKind>>| lsp_types::ClientCapabilities {
+            general: Some(lsp_types::GeneralClientCapabilities {
+                position_encodings: encodings,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+    let negotiate = |encodings| negotiate_position_encoding(&capabilities(encodings));
+    assert_eq!(negotiate(None), PositionEncoding::Utf16);
+    assert_eq!(negotiate(Some(vec![])), PositionEncoding::Utf16);
+    assert_eq!(
+        negotiate(Some(vec![lsp_types::PositionEncodingKind::UTF16])),
+        PositionEncoding::Utf16
+    );
+    assert_eq!(
+        negotiate(Some(vec![
+            lsp_types::PositionEncodingKind::UTF32,
+            lsp_types::PositionEncodingKind::UTF16
+        ])),
+        PositionEncoding::Utf32
+    );
+    assert_eq!(
+        negotiate(Some(vec![
+            lsp_types::PositionEncodingKind::UTF16,
+            lsp_types::PositionEncodingKind::UTF8
+        ])),
+        PositionEncoding::Utf8
+    );
+    assert_eq!(negotiate_position_encoding(&Default::default()), PositionEncoding::Utf16);
+}
+
+#[test]
+fn test_workspace_edit_versions_open_documents() {
+    let indices = SingleFile(Arc::new(LineIndex::new(TEXT)), PositionEncoding::Utf16);
+    let (open, closed) = {
+        let mut vfs = gqls_ide::VFS.write();
+        (vfs.intern("/open.graphql"), vfs.intern("/closed.graphql"))
+    };
+    let mut change = SourceChange::default();
+    change.insert(open, gqls_ide::Patch::new(range!(0:14..0:17), "Bar".to_owned()));
+    change.insert(closed, gqls_ide::Patch::new(range!(0:14..0:17), "Bar".to_owned()));
+    let versions = HashMap::from([(open, 3)]);
+    let edits = match workspace_edit(&indices, &versions, &change).document_changes {
+        Some(lsp_types::DocumentChanges::Edits(edits)) => edits,
+        changes => panic!("expected document edits, found {changes:?}"),
+    };
+    let versions = edits.iter().map(|edit| edit.text_document.version).collect::<Vec<_>>();
+    assert_eq!(versions, [Some(3), None]);
+}
@@ -0,0 +1,85 @@
+//! `gqls diff`, reports the changes between two versions of a schema
+
+use anyhow::{bail, Context};
+use gqls_diff::Criticality;
+use gqls_ide::{Change, Changeset, Ide, Schema};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::DEFAULT_PROJECT;
+use crate::format::graphql_files;
+use crate::lsp;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffArgs {
+    /// a schema file (sdl or an introspection result) or a directory of graphql files
+    pub old: PathBuf,
+    pub new: PathBuf,
+}
+
+impl DiffArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut paths = vec![];
+        for arg in args {
+            match arg.as_str() {
+                arg if arg.starts_with('-') => bail!("unexpected argument `{arg}`"),
+                path => paths.push(PathBuf::from(path)),
+            }
+        }
+        match <[PathBuf; 2]>::try_from(paths) {
+            Ok([old, new]) => Ok(Self { old, new }),
+            Err(_) => bail!("expected the paths of the old and new schema"),
+        }
+    }
+}
+
+/// Write the changes from the old to the new schema of `args` (relative paths are relative to
+/// `root`) to `out`, returning whether none of them are breaking
+pub fn diff(root: &Path, args: &DiffArgs, out: &mut impl Write) -> anyhow::Result<bool> {
+    let old = load_schema(&root.join(&args.old))?;
+    let new = load_schema(&root.join(&args.new))?;
+    let changes = gqls_diff::diff(&old, &new);
+    for change in &changes {
+        writeln!(out, "{}: {}: {}", change.criticality, change.coordinate, change.message)?;
+    }
+    Ok(changes.iter().all(|change| change.criticality != Criticality::Breaking))
+}
+
+/// The schema of the files at `path` as a project of its own
+pub(crate) fn load_schema(path: &Path) -> anyhow::Result<Schema> {
+    let mut files = vec![];
+    for path in graphql_files(&[path.to_path_buf()])? {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        if let Some(text) = lsp::file_content(&path, text)? {
+            files.push((path, text));
+        }
+    }
+    if files.is_empty() {
+        bail!("no graphql files found in `{}`", path.display());
+    }
+    Ok(build_schema(files))
+}
+
+/// The schema of a project consisting of `files`, which must not be empty
+pub(crate) fn build_schema(files: Vec<(PathBuf, String)>) -> Schema {
+    let mut ide = Ide::default();
+    let files = files
+        .into_iter()
+        .map(|(path, text)| (ide.intern_path(path), text))
+        .collect::<Vec<_>>();
+    let project = ide.intern_project(DEFAULT_PROJECT.to_owned());
+    let project_files = files.iter().map(|&(file, _)| file).collect::<HashSet<_>>();
+    let first = files[0].0;
+    let mut changeset =
+        Changeset::default().with_projects(HashMap::from([(project, project_files)]));
+    for (file, text) in files {
+        changeset = changeset.with_change(Change::set(file, text));
+    }
+    ide.load(changeset);
+    ide.snapshot().schema(first)
+}
+
+#[cfg(test)]
+mod tests;
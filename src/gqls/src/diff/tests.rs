@@ -0,0 +1,45 @@
+use expect_test::expect;
+use std::path::PathBuf;
+
+use super::{diff, DiffArgs};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/diff/fixtures")
+}
+
+#[test]
+fn test_parse_diff_args() -> anyhow::Result<()> {
+    assert_eq!(
+        DiffArgs::parse(vec!["old.graphql".to_owned(), "new".to_owned()])?,
+        DiffArgs { old: PathBuf::from("old.graphql"), new: PathBuf::from("new") }
+    );
+    assert!(DiffArgs::parse(vec!["old.graphql".to_owned()]).is_err());
+    assert!(DiffArgs::parse(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]).is_err());
+    assert!(DiffArgs::parse(vec!["--json".to_owned()]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_diff() -> anyhow::Result<()> {
+    let mut out = vec![];
+    let args = DiffArgs { old: PathBuf::from("old.graphql"), new: PathBuf::from("new") };
+    assert!(!diff(&fixtures(), &args, &mut out)?);
+    expect![[r#"
+        breaking: Query.users: field `Query.users` was removed
+        breaking: User.email: field `User.email` was removed
+        safe: Role: type `Role` was added
+        safe: User.name: field `User.name` changed type from `String` to `String!`
+        safe: User.role: field `User.role` was added
+    "#]]
+    .assert_eq(&String::from_utf8(out)?);
+    Ok(())
+}
+
+#[test]
+fn test_diff_without_breaking_changes() -> anyhow::Result<()> {
+    let args = DiffArgs { old: PathBuf::from("old.graphql"), new: PathBuf::from("old.graphql") };
+    let mut out = vec![];
+    assert!(diff(&fixtures(), &args, &mut out)?);
+    assert!(out.is_empty());
+    Ok(())
+}
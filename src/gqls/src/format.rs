@@ -0,0 +1,98 @@
+//! `gqls format`, formats graphql files in place with the formatter of the language server
+
+use crate::lsp;
+use anyhow::{anyhow, bail, Context};
+use gqls_ide::DocumentKind;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Url, WorkspaceFolder};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FormatArgs {
+    /// report the files that aren't formatted rather than rewriting them
+    pub check: bool,
+    /// directories are searched for graphql files, defaults to the root
+    pub paths: Vec<PathBuf>,
+}
+
+impl FormatArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut check = false;
+        let mut paths = vec![];
+        for arg in args {
+            match arg.as_str() {
+                "--check" => check = true,
+                arg if arg.starts_with('-') => bail!("unexpected argument `{arg}`"),
+                path => paths.push(PathBuf::from(path)),
+            }
+        }
+        Ok(Self { check, paths })
+    }
+}
+
+/// Format the files of `args` (relative paths are relative to `root`), returning whether every
+/// file could be formatted, or with `--check`, whether every file is already formatted
+pub fn format(root: &Path, args: &FormatArgs, out: &mut impl Write) -> anyhow::Result<bool> {
+    let paths = if args.paths.is_empty() {
+        vec![root.to_path_buf()]
+    } else {
+        args.paths.iter().map(|path| root.join(path)).collect()
+    };
+    let uri = Url::from_directory_path(root)
+        .map_err(|()| anyhow!("expected an absolute path, found `{}`", root.display()))?;
+    let workspace = WorkspaceFolder { uri, name: String::new() };
+    let (generated, globs) = lsp::discover_generated(vec![workspace])?;
+    let mut success = true;
+    for path in graphql_files(&paths)? {
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        // generated files are left as the tool that generates them wrote them
+        if generated.is_marked(&text) || lsp::is_matched(&globs, &path) {
+            continue;
+        }
+        let display = path.strip_prefix(root).unwrap_or(&path).display();
+        match gqls_ide::format(&text, &Default::default()) {
+            Some(formatted) if formatted == text => {}
+            Some(_) if args.check => {
+                writeln!(out, "{display}: not formatted")?;
+                success = false;
+            }
+            Some(formatted) => std::fs::write(&path, formatted)
+                .with_context(|| format!("failed to write `{}`", path.display()))?,
+            None => {
+                writeln!(out, "{display}: skipped as it has syntax errors")?;
+                success = false;
+            }
+        }
+    }
+    Ok(success)
+}
+
+/// The files of `paths`, the graphql files of directories are in sorted order
+pub(crate) fn graphql_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        let mut dir_files = vec![];
+        for entry in walkdir::WalkDir::new(path)
+            .into_iter()
+            .filter_entry(|entry| !entry.path().ends_with(".git"))
+        {
+            let entry = entry?;
+            if entry.file_type().is_file()
+                && DocumentKind::of(entry.path()) == DocumentKind::Graphql
+            {
+                dir_files.push(entry.into_path());
+            }
+        }
+        dir_files.sort();
+        files.extend(dir_files);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,80 @@
+use expect_test::expect;
+use std::path::PathBuf;
+
+use super::{format, FormatArgs};
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/format/fixtures")
+}
+
+#[test]
+fn test_parse_format_args() -> anyhow::Result<()> {
+    assert_eq!(FormatArgs::parse(vec![])?, FormatArgs::default());
+    assert_eq!(
+        FormatArgs::parse(vec!["--check".to_owned(), "foo.graphql".to_owned()])?,
+        FormatArgs { check: true, paths: vec![PathBuf::from("foo.graphql")] }
+    );
+    assert!(FormatArgs::parse(vec!["--fix".to_owned()]).is_err());
+    Ok(())
+}
+
+// `generated.graphql` isn't formatted either, but it is skipped as it is generated
+#[test]
+fn test_format_check() -> anyhow::Result<()> {
+    let mut out = vec![];
+    let args = FormatArgs { check: true, paths: vec![] };
+    assert!(!format(&fixtures(), &args, &mut out)?);
+    expect![[r#"
+        invalid.graphql: skipped as it has syntax errors
+        unformatted.graphql: not formatted
+    "#]]
+    .assert_eq(&String::from_utf8(out)?);
+
+    let args = FormatArgs { check: true, paths: vec![PathBuf::from("formatted.graphql")] };
+    assert!(format(&fixtures(), &args, &mut vec![])?);
+    Ok(())
+}
+
+#[test]
+fn test_format_in_place() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("gqls-format-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("unformatted.graphql");
+    std::fs::copy(fixtures().join("unformatted.graphql"), &path)?;
+
+    let mut out = vec![];
+    assert!(format(&dir, &FormatArgs::default(), &mut out)?);
+    assert!(out.is_empty());
+    assert_eq!(std::fs::read_to_string(&path)?, "type Bar {\n  baz: Int\n}\n");
+    assert!(format(&dir, &FormatArgs { check: true, paths: vec![] }, &mut out)?);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn test_format_skips_configured_generated_files() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("gqls-format-generated-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("generated"))?;
+    let config = r#"
+        schema = "**/*.graphql"
+
+        [generated]
+        marker = "DO NOT EDIT"
+        files = ["generated/**"]
+    "#;
+    std::fs::write(dir.join(".graphqlrc.toml"), config)?;
+    std::fs::write(dir.join("marked.graphql"), "# DO NOT EDIT\ntype Foo {a: Int}\n")?;
+    std::fs::write(dir.join("generated/schema.graphql"), "type Bar {a: Int}\n")?;
+    std::fs::write(dir.join("unformatted.graphql"), "type Baz {a: Int}\n")?;
+
+    let mut out = vec![];
+    assert!(!format(&dir, &FormatArgs { check: true, paths: vec![] }, &mut out)?);
+    expect![[r#"
+        unformatted.graphql: not formatted
+    "#]]
+    .assert_eq(&String::from_utf8(out)?);
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
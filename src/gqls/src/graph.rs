@@ -0,0 +1,259 @@
+//! `gqls graph` and `gqls/schemaGraph`, export the dependencies between the types of a schema
+//! as a graph in the DOT language (e.g. for graphviz) or as json for other visualization tools
+
+use anyhow::{anyhow, bail, Context};
+use gqls_ide::{Ide, SchemaDependency, SchemaType, Snapshot};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tower_lsp::lsp_types::{Url, WorkspaceFolder};
+use vfs::FileId;
+
+use crate::lsp;
+
+/// The builtin scalars are used by almost every type, so they would only clutter the graph
+const BUILTIN_SCALARS: [&str; 5] = ["Boolean", "Float", "ID", "Int", "String"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// a `digraph` in the DOT language
+    Dot,
+    /// a `{ nodes, edges }` object
+    Json,
+}
+
+impl Default for GraphFormat {
+    fn default() -> Self {
+        GraphFormat::Dot
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(GraphFormat::Dot),
+            "json" => Ok(GraphFormat::Json),
+            _ => bail!("unknown graph format `{s}`, expected `dot` or `json`"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GraphArgs {
+    pub format: GraphFormat,
+    /// where to write the graph (relative to the root), defaults to the output
+    pub output: Option<PathBuf>,
+}
+
+impl GraphArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let mut graph_args = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    let name = args.next().context("expected a format after `--format`")?;
+                    graph_args.format = name.parse()?;
+                }
+                "--output" | "-o" => {
+                    let path = args.next().context("expected a path after `--output`")?;
+                    graph_args.output = Some(PathBuf::from(path));
+                }
+                arg => bail!("unexpected argument `{arg}`"),
+            }
+        }
+        Ok(graph_args)
+    }
+}
+
+/// Write the dependency graph of the schemas of the projects of the workspace at `root` to
+/// `out`, or to the output of `args` if given. Types of the same name in different projects
+/// are the same node.
+pub fn graph(root: &Path, args: &GraphArgs, out: &mut impl Write) -> anyhow::Result<bool> {
+    let uri = Url::from_directory_path(root)
+        .map_err(|()| anyhow!("expected an absolute path, found `{}`", root.display()))?;
+    let workspace = WorkspaceFolder { uri, name: String::new() };
+
+    let mut ide = Ide::default();
+    let summary = lsp::load_workspaces(&mut ide, vec![workspace])?;
+    let snapshot = ide.snapshot();
+    let mut graph = SchemaGraph::default();
+    for &project in &summary.projects {
+        if let Some(&file) = snapshot.files_of(&HashSet::from([project])).first() {
+            graph.extend(&snapshot, file);
+        }
+    }
+    // the projects are unordered
+    graph.nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    graph.edges.sort_by(|a, b| a.from.cmp(&b.from));
+
+    let graph = graph.render(args.format);
+    match &args.output {
+        Some(path) => {
+            let path = root.join(path);
+            std::fs::write(&path, graph)
+                .with_context(|| format!("failed to write `{}`", path.display()))?;
+            writeln!(out, "wrote the graph to `{}`", path.display())?;
+        }
+        None => write!(out, "{graph}")?,
+    }
+    Ok(true)
+}
+
+/// The types and directives of a schema and the references between them, excluding the builtin
+/// scalars and the types that don't resolve
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemaGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GraphNode {
+    /// the name of the type, or of the directive with its `@`
+    pub name: String,
+    /// the `__TypeKind` of the type, e.g. `OBJECT`, or `DIRECTIVE`
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: GraphEdgeKind,
+    /// the field of a field edge, e.g. `user` or `user(id)` if it is the type of an argument,
+    /// or the argument of a directive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphEdgeKind {
+    /// the type of a field, input field or argument of a field or directive
+    Field,
+    /// an object or interface implements an interface
+    Implements,
+    /// a union has an object as a member
+    Member,
+}
+
+impl SchemaGraph {
+    /// The graph of the schema of the project(s) of `file`
+    pub fn new(snapshot: &Snapshot, file: FileId) -> Self {
+        let mut graph = Self::default();
+        graph.extend(snapshot, file);
+        graph
+    }
+
+    /// Add the types and directives of the project(s) of `file` and the references between them
+    /// that aren't in the graph yet
+    pub fn extend(&mut self, snapshot: &Snapshot, file: FileId) {
+        let schema = snapshot.schema(file);
+        let dependencies = snapshot.schema_dependencies(file);
+        let mut outgoing = HashMap::<&str, Vec<&SchemaDependency>>::new();
+        for dependency in &dependencies {
+            outgoing.entry(&dependency.from).or_default().push(dependency);
+        }
+
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for ty in &schema.types {
+            if is_builtin(&ty.name) {
+                continue;
+            }
+            nodes.push(GraphNode { name: ty.name.clone(), kind: ty.kind.name().to_owned() });
+            edges.extend(type_edges(ty, outgoing.remove(ty.name.as_str()).unwrap_or_default()));
+        }
+        // the directives only have a node if they refer to a type
+        for directive in &schema.directives {
+            let name = format!("@{}", directive.name);
+            if let Some(dependencies) = outgoing.remove(name.as_str()) {
+                edges.extend(dependencies.into_iter().filter_map(dependency_edge));
+                nodes.push(GraphNode { name, kind: "DIRECTIVE".to_owned() });
+            }
+        }
+
+        let mut seen = self.nodes.iter().cloned().collect::<HashSet<_>>();
+        self.nodes.extend(nodes.into_iter().filter(|node| seen.insert(node.clone())));
+        let mut seen = self.edges.iter().cloned().collect::<HashSet<_>>();
+        self.edges.extend(edges.into_iter().filter(|edge| seen.insert(edge.clone())));
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Json =>
+                serde_json::to_string_pretty(self).expect("the graph is serializable") + "\n",
+        }
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph schema {\n");
+        for node in &self.nodes {
+            let shape = match node.kind.as_str() {
+                "INTERFACE" => "shape=box, style=rounded",
+                "UNION" => "shape=hexagon",
+                "ENUM" => "shape=octagon",
+                "INPUT_OBJECT" => "shape=component",
+                "SCALAR" => "shape=ellipse",
+                "DIRECTIVE" => "shape=note",
+                _ => "shape=box",
+            };
+            let _ = writeln!(dot, "    \"{}\" [{}];", node.name, shape);
+        }
+        for edge in &self.edges {
+            let attributes = match (edge.kind, &edge.label) {
+                (GraphEdgeKind::Field, Some(label)) => format!(" [label=\"{label}\"]"),
+                (GraphEdgeKind::Field, None) => String::new(),
+                (GraphEdgeKind::Implements, _) => " [style=dashed, arrowhead=empty]".to_owned(),
+                (GraphEdgeKind::Member, _) => " [style=dotted]".to_owned(),
+            };
+            let _ = writeln!(dot, "    \"{}\" -> \"{}\"{};", edge.from, edge.to, attributes);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// the references of `ty` followed by the interfaces it implements
+fn type_edges(ty: &SchemaType, dependencies: Vec<&SchemaDependency>) -> Vec<GraphEdge> {
+    let mut edges = dependencies.into_iter().filter_map(dependency_edge).collect::<Vec<_>>();
+    edges.extend(ty.interfaces.iter().map(|interface| GraphEdge {
+        from: ty.name.clone(),
+        to: interface.clone(),
+        kind: GraphEdgeKind::Implements,
+        label: None,
+    }));
+    edges
+}
+
+// only the members of a union are references without a field or argument
+fn dependency_edge(dependency: &SchemaDependency) -> Option<GraphEdge> {
+    if is_builtin(&dependency.to) {
+        return None;
+    }
+    let kind = match dependency.label {
+        Some(_) => GraphEdgeKind::Field,
+        None => GraphEdgeKind::Member,
+    };
+    Some(GraphEdge {
+        from: dependency.from.clone(),
+        to: dependency.to.clone(),
+        kind,
+        label: dependency.label.clone(),
+    })
+}
+
+fn is_builtin(name: &str) -> bool {
+    BUILTIN_SCALARS.contains(&name)
+}
+
+#[cfg(test)]
+mod tests;
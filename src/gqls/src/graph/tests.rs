@@ -0,0 +1,85 @@
+use expect_test::expect;
+use serde_json::json;
+use std::path::PathBuf;
+
+use super::{graph, GraphArgs, GraphFormat, SchemaGraph};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/graph/fixtures").join(name)
+}
+
+fn render(format: GraphFormat) -> anyhow::Result<String> {
+    let mut out = vec![];
+    let args = GraphArgs { format, output: None };
+    assert!(graph(&fixture_path("workspace"), &args, &mut out)?);
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn test_parse_graph_args() -> anyhow::Result<()> {
+    assert_eq!(GraphArgs::parse(vec![])?, GraphArgs::default());
+    assert_eq!(
+        GraphArgs::parse(vec![
+            "--format".to_owned(),
+            "json".to_owned(),
+            "-o".to_owned(),
+            "graph.json".to_owned(),
+        ])?,
+        GraphArgs { format: GraphFormat::Json, output: Some(PathBuf::from("graph.json")) }
+    );
+    assert!(GraphArgs::parse(vec!["--format".to_owned(), "svg".to_owned()]).is_err());
+    assert!(GraphArgs::parse(vec!["--output".to_owned()]).is_err());
+    assert!(GraphArgs::parse(vec!["--depth".to_owned()]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_graph_dot() -> anyhow::Result<()> {
+    // the builtin scalars, unresolved types and directives without references are left out
+    expect![[r#"
+        digraph schema {
+            "@auth" [shape=note];
+            "Date" [shape=ellipse];
+            "Filter" [shape=component];
+            "Node" [shape=box, style=rounded];
+            "Query" [shape=box];
+            "Result" [shape=hexagon];
+            "Role" [shape=octagon];
+            "User" [shape=box];
+            "@auth" -> "Role" [label="role"];
+            "Filter" -> "Role" [label="role"];
+            "Query" -> "Filter" [label="user(filter)"];
+            "Query" -> "User" [label="user"];
+            "Query" -> "Node" [label="node"];
+            "Query" -> "Result" [label="search"];
+            "Result" -> "User" [style=dotted];
+            "User" -> "Role" [label="role"];
+            "User" -> "User" [label="friends"];
+            "User" -> "Date" [label="joined"];
+            "User" -> "Node" [style=dashed, arrowhead=empty];
+        }
+    "#]]
+    .assert_eq(&render(GraphFormat::Dot)?);
+    Ok(())
+}
+
+#[test]
+fn test_graph_json() -> anyhow::Result<()> {
+    let graph = serde_json::from_str::<SchemaGraph>(&render(GraphFormat::Json)?)?;
+    assert_eq!(graph.nodes.len(), 8);
+    assert_eq!(graph.edges.len(), 11);
+
+    let graph = serde_json::to_value(graph)?;
+    assert_eq!(graph["nodes"][0], json!({ "name": "@auth", "kind": "DIRECTIVE" }));
+    assert_eq!(graph["nodes"][2], json!({ "name": "Filter", "kind": "INPUT_OBJECT" }));
+    assert_eq!(
+        graph["edges"][0],
+        json!({ "from": "@auth", "to": "Role", "kind": "field", "label": "role" })
+    );
+    assert_eq!(
+        graph["edges"][2],
+        json!({ "from": "Query", "to": "Filter", "kind": "field", "label": "user(filter)" })
+    );
+    assert_eq!(graph["edges"][10], json!({ "from": "User", "to": "Node", "kind": "implements" }));
+    Ok(())
+}
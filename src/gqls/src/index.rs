@@ -0,0 +1,299 @@
+//! `gqls index`, exports the definitions, references and hovers of the workspace at the given
+//! root as an [LSIF] dump, which code search tools such as Sourcegraph consume for precise
+//! navigation
+//!
+//! [LSIF]: https://microsoft.github.io/language-server-protocol/specifications/lsif/0.4.0/specification/
+
+use anyhow::{anyhow, bail, Context};
+use gqls_ide::{Ide, Location, Position, PositionEncoding, Snapshot};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Url, WorkspaceFolder};
+use vfs::FileId;
+
+use crate::convert::FileIndex;
+use crate::lsp;
+
+const LSIF_VERSION: &str = "0.4.3";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IndexArgs {
+    /// where to write the dump (relative to the root), defaults to the output
+    pub output: Option<PathBuf>,
+}
+
+impl IndexArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let mut output = None;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--output" | "-o" => {
+                    let path = args.next().context("expected a path after `--output`")?;
+                    output = Some(PathBuf::from(path));
+                }
+                arg => bail!("unexpected argument `{arg}`"),
+            }
+        }
+        Ok(Self { output })
+    }
+}
+
+/// Write the LSIF dump of the workspace at `root` to `out`, or to the output of `args` if given
+pub fn index(root: &Path, args: &IndexArgs, out: &mut impl Write) -> anyhow::Result<bool> {
+    let uri = Url::from_directory_path(root)
+        .map_err(|()| anyhow!("expected an absolute path, found `{}`", root.display()))?;
+    let workspace = WorkspaceFolder { uri: uri.clone(), name: String::new() };
+
+    let mut ide = Ide::default();
+    let summary = lsp::load_workspaces(&mut ide, vec![workspace])?;
+    let snapshot = ide.snapshot();
+    // the builtins and preludes are virtual, so there is nothing for a code search tool to show
+    let files = snapshot
+        .files_of(&summary.projects)
+        .into_iter()
+        .filter(|file| file.exists())
+        .collect::<Vec<_>>();
+
+    match &args.output {
+        Some(path) => {
+            let path = root.join(path);
+            let file = std::fs::File::create(&path)
+                .with_context(|| format!("failed to create `{}`", path.display()))?;
+            let mut writer = BufWriter::new(file);
+            Indexer::new(&snapshot, &mut writer).index(&uri, &files)?;
+            writer.flush()?;
+            writeln!(out, "indexed {} files into `{}`", files.len(), path.display())?;
+        }
+        None => Indexer::new(&snapshot, out).index(&uri, &files)?,
+    }
+    Ok(true)
+}
+
+/// The definitions that share their references, e.g. a type along with its extensions
+#[derive(Debug)]
+struct Symbol {
+    /// e.g. `User` or `User.id`
+    moniker: String,
+    definitions: Vec<Location>,
+    references: Vec<Location>,
+}
+
+struct Indexer<'a, W> {
+    snapshot: &'a Snapshot,
+    out: &'a mut W,
+    next_id: u64,
+}
+
+impl<'a, W: Write> Indexer<'a, W> {
+    fn new(snapshot: &'a Snapshot, out: &'a mut W) -> Self {
+        Self { snapshot, out, next_id: 1 }
+    }
+
+    fn index(mut self, root: &Url, files: &[FileId]) -> anyhow::Result<()> {
+        self.vertex(
+            "metaData",
+            json!({
+                "version": LSIF_VERSION,
+                "projectRoot": root,
+                "positionEncoding": "utf-16",
+                "toolInfo": { "name": "gqls", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        )?;
+        let project = self.vertex("project", json!({ "kind": "graphql" }))?;
+
+        let symbols = self.symbols(files);
+        let mut documents = HashMap::new();
+        let mut ranges = HashMap::new();
+        for &file in files {
+            let uri = Url::from_file_path(file)
+                .map_err(|()| anyhow!("expected an absolute path, found `{}`", file.display()))?;
+            let document =
+                self.vertex("document", json!({ "uri": uri, "languageId": "graphql" }))?;
+            documents.insert(file, document);
+
+            let index = FileIndex {
+                index: self.snapshot.line_index(file),
+                encoding: PositionEncoding::Utf16,
+            };
+            let mut contained = vec![];
+            let locations = symbols
+                .iter()
+                .flat_map(|symbol| symbol.definitions.iter().chain(&symbol.references))
+                .filter(|location| location.file == file);
+            for &location in locations {
+                if ranges.contains_key(&location) {
+                    continue;
+                }
+                let start = index.encode(location.range.start);
+                let end = index.encode(location.range.end);
+                let range = self.vertex(
+                    "range",
+                    json!({
+                        "start": { "line": start.row, "character": start.column },
+                        "end": { "line": end.row, "character": end.column },
+                    }),
+                )?;
+                ranges.insert(location, range);
+                contained.push(range);
+            }
+            if !contained.is_empty() {
+                self.edges("contains", document, &contained, json!({}))?;
+            }
+        }
+        let document_ids = files.iter().map(|file| documents[file]).collect::<Vec<_>>();
+        if !document_ids.is_empty() {
+            self.edges("contains", project, &document_ids, json!({}))?;
+        }
+
+        let mut linked = HashSet::new();
+        for symbol in &symbols {
+            let result_set = self.vertex("resultSet", json!({}))?;
+            for location in symbol.definitions.iter().chain(&symbol.references) {
+                // a range can only be part of a single result set
+                if linked.insert(*location) {
+                    self.edge("next", ranges[location], result_set)?;
+                }
+            }
+
+            let moniker = self.vertex(
+                "moniker",
+                json!({ "kind": "export", "scheme": "graphql", "identifier": symbol.moniker }),
+            )?;
+            self.edge("moniker", result_set, moniker)?;
+
+            let definition = symbol.definitions[0];
+            let hover = self.snapshot.hover(Position::new(definition.file, definition.range.start));
+            if let Some(hover) = hover {
+                let contents = json!({ "kind": "markdown", "value": hover.contents });
+                let hover =
+                    self.vertex("hoverResult", json!({ "result": { "contents": contents } }))?;
+                self.edge("textDocument/hover", result_set, hover)?;
+            }
+
+            let definitions = self.vertex("definitionResult", json!({}))?;
+            self.edge("textDocument/definition", result_set, definitions)?;
+            for (file, ranges) in by_file(&symbol.definitions, &ranges) {
+                let data = json!({ "document": documents[&file] });
+                self.edges("item", definitions, &ranges, data)?;
+            }
+
+            let references = self.vertex("referenceResult", json!({}))?;
+            self.edge("textDocument/references", result_set, references)?;
+            for (file, ranges) in by_file(&symbol.definitions, &ranges) {
+                let data = json!({ "document": documents[&file], "property": "definitions" });
+                self.edges("item", references, &ranges, data)?;
+            }
+            let uses = symbol
+                .references
+                .iter()
+                .filter(|location| !symbol.definitions.contains(location))
+                .copied()
+                .collect::<Vec<_>>();
+            for (file, ranges) in by_file(&uses, &ranges) {
+                let data = json!({ "document": documents[&file], "property": "references" });
+                self.edges("item", references, &ranges, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The types, directives and fields defined in `files`, along with their references within
+    /// the files
+    fn symbols(&self, files: &[FileId]) -> Vec<Symbol> {
+        let mut symbols = Vec::<Symbol>::new();
+        let mut by_references = HashMap::<Vec<Location>, usize>::new();
+        let mut push = |moniker: String, definition: Location| {
+            let position = Position::new(definition.file, definition.range.start);
+            let mut references = self
+                .snapshot
+                .find_references(position)
+                .into_iter()
+                .filter(|location| files.contains(&location.file))
+                .collect::<Vec<_>>();
+            references.sort();
+            references.dedup();
+            if references.is_empty() {
+                references.push(definition);
+            }
+            match by_references.get(&references) {
+                Some(&idx) => symbols[idx].definitions.push(definition),
+                None => {
+                    by_references.insert(references.clone(), symbols.len());
+                    symbols.push(Symbol { moniker, definitions: vec![definition], references });
+                }
+            }
+        };
+
+        for &file in files {
+            for symbol in self.snapshot.document_symbols(file) {
+                if symbol.name.is_missing() {
+                    continue;
+                }
+                push(symbol.name.to_string(), Location::new(file, symbol.name.range));
+                for field in symbol.children.iter().filter(|field| !field.name.is_missing()) {
+                    let moniker = format!("{}.{}", symbol.name, field.name);
+                    push(moniker, Location::new(file, field.name.range));
+                }
+            }
+        }
+        symbols
+    }
+
+    fn vertex(&mut self, label: &str, data: Value) -> anyhow::Result<u64> {
+        self.emit("vertex", label, data)
+    }
+
+    /// A 1:1 edge
+    fn edge(&mut self, label: &str, out_v: u64, in_v: u64) -> anyhow::Result<()> {
+        self.emit("edge", label, json!({ "outV": out_v, "inV": in_v }))?;
+        Ok(())
+    }
+
+    /// A 1:n edge, `data` is the document of the ranges of an `item` edge (and the property of
+    /// those of a reference result)
+    fn edges(
+        &mut self,
+        label: &str,
+        out_v: u64,
+        in_vs: &[u64],
+        mut data: Value,
+    ) -> anyhow::Result<()> {
+        data["outV"] = json!(out_v);
+        data["inVs"] = json!(in_vs);
+        self.emit("edge", label, data)?;
+        Ok(())
+    }
+
+    fn emit(&mut self, kind: &str, label: &str, mut data: Value) -> anyhow::Result<u64> {
+        let id = self.next_id();
+        data["id"] = json!(id);
+        data["type"] = json!(kind);
+        data["label"] = json!(label);
+        writeln!(self.out, "{data}")?;
+        Ok(id)
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+/// The ids of the ranges of `locations` grouped by their file
+fn by_file(
+    locations: &[Location],
+    ranges: &HashMap<Location, u64>,
+) -> BTreeMap<FileId, Vec<u64>> {
+    let mut by_file = BTreeMap::<FileId, Vec<u64>>::new();
+    for location in locations {
+        by_file.entry(location.file).or_default().push(ranges[location]);
+    }
+    by_file
+}
+
+#[cfg(test)]
+mod tests;
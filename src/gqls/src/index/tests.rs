@@ -0,0 +1,97 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::{index, IndexArgs};
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/index/fixtures").join(name)
+}
+
+fn dump(name: &str) -> anyhow::Result<Vec<Value>> {
+    let mut out = vec![];
+    assert!(index(&fixture_path(name), &IndexArgs::default(), &mut out)?);
+    let out = String::from_utf8(out)?;
+    Ok(out.lines().map(serde_json::from_str).collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn test_parse_index_args() -> anyhow::Result<()> {
+    assert_eq!(IndexArgs::parse(vec![])?, IndexArgs::default());
+    assert_eq!(
+        IndexArgs::parse(vec!["--output".to_owned(), "dump.lsif".to_owned()])?,
+        IndexArgs { output: Some(PathBuf::from("dump.lsif")) }
+    );
+    assert!(IndexArgs::parse(vec!["--output".to_owned()]).is_err());
+    assert!(IndexArgs::parse(vec!["--scip".to_owned()]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_index_edges_refer_to_earlier_vertices() -> anyhow::Result<()> {
+    let dump = dump("workspace")?;
+    assert_eq!(dump[0]["label"], "metaData");
+    assert_eq!(dump[0]["positionEncoding"], "utf-16");
+
+    let mut seen = HashMap::new();
+    for element in &dump {
+        let id = element["id"].as_u64().unwrap();
+        assert!(seen.insert(id, element["type"].clone()).is_none(), "duplicate id {id}");
+        if element["type"] == "edge" {
+            let targets = match &element["inVs"] {
+                Value::Array(targets) => targets.clone(),
+                _ => vec![element["inV"].clone()],
+            };
+            for target in targets.iter().chain([&element["outV"]]) {
+                let target = target.as_u64().unwrap();
+                assert_eq!(seen.get(&target), Some(&Value::from("vertex")), "{element}");
+            }
+        }
+    }
+
+    let documents = dump.iter().filter(|element| element["label"] == "document").count();
+    assert_eq!(documents, 2);
+    Ok(())
+}
+
+#[test]
+fn test_index_definitions_and_references() -> anyhow::Result<()> {
+    let dump = dump("workspace")?;
+    let edge = |label: &str, from: &Value| {
+        dump.iter().find(|element| element["label"] == label && &element["outV"] == from).unwrap()
+    };
+    // the number of ranges of each property of the reference result of the moniker
+    let references = |identifier: &str| {
+        let moniker = dump
+            .iter()
+            .find(|element| element["label"] == "moniker" && element["identifier"] == identifier)
+            .unwrap_or_else(|| panic!("missing moniker `{identifier}`"));
+        let result_set = &dump
+            .iter()
+            .find(|element| element["label"] == "moniker" && element["inV"] == moniker["id"])
+            .unwrap()["outV"];
+        let result = &edge("textDocument/references", result_set)["inV"];
+        let mut counts = HashMap::<String, usize>::new();
+        for item in dump.iter().filter(|e| e["label"] == "item" && &e["outV"] == result) {
+            let property = item["property"].as_str().unwrap().to_owned();
+            *counts.entry(property).or_default() += item["inVs"].as_array().unwrap().len();
+        }
+        counts
+    };
+
+    let mut monikers = dump
+        .iter()
+        .filter(|element| element["label"] == "moniker")
+        .map(|element| element["identifier"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    monikers.sort_unstable();
+    assert_eq!(monikers, ["Query", "Query.user", "User", "User.friends", "User.id", "User.name"]);
+
+    // the definition and the extension of `User` share their result set
+    assert_eq!(
+        references("User"),
+        HashMap::from([("definitions".to_owned(), 2), ("references".to_owned(), 2)])
+    );
+    assert_eq!(references("Query.user"), HashMap::from([("definitions".to_owned(), 1)]));
+    Ok(())
+}
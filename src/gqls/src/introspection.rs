@@ -0,0 +1,485 @@
+//! Conversion between a project schema and the standard introspection result format
+//! (i.e. the `data` of the introspection query that tooling such as `buildClientSchema` expects)
+
+use anyhow::{anyhow, Context};
+use gqls_ide::{
+    Schema, SchemaDirective, SchemaField, SchemaInputValue, SchemaType, SchemaTypeKind, TypeRef
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) fn to_introspection_json(schema: &Schema) -> Value {
+    let root = |name: &Option<String>| name.as_ref().map(|name| json!({ "name": name }));
+    json!({
+        "__schema": {
+            "queryType": root(&schema.query_type),
+            "mutationType": root(&schema.mutation_type),
+            "subscriptionType": root(&schema.subscription_type),
+            "types": schema.types.iter().map(ty).collect::<Vec<_>>(),
+            "directives": schema.directives.iter().map(directive).collect::<Vec<_>>(),
+        }
+    })
+}
+
+fn ty(ty: &SchemaType) -> Value {
+    let SchemaType {
+        kind,
+        name,
+        description,
+        fields,
+        input_fields,
+        interfaces,
+        possible_types,
+        enum_values,
+        ..
+    } = ty;
+    let has_fields = matches!(kind, SchemaTypeKind::Object | SchemaTypeKind::Interface);
+    let is_abstract = matches!(kind, SchemaTypeKind::Interface | SchemaTypeKind::Union);
+    let type_refs = |of_kind, names: &[String]| {
+        names
+            .iter()
+            .map(|name| type_ref(&TypeRef::Named(Some(of_kind), name.clone())))
+            .collect::<Vec<_>>()
+    };
+    json!({
+        "kind": kind.name(),
+        "name": name,
+        "description": description,
+        "fields": has_fields.then(|| fields.iter().map(field).collect::<Vec<_>>()),
+        "inputFields": (*kind == SchemaTypeKind::InputObject)
+            .then(|| input_fields.iter().map(input_value).collect::<Vec<_>>()),
+        "interfaces": has_fields.then(|| type_refs(SchemaTypeKind::Interface, interfaces)),
+        "enumValues": (*kind == SchemaTypeKind::Enum).then(|| {
+            enum_values
+                .iter()
+                .map(|value| {
+                    json!({
+                        "name": value.name,
+                        "description": value.description,
+                        "isDeprecated": value.is_deprecated,
+                        "deprecationReason": null,
+                    })
+                })
+                .collect::<Vec<_>>()
+        }),
+        "possibleTypes": is_abstract.then(|| type_refs(SchemaTypeKind::Object, possible_types)),
+    })
+}
+
+fn field(field: &SchemaField) -> Value {
+    json!({
+        "name": field.name,
+        "description": field.description,
+        "args": field.args.iter().map(input_value).collect::<Vec<_>>(),
+        "type": type_ref(&field.ty),
+        "isDeprecated": field.is_deprecated,
+        "deprecationReason": null,
+    })
+}
+
+fn input_value(value: &SchemaInputValue) -> Value {
+    json!({
+        "name": value.name,
+        "description": value.description,
+        "type": type_ref(&value.ty),
+        "defaultValue": value.default_value,
+    })
+}
+
+fn directive(directive: &SchemaDirective) -> Value {
+    json!({
+        "name": directive.name,
+        "description": null,
+        "locations": directive.locations,
+        "args": directive.args.iter().map(input_value).collect::<Vec<_>>(),
+    })
+}
+
+fn type_ref(ty: &TypeRef) -> Value {
+    match ty {
+        TypeRef::Named(kind, name) =>
+            json!({ "kind": kind.map(SchemaTypeKind::name), "name": name, "ofType": null }),
+        TypeRef::List(ty) => json!({ "kind": "LIST", "name": null, "ofType": type_ref(ty) }),
+        TypeRef::NonNull(ty) => json!({ "kind": "NON_NULL", "name": null, "ofType": type_ref(ty) }),
+    }
+}
+
+/// The standard introspection query, its result is accepted by [`from_introspection_json`]
+pub(crate) const INTROSPECTION_QUERY: &str = "
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+    directives {
+      name
+      description
+      locations
+      args { ...InputValue }
+    }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) { name description }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}
+";
+
+/// How long an endpoint has to respond to the introspection query
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run the introspection query against the graphql endpoint at `url` and return the response
+pub(crate) fn fetch(url: &str) -> anyhow::Result<String> {
+    let body = json!({ "operationName": "IntrospectionQuery", "query": INTROSPECTION_QUERY });
+    let response = ureq::post(url)
+        .timeout(FETCH_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&body.to_string())
+        .with_context(|| format!("failed to introspect `{url}`"))?;
+    Ok(response.into_string()?)
+}
+
+/// Introspection results are included in projects as `.json` files, although only the ones that
+/// have the shape of one are loaded (see [`from_introspection_json`])
+pub(crate) fn is_introspection_file(path: &Path) -> bool {
+    path.extension() == Some("json".as_ref())
+}
+
+// types and directives that are implicitly defined by every schema
+const BUILTINS: [&str; 9] =
+    ["Boolean", "Float", "ID", "Int", "String", "deprecated", "include", "skip", "specifiedBy"];
+
+/// Convert an introspection result into sdl so it can be used like any other schema file.
+/// Both the bare `{ "__schema": .. }` and the full `{ "data": { "__schema": .. } }` are accepted,
+/// any other json is not an introspection result at all and is `None`.
+///
+/// Each name is placed at the line and column of the same name in the json where possible, so
+/// the ranges of the definitions (e.g. of a goto definition) point into the `.json` file.
+pub(crate) fn from_introspection_json(json: &str) -> anyhow::Result<Option<String>> {
+    let value = serde_json::from_str::<Value>(json)?;
+    let schema = match introspection_schema(&value) {
+        Some(schema) => schema,
+        None => return Ok(None),
+    };
+
+    let mut sdl = SdlWriter::new(json);
+    writeln!(sdl, "# generated from an introspection result, do not edit")?;
+    for (i, ty) in array(schema, "types")?.iter().enumerate() {
+        let name = str(ty, "name")?;
+        if name.starts_with("__") || BUILTINS.contains(&name) {
+            continue;
+        }
+        let path = format!("types.{i}");
+        writeln!(sdl)?;
+        write_description(&mut sdl, ty, "")?;
+        match str(ty, "kind")? {
+            "SCALAR" => {
+                write!(sdl, "scalar ")?;
+                sdl.name(&path, "", name)?;
+            }
+            "UNION" => {
+                let members = array(ty, "possibleTypes")?
+                    .iter()
+                    .map(|member| str(member, "name"))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                write!(sdl, "union ")?;
+                sdl.name(&path, "", name)?;
+                write!(sdl, " = {}", members.join(" | "))?;
+            }
+            "ENUM" => {
+                write!(sdl, "enum ")?;
+                sdl.name(&path, "", name)?;
+                write_block(&mut sdl, ty, &path, "enumValues", |sdl, path, value| {
+                    sdl.name(path, "", str(value, "name")?)?;
+                    Ok(())
+                })?;
+            }
+            "INPUT_OBJECT" => {
+                write!(sdl, "input ")?;
+                sdl.name(&path, "", name)?;
+                write_block(&mut sdl, ty, &path, "inputFields", |sdl, path, field| {
+                    sdl.name(path, "", str(field, "name")?)?;
+                    write!(sdl, "{}", input_type_sdl(field)?)?;
+                    Ok(())
+                })?;
+            }
+            kind @ ("OBJECT" | "INTERFACE") => {
+                write!(sdl, "{} ", if kind == "OBJECT" { "type" } else { "interface" })?;
+                sdl.name(&path, "", name)?;
+                let interfaces = ty
+                    .get("interfaces")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|interface| str(interface, "name"))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                if !interfaces.is_empty() {
+                    write!(sdl, " implements {}", interfaces.join(" & "))?;
+                }
+                write_block(&mut sdl, ty, &path, "fields", |sdl, path, field| {
+                    sdl.name(path, "", str(field, "name")?)?;
+                    write!(sdl, "{}: {}", args_sdl(field)?, type_sdl(&field["type"])?)?;
+                    Ok(())
+                })?;
+            }
+            kind => return Err(anyhow!("unknown type kind `{kind}` of type `{name}`")),
+        }
+        writeln!(sdl)?;
+    }
+
+    let directives = schema.get("directives").and_then(Value::as_array).into_iter().flatten();
+    for (i, directive) in directives.enumerate() {
+        let name = str(directive, "name")?;
+        if BUILTINS.contains(&name) {
+            continue;
+        }
+        let locations = array(directive, "locations")?
+            .iter()
+            .map(|location| location.as_str().context("expected a directive location"))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        writeln!(sdl)?;
+        write_description(&mut sdl, directive, "")?;
+        write!(sdl, "directive ")?;
+        sdl.name(&format!("directives.{i}"), "@", name)?;
+        writeln!(sdl, "{} on {}", args_sdl(directive)?, locations.join(" | "))?;
+    }
+    Ok(Some(sdl.sdl))
+}
+
+/// The `__schema` of `value` if it has the shape of an introspection result, rather than being
+/// any other json that happens to be included in a project
+fn introspection_schema(value: &Value) -> Option<&Value> {
+    let schema = value.get("data").unwrap_or(value).get("__schema")?;
+    let types = schema.get("types")?.as_array()?;
+    types
+        .iter()
+        .all(|ty| ty.get("kind").map_or(false, Value::is_string) && ty.get("name").is_some())
+        .then(|| schema)
+}
+
+/// Writes the sdl of an introspection result, see [`SdlWriter::name`]
+struct SdlWriter {
+    positions: HashMap<String, (usize, usize)>,
+    sdl: String,
+    /// the line and column of the end of the sdl
+    point: (usize, usize),
+}
+
+impl SdlWriter {
+    fn new(json: &str) -> Self {
+        Self { positions: name_positions(json), sdl: String::new(), point: (0, 0) }
+    }
+
+    /// Write `prefix` and `name` so that the name is at the same position as the name of the
+    /// object at `path` in the json. The definitions are written in the order of the json, but
+    /// if the sdl has already gone past the position anyway the name is written where it is.
+    fn name(&mut self, path: &str, prefix: &str, name: &str) -> fmt::Result {
+        if let Some(&(row, column)) = self.positions.get(path) {
+            if row > self.point.0 {
+                // the indentation before the name would only trail the line
+                let len = self.sdl.trim_end_matches(' ').len();
+                self.point.1 -= self.sdl.len() - len;
+                self.sdl.truncate(len);
+                self.write_str(&"\n".repeat(row - self.point.0))?;
+            }
+            let column = column.saturating_sub(prefix.len());
+            if row == self.point.0 && column > self.point.1 {
+                self.write_str(&" ".repeat(column - self.point.1))?;
+            }
+        }
+        self.write_str(prefix)?;
+        self.write_str(name)
+    }
+}
+
+impl fmt::Write for SdlWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.point = advance(self.point, s);
+        self.sdl.push_str(s);
+        Ok(())
+    }
+}
+
+/// The line and column of the `"name"` of each object of the json by the path of the object
+/// relative to the `__schema` (e.g. `types.0.fields.1`), as serde_json keeps no positions
+fn name_positions(json: &str) -> HashMap<String, (usize, usize)> {
+    enum Segment<'a> {
+        Key(&'a str),
+        Index(usize),
+    }
+
+    let mut names = vec![];
+    let mut path = vec![];
+    let mut expects_key = false;
+    let mut chars = json.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => {
+                path.push(Segment::Key(""));
+                expects_key = true;
+            }
+            '[' => path.push(Segment::Index(0)),
+            '}' | ']' => {
+                path.pop();
+            }
+            ',' => match path.last_mut() {
+                Some(Segment::Index(index)) => *index += 1,
+                Some(Segment::Key(_)) => expects_key = true,
+                None => {}
+            },
+            '"' => {
+                let (mut end, mut escaped) = (json.len(), false);
+                for (j, c) in chars.by_ref() {
+                    match c {
+                        '"' if !escaped => {
+                            end = j;
+                            break;
+                        }
+                        '\\' => escaped = !escaped,
+                        _ => escaped = false,
+                    }
+                }
+                if expects_key {
+                    if let Some(Segment::Key(key)) = path.last_mut() {
+                        *key = &json[i + 1..end];
+                    }
+                    expects_key = false;
+                } else if let Some(Segment::Key("name")) = path.last() {
+                    let segments = path[..path.len() - 1].iter().map(|segment| match segment {
+                        Segment::Key(key) => key.to_string(),
+                        Segment::Index(index) => index.to_string(),
+                    });
+                    names.push((segments.collect::<Vec<_>>().join("."), i + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut positions = HashMap::new();
+    let (mut offset, mut point) = (0, (0, 0));
+    for (path, name_offset) in names {
+        point = advance(point, &json[offset..name_offset]);
+        offset = name_offset;
+        if let Some((_, path)) = path.split_once("__schema.") {
+            positions.insert(path.to_owned(), point);
+        }
+    }
+    positions
+}
+
+/// The line and column after `text` if it starts at `point`, the columns are in bytes
+fn advance((row, column): (usize, usize), text: &str) -> (usize, usize) {
+    match text.rfind('\n') {
+        Some(i) => (row + text.matches('\n').count(), text.len() - i - 1),
+        None => (row, column + text.len()),
+    }
+}
+
+fn str<'a>(value: &'a Value, key: &str) -> anyhow::Result<&'a str> {
+    let field = value.get(key).and_then(Value::as_str);
+    field.with_context(|| format!("expected string field `{key}`"))
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> anyhow::Result<&'a Vec<Value>> {
+    let field = value.get(key).and_then(Value::as_array);
+    field.with_context(|| format!("expected array field `{key}`"))
+}
+
+fn write_description(sdl: &mut impl Write, value: &Value, indent: &str) -> anyhow::Result<()> {
+    if let Some(description) = value.get("description").and_then(Value::as_str) {
+        // json string escapes are valid graphql string escapes
+        writeln!(sdl, "{indent}{}", serde_json::to_string(description)?)?;
+    }
+    Ok(())
+}
+
+/// Write the braced items of the `key` array of the type at `path`, which are left out entirely
+/// rather than written as an empty `{}` as that is not valid sdl
+fn write_block(
+    sdl: &mut SdlWriter,
+    ty: &Value,
+    path: &str,
+    key: &str,
+    mut write_item: impl FnMut(&mut SdlWriter, &str, &Value) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let items = array(ty, key)?;
+    if items.is_empty() {
+        return Ok(());
+    }
+    writeln!(sdl, " {{")?;
+    for (i, item) in items.iter().enumerate() {
+        write_description(sdl, item, "  ")?;
+        write!(sdl, "  ")?;
+        write_item(sdl, &format!("{path}.{key}.{i}"), item)?;
+        writeln!(sdl)?;
+    }
+    write!(sdl, "}}")?;
+    Ok(())
+}
+
+fn args_sdl(value: &Value) -> anyhow::Result<String> {
+    let args = match value.get("args").and_then(Value::as_array) {
+        Some(args) if !args.is_empty() => args,
+        _ => return Ok(String::new()),
+    };
+    let args = args
+        .iter()
+        .map(|arg| {
+            let mut sdl = String::new();
+            write_description(&mut sdl, arg, "")?;
+            write!(sdl, "{}{}", str(arg, "name")?, input_type_sdl(arg)?)?;
+            Ok(sdl.replace('\n', " "))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(format!("({})", args.join(", ")))
+}
+
+/// The type and default value of an input value, everything but its name
+fn input_type_sdl(value: &Value) -> anyhow::Result<String> {
+    let mut sdl = format!(": {}", type_sdl(&value["type"])?);
+    if let Some(default) = value.get("defaultValue").and_then(Value::as_str) {
+        write!(sdl, " = {default}")?;
+    }
+    Ok(sdl)
+}
+
+fn type_sdl(ty: &Value) -> anyhow::Result<String> {
+    match str(ty, "kind")? {
+        "NON_NULL" => Ok(format!("{}!", type_sdl(&ty["ofType"])?)),
+        "LIST" => Ok(format!("[{}]", type_sdl(&ty["ofType"])?)),
+        _ => str(ty, "name").map(ToOwned::to_owned),
+    }
+}
+
+#[cfg(test)]
+mod tests;
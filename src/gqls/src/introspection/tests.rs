@@ -0,0 +1,368 @@
+use gqls_ide::{
+    Schema, SchemaDirective, SchemaEnumValue, SchemaField, SchemaInputValue, SchemaType, SchemaTypeKind, TypeRef
+};
+use expect_test::expect;
+use serde_json::{json, Value};
+
+use super::{from_introspection_json, to_introspection_json};
+
+fn named(kind: SchemaTypeKind, name: &str) -> TypeRef {
+    TypeRef::Named(Some(kind), name.to_owned())
+}
+
+fn ty(kind: SchemaTypeKind, name: &str) -> SchemaType {
+    SchemaType {
+        kind,
+        name: name.to_owned(),
+        description: None,
+        directives: vec![],
+        fields: vec![],
+        input_fields: vec![],
+        interfaces: vec![],
+        possible_types: vec![],
+        enum_values: vec![],
+    }
+}
+
+#[test]
+fn test_introspection_json() {
+    let arg = SchemaInputValue {
+        name: "id".to_owned(),
+        description: Some("the id".to_owned()),
+        ty: TypeRef::NonNull(Box::new(named(SchemaTypeKind::Scalar, "ID"))),
+        default_value: None,
+        directives: vec![],
+    };
+    let schema = Schema {
+        query_type: Some("Query".to_owned()),
+        mutation_type: None,
+        subscription_type: None,
+        types: vec![
+            SchemaType {
+                description: Some("the root".to_owned()),
+                fields: vec![SchemaField {
+                    name: "foo".to_owned(),
+                    description: Some("the foo".to_owned()),
+                    args: vec![arg],
+                    ty: TypeRef::List(Box::new(named(SchemaTypeKind::Union, "U"))),
+                    is_deprecated: true,
+                    directives: vec![],
+                }],
+                ..ty(SchemaTypeKind::Object, "Query")
+            },
+            SchemaType {
+                possible_types: vec!["Query".to_owned()],
+                ..ty(SchemaTypeKind::Union, "U")
+            },
+            SchemaType {
+                enum_values: vec![
+                    SchemaEnumValue {
+                        name: "A".to_owned(),
+                        description: None,
+                        is_deprecated: false,
+                    },
+                    SchemaEnumValue {
+                        name: "B".to_owned(),
+                        description: Some("the b".to_owned()),
+                        is_deprecated: true,
+                    },
+                ],
+                ..ty(SchemaTypeKind::Enum, "E")
+            },
+        ],
+        directives: vec![SchemaDirective {
+            name: "d".to_owned(),
+            locations: vec!["OBJECT"],
+            args: vec![SchemaInputValue {
+                name: "a".to_owned(),
+                description: None,
+                ty: TypeRef::Named(None, "Unresolved".to_owned()),
+                default_value: Some("1".to_owned()),
+                directives: vec![],
+            }],
+        }],
+    };
+
+    assert_eq!(
+        to_introspection_json(&schema),
+        json!({
+            "__schema": {
+                "queryType": { "name": "Query" },
+                "mutationType": null,
+                "subscriptionType": null,
+                "types": [
+                    {
+                        "kind": "OBJECT",
+                        "name": "Query",
+                        "description": "the root",
+                        "fields": [{
+                            "name": "foo",
+                            "description": "the foo",
+                            "args": [{
+                                "name": "id",
+                                "description": "the id",
+                                "type": {
+                                    "kind": "NON_NULL",
+                                    "name": null,
+                                    "ofType": { "kind": "SCALAR", "name": "ID", "ofType": null },
+                                },
+                                "defaultValue": null,
+                            }],
+                            "type": {
+                                "kind": "LIST",
+                                "name": null,
+                                "ofType": { "kind": "UNION", "name": "U", "ofType": null },
+                            },
+                            "isDeprecated": true,
+                            "deprecationReason": null,
+                        }],
+                        "inputFields": null,
+                        "interfaces": [],
+                        "enumValues": null,
+                        "possibleTypes": null,
+                    },
+                    {
+                        "kind": "UNION",
+                        "name": "U",
+                        "description": null,
+                        "fields": null,
+                        "inputFields": null,
+                        "interfaces": null,
+                        "enumValues": null,
+                        "possibleTypes": [{ "kind": "OBJECT", "name": "Query", "ofType": null }],
+                    },
+                    {
+                        "kind": "ENUM",
+                        "name": "E",
+                        "description": null,
+                        "fields": null,
+                        "inputFields": null,
+                        "interfaces": null,
+                        "enumValues": [
+                            {
+                                "name": "A",
+                                "description": null,
+                                "isDeprecated": false,
+                                "deprecationReason": null,
+                            },
+                            {
+                                "name": "B",
+                                "description": "the b",
+                                "isDeprecated": true,
+                                "deprecationReason": null,
+                            },
+                        ],
+                        "possibleTypes": null,
+                    },
+                ],
+                "directives": [{
+                    "name": "d",
+                    "description": null,
+                    "locations": ["OBJECT"],
+                    "args": [{
+                        "name": "a",
+                        "description": null,
+                        "type": { "kind": null, "name": "Unresolved", "ofType": null },
+                        "defaultValue": "1",
+                    }],
+                }],
+            }
+        })
+    );
+}
+
+#[test]
+fn test_from_introspection_json() -> anyhow::Result<()> {
+    let named = |kind: &str, name: &str| json!({ "kind": kind, "name": name, "ofType": null });
+    let wrap = |kind: &str, ty: Value| json!({ "kind": kind, "name": null, "ofType": ty });
+    let introspection = json!({
+        "data": {
+            "__schema": {
+                "queryType": { "name": "Query" },
+                "types": [
+                    {
+                        "kind": "OBJECT",
+                        "name": "Query",
+                        "description": "the root",
+                        "fields": [{
+                            "name": "node",
+                            "description": null,
+                            "args": [{
+                                "name": "id",
+                                "description": "the id",
+                                "type": wrap("NON_NULL", named("SCALAR", "ID")),
+                                "defaultValue": null,
+                            }],
+                            "type": named("INTERFACE", "Node"),
+                        }],
+                        "interfaces": [],
+                    },
+                    {
+                        "kind": "INTERFACE",
+                        "name": "Node",
+                        "fields": [{
+                            "name": "id",
+                            "args": [],
+                            "type": wrap("NON_NULL", named("SCALAR", "ID")),
+                        }],
+                        "interfaces": [],
+                    },
+                    {
+                        "kind": "OBJECT",
+                        "name": "Foo",
+                        "fields": [{
+                            "name": "id",
+                            "args": [],
+                            "type": wrap("NON_NULL", named("SCALAR", "ID")),
+                        }],
+                        "interfaces": [named("INTERFACE", "Node")],
+                    },
+                    { "kind": "UNION", "name": "U", "possibleTypes": [named("OBJECT", "Foo")] },
+                    {
+                        "kind": "ENUM",
+                        "name": "E",
+                        "enumValues": [{ "name": "A" }, { "name": "B" }],
+                    },
+                    {
+                        "kind": "INPUT_OBJECT",
+                        "name": "I",
+                        "inputFields": [{
+                            "name": "e",
+                            "type": wrap("LIST", named("ENUM", "E")),
+                            "defaultValue": "[A]",
+                        }],
+                    },
+                    { "kind": "SCALAR", "name": "DateTime" },
+                    { "kind": "SCALAR", "name": "String" },
+                    { "kind": "OBJECT", "name": "__Type", "fields": [], "interfaces": [] },
+                ],
+                "directives": [
+                    {
+                        "name": "d",
+                        "locations": ["OBJECT", "FIELD_DEFINITION"],
+                        "args": [{
+                            "name": "a",
+                            "type": named("SCALAR", "Int"),
+                            "defaultValue": "1",
+                        }],
+                    },
+                    { "name": "skip", "locations": ["FIELD"], "args": [] },
+                ],
+            }
+        }
+    });
+
+    let sdl = from_introspection_json(&introspection.to_string())?.unwrap();
+    expect![[r#"
+        # generated from an introspection result, do not edit
+
+        "the root"
+        type Query {
+          node("the id" id: ID!): Node
+        }
+
+        interface Node {
+          id: ID!
+        }
+
+        type Foo implements Node {
+          id: ID!
+        }
+
+        union U = Foo
+
+        enum E {
+          A
+          B
+        }
+
+        input I {
+          e: [E] = [A]
+        }
+
+        scalar DateTime
+
+        directive @d(a: Int = 1) on OBJECT | FIELD_DEFINITION
+    "#]]
+    .assert_eq(&sdl);
+    Ok(())
+}
+
+#[test]
+fn test_from_introspection_json_requires_schema() {
+    assert!(from_introspection_json(r#"{ "data": {} }"#).unwrap().is_none());
+    assert!(
+        from_introspection_json(r#"{ "name": "foo", "types": "index.d.ts" }"#).unwrap().is_none()
+    );
+    assert!(from_introspection_json("not json").is_err());
+}
+
+#[test]
+fn test_from_introspection_json_empty_fields() -> anyhow::Result<()> {
+    let introspection = json!({
+        "__schema": {
+            "types": [
+                { "kind": "OBJECT", "name": "Foo", "fields": [], "interfaces": [] },
+                { "kind": "INPUT_OBJECT", "name": "Bar", "inputFields": [] },
+                { "kind": "ENUM", "name": "Baz", "enumValues": [] },
+            ],
+        }
+    });
+    let sdl = from_introspection_json(&introspection.to_string())?.unwrap();
+    expect![[r#"
+        # generated from an introspection result, do not edit
+
+        type Foo
+
+        input Bar
+
+        enum Baz
+    "#]]
+    .assert_eq(&sdl);
+    Ok(())
+}
+
+#[test]
+fn test_from_introspection_json_positions() -> anyhow::Result<()> {
+    let json = r#"{
+  "__schema": {
+    "queryType": { "name": "Query" },
+    "types": [
+      {
+        "kind": "OBJECT",
+        "name": "Query",
+        "description": "the root",
+        "fields": [
+          {
+            "name": "foo",
+            "args": [],
+            "type": { "kind": "ENUM", "name": "Foo", "ofType": null }
+          }
+        ],
+        "interfaces": []
+      },
+      {
+        "kind": "ENUM",
+        "name": "Foo",
+        "enumValues": [
+          { "name": "A" },
+          { "name": "B" }
+        ]
+      }
+    ],
+    "directives": [{ "name": "d", "locations": ["OBJECT"], "args": [] }]
+  }
+}"#;
+    let sdl = from_introspection_json(json)?.unwrap();
+    // each definition is named at the line and column of its name in the json, rather than of
+    // the root operation type or field type with the same name
+    let names = [("Query", 6, 17), ("foo", 10, 21), ("Foo", 19, 17), ("A", 21, 21), ("d", 26, 30)];
+    for (name, row, column) in names {
+        for text in [json, &sdl] {
+            let line = text.lines().nth(row).unwrap();
+            assert_eq!(&line[column..column + name.len()], name, "`{name}` in\n{sdl}");
+        }
+    }
+    assert!(sdl.lines().nth(26).unwrap().ends_with("@d on OBJECT"));
+    Ok(())
+}
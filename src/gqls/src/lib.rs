@@ -1,7 +1,37 @@
+mod baseline;
 mod config;
 mod convert;
+mod diff;
+mod format;
+mod graph;
+mod index;
+mod introspection;
+mod lint;
 mod lsp;
+mod progress;
+mod published;
+mod report;
+mod scheduler;
+mod serve;
+mod settings;
+mod timings;
 mod tokens;
 
-pub use convert::{Convert, UrlExt};
+pub use baseline::Baseline;
+pub use convert::{
+    convert_diagnostic, Convert, ConvertIn, ConvertWith, FileIndex, LineIndices, UrlExt
+};
+pub use diff::{diff, DiffArgs};
+pub use format::{format, FormatArgs};
+pub use graph::{graph, GraphArgs, GraphEdge, GraphEdgeKind, GraphFormat, GraphNode, SchemaGraph};
+pub use index::{index, IndexArgs};
+pub use lint::{lint, LintArgs};
 pub use lsp::{capabilities, Gqls};
+pub use report::OutputFormat;
+pub use serve::{serve, Transport};
+pub use settings::{FeatureSettings, FormattingSettings, LintLevel, Settings};
+pub use timings::{Timing, Timings, TimingsLayer, TIMINGS};
+
+/// The analyzer for embedding without speaking LSP, see [`AnalysisHost`]
+pub use gqls_ide as ide;
+pub use gqls_ide::{Analysis, AnalysisHost};
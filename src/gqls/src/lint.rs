@@ -0,0 +1,94 @@
+//! `gqls lint`, reports the diagnostics of the workspace at the given root
+
+use anyhow::{anyhow, bail, Context};
+use gqls_ide::{Diagnostics, Ide, Severity};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::{Url, WorkspaceFolder};
+
+use crate::baseline::Baseline;
+use crate::lsp;
+use crate::report::{self, OutputFormat};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintArgs {
+    /// if the file doesn't exist yet it is created with the current violations,
+    /// defaults to the `baseline` of the configuration file
+    pub baseline: Option<PathBuf>,
+    pub format: OutputFormat,
+}
+
+impl LintArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let mut baseline = None;
+        let mut format = OutputFormat::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--baseline" => {
+                    let path = args.next().context("expected a path after `--baseline`")?;
+                    baseline = Some(PathBuf::from(path));
+                }
+                "--format" => {
+                    let name = args.next().context("expected a format after `--format`")?;
+                    format = name.parse()?;
+                }
+                arg => bail!("unexpected argument `{arg}`"),
+            }
+        }
+        Ok(Self { baseline, format })
+    }
+}
+
+/// Write the diagnostics that aren't suppressed by the baseline to `out`,
+/// returning whether there were none (besides informational ones)
+pub fn lint(root: &Path, args: &LintArgs, out: &mut impl Write) -> anyhow::Result<bool> {
+    let uri = Url::from_directory_path(root)
+        .map_err(|()| anyhow!("expected an absolute path, found `{}`", root.display()))?;
+    let workspace = WorkspaceFolder { uri, name: String::new() };
+
+    let mut ide = Ide::default();
+    let summary = lsp::load_workspaces(&mut ide, vec![workspace])?;
+    let snapshot = ide.snapshot();
+    let mut diagnostics = summary
+        .projects
+        .iter()
+        .flat_map(|&project| snapshot.diagnostics(project))
+        .collect::<Diagnostics>();
+
+    let baseline_path = match &args.baseline {
+        Some(path) => Some(path.clone()),
+        None => lsp::read_config(root)?
+            .and_then(|config| config.baseline().map(|path| root.join(path))),
+    };
+    let baseline = match baseline_path {
+        Some(path) if path.exists() => Baseline::read(&path)
+            .with_context(|| format!("failed to read baseline `{}`", path.display()))?,
+        Some(path) => {
+            let baseline = Baseline::record(root, &diagnostics);
+            baseline.write(&path)?;
+            let message = format!("recorded {} violations in `{}`", baseline.len(), path.display());
+            // machine readable output shouldn't be interleaved with anything else
+            match args.format {
+                OutputFormat::Text => writeln!(out, "{message}")?,
+                OutputFormat::Json | OutputFormat::Sarif => tracing::info!("{message}"),
+            }
+            baseline
+        }
+        None => Baseline::default(),
+    };
+    baseline.suppress(root, &mut diagnostics);
+
+    let mut diagnostics = diagnostics
+        .into_iter()
+        .flat_map(|(file, diagnostics)| diagnostics.into_iter().map(move |diag| (file, diag)))
+        .collect::<Vec<_>>();
+    diagnostics.sort_by_key(|(file, diagnostic)| (*file, diagnostic.range.start));
+    report::write(&snapshot, root, args.format, &diagnostics, out)?;
+    Ok(diagnostics
+        .iter()
+        .all(|(_, diagnostic)| matches!(diagnostic.severity, Severity::Info | Severity::Hint)))
+}
+
+#[cfg(test)]
+mod tests;
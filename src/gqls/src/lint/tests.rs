@@ -0,0 +1,53 @@
+use expect_test::expect;
+use serde_json::Value;
+use std::path::PathBuf;
+
+use super::{lint, LintArgs};
+use crate::report::OutputFormat;
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/lint/fixtures").join(name)
+}
+
+#[test]
+fn test_parse_lint_args() -> anyhow::Result<()> {
+    assert_eq!(LintArgs::parse(vec![])?, LintArgs::default());
+    assert_eq!(
+        LintArgs::parse(vec!["--baseline".to_owned(), "baseline.json".to_owned()])?,
+        LintArgs { baseline: Some(PathBuf::from("baseline.json")), ..Default::default() }
+    );
+    assert_eq!(
+        LintArgs::parse(vec!["--format".to_owned(), "sarif".to_owned()])?,
+        LintArgs { format: OutputFormat::Sarif, ..Default::default() }
+    );
+    assert!(LintArgs::parse(vec!["--baseline".to_owned()]).is_err());
+    assert!(LintArgs::parse(vec!["--format".to_owned(), "xml".to_owned()]).is_err());
+    assert!(LintArgs::parse(vec!["--fix".to_owned()]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_lint_with_baseline() -> anyhow::Result<()> {
+    let mut out = vec![];
+    let clean = lint(&fixture_path("baseline"), &LintArgs::default(), &mut out)?;
+    assert!(!clean);
+    expect![[r#"
+        foo.graphql:3:3: warning[L0003]: field `new_violation` should be in camelCase: `newViolation`
+    "#]]
+    .assert_eq(&String::from_utf8(out)?);
+    Ok(())
+}
+
+#[test]
+fn test_lint_json_output() -> anyhow::Result<()> {
+    let mut out = vec![];
+    let args = LintArgs { format: OutputFormat::Json, ..Default::default() };
+    assert!(!lint(&fixture_path("baseline"), &args, &mut out)?);
+    let out = String::from_utf8(out)?;
+    let lines = out.lines().map(serde_json::from_str).collect::<Result<Vec<Value>, _>>()?;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["file"], "foo.graphql");
+    assert_eq!(lines[0]["code"], "L0003");
+    assert_eq!(lines[0]["severity"], "warning");
+    Ok(())
+}
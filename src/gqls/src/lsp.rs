@@ -1,15 +1,27 @@
-use crate::config::{Config, DEFAULT_PROJECT};
-use crate::convert::{self, PathExt};
-use crate::{tokens, Convert, UrlExt};
+use crate::baseline::Baseline;
+use crate::config::{Config, DirectoryConfig, DEFAULT_PROJECT, DIRECTORY_CONFIG};
+use crate::convert::{self, LineIndices, PathExt, SnapshotIndices};
+use crate::graph::{GraphFormat, SchemaGraph};
+use crate::introspection::{self, is_introspection_file};
+use crate::progress::ProgressReporter;
+use crate::published::PublishedSchema;
+use crate::scheduler::Scheduler;
+use crate::settings::{LintLevel, Settings, ToggleableFeature, WorkspaceFolderMode};
+use crate::timings::{Timing, TIMINGS};
+use crate::{tokens, Convert, ConvertIn, ConvertWith, UrlExt};
 use anyhow::Result;
 use core::panic::{AssertUnwindSafe, UnwindSafe};
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use gqls_ide::{
-    Change, ChangeKind, Changeset, ChangesetSummary, FileId, Ide, Patch, Vfs, VfsProxy
+    AssistConfig, AssistKind, AssistResolveStrategy, Cancellable, Change, ChangeKind, Changeset, ChangesetSummary, CustomScalar, CustomScalars, DiagnosticPass, Diagnostics, FieldSetDirective, FileId, GeneratedFiles, Ide, Lint, LintOverrides, Patch, PositionEncoding, Project, RootTypes, Snapshot, SourceChange, Vfs, VfsProxy
 };
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use tower_lsp::lsp_types::notification::PublishDiagnostics;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{jsonrpc, Client, ClientSocket, LanguageServer, LspService};
@@ -17,27 +29,86 @@ use tower_lsp::{jsonrpc, Client, ClientSocket, LanguageServer, LspService};
 pub struct Gqls {
     // FIXME not really a good thing to do as it's not really unwind safe
     client: AssertUnwindSafe<Client>,
-    ide: AssertUnwindSafe<Mutex<Ide>>,
-    workspace_folders: OnceCell<Vec<WorkspaceFolder>>,
+    ide: AssertUnwindSafe<Arc<Mutex<Ide>>>,
+    /// the version of each document open in the client as of the last change loaded into the
+    /// ide, only updated while the ide is locked so a snapshot can be taken along with them
+    versions: AssertUnwindSafe<Arc<Mutex<HashMap<FileId, i32>>>>,
+    /// `None` until initialized, the client may add and remove folders after that
+    workspace_folders: AssertUnwindSafe<Mutex<Option<Vec<WorkspaceFolder>>>>,
+    /// the results of the last completed [`DiagnosticPass::Local`] and [`DiagnosticPass::Project`]
+    semantic_diagnostics: AssertUnwindSafe<Arc<Mutex<Diagnostics>>>,
+    /// coalesces the background passes of rapid changes, outdated passes are discarded
+    scheduler: Scheduler,
+    /// the configured lint baseline of each workspace root
+    baselines: AssertUnwindSafe<Arc<Mutex<Vec<(PathBuf, Baseline)>>>>,
+    /// the published schema of each project that has one configured
+    published: AssertUnwindSafe<Arc<Mutex<HashMap<Project, PublishedSchema>>>>,
+    settings: AssertUnwindSafe<Mutex<Settings>>,
+    /// the features whose capabilities are registered dynamically (if enabled) rather than
+    /// advertised on initialization, so they can be toggled while the server is running
+    dynamic_features: OnceCell<BTreeSet<ToggleableFeature>>,
+    /// whether the client can watch the files of the workspaces, see [`workspace_watchers`]
+    watch_files: OnceCell<bool>,
+    /// the projects of the workspaces, which are analyzed once the client is initialized
+    initial_projects: AssertUnwindSafe<Mutex<Option<HashSet<Project>>>>,
+    /// whether the client accepts server initiated progress
+    work_done_progress: OnceCell<bool>,
+    /// what the columns of the positions exchanged with the client count
+    position_encoding: OnceCell<PositionEncoding>,
+    /// whether the client resolves the edits of code actions lazily
+    resolve_code_actions: OnceCell<bool>,
+    /// whether the client can run [`convert::RENAME_AT_COMMAND`], see [`client_commands`]
+    rename_at: OnceCell<bool>,
 }
 
 impl Gqls {
     pub fn new(client: Client) -> Self {
         Self {
             client: AssertUnwindSafe(client),
-            workspace_folders: Default::default(),
+            workspace_folders: AssertUnwindSafe(Default::default()),
             // FIXME implements default in 1.62
             ide: AssertUnwindSafe(Default::default()),
+            versions: AssertUnwindSafe(Default::default()),
+            semantic_diagnostics: AssertUnwindSafe(Default::default()),
+            scheduler: Default::default(),
+            baselines: AssertUnwindSafe(Default::default()),
+            published: AssertUnwindSafe(Default::default()),
+            settings: AssertUnwindSafe(Default::default()),
+            dynamic_features: Default::default(),
+            watch_files: Default::default(),
+            initial_projects: AssertUnwindSafe(Default::default()),
+            work_done_progress: Default::default(),
+            position_encoding: Default::default(),
+            resolve_code_actions: Default::default(),
+            rename_at: Default::default(),
         }
     }
 
     pub fn service() -> (LspService<Gqls>, ClientSocket) {
-        LspService::build(Self::new).custom_method("gqls/syntaxTree", Gqls::syntax_tree).finish()
+        LspService::build(Self::new)
+            .custom_method("gqls/syntaxTree", Gqls::syntax_tree)
+            .custom_method("gqls/fileSummary", Gqls::file_summary)
+            .custom_method("gqls/documentFeatures", Gqls::document_features)
+            .custom_method("gqls/nextDefinition", Gqls::next_definition)
+            .custom_method("gqls/extensions", Gqls::extensions)
+            .custom_method("gqls/schemaGraph", Gqls::schema_graph)
+            .custom_method("gqls/typeInfo", Gqls::type_info)
+            .custom_method("gqls/debug/timings", Gqls::timings)
+            .finish()
     }
 }
 
-pub fn capabilities() -> ServerCapabilities {
-    ServerCapabilities {
+/// Serializes the schema of the project(s) of the file (the only argument) as introspection json
+pub(crate) const EXPORT_INTROSPECTION: &str = "gqls/exportIntrospection";
+
+/// The source action that sorts the members of the definition at the cursor
+pub(crate) const ORGANIZE_FIELDS: &str = "source.organizeFields";
+
+const DID_CHANGE_WATCHED_FILES: &str = "workspace/didChangeWatchedFiles";
+
+/// The capabilities of the server with the features disabled by `settings` omitted
+pub fn capabilities(settings: &Settings) -> ServerCapabilities {
+    let mut capabilities = ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
             open_close: Some(true),
             change: Some(TextDocumentSyncKind::INCREMENTAL),
@@ -53,6 +124,7 @@ pub fn capabilities() -> ServerCapabilities {
         workspace_symbol_provider: Some(OneOf::Left(true)),
         type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
         implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
         semantic_tokens_provider: Some(
             SemanticTokensOptions {
                 work_done_progress_options: Default::default(),
@@ -88,17 +160,81 @@ pub fn capabilities() -> ServerCapabilities {
                 }),
             }),
         }),
-        // hover_provider: Some(HoverProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![EXPORT_INTROSPECTION.to_owned()],
+            ..Default::default()
+        }),
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(["(", ","].map(ToString::to_string).to_vec()),
+            ..Default::default()
+        }),
+        code_lens_provider: Some(CodeLensOptions { resolve_provider: Some(true) }),
+        document_link_provider: Some(DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: Default::default(),
+        }),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![
+                CodeActionKind::QUICKFIX,
+                CodeActionKind::from(ORGANIZE_FIELDS),
+                CodeActionKind::REFACTOR_EXTRACT,
+                CodeActionKind::REFACTOR_INLINE,
+                CodeActionKind::REFACTOR_REWRITE,
+            ]),
+            resolve_provider: Some(true),
+            ..Default::default()
+        })),
+        document_formatting_provider: Some(OneOf::Left(true)),
         completion_provider: Some(CompletionOptions {
             resolve_provider: Some(false),
-            trigger_characters: Some(["@", ":", "|"].map(ToString::to_string).to_vec()),
+            trigger_characters: Some(["@", ":", "|", "\""].map(ToString::to_string).to_vec()),
             ..Default::default()
         }),
         rename_provider: Some(OneOf::Right(RenameOptions {
             prepare_provider: Some(true),
             work_done_progress_options: Default::default(),
         })),
+        // replaced by the encoding negotiated with the client on initialization
+        position_encoding: Some(PositionEncodingKind::UTF16),
         ..Default::default()
+    };
+    for feature in ToggleableFeature::ALL {
+        if !settings.is_enabled(feature) {
+            remove_capability(&mut capabilities, feature);
+        }
+    }
+    capabilities
+}
+
+fn remove_capability(capabilities: &mut ServerCapabilities, feature: ToggleableFeature) {
+    match feature {
+        ToggleableFeature::Completions => capabilities.completion_provider = None,
+        ToggleableFeature::Hover => capabilities.hover_provider = None,
+        ToggleableFeature::SemanticTokens => capabilities.semantic_tokens_provider = None,
+        ToggleableFeature::CodeLens => capabilities.code_lens_provider = None,
+        ToggleableFeature::Formatting => capabilities.document_formatting_provider = None,
+    }
+}
+
+/// The options to dynamically register the capability of `feature` with,
+/// the same as the options that would otherwise be advertised on initialization
+fn registration(feature: ToggleableFeature) -> Registration {
+    let capabilities = capabilities(&Settings::default());
+    let options = match feature {
+        ToggleableFeature::Completions => serde_json::to_value(capabilities.completion_provider),
+        ToggleableFeature::Hover | ToggleableFeature::Formatting => Ok(serde_json::json!({})),
+        ToggleableFeature::SemanticTokens =>
+            serde_json::to_value(capabilities.semantic_tokens_provider),
+        ToggleableFeature::CodeLens => serde_json::to_value(capabilities.code_lens_provider),
+    };
+    let mut options = options.expect("capabilities are serializable");
+    // a null document selector applies the registration to the documents of the client
+    options["documentSelector"] = serde_json::Value::Null;
+    Registration {
+        id: feature.method().to_owned(),
+        method: feature.method().to_owned(),
+        register_options: Some(options),
     }
 }
 
@@ -121,36 +257,36 @@ impl VfsExt for VfsProxy {
 }
 
 impl Gqls {
-    fn reinit(&self) -> jsonrpc::Result<ChangesetSummary> {
-        self.init(self.workspace_folders.get().expect("called reinit before init").clone())
+    fn reinit(&self) -> jsonrpc::Result<HashSet<Project>> {
+        let workspaces = self.workspace_folders.lock().clone().expect("called reinit before init");
+        self.init(workspaces)
     }
 
-    fn init(&self, workspaces: Vec<WorkspaceFolder>) -> jsonrpc::Result<ChangesetSummary> {
-        let projects = discover_projects(workspaces).map_err(|err| {
+    // the diagnostics are left to the caller, analyzing every file can take a while
+    fn init(&self, workspaces: Vec<WorkspaceFolder>) -> jsonrpc::Result<HashSet<Project>> {
+        let internal_error = |err: anyhow::Error| {
             tracing::error!(%err);
             jsonrpc::Error::internal_error()
-        })?;
-
-        let mut ide = self.ide.lock();
-        let mut changeset = Changeset::default().with_projects(
-            projects
-                .iter()
-                .map(|(k, v)| {
-                    (
-                        ide.intern_project(k.to_owned()),
-                        v.iter().map(|(path, _)| ide.intern_path(path.clone())).collect(),
-                    )
-                })
-                .collect(),
-        );
-
-        for (_, files) in projects {
-            for (path, content) in files {
-                changeset = changeset.with_change(Change::set(ide.intern_path(path), content))
-            }
+        };
+        *self.baselines.lock() = discover_baselines(workspaces.clone()).map_err(internal_error)?;
+        let settings = self.settings.lock().clone();
+        // loading a published schema may have to fetch it, which must not block the ide
+        let mut schemas = vec![];
+        for (folder, workspaces) in project_scopes(workspaces.clone()) {
+            let discovered = discover_published(workspaces).map_err(internal_error)?;
+            schemas.extend(discovered.into_iter().map(|(project, schema)| {
+                (qualify_project(folder.as_ref(), &project), schema)
+            }));
         }
-
-        Ok(ide.apply(changeset))
+        let mut ide = self.ide.lock();
+        let published = schemas
+            .into_iter()
+            .map(|(project, schema)| (ide.intern_project(project), schema))
+            .collect();
+        *self.published.lock() = published;
+        let changeset =
+            workspace_changeset(&mut ide, workspaces, &settings).map_err(internal_error)?;
+        Ok(ide.load(changeset))
     }
 
     // dirty hack to retry a request if it fails by reinitializing
@@ -160,19 +296,76 @@ impl Gqls {
         &self,
         mut f: impl FnMut(&mut Ide) -> jsonrpc::Result<R> + UnwindSafe,
     ) -> jsonrpc::Result<R> {
-        let start = std::time::Instant::now();
         // FIXME hacking unwind safety
         // HACK when just any panics or errors, reinit and try once more
-        let r = match std::panic::catch_unwind(AssertUnwindSafe(|| f(&mut self.ide.lock()))) {
+        match std::panic::catch_unwind(AssertUnwindSafe(|| f(&mut self.ide.lock()))) {
             Ok(Ok(res)) => Ok(res),
             _ => {
                 tracing::warn!("retrying failed request");
                 self.reinit()?;
                 f(&mut self.ide.lock())
             }
+        }
+    }
+
+    // Read-only requests run against a snapshot so the ide isn't locked while they run,
+    // a change made in the meantime cancels them rather than waiting for them to finish.
+    // Failures other than cancellation are retried like `with_ide`.
+    fn with_snapshot<R>(&self, f: impl Fn(&Snapshot) -> jsonrpc::Result<R>) -> jsonrpc::Result<R> {
+        self.with_versioned_snapshot(|snapshot, _| f(snapshot))
+    }
+
+    // Like `with_snapshot`, along with the versions of the open documents the snapshot is of, for
+    // the requests that respond with edits
+    fn with_versioned_snapshot<R>(
+        &self,
+        f: impl Fn(&Snapshot, &HashMap<FileId, i32>) -> jsonrpc::Result<R>,
+    ) -> jsonrpc::Result<R> {
+        let run = || {
+            let (snapshot, versions) = {
+                let ide = self.ide.lock();
+                (ide.snapshot(), self.versions.lock().clone())
+            };
+            let f = |snapshot: &Snapshot| f(snapshot, &versions);
+            snapshot.catch_cancelled(f).map_err(|_| request_cancelled())
         };
-        tracing::info!("took {}ms", start.elapsed().as_millis());
-        r
+        match std::panic::catch_unwind(AssertUnwindSafe(&run)) {
+            Ok(Ok(Ok(res))) => Ok(res),
+            Ok(Err(cancelled)) => Err(cancelled),
+            _ => {
+                tracing::warn!("retrying failed request");
+                self.reinit()?;
+                run().and_then(|res| res)
+            }
+        }
+    }
+}
+
+fn request_cancelled() -> jsonrpc::Error {
+    jsonrpc::Error {
+        code: jsonrpc::ErrorCode::RequestCancelled,
+        message: "request cancelled by a change".to_owned(),
+        data: None,
+    }
+}
+
+/// The commands the client can run on behalf of the server, which it lists under
+/// `experimental.commands.commands` of its capabilities
+fn client_commands(capabilities: &ClientCapabilities) -> Vec<&str> {
+    capabilities
+        .experimental
+        .as_ref()
+        .and_then(|experimental| experimental.pointer("/commands/commands"))
+        .and_then(serde_json::Value::as_array)
+        .map(|commands| commands.iter().filter_map(serde_json::Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// The items of the call hierarchy are identified by the start of their name
+fn hierarchy_item_position(item: &CallHierarchyItem) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: item.uri.clone() },
+        position: item.selection_range.start,
     }
 }
 
@@ -183,18 +376,77 @@ impl LanguageServer for Gqls {
         // TODO should probably check client capabilities, but going to assume they have everything we need for now
 
         let workspaces = params.workspace_folders.unwrap_or_default();
-        self.workspace_folders.set(workspaces.clone()).expect("initialize called twice");
-        let summary = self.init(workspaces)?;
-        self.send_diagnostics(summary).await;
+        let previous = self.workspace_folders.lock().replace(workspaces.clone());
+        assert!(previous.is_none(), "initialize called twice");
+        let settings = Settings::from_value(params.initialization_options);
+        *self.settings.lock() = settings.clone();
+        self.load_index_cache();
+        // the client must not be sent any diagnostics until it has received the response
+        *self.initial_projects.lock() = Some(self.init(workspaces)?);
+        let work_done_progress = params
+            .capabilities
+            .window
+            .as_ref()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or_default();
+        self.work_done_progress.set(work_done_progress).expect("initialize called twice");
+        let position_encoding = convert::negotiate_position_encoding(&params.capabilities);
+        self.position_encoding.set(position_encoding).expect("initialize called twice");
+        let resolve_code_actions = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.code_action.as_ref())
+            .and_then(|code_action| code_action.resolve_support.as_ref())
+            .map_or(false, |support| support.properties.iter().any(|property| property == "edit"));
+        self.resolve_code_actions.set(resolve_code_actions).expect("initialize called twice");
+        let rename_at = client_commands(&params.capabilities).contains(&convert::RENAME_AT_COMMAND);
+        self.rename_at.set(rename_at).expect("initialize called twice");
+
+        let dynamic_features = ToggleableFeature::ALL
+            .into_iter()
+            .filter(|feature| feature.supports_dynamic_registration(&params.capabilities))
+            .collect::<BTreeSet<_>>();
+        let mut capabilities = capabilities(&settings);
+        for &feature in &dynamic_features {
+            remove_capability(&mut capabilities, feature);
+        }
+        self.dynamic_features.set(dynamic_features).expect("initialize called twice");
+        let watch_files = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+            .and_then(|caps| caps.dynamic_registration)
+            .unwrap_or_default();
+        self.watch_files.set(watch_files).expect("initialize called twice");
+        capabilities.position_encoding = Some(convert::position_encoding_kind(position_encoding));
 
         Ok(InitializeResult {
-            capabilities: capabilities(),
+            capabilities,
             server_info: Some(ServerInfo { name: "gqls".to_owned(), version: None }),
         })
     }
 
     #[tracing::instrument(skip_all)]
     async fn initialized(&self, _: InitializedParams) {
+        if let Err(err) = write_preludes() {
+            tracing::warn!("failed to write the preludes: {err}");
+        }
+        let settings = self.settings.lock().clone();
+        self.update_registrations(None, &settings).await;
+        self.register_watchers(false).await;
+        let projects = self.initial_projects.lock().take();
+        if let Some(projects) = projects {
+            // the requests made in the meantime are served from the files analyzed so far
+            let analysis = self.analyze_workspace(projects);
+            let ide = Arc::clone(&self.ide.0);
+            let cache_directory = self.cache_directory();
+            tokio::spawn(async move {
+                analysis.await;
+                save_index_cache(&ide, cache_directory);
+            });
+        }
         tracing::info!("gqls initialized");
     }
 
@@ -206,19 +458,70 @@ impl LanguageServer for Gqls {
     #[tracing::instrument(skip_all)]
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         // maybe we can make sure text is not desynced by doing a hard reset with Change::Set using `params.text`
+        let path = match params.text_document.uri.to_path() {
+            Ok(path) => path,
+            Err(err) => {
+                tracing::error!(%err);
+                return;
+            }
+        };
+        // the text is only included if the client was asked to, otherwise it is read from disk
+        let text = match params.text {
+            Some(text) => text,
+            None => match read_lossy(&path) {
+                Ok((text, _)) => text,
+                Err(err) => {
+                    tracing::error!(%err);
+                    return;
+                }
+            },
+        };
+        let text = match file_content(&path, text) {
+            Ok(Some(text)) => text,
+            Ok(None) => return,
+            Err(err) => {
+                tracing::error!(%err);
+                return;
+            }
+        };
         if let Ok(summary) = self.with_ide(|ide| {
-            let uri = params.text_document.uri.to_path()?;
-            let changeset =
-                Changeset::single(Change::set(ide.intern_path(uri), params.text.clone().unwrap()));
-            Ok(ide.apply(changeset))
+            let file = ide.intern_path(path.clone());
+            if !is_introspection_file(&path) {
+                ide.vfs().set_overlay(file, text.clone());
+            }
+            Ok(ide.apply(Changeset::single(Change::set(file, text.clone()))))
         }) {
             self.send_diagnostics(summary).await;
         }
     }
 
     #[tracing::instrument(skip_all)]
-    async fn did_change_workspace_folders(&self, _params: DidChangeWorkspaceFoldersParams) {
-        todo!("did_change_workspace_folders")
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let settings = Settings::from_value(Some(params.settings));
+        let previous = std::mem::replace(&mut *self.settings.lock(), settings.clone());
+        self.update_registrations(Some(&previous), &settings).await;
+        // before initialization the workspace is loaded with the new settings anyway
+        if !settings.workspace_changed(&previous) || self.workspace_folders.lock().is_none() {
+            return;
+        }
+        match self.reinit() {
+            Ok(projects) => self.analyze_workspace(projects).await,
+            Err(err) => tracing::error!(%err, "failed to reload the workspace"),
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        if let Err(err) = self.handle_did_change_watched_files(params).await {
+            tracing::error!(%err);
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        if let Err(err) = self.handle_did_change_workspace_folders(params.event).await {
+            tracing::error!(%err);
+        }
     }
 
     #[tracing::instrument(skip_all)]
@@ -226,13 +529,36 @@ impl LanguageServer for Gqls {
         &self,
         params: WorkspaceSymbolParams,
     ) -> jsonrpc::Result<Option<Vec<SymbolInformation>>> {
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
             let symbols = snapshot.workspace_symbols(&params.query);
-            Ok(Some(symbols.convert()))
+            Ok(Some(symbols.convert_with(&indices)))
         })
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> jsonrpc::Result<Option<serde_json::Value>> {
+        match params.command.as_str() {
+            EXPORT_INTROSPECTION => {
+                let url = match &params.arguments[..] {
+                    [url] => serde_json::from_value::<Url>(url.clone())
+                        .map_err(|err| jsonrpc::Error::invalid_params(err.to_string()))?,
+                    _ => return Err(jsonrpc::Error::invalid_params("expected a document uri")),
+                };
+                self.with_snapshot(|snapshot| {
+                    let path = VfsProxy.path(&url)?;
+                    let schema = snapshot.schema(path);
+                    Ok(Some(introspection::to_introspection_json(&schema)))
+                })
+            }
+            command =>
+                Err(jsonrpc::Error::invalid_params(format!("unknown command `{command}`"))),
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     async fn will_create_files(
         &self,
@@ -270,8 +596,14 @@ impl LanguageServer for Gqls {
 
     #[tracing::instrument(skip_all)]
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        // introspection results were already converted when the projects were discovered
+        if params.text_document.uri.to_path().map_or(false, |path| is_introspection_file(&path)) {
+            return;
+        }
         let summary = self.with_ide(|ide| {
             let file = ide.intern_path(params.text_document.uri.to_path()?);
+            self.versions.lock().insert(file, params.text_document.version);
+            ide.vfs().set_overlay(file, params.text_document.text.clone());
             Ok(ide.apply(Change::set(file, params.text_document.text.clone())))
         });
         match summary {
@@ -287,29 +619,66 @@ impl LanguageServer for Gqls {
         }
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        if let Err(err) = self.handle_did_close(params).await {
+            tracing::error!(%err);
+        }
+    }
+
     #[tracing::instrument(skip_all)]
     async fn completion(
         &self,
         params: CompletionParams,
     ) -> jsonrpc::Result<Option<CompletionResponse>> {
+        if !self.is_enabled(ToggleableFeature::Completions) {
+            return Ok(None);
+        }
         let position = params.text_document_position;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let completions = snapshot.completions(position.convert()?);
+        let config = self.settings.lock().completion.config();
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let completions = snapshot.completions(position.convert_with(&indices)?, &config);
             Ok(Some(CompletionResponse::Array(completions.convert())))
         })
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn hover(&self, params: HoverParams) -> jsonrpc::Result<Option<Hover>> {
+        if !self.is_enabled(ToggleableFeature::Hover) {
+            return Ok(None);
+        }
+        let position = params.text_document_position_params;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            Ok(snapshot.hover(position.convert_with(&indices)?).map(|hover| hover.convert()))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn signature_help(
+        &self,
+        params: SignatureHelpParams,
+    ) -> jsonrpc::Result<Option<SignatureHelp>> {
+        let position = params.text_document_position_params;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let help = snapshot.signature_help(position.convert_with(&indices)?);
+            Ok(help.map(|help| help.convert()))
+        })
+    }
+
     #[tracing::instrument(skip_all)]
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
     ) -> jsonrpc::Result<Option<GotoDefinitionResponse>> {
         let position = params.text_document_position_params;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let locations = snapshot.goto_definition(position.convert()?);
-            Ok(convert::locations_to_goto_definition_response(&locations))
+        let config = self.settings.lock().definition.config();
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let locations = snapshot.goto_definition(position.convert_with(&indices)?, &config);
+            Ok(convert::locations_to_goto_definition_response(snapshot, &locations))
         })
     }
 
@@ -319,10 +688,10 @@ impl LanguageServer for Gqls {
         params: GotoDefinitionParams,
     ) -> jsonrpc::Result<Option<GotoDefinitionResponse>> {
         let position = params.text_document_position_params;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let locations = snapshot.goto_type_definition(position.convert()?);
-            Ok(convert::locations_to_goto_definition_response(&locations))
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let locations = snapshot.goto_type_definition(position.convert_with(&indices)?);
+            Ok(convert::locations_to_goto_definition_response(snapshot, &locations))
         })
     }
 
@@ -332,36 +701,156 @@ impl LanguageServer for Gqls {
         params: GotoDefinitionParams,
     ) -> jsonrpc::Result<Option<GotoDefinitionResponse>> {
         let position = params.text_document_position_params;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let locations = snapshot.goto_implementation(position.convert()?);
-            Ok(convert::locations_to_goto_definition_response(&locations))
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let locations = snapshot.goto_implementation(position.convert_with(&indices)?);
+            Ok(convert::locations_to_goto_definition_response(snapshot, &locations))
         })
     }
 
     #[tracing::instrument(skip_all)]
     async fn references(&self, params: ReferenceParams) -> jsonrpc::Result<Option<Vec<Location>>> {
         let position = params.text_document_position;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let locations = snapshot.find_references(position.convert()?);
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let locations = snapshot.find_references(position.convert_with(&indices)?);
             match &locations[..] {
                 [] => Ok(None),
-                locations => Ok(Some(locations.convert())),
+                locations => Ok(Some(locations.convert_with(&indices))),
+            }
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyItem>>> {
+        let position = params.text_document_position_params;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let items = snapshot.prepare_dependency_hierarchy(position.convert_with(&indices)?);
+            match &items[..] {
+                [] => Ok(None),
+                items => Ok(Some(items.convert_with(&indices))),
             }
         })
     }
 
+    // the "calls" of a type are the references to other types from its fields, arguments and
+    // union members
+    #[tracing::instrument(skip_all)]
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let position = hierarchy_item_position(&params.item);
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let calls = snapshot
+                .incoming_dependencies(position.convert_with(&indices)?)
+                .into_iter()
+                .map(|call| CallHierarchyIncomingCall {
+                    from_ranges: call.ranges.convert_in(&indices.file_index(call.item.file)),
+                    from: call.item.convert_with(&indices),
+                })
+                .collect();
+            Ok(Some(calls))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> jsonrpc::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let position = hierarchy_item_position(&params.item);
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let position = position.convert_with(&indices)?;
+            // the ranges of outgoing calls are in the file of the caller
+            let index = indices.file_index(position.file);
+            let calls = snapshot
+                .outgoing_dependencies(position)
+                .into_iter()
+                .map(|call| CallHierarchyOutgoingCall {
+                    from_ranges: call.ranges.convert_in(&index),
+                    to: call.item.convert_with(&indices),
+                })
+                .collect();
+            Ok(Some(calls))
+        })
+    }
+
     #[tracing::instrument(skip_all)]
     async fn document_symbol(
         &self,
         params: DocumentSymbolParams,
     ) -> jsonrpc::Result<Option<DocumentSymbolResponse>> {
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let path = ide.vfs().path(&params.text_document.uri)?;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
             let symbols = snapshot.document_symbols(path);
-            Ok(Some(DocumentSymbolResponse::Nested(symbols.convert())))
+            let symbols = symbols.convert_in(&indices.file_index(path));
+            Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn document_link(
+        &self,
+        params: DocumentLinkParams,
+    ) -> jsonrpc::Result<Option<Vec<DocumentLink>>> {
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let links = snapshot.document_links(path).convert_in(&indices.file_index(path));
+            Ok(Some(links.into_iter().filter(|link| link.target.is_some()).collect()))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn code_lens(&self, params: CodeLensParams) -> jsonrpc::Result<Option<Vec<CodeLens>>> {
+        if !self.is_enabled(ToggleableFeature::CodeLens) {
+            return Ok(None);
+        }
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            Ok(Some(snapshot.code_lenses(path).convert_with(&indices)))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn code_lens_resolve(&self, lens: CodeLens) -> jsonrpc::Result<CodeLens> {
+        let data = lens
+            .data
+            .clone()
+            .and_then(|data| serde_json::from_value::<CodeLensData>(data).ok())
+            .ok_or_else(|| jsonrpc::Error::invalid_params("invalid code lens data"))?;
+        let kind = data
+            .kind
+            .parse::<gqls_ide::CodeLensKind>()
+            .map_err(|()| jsonrpc::Error::invalid_params("invalid code lens kind"))?;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let position = data.text_document_position.convert_with(&indices)?;
+            let range = lens.range.convert_in(&indices.file_index(position.file));
+            let unresolved = gqls_ide::CodeLens { position, range, kind };
+            let locations = snapshot.resolve_code_lens(unresolved);
+            Ok(CodeLens {
+                range: lens.range,
+                command: Some(Command {
+                    title: kind.title(locations.len()),
+                    command: "gqls.showReferences".to_owned(),
+                    arguments: Some(vec![
+                        serde_json::json!(data.text_document_position.text_document.uri),
+                        serde_json::json!(data.text_document_position.position),
+                        serde_json::json!(locations.convert_with(&indices)),
+                    ]),
+                }),
+                data: None,
+            })
         })
     }
 
@@ -370,23 +859,27 @@ impl LanguageServer for Gqls {
         &self,
         params: TextDocumentPositionParams,
     ) -> jsonrpc::Result<Option<PrepareRenameResponse>> {
-        self.with_ide(|ide| match ide.snapshot().prepare_rename(params.convert()?) {
-            Ok(range) => Ok(Some(PrepareRenameResponse::Range(range.convert()))),
-            Err(_) => Ok(None),
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let position = params.convert_with(&indices)?;
+            match snapshot.prepare_rename(position) {
+                Ok(range) => {
+                    let range = range.convert_in(&indices.file_index(position.file));
+                    Ok(Some(PrepareRenameResponse::Range(range)))
+                }
+                Err(_) => Ok(None),
+            }
         })
     }
 
     #[tracing::instrument(skip_all)]
     async fn rename(&self, params: RenameParams) -> jsonrpc::Result<Option<WorkspaceEdit>> {
         let position = params.text_document_position;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            match snapshot.rename(position.convert()?, &params.new_name) {
-                Ok(edits) => Ok(Some(WorkspaceEdit {
-                    document_changes: Some(DocumentChanges::Edits(edits.convert())),
-                    ..Default::default()
-                })),
-                Err(err) => Err(err.convert()),
+        self.with_versioned_snapshot(|snapshot, versions| {
+            let indices = self.indices(snapshot);
+            match snapshot.rename(position.convert_with(&indices)?, &params.new_name) {
+                Ok(change) => Ok(Some(convert::workspace_edit(&indices, versions, &change))),
+                Err(err) => Err(err.convert_with(&indices)),
             }
         })
     }
@@ -396,88 +889,1288 @@ impl LanguageServer for Gqls {
         &self,
         params: SemanticTokensParams,
     ) -> jsonrpc::Result<Option<SemanticTokensResult>> {
+        if !self.is_enabled(ToggleableFeature::SemanticTokens) {
+            return Ok(None);
+        }
         let uri = params.text_document.uri;
-        self.with_ide(|ide| {
-            let snapshot = ide.snapshot();
-            let path = ide.vfs().path(&uri)?;
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&uri)?;
             Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
-                data: tokens::convert(&snapshot.semantic_tokens(path)),
+                data: tokens::convert(&indices.file_index(path), &snapshot.semantic_tokens(path)),
                 result_id: None,
             })))
         })
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn formatting(
+        &self,
+        params: DocumentFormattingParams,
+    ) -> jsonrpc::Result<Option<Vec<TextEdit>>> {
+        if !self.is_enabled(ToggleableFeature::Formatting) {
+            return Ok(None);
+        }
+        // unlike workspace edits, the edits of a formatting response aren't versioned, the client
+        // applies them to the version of the document it requested them for
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let config = self.settings.lock().formatting.config();
+            Ok(Some(snapshot.format(path, &config).convert_in(&indices.file_index(path))))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn code_action(
+        &self,
+        params: CodeActionParams,
+    ) -> jsonrpc::Result<Option<CodeActionResponse>> {
+        // an action is requested if its kind is (a subkind of) one of the requested kinds
+        let is_requested = |kind: &CodeActionKind| match &params.context.only {
+            Some(only) => only.iter().any(|only| {
+                let (kind, only) = (kind.as_str(), only.as_str());
+                kind == only || kind.starts_with(&format!("{only}."))
+            }),
+            None => true,
+        };
+        self.with_versioned_snapshot(|snapshot, versions| {
+            let indices = self.indices(snapshot);
+            let workspace_edit =
+                |change: SourceChange| Some(convert::workspace_edit(&indices, versions, &change));
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let range = params.range.convert_in(&indices.file_index(path));
+            let mut actions = vec![];
+            let diagnostics = if is_requested(&CodeActionKind::QUICKFIX) {
+                snapshot.file_diagnostics(path)
+            } else {
+                Default::default()
+            };
+            for diagnostic in diagnostics {
+                // the requested range is often just the cursor
+                if diagnostic.range.end < range.start || range.end < diagnostic.range.start {
+                    continue;
+                }
+                let lsp_diagnostic = convert::convert_diagnostic(&indices, path, &diagnostic);
+                for fix in &diagnostic.fixes {
+                    let change = SourceChange::single(path, vec![fix.patch.clone()]);
+                    actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                        title: fix.title.clone(),
+                        kind: Some(CodeActionKind::QUICKFIX),
+                        diagnostics: Some(vec![lsp_diagnostic.clone()]),
+                        edit: workspace_edit(change),
+                        is_preferred: Some(true),
+                        ..Default::default()
+                    }));
+                }
+            }
+
+            let allowed = AssistKind::ALL.into_iter().filter(|kind| is_requested(&kind.convert()));
+            // the edits of the assists are only built once the client resolves one of them, if
+            // it can
+            let resolve = match self.resolve_code_actions.get() {
+                Some(true) => AssistResolveStrategy::None,
+                _ => AssistResolveStrategy::All,
+            };
+            let config = AssistConfig { allowed: Some(allowed.collect()), resolve };
+            for assist in snapshot.assists(&config, path, range) {
+                let data = AssistData {
+                    text_document: params.text_document.clone(),
+                    range: params.range,
+                    id: assist.id.0.to_owned(),
+                };
+                let rename_at = self.rename_at.get().copied().unwrap_or_default();
+                let action = convert::convert_assist(&indices, versions, &assist, data, rename_at);
+                actions.push(CodeActionOrCommand::CodeAction(action));
+            }
+            Ok(Some(actions))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn code_action_resolve(&self, action: CodeAction) -> jsonrpc::Result<CodeAction> {
+        let data = action
+            .data
+            .and_then(|data| serde_json::from_value::<AssistData>(data).ok())
+            .ok_or_else(|| jsonrpc::Error::invalid_params("invalid code action data"))?;
+        self.with_versioned_snapshot(|snapshot, versions| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&data.text_document.uri)?;
+            let range = data.range.convert_in(&indices.file_index(path));
+            let resolve = AssistResolveStrategy::Single(data.id.clone());
+            let config = AssistConfig { allowed: None, resolve };
+            let assist = snapshot
+                .assists(&config, path, range)
+                .into_iter()
+                .find(|assist| assist.id.0 == data.id)
+                .ok_or_else(|| {
+                    jsonrpc::Error::invalid_params(format!("`{}` no longer applies", data.id))
+                })?;
+            let rename_at = self.rename_at.get().copied().unwrap_or_default();
+            Ok(convert::convert_assist(&indices, versions, &assist, data.clone(), rename_at))
+        })
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SyntaxTreeParams {
     pub text_document: VersionedTextDocumentIdentifier,
+    /// only the tree of the smallest node that covers the range, if given
+    #[serde(default)]
+    pub range: Option<Range>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileSummaryParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+/// Stashed in the code action of an assist so its edit can be built once it is resolved
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AssistData {
+    pub text_document: TextDocumentIdentifier,
+    /// the range the assists were requested for
+    pub range: Range,
+    pub id: String,
+}
+
+/// Stashed in a code lens so it can be resolved later
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CodeLensData {
+    pub text_document_position: TextDocumentPositionParams,
+    pub kind: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentFeaturesParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+/// Every known feature is present in `features` so clients can tell what is explicitly unsupported
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DocumentFeaturesResponse {
+    pub kind: String,
+    pub features: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchemaGraphParams {
+    /// the graph is of the schema of the project(s) of the document
+    pub text_document: TextDocumentIdentifier,
+    /// defaults to json, in which case the result is the graph itself rather than a string
+    #[serde(default)]
+    pub format: Option<GraphFormat>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeInfoParams {
+    /// the type is looked up in the project(s) of the document
+    pub text_document: TextDocumentIdentifier,
+    pub name: String,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingsParams {
+    /// clear the timings once they are returned
+    #[serde(default)]
+    pub reset: bool,
 }
 
 impl Gqls {
+    #[tracing::instrument(skip_all)]
     async fn syntax_tree(&self, params: SyntaxTreeParams) -> jsonrpc::Result<String> {
-        self.with_ide(|ide| {
-            let path = ide.vfs().path(&params.text_document.uri)?;
-            let snapshot = ide.snapshot();
-            Ok(snapshot.syntax_tree(path))
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let range = params.range.map(|range| range.convert_in(&indices.file_index(path)));
+            Ok(snapshot.syntax_tree(path, range))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn file_summary(&self, params: FileSummaryParams) -> jsonrpc::Result<String> {
+        self.with_snapshot(|snapshot| {
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            Ok(snapshot.file_summary(path).to_string())
         })
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn document_features(
+        &self,
+        params: DocumentFeaturesParams,
+    ) -> jsonrpc::Result<DocumentFeaturesResponse> {
+        let settings = self.settings.lock().clone();
+        self.with_snapshot(|snapshot| {
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let mut features = snapshot.document_features(path);
+            settings.mask_features(&mut features);
+            Ok(features.convert())
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn next_definition(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> jsonrpc::Result<Option<Location>> {
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let location = snapshot.goto_next_definition(params.convert_with(&indices)?);
+            Ok(location.map(|location| location.convert_with(&indices)))
+        })
+    }
+
+    /// The `extend` blocks of the type under the cursor, to audit how it is modified
+    #[tracing::instrument(skip_all)]
+    async fn extensions(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> jsonrpc::Result<Vec<Location>> {
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let locations = snapshot.extensions(params.convert_with(&indices)?);
+            Ok(locations.convert_with(&indices))
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn schema_graph(&self, params: SchemaGraphParams) -> jsonrpc::Result<serde_json::Value> {
+        self.with_snapshot(|snapshot| {
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let graph = SchemaGraph::new(snapshot, path);
+            Ok(match params.format.unwrap_or(GraphFormat::Json) {
+                GraphFormat::Json =>
+                    serde_json::to_value(graph).expect("the graph is serializable"),
+                GraphFormat::Dot => serde_json::Value::String(graph.to_dot()),
+            })
+        })
+    }
+
+    /// The type `name` with its extensions merged, for code generators to query the live analysis
+    #[tracing::instrument(skip_all)]
+    async fn type_info(
+        &self,
+        params: TypeInfoParams,
+    ) -> jsonrpc::Result<Option<serde_json::Value>> {
+        self.with_snapshot(|snapshot| {
+            let indices = self.indices(snapshot);
+            let path = VfsProxy.path(&params.text_document.uri)?;
+            let info = snapshot.type_info(path, &params.name);
+            Ok(info.map(|info| info.convert_with(&indices)))
+        })
+    }
+
+    // the timings of the spans of the requests and the queries they execute, see `TimingsLayer`
+    #[tracing::instrument(skip_all)]
+    async fn timings(&self, params: TimingsParams) -> jsonrpc::Result<Vec<Timing>> {
+        let timings = TIMINGS.timings();
+        if params.reset {
+            TIMINGS.reset();
+        }
+        Ok(timings)
+    }
+
+    /// Index and validate every file of `projects` and publish their diagnostics, including the
+    /// files that aren't open. Cancellation is checked between files, and progress is reported
+    /// per file as this can take a while for large workspaces. The returned future doesn't borrow
+    /// the server so it can be spawned.
+    fn analyze_workspace(&self, projects: HashSet<Project>) -> impl Future<Output = ()> {
+        let generation = self.scheduler.generation();
+        let snapshot = self.ide.lock().snapshot();
+        let supported = self.work_done_progress.get().copied().unwrap_or_default();
+        let encoding = self.position_encoding();
+        let client = self.client.0.clone();
+        let cache = Arc::clone(&self.semantic_diagnostics.0);
+        let baselines = Arc::clone(&self.baselines.0);
+        let published = Arc::clone(&self.published.0);
+        let scheduler = self.scheduler.clone();
+        async move {
+            let files = snapshot.files_of(&projects);
+            let total = files.len() * 3;
+
+            let progress = ProgressReporter::create(&client, supported, "gqls").await;
+            let mut syntax = Diagnostics::default();
+            let mut fresh = Diagnostics::default();
+            let passes = [
+                (DiagnosticPass::Syntax, "parsing"),
+                (DiagnosticPass::Local, "indexing"),
+                (DiagnosticPass::Project, "validating"),
+            ];
+            for (n, (pass, verb)) in passes.into_iter().enumerate() {
+                for (i, &file) in files.iter().enumerate() {
+                    let message = format!("{verb} {} files", files.len());
+                    progress.report(message, n * files.len() + i, total).await;
+                    // a change made in the meantime cancels the snapshot (and publishes its own
+                    // diagnostics), which unwinds from the next query
+                    match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        snapshot.file_pass_diagnostics(file, pass)
+                    })) {
+                        Ok(file_diagnostics) => {
+                            let diagnostics = match pass {
+                                DiagnosticPass::Syntax => &mut syntax,
+                                DiagnosticPass::Local | DiagnosticPass::Project => &mut fresh,
+                            };
+                            diagnostics.entry(file).or_default().extend(file_diagnostics);
+                        }
+                        Err(_) => {
+                            tracing::info!("workspace analysis was cancelled");
+                            progress.end(Some("cancelled".to_owned())).await;
+                            return;
+                        }
+                    }
+                }
+            }
+            let cancelled = {
+                let published = published.lock();
+                std::panic::catch_unwind(AssertUnwindSafe(|| {
+                    published_diagnostics(&snapshot, &published, &projects, &mut fresh)
+                }))
+                .is_err()
+            };
+            if cancelled {
+                tracing::info!("workspace analysis was cancelled");
+                progress.end(Some("cancelled".to_owned())).await;
+                return;
+            }
+
+            for (root, baseline) in baselines.lock().iter() {
+                baseline.suppress(root, &mut fresh);
+            }
+            let is_current = {
+                let mut cache = cache.lock();
+                let is_current = scheduler.is_current(generation);
+                if is_current {
+                    cache.extend(fresh.clone());
+                }
+                is_current
+            };
+            if is_current {
+                let indices = SnapshotIndices { snapshot: &snapshot, encoding };
+                if let Ok(params) = diagnostics_params(&indices, &syntax, &fresh, false) {
+                    publish_diagnostics(&client, params).await;
+                }
+            }
+            progress.end(Some(format!("analyzed {} files", files.len()))).await;
+        }
+    }
+
+    /// Utf-16 until negotiated on initialization
+    fn position_encoding(&self) -> PositionEncoding {
+        self.position_encoding.get().copied().unwrap_or_default()
+    }
+
+    fn indices<'a>(&self, snapshot: &'a Snapshot) -> SnapshotIndices<'a> {
+        SnapshotIndices { snapshot, encoding: self.position_encoding() }
+    }
+
+    /// The configured cache directory, see [`Settings::cache_directory`]
+    fn cache_directory(&self) -> Option<PathBuf> {
+        let dir = self.settings.lock().cache_directory.clone()?;
+        if dir.is_absolute() {
+            return Some(dir);
+        }
+        let root = self.workspace_folders.lock().as_ref()?.first()?.uri.to_path().ok()?;
+        Some(root.join(dir))
+    }
+
+    // the cache only saves work, so a cache that can't be read or written is just skipped
+    fn load_index_cache(&self) {
+        let dir = match self.cache_directory() {
+            Some(dir) => dir,
+            None => return,
+        };
+        match self.ide.lock().load_index_cache(&dir) {
+            Ok(n) => tracing::info!("loaded {n} files from the index cache"),
+            Err(err) => tracing::warn!(%err, "ignoring the index cache in `{}`", dir.display()),
+        }
+    }
+
+    fn is_enabled(&self, feature: ToggleableFeature) -> bool {
+        self.settings.lock().is_enabled(feature)
+    }
+
+    // Statically advertised capabilities can't be withdrawn, so only the dynamically registered
+    // features are (un)registered, the handlers of the others just stop responding.
+    async fn update_registrations(&self, previous: Option<&Settings>, settings: &Settings) {
+        let dynamic_features = match self.dynamic_features.get() {
+            Some(features) => features,
+            None => return,
+        };
+        let mut registrations = vec![];
+        let mut unregistrations = vec![];
+        for &feature in dynamic_features {
+            let was_enabled = previous.map_or(false, |previous| previous.is_enabled(feature));
+            match (was_enabled, settings.is_enabled(feature)) {
+                (false, true) => registrations.push(registration(feature)),
+                (true, false) => unregistrations.push(Unregistration {
+                    id: feature.method().to_owned(),
+                    method: feature.method().to_owned(),
+                }),
+                _ => {}
+            }
+        }
+        if !registrations.is_empty() {
+            if let Err(err) = self.client.register_capability(registrations).await {
+                tracing::error!(%err, "failed to register capabilities");
+            }
+        }
+        if !unregistrations.is_empty() {
+            if let Err(err) = self.client.unregister_capability(unregistrations).await {
+                tracing::error!(%err, "failed to unregister capabilities");
+            }
+        }
+    }
+
+    // The watchers depend on the configuration, so they are registered once it has been read and
+    // `replace`d whenever it changes.
+    async fn register_watchers(&self, replace: bool) {
+        let workspaces = match &*self.workspace_folders.lock() {
+            Some(workspaces) if self.watch_files.get() == Some(&true) => workspaces.clone(),
+            _ => return,
+        };
+        let watchers = match workspace_watchers(workspaces) {
+            Ok(watchers) => watchers,
+            Err(err) => {
+                tracing::error!(%err, "failed to read the files to watch");
+                return;
+            }
+        };
+        if replace {
+            let unregistration = Unregistration {
+                id: DID_CHANGE_WATCHED_FILES.to_owned(),
+                method: DID_CHANGE_WATCHED_FILES.to_owned(),
+            };
+            if let Err(err) = self.client.unregister_capability(vec![unregistration]).await {
+                tracing::error!(%err, "failed to unregister the file watchers");
+            }
+        }
+        let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+        let registration = Registration {
+            id: DID_CHANGE_WATCHED_FILES.to_owned(),
+            method: DID_CHANGE_WATCHED_FILES.to_owned(),
+            register_options: Some(serde_json::to_value(options).expect("serializable")),
+        };
+        if let Err(err) = self.client.register_capability(vec![registration]).await {
+            tracing::error!(%err, "failed to register the file watchers");
+        }
+    }
+
+    // Only the contents of known files are updated in place, anything that can change which files
+    // belong to which projects (the configuration, creations and deletions) reloads the workspace.
+    async fn handle_did_change_watched_files(
+        &self,
+        params: DidChangeWatchedFilesParams,
+    ) -> Result<()> {
+        let WatchedChanges { reload, config_changed, changed } = watched_changes(params.changes)?;
+        if reload || config_changed {
+            let projects = self.reinit()?;
+            if config_changed {
+                self.register_watchers(true).await;
+            }
+            self.analyze_workspace(projects).await;
+            return Ok(());
+        }
+
+        let mut contents = vec![];
+        for path in changed {
+            let text = read_lossy(&path)?;
+            contents.push((path, text));
+        }
+        let summary = self.with_ide(|ide| {
+            let mut changeset = Changeset::default();
+            for (path, text) in &contents {
+                let file = match ide.vfs().get(path) {
+                    Some(file) => file,
+                    None => continue,
+                };
+                changeset = changeset.with_change(disk_change(ide, file, text.clone()));
+            }
+            Ok(ide.apply(changeset))
+        })?;
+        self.send_diagnostics(summary).await;
+        Ok(())
+    }
+
+    // The files of added folders are indexed by reloading the workspace, which also drops the
+    // files that only belonged to removed folders from the projects. Nothing would ever publish
+    // diagnostics for those again, so they are cleared rather than left behind in the client.
+    async fn handle_did_change_workspace_folders(
+        &self,
+        event: WorkspaceFoldersChangeEvent,
+    ) -> Result<()> {
+        match &mut *self.workspace_folders.lock() {
+            Some(workspaces) => {
+                workspaces.retain(|workspace| {
+                    !event.removed.iter().any(|removed| removed.uri == workspace.uri)
+                });
+                for added in event.added {
+                    if !workspaces.iter().any(|workspace| workspace.uri == added.uri) {
+                        workspaces.push(added);
+                    }
+                }
+            }
+            // the folders are read on initialization
+            None => return Ok(()),
+        }
+
+        let loaded_files =
+            |ide: &Ide| ide.snapshot().all_files().into_iter().collect::<HashSet<_>>();
+        let previous = loaded_files(&self.ide.lock());
+        let projects = self.reinit()?;
+        let current = loaded_files(&self.ide.lock());
+        let evicted = previous.difference(&current).copied().collect::<Vec<_>>();
+        if !evicted.is_empty() {
+            let mut cache = self.semantic_diagnostics.lock();
+            for file in &evicted {
+                cache.remove(file);
+            }
+        }
+        let params = evicted
+            .iter()
+            .map(|file| PublishDiagnosticsParams {
+                uri: file.to_url(),
+                diagnostics: vec![],
+                version: None,
+            })
+            .collect();
+        publish_diagnostics(&self.client, params).await;
+
+        self.register_watchers(true).await;
+        self.analyze_workspace(projects).await;
+        Ok(())
+    }
+
     async fn handle_did_change(&self, params: DidChangeTextDocumentParams) -> Result<()> {
         let path = params.text_document.uri.to_path()?;
         tracing::info!("path: {path:?}");
+        // introspection results are read-only sources, they are only reloaded on save
+        if is_introspection_file(&path) {
+            return Ok(());
+        }
+        let encoding = self.position_encoding();
         let summary = self.with_ide(|ide| {
             let path = ide.intern_path(path.clone());
-            let changes = params
-                .content_changes
-                .iter()
-                .map(|change| match change.range {
-                    Some(range) => ChangeKind::Patch(Patch {
-                        range: range.convert(),
-                        with: change.text.clone(),
-                    }),
+            self.versions.lock().insert(path, params.text_document.version);
+            // the range of each change is relative to the text after the previous changes, so
+            // the changes are converted (with the line index of that text) and loaded one by one
+            let (last, changes) = match params.content_changes.split_last() {
+                Some(changes) => changes,
+                None => return Ok(ide.apply(Changeset::default())),
+            };
+            let to_change = |ide: &Ide, change: &TextDocumentContentChangeEvent| {
+                let kind = match change.range {
+                    Some(range) => {
+                        let snapshot = ide.snapshot();
+                        let indices = SnapshotIndices { snapshot: &snapshot, encoding };
+                        let range = range.convert_in(&indices.file_index(path));
+                        ChangeKind::Patch(Patch { range, with: change.text.clone() })
+                    }
                     None => ChangeKind::Set(change.text.clone()),
-                })
-                .map(|kind| Change::new(path, kind))
-                .collect();
-            let changeset = Changeset::new(changes);
-            Ok(ide.apply(changeset))
+                };
+                Change::new(path, kind)
+            };
+            for change in changes {
+                let change = to_change(ide, change);
+                ide.load(change);
+            }
+            let change = to_change(ide, last);
+            let summary = ide.apply(change);
+            ide.vfs().set_overlay(path, ide.snapshot().file_text(path).to_string());
+            Ok(summary)
         })?;
         self.send_diagnostics(summary).await;
         Ok(())
     }
 
+    // closing a buffer (dirty or not) reverts the file to its contents on disk
+    async fn handle_did_close(&self, params: DidCloseTextDocumentParams) -> Result<()> {
+        let path = params.text_document.uri.to_path()?;
+        if is_introspection_file(&path) {
+            return Ok(());
+        }
+        // a buffer that was never saved (or whose file was deleted) leaves nothing behind
+        let text = match read_lossy(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Default::default(),
+            Err(err) => return Err(err.into()),
+        };
+        let summary = self.with_ide(|ide| {
+            let file = ide.intern_path(path.clone());
+            self.versions.lock().remove(&file);
+            ide.vfs().remove_overlay(file);
+            Ok(ide.apply(disk_change(ide, file, text.clone())))
+        })?;
+        self.send_diagnostics(summary).await;
+        Ok(())
+    }
+
+    // The syntax diagnostics are published immediately along with the previous semantic
+    // diagnostics marked as stale. Once no change has been made for the configured debounce,
+    // the local and project passes are recomputed in the background.
     #[tracing::instrument(skip_all)]
     async fn send_diagnostics(&self, summary: ChangesetSummary) {
         tracing::info!("emitting diagnostics");
-        for (path, diagnostics) in summary.diagnostics {
-            let diagnostics = diagnostics.iter().map(Convert::convert).collect::<Vec<_>>();
-            self.client
-                .send_notification::<PublishDiagnostics>(PublishDiagnosticsParams {
-                    uri: path.to_url(),
-                    diagnostics,
-                    version: None,
+        let generation = self.scheduler.bump();
+        let stale = self.semantic_diagnostics.lock().clone();
+        let encoding = self.position_encoding();
+        {
+            let snapshot = self.ide.lock().snapshot();
+            // a newer change cancels the snapshot and publishes its own diagnostics
+            let indices = SnapshotIndices { snapshot: &snapshot, encoding };
+            if let Ok(params) = diagnostics_params(&indices, &summary.diagnostics, &stale, true) {
+                publish_diagnostics(&self.client, params).await;
+            }
+        }
+
+        let debounce = Duration::from_millis(self.settings.lock().diagnostics.debounce);
+        let client = self.client.0.clone();
+        let ide = Arc::clone(&self.ide.0);
+        let cache = Arc::clone(&self.semantic_diagnostics.0);
+        let baselines = Arc::clone(&self.baselines.0);
+        let published = Arc::clone(&self.published.0);
+        let scheduler = self.scheduler.clone();
+        tokio::spawn(async move {
+            let ChangesetSummary { diagnostics: syntax, projects } = summary;
+            // the snapshot is only taken once debounced as it would block the changes made in
+            // the meantime, a change made while the pass runs cancels it
+            let pass = scheduler.debounce(generation, debounce, || {
+                let snapshot = ide.lock().snapshot();
+                tokio::task::spawn_blocking(move || {
+                    // a newer change cancels the snapshot which unwinds
+                    std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        let fresh = semantic_diagnostics(
+                            &snapshot,
+                            &projects,
+                            &published.lock(),
+                            &baselines.lock(),
+                        );
+                        let indices = SnapshotIndices { snapshot: &snapshot, encoding };
+                        diagnostics_params(&indices, &syntax, &fresh, false)
+                            .map(|params| (fresh, params))
+                    }))
                 })
-                .await;
+            });
+            let (fresh, params) = match pass.await {
+                Some(Ok(Ok(Ok(pass)))) => pass,
+                Some(_) => {
+                    tracing::info!("background diagnostics pass was cancelled");
+                    return;
+                }
+                None => {
+                    tracing::debug!("background diagnostics pass was superseded");
+                    return;
+                }
+            };
+
+            {
+                let mut cache = cache.lock();
+                if !scheduler.is_current(generation) {
+                    return;
+                }
+                cache.extend(fresh);
+            }
+            tracing::debug!(runs = scheduler.runs(), "publishing background diagnostics");
+            publish_diagnostics(&client, params).await;
+        });
+    }
+}
+
+/// The [`DiagnosticPass::Local`] and [`DiagnosticPass::Project`] diagnostics of `projects`,
+/// including their breaking changes and excluding the lints suppressed by the baselines
+fn semantic_diagnostics(
+    snapshot: &Snapshot,
+    projects: &HashSet<Project>,
+    published: &HashMap<Project, PublishedSchema>,
+    baselines: &[(PathBuf, Baseline)],
+) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+    for &project in projects {
+        for pass in [DiagnosticPass::Local, DiagnosticPass::Project] {
+            for (file, file_diagnostics) in snapshot.pass_diagnostics(project, pass) {
+                diagnostics.entry(file).or_default().extend(file_diagnostics);
+            }
+        }
+    }
+    published_diagnostics(snapshot, published, projects, &mut diagnostics);
+    for (root, baseline) in baselines {
+        baseline.suppress(root, &mut diagnostics);
+    }
+    diagnostics
+}
+
+/// Add the breaking changes of the `projects` that have a published schema to `diagnostics`
+fn published_diagnostics(
+    snapshot: &Snapshot,
+    published: &HashMap<Project, PublishedSchema>,
+    projects: &HashSet<Project>,
+    diagnostics: &mut Diagnostics,
+) {
+    for project in projects {
+        let schema = match published.get(project) {
+            Some(schema) => schema,
+            None => continue,
+        };
+        for (file, breaking) in schema.diagnostics(snapshot, *project) {
+            diagnostics.entry(file).or_default().extend(breaking);
         }
     }
 }
 
-fn read_config(path: &Path) -> anyhow::Result<Option<Config>> {
+/// The `syntax` diagnostics of each file along with its `semantic` diagnostics. The positions are
+/// converted with the line indices of the snapshot, so it must be the one they were computed with.
+fn diagnostics_params(
+    indices: &SnapshotIndices<'_>,
+    syntax: &Diagnostics,
+    semantic: &Diagnostics,
+    stale: bool,
+) -> Cancellable<Vec<PublishDiagnosticsParams>> {
+    indices.snapshot.catch_cancelled(|_| {
+        syntax
+            .iter()
+            .map(|(&path, diagnostics)| {
+                let semantic_diagnostics =
+                    semantic.get(path).into_iter().flatten().map(|diagnostic| {
+                        let mut diagnostic =
+                            convert::convert_diagnostic(indices, path, diagnostic);
+                        if stale {
+                            diagnostic.source = Some("gqls (stale)".to_owned());
+                        }
+                        diagnostic
+                    });
+                let diagnostics = diagnostics
+                    .iter()
+                    .map(|diagnostic| convert::convert_diagnostic(indices, path, diagnostic))
+                    .chain(semantic_diagnostics)
+                    .collect();
+                PublishDiagnosticsParams { uri: path.to_url(), diagnostics, version: None }
+            })
+            .collect()
+    })
+}
+
+async fn publish_diagnostics(client: &Client, params: Vec<PublishDiagnosticsParams>) {
+    for params in params {
+        client.send_notification::<PublishDiagnostics>(params).await;
+    }
+}
+
+fn save_index_cache(ide: &Mutex<Ide>, dir: Option<PathBuf>) {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => return,
+    };
+    if let Err(err) = ide.lock().save_index_cache(&dir) {
+        tracing::warn!(%err, "failed to write the index cache to `{}`", dir.display());
+    }
+}
+
+fn is_config_file(path: &Path) -> bool {
+    path.file_stem() == Some(".graphqlrc".as_ref())
+}
+
+fn is_directory_config_file(path: &Path) -> bool {
+    path.file_name() == Some(DIRECTORY_CONFIG.as_ref())
+}
+
+/// What the watched file events call for: the workspace is reinitialized if a file was created,
+/// deleted or is an introspection result, and the watchers as well if a configuration changed.
+/// Otherwise only the texts of the `changed` files are read again.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct WatchedChanges {
+    reload: bool,
+    config_changed: bool,
+    changed: Vec<PathBuf>,
+}
+
+fn watched_changes(events: Vec<FileEvent>) -> anyhow::Result<WatchedChanges> {
+    let mut changes = WatchedChanges::default();
+    for event in events {
+        let path = event.uri.to_path()?;
+        if is_config_file(&path) || is_directory_config_file(&path) {
+            changes.config_changed = true;
+        } else if event.typ != FileChangeType::CHANGED || is_introspection_file(&path) {
+            changes.reload = true;
+        } else {
+            changes.changed.push(path);
+        }
+    }
+    Ok(changes)
+}
+
+pub(crate) fn read_config(path: &Path) -> anyhow::Result<Option<Config>> {
     assert!(path.is_dir());
     for entry in std::fs::read_dir(path)? {
         let entry = entry?;
         let path = entry.path();
-        if entry.file_type()?.is_file() && path.file_stem() == Some(".graphqlrc".as_ref()) {
+        if entry.file_type()?.is_file() && is_config_file(&path) {
             return Ok(Some(Config::read(&path)?));
         }
     }
     Ok(None)
 }
 
+/// Discover the projects of the workspaces and load all of their files into `ide`
+pub(crate) fn load_workspaces(
+    ide: &mut Ide,
+    workspaces: Vec<WorkspaceFolder>,
+) -> anyhow::Result<ChangesetSummary> {
+    let changeset = workspace_changeset(ide, workspaces, &Settings::default())?;
+    Ok(ide.apply(changeset))
+}
+
+/// The changeset that sets up the projects of the workspaces with all of their files
+fn workspace_changeset(
+    ide: &mut Ide,
+    workspaces: Vec<WorkspaceFolder>,
+    settings: &Settings,
+) -> anyhow::Result<Changeset> {
+    let max_file_size = discover_max_file_size(workspaces.clone())?;
+    // the directory configurations of every workspace, the files are only walked once for them
+    let directory_configs = discover_directory_configs(workspaces.clone())?;
+    let (mut generated, generated_globs) = discover_generated(workspaces.clone())?;
+    let mut changeset = Changeset::default();
+    let mut projects = HashMap::<Project, HashSet<FileId>>::default();
+    let mut lints = HashMap::default();
+    let mut field_sets = HashMap::default();
+    let mut scalars = HashMap::default();
+    let mut root_types = HashMap::default();
+    let mut import_projects = HashSet::default();
+    // the projects of the same name in each of the folders
+    let mut groups = HashMap::<String, HashSet<Project>>::default();
+    for (folder, workspaces) in project_scopes(workspaces) {
+        let discovered =
+            discover_projects(workspaces.clone(), &settings.exclude, &directory_configs)?;
+        let intern = |ide: &mut Ide, project: &str| {
+            ide.intern_project(qualify_project(folder.as_ref(), project))
+        };
+        let discovered_lints =
+            discover_lints(workspaces.clone(), discovered.keys(), &settings.lints)?;
+        for (project, project_lints) in discovered_lints {
+            lints.insert(intern(ide, &project), project_lints);
+        }
+        for (project, directives) in discover_field_sets(workspaces.clone(), discovered.keys())? {
+            field_sets.insert(intern(ide, &project), directives);
+        }
+        for (project, registry) in discover_custom_scalars(workspaces.clone(), discovered.keys())? {
+            scalars.insert(intern(ide, &project), registry);
+        }
+        for (project, names) in discover_root_types(workspaces.clone(), discovered.keys())? {
+            root_types.insert(intern(ide, &project), names);
+        }
+        for project in discover_import_projects(workspaces, discovered.keys())? {
+            import_projects.insert(intern(ide, &project));
+        }
+
+        // the open buffers shadow the files on disk
+        for (name, files) in discovered {
+            let project = intern(ide, &name);
+            groups.entry(name).or_default().insert(project);
+            let project_files = projects.entry(project).or_default();
+            for (path, content) in files {
+                let file = ide.intern_path(path);
+                project_files.insert(file);
+                changeset = changeset.with_change(disk_change(ide, file, content))
+            }
+        }
+    }
+
+    let files = projects.values().flatten().copied();
+    generated.files = files.filter(|file| is_matched(&generated_globs, file)).collect();
+
+    let groups = match settings.workspace_folders {
+        WorkspaceFolderMode::Stitched =>
+            groups.into_values().filter(|group| group.len() > 1).collect(),
+        WorkspaceFolderMode::Isolated => vec![],
+    };
+    Ok(changeset
+        .with_projects(projects)
+        .with_project_groups(groups)
+        .with_lints(lints)
+        .with_directory_lints(directory_lints(&directory_configs))
+        .with_field_set_directives(field_sets)
+        .with_custom_scalars(scalars)
+        .with_root_types(root_types)
+        .with_generated_files(generated)
+        .with_import_projects(import_projects)
+        .with_max_file_size(max_file_size))
+}
+
+/// The workspace folders whose projects are discovered together, along with the folder that
+/// qualifies the names of the projects. The projects of a single folder keep their names.
+fn project_scopes(
+    workspaces: Vec<WorkspaceFolder>,
+) -> Vec<(Option<WorkspaceFolder>, Vec<WorkspaceFolder>)> {
+    if workspaces.len() <= 1 {
+        return vec![(None, workspaces)];
+    }
+    workspaces.into_iter().map(|workspace| (Some(workspace.clone()), vec![workspace])).collect()
+}
+
+// qualified by the uri rather than the name of the folder, which needn't be unique
+fn qualify_project(folder: Option<&WorkspaceFolder>, project: &str) -> String {
+    match folder {
+        Some(folder) => gqls_ide::qualify_project(folder.uri.as_str(), project),
+        None => project.to_owned(),
+    }
+}
+
+/// The files of the workspaces the client notifies the server about: the configuration and the
+/// files it matches, or every graphql file if there is none
+fn workspace_watchers(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<Vec<FileSystemWatcher>> {
+    let mut watchers = vec![];
+    for workspace in workspaces {
+        let root = workspace.uri.to_path()?;
+        let config = read_config(&root)?;
+        let globs = match &config {
+            Some(config) => config.schema_globs(),
+            None => vec!["**/*.graphql"],
+        };
+        for glob in [".graphqlrc*", "**/.gqlsrc.toml"].into_iter().chain(globs) {
+            let glob_pattern = format!("{}/{glob}", root.display());
+            watchers.push(FileSystemWatcher { glob_pattern, kind: None });
+        }
+    }
+    Ok(watchers)
+}
+
+// a baseline that can't be read is skipped rather than failing the entire workspace
+fn discover_baselines(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<Vec<(PathBuf, Baseline)>> {
+    let mut baselines = vec![];
+    for workspace in workspaces {
+        let root = workspace.uri.to_path()?;
+        let config = read_config(&root)?;
+        let path = match config.as_ref().and_then(Config::baseline) {
+            Some(path) => root.join(path),
+            None => continue,
+        };
+        match Baseline::read(&path) {
+            Ok(baseline) => baselines.push((root, baseline)),
+            Err(err) => tracing::warn!(%err, "ignoring baseline `{}`", path.display()),
+        }
+    }
+    Ok(baselines)
+}
+
+// a published schema that can't be loaded (e.g. the endpoint is down) is skipped as well
+fn discover_published(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<Vec<(String, PublishedSchema)>> {
+    let mut schemas = vec![];
+    for workspace in workspaces {
+        let root = workspace.uri.to_path()?;
+        let config = match read_config(&root)? {
+            Some(config) => config,
+            None => continue,
+        };
+        for (project, published) in config.published() {
+            match PublishedSchema::load(&root, published) {
+                Ok(schema) => schemas.push((project.to_owned(), schema)),
+                Err(err) =>
+                    tracing::warn!(%err, "ignoring published schema `{}`", published.schema),
+            }
+        }
+    }
+    Ok(schemas)
+}
+
+/// The default lints and those enabled by the configuration of each project, with the `levels` of
+/// the client settings applied on top
+fn discover_lints<'a>(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+    projects: impl IntoIterator<Item = &'a String> + Clone,
+    levels: &BTreeMap<String, LintLevel>,
+) -> anyhow::Result<HashMap<String, HashSet<Lint>>> {
+    let mut lints = projects
+        .clone()
+        .into_iter()
+        .map(|project| (project.to_owned(), HashSet::from(Lint::DEFAULT)))
+        .collect::<HashMap<_, _>>();
+    for workspace in workspaces {
+        let config = match read_config(&workspace.uri.to_path()?)? {
+            Some(config) => config,
+            None => continue,
+        };
+        for project in projects.clone() {
+            for name in config.lints(project) {
+                match name.parse() {
+                    Ok(lint) => {
+                        lints.entry(project.to_owned()).or_default().insert(lint);
+                    }
+                    Err(err) => tracing::warn!(%err, "ignoring lint in project `{project}`"),
+                }
+            }
+        }
+    }
+    for (name, &level) in levels {
+        let lint = match name.parse::<Lint>() {
+            Ok(lint) => lint,
+            Err(err) => {
+                tracing::warn!(%err, "ignoring lint level in the settings");
+                continue;
+            }
+        };
+        for project in projects.clone() {
+            let project_lints = lints.entry(project.to_owned()).or_default();
+            match level {
+                LintLevel::Allow => project_lints.remove(&lint),
+                LintLevel::Warn => project_lints.insert(lint),
+            };
+        }
+    }
+    Ok(lints)
+}
+
+// a directory configuration that can't be read is skipped rather than failing the workspace
+pub(crate) fn discover_directory_configs(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<Vec<(PathBuf, DirectoryConfig)>> {
+    let mut configs = vec![];
+    for workspace in workspaces {
+        let root = workspace.uri.to_path()?;
+        for entry in walkdir::WalkDir::new(&root)
+            .into_iter()
+            .filter_entry(|entry| !entry.path().ends_with(".git"))
+        {
+            let entry = entry?;
+            if !entry.file_type().is_file() || !is_directory_config_file(entry.path()) {
+                continue;
+            }
+            let dir = match entry.path().parent() {
+                Some(dir) => dir.to_path_buf(),
+                None => continue,
+            };
+            let path = entry.path();
+            match DirectoryConfig::read(path) {
+                Ok(config) => configs.push((dir, config)),
+                Err(err) => tracing::warn!(%err, "ignoring configuration `{}`", path.display()),
+            }
+        }
+    }
+    Ok(configs)
+}
+
+/// The lints that the directory configurations enable or disable for the files below them
+fn directory_lints(configs: &[(PathBuf, DirectoryConfig)]) -> HashMap<PathBuf, LintOverrides> {
+    let mut lints = HashMap::default();
+    for (dir, config) in configs {
+        let mut overrides = LintOverrides::default();
+        for (name, &level) in &config.lints {
+            let lint = match name.parse::<Lint>() {
+                Ok(lint) => lint,
+                Err(err) => {
+                    tracing::warn!(%err, "ignoring lint level in `{}`", dir.display());
+                    continue;
+                }
+            };
+            match level {
+                LintLevel::Allow => overrides.disabled.insert(lint),
+                LintLevel::Warn => overrides.enabled.insert(lint),
+            };
+        }
+        lints.insert(dir.clone(), overrides);
+    }
+    lints
+}
+
+fn discover_field_sets<'a>(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+    projects: impl IntoIterator<Item = &'a String> + Clone,
+) -> anyhow::Result<HashMap<String, Vec<FieldSetDirective>>> {
+    let mut directives = HashMap::<String, Vec<FieldSetDirective>>::default();
+    for workspace in workspaces {
+        let config = match read_config(&workspace.uri.to_path()?)? {
+            Some(config) => config,
+            None => continue,
+        };
+        for project in projects.clone() {
+            for field_set in config.field_sets(project) {
+                directives.entry(project.to_owned()).or_default().push(FieldSetDirective {
+                    name: format!("@{}", field_set.directive.trim_start_matches('@')),
+                    argument: field_set.argument.clone(),
+                    returned: field_set.returned,
+                });
+            }
+        }
+    }
+    Ok(directives)
+}
+
+fn discover_custom_scalars<'a>(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+    projects: impl IntoIterator<Item = &'a String> + Clone,
+) -> anyhow::Result<HashMap<String, CustomScalars>> {
+    let mut scalars = HashMap::<String, CustomScalars>::default();
+    for workspace in workspaces {
+        let config = match read_config(&workspace.uri.to_path()?)? {
+            Some(config) => config,
+            None => continue,
+        };
+        for project in projects.clone() {
+            for (name, scalar) in config.scalars(project).into_iter().flatten() {
+                scalars.entry(project.to_owned()).or_default().insert(
+                    name.clone(),
+                    CustomScalar {
+                        description: scalar.description.clone(),
+                        specified_by: scalar.specified_by.clone(),
+                    },
+                );
+            }
+        }
+    }
+    Ok(scalars)
+}
+
+fn discover_root_types<'a>(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+    projects: impl IntoIterator<Item = &'a String> + Clone,
+) -> anyhow::Result<HashMap<String, RootTypes>> {
+    let mut root_types = HashMap::<String, RootTypes>::default();
+    for workspace in workspaces {
+        let config = match read_config(&workspace.uri.to_path()?)? {
+            Some(config) => config,
+            None => continue,
+        };
+        for project in projects.clone() {
+            let names = match config.root_types(project) {
+                Some(names) => names,
+                None => continue,
+            };
+            let defaults = RootTypes::default();
+            root_types.insert(
+                project.to_owned(),
+                RootTypes {
+                    query: names.query.clone().unwrap_or(defaults.query),
+                    mutation: names.mutation.clone().unwrap_or(defaults.mutation),
+                    subscription: names.subscription.clone().unwrap_or(defaults.subscription),
+                },
+            );
+        }
+    }
+    Ok(root_types)
+}
+
+fn discover_import_projects<'a>(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+    projects: impl IntoIterator<Item = &'a String> + Clone,
+) -> anyhow::Result<HashSet<String>> {
+    let mut enforced = HashSet::default();
+    for workspace in workspaces {
+        let config = match read_config(&workspace.uri.to_path()?)? {
+            Some(config) => config,
+            None => continue,
+        };
+        let projects = projects.clone().into_iter();
+        enforced.extend(projects.filter(|project| config.enforces_imports(project)).cloned());
+    }
+    Ok(enforced)
+}
+
+// the preludes are only ever read from memory, but goto definition needs a file to open
+fn write_preludes() -> std::io::Result<()> {
+    let preludes = [
+        (&*gqls_ide::BUILTINS_PATH, gqls_ide::BUILTINS),
+        (&*gqls_ide::FEDERATION_PRELUDE_PATH, gqls_ide::FEDERATION_PRELUDE),
+    ];
+    for (path, prelude) in preludes {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, prelude)?;
+    }
+    Ok(())
+}
+
+/// The contents of a file with any invalid utf8 replaced, and whether any was (which is then
+/// reported as a diagnostic, see [`Change::lossy`])
+pub(crate) fn read_lossy(path: &Path) -> std::io::Result<(String, bool)> {
+    let bytes = std::fs::read(path)?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => (text, false),
+        Err(err) => (String::from_utf8_lossy(err.as_bytes()).into_owned(), true),
+    })
+}
+
+/// Set `file` to the `text` read from disk, unless the open buffer of the file shadows it
+fn disk_change(ide: &Ide, file: FileId, (text, lossy): (String, bool)) -> Change {
+    match ide.vfs().overlay(file) {
+        Some(buffer) => Change::set(file, buffer),
+        None => Change::set(file, text).with_lossy(lossy),
+    }
+}
+
+/// The graphql source of a file, converting introspection results into sdl. `None` if the file
+/// is json other than an introspection result, which is not a source of the project.
+pub(crate) fn file_content(path: &Path, text: String) -> anyhow::Result<Option<String>> {
+    if is_introspection_file(path) {
+        introspection::from_introspection_json(&text)
+    } else {
+        Ok(Some(text))
+    }
+}
+
+/// How generated files are recognized along with the globs of the generated files of each
+/// workspace root. The marker of the first workspace that configures one wins, as the database
+/// has a single one.
+pub(crate) fn discover_generated(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<(GeneratedFiles, Vec<(PathBuf, GlobSet)>)> {
+    let mut generated = GeneratedFiles::default();
+    let (mut marker, mut lines) = (None, None);
+    let mut globs = vec![];
+    for workspace in workspaces {
+        let root = workspace.uri.to_path()?;
+        let config = match read_config(&root)?.as_ref().and_then(Config::generated).cloned() {
+            Some(config) => config,
+            None => continue,
+        };
+        marker = marker.or(config.marker);
+        lines = lines.or(config.lines);
+        globs.push((root, compile_globs(&config.files)?));
+    }
+    generated.marker = marker.unwrap_or(generated.marker);
+    generated.lines = lines.unwrap_or(generated.lines);
+    Ok((generated, globs))
+}
+
+// the smallest limit wins when the workspaces disagree, as the database has a single one
+fn discover_max_file_size(
+    workspaces: impl IntoIterator<Item = WorkspaceFolder>,
+) -> anyhow::Result<usize> {
+    let mut max_file_size = None;
+    for workspace in workspaces {
+        let config = read_config(&workspace.uri.to_path()?)?;
+        if let Some(size) = config.as_ref().and_then(Config::max_file_size) {
+            max_file_size = Some(max_file_size.map_or(size, |max: usize| max.min(size)));
+        }
+    }
+    Ok(max_file_size.unwrap_or(gqls_ide::DEFAULT_MAX_FILE_SIZE))
+}
+
 fn discover_projects(
     workspaces: impl IntoIterator<Item = WorkspaceFolder>,
-) -> anyhow::Result<HashMap<String, Vec<(PathBuf, String)>>> {
+    exclude: &[String],
+    directory_configs: &[(PathBuf, DirectoryConfig)],
+) -> anyhow::Result<HashMap<String, Vec<(PathBuf, (String, bool))>>> {
+    let excluded = compile_globs(exclude)?;
+    // the globs excluded by a directory configuration are relative to its directory
+    let directory_excluded = directory_configs
+        .iter()
+        .map(|(dir, config)| Ok((dir.clone(), compile_globs(&config.exclude)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
     let mut projects = HashMap::default();
     for workspace in workspaces {
         let path = workspace.uri.to_path()?;
@@ -488,11 +2181,15 @@ fn discover_projects(
             .filter_entry(|entry| !entry.path().ends_with(".git"))
         {
             let entry = entry?;
-            if !entry.file_type().is_file() {
+            let relative = entry.path().strip_prefix(&path).unwrap();
+            if !entry.file_type().is_file()
+                || excluded.is_match(relative)
+                || is_matched(&directory_excluded, entry.path())
+            {
                 continue;
             }
             let file_projects = match &config {
-                Some(config) => config.project_matches(entry.path().strip_prefix(&path).unwrap()),
+                Some(config) => config.project_matches(relative),
                 // If configuration file is found, then all `*.graphql` files are assigned to the default project
                 None => (entry.path().extension() == Some("graphql".as_ref()))
                     .then(|| DEFAULT_PROJECT)
@@ -504,7 +2201,15 @@ fn discover_projects(
                 continue;
             }
             let path = entry.path().to_path_buf();
-            let content = std::fs::read_to_string(&path).unwrap();
+            let (text, lossy) = read_lossy(&path)?;
+            let content = match file_content(&path, text) {
+                Ok(Some(content)) => (content, lossy),
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!("skipping `{}`: {err}", path.display());
+                    continue;
+                }
+            };
             // FIXME shouldn't have to clone everything
             for file_project in file_projects {
                 projects
@@ -517,5 +2222,27 @@ fn discover_projects(
     Ok(projects)
 }
 
+/// Whether `path` is matched by the globs of one of the directories it is in, which are relative
+/// to the directory
+pub(crate) fn is_matched(globs: &[(PathBuf, GlobSet)], path: &Path) -> bool {
+    globs.iter().any(|(dir, globs)| {
+        path.strip_prefix(dir).map_or(false, |relative| globs.is_match(relative))
+    })
+}
+
+// an invalid glob is skipped rather than failing the workspace
+fn compile_globs(globs: &[String]) -> anyhow::Result<GlobSet> {
+    let mut set = GlobSetBuilder::new();
+    for glob in globs {
+        match Glob::new(glob.trim_start_matches("./")) {
+            Ok(glob) => {
+                set.add(glob);
+            }
+            Err(err) => tracing::warn!(%err, "ignoring invalid glob `{glob}`"),
+        }
+    }
+    Ok(set.build()?)
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,11 @@
-use maplit::hashmap;
+use gqls_ide::{Feature, Ide, Lint, LintOverrides};
+use maplit::{btreemap, hashmap, hashset};
+use std::path::{Path, PathBuf};
 use tower_lsp::lsp_types::{self, WorkspaceFolder};
 
 use crate::config::DEFAULT_PROJECT;
 use crate::lsp;
+use crate::settings::{FeatureSettings, LintLevel, Settings, WorkspaceFolderMode};
 
 macro_rules! fixture_path {
     ($name:literal) => {{
@@ -23,17 +26,18 @@ fn test_project_discovery() -> anyhow::Result<()> {
     let path = fixture_path!("simple");
     let config = lsp::read_config(&path)?;
     assert!(config.is_some());
-    let mut projects = lsp::discover_projects(std::iter::once(WorkspaceFolder {
-        uri: fixtures!("simple"),
-        name: String::new(),
-    }))?;
+    let mut projects = lsp::discover_projects(
+        std::iter::once(WorkspaceFolder { uri: fixtures!("simple"), name: String::new() }),
+        &[],
+        &[],
+    )?;
     projects.iter_mut().for_each(|(_, files)| files.sort());
     assert_eq!(
         projects,
         hashmap! {
             DEFAULT_PROJECT.to_owned() => vec![
-                (path.join("bar.graphql"), "".to_owned()),
-                (path.join("foo.graphql"), "".to_owned()),
+                (path.join("bar.graphql"), ("".to_owned(), false)),
+                (path.join("foo.graphql"), ("".to_owned(), false)),
             ]
         }
     );
@@ -43,20 +47,220 @@ fn test_project_discovery() -> anyhow::Result<()> {
 #[test]
 fn test_project_discovery_multi() -> anyhow::Result<()> {
     let path = fixture_path!("multi");
-    let projects = lsp::discover_projects(std::iter::once(WorkspaceFolder {
-        uri: fixtures!("multi"),
-        name: String::new(),
-    }))?;
+    let projects = lsp::discover_projects(
+        std::iter::once(WorkspaceFolder { uri: fixtures!("multi"), name: String::new() }),
+        &[],
+        &[],
+    )?;
     assert_eq!(
         projects,
         hashmap! {
             "bar".to_owned() => vec![
-                (path.join("bar.graphql"), "".to_owned()),
+                (path.join("bar.graphql"), ("".to_owned(), false)),
             ],
             "foo".to_owned() => vec![
-                (path.join("foo.graphql"), "".to_owned()),
+                (path.join("foo.graphql"), ("".to_owned(), false)),
             ]
         }
     );
     Ok(())
 }
+
+#[test]
+fn test_project_discovery_introspection() -> anyhow::Result<()> {
+    let path = fixture_path!("introspection");
+    // the names are at the same positions as in the json
+    let sdl = concat!(
+        "# generated from an introspection result, do not edit\n",
+        "\ntype\n\n\n\n\n                   Query {\n\n                       foo: Int\n}\n"
+    );
+    let mut projects = lsp::discover_projects(
+        std::iter::once(WorkspaceFolder { uri: fixtures!("introspection"), name: String::new() }),
+        &[],
+        &[],
+    )?;
+    projects.iter_mut().for_each(|(_, files)| files.sort());
+    assert_eq!(
+        projects,
+        hashmap! {
+            DEFAULT_PROJECT.to_owned() => vec![
+                (path.join("foo.graphql"), ("".to_owned(), false)),
+                (path.join("schema.json"), (sdl.to_owned(), false)),
+            ]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_lint_discovery() -> anyhow::Result<()> {
+    let workspaces = vec![WorkspaceFolder { uri: fixtures!("lints"), name: String::new() }];
+    let projects = lsp::discover_projects(workspaces.clone(), &[], &[])?;
+    let lints = lsp::discover_lints(workspaces, projects.keys(), &Default::default())?;
+    assert_eq!(
+        lints,
+        hashmap! {
+            "foo".to_owned() => hashset! { Lint::FieldNameCase, Lint::MissingQueryType },
+            "bar".to_owned() => hashset! { Lint::MissingQueryType },
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_project_discovery_exclude() -> anyhow::Result<()> {
+    let path = fixture_path!("simple");
+    let projects = lsp::discover_projects(
+        std::iter::once(WorkspaceFolder { uri: fixtures!("simple"), name: String::new() }),
+        &["./bar.graphql".to_owned(), "[invalid".to_owned()],
+        &[],
+    )?;
+    assert_eq!(
+        projects,
+        hashmap! {
+            DEFAULT_PROJECT.to_owned() => vec![
+                (path.join("foo.graphql"), ("".to_owned(), false)),
+            ]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_lint_discovery_with_levels() -> anyhow::Result<()> {
+    let workspaces = vec![WorkspaceFolder { uri: fixtures!("lints"), name: String::new() }];
+    let projects = lsp::discover_projects(workspaces.clone(), &[], &[])?;
+    let levels = btreemap! {
+        "field-name-case".to_owned() => LintLevel::Allow,
+        "unused-definition".to_owned() => LintLevel::Warn,
+        "not-a-lint".to_owned() => LintLevel::Warn,
+    };
+    let lints = lsp::discover_lints(workspaces, projects.keys(), &levels)?;
+    assert_eq!(
+        lints,
+        hashmap! {
+            "foo".to_owned() => hashset! { Lint::UnusedDefinition, Lint::MissingQueryType },
+            "bar".to_owned() => hashset! { Lint::UnusedDefinition, Lint::MissingQueryType },
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn test_directory_config_discovery() -> anyhow::Result<()> {
+    let path = fixture_path!("directories");
+    let workspaces = vec![WorkspaceFolder { uri: fixtures!("directories"), name: String::new() }];
+    let configs = lsp::discover_directory_configs(workspaces.clone())?;
+    let mut projects = lsp::discover_projects(workspaces, &[], &configs)?;
+    projects.iter_mut().for_each(|(_, files)| files.sort());
+    let files = projects[DEFAULT_PROJECT].iter().map(|(path, _)| path.clone()).collect::<Vec<_>>();
+    assert_eq!(files, [
+        path.join("foo.graphql"),
+        path.join("generated/bar.graphql"),
+        path.join("generated/vendor/baz.graphql"),
+    ]);
+
+    assert_eq!(
+        lsp::directory_lints(&configs),
+        hashmap! {
+            path.join("generated") => LintOverrides {
+                disabled: hashset! { Lint::UnusedDefinition },
+                ..Default::default()
+            },
+            path.join("generated/vendor") => LintOverrides {
+                enabled: hashset! { Lint::UnusedDefinition },
+                ..Default::default()
+            },
+        }
+    );
+    Ok(())
+}
+
+// `api` uses the `User` type defined in the `types` folder
+fn stitched_workspace_diagnostics(mode: WorkspaceFolderMode) -> anyhow::Result<Vec<String>> {
+    let workspaces = vec![
+        WorkspaceFolder { uri: fixtures!("stitched/api"), name: "api".to_owned() },
+        WorkspaceFolder { uri: fixtures!("stitched/types"), name: "types".to_owned() },
+    ];
+    let settings = Settings { workspace_folders: mode, ..Default::default() };
+    let mut ide = Ide::default();
+    let changeset = lsp::workspace_changeset(&mut ide, workspaces, &settings)?;
+    let projects = ide.load(changeset);
+    assert_eq!(projects.len(), 2);
+    let file = ide.vfs().get(fixture_path!("stitched/api/query.graphql")).unwrap();
+    let diagnostics = ide.snapshot().file_diagnostics(file);
+    Ok(diagnostics.iter().map(|diagnostic| diagnostic.message.clone()).collect())
+}
+
+#[test]
+fn test_stitched_workspace_folders() -> anyhow::Result<()> {
+    assert!(stitched_workspace_diagnostics(WorkspaceFolderMode::Stitched)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_isolated_workspace_folders() -> anyhow::Result<()> {
+    let diagnostics = stitched_workspace_diagnostics(WorkspaceFolderMode::Isolated)?;
+    assert_eq!(diagnostics, vec!["unresolved type `User`".to_owned()]);
+    Ok(())
+}
+
+#[test]
+fn test_workspace_watchers() -> anyhow::Result<()> {
+    let root = fixture_path!("multi");
+    let workspaces = vec![WorkspaceFolder { uri: fixtures!("multi"), name: String::new() }];
+    let watchers = lsp::workspace_watchers(workspaces)?;
+    let globs = watchers.into_iter().map(|watcher| watcher.glob_pattern).collect::<Vec<_>>();
+    assert_eq!(
+        globs,
+        vec![
+            format!("{}/.graphqlrc*", root.display()),
+            format!("{}/**/.gqlsrc.toml", root.display()),
+            format!("{}/bar.graphql", root.display()),
+            format!("{}/foo.graphql", root.display()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_directory_config_change_reloads_workspace() -> anyhow::Result<()> {
+    let path = fixture_path!("directories");
+    let event = |path: PathBuf| lsp_types::FileEvent {
+        uri: lsp_types::Url::from_file_path(path).unwrap(),
+        typ: lsp_types::FileChangeType::CHANGED,
+    };
+    let changes = lsp::watched_changes(vec![event(path.join("generated/.gqlsrc.toml"))])?;
+    assert_eq!(changes, lsp::WatchedChanges { config_changed: true, ..Default::default() });
+
+    // an edit of a schema file only reads its text again
+    let changes = lsp::watched_changes(vec![event(path.join("foo.graphql"))])?;
+    assert_eq!(changes, lsp::WatchedChanges {
+        changed: vec![path.join("foo.graphql")],
+        ..Default::default()
+    });
+    Ok(())
+}
+
+#[test]
+fn test_document_features_without_disabled_features() {
+    let features = FeatureSettings { hover: false, formatting: false, ..Default::default() };
+    let settings = Settings { features, ..Default::default() };
+    let ide = Ide::default();
+    let mut features = ide.snapshot().document_features(Path::new("foo.graphql"));
+    settings.mask_features(&mut features);
+    assert!(!features.is_enabled(Feature::Hover));
+    assert!(!features.is_enabled(Feature::Formatting));
+    assert!(features.is_enabled(Feature::Completion));
+    assert!(features.is_enabled(Feature::Extraction));
+}
+
+#[test]
+fn test_client_commands() {
+    let capabilities = lsp_types::ClientCapabilities {
+        experimental: Some(serde_json::json!({ "commands": { "commands": ["gqls.renameAt"] } })),
+        ..Default::default()
+    };
+    assert_eq!(lsp::client_commands(&capabilities), vec!["gqls.renameAt"]);
+    assert!(lsp::client_commands(&lsp_types::ClientCapabilities::default()).is_empty());
+}
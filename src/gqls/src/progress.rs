@@ -0,0 +1,76 @@
+//! Work done progress (`$/progress`) for the analyses that can take a while on large workspaces
+
+use tower_lsp::lsp_types::notification::Progress;
+use tower_lsp::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp::lsp_types::*;
+use tower_lsp::Client;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Reports nothing if the client doesn't support progress, so callers needn't check
+pub(crate) struct ProgressReporter {
+    client: Client,
+    token: Option<ProgressToken>,
+}
+
+impl ProgressReporter {
+    /// Begin progress with a token created by the server, `supported` is whether the client
+    /// accepts server initiated progress
+    pub(crate) async fn create(client: &Client, supported: bool, title: &str) -> Self {
+        let token = match supported {
+            true => Some(ProgressToken::String(format!("gqls/{}", next_progress_id()))),
+            false => None,
+        };
+        if let Some(token) = &token {
+            let params = WorkDoneProgressCreateParams { token: token.clone() };
+            if let Err(err) = client.send_request::<WorkDoneProgressCreate>(params).await {
+                tracing::warn!(%err, "failed to create progress");
+                return Self { client: client.clone(), token: None };
+            }
+        }
+        Self::begin(client, token, title).await
+    }
+
+    /// Begin progress with a `token` that the client has accepted, the analyses outlive the
+    /// requests that start them so the `workDoneToken` of a request is of no use
+    async fn begin(client: &Client, token: Option<ProgressToken>, title: &str) -> Self {
+        let progress = Self { client: client.clone(), token };
+        progress
+            .send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: title.to_owned(),
+                cancellable: Some(false),
+                message: None,
+                percentage: Some(0),
+            }))
+            .await;
+        progress
+    }
+
+    /// Report that `done` out of `total` units of work are complete
+    pub(crate) async fn report(&self, message: impl Into<String>, done: usize, total: usize) {
+        let percentage = if total == 0 { 100 } else { done * 100 / total };
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(false),
+            message: Some(message.into()),
+            percentage: Some(percentage as u32),
+        }))
+        .await;
+    }
+
+    pub(crate) async fn end(self, message: Option<String>) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd { message })).await;
+    }
+
+    async fn send(&self, progress: WorkDoneProgress) {
+        if let Some(token) = &self.token {
+            let value = ProgressParamsValue::WorkDone(progress);
+            let params = ProgressParams { token: token.clone(), value };
+            self.client.send_notification::<Progress>(params).await;
+        }
+    }
+}
+
+fn next_progress_id() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    NEXT.fetch_add(1, Ordering::SeqCst)
+}
@@ -0,0 +1,83 @@
+//! Breaking changes of a project against its published schema, which are reported as warnings
+//! while the project is edited. A breaking change is intended if its schema coordinate is in the
+//! `allow` list of the configuration or in a `# gqls-allow-breaking <coordinates..>` comment.
+
+use anyhow::Context;
+use gqls_diff::Criticality;
+use gqls_ide::{diagnostic, range, Diagnostics, Location, Project, Schema, Snapshot};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::config::PublishedConfig;
+use crate::{diff, introspection};
+
+const ALLOW_COMMENT: &str = "gqls-allow-breaking";
+
+#[derive(Debug)]
+pub(crate) struct PublishedSchema {
+    schema: Schema,
+    allow: HashSet<String>,
+}
+
+impl PublishedSchema {
+    /// Load the published schema of `config`, paths are relative to the workspace `root`
+    pub(crate) fn load(root: &Path, config: &PublishedConfig) -> anyhow::Result<Self> {
+        let schema = if config.schema.starts_with("http://")
+            || config.schema.starts_with("https://")
+        {
+            let json = introspection::fetch(&config.schema)?;
+            let sdl = introspection::from_introspection_json(&json)?.with_context(|| {
+                format!("`{}` did not respond with an introspection result", config.schema)
+            })?;
+            diff::build_schema(vec![(PathBuf::from(&config.schema), sdl)])
+        } else {
+            diff::load_schema(&root.join(&config.schema))?
+        };
+        Ok(Self::new(schema, config.allow.iter().cloned().collect()))
+    }
+
+    pub(crate) fn new(schema: Schema, allow: HashSet<String>) -> Self {
+        Self { schema, allow }
+    }
+
+    /// The unintended breaking changes from the published schema to `project`, each is reported
+    /// on the definition that changed or the closest enclosing one if it was removed
+    pub(crate) fn diagnostics(&self, snapshot: &Snapshot, project: Project) -> Diagnostics {
+        let mut diagnostics = Diagnostics::default();
+        let files = snapshot.files_of(&HashSet::from([project]));
+        let first = match files.first() {
+            Some(&file) => file,
+            None => return diagnostics,
+        };
+
+        let mut allow = self.allow.clone();
+        for &file in &files {
+            allow.extend(allowed_in(&snapshot.comments(file)));
+        }
+        let changes = gqls_diff::diff(&self.schema, &snapshot.schema(first));
+        for change in changes {
+            if change.criticality != Criticality::Breaking || allow.contains(&change.coordinate) {
+                continue;
+            }
+            // removed types and directives have nothing left to be reported on
+            let location = snapshot
+                .coordinate_location(first, &change.coordinate)
+                .unwrap_or_else(|| Location::new(first, range!(0:0..0:0)));
+            let diagnostic = diagnostic!(L0004 @ location.range, change = change.message);
+            diagnostics.entry(location.file).or_default().insert(diagnostic);
+        }
+        diagnostics
+    }
+}
+
+/// The coordinates listed by the allow comments among `comments`
+fn allowed_in(comments: &[String]) -> impl Iterator<Item = String> + '_ {
+    comments
+        .iter()
+        .filter_map(|comment| comment.trim_start().strip_prefix(ALLOW_COMMENT))
+        .flat_map(str::split_whitespace)
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,114 @@
+use gqls_ide::{range, Change, Changeset, Ide, Location};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::{allowed_in, PublishedSchema};
+use crate::diff;
+
+const PUBLISHED: &str = "
+type Query {
+  user(id: ID!): User
+  users: [User!]!
+}
+
+type User {
+  id: ID!
+  name: String!
+}
+
+type Removed {
+  id: ID!
+}
+";
+
+fn breaking_changes(text: &str, allow: &[&str]) -> Vec<(Location, String)> {
+    let schema = diff::build_schema(vec![(PathBuf::from("published.graphql"), PUBLISHED.into())]);
+    let published = PublishedSchema::new(schema, allow.iter().map(|&s| s.to_owned()).collect());
+
+    let mut ide = Ide::default();
+    let file = ide.vfs().intern("/workspace/schema.graphql");
+    let project = ide.intern_project("default".to_owned());
+    let projects = HashMap::from([(project, HashSet::from([file]))]);
+    ide.load(Changeset::single(Change::set(file, text.to_owned())).with_projects(projects));
+
+    let mut changes = published
+        .diagnostics(&ide.snapshot(), project)
+        .into_iter()
+        .flat_map(|(file, diagnostics)| {
+            diagnostics
+                .into_iter()
+                .map(move |diagnostic| (Location::new(file, diagnostic.range), diagnostic.message))
+        })
+        .collect::<Vec<_>>();
+    changes.sort();
+    changes
+}
+
+#[test]
+fn test_published_breaking_changes() {
+    let text = "
+type Query {
+  user(id: ID!, filter: String!): User
+}
+
+type User {
+  id: ID!
+  name: String
+}
+";
+    let file = Path::new("/workspace/schema.graphql");
+    let message = |change: &str| format!("breaking change to the published schema: {change}");
+    assert_eq!(
+        breaking_changes(text, &[]),
+        [
+            (Location::new(file, range!(0:0..0:0)), message("type `Removed` was removed")),
+            (Location::new(file, range!(1:5..1:10)), message("field `Query.users` was removed")),
+            (
+                Location::new(file, range!(2:16..2:22)),
+                message("required argument `Query.user(filter:)` was added")
+            ),
+            (
+                Location::new(file, range!(7:2..7:6)),
+                message("field `User.name` changed type from `String!` to `String`")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_published_breaking_changes_allowed() {
+    let text = "
+# gqls-allow-breaking Query.users Removed
+type Query {
+  user(id: ID!): User
+}
+
+type User {
+  id: ID!
+  name: String! # gqls-allow-breaking User.email
+}
+";
+    assert_eq!(breaking_changes(text, &[]), []);
+    let text = text.replace("# gqls-allow-breaking Query.users Removed", "");
+    assert_eq!(breaking_changes(&text, &["Removed", "Query.users"]), []);
+}
+
+#[test]
+fn test_allowed_in() {
+    let text = r##"
+# gqls-allow-breaking A B.c
+type A { c: Int } # gqls-allow-breaking D
+# not allowed E
+"# gqls-allow-breaking F"
+type F { c: Int }
+"""
+# gqls-allow-breaking G
+"""
+type H { c: Int }
+"##;
+    let mut ide = Ide::default();
+    let file = ide.vfs().intern("/workspace/schema.graphql");
+    ide.load(Changeset::single(Change::set(file, text.to_owned())));
+    let comments = ide.snapshot().comments(file);
+    assert_eq!(allowed_in(&comments).collect::<Vec<_>>(), ["A", "B.c", "D"]);
+}
@@ -0,0 +1,206 @@
+//! The output formats of `gqls lint`, text for people and json lines or SARIF for tools
+//! (e.g. code scanning)
+
+use anyhow::bail;
+use gqls_ide::{Diagnostic, FileId, PositionEncoding, Range, Severity, Snapshot};
+use serde_json::json;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use tower_lsp::lsp_types::Url;
+
+use crate::baseline;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `path:line:column: severity[code]: message` per diagnostic
+    Text,
+    /// a json object per diagnostic, one per line
+    Json,
+    /// a SARIF 2.1.0 log
+    Sarif,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            _ => bail!("unknown output format `{s}`, expected `text`, `json` or `sarif`"),
+        }
+    }
+}
+
+/// Write the diagnostics in `format`, the paths of files under `root` are relative to it
+pub fn write(
+    snapshot: &Snapshot,
+    root: &Path,
+    format: OutputFormat,
+    diagnostics: &[(FileId, Diagnostic)],
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Text => write_text(root, diagnostics, out),
+        OutputFormat::Json => write_json_lines(root, diagnostics, out),
+        OutputFormat::Sarif => write_sarif(snapshot, root, diagnostics, out),
+    }
+}
+
+fn write_text(
+    root: &Path,
+    diagnostics: &[(FileId, Diagnostic)],
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    for (file, diagnostic) in diagnostics {
+        let start = diagnostic.range.start;
+        writeln!(
+            out,
+            "{}:{}:{}: {}[{}]: {}",
+            path(root, file),
+            start.row + 1,
+            start.column + 1,
+            severity(&diagnostic.severity),
+            diagnostic.code,
+            diagnostic.message
+        )?;
+    }
+    Ok(())
+}
+
+/// Lines and columns are 1-based like the text output, columns count bytes
+fn write_json_lines(
+    root: &Path,
+    diagnostics: &[(FileId, Diagnostic)],
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let range = |range: Range| {
+        json!({
+            "start": { "line": range.start.row + 1, "column": range.start.column + 1 },
+            "end": { "line": range.end.row + 1, "column": range.end.column + 1 },
+        })
+    };
+    for (file, diagnostic) in diagnostics {
+        let related = diagnostic
+            .labels
+            .iter()
+            .map(|label| {
+                json!({
+                    "file": path(root, label.location.file),
+                    "range": range(label.location.range),
+                    "message": label.message,
+                })
+            })
+            .collect::<Vec<_>>();
+        let line = json!({
+            "file": path(root, file),
+            "range": range(diagnostic.range),
+            "severity": severity(&diagnostic.severity),
+            "code": diagnostic.code.to_string(),
+            "message": diagnostic.message,
+            "related": related,
+        });
+        serde_json::to_writer(&mut *out, &line)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Columns count utf-16 code units, the default of SARIF
+fn write_sarif(
+    snapshot: &Snapshot,
+    root: &Path,
+    diagnostics: &[(FileId, Diagnostic)],
+    out: &mut impl Write,
+) -> anyhow::Result<()> {
+    let location = |file: FileId, range: Range| {
+        let index = snapshot.line_index(file);
+        let start = index.encode(range.start, PositionEncoding::Utf16);
+        let end = index.encode(range.end, PositionEncoding::Utf16);
+        let uri = baseline::relative_path(root, file)
+            .or_else(|| Url::from_file_path(file).ok().map(String::from))
+            .unwrap_or_else(|| file.display().to_string());
+        json!({
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": {
+                    "startLine": start.row + 1,
+                    "startColumn": start.column + 1,
+                    "endLine": end.row + 1,
+                    "endColumn": end.column + 1,
+                },
+            },
+        })
+    };
+
+    let results = diagnostics
+        .iter()
+        .map(|(file, diagnostic)| {
+            let related = diagnostic
+                .labels
+                .iter()
+                .enumerate()
+                .map(|(id, label)| {
+                    let mut related = location(label.location.file, label.location.range);
+                    related["id"] = json!(id);
+                    related["message"] = json!({ "text": label.message });
+                    related
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "ruleId": diagnostic.code.to_string(),
+                "level": severity(&diagnostic.severity),
+                "message": { "text": diagnostic.message },
+                "locations": [location(*file, diagnostic.range)],
+                "relatedLocations": related,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut codes =
+        diagnostics.iter().map(|(_, diagnostic)| diagnostic.code.to_string()).collect::<Vec<_>>();
+    codes.sort();
+    codes.dedup();
+    let rules = codes.into_iter().map(|code| json!({ "id": code })).collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": { "name": "gqls", "version": env!("CARGO_PKG_VERSION"), "rules": rules },
+            },
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *out, &log)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `file` relative to `root` if it is under it
+fn path(root: &Path, file: &Path) -> String {
+    baseline::relative_path(root, file).unwrap_or_else(|| file.display().to_string())
+}
+
+/// Both the text output and SARIF (as the level of a result) use these names
+fn severity(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests;
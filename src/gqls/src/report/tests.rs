@@ -0,0 +1,99 @@
+use gqls_ide::{
+    diagnostic, range, Change, Changeset, Diagnostic, FileId, Ide, Location, Snapshot
+};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::{write, OutputFormat};
+
+// `é` is 2 bytes but a single utf-16 code unit
+const TEXT: &str = "\"é\" type Foo @bar\n";
+
+fn setup() -> (Snapshot, Vec<(FileId, Diagnostic)>) {
+    let mut ide = Ide::default();
+    let file = ide.vfs().intern("/workspace/foo.graphql");
+    let project = ide.intern_project("default".to_owned());
+    let projects = HashMap::from([(project, HashSet::from([file]))]);
+    let _ = ide.load(Changeset::single(Change::set(file, TEXT.to_owned())).with_projects(projects));
+    let definition = Location::new(file, range!(0:10..0:13));
+    let diagnostic = diagnostic!(
+        E0002 @ range!(0:14..0:18), name = "bar"; [definition => "on this type"]
+    );
+    (ide.snapshot(), vec![(file, diagnostic)])
+}
+
+fn output(format: OutputFormat) -> anyhow::Result<String> {
+    let (snapshot, diagnostics) = setup();
+    let mut out = vec![];
+    write(&snapshot, Path::new("/workspace"), format, &diagnostics, &mut out)?;
+    Ok(String::from_utf8(out)?)
+}
+
+#[test]
+fn test_parse_output_format() {
+    assert_eq!("text".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
+    assert_eq!("json".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+    assert_eq!("sarif".parse::<OutputFormat>().unwrap(), OutputFormat::Sarif);
+    assert!("xml".parse::<OutputFormat>().is_err());
+}
+
+#[test]
+fn test_text_output() -> anyhow::Result<()> {
+    let expected = "foo.graphql:1:15: error[0002]: unresolved directive `bar`\n";
+    assert_eq!(output(OutputFormat::Text)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_json_lines_output() -> anyhow::Result<()> {
+    let output = output(OutputFormat::Json)?;
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 1);
+    let line = serde_json::from_str::<Value>(lines[0])?;
+    assert_eq!(
+        line,
+        json!({
+            "file": "foo.graphql",
+            "range": { "start": { "line": 1, "column": 15 }, "end": { "line": 1, "column": 19 } },
+            "severity": "error",
+            "code": "0002",
+            "message": "unresolved directive `bar`",
+            "related": [{
+                "file": "foo.graphql",
+                "range": {
+                    "start": { "line": 1, "column": 11 },
+                    "end": { "line": 1, "column": 14 },
+                },
+                "message": "on this type",
+            }],
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sarif_output() -> anyhow::Result<()> {
+    let log = serde_json::from_str::<Value>(&output(OutputFormat::Sarif)?)?;
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "gqls");
+    assert_eq!(run["tool"]["driver"]["rules"], json!([{ "id": "0002" }]));
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "0002");
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "unresolved directive `bar`");
+    // the columns count utf-16 code units
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "foo.graphql");
+    assert_eq!(
+        location["region"],
+        json!({ "startLine": 1, "startColumn": 14, "endLine": 1, "endColumn": 18 })
+    );
+    let related = &result["relatedLocations"][0];
+    assert_eq!(related["id"], 0);
+    assert_eq!(related["message"]["text"], "on this type");
+    assert_eq!(related["physicalLocation"]["region"]["startColumn"], 10);
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! Coalesces bursts of changes into a single analysis, so typing doesn't queue up a background
+//! pass per keystroke
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Every change supersedes the analyses scheduled before it. An analysis that is superseded
+/// during its debounce never starts, while one that is already running is cancelled by the
+/// change itself (applying it requests the cancellation of the outstanding snapshots) and its
+/// results are discarded.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Scheduler {
+    generation: Arc<AtomicUsize>,
+    /// the number of analyses that were started, i.e. that weren't superseded while debouncing
+    runs: Arc<AtomicUsize>,
+}
+
+impl Scheduler {
+    /// Supersede the scheduled analyses, returning the generation of the change
+    pub(crate) fn bump(&self) -> usize {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub(crate) fn generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Whether no change was made since `generation`
+    pub(crate) fn is_current(&self, generation: usize) -> bool {
+        self.generation() == generation
+    }
+
+    /// Run `analysis` once no change was made for `debounce` after `generation`, returns `None`
+    /// if it was superseded before it started
+    pub(crate) async fn debounce<F: Future>(
+        &self,
+        generation: usize,
+        debounce: Duration,
+        analysis: impl FnOnce() -> F,
+    ) -> Option<F::Output> {
+        tokio::time::sleep(debounce).await;
+        if !self.is_current(generation) {
+            return None;
+        }
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        Some(analysis().await)
+    }
+
+    /// The number of analyses that were started so far
+    pub(crate) fn runs(&self) -> usize {
+        self.runs.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use super::Scheduler;
+
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+// a burst of edits, each scheduling the analysis of the state after it
+async fn burst(scheduler: &Scheduler, edits: usize) -> Vec<Option<usize>> {
+    let tasks = (0..edits)
+        .map(|_| {
+            let generation = scheduler.bump();
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                scheduler.debounce(generation, DEBOUNCE, || async move { generation }).await
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut results = vec![];
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    results
+}
+
+#[tokio::test]
+async fn test_burst_of_edits_is_analyzed_once() {
+    let scheduler = Scheduler::default();
+    let results = burst(&scheduler, 100).await;
+    assert_eq!(scheduler.runs(), 1);
+    // only the analysis of the last edit runs
+    assert_eq!(results.iter().flatten().collect::<Vec<_>>(), [&100]);
+}
+
+#[tokio::test]
+async fn test_edits_further_apart_than_the_debounce_are_each_analyzed() {
+    let scheduler = Scheduler::default();
+    for _ in 0..3 {
+        burst(&scheduler, 10).await;
+    }
+    assert_eq!(scheduler.runs(), 3);
+}
+
+#[tokio::test]
+async fn test_edit_discards_running_analysis() {
+    let scheduler = Scheduler::default();
+    let generation = scheduler.bump();
+    let analysis = scheduler.debounce(generation, Duration::ZERO, || async {
+        // an edit arrives while the analysis is running
+        scheduler.bump();
+        scheduler.is_current(generation)
+    });
+    assert_eq!(analysis.await, Some(false));
+    assert_eq!(scheduler.runs(), 1);
+}
@@ -0,0 +1,97 @@
+//! The transports the language server is served over, stdio unless `--tcp` or `--socket` is given
+
+use anyhow::{bail, Context};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tower_lsp::Server;
+
+use crate::Gqls;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    Stdio,
+    /// listen on the address and serve the first client that connects to it
+    Tcp(SocketAddr),
+    /// listen on a unix domain socket at the path and serve the first client that connects to it
+    Socket(PathBuf),
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Self::Stdio
+    }
+}
+
+impl Transport {
+    /// `--tcp` takes a port on localhost or a full address, e.g. `0.0.0.0:9257` in a container
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut args = args.into_iter();
+        let mut transport = Self::default();
+        while let Some(arg) = args.next() {
+            transport = match arg.as_str() {
+                "--stdio" => Self::Stdio,
+                "--tcp" => {
+                    let addr = args.next().context("expected a port or address after `--tcp`")?;
+                    match addr.parse::<u16>() {
+                        Ok(port) => Self::Tcp(SocketAddr::from((Ipv4Addr::LOCALHOST, port))),
+                        Err(_) => Self::Tcp(
+                            addr.parse().with_context(|| format!("invalid address `{addr}`"))?,
+                        ),
+                    }
+                }
+                "--socket" => {
+                    let path = args.next().context("expected a path after `--socket`")?;
+                    Self::Socket(PathBuf::from(path))
+                }
+                arg => bail!("unexpected argument `{arg}`"),
+            };
+        }
+        Ok(transport)
+    }
+}
+
+/// Serve the language server over `transport` until the client exits
+pub async fn serve(transport: &Transport) -> anyhow::Result<()> {
+    match transport {
+        Transport::Stdio => serve_over(tokio::io::stdin(), tokio::io::stdout()).await,
+        Transport::Tcp(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("failed to listen on `{addr}`"))?;
+            tracing::info!("listening on `{}`", listener.local_addr()?);
+            let (stream, peer) = listener.accept().await?;
+            tracing::info!("serving `{peer}`");
+            let (read, write) = stream.into_split();
+            serve_over(read, write).await;
+        }
+        Transport::Socket(path) => serve_socket(path).await?,
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn serve_socket(path: &std::path::Path) -> anyhow::Result<()> {
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("failed to listen on `{}`", path.display()))?;
+    tracing::info!("listening on `{}`", path.display());
+    let accepted = listener.accept().await;
+    // the socket file outlives the listener, so it is removed as soon as it has served its purpose
+    let _ = std::fs::remove_file(path);
+    let (read, write) = accepted?.0.into_split();
+    serve_over(read, write).await;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+async fn serve_socket(_path: &std::path::Path) -> anyhow::Result<()> {
+    bail!("unix domain sockets are not supported on this platform, use `--tcp` instead")
+}
+
+async fn serve_over(read: impl AsyncRead + Unpin, write: impl AsyncWrite + Unpin) {
+    let (service, socket) = Gqls::service();
+    Server::new(read, write, socket).serve(service).await;
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,48 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+use super::Transport;
+
+fn parse(args: &[&str]) -> anyhow::Result<Transport> {
+    Transport::parse(args.iter().map(|&arg| arg.to_owned()))
+}
+
+#[test]
+fn test_parse_transport() -> anyhow::Result<()> {
+    assert_eq!(parse(&[])?, Transport::Stdio);
+    assert_eq!(parse(&["--stdio"])?, Transport::Stdio);
+    assert_eq!(parse(&["--tcp", "9257"])?, Transport::Tcp("127.0.0.1:9257".parse()?));
+    assert_eq!(
+        parse(&["--tcp", "0.0.0.0:9257"])?,
+        Transport::Tcp("0.0.0.0:9257".parse::<SocketAddr>()?)
+    );
+    assert_eq!(
+        parse(&["--socket", "/tmp/gqls.sock"])?,
+        Transport::Socket(PathBuf::from("/tmp/gqls.sock"))
+    );
+    assert!(parse(&["--tcp"]).is_err());
+    assert!(parse(&["--tcp", "localhost"]).is_err());
+    assert!(parse(&["--socket"]).is_err());
+    assert!(parse(&["--port", "9257"]).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_serve_tcp_exits_with_client() -> anyhow::Result<()> {
+    let port = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let server = tokio::spawn(async move { super::serve(&Transport::Tcp(addr)).await });
+
+    let mut stream = loop {
+        match tokio::net::TcpStream::connect(addr).await {
+            Ok(stream) => break stream,
+            Err(_) => tokio::task::yield_now().await,
+        }
+    };
+    // the server stops serving once the client goes away without a shutdown
+    stream.shutdown().await?;
+    drop(stream);
+    server.await??;
+    Ok(())
+}
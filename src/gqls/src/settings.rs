@@ -0,0 +1,266 @@
+//! Client settings (the `gqls` section of the client configuration), read from the
+//! initialization options and updated by `workspace/didChangeConfiguration`
+
+use gqls_ide::{
+    CompletionConfig, DefinitionConfig, Docstrings, DocumentFeatures, Feature, FormatConfig, TypeWrappers
+};
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tower_lsp::lsp_types::ClientCapabilities;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub features: FeatureSettings,
+    pub completion: CompletionSettings,
+    pub definition: DefinitionSettings,
+    pub formatting: FormattingSettings,
+    pub diagnostics: DiagnosticsSettings,
+    /// the level of lints by name (e.g. `"unused-definition": "warn"`) in every project,
+    /// overriding whether the project configuration enables them
+    pub lints: BTreeMap<String, LintLevel>,
+    /// globs of the files (relative to the workspace root) that are never loaded, e.g. a
+    /// generated schema that is also matched by a project
+    pub exclude: Vec<String>,
+    /// where the index of the workspace is persisted across restarts, relative paths are
+    /// relative to the first workspace folder. There is no cache unless this is set.
+    pub cache_directory: Option<PathBuf>,
+    /// whether the projects of the workspace folders form one schema, see [`WorkspaceFolderMode`]
+    pub workspace_folders: WorkspaceFolderMode,
+}
+
+/// How the projects of multiple workspace folders relate to each other, a single folder is the
+/// same in either mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceFolderMode {
+    /// the projects of the same name in each folder are stitched into one logical schema, so
+    /// names in one folder resolve to the definitions in the others
+    Stitched,
+    /// the projects of each folder are separate, names only resolve within their own folder
+    Isolated,
+}
+
+impl Default for WorkspaceFolderMode {
+    fn default() -> Self {
+        Self::Stitched
+    }
+}
+
+/// Every feature is enabled unless explicitly disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FeatureSettings {
+    pub completions: bool,
+    pub hover: bool,
+    pub semantic_tokens: bool,
+    pub code_lens: bool,
+    pub formatting: bool,
+}
+
+impl Default for FeatureSettings {
+    fn default() -> Self {
+        Self {
+            completions: true,
+            hover: true,
+            semantic_tokens: true,
+            code_lens: true,
+            formatting: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompletionSettings {
+    /// one of `none`, `variants` or `snippet`, see [`TypeWrappers`]
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub type_wrappers: TypeWrappers,
+    /// one of `none`, `summary` or `arguments`, see [`Docstrings`]
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub docstrings: Docstrings,
+}
+
+impl CompletionSettings {
+    pub fn config(&self) -> CompletionConfig {
+        CompletionConfig { type_wrappers: self.type_wrappers, docstrings: self.docstrings }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DefinitionSettings {
+    /// go to the base definition of a type only, its extensions are left to find references
+    pub exclude_extensions: bool,
+}
+
+impl DefinitionSettings {
+    pub fn config(&self) -> DefinitionConfig {
+        DefinitionConfig { exclude_extensions: self.exclude_extensions }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// the lint is disabled
+    Allow,
+    /// the lint is enabled
+    Warn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormattingSettings {
+    pub indent_width: usize,
+}
+
+impl Default for FormattingSettings {
+    fn default() -> Self {
+        Self { indent_width: FormatConfig::default().indent_width }
+    }
+}
+
+impl FormattingSettings {
+    pub fn config(&self) -> FormatConfig {
+        FormatConfig { indent_width: self.indent_width }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagnosticsSettings {
+    /// how long (in milliseconds) no change must be made before the semantic diagnostics are
+    /// recomputed, the syntax diagnostics are always published immediately
+    pub debounce: u64,
+}
+
+impl Default for DiagnosticsSettings {
+    fn default() -> Self {
+        Self { debounce: 200 }
+    }
+}
+
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+}
+
+/// The features that can be toggled by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ToggleableFeature {
+    Completions,
+    Hover,
+    SemanticTokens,
+    CodeLens,
+    Formatting,
+}
+
+impl ToggleableFeature {
+    pub const ALL: [ToggleableFeature; 5] = [
+        ToggleableFeature::Completions,
+        ToggleableFeature::Hover,
+        ToggleableFeature::SemanticTokens,
+        ToggleableFeature::CodeLens,
+        ToggleableFeature::Formatting,
+    ];
+
+    /// The toggle of the document feature `feature`, if it can be turned off
+    pub fn of(feature: Feature) -> Option<Self> {
+        match feature {
+            Feature::Completion => Some(ToggleableFeature::Completions),
+            Feature::Hover => Some(ToggleableFeature::Hover),
+            Feature::SemanticTokens => Some(ToggleableFeature::SemanticTokens),
+            Feature::Formatting => Some(ToggleableFeature::Formatting),
+            Feature::Definition
+            | Feature::Diagnostics
+            | Feature::DocumentSymbols
+            | Feature::Extraction
+            | Feature::Implementation
+            | Feature::References
+            | Feature::Rename
+            | Feature::TypeDefinition => None,
+        }
+    }
+
+    /// The method the capability of the feature is (dynamically) registered for
+    pub fn method(self) -> &'static str {
+        match self {
+            ToggleableFeature::Completions => "textDocument/completion",
+            ToggleableFeature::Hover => "textDocument/hover",
+            ToggleableFeature::SemanticTokens => "textDocument/semanticTokens",
+            ToggleableFeature::CodeLens => "textDocument/codeLens",
+            ToggleableFeature::Formatting => "textDocument/formatting",
+        }
+    }
+
+    /// Whether the client can register and unregister the capability after initialization
+    pub fn supports_dynamic_registration(self, capabilities: &ClientCapabilities) -> bool {
+        let text_document = match &capabilities.text_document {
+            Some(text_document) => text_document,
+            None => return false,
+        };
+        let dynamic_registration = match self {
+            ToggleableFeature::Completions =>
+                text_document.completion.as_ref().and_then(|caps| caps.dynamic_registration),
+            ToggleableFeature::Hover =>
+                text_document.hover.as_ref().and_then(|caps| caps.dynamic_registration),
+            ToggleableFeature::SemanticTokens =>
+                text_document.semantic_tokens.as_ref().and_then(|caps| caps.dynamic_registration),
+            ToggleableFeature::CodeLens =>
+                text_document.code_lens.as_ref().and_then(|caps| caps.dynamic_registration),
+            ToggleableFeature::Formatting =>
+                text_document.formatting.as_ref().and_then(|caps| caps.dynamic_registration),
+        };
+        dynamic_registration == Some(true)
+    }
+}
+
+impl Settings {
+    /// Read the settings from either the `gqls` section itself or an object containing it,
+    /// invalid settings are ignored in favour of the defaults
+    pub fn from_value(value: Option<serde_json::Value>) -> Self {
+        let value = match value {
+            Some(serde_json::Value::Object(mut object)) if object.contains_key("gqls") =>
+                object.remove("gqls").unwrap(),
+            Some(serde_json::Value::Null) | None => return Self::default(),
+            Some(value) => value,
+        };
+        serde_json::from_value(value).unwrap_or_else(|err| {
+            tracing::warn!(%err, "ignoring invalid settings");
+            Self::default()
+        })
+    }
+
+    /// Whether the settings that determine the contents of the workspace differ, the workspace
+    /// is reloaded when they change
+    pub fn workspace_changed(&self, previous: &Settings) -> bool {
+        self.lints != previous.lints
+            || self.exclude != previous.exclude
+            || self.workspace_folders != previous.workspace_folders
+    }
+
+    pub fn is_enabled(&self, feature: ToggleableFeature) -> bool {
+        match feature {
+            ToggleableFeature::Completions => self.features.completions,
+            ToggleableFeature::Hover => self.features.hover,
+            ToggleableFeature::SemanticTokens => self.features.semantic_tokens,
+            ToggleableFeature::CodeLens => self.features.code_lens,
+            ToggleableFeature::Formatting => self.features.formatting,
+        }
+    }
+
+    /// Drop the features of a document that are turned off
+    pub fn mask_features(&self, features: &mut DocumentFeatures) {
+        features.enabled.retain(|&feature| {
+            ToggleableFeature::of(feature).map_or(true, |toggle| self.is_enabled(toggle))
+        });
+    }
+}
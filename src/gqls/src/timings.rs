@@ -0,0 +1,117 @@
+//! The durations of the spans of the server, i.e. the lsp requests and the salsa queries they
+//! execute, aggregated by name so users can report which feature is slow on their schema
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{span, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// The timings recorded by [`TimingsLayer::default`], which are served by `gqls/debug/timings`
+pub static TIMINGS: Lazy<Arc<Timings>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default)]
+pub struct Timings {
+    spans: Mutex<HashMap<String, Stats>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Stats {
+    count: usize,
+    total: Duration,
+    max: Duration,
+}
+
+/// The timing of all the spans of the same name, a span includes the spans nested within it
+#[derive(Debug, PartialEq, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timing {
+    /// the target of the span (i.e. its module) followed by its name, e.g. `gqls::lsp::hover`
+    pub name: String,
+    pub count: usize,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Timings {
+    fn record(&self, name: String, elapsed: Duration) {
+        let mut spans = self.spans.lock();
+        let stats = spans.entry(name).or_default();
+        stats.count += 1;
+        stats.total += elapsed;
+        stats.max = stats.max.max(elapsed);
+    }
+
+    /// The timings recorded so far, the slowest in total first
+    pub fn timings(&self) -> Vec<Timing> {
+        let mut spans = self
+            .spans
+            .lock()
+            .iter()
+            .map(|(name, &stats)| (name.clone(), stats))
+            .collect::<Vec<_>>();
+        spans.sort_by(|(a, x), (b, y)| y.total.cmp(&x.total).then_with(|| a.cmp(b)));
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        spans
+            .into_iter()
+            .map(|(name, stats)| Timing {
+                name,
+                count: stats.count,
+                total_ms: ms(stats.total),
+                max_ms: ms(stats.max),
+            })
+            .collect()
+    }
+
+    pub fn reset(&self) {
+        self.spans.lock().clear();
+    }
+}
+
+/// Records the duration of every span from its creation until it is closed
+pub struct TimingsLayer {
+    timings: Arc<Timings>,
+}
+
+impl TimingsLayer {
+    fn new(timings: Arc<Timings>) -> Self {
+        Self { timings }
+    }
+}
+
+impl Default for TimingsLayer {
+    fn default() -> Self {
+        Self::new(Arc::clone(&TIMINGS))
+    }
+}
+
+struct Started(Instant);
+
+impl<S> Layer<S> for TimingsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        if let Some(Started(start)) = span.extensions().get::<Started>() {
+            let metadata = span.metadata();
+            let name = format!("{}::{}", metadata.target(), metadata.name());
+            self.timings.record(name, start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use maplit::btreemap;
+use tracing_subscriber::layer::SubscriberExt;
+
+use super::{Timings, TimingsLayer};
+
+#[test]
+fn test_timings_are_aggregated_by_name() {
+    let timings = Arc::new(Timings::default());
+    let subscriber = tracing_subscriber::registry().with(TimingsLayer::new(Arc::clone(&timings)));
+    tracing::subscriber::with_default(subscriber, || {
+        for _ in 0..2 {
+            let _request = tracing::info_span!("request").entered();
+            let _query = tracing::debug_span!("query").entered();
+        }
+    });
+
+    let counts = timings
+        .timings()
+        .into_iter()
+        .map(|timing| (timing.name, timing.count))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(
+        counts,
+        btreemap! {
+            "gqls::timings::tests::query".to_owned() => 2,
+            "gqls::timings::tests::request".to_owned() => 2,
+        }
+    );
+    assert!(timings.timings().iter().all(|timing| timing.max_ms <= timing.total_ms));
+}
+
+#[test]
+fn test_reset_timings() {
+    let timings = Arc::new(Timings::default());
+    let subscriber = tracing_subscriber::registry().with(TimingsLayer::new(Arc::clone(&timings)));
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("request").entered();
+    });
+    assert_eq!(timings.timings().len(), 1);
+    timings.reset();
+    assert!(timings.timings().is_empty());
+}
@@ -1,10 +1,11 @@
 use gqls_ide::RangeExt;
 use tower_lsp::lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType};
 
+use crate::convert::FileIndex;
 use crate::Convert;
 
 pub const TOKEN_TYPE_UNION: SemanticTokenType = SemanticTokenType::new("union");
-pub const TOKEN_TYPE_SCALAR: SemanticTokenType = SemanticTokenType::new("union");
+pub const TOKEN_TYPE_SCALAR: SemanticTokenType = SemanticTokenType::new("scalar");
 
 pub const TYPES: &[SemanticTokenType] = &[
     SemanticTokenType::COMMENT,
@@ -26,7 +27,8 @@ pub const TYPES: &[SemanticTokenType] = &[
 
 pub const MODIFIERS: &[SemanticTokenModifier] = &[];
 
-pub(crate) fn convert(ts: &[gqls_ide::SemanticToken]) -> Vec<SemanticToken> {
+// the columns and lengths of the tokens are in the encoding of the client
+pub(crate) fn convert(index: &FileIndex, ts: &[gqls_ide::SemanticToken]) -> Vec<SemanticToken> {
     let mut semantic_tokens = Vec::with_capacity(ts.len());
     for i in 0..ts.len() {
         let token = &ts[i];
@@ -34,6 +36,7 @@ pub(crate) fn convert(ts: &[gqls_ide::SemanticToken]) -> Vec<SemanticToken> {
         if range.is_empty() {
             continue;
         }
+        let start = index.encode(range.start_point);
         semantic_tokens.push(SemanticToken {
             delta_line: if i == 0 {
                 range.start_point.row as u32
@@ -41,13 +44,14 @@ pub(crate) fn convert(ts: &[gqls_ide::SemanticToken]) -> Vec<SemanticToken> {
                 (range.start_point.row - ts[i - 1].range.end_point.row) as u32
             },
             delta_start: if i == 0 || range.start_point.row != ts[i - 1].range.end_point.row {
-                range.start_point.column as u32
+                start.column as u32
             } else {
                 assert_eq!(range.start_point.row, ts[i - 1].range.end_point.row);
                 assert!(range.start_point.column >= ts[i - 1].range.end_point.column);
-                (range.start_point.column - ts[i - 1].range.start_point.column) as u32
+                let prev = index.encode(ts[i - 1].range.start_point);
+                (start.column - prev.column) as u32
             },
-            length: (token.range.end_byte - token.range.start_byte) as u32,
+            length: index.encoded_len(range.start_byte..range.end_byte) as u32,
             token_type: TYPES
                 .iter()
                 .position(|k| k == &token.kind.convert())
@@ -1,11 +1,13 @@
 use anyhow::Result;
 use expect_test::expect;
 use futures::StreamExt;
-use gqls::{Convert, Gqls};
+use gqls::{Gqls, LineIndices};
+use gqls_ide::{FileId, LineIndex, PositionEncoding};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
 use tower::{Service, ServiceExt};
 use tower_lsp::jsonrpc::{Request, Response};
 use tower_lsp::lsp_types::notification::Notification as _;
@@ -134,7 +136,7 @@ fn test_url_macro() {
 async fn test_lsp_init() -> Result<()> {
     let (service, _) = make_service!();
     let response = request!(service: "initialize", json!({ "capabilities": {} }));
-    assert_eq!(response.capabilities, gqls::capabilities());
+    assert_eq!(response.capabilities, gqls::capabilities(&Default::default()));
     Ok(())
 }
 
@@ -143,7 +145,101 @@ async fn test_lsp_init() -> Result<()> {
 async fn test_lsp_init_with_graphql_files() -> Result<()> {
     let (service, _) = make_service!();
     let response = request_init!(service: "simple");
-    assert_eq!(response.capabilities, gqls::capabilities());
+    assert_eq!(response.capabilities, gqls::capabilities(&Default::default()));
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_init_with_disabled_features() -> Result<()> {
+    let (service, _) = make_service!();
+    let response = request!(service: "initialize", json!({
+        "capabilities": {},
+        "workspaceFolders": workspaces!("simple"),
+        "initializationOptions": { "features": { "hover": false, "codeLens": false } }
+    }));
+    let capabilities = response.capabilities;
+    assert!(capabilities.hover_provider.is_none());
+    assert!(capabilities.code_lens_provider.is_none());
+    assert!(capabilities.completion_provider.is_some());
+    assert!(capabilities.semantic_tokens_provider.is_some());
+
+    let hover = request!(service: "textDocument/hover", json!({
+        "textDocument": { "uri": url!("simple"."test.graphql") },
+        "position": { "line": 0, "character": 0 }
+    }));
+    assert_eq!(hover, None);
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_workspace_diagnostics_on_initialized() -> Result<()> {
+    let (service, mut socket) = make_service!();
+    request_init!(service: "simple");
+    let initialized = service.call(build_notification!("initialized", json!({})));
+    let (response, notification) = futures::join!(initialized, socket.next());
+    assert!(response?.is_none());
+    assert_eq!(
+        notification.unwrap(),
+        build_notification!(
+            "textDocument/publishDiagnostics",
+            json!({
+                "uri": url!("simple"."test.graphql"),
+                "diagnostics": []
+            })
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_change_workspace_folders() -> Result<()> {
+    let (service, socket) = make_service!();
+    request_init!(service: "empty");
+
+    // the diagnostics of the workspace are published once the folder is indexed
+    let published = tokio::spawn(socket.take(4).collect::<Vec<_>>());
+    notify!(service: "workspace/didChangeWorkspaceFolders", json!({
+        "event": { "added": workspaces!("simple"), "removed": [] }
+    }));
+    let hover = request!(service: "textDocument/hover", json!({
+        "textDocument": { "uri": url!("simple"."test.graphql") },
+        "position": { "line": 0, "character": 5 }
+    }));
+    assert!(hover.is_some());
+
+    // the files of a removed folder have their diagnostics cleared
+    notify!(service: "workspace/didChangeWorkspaceFolders", json!({
+        "event": { "added": [], "removed": workspaces!("simple") }
+    }));
+    let uris = published
+        .await?
+        .into_iter()
+        .map(|notification| notification.params().unwrap()["uri"].as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    let (simple, empty) = (url!("simple"."test.graphql"), url!("empty"."empty.graphql"));
+    assert_eq!(uris.iter().filter(|&uri| uri == simple.as_str()).count(), 2);
+    assert_eq!(uris.iter().filter(|&uri| uri == empty.as_str()).count(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_writes_index_cache() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("gqls-lsp-index-cache-{}", std::process::id()));
+    let (service, mut socket) = make_service!();
+    request!(service: "initialize", json!({
+        "capabilities": {},
+        "workspaceFolders": workspaces!("simple"),
+        "initializationOptions": { "cacheDirectory": dir }
+    }));
+    let initialized = service.call(build_notification!("initialized", json!({})));
+    let (response, _) = futures::join!(initialized, socket.next());
+    assert!(response?.is_none());
+    assert!(dir.join("items.bin").is_file());
+    std::fs::remove_dir_all(&dir)?;
     Ok(())
 }
 
@@ -167,6 +263,248 @@ async fn test_lsp_document_change() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_document_change_with_wide_chars() -> Result<()> {
+    let (service, _socket) = make_service!();
+    request_init!(service: "empty");
+    let uri = url!("empty"."empty.graphql");
+    let change = |range: Option<lsp_types::Range>, text: &str| TextDocumentContentChangeEvent {
+        range,
+        range_length: None,
+        text: text.to_owned(),
+    };
+    // the client's columns are utf-16 code units, `😀` is 2 of them (but 4 bytes)
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: next_id() as i32,
+        },
+        content_changes: vec![
+            change(None, "\"😀\" type Foo"),
+            change(Some(Range::new(Position::new(0, 10), Position::new(0, 13))), "Bar"),
+        ],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    let response = request!(service: "textDocument/documentSymbol", json!({
+        "textDocument": { "uri": uri },
+    }));
+    let symbols = match response {
+        Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+        _ => panic!("expected nested document symbols"),
+    };
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].name, "Bar");
+    assert_eq!(symbols[0].range.end, Position::new(0, 13));
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_negotiates_position_encoding() -> Result<()> {
+    let (service, _socket) = make_service!();
+    let response = request!(service: "initialize", json!({
+        "capabilities": { "general": { "positionEncodings": ["utf-32", "utf-16"] } },
+        "workspaceFolders": workspaces!("empty"),
+    }));
+    assert_eq!(response.capabilities.position_encoding, Some(PositionEncodingKind::UTF32));
+
+    let uri = url!("empty"."empty.graphql");
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: next_id() as i32,
+        },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: Some(Range::default()),
+            range_length: None,
+            text: "\"😀\" type Foo".to_owned(),
+        }],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    // `😀` is a single character
+    let response = request!(service: "textDocument/documentSymbol", json!({
+        "textDocument": { "uri": uri },
+    }));
+    let symbols = match response {
+        Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+        _ => panic!("expected nested document symbols"),
+    };
+    assert_eq!(symbols[0].range.end, Position::new(0, 12));
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_formatting() -> Result<()> {
+    let (service, _socket) = make_service!();
+    request_init!(service: "empty");
+    let uri = url!("empty"."empty.graphql");
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: next_id() as i32,
+        },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "type Foo {bar: Int}".to_owned(),
+        }],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    let response = request!(service: "textDocument/formatting", json!({
+        "textDocument": { "uri": uri },
+        "options": { "tabSize": 2, "insertSpaces": true },
+    }));
+    let edits = response.expect("expected formatting edits");
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range, Range::new(Position::new(0, 0), Position::new(0, 19)));
+    assert_eq!(edits[0].new_text, "type Foo {\n  bar: Int\n}\n");
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_unresolved_type_quick_fix() -> Result<()> {
+    let (service, _socket) = make_service!();
+    request_init!(service: "empty");
+    let uri = url!("empty"."empty.graphql");
+    let version = next_id() as i32;
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "type Users { id: ID! }\ntype Query { users: [Usrs!]! }".to_owned(),
+        }],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    let response = request!(service: "textDocument/codeAction", json!({
+        "textDocument": { "uri": uri },
+        "range": { "start": { "line": 1, "character": 23 }, "end": { "line": 1, "character": 23 } },
+        "context": { "diagnostics": [] },
+    }));
+    let actions = response.expect("expected code actions");
+    let action = match &actions[..] {
+        [CodeActionOrCommand::CodeAction(action)] => action,
+        actions => panic!("expected a single code action, found {actions:?}"),
+    };
+    assert_eq!(action.title, "Replace with `Users`");
+    let edits = match action.edit.as_ref().and_then(|edit| edit.document_changes.as_ref()) {
+        Some(DocumentChanges::Edits(edits)) => edits,
+        _ => panic!("expected document edits"),
+    };
+    // the client rejects the edit if the document changed since
+    assert_eq!(edits[0].text_document.version, Some(version));
+    let edit = match &edits[0].edits[..] {
+        [OneOf::Left(edit)] => edit,
+        _ => panic!("expected a single text edit"),
+    };
+    assert_eq!(edit.range, Range::new(Position::new(1, 21), Position::new(1, 25)));
+    assert_eq!(edit.new_text, "Users");
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_organize_fields() -> Result<()> {
+    let (service, _socket) = make_service!();
+    request_init!(service: "empty");
+    let uri = url!("empty"."empty.graphql");
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier {
+            uri: uri.clone(),
+            version: next_id() as i32,
+        },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "type Foo { b: Int a: Ints }".to_owned(),
+        }],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    // the quick fix for `Ints` is not of the requested kind
+    let response = request!(service: "textDocument/codeAction", json!({
+        "textDocument": { "uri": uri },
+        "range": { "start": { "line": 0, "character": 22 }, "end": { "line": 0, "character": 22 } },
+        "context": { "diagnostics": [], "only": ["source"] },
+    }));
+    let actions = response.expect("expected code actions");
+    let action = match &actions[..] {
+        [CodeActionOrCommand::CodeAction(action)] => action,
+        actions => panic!("expected a single code action, found {actions:?}"),
+    };
+    assert_eq!(action.kind.as_ref().map(CodeActionKind::as_str), Some("source.organizeFields"));
+    let edits = match action.edit.as_ref().and_then(|edit| edit.document_changes.as_ref()) {
+        Some(DocumentChanges::Edits(edits)) => edits,
+        _ => panic!("expected document edits"),
+    };
+    let edit = match &edits[0].edits[..] {
+        [OneOf::Left(edit)] => edit,
+        _ => panic!("expected a single text edit"),
+    };
+    assert_eq!(edit.range, Range::new(Position::new(0, 11), Position::new(0, 25)));
+    assert_eq!(edit.new_text, "a: Ints b: Int");
+    Ok(())
+}
+
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_resolve_code_action() -> Result<()> {
+    let (service, _socket) = make_service!();
+    request!(service: "initialize", json!({
+        "capabilities": {
+            "textDocument": { "codeAction": { "resolveSupport": { "properties": ["edit"] } } },
+        },
+        "workspaceFolders": workspaces!("empty"),
+    }));
+    let uri = url!("empty"."empty.graphql");
+    let version = next_id() as i32;
+    let params = lsp_types::DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "type Foo { b: Int a: Int }".to_owned(),
+        }],
+    };
+    notify!(service: "textDocument/didChange", params);
+
+    let response = request!(service: "textDocument/codeAction", json!({
+        "textDocument": { "uri": uri },
+        "range": { "start": { "line": 0, "character": 22 }, "end": { "line": 0, "character": 22 } },
+        "context": { "diagnostics": [], "only": ["source"] },
+    }));
+    let actions = response.expect("expected code actions");
+    let action = match &actions[..] {
+        [CodeActionOrCommand::CodeAction(action)] => action,
+        actions => panic!("expected a single code action, found {actions:?}"),
+    };
+    // the edit is only built once the action is resolved
+    assert!(action.edit.is_none());
+    assert!(action.data.is_some());
+
+    let resolved = request!(service: "codeAction/resolve", action);
+    assert_eq!(resolved.title, "Sort fields alphabetically");
+    let edits = match resolved.edit.as_ref().and_then(|edit| edit.document_changes.as_ref()) {
+        Some(DocumentChanges::Edits(edits)) => edits,
+        _ => panic!("expected document edits"),
+    };
+    assert_eq!(edits[0].text_document.version, Some(version));
+    let edit = match &edits[0].edits[..] {
+        [OneOf::Left(edit)] => edit,
+        _ => panic!("expected a single text edit"),
+    };
+    assert_eq!(edit.range, Range::new(Position::new(0, 11), Position::new(0, 24)));
+    assert_eq!(edit.new_text, "a: Int b: Int");
+    Ok(())
+}
+
 #[tokio::test]
 #[tracing_test::traced_test]
 async fn test_lsp_diagnostics() -> Result<()> {
@@ -188,6 +526,20 @@ async fn test_lsp_diagnostics() -> Result<()> {
     notify!(service: "textDocument/didChange", params);
     let req = socket.next().await.unwrap();
 
+    struct Indices;
+
+    impl LineIndices for Indices {
+        fn line_index(&self, _: FileId) -> Arc<LineIndex> {
+            Arc::new(LineIndex::new("bad gql"))
+        }
+
+        fn encoding(&self) -> PositionEncoding {
+            PositionEncoding::Utf16
+        }
+    }
+
+    let file = gqls_ide::VFS.read().get(uri.to_file_path().unwrap()).unwrap();
+    let diagnostic = gqls_ide::diagnostic!(E0001 @ gqls_ide::range!(0:0..0:7));
     assert_eq!(
         req,
         build_notification!(
@@ -195,7 +547,7 @@ async fn test_lsp_diagnostics() -> Result<()> {
             json!({
                 "uri": uri,
                 "diagnostics": [
-                    gqls_ide::diagnostic!(E0001 @ gqls_ide::range!(0:0..0:7) ).convert(),
+                    gqls::convert_diagnostic(&Indices, file, &diagnostic),
                 ]
             })
         )
@@ -203,6 +555,32 @@ async fn test_lsp_diagnostics() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+#[tracing_test::traced_test]
+async fn test_lsp_semantic_diagnostics_follow_syntax_diagnostics() -> Result<()> {
+    let (service, mut socket) = make_service!();
+    request!(service: "initialize", json!({
+        "capabilities": {},
+        "workspaceFolders": workspaces!("semantic"),
+        "initializationOptions": { "diagnostics": { "debounce": 0 } }
+    }));
+    let uri = url!("semantic"."semantic.graphql");
+    notify!(service: "textDocument/didChange", json!({
+        "textDocument": { "uri": uri, "version": next_id() },
+        "contentChanges": [{ "text": "type Query { bar: Bar }" }]
+    }));
+
+    // the syntax is fine, so the unresolved type is only reported by the second update
+    let syntax = socket.next().await.unwrap();
+    assert_eq!(syntax.params().unwrap()["diagnostics"], json!([]));
+    let semantic = socket.next().await.unwrap();
+    let diagnostics = semantic.params().unwrap()["diagnostics"].as_array().unwrap().clone();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["code"], json!(3));
+    assert_eq!(diagnostics[0]["message"], json!("unresolved type `Bar`"));
+    Ok(())
+}
+
 #[tokio::test]
 #[tracing_test::traced_test]
 async fn test_lsp_configuration() -> Result<()> {
@@ -263,6 +641,13 @@ async fn test_lsp_semantic_tokens() -> Result<()> {
             },
             SemanticToken {
                 delta_line: 3,
+                delta_start: 0,
+                length: 6,
+                token_type: 4,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line: 0,
                 delta_start: 7,
                 length: 4,
                 token_type: 4,
@@ -331,6 +716,13 @@ async fn test_lsp_semantic_tokens() -> Result<()> {
                 token_type: 11,
                 token_modifiers_bitset: 0,
             },
+            SemanticToken {
+                delta_line: 0,
+                delta_start: 4,
+                length: 10,
+                token_type: 4,
+                token_modifiers_bitset: 0,
+            },
             SemanticToken {
                 delta_line: 1,
                 delta_start: 2,
@@ -356,7 +748,7 @@ async fn test_lsp_semantic_tokens() -> Result<()> {
                 delta_line: 0,
                 delta_start: 6,
                 length: 1,
-                token_type: 13,
+                token_type: 14,
                 token_modifiers_bitset: 0,
             },
             SemanticToken {
@@ -410,6 +802,13 @@ async fn test_lsp_semantic_tokens() -> Result<()> {
             },
             SemanticToken {
                 delta_line: 3,
+                delta_start: 0,
+                length: 5,
+                token_type: 4,
+                token_modifiers_bitset: 0,
+            },
+            SemanticToken {
+                delta_line: 0,
                 delta_start: 6,
                 length: 1,
                 token_type: 11,
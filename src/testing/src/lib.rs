@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use gqls_base_db::{FileData, SourceDatabase};
-use gqls_fixture::{hashmap, Fixture, FixtureFile};
+use gqls_fixture::{Fixture, FixtureFile};
 
 pub use {gqls_base_db, gqls_syntax, maplit};
 
@@ -16,9 +16,12 @@ where
     DB: SourceDatabase + Default,
 {
     fn setup_fixture(&mut self, fixture: &Fixture) {
-        self.set_projects(Arc::new(
-            hashmap! { "default" => fixture.files().keys().cloned().collect() },
-        ));
+        self.set_projects(Arc::new(fixture.projects()));
+        self.set_project_groups(Default::default());
+        self.set_import_projects(Default::default());
+        self.set_max_file_size(gqls_base_db::DEFAULT_MAX_FILE_SIZE);
+        self.set_custom_scalars(Default::default());
+        self.set_root_types(Default::default());
         for (id, file) in fixture.files() {
             self.set_file_data(id, FileData::new(&file.text, gqls_syntax::parse_fresh(&file.text)));
         }
@@ -1,5 +1,6 @@
 #![deny(rust_2018_idioms)]
 
+use std::collections::HashMap;
 use std::path::Path;
 
 // FIXME interner doesn't really belong in this crate
@@ -14,6 +15,8 @@ pub type FileId = &'static Path;
 #[derive(Default)]
 pub struct Vfs {
     interner: PathInterner,
+    /// the contents of the open (possibly unsaved) buffers, which shadow the contents on disk
+    overlays: HashMap<FileId, String>,
 }
 
 impl Vfs {
@@ -24,4 +27,28 @@ impl Vfs {
     pub fn get(&self, path: impl AsRef<Path>) -> Option<FileId> {
         self.interner.get(path.as_ref())
     }
+
+    /// Shadow the contents of `file` on disk with the contents of its buffer, replacing any
+    /// previous overlay
+    pub fn set_overlay(&mut self, file: FileId, text: String) {
+        self.overlays.insert(file, text);
+    }
+
+    /// Stop shadowing the contents of `file` on disk, returning the overlay if there was one
+    pub fn remove_overlay(&mut self, file: FileId) -> Option<String> {
+        self.overlays.remove(file)
+    }
+
+    pub fn overlay(&self, file: FileId) -> Option<&str> {
+        self.overlays.get(file).map(String::as_str)
+    }
+
+    /// The contents of `file`, which is the overlay if there is one (regardless of whether the
+    /// file changed on disk since) and `disk` otherwise
+    pub fn contents(&self, file: FileId, disk: String) -> String {
+        self.overlay(file).map_or(disk, ToOwned::to_owned)
+    }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,32 @@
+use crate::Vfs;
+
+#[test]
+fn test_overlay_shadows_disk() {
+    let mut vfs = Vfs::default();
+    let file = vfs.intern("foo.graphql");
+    assert_eq!(vfs.contents(file, "type Foo".to_owned()), "type Foo");
+
+    vfs.set_overlay(file, "type Bar".to_owned());
+    assert_eq!(vfs.overlay(file), Some("type Bar"));
+    // the file changing on disk while it is open doesn't affect the buffer
+    assert_eq!(vfs.contents(file, "type Baz".to_owned()), "type Bar");
+
+    vfs.set_overlay(file, "type Qux".to_owned());
+    assert_eq!(vfs.contents(file, "type Baz".to_owned()), "type Qux");
+
+    // closing the (dirty) buffer reverts to whatever is on disk by then
+    assert_eq!(vfs.remove_overlay(file).as_deref(), Some("type Qux"));
+    assert_eq!(vfs.overlay(file), None);
+    assert_eq!(vfs.contents(file, "type Baz".to_owned()), "type Baz");
+    assert_eq!(vfs.remove_overlay(file), None);
+}
+
+#[test]
+fn test_overlays_are_per_file() {
+    let mut vfs = Vfs::default();
+    let foo = vfs.intern("foo.graphql");
+    let bar = vfs.intern("bar.graphql");
+    vfs.set_overlay(foo, "type Foo".to_owned());
+    assert_eq!(vfs.overlay(bar), None);
+    assert_eq!(vfs.contents(bar, "type Bar".to_owned()), "type Bar");
+}
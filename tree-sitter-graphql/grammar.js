@@ -250,8 +250,8 @@ module.exports = grammar({
     variable: ($) => seq("$", $.name),
     string_value: ($) =>
       choice(
-        seq('"""', /([^"]|\n|""?[^"])*/, '"""'),
-        seq('"', /[^"\\\n]*/, '"')
+        seq('"""', /((""?)?([^"\\]|\\(""")?))*/, '"""'),
+        seq('"', /([^"\\\n]|\\.)*/, '"')
       ),
     int_value: ($) => /-?(0|[1-9][0-9]*)/,
     float_value: ($) =>
@@ -289,6 +289,14 @@ module.exports = grammar({
       field(
         "location",
         choice(
+          "QUERY",
+          "MUTATION",
+          "SUBSCRIPTION",
+          "FIELD",
+          "FRAGMENT_DEFINITION",
+          "FRAGMENT_SPREAD",
+          "INLINE_FRAGMENT",
+          "VARIABLE_DEFINITION",
           "SCHEMA",
           "SCALAR",
           "OBJECT",
@@ -42,6 +42,8 @@ async function activateInner(context: vscode.ExtensionContext) {
 
   const clientOptions: lc.LanguageClientOptions = {
     documentSelector: [{ scheme: "file", language: "graphql" }],
+    initializationOptions: workspace.getConfiguration("gqls"),
+    synchronize: { configurationSection: "gqls" },
   };
 
   const client = new lc.LanguageClient(
@@ -51,12 +53,40 @@ async function activateInner(context: vscode.ExtensionContext) {
     clientOptions
   );
 
+  client.registerFeature(new ClientCommandsFeature());
   lcx = { client, subscriptions: context.subscriptions };
   context.subscriptions.push(client.start());
 
   context.subscriptions.push(
     vscode.commands.registerCommand("gqls.syntax-tree", showSyntaxTree(lcx!))
   );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("gqls.file-summary", showFileSummary(lcx!))
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("gqls.timings", showTimings(lcx!))
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("gqls.showReferences", showReferences(lcx!))
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("gqls.renameAt", renameAt(lcx!))
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "gqls.export-introspection",
+      exportIntrospection(lcx!)
+    )
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand(
+      "gqls.next-definition",
+      nextDefinition(lcx!)
+    )
+  );
+  context.subscriptions.push(
+    vscode.commands.registerCommand("gqls.peek-extensions", peekExtensions(lcx!))
+  );
 }
 
 export function deactivate() {
@@ -83,6 +113,9 @@ const showSyntaxTree = (lcx: LspContext) => async () => {
           uri: editor.document.uri.toString(),
           version: editor.document.version,
         },
+        range: editor.selection.isEmpty
+          ? undefined
+          : lcx.client.code2ProtocolConverter.asRange(editor.selection),
       };
       return lcx.client.sendRequest(syntaxTree, params, token);
     },
@@ -111,6 +144,173 @@ const showSyntaxTree = (lcx: LspContext) => async () => {
   });
 };
 
+// invoked by code lenses
+const showReferences =
+  (lcx: LspContext) =>
+  async (uri: string, position: lc.Position, locations: lc.Location[]) => {
+    const converter = lcx.client.protocol2CodeConverter;
+    await vscode.commands.executeCommand(
+      "editor.action.showReferences",
+      vscode.Uri.parse(uri),
+      converter.asPosition(position),
+      locations.map(converter.asLocation)
+    );
+  };
+
+// the server only attaches the commands the client advertises to its code actions
+class ClientCommandsFeature implements lc.StaticFeature {
+  fillClientCapabilities(capabilities: lc.ClientCapabilities): void {
+    capabilities.experimental = {
+      ...capabilities.experimental,
+      commands: { commands: ["gqls.renameAt"] },
+    };
+  }
+
+  initialize(): void {}
+
+  dispose(): void {}
+}
+
+// invoked by the extract type code action
+const renameAt =
+  (lcx: LspContext) => async (uri: string, position: lc.Position) => {
+    const editor = activeEditor();
+    if (!editor || editor.document.uri.toString() !== uri) {
+      return;
+    }
+    const pos = lcx.client.protocol2CodeConverter.asPosition(position);
+    editor.selection = new vscode.Selection(pos, pos);
+    await vscode.commands.executeCommand("editor.action.rename");
+  };
+
+const showFileSummary = (lcx: LspContext) => async () => {
+  const editor = activeEditor();
+  if (!editor) {
+    return;
+  }
+
+  const params: FileSummaryParams = {
+    textDocument: { uri: editor.document.uri.toString() },
+  };
+  const content = await lcx.client.sendRequest(fileSummary, params);
+  const document = await vscode.workspace.openTextDocument({
+    language: "markdown",
+    content,
+  });
+
+  await vscode.window.showTextDocument(document, {
+    viewColumn: vscode.ViewColumn.Two,
+    preserveFocus: true,
+  });
+};
+
+// a table of how long the requests (and the queries they run) took, slowest first
+const showTimings = (lcx: LspContext) => async () => {
+  const params: TimingsParams = { reset: false };
+  const timings = await lcx.client.sendRequest(timingsRequest, params);
+  const rows = timings.map(
+    (timing) =>
+      `| \`${timing.name}\` | ${timing.count} | ${timing.totalMs.toFixed(
+        1
+      )} | ${timing.maxMs.toFixed(1)} |`
+  );
+  const content = [
+    "| span | count | total (ms) | max (ms) |",
+    "| --- | --- | --- | --- |",
+    ...rows,
+  ].join("\n");
+  const document = await vscode.workspace.openTextDocument({
+    language: "markdown",
+    content,
+  });
+
+  await vscode.window.showTextDocument(document, {
+    viewColumn: vscode.ViewColumn.Two,
+    preserveFocus: true,
+  });
+};
+
+const exportIntrospection = (lcx: LspContext) => async () => {
+  const editor = activeEditor();
+  if (!editor) {
+    return;
+  }
+
+  const introspection = await lcx.client.sendRequest(
+    lc.ExecuteCommandRequest.type,
+    {
+      command: "gqls/exportIntrospection",
+      arguments: [editor.document.uri.toString()],
+    }
+  );
+  const { types, directives } = introspection.__schema;
+  const document = await vscode.workspace.openTextDocument({
+    language: "json",
+    content: JSON.stringify(introspection, null, 2),
+  });
+
+  await vscode.window.showTextDocument(document, {
+    viewColumn: vscode.ViewColumn.Two,
+    preserveFocus: true,
+  });
+  vscode.window.showInformationMessage(
+    `Exported ${types.length} types and ${directives.length} directives`
+  );
+};
+
+// cycles through the definition and extensions of the name under the cursor
+const nextDefinition = (lcx: LspContext) => async () => {
+  const editor = activeEditor();
+  if (!editor) {
+    return;
+  }
+
+  const params: lc.TextDocumentPositionParams = {
+    textDocument: { uri: editor.document.uri.toString() },
+    position: lcx.client.code2ProtocolConverter.asPosition(
+      editor.selection.active
+    ),
+  };
+  const location = await lcx.client.sendRequest(nextDefinitionRequest, params);
+  if (!location) {
+    return;
+  }
+
+  const converter = lcx.client.protocol2CodeConverter;
+  const document = await vscode.workspace.openTextDocument(
+    converter.asUri(location.uri)
+  );
+  const range = converter.asRange(location.range);
+  const target = await vscode.window.showTextDocument(document);
+  target.selection = new vscode.Selection(range.start, range.start);
+  target.revealRange(
+    range,
+    vscode.TextEditorRevealType.InCenterIfOutsideViewport
+  );
+};
+
+// peeks at the extend blocks of the type under the cursor
+const peekExtensions = (lcx: LspContext) => async () => {
+  const editor = activeEditor();
+  if (!editor) {
+    return;
+  }
+
+  const position = editor.selection.active;
+  const params: lc.TextDocumentPositionParams = {
+    textDocument: { uri: editor.document.uri.toString() },
+    position: lcx.client.code2ProtocolConverter.asPosition(position),
+  };
+  const locations = await lcx.client.sendRequest(extensionsRequest, params);
+  const converter = lcx.client.protocol2CodeConverter;
+  await vscode.commands.executeCommand(
+    "editor.action.showReferences",
+    editor.document.uri,
+    position,
+    locations.map(converter.asLocation)
+  );
+};
+
 export interface SyntaxTreeParams {
   textDocument: lc.VersionedTextDocumentIdentifier;
   range?: lc.Range;
@@ -120,6 +320,58 @@ export const syntaxTree = new lc.RequestType<SyntaxTreeParams, string, void>(
   "gqls/syntaxTree"
 );
 
+export interface FileSummaryParams {
+  textDocument: lc.TextDocumentIdentifier;
+}
+
+export const fileSummary = new lc.RequestType<FileSummaryParams, string, void>(
+  "gqls/fileSummary"
+);
+
+export interface TimingsParams {
+  reset: boolean;
+}
+
+export interface Timing {
+  name: string;
+  count: number;
+  totalMs: number;
+  maxMs: number;
+}
+
+export const timingsRequest = new lc.RequestType<
+  TimingsParams,
+  Timing[],
+  void
+>("gqls/debug/timings");
+
+export const nextDefinitionRequest = new lc.RequestType<
+  lc.TextDocumentPositionParams,
+  lc.Location | null,
+  void
+>("gqls/nextDefinition");
+
+export const extensionsRequest = new lc.RequestType<
+  lc.TextDocumentPositionParams,
+  lc.Location[],
+  void
+>("gqls/extensions");
+
+export interface DocumentFeaturesParams {
+  textDocument: lc.TextDocumentIdentifier;
+}
+
+export interface DocumentFeatures {
+  kind: "graphql" | "embedded";
+  features: Record<string, boolean>;
+}
+
+export const documentFeatures = new lc.RequestType<
+  DocumentFeaturesParams,
+  DocumentFeatures,
+  void
+>("gqls/documentFeatures");
+
 export function isGqlDocument(
   document: vscode.TextDocument
 ): document is GqlDocument {